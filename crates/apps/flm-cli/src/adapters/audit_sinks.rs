@@ -0,0 +1,3 @@
+//! Re-export shared AuditSinkRepository adapter for CLI consumers.
+
+pub use flm_core::adapters::SqliteAuditSinkRepository;
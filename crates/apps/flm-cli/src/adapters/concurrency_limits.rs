@@ -0,0 +1,3 @@
+//! Re-export shared ConcurrencyLimitRepository adapter for CLI consumers.
+
+pub use flm_core::adapters::SqliteConcurrencyLimitRepository;
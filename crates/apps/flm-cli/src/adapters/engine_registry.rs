@@ -0,0 +1,3 @@
+//! Re-export shared EngineRegistryRepository adapter for CLI consumers.
+
+pub use flm_core::adapters::SqliteEngineRegistryRepository;
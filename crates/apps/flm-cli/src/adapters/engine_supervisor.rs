@@ -0,0 +1,384 @@
+//! Engine process supervisor
+//!
+//! `flm engines start` launches a detached `flm engines supervise` process per
+//! engine. The supervisor spawns the engine binary, appends its output to a log
+//! file, restarts it when it crashes and publishes its status to a state file.
+//!
+//! Files live in `<runtime dir>/engines/<engine_id>/`:
+//! - `launch.json`: command line written by the controller (`SupervisorSpec`)
+//! - `state.json`: `EngineProcessStatus`, refreshed every second as a heartbeat
+//! - `stop`: created by the controller to request a shutdown
+//! - `engine.log`: engine stdout/stderr plus supervisor events
+
+use chrono::Utc;
+use flm_core::domain::engine::{EngineProcessState, EngineProcessStatus};
+use flm_core::domain::models::EngineId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// Interval between child/stop-file polls
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Interval between state file heartbeats
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// Grace period between SIGTERM and a forced kill
+const STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// Uptime after which a crash no longer counts as consecutive
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// First restart delay; doubled for each consecutive crash
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// `engine.log` is rotated to `engine.log.1` when it exceeds this size at (re)start
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Command line and restart policy for a supervised engine (`launch.json`)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SupervisorSpec {
+    pub engine_id: EngineId,
+    pub program: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub restart_on_crash: bool,
+    pub max_restarts: u32,
+}
+
+/// File layout of a supervised engine's runtime directory
+#[derive(Clone, Debug)]
+pub struct EngineRuntimeFiles {
+    dir: PathBuf,
+}
+
+impl EngineRuntimeFiles {
+    /// Files for `engine_id` under `runtime_dir` (usually `get_runtime_dir()`)
+    pub fn new(runtime_dir: &Path, engine_id: &str) -> Self {
+        Self {
+            dir: runtime_dir.join("engines").join(engine_id),
+        }
+    }
+
+    /// Files rooted at an existing engine directory
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn launch_path(&self) -> PathBuf {
+        self.dir.join("launch.json")
+    }
+
+    pub fn state_path(&self) -> PathBuf {
+        self.dir.join("state.json")
+    }
+
+    pub fn stop_path(&self) -> PathBuf {
+        self.dir.join("stop")
+    }
+
+    pub fn log_path(&self) -> PathBuf {
+        self.dir.join("engine.log")
+    }
+
+    pub fn read_spec(&self) -> Result<SupervisorSpec, String> {
+        let path = self.launch_path();
+        let data = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        serde_json::from_str(&data).map_err(|e| format!("Invalid {}: {e}", path.display()))
+    }
+
+    pub fn write_spec(&self, spec: &SupervisorSpec) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(spec)
+            .map_err(|e| format!("Failed to serialize launch spec: {e}"))?;
+        write_atomic(&self.launch_path(), &data)
+    }
+
+    /// Status last written by the supervisor (None if it never ran)
+    pub fn read_status(&self) -> Option<EngineProcessStatus> {
+        let data = fs::read_to_string(self.state_path()).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn write_status(&self, status: &EngineProcessStatus) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(status)
+            .map_err(|e| format!("Failed to serialize engine status: {e}"))?;
+        write_atomic(&self.state_path(), &data)
+    }
+}
+
+/// Run the supervisor loop until the engine is stopped or gives up
+///
+/// Blocks the calling thread. Errors are also recorded in `state.json` as
+/// `Failed` so the controller can report them.
+pub fn run_supervisor(files: &EngineRuntimeFiles) -> Result<(), String> {
+    let spec = files.read_spec()?;
+    let mut supervisor = Supervisor {
+        files,
+        status: EngineProcessStatus {
+            engine_id: spec.engine_id.clone(),
+            state: EngineProcessState::Starting,
+            pid: None,
+            restarts: 0,
+            started_at: None,
+            last_exit_code: None,
+            reason: None,
+            log_path: files.log_path().to_string_lossy().to_string(),
+            updated_at: Utc::now().to_rfc3339(),
+        },
+    };
+    supervisor.publish()?;
+
+    let mut consecutive_crashes = 0u32;
+    loop {
+        if supervisor.stop_requested() {
+            return supervisor.finish(EngineProcessState::Stopped, None);
+        }
+
+        rotate_log(&files.log_path());
+        let mut child = match spawn_engine(&spec, &files.log_path()) {
+            Ok(child) => child,
+            Err(e) => {
+                supervisor.log_event(&e);
+                return supervisor.finish(EngineProcessState::Failed, Some(e));
+            }
+        };
+        let spawned_at = Instant::now();
+        supervisor.status.state = EngineProcessState::Running;
+        supervisor.status.pid = Some(child.id());
+        supervisor.status.started_at = Some(Utc::now().to_rfc3339());
+        supervisor.status.reason = None;
+        supervisor.log_event(&format!(
+            "started {} (pid {})",
+            command_line(&spec),
+            child.id()
+        ));
+        supervisor.publish()?;
+
+        let exit = match supervisor.wait_for_exit(&mut child)? {
+            ChildExit::Stopped(status) => {
+                supervisor.status.last_exit_code = status.and_then(|s| s.code());
+                supervisor.log_event("stopped on request");
+                return supervisor.finish(EngineProcessState::Stopped, None);
+            }
+            ChildExit::Exited(status) => status,
+        };
+
+        supervisor.status.pid = None;
+        supervisor.status.last_exit_code = exit.code();
+        let reason = format!("engine exited with {}", describe_exit(&exit));
+        supervisor.log_event(&reason);
+
+        if !spec.restart_on_crash {
+            return supervisor.finish(EngineProcessState::Failed, Some(reason));
+        }
+        if spawned_at.elapsed() >= STABLE_UPTIME {
+            consecutive_crashes = 0;
+        }
+        consecutive_crashes += 1;
+        if consecutive_crashes > spec.max_restarts {
+            let reason = format!(
+                "{reason}; giving up after {} consecutive restart(s)",
+                spec.max_restarts
+            );
+            supervisor.log_event(&reason);
+            return supervisor.finish(EngineProcessState::Failed, Some(reason));
+        }
+
+        let backoff = restart_backoff(consecutive_crashes);
+        supervisor.status.state = EngineProcessState::Restarting;
+        supervisor.status.restarts += 1;
+        supervisor.status.reason = Some(reason);
+        supervisor.log_event(&format!("restarting in {}s", backoff.as_secs()));
+        supervisor.publish()?;
+
+        let deadline = Instant::now() + backoff;
+        while Instant::now() < deadline {
+            if supervisor.stop_requested() {
+                return supervisor.finish(EngineProcessState::Stopped, None);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+            supervisor.heartbeat()?;
+        }
+    }
+}
+
+enum ChildExit {
+    /// Stopped on request (exit status if it could be collected)
+    Stopped(Option<ExitStatus>),
+    /// Exited on its own
+    Exited(ExitStatus),
+}
+
+struct Supervisor<'a> {
+    files: &'a EngineRuntimeFiles,
+    status: EngineProcessStatus,
+}
+
+impl Supervisor<'_> {
+    fn stop_requested(&self) -> bool {
+        self.files.stop_path().exists()
+    }
+
+    fn publish(&mut self) -> Result<(), String> {
+        self.status.updated_at = Utc::now().to_rfc3339();
+        self.files.write_status(&self.status)
+    }
+
+    fn heartbeat(&mut self) -> Result<(), String> {
+        let last = chrono::DateTime::parse_from_rfc3339(&self.status.updated_at)
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_default();
+        let due = Utc::now()
+            .signed_duration_since(last)
+            .to_std()
+            .unwrap_or_default();
+        if due >= HEARTBEAT_INTERVAL {
+            self.publish()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, state: EngineProcessState, reason: Option<String>) -> Result<(), String> {
+        self.status.state = state;
+        self.status.pid = None;
+        self.status.reason = reason;
+        let _ = fs::remove_file(self.files.stop_path());
+        self.publish()
+    }
+
+    fn wait_for_exit(&mut self, child: &mut Child) -> Result<ChildExit, String> {
+        loop {
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|e| format!("Failed to poll engine process: {e}"))?
+            {
+                return Ok(ChildExit::Exited(status));
+            }
+            if self.stop_requested() {
+                return Ok(ChildExit::Stopped(terminate(child)));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+            self.heartbeat()?;
+        }
+    }
+
+    /// Append a supervisor line to the engine log
+    fn log_event(&self, message: &str) {
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.files.log_path())
+        {
+            let _ = writeln!(
+                file,
+                "[flm-supervisor {}] {message}",
+                Utc::now().to_rfc3339()
+            );
+        }
+    }
+}
+
+fn spawn_engine(spec: &SupervisorSpec, log_path: &Path) -> Result<Child, String> {
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| format!("Failed to open {}: {e}", log_path.display()))?;
+    let stderr = log
+        .try_clone()
+        .map_err(|e| format!("Failed to open {}: {e}", log_path.display()))?;
+
+    Command::new(&spec.program)
+        .args(&spec.args)
+        .envs(&spec.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(stderr))
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {e}", spec.program))
+}
+
+/// Ask the engine to shut down (SIGTERM on Unix), then kill it after a grace period
+fn terminate(child: &mut Child) -> Option<ExitStatus> {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .arg("-TERM")
+            .arg(child.id().to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        let deadline = Instant::now() + STOP_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            match child.try_wait() {
+                Ok(Some(status)) => return Some(status),
+                Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                Err(_) => break,
+            }
+        }
+    }
+    let _ = child.kill();
+    child.wait().ok()
+}
+
+fn restart_backoff(consecutive_crashes: u32) -> Duration {
+    let factor = 2u32.saturating_pow(consecutive_crashes.saturating_sub(1).min(16));
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+fn rotate_log(log_path: &Path) {
+    let too_large = fs::metadata(log_path)
+        .map(|m| m.len() > MAX_LOG_BYTES)
+        .unwrap_or(false);
+    if too_large {
+        let rotated = log_path.with_extension("log.1");
+        let _ = fs::rename(log_path, rotated);
+    }
+}
+
+fn describe_exit(status: &ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("exit code {code}"),
+        None => "a signal".to_string(),
+    }
+}
+
+fn command_line(spec: &SupervisorSpec) -> String {
+    std::iter::once(spec.program.as_str())
+        .chain(spec.args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn write_atomic(path: &Path, data: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut file =
+        File::create(&tmp).map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    file.write_all(data.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_backoff_is_exponential_and_capped() {
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(2), Duration::from_secs(2));
+        assert_eq!(restart_backoff(4), Duration::from_secs(8));
+        assert_eq!(restart_backoff(10), MAX_BACKOFF);
+        assert_eq!(restart_backoff(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -4,10 +4,16 @@
 //! defined in flm-core.
 
 pub mod api_prompts;
+pub mod audit_sinks;
+pub mod concurrency_limits;
 pub mod config;
 pub mod engine;
 pub mod engine_health_log;
+pub mod engine_process;
+pub mod engine_registry;
+pub mod engine_supervisor;
 pub mod http;
+pub mod model_groups;
 pub mod model_profiles;
 pub mod process_controller;
 pub mod proxy;
@@ -15,11 +21,16 @@ pub mod security;
 
 // Re-export for convenience
 pub use api_prompts::{ApiPromptRecord, ApiPromptStore};
+pub use audit_sinks::SqliteAuditSinkRepository;
+pub use concurrency_limits::SqliteConcurrencyLimitRepository;
 pub use config::SqliteConfigRepository;
 pub use engine::SqliteEngineRepository;
 pub use engine_health_log::SqliteEngineHealthLogRepository;
+pub use engine_process::SqliteEngineProcessConfigRepository;
+pub use engine_registry::SqliteEngineRegistryRepository;
 pub use http::ReqwestHttpClient;
+pub use model_groups::SqliteModelGroupRepository;
 pub use model_profiles::{ModelProfileRecord, ModelProfileStore};
 pub use process_controller::DefaultEngineProcessController;
 pub use proxy::SqliteProxyRepository;
-pub use security::SqliteSecurityRepository;
+pub use security::{SqliteSecurityRepository, UsageGroupBy, UsageReportRow};
@@ -0,0 +1,3 @@
+//! Re-export shared ModelGroupRepository adapter for CLI consumers.
+
+pub use flm_core::adapters::SqliteModelGroupRepository;
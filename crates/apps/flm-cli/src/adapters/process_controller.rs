@@ -1,27 +1,125 @@
 //! EngineProcessController implementation
 //!
 //! This adapter implements the EngineProcessController trait for detecting
-//! engine binaries and running processes according to ENGINE_DETECT.md.
-
-use flm_core::domain::engine::{EngineBinaryInfo, EngineRuntimeInfo};
+//! engine binaries and running processes according to ENGINE_DETECT.md, and
+//! for starting/stopping engines under an `flm engines supervise` process.
+
+use super::engine_supervisor::{EngineRuntimeFiles, SupervisorSpec, HEARTBEAT_INTERVAL};
+use crate::utils::get_runtime_dir;
+use chrono::Utc;
+use flm_core::domain::engine::{
+    EngineBinaryInfo, EngineProcessConfig, EngineProcessState, EngineProcessStatus,
+    EngineRuntimeInfo,
+};
 use flm_core::domain::models::EngineKind;
+use flm_core::error::EngineError;
 use flm_core::ports::EngineProcessController;
 use reqwest::Url;
+use std::fs;
 use std::net::TcpStream;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Duration;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long `start` waits for the supervisor to report the engine as running
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long `stop` waits for the supervisor to shut the engine down
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
 
-/// Process controller implementation for engine detection
+/// Process controller implementation for engine detection and lifecycle
 ///
 /// This implementation detects engine binaries and running processes
-/// according to the specifications in ENGINE_DETECT.md.
-pub struct DefaultEngineProcessController;
+/// according to the specifications in ENGINE_DETECT.md. Engines started via
+/// `start` run under a detached supervisor (`flm engines supervise`) that
+/// keeps its state in `<runtime dir>/engines/<engine_id>/`.
+pub struct DefaultEngineProcessController {
+    runtime_dir: Option<PathBuf>,
+    supervisor_program: Option<PathBuf>,
+}
 
 impl DefaultEngineProcessController {
     /// Create a new DefaultEngineProcessController
     pub fn new() -> Self {
-        Self
+        Self {
+            runtime_dir: None,
+            supervisor_program: None,
+        }
+    }
+
+    /// Keep supervisor files under `runtime_dir` instead of `get_runtime_dir()`
+    pub fn with_runtime_dir(mut self, runtime_dir: impl Into<PathBuf>) -> Self {
+        self.runtime_dir = Some(runtime_dir.into());
+        self
+    }
+
+    /// Run supervisors with `program` (an `flm` binary) instead of the current executable
+    pub fn with_supervisor_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.supervisor_program = Some(program.into());
+        self
+    }
+
+    /// Supervisor files of an engine
+    pub fn runtime_files(&self, engine_id: &str) -> EngineRuntimeFiles {
+        let runtime_dir = self.runtime_dir.clone().unwrap_or_else(get_runtime_dir);
+        EngineRuntimeFiles::new(&runtime_dir, engine_id)
+    }
+
+    /// Resolve the engine binary (explicit path, PATH, then known install locations)
+    fn resolve_engine_binary(&self, config: &EngineProcessConfig) -> Result<String, String> {
+        if let Some(binary) = &config.binary_path {
+            let path = Path::new(binary);
+            if path.is_file() {
+                return Ok(binary.clone());
+            }
+            return which::which(binary)
+                .map(|p| p.to_string_lossy().to_string())
+                .map_err(|_| format!("Engine binary not found: {binary}"));
+        }
+
+        let detected = match config.kind {
+            EngineKind::Ollama => self.detect_ollama_binary(),
+            EngineKind::LlamaCpp => self.detect_llamacpp_binary(),
+            _ => None,
+        };
+        if let Some(info) = detected {
+            return Ok(info.binary_path);
+        }
+        let name = EngineProcessConfig::default_binary(&config.kind).ok_or_else(|| {
+            format!(
+                "Engine kind '{}' cannot be started by flm",
+                config.kind.as_str()
+            )
+        })?;
+        which::which(name)
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|_| format!("{name} not found in PATH (use --binary to set its location)"))
+    }
+
+    fn spawn_supervisor(&self, files: &EngineRuntimeFiles) -> Result<std::process::Child, String> {
+        let program = match &self.supervisor_program {
+            Some(program) => program.clone(),
+            None => std::env::current_exe()
+                .map_err(|e| format!("Failed to locate the flm executable: {e}"))?,
+        };
+        let mut command = Command::new(program);
+        command
+            .arg("engines")
+            .arg("supervise")
+            .arg("--dir")
+            .arg(files.dir())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        // Detach from the terminal's process group so Ctrl-C in the shell
+        // that ran `flm engines start` does not stop the engine
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        command
+            .spawn()
+            .map_err(|e| format!("Failed to launch engine supervisor: {e}"))
     }
 
     /// Detect Ollama binary
@@ -141,6 +239,25 @@ impl DefaultEngineProcessController {
         })
     }
 
+    /// Detect Stable Diffusion web UI (AUTOMATIC1111-compatible API)
+    fn detect_sdwebui_running(&self) -> Option<EngineRuntimeInfo> {
+        let base_url =
+            std::env::var("SD_WEBUI_URL").unwrap_or_else(|_| "http://localhost:7860".to_string());
+        let normalized = base_url.trim_end_matches('/').to_string();
+        let (host, port) = parse_host_port(&normalized, 7860)?;
+
+        if !self.is_port_open(&host, port) {
+            return None;
+        }
+
+        Some(EngineRuntimeInfo {
+            engine_id: "sdwebui-default".to_string(),
+            kind: EngineKind::StableDiffusion,
+            base_url: normalized,
+            port: Some(port),
+        })
+    }
+
     fn detect_ollama_running(&self) -> Option<EngineRuntimeInfo> {
         let base_url = std::env::var("OLLAMA_BASE_URL")
             .unwrap_or_else(|_| "http://localhost:11434".to_string());
@@ -353,6 +470,114 @@ impl EngineProcessController for DefaultEngineProcessController {
             results.push(runtime);
         }
 
+        if let Some(runtime) = self.detect_sdwebui_running() {
+            results.push(runtime);
+        }
+
         results
     }
+
+    fn start(&self, config: &EngineProcessConfig) -> Result<EngineProcessStatus, EngineError> {
+        let process_error = |reason: String| EngineError::ProcessError {
+            engine_id: config.engine_id.clone(),
+            reason,
+        };
+        if let Some(status) = self.status(&config.engine_id) {
+            if status.state.is_active() {
+                return Err(process_error(format!(
+                    "already running (state: {:?}, pid: {:?})",
+                    status.state, status.pid
+                )));
+            }
+        }
+
+        let args = config.command_args().map_err(process_error)?;
+        let program = self.resolve_engine_binary(config).map_err(process_error)?;
+        let files = self.runtime_files(&config.engine_id);
+        fs::create_dir_all(files.dir()).map_err(|e| {
+            process_error(format!("Failed to create {}: {e}", files.dir().display()))
+        })?;
+        let _ = fs::remove_file(files.stop_path());
+        let _ = fs::remove_file(files.state_path());
+        files
+            .write_spec(&SupervisorSpec {
+                engine_id: config.engine_id.clone(),
+                program,
+                args,
+                env: config.command_env(),
+                restart_on_crash: config.restart_on_crash,
+                max_restarts: config.max_restarts,
+            })
+            .map_err(process_error)?;
+
+        let mut supervisor = self.spawn_supervisor(&files).map_err(process_error)?;
+        let deadline = Instant::now() + START_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Some(status) = files.read_status() {
+                match status.state {
+                    EngineProcessState::Running => return Ok(status),
+                    EngineProcessState::Failed | EngineProcessState::Stopped => {
+                        return Err(process_error(
+                            status
+                                .reason
+                                .unwrap_or_else(|| "engine exited during startup".to_string()),
+                        ))
+                    }
+                    EngineProcessState::Starting | EngineProcessState::Restarting => {}
+                }
+            } else if let Ok(Some(exit)) = supervisor.try_wait() {
+                return Err(process_error(format!(
+                    "supervisor exited during startup ({exit})"
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        Err(EngineError::Timeout {
+            operation: format!("start engine {}", config.engine_id),
+        })
+    }
+
+    fn stop(&self, engine_id: &str) -> Result<EngineProcessStatus, EngineError> {
+        let not_running = || EngineError::ProcessError {
+            engine_id: engine_id.to_string(),
+            reason: "not running".to_string(),
+        };
+        let status = self.status(engine_id).ok_or_else(not_running)?;
+        if !status.state.is_active() {
+            return Err(not_running());
+        }
+
+        let files = self.runtime_files(engine_id);
+        fs::write(files.stop_path(), b"").map_err(|e| EngineError::ProcessError {
+            engine_id: engine_id.to_string(),
+            reason: format!("Failed to request stop: {e}"),
+        })?;
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while Instant::now() < deadline {
+            match self.status(engine_id) {
+                Some(status) if status.state.is_active() => {}
+                Some(status) => return Ok(status),
+                None => break,
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        Err(EngineError::Timeout {
+            operation: format!("stop engine {engine_id}"),
+        })
+    }
+
+    fn status(&self, engine_id: &str) -> Option<EngineProcessStatus> {
+        let mut status = self.runtime_files(engine_id).read_status()?;
+        // A supervisor that died (e.g. killed or host rebooted) stops refreshing its heartbeat
+        let stale_after = chrono::Duration::from_std(HEARTBEAT_INTERVAL * 10).ok()?;
+        let updated_at = chrono::DateTime::parse_from_rfc3339(&status.updated_at).ok()?;
+        if status.state.is_active() && Utc::now().signed_duration_since(updated_at) > stale_after {
+            status.state = EngineProcessState::Failed;
+            status.pid = None;
+            status.reason = Some("supervisor is no longer running".to_string());
+        }
+        Some(status)
+    }
 }
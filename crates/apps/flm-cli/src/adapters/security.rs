@@ -1,13 +1,54 @@
 //! SecurityRepository implementation using SQLite
 
-use flm_core::domain::security::{ApiKeyRecord, DnsCredentialProfile, SecurityPolicy};
+use flm_core::domain::audit::{AuditChainAnchor, AuditLogEntry, ChainedAuditLog};
+use flm_core::domain::security::{
+    ApiKeyRecord, ApiKeyScopes, DnsCredentialProfile, SecurityPolicy,
+};
 use flm_core::error::RepoError;
 use flm_core::ports::SecurityRepository;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
+/// Second grouping level of a usage report (rows are always per API key)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UsageGroupBy {
+    /// One row per API key
+    Key,
+    /// One row per API key and model ID
+    Model,
+    /// One row per API key and UTC day
+    Day,
+}
+
+impl UsageGroupBy {
+    /// SQL expression for the group column
+    fn column(self) -> &'static str {
+        match self {
+            Self::Key => "NULL",
+            Self::Model => "model_id",
+            Self::Day => "substr(created_at, 1, 10)",
+        }
+    }
+}
+
+/// Aggregated `usage_records` rows of one API key (and model or day)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsageReportRow {
+    pub api_key_id: String,
+    /// Model ID or UTC day (`YYYY-MM-DD`), depending on the grouping
+    pub group: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub avg_latency_ms: i64,
+    /// Requests charged with estimated usage (the engine reported none)
+    pub estimated_requests: i64,
+}
+
 /// SQLite-based SecurityRepository implementation
 pub struct SqliteSecurityRepository {
     pool: SqlitePool,
@@ -257,6 +298,74 @@ impl SqliteSecurityRepository {
         Ok(rows)
     }
 
+    /// List audit logs with their hash chain columns, in `id` order
+    ///
+    /// `since` is compared with `created_at` ("YYYY-MM-DD HH:MM:SS", UTC).
+    pub async fn list_chained_audit_logs(
+        &self,
+        after_id: i64,
+        since: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<ChainedAuditLog>, RepoError> {
+        let rows = sqlx::query(
+            "SELECT id, request_id, api_key_id, endpoint, status, latency_ms, event_type, severity, ip, details, created_at, prev_hash, entry_hash, hash_alg FROM audit_logs WHERE id > ? AND (? IS NULL OR created_at >= ?) ORDER BY id LIMIT ?",
+        )
+        .bind(after_id)
+        .bind(since)
+        .bind(since)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to list audit logs: {e}"),
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ChainedAuditLog {
+                    entry: AuditLogEntry {
+                        id: row.try_get("id")?,
+                        request_id: row.try_get("request_id")?,
+                        api_key_id: row.try_get("api_key_id")?,
+                        endpoint: row.try_get("endpoint")?,
+                        status: row.try_get("status")?,
+                        latency_ms: row.try_get("latency_ms")?,
+                        event_type: row.try_get("event_type")?,
+                        severity: row.try_get("severity")?,
+                        ip: row.try_get("ip")?,
+                        details: row.try_get("details")?,
+                        created_at: row.try_get("created_at")?,
+                    },
+                    prev_hash: row.try_get("prev_hash")?,
+                    entry_hash: row.try_get("entry_hash")?,
+                    hash_alg: row.try_get("hash_alg")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to read audit log: {e}"),
+            })
+    }
+
+    /// Latest anchor left by retention pruning (`None` if nothing was pruned)
+    pub async fn latest_audit_chain_anchor(&self) -> Result<Option<AuditChainAnchor>, RepoError> {
+        let row: Option<(i64, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT pruned_through_id, entry_hash, hash_alg FROM audit_chain_anchors ORDER BY pruned_through_id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to read audit chain anchor: {e}"),
+        })?;
+        Ok(row.map(
+            |(pruned_through_id, entry_hash, hash_alg)| AuditChainAnchor {
+                pruned_through_id,
+                entry_hash,
+                hash_alg,
+            },
+        ))
+    }
+
     /// List intrusion attempts with optional filters
     pub async fn list_intrusion_attempts(
         &self,
@@ -440,6 +549,109 @@ impl SqliteSecurityRepository {
 
         Ok(rows)
     }
+
+    /// List token usage per API key for one UTC day and that day's month
+    ///
+    /// Returns a vector of (api_key_id, day_tokens, day_requests, month_tokens, month_requests)
+    pub async fn list_token_usage(
+        &self,
+        usage_date: &str,
+        api_key_id: Option<&str>,
+    ) -> Result<Vec<(String, i64, i64, i64, i64)>, RepoError> {
+        let month_prefix = format!("{}%", usage_date.get(..8).unwrap_or(usage_date));
+        let query = if api_key_id.is_some() {
+            "SELECT api_key_id, \
+             SUM(CASE WHEN usage_date = ? THEN total_tokens ELSE 0 END), \
+             SUM(CASE WHEN usage_date = ? THEN requests ELSE 0 END), \
+             SUM(total_tokens), SUM(requests) \
+             FROM token_usage WHERE usage_date LIKE ? AND api_key_id = ? \
+             GROUP BY api_key_id ORDER BY api_key_id"
+        } else {
+            "SELECT api_key_id, \
+             SUM(CASE WHEN usage_date = ? THEN total_tokens ELSE 0 END), \
+             SUM(CASE WHEN usage_date = ? THEN requests ELSE 0 END), \
+             SUM(total_tokens), SUM(requests) \
+             FROM token_usage WHERE usage_date LIKE ? \
+             GROUP BY api_key_id ORDER BY api_key_id"
+        };
+
+        let mut sql_query = sqlx::query_as::<_, (String, i64, i64, i64, i64)>(query)
+            .bind(usage_date)
+            .bind(usage_date)
+            .bind(month_prefix);
+
+        if let Some(key_id) = api_key_id {
+            sql_query = sql_query.bind(key_id);
+        }
+
+        let rows = sql_query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to list token usage: {e}"),
+            })?;
+
+        Ok(rows)
+    }
+
+    /// Aggregate per-request usage recorded by the proxy
+    ///
+    /// # Arguments
+    /// * `since` - Only count requests at or after this RFC3339 UTC timestamp
+    /// * `api_key_id` - Only count requests of this API key
+    /// * `group_by` - Split each key's usage by model or day
+    pub async fn usage_report(
+        &self,
+        since: Option<&str>,
+        api_key_id: Option<&str>,
+        group_by: UsageGroupBy,
+    ) -> Result<Vec<UsageReportRow>, RepoError> {
+        let query = format!(
+            "SELECT api_key_id, {group} AS usage_group, COUNT(*), \
+             SUM(prompt_tokens), SUM(completion_tokens), SUM(total_tokens), \
+             CAST(AVG(latency_ms) AS INTEGER), SUM(estimated) \
+             FROM usage_records \
+             WHERE created_at >= ? AND (? IS NULL OR api_key_id = ?) \
+             GROUP BY api_key_id, usage_group ORDER BY api_key_id, usage_group",
+            group = group_by.column()
+        );
+
+        let rows =
+            sqlx::query_as::<_, (String, Option<String>, i64, i64, i64, i64, i64, i64)>(&query)
+                .bind(since.unwrap_or(""))
+                .bind(api_key_id)
+                .bind(api_key_id)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| RepoError::IoError {
+                    reason: format!("Failed to build usage report: {e}"),
+                })?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    api_key_id,
+                    group,
+                    requests,
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens,
+                    avg_latency_ms,
+                    estimated_requests,
+                )| UsageReportRow {
+                    api_key_id,
+                    group,
+                    requests,
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens,
+                    avg_latency_ms,
+                    estimated_requests,
+                },
+            )
+            .collect())
+    }
 }
 
 #[async_trait::async_trait]
@@ -447,13 +659,14 @@ impl SecurityRepository for SqliteSecurityRepository {
     async fn save_api_key(&self, key: ApiKeyRecord) -> Result<(), RepoError> {
         self.check_write_allowed("save API key")?;
         sqlx::query(
-            "INSERT OR REPLACE INTO api_keys (id, label, hash, created_at, revoked_at) VALUES (?, ?, ?, ?, ?)",
+            "INSERT OR REPLACE INTO api_keys (id, label, hash, created_at, revoked_at, scopes_json) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(&key.id)
         .bind(&key.label)
         .bind(&key.hash)
         .bind(&key.created_at)
         .bind(&key.revoked_at)
+        .bind(api_key_scopes_json(&key.scopes)?)
         .execute(&self.pool)
         .await
         .map_err(|e| RepoError::IoError {
@@ -464,8 +677,8 @@ impl SecurityRepository for SqliteSecurityRepository {
     }
 
     async fn fetch_api_key(&self, id: &str) -> Result<Option<ApiKeyRecord>, RepoError> {
-        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
-            "SELECT id, label, hash, created_at, revoked_at FROM api_keys WHERE id = ?",
+        let row = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT id, label, hash, created_at, revoked_at, scopes_json FROM api_keys WHERE id = ?",
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -474,20 +687,12 @@ impl SecurityRepository for SqliteSecurityRepository {
             reason: format!("Failed to fetch API key: {e}"),
         })?;
 
-        Ok(
-            row.map(|(id, label, hash, created_at, revoked_at)| ApiKeyRecord {
-                id,
-                label,
-                hash,
-                created_at,
-                revoked_at,
-            }),
-        )
+        row.map(api_key_from_row).transpose()
     }
 
     async fn list_api_keys(&self) -> Result<Vec<ApiKeyRecord>, RepoError> {
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
-            "SELECT id, label, hash, created_at, revoked_at FROM api_keys ORDER BY created_at DESC",
+        let rows = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT id, label, hash, created_at, revoked_at, scopes_json FROM api_keys ORDER BY created_at DESC",
         )
         .fetch_all(&self.pool)
         .await
@@ -495,22 +700,13 @@ impl SecurityRepository for SqliteSecurityRepository {
             reason: format!("Failed to list API keys: {e}"),
         })?;
 
-        Ok(rows
-            .into_iter()
-            .map(|(id, label, hash, created_at, revoked_at)| ApiKeyRecord {
-                id,
-                label,
-                hash,
-                created_at,
-                revoked_at,
-            })
-            .collect())
+        rows.into_iter().map(api_key_from_row).collect()
     }
 
     async fn list_active_api_keys(&self) -> Result<Vec<ApiKeyRecord>, RepoError> {
         // Optimized query: filter revoked keys at database level
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
-            "SELECT id, label, hash, created_at, revoked_at FROM api_keys WHERE revoked_at IS NULL ORDER BY created_at DESC",
+        let rows = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT id, label, hash, created_at, revoked_at, scopes_json FROM api_keys WHERE revoked_at IS NULL ORDER BY created_at DESC",
         )
         .fetch_all(&self.pool)
         .await
@@ -518,16 +714,7 @@ impl SecurityRepository for SqliteSecurityRepository {
             reason: format!("Failed to list active API keys: {e}"),
         })?;
 
-        Ok(rows
-            .into_iter()
-            .map(|(id, label, hash, created_at, revoked_at)| ApiKeyRecord {
-                id,
-                label,
-                hash,
-                created_at,
-                revoked_at,
-            })
-            .collect())
+        rows.into_iter().map(api_key_from_row).collect()
     }
 
     async fn mark_api_key_revoked(&self, id: &str, revoked_at: &str) -> Result<(), RepoError> {
@@ -696,6 +883,47 @@ impl SecurityRepository for SqliteSecurityRepository {
     }
 }
 
+/// `api_keys` row: id, label, hash, created_at, revoked_at, scopes_json
+type ApiKeyRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+fn api_key_from_row(row: ApiKeyRow) -> Result<ApiKeyRecord, RepoError> {
+    let (id, label, hash, created_at, revoked_at, scopes_json) = row;
+    // A key whose scopes cannot be read must not become unrestricted
+    let scopes = match scopes_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| RepoError::IoError {
+            reason: format!("Invalid scopes for API key {id}: {e}"),
+        })?,
+        None => ApiKeyScopes::default(),
+    };
+    Ok(ApiKeyRecord {
+        id,
+        label,
+        hash,
+        created_at,
+        revoked_at,
+        scopes,
+    })
+}
+
+/// Serialize scopes for `api_keys.scopes_json` (NULL when unrestricted)
+fn api_key_scopes_json(scopes: &ApiKeyScopes) -> Result<Option<String>, RepoError> {
+    if scopes.is_unrestricted() {
+        return Ok(None);
+    }
+    serde_json::to_string(scopes)
+        .map(Some)
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to serialize API key scopes: {e}"),
+        })
+}
+
 /// Set restrictive file permissions for database files (Unix only)
 ///
 /// Sets permissions to 600 (rw-------) to ensure only the owner can read/write.
@@ -9,6 +9,13 @@ pub enum ApiKeysSubcommand {
         /// Human-readable label for the API key
         #[arg(long)]
         label: String,
+        /// Restriction on the key, as "<name>=<value>" (repeatable)
+        ///
+        /// Names: endpoints (chat, embeddings, audio, images, models, metrics),
+        /// models (flm://{engine_id}/{model} globs), ips (IPs or CIDR ranges),
+        /// each comma-separated; rpm, burst, tpm, daily_tokens, monthly_tokens.
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
     },
     /// List all API keys (metadata only)
     List,
@@ -17,7 +24,15 @@ pub enum ApiKeysSubcommand {
         /// API key ID to revoke
         id: String,
     },
-    /// Rotate an API key (revoke old and create new)
+    /// Replace the scopes of an API key (no --scope removes all restrictions)
+    Scope {
+        /// API key ID
+        id: String,
+        /// Restriction on the key, as "<name>=<value>" (repeatable, see `create`)
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
+    },
+    /// Rotate an API key (revoke old and create new, keeping its scopes)
     Rotate {
         /// API key ID to rotate
         id: String,
@@ -27,7 +27,7 @@ pub enum ApiPromptsSubcommand {
         /// API identifier
         #[arg(long = "api-id")]
         api_id: String,
-        /// Path to prompt template file (UTF-8 text)
+        /// Path to prompt template file (UTF-8 text; supports {{api_key_label}}, {{date}}, {{model_id}})
         #[arg(long = "file")]
         file: String,
     },
@@ -0,0 +1,33 @@
+//! Concurrency limits CLI definitions
+
+use clap::{Args, Subcommand};
+
+#[derive(Subcommand, Clone)]
+pub enum ConcurrencyLimitsSubcommand {
+    /// List concurrency limits
+    List,
+    /// Set (create/replace) the limit for an engine or model
+    Set(ConcurrencyLimitSetArgs),
+    /// Delete the limit for an engine or model
+    Delete {
+        /// Engine ID or model ID (flm://engine/model)
+        #[arg(long)]
+        target: String,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct ConcurrencyLimitSetArgs {
+    /// Engine ID (e.g. "ollama-default") or model ID (flm://engine/model)
+    #[arg(long)]
+    pub target: String,
+    /// Maximum number of chat requests running at once
+    #[arg(long)]
+    pub max_concurrent: u32,
+    /// Maximum number of requests waiting for a slot (0 rejects immediately when busy)
+    #[arg(long, default_value = "32")]
+    pub max_queue: u32,
+    /// Seconds a queued request waits before it is rejected
+    #[arg(long, default_value = "30")]
+    pub queue_timeout_secs: u32,
+}
@@ -1,6 +1,6 @@
 //! Engines command definitions
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 
 #[derive(Subcommand, Clone)]
 pub enum EnginesSubcommand {
@@ -28,4 +28,118 @@ pub enum EnginesSubcommand {
         #[arg(long, default_value = "100")]
         limit: u32,
     },
+    /// Register an engine by URL (e.g. a remote GPU server)
+    Add(EngineAddArgs),
+    /// Remove a registered engine
+    Remove {
+        /// Engine ID
+        #[arg(long)]
+        id: String,
+    },
+    /// List registered engines
+    List,
+    /// Start a local engine process under supervision (ollama, llamacpp, vllm)
+    Start(EngineStartArgs),
+    /// Stop an engine started with `flm engines start`
+    Stop {
+        /// Engine ID
+        #[arg(long)]
+        id: String,
+    },
+    /// Restart an engine started with `flm engines start`
+    Restart {
+        /// Engine ID
+        #[arg(long)]
+        id: String,
+    },
+    /// Show the log of an engine started with `flm engines start`
+    Logs {
+        /// Engine ID
+        #[arg(long)]
+        id: String,
+        /// Number of lines to show from the end of the log
+        #[arg(long, default_value = "100")]
+        lines: usize,
+        /// Keep printing new log lines until interrupted
+        #[arg(long)]
+        follow: bool,
+    },
+    /// Run the supervisor of an engine process (used internally by `start`)
+    #[command(hide = true)]
+    Supervise {
+        /// Engine runtime directory containing launch.json
+        #[arg(long)]
+        dir: String,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct EngineStartArgs {
+    /// Engine ID (registered as http://{host}:{port} if not already registered)
+    #[arg(long)]
+    pub id: String,
+    /// Engine kind (ollama, llamacpp, vllm); required the first time an engine is started
+    #[arg(long)]
+    pub kind: Option<String>,
+    /// GGUF file (llamacpp) or model name (vllm)
+    #[arg(long)]
+    pub model: Option<String>,
+    /// Engine binary (default: ollama, llama-server or vllm from PATH)
+    #[arg(long)]
+    pub binary: Option<String>,
+    /// Listen host (default: 127.0.0.1)
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Listen port (default: the engine's standard port)
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Extra argument passed to the engine (repeatable, replaces saved args)
+    #[arg(long = "arg", allow_hyphen_values = true)]
+    pub args: Vec<String>,
+    /// Environment variable for the engine, as "KEY=VALUE" (repeatable, replaces saved env)
+    #[arg(long = "env")]
+    pub env: Vec<String>,
+    /// Do not restart the engine when it crashes
+    #[arg(long)]
+    pub no_restart: bool,
+    /// Consecutive restarts allowed before giving up (default: 5)
+    #[arg(long)]
+    pub max_restarts: Option<u32>,
+}
+
+#[derive(Args, Clone)]
+pub struct EngineAddArgs {
+    /// Engine ID (used in flm://{id}/{model})
+    #[arg(long)]
+    pub id: String,
+    /// Engine kind (ollama, vllm, lmstudio, llamacpp, sdwebui, openai)
+    #[arg(long)]
+    pub kind: String,
+    /// Base URL of the engine API (http or https)
+    #[arg(long)]
+    pub base_url: String,
+    /// Header sent with every request, as "Name: value" (e.g. "Authorization: Bearer ...")
+    #[arg(long)]
+    pub auth_header: Option<String>,
+    /// Bearer token sent as "Authorization: Bearer ..." (stored in the OS keyring; consider --bearer-token-stdin)
+    #[arg(long)]
+    pub bearer_token: Option<String>,
+    /// Read the bearer token from stdin (pipe)
+    #[arg(long, default_value_t = false)]
+    pub bearer_token_stdin: bool,
+    /// Capability override for an openai engine model, as "<model>=<caps>" (repeatable)
+    ///
+    /// Caps are comma-separated from reasoning, tools, vision, audio-inputs,
+    /// audio-outputs (or "none"); use "*" as the model to cover every model.
+    #[arg(long = "model-capability")]
+    pub model_capabilities: Vec<String>,
+    /// Request timeout in seconds (default: adapter default)
+    #[arg(long)]
+    pub timeout_secs: Option<u64>,
+    /// PEM CA certificate to trust for https base URLs
+    #[arg(long)]
+    pub tls_ca_cert: Option<String>,
+    /// Skip TLS certificate verification (development only)
+    #[arg(long)]
+    pub tls_insecure: bool,
 }
@@ -5,14 +5,17 @@
 pub mod api_keys;
 pub mod api_prompts;
 pub mod chat;
+pub mod concurrency_limits;
 pub mod config;
 pub mod engines;
 pub mod migrate;
+pub mod model_groups;
 pub mod model_profiles;
 pub mod models;
 pub mod proxy;
 pub mod secrets;
 pub mod security;
+pub mod usage;
 
 use clap::{Parser, Subcommand};
 
@@ -66,6 +69,18 @@ pub enum Commands {
         #[command(subcommand)]
         subcommand: models::ModelsSubcommand,
     },
+    /// Model group (load balancing) management
+    #[command(name = "model-groups")]
+    ModelGroups {
+        #[command(subcommand)]
+        subcommand: model_groups::ModelGroupsSubcommand,
+    },
+    /// Per-engine / per-model concurrency limits
+    #[command(name = "concurrency-limits")]
+    ConcurrencyLimits {
+        #[command(subcommand)]
+        subcommand: concurrency_limits::ConcurrencyLimitsSubcommand,
+    },
     /// Model profile management
     #[command(name = "model-profiles")]
     ModelProfiles {
@@ -82,6 +97,11 @@ pub enum Commands {
         #[command(subcommand)]
         subcommand: security::SecuritySubcommand,
     },
+    /// Per-API-key usage reporting
+    Usage {
+        #[command(subcommand)]
+        subcommand: usage::UsageSubcommand,
+    },
     /// Secrets management (DNS credentials, etc.)
     Secrets {
         #[command(subcommand)]
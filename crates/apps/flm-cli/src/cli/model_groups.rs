@@ -0,0 +1,30 @@
+//! Model groups CLI definitions
+
+use clap::{Args, Subcommand};
+
+#[derive(Subcommand, Clone)]
+pub enum ModelGroupsSubcommand {
+    /// List model groups
+    List,
+    /// Save (create/replace) a group
+    Save(ModelGroupSaveArgs),
+    /// Delete a group by name
+    Delete {
+        /// Group name
+        #[arg(long)]
+        name: String,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct ModelGroupSaveArgs {
+    /// Public model name (served as flm://group/{name})
+    #[arg(long)]
+    pub name: String,
+    /// Backend selection strategy (round-robin, least-in-flight, latency-weighted)
+    #[arg(long, default_value = "round-robin")]
+    pub strategy: String,
+    /// Member model ID (flm://engine/model); repeat for each backend, in priority order
+    #[arg(long = "member", required = true)]
+    pub members: Vec<String>,
+}
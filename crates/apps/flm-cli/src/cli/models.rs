@@ -7,7 +7,50 @@ pub enum ModelsSubcommand {
     /// List available models for an engine
     List {
         /// Engine ID to list models for (e.g., "ollama-default")
+        #[arg(long, required_unless_present = "gguf", conflicts_with = "gguf")]
+        engine: Option<String>,
+        /// List the GGUF files in the managed models directory instead
         #[arg(long)]
-        engine: String,
+        gguf: bool,
+    },
+    /// Download a model (Ollama model name, or an http(s) URL of a GGUF file)
+    Pull {
+        /// Model name (e.g., "llama3.2:3b") or GGUF download URL
+        model: String,
+        /// Engine that pulls the model (default: "ollama-default"; not used for GGUF URLs)
+        #[arg(long)]
+        engine: Option<String>,
+        /// Expected SHA-256 of the GGUF file (hex); the download is discarded on mismatch
+        #[arg(long)]
+        sha256: Option<String>,
+        /// File name to save the GGUF file as (default: last URL path segment)
+        #[arg(long)]
+        file_name: Option<String>,
+    },
+    /// Delete an installed model (Ollama model name or GGUF file name)
+    Delete {
+        /// Model name (e.g., "llama3.2:3b" or "tiny-q4.gguf")
+        model: String,
+        /// Engine that holds the model (default: "ollama-default"; not used for GGUF files)
+        #[arg(long)]
+        engine: Option<String>,
+    },
+    /// Show details of an installed model
+    Show {
+        /// Model name (e.g., "llama3.2:3b" or "tiny-q4.gguf")
+        model: String,
+        /// Engine that holds the model (default: "ollama-default"; not used for GGUF files)
+        #[arg(long)]
+        engine: Option<String>,
+    },
+    /// Copy an installed model under a new name
+    Copy {
+        /// Existing model name
+        source: String,
+        /// New model name
+        destination: String,
+        /// Engine that holds the model (default: "ollama-default"; not used for GGUF files)
+        #[arg(long)]
+        engine: Option<String>,
     },
 }
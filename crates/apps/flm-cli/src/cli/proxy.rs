@@ -47,6 +47,9 @@ pub enum ProxySubcommand {
         /// TXT設置後の待機秒数（dns-01 機能が有効なビルドのみ）
         #[arg(long = "dns-propagation-wait", hide = DNS_FLAGS_HIDDEN)]
         acme_dns_propagation_wait: Option<u64>,
+        /// Also serve the Ollama-compatible API (/api/chat, /api/generate, /api/tags, /api/show)
+        #[arg(long, default_value_t = false)]
+        ollama_api: bool,
         /// Run in foreground (don't daemonize)
         #[arg(long)]
         no_daemon: bool,
@@ -1,6 +1,6 @@
 //! Security command definition
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 
 #[derive(Subcommand, Clone)]
 pub enum SecuritySubcommand {
@@ -38,6 +38,8 @@ pub enum SecuritySubcommand {
         /// Number of logs to skip
         #[arg(long, default_value = "0")]
         offset: u32,
+        #[command(subcommand)]
+        subcommand: Option<AuditLogsSubcommand>,
     },
     /// Intrusion detection events viewing
     Intrusion {
@@ -89,6 +91,109 @@ pub enum SecuritySubcommand {
         #[arg(long)]
         api_key_id: Option<String>,
     },
+    /// Token quota consumption per API key (today and this month, UTC)
+    Quotas {
+        /// Filter by API key ID
+        #[arg(long)]
+        api_key_id: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum AuditLogsSubcommand {
+    /// Check the audit log hash chain and report the first broken link
+    Verify {
+        /// File holding the base64 HMAC key (default: the keyring key, if any)
+        #[arg(long)]
+        hmac_key_file: Option<String>,
+    },
+    /// Create the key that HMAC-signs new audit log entries (OS keyring)
+    #[command(name = "init-hmac-key")]
+    InitHmacKey {
+        /// Also write the base64 key to this file for offline verification
+        #[arg(long)]
+        export: Option<String>,
+    },
+    /// Manage external audit log sinks (syslog, JSONL files, webhooks)
+    Sinks {
+        #[command(subcommand)]
+        subcommand: AuditSinksSubcommand,
+    },
+    /// Write audit log entries with their hash chain columns (--format jsonl or csv)
+    Export {
+        /// Only entries at or after this time (RFC 3339, YYYY-MM-DD, or a duration like 7d / 12h)
+        #[arg(long)]
+        since: Option<String>,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+pub enum AuditSinksSubcommand {
+    /// Add a sink, or replace the sink with the same ID
+    Add(Box<AuditSinkAddArgs>),
+    /// List configured sinks
+    List,
+    /// Remove a sink (and its webhook token)
+    Remove {
+        /// Sink ID
+        #[arg(long)]
+        id: String,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct AuditSinkAddArgs {
+    /// Sink ID
+    #[arg(long)]
+    pub id: String,
+    /// Sink kind (syslog, jsonl, webhook)
+    #[arg(long)]
+    pub kind: String,
+    /// syslog: collector address as host:port
+    #[arg(long)]
+    pub address: Option<String>,
+    /// syslog: transport (udp or tcp, default: udp)
+    #[arg(long)]
+    pub transport: Option<String>,
+    /// syslog: facility number 0-23 (default: 13, log audit)
+    #[arg(long)]
+    pub facility: Option<u8>,
+    /// syslog: APP-NAME header (default: flm-proxy)
+    #[arg(long)]
+    pub app_name: Option<String>,
+    /// jsonl: file to append to
+    #[arg(long)]
+    pub path: Option<String>,
+    /// jsonl: rotate when the file would exceed this size (default: 10485760)
+    #[arg(long)]
+    pub max_bytes: Option<u64>,
+    /// jsonl: rotated files kept (default: 5)
+    #[arg(long)]
+    pub max_files: Option<u32>,
+    /// webhook: URL that receives JSON arrays of entries (POST)
+    #[arg(long)]
+    pub url: Option<String>,
+    /// webhook: entries per request (default: 100)
+    #[arg(long)]
+    pub batch_size: Option<u32>,
+    /// webhook: send a partial batch after this many seconds (default: 5)
+    #[arg(long)]
+    pub flush_interval_secs: Option<u32>,
+    /// webhook: retries of a failed request (default: 5)
+    #[arg(long)]
+    pub max_retries: Option<u32>,
+    /// webhook: Authorization scheme for the token (default: Bearer)
+    #[arg(long)]
+    pub auth_scheme: Option<String>,
+    /// webhook: token sent as "Authorization: <scheme> <token>" (stored in the OS keyring; consider --bearer-token-stdin)
+    #[arg(long)]
+    pub bearer_token: Option<String>,
+    /// webhook: read the token from stdin (pipe)
+    #[arg(long, default_value_t = false)]
+    pub bearer_token_stdin: bool,
 }
 
 #[derive(Subcommand, Clone)]
@@ -117,21 +222,84 @@ pub enum PolicySubcommand {
         #[arg(long)]
         file: Option<String>,
     },
+    /// Show the fields a new policy would change in the current one
+    Diff {
+        /// Policy JSON as string
+        #[arg(long)]
+        json: Option<String>,
+        /// Path to policy JSON file
+        #[arg(long)]
+        file: Option<String>,
+    },
+    /// Show which policy rules a request would hit
+    Test {
+        /// Client IP address of the request
+        #[arg(long)]
+        ip: String,
+        /// ID of the API key the request authenticates with
+        #[arg(long)]
+        key: Option<String>,
+        /// Test this policy JSON instead of the current policy
+        #[arg(long)]
+        json: Option<String>,
+        /// Test the policy in this file instead of the current policy
+        #[arg(long)]
+        file: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Clone)]
 pub enum BackupSubcommand {
-    /// Create an encrypted backup of security.db
+    /// Create encrypted, authenticated backups of security.db and config.db
     Create {
         /// Output directory (default: OS config directory/flm/backups/)
         #[arg(long)]
         output: Option<String>,
+        /// Database to back up: security, config or all
+        #[arg(long, default_value = "all", value_parser = ["security", "config", "all"])]
+        database: String,
+        /// Backups kept per database (default: security.backup.retention, else 3)
+        #[arg(long)]
+        keep: Option<usize>,
+        /// File holding the passphrase (default: FLM_BACKUP_PASSPHRASE, else the keyring key)
+        #[arg(long)]
+        passphrase_file: Option<String>,
     },
-    /// Restore security.db from a backup
+    /// Check that a backup opens with the key and holds an intact database
+    Verify {
+        /// Path to backup file
+        #[arg(long)]
+        file: String,
+        /// File holding the passphrase (default: FLM_BACKUP_PASSPHRASE)
+        #[arg(long)]
+        passphrase_file: Option<String>,
+    },
+    /// Restore security.db or config.db from a backup
     Restore {
         /// Path to backup file
         #[arg(long)]
         file: String,
+        /// File holding the passphrase (default: FLM_BACKUP_PASSPHRASE)
+        #[arg(long)]
+        passphrase_file: Option<String>,
+        /// Restore an unencrypted legacy backup, which cannot be authenticated
+        #[arg(long)]
+        allow_plaintext: bool,
+    },
+    /// Show or change the proxy's periodic backups (keyring key only)
+    Schedule {
+        /// Hours between backups taken by the running proxy
+        #[arg(long, conflicts_with = "disable")]
+        every_hours: Option<u32>,
+        /// Output directory (default: OS config directory/flm/backups/)
+        #[arg(long)]
+        output: Option<String>,
+        /// Backups kept per database
+        #[arg(long)]
+        keep: Option<usize>,
+        /// Stop periodic backups
+        #[arg(long)]
+        disable: bool,
     },
 }
 
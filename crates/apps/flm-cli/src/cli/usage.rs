@@ -0,0 +1,21 @@
+//! Usage reporting CLI definitions
+
+use clap::Subcommand;
+
+#[derive(Subcommand, Clone)]
+pub enum UsageSubcommand {
+    /// Token usage per API key, recorded by the proxy for each metered request
+    ///
+    /// Prints JSON (default), CSV (`--format csv`) or text.
+    Report {
+        /// Only include this API key ID
+        #[arg(long)]
+        key: Option<String>,
+        /// Start of the period: YYYY-MM-DD, RFC3339 or a relative age such as 7d / 12h (UTC)
+        #[arg(long)]
+        since: Option<String>,
+        /// Split each key's usage by model or UTC day
+        #[arg(long, value_parser = ["key", "model", "day"], default_value = "key")]
+        group_by: String,
+    },
+}
@@ -2,7 +2,10 @@
 
 use crate::adapters::SqliteSecurityRepository;
 use crate::cli::api_keys::ApiKeysSubcommand;
+use crate::commands::CliUserError;
 use crate::utils::get_security_db_path;
+use flm_core::domain::security::{ApiKeyScopes, EndpointScope};
+use flm_core::error::RepoError;
 use flm_core::services::SecurityService;
 use serde_json::json;
 use std::path::PathBuf;
@@ -10,9 +13,11 @@ use std::path::PathBuf;
 /// Execute api-keys create command
 pub async fn execute_create(
     label: String,
+    scopes: Vec<String>,
     db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let scopes = parse_scopes(&scopes)?;
     let db_path = db_path
         .map(PathBuf::from)
         .unwrap_or_else(get_security_db_path);
@@ -21,7 +26,7 @@ pub async fn execute_create(
     let repo = SqliteSecurityRepository::new(&db_path).await?;
     let service = SecurityService::new(repo);
 
-    let result = service.create_api_key(&label).await?;
+    let result = service.create_scoped_api_key(&label, scopes).await?;
 
     if format == "json" {
         let output = json!({
@@ -30,7 +35,8 @@ pub async fn execute_create(
                 "id": result.record.id,
                 "label": result.record.label,
                 "plain_key": result.plain,
-                "created_at": result.record.created_at
+                "created_at": result.record.created_at,
+                "scopes": result.record.scopes
             }
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
@@ -39,6 +45,9 @@ pub async fn execute_create(
         println!("  ID: {}", result.record.id);
         println!("  Label: {}", result.record.label);
         println!("  Plain Key: {}", result.plain);
+        if !result.record.scopes.is_unrestricted() {
+            println!("  Scopes: {}", describe_scopes(&result.record.scopes));
+        }
         println!("\n⚠️  WARNING: This key will only be shown once. Save it securely!");
     }
 
@@ -74,6 +83,9 @@ pub async fn execute_list(
         println!("API Keys:");
         for key in keys {
             println!("  {} - {} (created: {})", key.id, key.label, key.created_at);
+            if !key.scopes.is_unrestricted() {
+                println!("    Scopes: {}", describe_scopes(&key.scopes));
+            }
         }
     }
 
@@ -112,6 +124,49 @@ pub async fn execute_revoke(
     Ok(())
 }
 
+/// Execute api-keys scope command
+pub async fn execute_scope(
+    id: String,
+    scopes: Vec<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let scopes = parse_scopes(&scopes)?;
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+
+    // Initialize repository (migrations run automatically)
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+    let service = SecurityService::new(repo);
+
+    let key = match service.set_api_key_scopes(&id, scopes).await {
+        Ok(key) => key,
+        Err(RepoError::NotFound { .. }) => {
+            return Err(CliUserError::new(format!("Active API key '{id}' not found")).into())
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "id": key.id,
+                "label": key.label,
+                "scopes": key.scopes
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if key.scopes.is_unrestricted() {
+        println!("API key '{id}' is now unrestricted");
+    } else {
+        println!("API key '{id}' scopes: {}", describe_scopes(&key.scopes));
+    }
+
+    Ok(())
+}
+
 /// Execute api-keys rotate command
 pub async fn execute_rotate(
     id: String,
@@ -160,9 +215,100 @@ pub async fn execute(
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match subcommand {
-        ApiKeysSubcommand::Create { label } => execute_create(label, db_path, format).await,
+        ApiKeysSubcommand::Create { label, scopes } => {
+            execute_create(label, scopes, db_path, format).await
+        }
         ApiKeysSubcommand::List => execute_list(db_path, format).await,
         ApiKeysSubcommand::Revoke { id } => execute_revoke(id, db_path, format).await,
+        ApiKeysSubcommand::Scope { id, scopes } => execute_scope(id, scopes, db_path, format).await,
         ApiKeysSubcommand::Rotate { id, label } => execute_rotate(id, label, db_path, format).await,
     }
 }
+
+/// Parse `--scope <name>=<value>` arguments
+///
+/// List scopes accumulate over repeated arguments; limits take the last value.
+fn parse_scopes(values: &[String]) -> Result<ApiKeyScopes, CliUserError> {
+    let mut scopes = ApiKeyScopes::default();
+    for value in values {
+        let (name, raw) = value
+            .split_once('=')
+            .map(|(name, raw)| (name.trim(), raw.trim()))
+            .ok_or_else(|| {
+                CliUserError::new(format!(
+                    "Invalid --scope '{value}' (expected \"<name>=<value>\", e.g. \"endpoints=chat\")"
+                ))
+            })?;
+        let items = || {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+        };
+        let limit = || {
+            raw.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                CliUserError::new(format!(
+                    "Invalid --scope '{value}': {name} must be a positive integer"
+                ))
+            })
+        };
+        let limit_u32 = || {
+            limit().and_then(|n| {
+                u32::try_from(n).map_err(|_| {
+                    CliUserError::new(format!("Invalid --scope '{value}': {name} is too large"))
+                })
+            })
+        };
+        match name {
+            "endpoints" => {
+                for item in items() {
+                    let endpoint: EndpointScope = item.parse().map_err(CliUserError::new)?;
+                    if !scopes.endpoints.contains(&endpoint) {
+                        scopes.endpoints.push(endpoint);
+                    }
+                }
+            }
+            "models" => scopes.models.extend(items().map(str::to_string)),
+            "ips" => scopes.ip_allowlist.extend(items().map(str::to_string)),
+            "rpm" => scopes.rate_limit.rpm = Some(limit_u32()?),
+            "burst" => scopes.rate_limit.burst = Some(limit_u32()?),
+            "tpm" => scopes.rate_limit.tpm = Some(limit()?),
+            "daily_tokens" => scopes.rate_limit.daily_tokens = Some(limit()?),
+            "monthly_tokens" => scopes.rate_limit.monthly_tokens = Some(limit()?),
+            other => {
+                return Err(CliUserError::new(format!(
+                    "Unknown scope '{other}' (expected endpoints, models, ips, rpm, burst, tpm, daily_tokens or monthly_tokens)"
+                )))
+            }
+        }
+    }
+    scopes.validate().map_err(CliUserError::new)?;
+    Ok(scopes)
+}
+
+/// One-line summary of a key's scopes for text output
+fn describe_scopes(scopes: &ApiKeyScopes) -> String {
+    let mut parts = Vec::new();
+    if !scopes.endpoints.is_empty() {
+        let endpoints: Vec<&str> = scopes.endpoints.iter().map(EndpointScope::as_str).collect();
+        parts.push(format!("endpoints={}", endpoints.join(",")));
+    }
+    if !scopes.models.is_empty() {
+        parts.push(format!("models={}", scopes.models.join(",")));
+    }
+    if !scopes.ip_allowlist.is_empty() {
+        parts.push(format!("ips={}", scopes.ip_allowlist.join(",")));
+    }
+    let limits = &scopes.rate_limit;
+    for (name, value) in [
+        ("rpm", limits.rpm.map(u64::from)),
+        ("burst", limits.burst.map(u64::from)),
+        ("tpm", limits.tpm),
+        ("daily_tokens", limits.daily_tokens),
+        ("monthly_tokens", limits.monthly_tokens),
+    ] {
+        if let Some(value) = value {
+            parts.push(format!("{name}={value}"));
+        }
+    }
+    parts.join(" ")
+}
@@ -5,6 +5,7 @@ use crate::cli::chat::Chat;
 use crate::utils::get_config_db_path;
 use flm_core::domain::chat::{
     ChatMessage, ChatRequest, ChatRole, MultimodalAttachment, MultimodalAttachmentKind,
+    SamplingParams,
 };
 use flm_core::ports::{EngineRepository, LlmEngine};
 use flm_core::services::EngineService;
@@ -231,16 +232,18 @@ pub async fn execute(
     let http_client = Box::new(ReqwestHttpClient::new()?);
     let engine_repo_arc = SqliteEngineRepository::new(&db_path).await?;
     let engine_repo: Box<dyn EngineRepository + Send + Sync> =
-        Box::new(ArcEngineRepositoryWrapper(engine_repo_arc));
+        Box::new(ArcEngineRepositoryWrapper(engine_repo_arc.clone()));
+    let registry =
+        crate::commands::engines::load_registered_engines(&db_path, engine_repo.as_ref()).await?;
 
     // Create service
-    let service = EngineService::new(process_controller, http_client, engine_repo);
+    let service = EngineService::new(process_controller, http_client, engine_repo)
+        .with_engine_registry(registry);
 
     // First, detect engines to register them
     service.detect_engines().await?;
 
     // Get engine and model capabilities to validate multimodal support
-    let engine_repo_arc = SqliteEngineRepository::new(&db_path).await?;
     let engines = engine_repo_arc.list_registered().await;
     let engine = engines
         .iter()
@@ -316,12 +319,18 @@ pub async fn execute(
             role: ChatRole::User,
             content: chat.prompt.clone(),
             attachments,
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: chat.stream,
         temperature: chat.temperature,
         max_tokens: chat.max_tokens,
         stop: Vec::new(),
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     if chat.stream {
@@ -3,12 +3,20 @@
 use crate::adapters::{SqliteConfigRepository, SqliteSecurityRepository};
 use crate::commands::CliUserError;
 use crate::utils::{get_config_db_path, get_security_db_path};
+use flm_core::domain::policy::PolicyDocument;
+use flm_core::domain::security::RetentionTable;
 use flm_core::services::{ConfigService, SecurityService};
 use serde_json::json;
 use sqlx::sqlite::SqlitePool;
 use sqlx::Row;
 use std::path::PathBuf;
 
+/// Rows in an event table without `max_rows` above which retention is suggested
+const UNBOUNDED_TABLE_WARN_ROWS: i64 = 1_000_000;
+
+/// security.db size above which retention is suggested
+const SECURITY_DB_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+
 /// Check result for a single check item
 #[derive(Debug, Clone)]
 struct CheckItem {
@@ -359,6 +367,7 @@ async fn check_security_db(
     }
 
     // Check security_policies integrity
+    let mut retention = None;
     if let Ok(rows) = sqlx::query("SELECT COUNT(*) as count FROM security_policies")
         .fetch_one(&pool)
         .await
@@ -381,23 +390,40 @@ async fn check_security_db(
                 .await
         {
             let policy_json: String = policy_row.get("policy_json");
-            match serde_json::from_str::<serde_json::Value>(&policy_json) {
-                Ok(_) => {
+            match PolicyDocument::parse_lenient(&policy_json) {
+                Ok((policy, ignored)) if !ignored.is_empty() => {
+                    // The proxy ignores these fields
+                    checks.push(CheckItem {
+                        name: "security.db default policy JSON".to_string(),
+                        status: CheckStatus::Warning,
+                        message: Some(format!(
+                            "Unknown fields are ignored: {}",
+                            ignored.join(", ")
+                        )),
+                    });
+                    retention = policy.retention;
+                }
+                Ok((policy, _)) => {
                     checks.push(CheckItem {
                         name: "security.db default policy JSON".to_string(),
                         status: CheckStatus::Ok,
                         message: if verbose {
-                            Some("Policy JSON is valid".to_string())
+                            Some(format!(
+                                "Policy JSON is valid (schema version {})",
+                                policy.schema_version
+                            ))
                         } else {
                             None
                         },
                     });
+                    retention = policy.retention;
                 }
-                Err(e) => {
+                Err(reason) => {
+                    // The proxy denies every request until the policy is fixed
                     checks.push(CheckItem {
                         name: "security.db default policy JSON".to_string(),
                         status: CheckStatus::Error,
-                        message: Some(format!("Invalid JSON: {e}")),
+                        message: Some(reason),
                     });
                 }
             }
@@ -412,6 +438,70 @@ async fn check_security_db(
         }
     }
 
+    // Event tables grow until the proxy prunes them (`retention` policy)
+    for table in RetentionTable::ALL {
+        let name = table.table_name();
+        let Ok(rows) = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {name}"))
+            .fetch_one(&pool)
+            .await
+        else {
+            continue;
+        };
+        let max_rows = retention
+            .as_ref()
+            .and_then(|retention| retention.table(table))
+            .and_then(|limits| limits.max_rows);
+        let message = match max_rows {
+            // Allow for rows written since the proxy's last pruning run
+            Some(max_rows) if rows as u64 > max_rows + max_rows / 10 => Some(format!(
+                "{rows} rows, above retention.{name}.max_rows ({max_rows}); is the proxy running?"
+            )),
+            None if rows > UNBOUNDED_TABLE_WARN_ROWS => Some(format!(
+                "{rows} rows; set retention.{name} in the security policy to prune old rows"
+            )),
+            _ => None,
+        };
+        checks.push(match message {
+            Some(message) => CheckItem {
+                name: format!("security.db {name} size"),
+                status: CheckStatus::Warning,
+                message: Some(message),
+            },
+            None => CheckItem {
+                name: format!("security.db {name} size"),
+                status: CheckStatus::Ok,
+                message: if verbose {
+                    Some(format!("{rows} rows"))
+                } else {
+                    None
+                },
+            },
+        });
+    }
+
+    if let Ok(metadata) = std::fs::metadata(db_path) {
+        let size_mib = metadata.len() / (1024 * 1024);
+        checks.push(if metadata.len() > SECURITY_DB_WARN_BYTES {
+            CheckItem {
+                name: "security.db file size".to_string(),
+                status: CheckStatus::Warning,
+                message: Some(format!(
+                    "{size_mib} MiB; set retention in the security policy to prune old events"
+                )),
+            }
+        } else {
+            CheckItem {
+                name: "security.db file size".to_string(),
+                status: CheckStatus::Ok,
+                message: if verbose {
+                    Some(format!("{size_mib} MiB"))
+                } else {
+                    None
+                },
+            }
+        });
+    }
+
     // Test SecurityService
     match SqliteSecurityRepository::new(db_path).await {
         Ok(repo) => {
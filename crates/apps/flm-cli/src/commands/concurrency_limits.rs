@@ -0,0 +1,175 @@
+//! `flm concurrency-limits` command implementation
+
+use crate::adapters::SqliteConcurrencyLimitRepository;
+use crate::cli::concurrency_limits::{ConcurrencyLimitSetArgs, ConcurrencyLimitsSubcommand};
+use crate::commands::CliUserError;
+use crate::utils::get_config_db_path;
+use flm_core::domain::models::ConcurrencyLimit;
+use flm_core::error::RepoError;
+use flm_core::ports::ConcurrencyLimitRepository;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// Upper bound for queue timeouts; longer waits are better handled by client retries
+const MAX_QUEUE_TIMEOUT_SECS: u32 = 3600;
+
+pub async fn execute(
+    subcommand: ConcurrencyLimitsSubcommand,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_config_db_path);
+
+    let repo = SqliteConcurrencyLimitRepository::new(&db_path).await?;
+
+    match subcommand {
+        ConcurrencyLimitsSubcommand::List => {
+            let limits = repo.list_limits().await?;
+            render_list(&limits, &format)?;
+        }
+        ConcurrencyLimitsSubcommand::Set(args) => {
+            let limit = save_limit(&repo, args).await?;
+            render_single(&limit, &format)?;
+        }
+        ConcurrencyLimitsSubcommand::Delete { target } => {
+            let deleted = repo.delete_limit(&target).await?;
+            if !deleted {
+                return Err(Box::new(CliUserError::new(format!(
+                    "Concurrency limit for '{target}' not found"
+                ))));
+            }
+            if format == "json" {
+                let output = json!({
+                    "version": "1.0",
+                    "data": {
+                        "deleted": true,
+                        "target": target
+                    }
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("Deleted concurrency limit for {target}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn save_limit(
+    repo: &SqliteConcurrencyLimitRepository,
+    args: ConcurrencyLimitSetArgs,
+) -> Result<ConcurrencyLimit, Box<dyn std::error::Error>> {
+    validate_target(&args.target)?;
+    if args.max_concurrent == 0 {
+        return Err(Box::new(CliUserError::new(
+            "--max-concurrent must be at least 1",
+        )));
+    }
+    if args.queue_timeout_secs == 0 || args.queue_timeout_secs > MAX_QUEUE_TIMEOUT_SECS {
+        return Err(Box::new(CliUserError::new(format!(
+            "--queue-timeout-secs must be between 1 and {MAX_QUEUE_TIMEOUT_SECS}"
+        ))));
+    }
+
+    let limit = repo
+        .save_limit(
+            &args.target,
+            args.max_concurrent,
+            args.max_queue,
+            args.queue_timeout_secs,
+        )
+        .await
+        .map_err(map_repo_err)?;
+
+    Ok(limit)
+}
+
+/// Targets are an engine ID or a concrete `flm://{engine_id}/{model}` (no groups or profiles)
+fn validate_target(target: &str) -> Result<(), CliUserError> {
+    let valid = match target.strip_prefix("flm://") {
+        Some(rest) => rest.split_once('/').is_some_and(|(engine, model)| {
+            !engine.is_empty() && engine != "group" && engine != "profile" && !model.is_empty()
+        }),
+        None => {
+            !target.is_empty()
+                && target.len() <= 128
+                && target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        }
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(CliUserError::new(format!(
+            "Invalid target '{target}' (expected an engine ID or flm://{{engine_id}}/{{model_name}})"
+        )))
+    }
+}
+
+fn render_list(
+    limits: &[ConcurrencyLimit],
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "limits": limits
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if limits.is_empty() {
+        println!("No concurrency limits found.");
+        return Ok(());
+    }
+
+    for limit in limits {
+        print_limit(limit);
+        println!("  Updated: {}", limit.updated_at);
+    }
+
+    Ok(())
+}
+
+fn render_single(limit: &ConcurrencyLimit, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "limit": limit
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_limit(limit);
+        println!("Restart the proxy to apply the change.");
+    }
+
+    Ok(())
+}
+
+fn print_limit(limit: &ConcurrencyLimit) {
+    let scope = if limit.is_model_limit() {
+        "model"
+    } else {
+        "engine"
+    };
+    println!("Target: {} ({scope})", limit.target);
+    println!("  Max concurrent: {}", limit.max_concurrent);
+    println!("  Max queue: {}", limit.max_queue);
+    println!("  Queue timeout: {}s", limit.queue_timeout_secs);
+}
+
+fn map_repo_err(err: RepoError) -> Box<dyn std::error::Error> {
+    match err {
+        RepoError::IoError { reason } => Box::new(CliUserError::new(reason)),
+        other => Box::new(other),
+    }
+}
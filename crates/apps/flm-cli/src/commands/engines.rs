@@ -1,19 +1,34 @@
 //! Engines command implementation
 
+use crate::adapters::engine_supervisor::{run_supervisor, EngineRuntimeFiles};
 use crate::adapters::{
     DefaultEngineProcessController, ReqwestHttpClient, SqliteEngineHealthLogRepository,
-    SqliteEngineRepository,
+    SqliteEngineProcessConfigRepository, SqliteEngineRegistryRepository, SqliteEngineRepository,
 };
-use crate::cli::engines::EnginesSubcommand;
+use crate::cli::engines::{EngineAddArgs, EngineStartArgs, EnginesSubcommand};
 use crate::commands::CliUserError;
+use crate::utils::secrets::{
+    delete_engine_auth_header, delete_engine_token, keyring_disabled, load_engine_auth_header,
+    load_engine_token, store_engine_auth_header, store_engine_token,
+};
 use chrono::Utc;
-use flm_core::domain::engine::EngineState;
-use flm_core::domain::models::EngineKind;
-use flm_core::ports::{EngineHealthLogRepository, EngineRepository, LlmEngine};
+use flm_core::domain::engine::{
+    EngineCredentials, EngineProcessConfig, EngineProcessStatus, EngineRegistration, EngineState,
+    EngineTlsConfig,
+};
+use flm_core::domain::models::{EngineId, EngineKind, ModelCapabilities};
+use flm_core::error::EngineError;
+use flm_core::ports::{
+    EngineHealthLogRepository, EngineProcessConfigRepository, EngineProcessController,
+    EngineRegistryRepository, EngineRepository, LlmEngine,
+};
 use flm_core::services::EngineService;
 use serde_json::json;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 const ENGINE_CACHE_TTL_SECONDS: u64 = 300;
 
@@ -69,8 +84,12 @@ pub async fn execute_detect(
     let engine_repo: Box<dyn flm_core::ports::EngineRepository + Send + Sync> =
         Box::new(ArcEngineRepositoryWrapper(engine_repo_arc.clone()));
 
+    // Registered engines are health-checked alongside auto-detected ones
+    let registry = load_registered_engines(&db_path, engine_repo.as_ref()).await?;
+
     // Create service
-    let service = EngineService::new(process_controller, http_client, engine_repo);
+    let service = EngineService::new(process_controller, http_client, engine_repo)
+        .with_engine_registry(registry);
 
     // Detect engines
     let states = if let Some(engine_name) = engine {
@@ -80,9 +99,11 @@ pub async fn execute_detect(
             "vllm" => EngineKind::Vllm,
             "lmstudio" | "lm-studio" => EngineKind::LmStudio,
             "llamacpp" | "llama-cpp" => EngineKind::LlamaCpp,
+            "sdwebui" | "stable-diffusion" => EngineKind::StableDiffusion,
+            "openai" | "openai-compatible" => EngineKind::OpenAiCompatible,
             _ => {
                 let message = format!(
-                    "Unknown engine: {engine_name}\nSupported engines: ollama, vllm, lmstudio, llamacpp"
+                    "Unknown engine: {engine_name}\nSupported engines: ollama, vllm, lmstudio, llamacpp, sdwebui, openai"
                 );
                 return Err(Box::new(CliUserError::new(message)));
             }
@@ -186,6 +207,856 @@ pub async fn execute(
             hours,
             limit,
         } => execute_health_history(engine, model, hours, limit, db_path, format).await,
+        EnginesSubcommand::Add(args) => execute_add(args, db_path, format).await,
+        EnginesSubcommand::Remove { id } => execute_remove(id, db_path, format).await,
+        EnginesSubcommand::List => execute_list(db_path, format).await,
+        EnginesSubcommand::Start(args) => execute_start(args, db_path, format).await,
+        EnginesSubcommand::Stop { id } => execute_stop(id, format).await,
+        EnginesSubcommand::Restart { id } => execute_restart(id, db_path, format).await,
+        EnginesSubcommand::Logs { id, lines, follow } => execute_logs(id, lines, follow).await,
+        EnginesSubcommand::Supervise { dir } => execute_supervise(dir).await,
+    }
+}
+
+/// Register engines from the `engines` table into `engine_repo`
+///
+/// Returns the registry so it can also be handed to `EngineService`.
+pub(crate) async fn load_registered_engines(
+    db_path: &Path,
+    engine_repo: &dyn EngineRepository,
+) -> Result<Arc<SqliteEngineRegistryRepository>, Box<dyn std::error::Error>> {
+    let registry = Arc::new(SqliteEngineRegistryRepository::new(db_path).await?);
+    let credentials = resolve_engine_credentials(registry.as_ref()).await?;
+    flm_proxy::engine_repo::register_from_registry(engine_repo, registry.as_ref(), &credentials)
+        .await;
+    Ok(registry)
+}
+
+/// Load the keyring secrets (bearer tokens, auth header values) of registered engines
+///
+/// Auth header values that earlier versions kept in `config.db` are moved to
+/// the keyring first. Missing secrets are left out; `register_from_registry`
+/// warns about them.
+pub(crate) async fn resolve_engine_credentials(
+    registry: &SqliteEngineRegistryRepository,
+) -> Result<HashMap<EngineId, EngineCredentials>, Box<dyn std::error::Error>> {
+    let mut credentials: HashMap<EngineId, EngineCredentials> = HashMap::new();
+    for (id, value) in registry.legacy_auth_header_values().await? {
+        if keyring_disabled() {
+            eprintln!(
+                "Warning: keyring is disabled (FLM_DISABLE_KEYRING); the auth header of engine '{id}' stays in config.db"
+            );
+        } else if let Err(e) = store_engine_auth_header(&id, &value) {
+            eprintln!(
+                "Warning: failed to move the auth header of engine '{id}' to the keyring: {e}"
+            );
+        } else {
+            registry.clear_legacy_auth_header_value(&id).await?;
+        }
+        credentials.entry(id).or_default().auth_header_value = Some(value);
+    }
+
+    for registration in registry.list().await? {
+        let entry = credentials.entry(registration.id.clone()).or_default();
+        if registration.bearer_token_in_keyring {
+            entry.bearer_token = load_engine_token(&registration.id).ok();
+        }
+        if registration.auth_header_name.is_some() && entry.auth_header_value.is_none() {
+            entry.auth_header_value = load_engine_auth_header(&registration.id).ok();
+        }
+    }
+    credentials.retain(|_, secrets| *secrets != EngineCredentials::default());
+    Ok(credentials)
+}
+
+/// Execute engines add command
+pub async fn execute_add(
+    args: EngineAddArgs,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_db_path);
+
+    validate_engine_id(&args.id)?;
+    let kind: EngineKind = args.kind.parse().map_err(CliUserError::new)?;
+    let mut base_url = validate_base_url(&args.base_url)?;
+    if kind == EngineKind::OpenAiCompatible {
+        // Users often paste the documented `.../v1` URL; the adapter adds `/v1` itself
+        if let Some(stripped) = base_url.strip_suffix("/v1") {
+            base_url = stripped.to_string();
+        }
+    }
+    let (auth_header_name, auth_header_value) = match args.auth_header.as_deref() {
+        Some(header) => {
+            let (name, value) = parse_auth_header(header)?;
+            (Some(name), Some(value))
+        }
+        None => (None, None),
+    };
+    let bearer_token = resolve_bearer_token(&args)?;
+    if bearer_token.is_some()
+        && auth_header_name
+            .as_ref()
+            .is_some_and(|name| name.eq_ignore_ascii_case("authorization"))
+    {
+        return Err(Box::new(CliUserError::new(
+            "Use either --bearer-token or an Authorization --auth-header, not both",
+        )));
+    }
+    let model_capabilities = parse_model_capabilities(&args.model_capabilities)?;
+    if !model_capabilities.is_empty() && kind != EngineKind::OpenAiCompatible {
+        return Err(Box::new(CliUserError::new(
+            "--model-capability is only supported for openai engines",
+        )));
+    }
+    if args.timeout_secs == Some(0) {
+        return Err(Box::new(CliUserError::new(
+            "--timeout-secs must be greater than 0",
+        )));
+    }
+    if let Some(path) = &args.tls_ca_cert {
+        if !Path::new(path).is_file() {
+            return Err(Box::new(CliUserError::new(format!(
+                "CA certificate not found: {path}"
+            ))));
+        }
+    }
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path).await?;
+    if registry.get(&args.id).await?.is_some() {
+        return Err(Box::new(CliUserError::new(format!(
+            "Engine '{}' is already registered (remove it first)",
+            args.id
+        ))));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let registration = EngineRegistration {
+        id: args.id,
+        kind,
+        base_url,
+        auth_header_name,
+        timeout_secs: args.timeout_secs,
+        tls: EngineTlsConfig {
+            ca_cert_path: args.tls_ca_cert,
+            insecure_skip_verify: args.tls_insecure,
+        },
+        bearer_token_in_keyring: bearer_token.is_some(),
+        model_capabilities,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    // Catch settings reqwest rejects (e.g. a malformed CA file) before saving
+    let credentials = EngineCredentials {
+        bearer_token,
+        auth_header_value,
+    };
+    flm_proxy::engine_repo::build_engine(&registration, &credentials)
+        .map_err(|e| CliUserError::new(e.to_string()))?;
+
+    // Secrets go to the keyring; config.db only records that they exist
+    registry.save(&registration).await?;
+    if let Err(e) = store_engine_secrets(&registration.id, &credentials) {
+        registry.remove(&registration.id).await?;
+        let _ = delete_engine_token(&registration.id);
+        return Err(Box::new(CliUserError::new(format!(
+            "Failed to store engine credentials in keyring: {e}"
+        ))));
+    }
+    if keyring_disabled() {
+        if credentials.bearer_token.is_some() {
+            eprintln!(
+                "Warning: keyring is disabled (FLM_DISABLE_KEYRING); the bearer token was not stored"
+            );
+        }
+        if credentials.auth_header_value.is_some() {
+            eprintln!(
+                "Warning: keyring is disabled (FLM_DISABLE_KEYRING); the auth header value was not stored"
+            );
+        }
+    }
+    let saved = registry
+        .get(&registration.id)
+        .await?
+        .unwrap_or(registration);
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": registration_json(&saved)
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Registered engine {} ({})", saved.id, saved.base_url);
+    }
+
+    Ok(())
+}
+
+/// Execute engines remove command
+pub async fn execute_remove(
+    id: String,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_db_path);
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path).await?;
+    let Some(registration) = registry.get(&id).await? else {
+        return Err(Box::new(CliUserError::new(format!(
+            "Engine '{id}' is not registered"
+        ))));
+    };
+    let controller = DefaultEngineProcessController::new();
+    if controller
+        .status(&id)
+        .is_some_and(|status| status.state.is_active())
+    {
+        return Err(Box::new(CliUserError::new(format!(
+            "Engine '{id}' is running; stop it first (flm engines stop --id {id})"
+        ))));
+    }
+    registry.remove(&id).await?;
+    let processes = SqliteEngineProcessConfigRepository::new(&db_path).await?;
+    if processes.remove(&id).await? {
+        let _ = std::fs::remove_dir_all(controller.runtime_files(&id).dir());
+    }
+
+    if registration.bearer_token_in_keyring && !keyring_disabled() {
+        if let Err(err) = delete_engine_token(&id) {
+            eprintln!("Warning: engine removed from DB but keyring cleanup failed: {err}");
+        }
+    }
+    if registration.auth_header_name.is_some() && !keyring_disabled() {
+        if let Err(err) = delete_engine_auth_header(&id) {
+            eprintln!("Warning: engine removed from DB but keyring cleanup failed: {err}");
+        }
+    }
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "deleted": true,
+                "id": id
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Removed engine {id}");
+    }
+
+    Ok(())
+}
+
+/// Execute engines list command
+pub async fn execute_list(
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_db_path);
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path).await?;
+    let registrations = registry.list().await?;
+    // Engines started with `flm engines start` also report their process state
+    let controller = DefaultEngineProcessController::new();
+    let mut process_status = HashMap::new();
+    for config in SqliteEngineProcessConfigRepository::new(&db_path)
+        .await?
+        .list()
+        .await?
+    {
+        let status = controller.status(&config.engine_id);
+        process_status.insert(config.engine_id, status);
+    }
+
+    if format == "json" {
+        let engines: Vec<_> = registrations
+            .iter()
+            .map(|registration| {
+                let mut value = registration_json(registration);
+                if let Some(status) = process_status.get(&registration.id) {
+                    value["process"] = json!(status);
+                }
+                value
+            })
+            .collect();
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engines": engines
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if registrations.is_empty() {
+        println!("No engines registered.");
+    } else {
+        println!("Registered {} engine(s):", registrations.len());
+        for registration in &registrations {
+            println!("\nEngine: {}", registration.id);
+            println!("  Kind: {}", registration.kind.as_str());
+            println!("  Base URL: {}", registration.base_url);
+            if let Some(name) = &registration.auth_header_name {
+                println!("  Auth Header: {name}: **** (stored in keyring)");
+            }
+            if registration.bearer_token_in_keyring {
+                println!("  Bearer Token: stored in keyring");
+            }
+            for (model, caps) in &registration.model_capabilities {
+                println!(
+                    "  Capabilities [{model}]: {}",
+                    capability_names(caps).join(", ")
+                );
+            }
+            if let Some(timeout) = registration.timeout_secs {
+                println!("  Timeout: {timeout}s");
+            }
+            if let Some(path) = &registration.tls.ca_cert_path {
+                println!("  TLS CA: {path}");
+            }
+            if registration.tls.insecure_skip_verify {
+                println!("  TLS Verify: disabled");
+            }
+            match process_status.get(&registration.id) {
+                Some(Some(status)) => println!("  Process: {}", describe_process(status)),
+                Some(None) => println!("  Process: not started"),
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute engines start command
+pub async fn execute_start(
+    args: EngineStartArgs,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_db_path);
+
+    validate_engine_id(&args.id)?;
+    let processes = SqliteEngineProcessConfigRepository::new(&db_path).await?;
+    let existing = processes.get(&args.id).await?;
+    let managed = existing.is_some();
+    let config = build_process_config(&args, existing)?;
+
+    // Started engines are reachable through the registry like `flm engines add` ones
+    let registry = SqliteEngineRegistryRepository::new(&db_path).await?;
+    let base_url = config.base_url();
+    match registry.get(&config.engine_id).await? {
+        None => {
+            registry
+                .save(&EngineRegistration {
+                    id: config.engine_id.clone(),
+                    kind: config.kind.clone(),
+                    base_url,
+                    auth_header_name: None,
+                    timeout_secs: None,
+                    tls: EngineTlsConfig::default(),
+                    bearer_token_in_keyring: false,
+                    model_capabilities: BTreeMap::new(),
+                    created_at: config.updated_at.clone(),
+                    updated_at: config.updated_at.clone(),
+                })
+                .await?;
+        }
+        Some(registration) if registration.kind != config.kind => {
+            return Err(Box::new(CliUserError::new(format!(
+                "Engine '{}' is registered as {}",
+                registration.id,
+                registration.kind.as_str()
+            ))));
+        }
+        Some(mut registration) if registration.base_url != base_url => {
+            if !managed {
+                return Err(Box::new(CliUserError::new(format!(
+                    "Engine '{}' is registered with base URL {} (use another ID or remove it first)",
+                    registration.id, registration.base_url
+                ))));
+            }
+            // Host or port of a managed engine changed
+            registration.base_url = base_url;
+            registration.updated_at = config.updated_at.clone();
+            registry.save(&registration).await?;
+        }
+        Some(_) => {}
+    }
+    processes.save(&config).await?;
+
+    let status = run_controller({
+        let config = config.clone();
+        move |controller| controller.start(&config)
+    })
+    .await?;
+    render_process_status("Started", &status, Some(&config), &format)
+}
+
+/// Execute engines stop command
+pub async fn execute_stop(id: String, format: String) -> Result<(), Box<dyn std::error::Error>> {
+    let status = run_controller({
+        let id = id.clone();
+        move |controller| controller.stop(&id)
+    })
+    .await?;
+    render_process_status("Stopped", &status, None, &format)
+}
+
+/// Execute engines restart command
+pub async fn execute_restart(
+    id: String,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_db_path);
+
+    let processes = SqliteEngineProcessConfigRepository::new(&db_path).await?;
+    let Some(config) = processes.get(&id).await? else {
+        return Err(Box::new(CliUserError::new(format!(
+            "Engine '{id}' has not been started with flm engines start"
+        ))));
+    };
+
+    let status = run_controller({
+        let config = config.clone();
+        move |controller| {
+            if controller
+                .status(&config.engine_id)
+                .is_some_and(|status| status.state.is_active())
+            {
+                controller.stop(&config.engine_id)?;
+            }
+            controller.start(&config)
+        }
+    })
+    .await?;
+    render_process_status("Restarted", &status, Some(&config), &format)
+}
+
+/// Execute engines logs command
+pub async fn execute_logs(
+    id: String,
+    lines: usize,
+    follow: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let log_path = DefaultEngineProcessController::new()
+        .runtime_files(&id)
+        .log_path();
+    let Ok(content) = std::fs::read(&log_path) else {
+        return Err(Box::new(CliUserError::new(format!(
+            "No log found for engine '{id}' (start it with flm engines start)"
+        ))));
+    };
+    let content = String::from_utf8_lossy(&content);
+    let tail: Vec<&str> = content.lines().rev().take(lines).collect();
+    for line in tail.iter().rev() {
+        println!("{line}");
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut position = content.len() as u64;
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let Ok(len) = std::fs::metadata(&log_path).map(|m| m.len()) else {
+            continue;
+        };
+        if len < position {
+            // Rotated on restart
+            position = 0;
+        }
+        if len > position {
+            let mut file = std::fs::File::open(&log_path)?;
+            file.seek(SeekFrom::Start(position))?;
+            let mut buffer = Vec::new();
+            file.read_to_end(&mut buffer)?;
+            position += buffer.len() as u64;
+            print!("{}", String::from_utf8_lossy(&buffer));
+        }
+    }
+}
+
+/// Execute the hidden engines supervise command (spawned by `start`)
+pub async fn execute_supervise(dir: String) -> Result<(), Box<dyn std::error::Error>> {
+    let files = EngineRuntimeFiles::from_dir(dir);
+    tokio::task::spawn_blocking(move || run_supervisor(&files))
+        .await?
+        .map_err(CliUserError::new)?;
+    Ok(())
+}
+
+/// Run a blocking lifecycle operation of the default process controller
+async fn run_controller<F>(operation: F) -> Result<EngineProcessStatus, Box<dyn std::error::Error>>
+where
+    F: FnOnce(&DefaultEngineProcessController) -> Result<EngineProcessStatus, EngineError>
+        + Send
+        + 'static,
+{
+    tokio::task::spawn_blocking(move || operation(&DefaultEngineProcessController::new()))
+        .await?
+        .map_err(|e| match e {
+            EngineError::ProcessError { engine_id, reason } => {
+                Box::new(CliUserError::new(format!("Engine '{engine_id}': {reason}")))
+                    as Box<dyn std::error::Error>
+            }
+            other => Box::new(other),
+        })
+}
+
+/// Merge `flm engines start` flags into the saved process configuration
+fn build_process_config(
+    args: &EngineStartArgs,
+    existing: Option<EngineProcessConfig>,
+) -> Result<EngineProcessConfig, CliUserError> {
+    let kind = match (&args.kind, &existing) {
+        (Some(kind), _) => kind.parse::<EngineKind>().map_err(CliUserError::new)?,
+        (None, Some(existing)) => existing.kind.clone(),
+        (None, None) => {
+            return Err(CliUserError::new(
+                "--kind is required the first time an engine is started",
+            ))
+        }
+    };
+    if let Some(existing) = &existing {
+        if existing.kind != kind {
+            return Err(CliUserError::new(format!(
+                "Engine '{}' was started as {}; remove it to change the kind",
+                existing.engine_id,
+                existing.kind.as_str()
+            )));
+        }
+    }
+    let default_port = EngineProcessConfig::default_port(&kind).ok_or_else(|| {
+        CliUserError::new(format!(
+            "Engine kind '{}' cannot be started by flm (supported: ollama, llamacpp, vllm)",
+            kind.as_str()
+        ))
+    })?;
+
+    let now = Utc::now().to_rfc3339();
+    let mut config = existing.unwrap_or_else(|| EngineProcessConfig {
+        engine_id: args.id.clone(),
+        kind: kind.clone(),
+        binary_path: None,
+        model: None,
+        host: EngineProcessConfig::DEFAULT_HOST.to_string(),
+        port: default_port,
+        args: Vec::new(),
+        env: BTreeMap::new(),
+        restart_on_crash: true,
+        max_restarts: EngineProcessConfig::DEFAULT_MAX_RESTARTS,
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    });
+
+    if let Some(model) = &args.model {
+        config.model = Some(if kind == EngineKind::LlamaCpp {
+            // Bare names refer to files downloaded with `flm models pull`
+            let path = Path::new(model);
+            let path = if path.exists() {
+                path.to_path_buf()
+            } else {
+                crate::utils::get_models_dir().join(model)
+            };
+            // The supervisor may restart the engine from another working directory
+            std::fs::canonicalize(path)
+                .map_err(|_| CliUserError::new(format!("GGUF model not found: {model}")))?
+                .to_string_lossy()
+                .to_string()
+        } else {
+            model.clone()
+        });
+    }
+    if let Some(binary) = &args.binary {
+        config.binary_path = Some(binary.clone());
+    }
+    if let Some(host) = &args.host {
+        let host = host.trim();
+        if host.is_empty() {
+            return Err(CliUserError::new("--host must not be empty"));
+        }
+        config.host = host.to_string();
+    }
+    if let Some(port) = args.port {
+        if port == 0 {
+            return Err(CliUserError::new("--port must be greater than 0"));
+        }
+        config.port = port;
+    }
+    if !args.args.is_empty() {
+        config.args = args.args.clone();
+    }
+    if !args.env.is_empty() {
+        config.env = parse_env_vars(&args.env)?;
+    }
+    config.restart_on_crash = !args.no_restart;
+    if let Some(max_restarts) = args.max_restarts {
+        config.max_restarts = max_restarts;
+    }
+    config.updated_at = now;
+
+    config.command_args().map_err(CliUserError::new)?;
+    if kind == EngineKind::LlamaCpp {
+        let model = config.model.as_deref().unwrap_or_default();
+        if !Path::new(model).is_file() {
+            return Err(CliUserError::new(format!("GGUF model not found: {model}")));
+        }
+    }
+    Ok(config)
+}
+
+/// Parse repeated `--env KEY=VALUE` values
+fn parse_env_vars(values: &[String]) -> Result<BTreeMap<String, String>, CliUserError> {
+    let mut env = BTreeMap::new();
+    for value in values {
+        let (key, val) = value
+            .split_once('=')
+            .filter(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+            .ok_or_else(|| {
+                CliUserError::new(format!("Invalid --env '{value}' (expected \"KEY=VALUE\")"))
+            })?;
+        env.insert(key.to_string(), val.to_string());
+    }
+    Ok(env)
+}
+
+fn describe_process(status: &EngineProcessStatus) -> String {
+    let state = serde_json::to_value(&status.state)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut details = Vec::new();
+    if let Some(pid) = status.pid {
+        details.push(format!("pid {pid}"));
+    }
+    if status.restarts > 0 {
+        details.push(format!("{} restart(s)", status.restarts));
+    }
+    if let Some(reason) = &status.reason {
+        details.push(reason.clone());
+    }
+    if details.is_empty() {
+        state
+    } else {
+        format!("{state} ({})", details.join(", "))
+    }
+}
+
+fn render_process_status(
+    action: &str,
+    status: &EngineProcessStatus,
+    config: Option<&EngineProcessConfig>,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engine_id": status.engine_id,
+                "base_url": config.map(EngineProcessConfig::base_url),
+                "process": status,
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match config {
+            Some(config) => println!(
+                "{action} engine {} at {}",
+                status.engine_id,
+                config.base_url()
+            ),
+            None => println!("{action} engine {}", status.engine_id),
+        }
+        println!("  Process: {}", describe_process(status));
+        println!("  Log: {}", status.log_path);
+    }
+
+    Ok(())
+}
+
+/// JSON view of a registration; the auth header value stays in the keyring and is shown masked
+fn registration_json(registration: &EngineRegistration) -> serde_json::Value {
+    json!({
+        "id": registration.id,
+        "kind": registration.kind,
+        "base_url": registration.base_url,
+        "auth_header": registration.auth_header_name.as_ref().map(|name| json!({
+            "name": name,
+            "value": "****",
+        })),
+        "bearer_token_in_keyring": registration.bearer_token_in_keyring,
+        "model_capabilities": registration.model_capabilities,
+        "timeout_secs": registration.timeout_secs,
+        "tls": registration.tls,
+        "created_at": registration.created_at,
+        "updated_at": registration.updated_at,
+    })
+}
+
+/// Same rules as engine IDs in `flm://{engine_id}/{model}`; `group` and `profile` are reserved
+fn validate_engine_id(id: &str) -> Result<(), CliUserError> {
+    if matches!(id, "group" | "profile") {
+        return Err(CliUserError::new(format!("Engine ID '{id}' is reserved")));
+    }
+    let valid = !id.is_empty()
+        && id.len() <= 100
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CliUserError::new(format!(
+            "Invalid engine ID '{id}' (use 1-100 of A-Z, a-z, 0-9, '-', '_')"
+        )))
+    }
+}
+
+fn validate_base_url(base_url: &str) -> Result<String, CliUserError> {
+    let url = reqwest::Url::parse(base_url)
+        .map_err(|e| CliUserError::new(format!("Invalid base URL '{base_url}': {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(CliUserError::new(format!(
+            "Invalid base URL '{base_url}' (expected http:// or https://)"
+        )));
+    }
+    Ok(base_url.trim_end_matches('/').to_string())
+}
+
+/// Split `--auth-header "Name: value"` into its name and value
+fn parse_auth_header(header: &str) -> Result<(String, String), CliUserError> {
+    let (name, value) = header
+        .split_once(':')
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+        .ok_or_else(|| {
+            CliUserError::new(
+                "--auth-header must be \"Name: value\" (e.g. \"Authorization: Bearer ...\")",
+            )
+        })?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Store the secrets of a new registration in the keyring
+fn store_engine_secrets(
+    engine_id: &str,
+    credentials: &EngineCredentials,
+) -> Result<(), keyring::Error> {
+    if let Some(token) = &credentials.bearer_token {
+        store_engine_token(engine_id, token)?;
+    }
+    if let Some(value) = &credentials.auth_header_value {
+        store_engine_auth_header(engine_id, value)?;
+    }
+    Ok(())
+}
+
+fn resolve_bearer_token(args: &EngineAddArgs) -> Result<Option<String>, CliUserError> {
+    bearer_token_from_args(args.bearer_token.as_deref(), args.bearer_token_stdin)
+}
+
+/// Token from `--bearer-token` or `--bearer-token-stdin` (at most one)
+pub(crate) fn bearer_token_from_args(
+    bearer_token: Option<&str>,
+    from_stdin: bool,
+) -> Result<Option<String>, CliUserError> {
+    match (bearer_token, from_stdin) {
+        (Some(token), false) => {
+            let token = token.trim().to_string();
+            if token.is_empty() {
+                Err(CliUserError::new("--bearer-token must not be empty"))
+            } else {
+                Ok(Some(token))
+            }
+        }
+        (None, true) => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| CliUserError::new(format!("Failed to read token from stdin: {e}")))?;
+            let token = buffer.trim().to_string();
+            if token.is_empty() {
+                Err(CliUserError::new("Token read from stdin is empty"))
+            } else {
+                Ok(Some(token))
+            }
+        }
+        (Some(_), true) => Err(CliUserError::new(
+            "Provide token via --bearer-token or --bearer-token-stdin (not both)",
+        )),
+        (None, false) => Ok(None),
+    }
+}
+
+/// Parse repeated `--model-capability "<model>=<caps>"` values
+fn parse_model_capabilities(
+    values: &[String],
+) -> Result<BTreeMap<String, ModelCapabilities>, CliUserError> {
+    let mut overrides = BTreeMap::new();
+    for value in values {
+        let (model, caps) = value
+            .split_once('=')
+            .map(|(model, caps)| (model.trim(), caps.trim()))
+            .filter(|(model, _)| !model.is_empty())
+            .ok_or_else(|| {
+                CliUserError::new(format!(
+                    "Invalid --model-capability '{value}' (expected \"<model>=<caps>\", e.g. \"llama-3.1-70b=tools,reasoning\")"
+                ))
+            })?;
+
+        let mut capabilities = ModelCapabilities::default();
+        for cap in caps.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            match cap {
+                "reasoning" => capabilities.reasoning = true,
+                "tools" => capabilities.tools = true,
+                "vision" => capabilities.vision = true,
+                "audio-inputs" => capabilities.audio_inputs = true,
+                "audio-outputs" => capabilities.audio_outputs = true,
+                "none" => {}
+                other => {
+                    return Err(CliUserError::new(format!(
+                        "Unknown capability '{other}' (expected reasoning, tools, vision, audio-inputs, audio-outputs or none)"
+                    )))
+                }
+            }
+        }
+        if overrides.insert(model.to_string(), capabilities).is_some() {
+            return Err(CliUserError::new(format!(
+                "Duplicate --model-capability for model '{model}'"
+            )));
+        }
+    }
+    Ok(overrides)
+}
+
+pub(crate) fn capability_names(caps: &ModelCapabilities) -> Vec<&'static str> {
+    let names: Vec<&'static str> = [
+        (caps.reasoning, "reasoning"),
+        (caps.tools, "tools"),
+        (caps.vision, "vision"),
+        (caps.audio_inputs, "audio-inputs"),
+        (caps.audio_outputs, "audio-outputs"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, name)| enabled.then_some(name))
+    .collect();
+    if names.is_empty() {
+        vec!["none"]
+    } else {
+        names
     }
 }
 
@@ -6,14 +6,17 @@ pub mod api_keys;
 pub mod api_prompts;
 pub mod chat;
 pub mod check;
+pub mod concurrency_limits;
 pub mod config;
 pub mod engines;
 pub mod error;
 pub mod migrate;
+pub mod model_groups;
 pub mod model_profiles;
 pub mod models;
 pub mod proxy;
 pub mod secrets;
 pub mod security;
+pub mod usage;
 
 pub use error::CliUserError;
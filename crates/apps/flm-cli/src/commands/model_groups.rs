@@ -0,0 +1,170 @@
+//! `flm model-groups` command implementation
+
+use crate::adapters::SqliteModelGroupRepository;
+use crate::cli::model_groups::{ModelGroupSaveArgs, ModelGroupsSubcommand};
+use crate::commands::CliUserError;
+use crate::utils::get_config_db_path;
+use flm_core::domain::models::{LoadBalanceStrategy, ModelGroup};
+use flm_core::error::RepoError;
+use flm_core::ports::ModelGroupRepository;
+use serde_json::json;
+use std::path::PathBuf;
+
+pub async fn execute(
+    subcommand: ModelGroupsSubcommand,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_config_db_path);
+
+    let store = SqliteModelGroupRepository::new(&db_path).await?;
+
+    match subcommand {
+        ModelGroupsSubcommand::List => {
+            let groups = store.list_groups().await?;
+            render_list(&groups, &format)?;
+        }
+        ModelGroupsSubcommand::Save(args) => {
+            let group = save_group(&store, args).await?;
+            render_single(&group, &format)?;
+        }
+        ModelGroupsSubcommand::Delete { name } => {
+            let deleted = store.delete_group(&name).await?;
+            if !deleted {
+                return Err(Box::new(CliUserError::new(format!(
+                    "Model group '{name}' not found"
+                ))));
+            }
+            if format == "json" {
+                let output = json!({
+                    "version": "1.0",
+                    "data": {
+                        "deleted": true,
+                        "name": name
+                    }
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("Deleted model group {name}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn save_group(
+    store: &SqliteModelGroupRepository,
+    args: ModelGroupSaveArgs,
+) -> Result<ModelGroup, Box<dyn std::error::Error>> {
+    validate_group_name(&args.name)?;
+
+    let strategy: LoadBalanceStrategy = args
+        .strategy
+        .parse()
+        .map_err(|reason: String| CliUserError::new(reason))?;
+
+    let mut members: Vec<String> = Vec::with_capacity(args.members.len());
+    for member in &args.members {
+        validate_member(member)?;
+        if members.contains(member) {
+            return Err(Box::new(CliUserError::new(format!(
+                "Duplicate member '{member}'"
+            ))));
+        }
+        members.push(member.clone());
+    }
+
+    let group = store
+        .save_group(&args.name, strategy, &members)
+        .await
+        .map_err(map_repo_err)?;
+
+    Ok(group)
+}
+
+/// Group names end up in `flm://group/{name}`, so keep them path-safe
+fn validate_group_name(name: &str) -> Result<(), CliUserError> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CliUserError::new(format!(
+            "Invalid group name '{name}' (use 1-128 of A-Z, a-z, 0-9, '-', '_', '.', ':')"
+        )))
+    }
+}
+
+fn validate_member(member: &str) -> Result<(), CliUserError> {
+    let valid = member
+        .strip_prefix("flm://")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(engine, model)| {
+            !engine.is_empty() && engine != "group" && engine != "profile" && !model.is_empty()
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(CliUserError::new(format!(
+            "Invalid member '{member}' (expected flm://{{engine_id}}/{{model_name}})"
+        )))
+    }
+}
+
+fn render_list(groups: &[ModelGroup], format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "groups": groups
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if groups.is_empty() {
+        println!("No model groups found.");
+        return Ok(());
+    }
+
+    for group in groups {
+        println!("Group: {}", group.name);
+        println!("  Strategy: {}", group.strategy.as_str());
+        println!("  Members: {}", group.members.join(", "));
+        println!("  Updated: {}", group.updated_at);
+    }
+
+    Ok(())
+}
+
+fn render_single(group: &ModelGroup, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "group": group
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Saved model group {}", group.name);
+        println!("  Strategy: {}", group.strategy.as_str());
+        println!("  Members: {}", group.members.join(", "));
+    }
+
+    Ok(())
+}
+
+fn map_repo_err(err: RepoError) -> Box<dyn std::error::Error> {
+    match err {
+        RepoError::IoError { reason } => Box::new(CliUserError::new(reason)),
+        other => Box::new(other),
+    }
+}
@@ -2,20 +2,28 @@
 
 use crate::adapters::{DefaultEngineProcessController, ReqwestHttpClient, SqliteEngineRepository};
 use crate::cli::models::ModelsSubcommand;
+use crate::commands::CliUserError;
 use async_trait::async_trait;
+use flm_core::domain::engine::{ModelDetails, ModelPullRequest};
 use flm_core::domain::models::EngineKind;
-use flm_core::ports::{EngineProcessController, EngineRepository, LlmEngine};
+use flm_core::ports::{EngineProcessController, EngineRepository, LlmEngine, ModelManager};
 use flm_core::services::EngineService;
-use flm_engine_llamacpp::LlamaCppEngine;
+use flm_engine_llamacpp::{GgufModelStore, LlamaCppEngine, GGUF_STORE_ID};
 use flm_engine_lmstudio::LmStudioEngine;
 use flm_engine_ollama::OllamaEngine;
+use flm_engine_sdwebui::SdWebUiEngine;
 use flm_engine_vllm::VllmEngine;
+use futures::StreamExt;
 use serde_json::json;
 use std::collections::HashMap;
 use std::env;
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Engine used by `pull`/`delete`/`show`/`copy` when `--engine` is omitted
+const DEFAULT_MODEL_ENGINE: &str = "ollama-default";
+
 /// Wrapper to convert Arc<SqliteEngineRepository> to Box<dyn EngineRepository + Send + Sync>
 struct ArcEngineRepositoryWrapper(Arc<SqliteEngineRepository>);
 
@@ -43,9 +51,18 @@ async fn register_detected_engines(
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Detect engines first
     let states = service.detect_engines().await?;
+    let registered: Vec<String> = engine_repo
+        .list_registered()
+        .await
+        .iter()
+        .map(|engine| engine.id())
+        .collect();
 
-    // Register engines based on detected states
+    // Register engines based on detected states (registry entries keep their own settings)
     for state in states {
+        if registered.contains(&state.id) {
+            continue;
+        }
         match state.kind {
             EngineKind::Ollama => {
                 let base_url = runtime_urls
@@ -84,6 +101,17 @@ async fn register_detected_engines(
                 let engine = Arc::new(LlamaCppEngine::new(state.id.clone(), base_url)?);
                 engine_repo.register(engine).await;
             }
+            EngineKind::StableDiffusion => {
+                let base_url = runtime_urls
+                    .get(&state.id)
+                    .cloned()
+                    .unwrap_or_else(|| "http://localhost:7860".to_string());
+
+                let engine = Arc::new(SdWebUiEngine::new(state.id.clone(), base_url)?);
+                engine_repo.register(engine).await;
+            }
+            // Only ever registered explicitly (`flm engines add`), never auto-detected
+            EngineKind::OpenAiCompatible => {}
         }
     }
 
@@ -97,12 +125,10 @@ async fn register_detected_engines(
     Ok(())
 }
 
-/// Execute models list command
-pub async fn execute_list(
-    engine_id: String,
+/// Create the engine service with registered and detected engines
+async fn load_engines(
     db_path: Option<String>,
-    format: String,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(EngineService, ArcEngineRepositoryWrapper), Box<dyn std::error::Error>> {
     let db_path = db_path
         .map(PathBuf::from)
         .unwrap_or_else(default_config_db_path);
@@ -120,13 +146,29 @@ pub async fn execute_list(
     let engine_repo: Box<dyn flm_core::ports::EngineRepository + Send + Sync> =
         Box::new(ArcEngineRepositoryWrapper(engine_repo_arc.clone()));
 
+    // Registered engines come first; detection only fills in the rest
+    let registry =
+        crate::commands::engines::load_registered_engines(&db_path, engine_repo.as_ref()).await?;
+
     // Create service
-    let service = EngineService::new(process_controller, http_client, engine_repo);
+    let service = EngineService::new(process_controller, http_client, engine_repo)
+        .with_engine_registry(registry);
 
     // Register detected engines
     let engine_repo_wrapper = ArcEngineRepositoryWrapper(engine_repo_arc);
     register_detected_engines(&service, &engine_repo_wrapper, &runtime_urls).await?;
 
+    Ok((service, engine_repo_wrapper))
+}
+
+/// Execute models list command
+pub async fn execute_list(
+    engine_id: String,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (service, _) = load_engines(db_path).await?;
+
     // List models
     let models = service.list_models(engine_id.clone()).await?;
 
@@ -153,6 +195,12 @@ pub async fn execute_list(
                 }
                 println!("    Streaming: {}", model.supports_streaming);
                 println!("    Embeddings: {}", model.supports_embeddings);
+                if let Some(capabilities) = &model.capabilities {
+                    println!(
+                        "    Capabilities: {}",
+                        crate::commands::engines::capability_names(capabilities).join(", ")
+                    );
+                }
             }
         }
     }
@@ -167,6 +215,321 @@ pub async fn execute(
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match subcommand {
-        ModelsSubcommand::List { engine } => execute_list(engine, db_path, format).await,
+        ModelsSubcommand::List { gguf: true, .. } => execute_list_gguf(format).await,
+        ModelsSubcommand::List { engine, .. } => {
+            execute_list(engine.unwrap_or_default(), db_path, format).await
+        }
+        ModelsSubcommand::Pull {
+            model,
+            engine,
+            sha256,
+            file_name,
+        } => {
+            let req = ModelPullRequest {
+                model,
+                sha256,
+                file_name,
+            };
+            execute_pull(req, engine, db_path, format).await
+        }
+        ModelsSubcommand::Delete { model, engine } => {
+            execute_delete(model, engine, db_path, format).await
+        }
+        ModelsSubcommand::Show { model, engine } => {
+            execute_show(model, engine, db_path, format).await
+        }
+        ModelsSubcommand::Copy {
+            source,
+            destination,
+            engine,
+        } => execute_copy(source, destination, engine, db_path, format).await,
+    }
+}
+
+/// Where a model lives: the managed GGUF directory or an engine with a model API
+enum ModelStore {
+    Gguf(GgufModelStore),
+    Engine(Arc<dyn LlmEngine>),
+}
+
+impl ModelStore {
+    /// Pick the store for a model name: URLs and `*.gguf` names are GGUF files
+    async fn resolve(
+        model: &str,
+        engine: Option<String>,
+        db_path: Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if is_gguf_target(model) {
+            if engine.is_some() {
+                return Err(Box::new(CliUserError::new(
+                    "--engine cannot be used with GGUF files (they are stored in the models directory)",
+                )));
+            }
+            let store =
+                GgufModelStore::new(GGUF_STORE_ID.to_string(), crate::utils::get_models_dir())?;
+            return Ok(Self::Gguf(store));
+        }
+
+        let engine_id = engine.unwrap_or_else(|| DEFAULT_MODEL_ENGINE.to_string());
+        let (_, engine_repo) = load_engines(db_path).await?;
+        let engine = engine_repo
+            .list_registered()
+            .await
+            .into_iter()
+            .find(|engine| engine.id() == engine_id)
+            .ok_or_else(|| CliUserError::new(format!("Engine not found: {engine_id}")))?;
+        if engine.model_manager().is_none() {
+            return Err(Box::new(CliUserError::new(format!(
+                "Engine '{engine_id}' does not support model management"
+            ))));
+        }
+        Ok(Self::Engine(engine))
+    }
+
+    fn manager(&self) -> &dyn ModelManager {
+        match self {
+            Self::Gguf(store) => store,
+            Self::Engine(engine) => engine
+                .model_manager()
+                .expect("checked in ModelStore::resolve"),
+        }
+    }
+}
+
+fn is_gguf_target(model: &str) -> bool {
+    model.starts_with("http://") || model.starts_with("https://") || model.ends_with(".gguf")
+}
+
+/// Execute models list --gguf command
+async fn execute_list_gguf(format: String) -> Result<(), Box<dyn std::error::Error>> {
+    let models_dir = crate::utils::get_models_dir();
+    let store = GgufModelStore::new(GGUF_STORE_ID.to_string(), &models_dir)?;
+    let models = store.list_installed().await?;
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engine_id": GGUF_STORE_ID,
+                "models_dir": models_dir.display().to_string(),
+                "models": models
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if models.is_empty() {
+        println!("No GGUF models in {}", models_dir.display());
+    } else {
+        println!("GGUF models in {}:", models_dir.display());
+        for model in models {
+            println!(
+                "  - {} ({})",
+                model.name,
+                format_size(model.size_bytes.unwrap_or_default())
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute models pull command
+async fn execute_pull(
+    req: ModelPullRequest,
+    engine: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = ModelStore::resolve(&req.model, engine, db_path).await?;
+    let manager = store.manager();
+    let model = req.model.clone();
+
+    let mut stream = manager.pull(req).await?;
+    let mut digest = None;
+    let mut last_status = String::new();
+    while let Some(progress) = stream.next().await {
+        let progress = progress?;
+        if progress.is_success() {
+            digest = progress.digest;
+            break;
+        }
+        if format == "json" {
+            continue;
+        }
+        // Progress goes to stderr so stdout stays clean for scripts
+        let mut stderr = std::io::stderr();
+        match (progress.completed, progress.total) {
+            (Some(completed), Some(total)) if total > 0 => {
+                let percent = completed.saturating_mul(100) / total;
+                write!(
+                    stderr,
+                    "\r{} {percent}% ({}/{})",
+                    progress.status,
+                    format_size(completed),
+                    format_size(total)
+                )?;
+            }
+            _ if progress.status != last_status => {
+                if !last_status.is_empty() {
+                    writeln!(stderr)?;
+                }
+                write!(stderr, "{}", progress.status)?;
+            }
+            _ => {}
+        }
+        stderr.flush()?;
+        last_status = progress.status;
+    }
+    if format != "json" && !last_status.is_empty() {
+        eprintln!();
+    }
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engine_id": manager.engine_id(),
+                "model": model,
+                "status": "success",
+                "digest": digest
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match digest {
+            Some(digest) => println!("Pulled {model} ({digest})"),
+            None => println!("Pulled {model}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute models delete command
+async fn execute_delete(
+    model: String,
+    engine: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = ModelStore::resolve(&model, engine, db_path).await?;
+    let manager = store.manager();
+    manager.delete(&model).await?;
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engine_id": manager.engine_id(),
+                "model": model,
+                "deleted": true
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Deleted {model} from {}", manager.engine_id());
+    }
+
+    Ok(())
+}
+
+/// Execute models show command
+async fn execute_show(
+    model: String,
+    engine: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = ModelStore::resolve(&model, engine, db_path).await?;
+    let details = store.manager().show(&model).await?;
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": details
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_model_details(&details);
+    }
+
+    Ok(())
+}
+
+/// Execute models copy command
+async fn execute_copy(
+    source: String,
+    destination: String,
+    engine: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if is_gguf_target(&source) != is_gguf_target(&destination) {
+        return Err(Box::new(CliUserError::new(
+            "GGUF files can only be copied to another *.gguf name",
+        )));
+    }
+    let store = ModelStore::resolve(&source, engine, db_path).await?;
+    let manager = store.manager();
+    manager.copy(&source, &destination).await?;
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "engine_id": manager.engine_id(),
+                "source": source,
+                "destination": destination
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Copied {source} to {destination}");
+    }
+
+    Ok(())
+}
+
+fn print_model_details(details: &ModelDetails) {
+    println!("Model: {}", details.name);
+    println!("  Engine: {}", details.engine_id);
+    let fields = [
+        ("Family", details.family.clone()),
+        ("Parameters", details.parameter_size.clone()),
+        ("Quantization", details.quantization.clone()),
+        ("Format", details.format.clone()),
+        ("Size", details.size_bytes.map(format_size)),
+        (
+            "Context length",
+            details.context_length.map(|n| n.to_string()),
+        ),
+        (
+            "Capabilities",
+            details
+                .capabilities
+                .as_ref()
+                .map(|caps| crate::commands::engines::capability_names(caps).join(", ")),
+        ),
+        ("Digest", details.digest.clone()),
+        ("Path", details.path.clone()),
+        ("Modified", details.modified_at.clone()),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("  {label}: {value}");
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
     }
+    format!("{value:.1} {}", UNITS[unit])
 }
@@ -2,7 +2,9 @@
 
 mod daemon;
 
-use crate::adapters::{SqliteProxyRepository, SqliteSecurityRepository};
+use crate::adapters::{
+    SqliteEngineRegistryRepository, SqliteProxyRepository, SqliteSecurityRepository,
+};
 use crate::cli::proxy::ProxySubcommand;
 use crate::utils::secrets::load_dns_token;
 use crate::utils::{get_config_db_path, get_security_db_path};
@@ -51,6 +53,7 @@ pub async fn execute(
             acme_dns_profile,
             acme_dns_lego_path,
             acme_dns_propagation_wait,
+            ollama_api,
             no_daemon,
         } => {
             let options = StartCommandOptions {
@@ -66,6 +69,7 @@ pub async fn execute(
                 acme_dns_profile,
                 acme_dns_lego_path,
                 acme_dns_propagation_wait,
+                ollama_api,
                 db_path_config,
                 db_path_security,
                 no_daemon,
@@ -111,6 +115,7 @@ struct StartCommandOptions {
     acme_dns_profile: Option<String>,
     acme_dns_lego_path: Option<String>,
     acme_dns_propagation_wait: Option<u64>,
+    ollama_api: bool,
     db_path_config: Option<String>,
     db_path_security: Option<String>,
     no_daemon: bool,
@@ -139,6 +144,7 @@ async fn execute_start(options: StartCommandOptions) -> Result<(), Box<dyn std::
         acme_dns_profile,
         acme_dns_lego_path,
         acme_dns_propagation_wait,
+        ollama_api,
         db_path_config,
         db_path_security,
         no_daemon,
@@ -276,6 +282,18 @@ async fn execute_start(options: StartCommandOptions) -> Result<(), Box<dyn std::
             None
         };
 
+    // Secrets of registered engines stay in the keyring until the proxy starts
+    let engine_registry = SqliteEngineRegistryRepository::new(&config_db_path).await?;
+    let resolved_engine_credentials =
+        crate::commands::engines::resolve_engine_credentials(&engine_registry).await?;
+
+    // Periodic backups are sealed with the keyring key, resolved here as well
+    let (backup_schedule, resolved_backup_key) =
+        crate::commands::security::resolve_backup_schedule(&config_db_path).await?;
+    let resolved_audit_hmac_key = crate::commands::security::resolve_audit_hmac_key();
+    let resolved_audit_sink_tokens =
+        crate::commands::security::resolve_audit_sink_tokens(&config_db_path).await?;
+
     // Build proxy config
     let config = ProxyConfig {
         port,
@@ -287,7 +305,13 @@ async fn execute_start(options: StartCommandOptions) -> Result<(), Box<dyn std::
         acme_dns_profile_id: acme_dns_profile,
         acme_dns_lego_path,
         acme_dns_propagation_secs: acme_dns_propagation_wait,
+        ollama_api,
         resolved_dns_credential,
+        resolved_engine_credentials,
+        backup_schedule,
+        resolved_backup_key,
+        resolved_audit_hmac_key,
+        resolved_audit_sink_tokens,
         egress: ProxyEgressConfig {
             mode: egress_mode_parsed.clone(),
             socks5_endpoint: match &egress_mode_parsed {
@@ -1,22 +1,43 @@
 //! Security command implementation
 
-use crate::adapters::SqliteSecurityRepository;
+use crate::adapters::{
+    SqliteAuditSinkRepository, SqliteConfigRepository, SqliteSecurityRepository,
+};
 use crate::cli::security::{
-    BackupSubcommand, CertificatesSubcommand, IpBlocklistSubcommand, PolicySubcommand,
-    SecuritySubcommand,
+    AuditLogsSubcommand, AuditSinkAddArgs, AuditSinksSubcommand, BackupSubcommand,
+    CertificatesSubcommand, IpBlocklistSubcommand, PolicySubcommand, SecuritySubcommand,
+};
+use crate::commands::engines::bearer_token_from_args;
+use crate::commands::usage::csv_field;
+use crate::commands::CliUserError;
+use crate::utils::secrets;
+use crate::utils::{get_config_db_path, get_security_db_path};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use flm_core::domain::audit::{
+    AuditChainVerifier, AuditSinkConfig, ChainedAuditLog, DEFAULT_JSONL_MAX_BYTES,
+    DEFAULT_JSONL_MAX_FILES, DEFAULT_SYSLOG_FACILITY, DEFAULT_WEBHOOK_BATCH_SIZE,
+    DEFAULT_WEBHOOK_FLUSH_INTERVAL_SECS, DEFAULT_WEBHOOK_MAX_RETRIES,
 };
-use crate::utils::get_security_db_path;
+use flm_core::domain::policy::{PolicyDocument, RuleVerdict};
+use flm_core::domain::proxy::BackupScheduleConfig;
 use flm_core::domain::security::SecurityPolicy;
-use flm_core::services::SecurityService;
+use flm_core::error::RepoError;
+use flm_core::ports::AuditSinkRepository;
+use flm_core::services::{ConfigService, SecurityService};
+use flm_proxy::backup::{self, BackupError, BackupHeader, BackupKey, BackupKind, KeySource};
+use flm_proxy::token_quota::TokenLimits;
 use serde_json::json;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Execute security command
 pub async fn execute(
     subcommand: SecuritySubcommand,
     db_path: Option<String>,
+    config_db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match subcommand {
@@ -24,17 +45,22 @@ pub async fn execute(
             execute_policy(subcommand, db_path, format).await
         }
         SecuritySubcommand::Backup { subcommand } => {
-            execute_backup(subcommand, db_path, format).await
+            execute_backup(subcommand, db_path, config_db_path, format).await
         }
         SecuritySubcommand::IpBlocklist { subcommand } => {
             execute_ip_blocklist(subcommand, db_path, format).await
         }
+        SecuritySubcommand::AuditLogs {
+            subcommand: Some(subcommand),
+            ..
+        } => execute_audit_logs_subcommand(subcommand, db_path, config_db_path, format).await,
         SecuritySubcommand::AuditLogs {
             event_type,
             severity,
             ip,
             limit,
             offset,
+            subcommand: None,
         } => execute_audit_logs(event_type, severity, ip, limit, offset, db_path, format).await,
         SecuritySubcommand::Intrusion {
             ip,
@@ -55,6 +81,9 @@ pub async fn execute(
         SecuritySubcommand::RateLimits { api_key_id } => {
             execute_rate_limits(api_key_id, db_path, format).await
         }
+        SecuritySubcommand::Quotas { api_key_id } => {
+            execute_quotas(api_key_id, db_path, format).await
+        }
     }
 }
 
@@ -69,9 +98,61 @@ async fn execute_policy(
         PolicySubcommand::Set { json, file } => {
             execute_policy_set(json, file, db_path, format).await
         }
+        PolicySubcommand::Diff { json, file } => {
+            execute_policy_diff(json, file, db_path, format).await
+        }
+        PolicySubcommand::Test {
+            ip,
+            key,
+            json,
+            file,
+        } => execute_policy_test(ip, key, json, file, db_path, format).await,
     }
 }
 
+/// Read a policy from `--json` or `--file` (`None` when neither is given)
+fn read_policy_input(
+    json: Option<String>,
+    file: Option<String>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match (json, file) {
+        (Some(json_str), None) => Ok(Some(json_str)),
+        (None, Some(file_path)) => fs::read_to_string(&file_path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read policy file '{file_path}': {e}").into()),
+        (Some(_), Some(_)) => Err("Cannot specify both --json and --file".into()),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Parse a policy with the same checks as `SecurityService::set_policy`
+fn parse_policy_input(policy_json: &str) -> Result<PolicyDocument, Box<dyn std::error::Error>> {
+    SecurityService::<SqliteSecurityRepository>::parse_policy(policy_json).map_err(|e| match e {
+        RepoError::ValidationError { reason } => {
+            CliUserError::new(format!("Invalid security policy: {reason}")).into()
+        }
+        e => e.into(),
+    })
+}
+
+/// Parse the stored policy the way the proxy reads it (`None` when no policy is set)
+fn parse_stored_policy(
+    policy: Option<SecurityPolicy>,
+) -> Result<Option<PolicyDocument>, Box<dyn std::error::Error>> {
+    policy
+        .map(|policy| {
+            PolicyDocument::parse_lenient(&policy.policy_json)
+                .map(|(document, _)| document)
+                .map_err(|reason| {
+                CliUserError::new(format!(
+                    "Current security policy is invalid and the proxy denies every request: {reason}"
+                ))
+                .into()
+            })
+        })
+        .transpose()
+}
+
 /// Execute policy show command
 async fn execute_policy_show(
     db_path: Option<String>,
@@ -124,21 +205,12 @@ async fn execute_policy_set(
     db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let policy_json = match (json, file) {
-        (Some(json_str), None) => json_str,
-        (None, Some(file_path)) => fs::read_to_string(&file_path)
-            .map_err(|e| format!("Failed to read policy file '{file_path}': {e}"))?,
-        (Some(_), Some(_)) => {
-            return Err("Cannot specify both --json and --file".into());
-        }
-        (None, None) => {
-            return Err("Must specify either --json or --file".into());
-        }
-    };
+    let policy_json =
+        read_policy_input(json, file)?.ok_or("Must specify either --json or --file")?;
 
-    // Validate JSON
-    serde_json::from_str::<serde_json::Value>(&policy_json)
-        .map_err(|e| format!("Invalid JSON: {e}"))?;
+    // Unknown fields are rejected here; older schema versions are migrated
+    // and the policy is stored in the current version's form
+    let document = parse_policy_input(&policy_json)?;
 
     let db_path = db_path
         .map(PathBuf::from)
@@ -149,7 +221,7 @@ async fn execute_policy_set(
 
     let policy = SecurityPolicy {
         id: "default".to_string(),
-        policy_json,
+        policy_json: document.to_json(),
         updated_at: chrono::Utc::now().to_rfc3339(),
     };
 
@@ -160,6 +232,7 @@ async fn execute_policy_set(
             "version": "1.0",
             "data": {
                 "id": policy.id,
+                "schema_version": document.schema_version,
                 "updated_at": policy.updated_at
             }
         });
@@ -167,113 +240,352 @@ async fn execute_policy_set(
     } else {
         println!("Security policy updated successfully");
         println!("  ID: {}", policy.id);
+        println!("  Schema version: {}", document.schema_version);
         println!("  Updated: {}", policy.updated_at);
     }
 
     Ok(())
 }
 
+/// Execute policy diff command
+async fn execute_policy_diff(
+    json: Option<String>,
+    file: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let policy_json =
+        read_policy_input(json, file)?.ok_or("Must specify either --json or --file")?;
+    let proposed = parse_policy_input(&policy_json)?;
+
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+    let service = SecurityService::new(repo);
+
+    let current = parse_stored_policy(service.get_policy("default").await?)?.unwrap_or_default();
+    let changes = current.diff(&proposed);
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "changes": changes
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if changes.is_empty() {
+        println!("No changes to the security policy");
+    } else {
+        println!("Security policy changes:");
+        for change in &changes {
+            match (&change.before, &change.after) {
+                (None, Some(after)) => println!("  + {}: {after}", change.path),
+                (Some(before), None) => println!("  - {}: {before}", change.path),
+                (Some(before), Some(after)) => {
+                    println!("  ~ {}: {before} -> {after}", change.path)
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute policy test command
+async fn execute_policy_test(
+    ip: String,
+    key: Option<String>,
+    json: Option<String>,
+    file: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client_ip: IpAddr = ip
+        .parse()
+        .map_err(|_| CliUserError::new(format!("Invalid IP address '{ip}'")))?;
+    let candidate = read_policy_input(json, file)?;
+
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+    let service = SecurityService::new(repo);
+
+    let policy = match candidate {
+        Some(policy_json) => parse_policy_input(&policy_json)?,
+        None => parse_stored_policy(service.get_policy("default").await?)?.ok_or_else(|| {
+            CliUserError::new("No security policy is set and the proxy denies every request")
+        })?,
+    };
+
+    let scopes = match &key {
+        Some(key_id) => {
+            let record = service
+                .list_api_keys()
+                .await?
+                .into_iter()
+                .find(|record| &record.id == key_id)
+                .ok_or_else(|| CliUserError::new(format!("API key '{key_id}' not found")))?;
+            if record.revoked_at.is_some() {
+                return Err(CliUserError::new(format!("API key '{key_id}' is revoked")).into());
+            }
+            Some(record.scopes)
+        }
+        None => None,
+    };
+
+    let rules = policy.explain(client_ip, scopes.as_ref());
+    let allowed = rules.iter().all(|rule| rule.verdict != RuleVerdict::Deny);
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "ip": client_ip.to_string(),
+                "key_id": key,
+                "decision": if allowed { "allow" } else { "deny" },
+                "rules": rules
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match &key {
+            Some(key_id) => println!("Request from {client_ip} with API key {key_id}:"),
+            None => println!("Request from {client_ip} without an API key:"),
+        }
+        for rule in &rules {
+            println!(
+                "  {:<5}  {:<20}  {}",
+                rule.verdict.as_str(),
+                rule.rule,
+                rule.detail
+            );
+        }
+        println!("Decision: {}", if allowed { "allowed" } else { "denied" });
+    }
+
+    Ok(())
+}
+
+/// config.db settings of the proxy's periodic backups
+const BACKUP_INTERVAL_SETTING: &str = "security.backup.interval_hours";
+const BACKUP_DIR_SETTING: &str = "security.backup.dir";
+const BACKUP_RETENTION_SETTING: &str = "security.backup.retention";
+
+/// Environment variable holding the backup passphrase
+const BACKUP_PASSPHRASE_ENV: &str = "FLM_BACKUP_PASSPHRASE";
+
+/// Shortest passphrase accepted for new backups
+const MIN_BACKUP_PASSPHRASE_LEN: usize = 12;
+
 /// Execute backup command
 async fn execute_backup(
     subcommand: BackupSubcommand,
     db_path: Option<String>,
+    config_db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match subcommand {
-        BackupSubcommand::Create { output } => execute_backup_create(output, db_path, format).await,
-        BackupSubcommand::Restore { file } => execute_backup_restore(file, db_path, format).await,
+        BackupSubcommand::Create {
+            output,
+            database,
+            keep,
+            passphrase_file,
+        } => {
+            execute_backup_create(
+                output,
+                database,
+                keep,
+                passphrase_file,
+                db_path,
+                config_db_path,
+                format,
+            )
+            .await
+        }
+        BackupSubcommand::Verify {
+            file,
+            passphrase_file,
+        } => execute_backup_verify(file, passphrase_file, format).await,
+        BackupSubcommand::Restore {
+            file,
+            passphrase_file,
+            allow_plaintext,
+        } => {
+            execute_backup_restore(
+                file,
+                passphrase_file,
+                allow_plaintext,
+                db_path,
+                config_db_path,
+                format,
+            )
+            .await
+        }
+        BackupSubcommand::Schedule {
+            every_hours,
+            output,
+            keep,
+            disable,
+        } => {
+            execute_backup_schedule(every_hours, output, keep, disable, config_db_path, format)
+                .await
+        }
     }
 }
 
 /// Execute backup create command
 pub async fn execute_backup_create(
     output: Option<String>,
+    database: String,
+    keep: Option<usize>,
+    passphrase_file: Option<String>,
     db_path: Option<String>,
+    config_db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let security_db_path = db_path
         .map(PathBuf::from)
         .unwrap_or_else(get_security_db_path);
+    let config_db_path = config_db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_config_db_path);
 
-    if !security_db_path.exists() {
-        return Err("security.db does not exist".into());
+    let mut databases = Vec::new();
+    if database != "config" {
+        if !security_db_path.exists() {
+            return Err("security.db does not exist".into());
+        }
+        databases.push(security_db_path);
+    }
+    if database != "security" {
+        if config_db_path.exists() {
+            databases.push(config_db_path.clone());
+        } else if database == "config" {
+            return Err("config.db does not exist".into());
+        }
     }
 
-    // Determine backup directory
-    let backup_dir = if let Some(output_path) = output {
-        PathBuf::from(output_path)
-    } else {
-        // Use OS config directory/flm/backups/
-        let app_data_dir =
-            crate::utils::paths::get_app_data_dir().unwrap_or_else(|_| PathBuf::from("."));
-        app_data_dir.join("backups")
+    let settings = read_backup_settings(&config_db_path).await?;
+    let retention = match keep.or(settings.retention) {
+        Some(0) => return Err(CliUserError::new("--keep must be at least 1").into()),
+        Some(keep) => keep,
+        None => backup::DEFAULT_RETENTION,
+    };
+    let backup_dir = output
+        .map(PathBuf::from)
+        .or(settings.dir)
+        .unwrap_or_else(default_backup_dir);
+    let key = backup_key_for_create(passphrase_file.as_deref())?;
+    let key_source = key.source();
+
+    let created = {
+        let backup_dir = backup_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            databases
+                .iter()
+                .map(|db_path| backup::create_backup(db_path, &backup_dir, &key, retention))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await??
     };
 
-    // Create backup directory if it doesn't exist
-    fs::create_dir_all(&backup_dir)?;
-
-    // Generate backup filename with timestamp
-    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
-    let backup_filename = format!("security.db.bak.{timestamp}");
-    let backup_path = backup_dir.join(&backup_filename);
-
-    // Copy security.db to backup location
-    fs::copy(&security_db_path, &backup_path)?;
-
-    // Manage backup generations (keep only 3 most recent)
-    let mut backup_files: Vec<(PathBuf, std::time::SystemTime)> = Vec::new();
-    for entry in fs::read_dir(&backup_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file()
-            && path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .is_some_and(|n| n.starts_with("security.db.bak."))
-        {
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    backup_files.push((path, modified));
-                }
+    if format == "json" {
+        let backups: Vec<serde_json::Value> = created
+            .iter()
+            .map(|(path, removed)| {
+                json!({
+                    "backup_path": path.to_string_lossy(),
+                    "removed": removed
+                        .iter()
+                        .map(|path| path.to_string_lossy())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let output_json = json!({
+            "version": "1.0",
+            "data": {
+                "backups": backups,
+                "backup_dir": backup_dir.to_string_lossy(),
+                "key_source": key_source.as_str(),
+                "retention": retention
             }
+        });
+        println!("{}", serde_json::to_string_pretty(&output_json)?);
+    } else {
+        println!("Backup created successfully");
+        for (path, _) in &created {
+            println!("  Path: {}", path.display());
+        }
+        println!("  Encryption: AES-256-GCM ({key_source} key)");
+        println!("  Retention: {retention} per database");
+        for (path, _) in &created {
+            eprintln!("Backup saved to: {}", path.display());
         }
     }
 
-    // Sort by modification time (newest first)
-    backup_files.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(())
+}
 
-    // Remove oldest backups if more than 3
-    if backup_files.len() > 3 {
-        for (path, _) in backup_files.iter().skip(3) {
-            if let Err(e) = fs::remove_file(path) {
-                eprintln!("Warning: Failed to remove old backup {path:?}: {e}");
-            }
+/// Execute backup verify command
+pub async fn execute_backup_verify(
+    file: String,
+    passphrase_file: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let contents =
+        fs::read(&file).map_err(|e| format!("Failed to read backup file {file}: {e}"))?;
+    let key = match backup::read_header(&contents).map_err(backup_user_error)? {
+        BackupKind::Encrypted(header) => backup_key_for(&header, passphrase_file.as_deref())?,
+        BackupKind::LegacyPlaintext => {
+            return Err(CliUserError::new(format!(
+                "{file} is an unencrypted legacy backup; restore it and create a new backup"
+            ))
+            .into())
         }
-    }
+    };
+    let verified = tokio::task::spawn_blocking(move || backup::verify_backup(&contents, &key))
+        .await?
+        .map_err(backup_user_error)?;
+    let header = &verified.header;
 
     if format == "json" {
         let output_json = json!({
             "version": "1.0",
             "data": {
-                "backup_path": backup_path.to_str().ok_or_else(|| {
-                    format!(
-                        "Error: Invalid UTF-8 encoding in backup path.\n\
-                        Path: {}\n\
-                        This usually indicates a system configuration issue.\n\
-                        Please ensure your system locale supports UTF-8 or use a different backup location.",
-                        backup_path.display()
-                    )
-                })?,
-                "timestamp": timestamp.to_string(),
-                "total_backups": backup_files.len().min(3)
+                "backup_path": file,
+                "valid": true,
+                "database": header.database,
+                "created_at": header.created_at,
+                "key_source": header.key_source.as_str(),
+                "cipher": header.cipher,
+                "size_bytes": header.size_bytes,
+                "tables": verified.tables,
+                "integrity_check": "ok"
             }
         });
         println!("{}", serde_json::to_string_pretty(&output_json)?);
     } else {
-        println!("Backup created successfully");
-        println!("  Path: {}", backup_path.display());
-        println!("  Timestamp: {timestamp}");
-        eprintln!("Backup saved to: {}", backup_path.display());
+        println!("Backup verified");
+        println!("  Path: {file}");
+        println!("  Database: {}", header.database);
+        println!("  Created: {}", header.created_at);
+        println!(
+            "  Encryption: {} ({} key)",
+            header.cipher.to_uppercase(),
+            header.key_source
+        );
+        println!(
+            "  Size: {} bytes, {} tables",
+            header.size_bytes, verified.tables
+        );
+        println!("  Integrity check: ok");
     }
 
     Ok(())
@@ -282,79 +594,367 @@ pub async fn execute_backup_create(
 /// Execute backup restore command
 pub async fn execute_backup_restore(
     file: String,
+    passphrase_file: Option<String>,
+    allow_plaintext: bool,
     db_path: Option<String>,
+    config_db_path: Option<String>,
     format: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let backup_path = PathBuf::from(&file);
     if !backup_path.exists() {
         return Err(format!("Backup file does not exist: {file}").into());
     }
+    let contents = fs::read(&backup_path)?;
 
-    let security_db_path = db_path
-        .map(PathBuf::from)
-        .unwrap_or_else(get_security_db_path);
+    let (database, key) = match backup::read_header(&contents).map_err(backup_user_error)? {
+        BackupKind::Encrypted(header) => {
+            let key = backup_key_for(&header, passphrase_file.as_deref())?;
+            (header.database, Some(key))
+        }
+        // Plain copies of security.db taken before backups were encrypted;
+        // nothing proves they were not modified
+        BackupKind::LegacyPlaintext if !allow_plaintext => {
+            return Err(CliUserError::new(format!(
+                "{file} is an unencrypted legacy backup that cannot be authenticated; \
+                 pass --allow-plaintext to restore it anyway"
+            ))
+            .into())
+        }
+        BackupKind::LegacyPlaintext => {
+            eprintln!(
+                "Warning: {file} is an unencrypted legacy backup; it cannot be authenticated \
+                 and is restored as is"
+            );
+            ("security.db".to_string(), None)
+        }
+    };
+    let target = match database.as_str() {
+        "security.db" => db_path
+            .map(PathBuf::from)
+            .unwrap_or_else(get_security_db_path),
+        "config.db" => config_db_path
+            .map(PathBuf::from)
+            .unwrap_or_else(get_config_db_path),
+        other => {
+            return Err(
+                CliUserError::new(format!("Backup holds an unknown database '{other}'")).into(),
+            )
+        }
+    };
 
-    // Check if security.db exists and warn user
-    if security_db_path.exists() && format != "json" {
+    // Check if the database exists and warn user
+    if target.exists() && format != "json" {
         eprintln!(
-            "Warning: security.db already exists at: {}",
-            security_db_path.display()
+            "Warning: {database} already exists at: {}",
+            target.display()
         );
         eprintln!("This operation will overwrite the existing database.");
         eprintln!("Make sure the application is stopped before proceeding.");
     }
 
-    // Create parent directory if needed
-    if let Some(parent) = security_db_path.parent() {
-        fs::create_dir_all(parent)?;
+    // The backup is authenticated and checked before the target is written
+    let kind = {
+        let target = target.clone();
+        tokio::task::spawn_blocking(move || {
+            backup::restore_backup(&contents, key.as_ref(), allow_plaintext, &target)
+        })
+        .await?
+        .map_err(backup_user_error)?
+    };
+
+    // Opening the repository runs migrations on the restored database
+    let verification = if database == "config.db" {
+        let repo = SqliteConfigRepository::new(&target).await?;
+        ConfigService::new(repo).list().await.map(|_| ())
+    } else {
+        let repo = SqliteSecurityRepository::new(&target).await?;
+        SecurityService::new(repo).list_api_keys().await.map(|_| ())
+    };
+    if let Err(e) = verification {
+        return Err(format!("Failed to verify restored database: {e}").into());
     }
 
-    // Copy backup to security.db location
-    fs::copy(&backup_path, &security_db_path)?;
+    let encrypted = matches!(kind, BackupKind::Encrypted(_));
+    if format == "json" {
+        let output_json = json!({
+            "version": "1.0",
+            "data": {
+                "restored_path": target.to_str().ok_or_else(|| {
+                    format!(
+                        "Error: Invalid UTF-8 encoding in restored database path.\n\
+                        Path: {}\n\
+                        This usually indicates a system configuration issue.\n\
+                        Please ensure your system locale supports UTF-8 or use a different database path.",
+                        target.display()
+                    )
+                })?,
+                "backup_path": file,
+                "database": database,
+                "encrypted": encrypted,
+                "migrations_applied": true
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output_json)?);
+    } else {
+        println!("Backup restored successfully");
+        println!("  Restored to: {}", target.display());
+        if !encrypted {
+            println!("  Source was an unencrypted legacy backup");
+        }
+        println!("  Migrations applied");
+        println!("\nNote: The application should be restarted to use the restored database.");
+    }
 
-    // Run migrations on the restored database
-    let repo = SqliteSecurityRepository::new(&security_db_path).await?;
-    // Repository initialization already runs migrations, so we just need to verify
-    let service = SecurityService::new(repo);
+    Ok(())
+}
 
-    // Verify the restored database is accessible
-    match service.list_api_keys().await {
-        Ok(_) => {
-            if format == "json" {
-                let output_json = json!({
-                    "version": "1.0",
-                    "data": {
-                        "restored_path": security_db_path.to_str().ok_or_else(|| {
-                            format!(
-                                "Error: Invalid UTF-8 encoding in restored database path.\n\
-                                Path: {}\n\
-                                This usually indicates a system configuration issue.\n\
-                                Please ensure your system locale supports UTF-8 or use a different database path.",
-                                security_db_path.display()
-                            )
-                        })?,
-                        "backup_path": file,
-                        "migrations_applied": true
-                    }
-                });
-                println!("{}", serde_json::to_string_pretty(&output_json)?);
-            } else {
-                println!("Backup restored successfully");
-                println!("  Restored to: {}", security_db_path.display());
-                println!("  Migrations applied");
-                println!(
-                    "\nNote: The application should be restarted to use the restored database."
-                );
+/// Execute backup schedule command
+async fn execute_backup_schedule(
+    every_hours: Option<u32>,
+    output: Option<String>,
+    keep: Option<usize>,
+    disable: bool,
+    config_db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_db_path = config_db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_config_db_path);
+    let service = ConfigService::new(SqliteConfigRepository::new(&config_db_path).await?);
+
+    if keep == Some(0) {
+        return Err(CliUserError::new("--keep must be at least 1").into());
+    }
+    if every_hours == Some(0) {
+        return Err(CliUserError::new(
+            "--every-hours must be at least 1; use --disable to stop periodic backups",
+        )
+        .into());
+    }
+    if let Some(hours) = every_hours {
+        // The proxy seals periodic backups with the keyring key
+        keyring_backup_key(true)?;
+        service
+            .set(BACKUP_INTERVAL_SETTING, &hours.to_string())
+            .await?;
+    }
+    if disable {
+        service.set(BACKUP_INTERVAL_SETTING, "0").await?;
+    }
+    if let Some(output) = output {
+        service.set(BACKUP_DIR_SETTING, &output).await?;
+    }
+    if let Some(keep) = keep {
+        service
+            .set(BACKUP_RETENTION_SETTING, &keep.to_string())
+            .await?;
+    }
+
+    let settings = read_backup_settings(&config_db_path).await?;
+    let output_dir = settings.dir.unwrap_or_else(default_backup_dir);
+    let retention = settings.retention.unwrap_or(backup::DEFAULT_RETENTION);
+    if format == "json" {
+        let output_json = json!({
+            "version": "1.0",
+            "data": {
+                "enabled": settings.interval_hours.is_some(),
+                "interval_hours": settings.interval_hours,
+                "output_dir": output_dir.to_string_lossy(),
+                "retention": retention
             }
+        });
+        println!("{}", serde_json::to_string_pretty(&output_json)?);
+    } else {
+        match settings.interval_hours {
+            Some(hours) => println!("Periodic backups: every {hours} hours"),
+            None => println!("Periodic backups: disabled"),
         }
-        Err(e) => {
-            return Err(format!("Failed to verify restored database: {e}").into());
+        println!("  Output: {}", output_dir.display());
+        println!("  Retention: {retention} per database");
+        if every_hours.is_some() || disable {
+            println!("\nNote: Takes effect the next time the proxy starts.");
         }
     }
 
     Ok(())
 }
 
+/// Backup settings stored in config.db
+#[derive(Default)]
+struct BackupSettings {
+    /// Hours between the proxy's backups; `None` when disabled
+    interval_hours: Option<u32>,
+    dir: Option<PathBuf>,
+    retention: Option<usize>,
+}
+
+/// Read the backup settings without creating config.db
+async fn read_backup_settings(
+    config_db_path: &Path,
+) -> Result<BackupSettings, Box<dyn std::error::Error>> {
+    if !config_db_path.exists() {
+        return Ok(BackupSettings::default());
+    }
+    let service = ConfigService::new(SqliteConfigRepository::new(config_db_path).await?);
+    let parse = |key: &str, value: Option<String>| -> Result<Option<u64>, CliUserError> {
+        value
+            .map(|value| {
+                value.trim().parse::<u64>().map_err(|_| {
+                    CliUserError::new(format!("Invalid {key} setting '{value}' in config.db"))
+                })
+            })
+            .transpose()
+    };
+    let interval_hours = parse(
+        BACKUP_INTERVAL_SETTING,
+        service.get(BACKUP_INTERVAL_SETTING).await?,
+    )?
+    .filter(|hours| *hours > 0)
+    .map(|hours| u32::try_from(hours).unwrap_or(u32::MAX));
+    let retention = parse(
+        BACKUP_RETENTION_SETTING,
+        service.get(BACKUP_RETENTION_SETTING).await?,
+    )?
+    .filter(|keep| *keep > 0)
+    .map(|keep| usize::try_from(keep).unwrap_or(usize::MAX));
+    let dir = service.get(BACKUP_DIR_SETTING).await?.map(PathBuf::from);
+    Ok(BackupSettings {
+        interval_hours,
+        dir,
+        retention,
+    })
+}
+
+/// Periodic backup settings for `flm proxy start`, with the keyring key
+///
+/// Returns `(None, None)` when periodic backups are disabled or the key is
+/// unavailable; the proxy then starts without them.
+pub(crate) async fn resolve_backup_schedule(
+    config_db_path: &Path,
+) -> Result<(Option<BackupScheduleConfig>, Option<String>), Box<dyn std::error::Error>> {
+    let settings = read_backup_settings(config_db_path).await?;
+    let Some(interval_hours) = settings.interval_hours else {
+        return Ok((None, None));
+    };
+    let key = match keyring_backup_key(false) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("Warning: periodic backups disabled: {e}");
+            return Ok((None, None));
+        }
+    };
+    let schedule = BackupScheduleConfig {
+        interval_hours,
+        output_dir: settings
+            .dir
+            .unwrap_or_else(default_backup_dir)
+            .to_string_lossy()
+            .to_string(),
+        retention: settings
+            .retention
+            .unwrap_or(backup::DEFAULT_RETENTION)
+            .try_into()
+            .unwrap_or(u32::MAX),
+    };
+    Ok((Some(schedule), key.encode()))
+}
+
+/// OS config directory/flm/backups/
+fn default_backup_dir() -> PathBuf {
+    crate::utils::paths::get_app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("backups")
+}
+
+/// Passphrase from `--passphrase-file`, else FLM_BACKUP_PASSPHRASE
+fn backup_passphrase(
+    passphrase_file: Option<&str>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let passphrase = match passphrase_file {
+        Some(path) => Some(
+            fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read passphrase file {path}: {e}"))?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        ),
+        None => std::env::var(BACKUP_PASSPHRASE_ENV).ok(),
+    };
+    match passphrase {
+        Some(passphrase) if passphrase.is_empty() => {
+            Err(CliUserError::new("Backup passphrase is empty").into())
+        }
+        passphrase => Ok(passphrase),
+    }
+}
+
+/// Key for new backups: the passphrase if one is given, else the keyring key
+fn backup_key_for_create(
+    passphrase_file: Option<&str>,
+) -> Result<BackupKey, Box<dyn std::error::Error>> {
+    match backup_passphrase(passphrase_file)? {
+        Some(passphrase) if passphrase.chars().count() < MIN_BACKUP_PASSPHRASE_LEN => {
+            Err(CliUserError::new(format!(
+                "Backup passphrase must be at least {MIN_BACKUP_PASSPHRASE_LEN} characters"
+            ))
+            .into())
+        }
+        Some(passphrase) => Ok(BackupKey::Passphrase(passphrase)),
+        None => keyring_backup_key(true),
+    }
+}
+
+/// Key that opens a backup with this header
+fn backup_key_for(
+    header: &BackupHeader,
+    passphrase_file: Option<&str>,
+) -> Result<BackupKey, Box<dyn std::error::Error>> {
+    match header.key_source {
+        KeySource::Passphrase => backup_passphrase(passphrase_file)?
+            .map(BackupKey::Passphrase)
+            .ok_or_else(|| {
+                CliUserError::new(format!(
+                    "Backup is encrypted with a passphrase; pass --passphrase-file or set {BACKUP_PASSPHRASE_ENV}"
+                ))
+                .into()
+            }),
+        KeySource::Keyring => keyring_backup_key(false),
+    }
+}
+
+/// The keyring-held backup key, generated and stored on first use if `create`
+fn keyring_backup_key(create: bool) -> Result<BackupKey, Box<dyn std::error::Error>> {
+    match secrets::load_backup_key() {
+        Ok(encoded) => Ok(BackupKey::decode(&encoded)?),
+        Err(keyring::Error::NoEntry) if create && !secrets::keyring_disabled() => {
+            let key = BackupKey::generate()?;
+            let encoded = key.encode().ok_or("generated backup key is not storable")?;
+            secrets::store_backup_key(&encoded)
+                .map_err(|e| format!("Failed to store the backup key in the OS keyring: {e}"))?;
+            Ok(key)
+        }
+        Err(keyring::Error::NoEntry) => {
+            let reason = if secrets::keyring_disabled() {
+                "the OS keyring is disabled (FLM_DISABLE_KEYRING)"
+            } else {
+                "no backup key is stored in the OS keyring"
+            };
+            Err(CliUserError::new(format!(
+                "Backup key unavailable: {reason}; pass --passphrase-file or set {BACKUP_PASSPHRASE_ENV}"
+            ))
+            .into())
+        }
+        Err(e) => Err(format!("Failed to read the backup key from the OS keyring: {e}").into()),
+    }
+}
+
+/// Backup errors caused by the file or key rather than by FLM itself
+fn backup_user_error(error: BackupError) -> Box<dyn std::error::Error> {
+    match error {
+        BackupError::Io(_) | BackupError::Sqlite(_) => error.into(),
+        other => CliUserError::new(other.to_string()).into(),
+    }
+}
+
 /// Execute IP blocklist command
 async fn execute_ip_blocklist(
     subcommand: IpBlocklistSubcommand,
@@ -574,6 +1174,497 @@ async fn execute_audit_logs(
     Ok(())
 }
 
+/// Audit log entries read per query while verifying the chain
+const AUDIT_VERIFY_PAGE_SIZE: u32 = 1000;
+
+/// Execute `flm security audit-logs <subcommand>`
+async fn execute_audit_logs_subcommand(
+    subcommand: AuditLogsSubcommand,
+    db_path: Option<String>,
+    config_db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match subcommand {
+        AuditLogsSubcommand::Verify { hmac_key_file } => {
+            execute_audit_logs_verify(hmac_key_file, db_path, format).await
+        }
+        AuditLogsSubcommand::InitHmacKey { export } => {
+            execute_audit_logs_init_hmac_key(export, format)
+        }
+        AuditLogsSubcommand::Sinks { subcommand } => {
+            execute_audit_sinks(subcommand, config_db_path, format).await
+        }
+        AuditLogsSubcommand::Export { since, output } => {
+            execute_audit_logs_export(since, output, db_path, format).await
+        }
+    }
+}
+
+/// Execute `flm security audit-logs verify`
+///
+/// Exits with status 1 when the chain is broken.
+async fn execute_audit_logs_verify(
+    hmac_key_file: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let key = match hmac_key_file {
+        Some(path) => Some(read_audit_hmac_key_file(&path)?),
+        None => keyring_audit_hmac_key()?,
+    };
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+
+    // Entries removed by retention pruning are verified up to the anchor
+    let anchor = repo.latest_audit_chain_anchor().await?;
+    let mut verifier = match &anchor {
+        Some(anchor) => AuditChainVerifier::from_anchor(key.as_deref(), anchor),
+        None => AuditChainVerifier::new(key.as_deref()),
+    };
+    let mut after_id = anchor.map_or(0, |anchor| anchor.pruned_through_id);
+    'pages: loop {
+        let rows = repo
+            .list_chained_audit_logs(after_id, None, AUDIT_VERIFY_PAGE_SIZE)
+            .await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after_id = last.entry.id;
+        for row in &rows {
+            if !verifier.push(row) {
+                break 'pages;
+            }
+        }
+    }
+    let report = verifier.finish();
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "intact": report.is_intact(),
+                "hmac_key": key.is_some(),
+                "rows": report.rows,
+                "legacy_rows": report.legacy_rows,
+                "verified_rows": report.verified_rows,
+                "hmac_rows": report.hmac_rows,
+                "last_id": report.last_id,
+                "last_hash": report.last_hash,
+                "pruned_through_id": report.pruned_through_id,
+                "first_break": report.first_break
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        match &report.first_break {
+            None => println!("Audit log chain intact"),
+            Some(first_break) => println!(
+                "Audit log chain broken at entry {} ({}): {}",
+                first_break.id,
+                first_break.kind.as_str(),
+                first_break.reason
+            ),
+        }
+        println!(
+            "  Entries verified: {} ({} HMAC-signed)",
+            report.verified_rows, report.hmac_rows
+        );
+        if report.legacy_rows > 0 {
+            println!("  Entries written before the chain: {}", report.legacy_rows);
+        }
+        if let Some(pruned_through_id) = report.pruned_through_id {
+            println!("  Entries pruned by retention: up to {pruned_through_id}");
+        }
+        if let (Some(id), Some(hash)) = (report.last_id, &report.last_hash) {
+            println!("  Last intact entry: {id} ({hash})");
+        }
+    }
+
+    if report.is_intact() {
+        Ok(())
+    } else {
+        Err(Box::new(CliUserError::silent()))
+    }
+}
+
+/// Execute `flm security audit-logs init-hmac-key`
+fn execute_audit_logs_init_hmac_key(
+    export: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if secrets::keyring_disabled() {
+        return Err(CliUserError::new(
+            "The OS keyring is disabled (FLM_DISABLE_KEYRING); the audit log HMAC key must be stored there",
+        )
+        .into());
+    }
+    match secrets::load_audit_hmac_key() {
+        Ok(_) => {
+            return Err(CliUserError::new(
+                "An audit log HMAC key already exists; entries signed with it could not be verified after replacing it",
+            )
+            .into())
+        }
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => {
+            return Err(format!("Failed to read the audit log HMAC key from the OS keyring: {e}").into())
+        }
+    }
+
+    let mut key = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut key);
+    let encoded = BASE64.encode(key);
+    if let Some(path) = &export {
+        write_secret_file(Path::new(path), &encoded)
+            .map_err(|e| format!("Failed to write {path}: {e}"))?;
+    }
+    secrets::store_audit_hmac_key(&encoded)
+        .map_err(|e| format!("Failed to store the audit log HMAC key in the OS keyring: {e}"))?;
+
+    if format == "json" {
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "keyring_service": secrets::AUDIT_HMAC_KEYRING_SERVICE,
+                "exported_to": export
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("Audit log HMAC key created in the OS keyring");
+        if let Some(path) = &export {
+            println!("  Exported to: {path}");
+        }
+        println!("\nNote: Entries are signed from the next time the proxy starts.");
+    }
+    Ok(())
+}
+
+/// Audit log HMAC key for `flm proxy start`, if one is in the keyring
+pub(crate) fn resolve_audit_hmac_key() -> Option<String> {
+    match secrets::load_audit_hmac_key() {
+        Ok(key) => Some(key),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            eprintln!("Warning: audit log entries will not be HMAC-signed: {e}");
+            None
+        }
+    }
+}
+
+fn keyring_audit_hmac_key() -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+    match secrets::load_audit_hmac_key() {
+        Ok(encoded) => Ok(Some(decode_audit_hmac_key(&encoded)?)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => {
+            Err(format!("Failed to read the audit log HMAC key from the OS keyring: {e}").into())
+        }
+    }
+}
+
+fn read_audit_hmac_key_file(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let encoded = fs::read_to_string(path)
+        .map_err(|e| CliUserError::new(format!("Failed to read HMAC key file {path}: {e}")))?;
+    decode_audit_hmac_key(encoded.trim())
+}
+
+fn decode_audit_hmac_key(encoded: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    match BASE64.decode(encoded) {
+        Ok(key) if !key.is_empty() => Ok(key),
+        _ => Err(CliUserError::new("The audit log HMAC key is not valid base64").into()),
+    }
+}
+
+/// Create a file readable only by the owner (fails if it exists)
+fn write_secret_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.write_all(b"\n")
+}
+
+/// Execute `flm security audit-logs sinks <subcommand>`
+async fn execute_audit_sinks(
+    subcommand: AuditSinksSubcommand,
+    config_db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_db_path = config_db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_config_db_path);
+    let repo = SqliteAuditSinkRepository::new(&config_db_path).await?;
+
+    match subcommand {
+        AuditSinksSubcommand::Add(args) => {
+            let config = audit_sink_config_from_args(&args)?;
+            config.validate().map_err(CliUserError::new)?;
+            let token =
+                bearer_token_from_args(args.bearer_token.as_deref(), args.bearer_token_stdin)?;
+            if token.is_some() && !matches!(config, AuditSinkConfig::Webhook { .. }) {
+                return Err(
+                    CliUserError::new("--bearer-token is only used by webhook sinks").into(),
+                );
+            }
+            if token.is_some() && secrets::keyring_disabled() {
+                return Err(CliUserError::new(
+                    "The OS keyring is disabled (FLM_DISABLE_KEYRING); webhook tokens are stored there",
+                )
+                .into());
+            }
+
+            let sink = repo.save_sink(&args.id, &config).await?;
+            if let Some(token) = &token {
+                secrets::store_audit_sink_token(&sink.id, token).map_err(|e| {
+                    format!("Failed to store the webhook token in the OS keyring: {e}")
+                })?;
+            }
+
+            if format == "json" {
+                let output = json!({
+                    "version": "1.0",
+                    "data": {
+                        "sink": sink,
+                        "token_in_keyring": token.is_some()
+                    }
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!(
+                    "Saved audit sink {} ({})",
+                    sink.id,
+                    describe_audit_sink(&sink.config)
+                );
+                println!("\nNote: The proxy picks up sink changes when it restarts.");
+            }
+        }
+        AuditSinksSubcommand::List => {
+            let sinks = repo.list_sinks().await?;
+            if format == "json" {
+                let output = json!({
+                    "version": "1.0",
+                    "data": {
+                        "sinks": sinks
+                    }
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else if sinks.is_empty() {
+                println!("No audit sinks configured");
+            } else {
+                for sink in &sinks {
+                    println!("{}: {}", sink.id, describe_audit_sink(&sink.config));
+                }
+            }
+        }
+        AuditSinksSubcommand::Remove { id } => {
+            if !repo.delete_sink(&id).await? {
+                return Err(CliUserError::new(format!("Audit sink '{id}' not found")).into());
+            }
+            match secrets::delete_audit_sink_token(&id) {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(e) => eprintln!("Warning: audit sink removed but keyring cleanup failed: {e}"),
+            }
+
+            if format == "json" {
+                let output = json!({
+                    "version": "1.0",
+                    "data": {
+                        "deleted": true,
+                        "id": id
+                    }
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("Removed audit sink {id}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn audit_sink_config_from_args(args: &AuditSinkAddArgs) -> Result<AuditSinkConfig, CliUserError> {
+    fn required(value: &Option<String>, flag: &str, kind: &str) -> Result<String, CliUserError> {
+        value
+            .clone()
+            .ok_or_else(|| CliUserError::new(format!("--{flag} is required for {kind} sinks")))
+    }
+
+    match args.kind.as_str() {
+        "syslog" => Ok(AuditSinkConfig::Syslog {
+            address: required(&args.address, "address", "syslog")?,
+            transport: args
+                .transport
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(CliUserError::new)?
+                .unwrap_or_default(),
+            facility: args.facility.unwrap_or(DEFAULT_SYSLOG_FACILITY),
+            app_name: args.app_name.clone(),
+        }),
+        "jsonl" => Ok(AuditSinkConfig::Jsonl {
+            path: required(&args.path, "path", "jsonl")?,
+            max_bytes: args.max_bytes.unwrap_or(DEFAULT_JSONL_MAX_BYTES),
+            max_files: args.max_files.unwrap_or(DEFAULT_JSONL_MAX_FILES),
+        }),
+        "webhook" => Ok(AuditSinkConfig::Webhook {
+            url: required(&args.url, "url", "webhook")?,
+            batch_size: args.batch_size.unwrap_or(DEFAULT_WEBHOOK_BATCH_SIZE),
+            flush_interval_secs: args
+                .flush_interval_secs
+                .unwrap_or(DEFAULT_WEBHOOK_FLUSH_INTERVAL_SECS),
+            max_retries: args.max_retries.unwrap_or(DEFAULT_WEBHOOK_MAX_RETRIES),
+            auth_scheme: args.auth_scheme.clone(),
+        }),
+        other => Err(CliUserError::new(format!(
+            "Unknown sink kind '{other}' (syslog, jsonl, webhook)"
+        ))),
+    }
+}
+
+fn describe_audit_sink(config: &AuditSinkConfig) -> String {
+    match config {
+        AuditSinkConfig::Syslog {
+            address, transport, ..
+        } => format!("syslog {}://{address}", transport.as_str()),
+        AuditSinkConfig::Jsonl {
+            path,
+            max_bytes,
+            max_files,
+        } => format!("jsonl {path} (rotate at {max_bytes} bytes, keep {max_files})"),
+        AuditSinkConfig::Webhook {
+            url, batch_size, ..
+        } => format!("webhook {url} (batches of {batch_size})"),
+    }
+}
+
+/// Webhook tokens for `flm proxy start`, by sink ID
+///
+/// Sinks without a token in the keyring are left out.
+pub(crate) async fn resolve_audit_sink_tokens(
+    config_db_path: &Path,
+) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let mut tokens = HashMap::new();
+    for sink in SqliteAuditSinkRepository::new(config_db_path)
+        .await?
+        .list_sinks()
+        .await?
+    {
+        if !matches!(sink.config, AuditSinkConfig::Webhook { .. }) {
+            continue;
+        }
+        if let Ok(token) = secrets::load_audit_sink_token(&sink.id) {
+            tokens.insert(sink.id, token);
+        }
+    }
+    Ok(tokens)
+}
+
+/// Columns of `flm security audit-logs export --format csv`
+const AUDIT_EXPORT_CSV_HEADER: &str = "id,created_at,request_id,api_key_id,endpoint,status,latency_ms,event_type,severity,ip,details,prev_hash,entry_hash,hash_alg";
+
+/// Execute `flm security audit-logs export`
+async fn execute_audit_logs_export(
+    since: Option<String>,
+    output: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let csv = match format.as_str() {
+        "csv" => true,
+        "json" | "jsonl" => false,
+        other => {
+            return Err(CliUserError::new(format!(
+                "Unsupported export format '{other}' (jsonl or csv)"
+            ))
+            .into())
+        }
+    };
+    // audit_logs.created_at is SQLite's "YYYY-MM-DD HH:MM:SS" (UTC)
+    let since = since
+        .as_deref()
+        .map(|value| crate::commands::usage::parse_since(value, chrono::Utc::now()))
+        .transpose()?
+        .map(|since| {
+            chrono::DateTime::parse_from_rfc3339(&since)
+                .map(|time| time.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string())
+        })
+        .transpose()?;
+
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+
+    let mut writer: Box<dyn std::io::Write> = match &output {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            fs::File::create(path).map_err(|e| format!("Failed to create {path}: {e}"))?,
+        )),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    if csv {
+        writeln!(writer, "{AUDIT_EXPORT_CSV_HEADER}")?;
+    }
+
+    let mut after_id = 0;
+    let mut exported = 0u64;
+    loop {
+        let rows = repo
+            .list_chained_audit_logs(after_id, since.as_deref(), AUDIT_VERIFY_PAGE_SIZE)
+            .await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after_id = last.entry.id;
+        for row in &rows {
+            if csv {
+                writeln!(writer, "{}", audit_log_csv_row(row))?;
+            } else {
+                writeln!(writer, "{}", serde_json::to_string(row)?)?;
+            }
+        }
+        exported += rows.len() as u64;
+    }
+    writer.flush()?;
+
+    if let Some(path) = &output {
+        eprintln!("Exported {exported} audit log entries to {path}");
+    }
+    Ok(())
+}
+
+fn audit_log_csv_row(row: &ChainedAuditLog) -> String {
+    let entry = &row.entry;
+    let optional = |value: &Option<String>| csv_field(value.as_deref().unwrap_or_default());
+    [
+        entry.id.to_string(),
+        csv_field(&entry.created_at),
+        csv_field(&entry.request_id),
+        optional(&entry.api_key_id),
+        csv_field(&entry.endpoint),
+        entry.status.to_string(),
+        entry
+            .latency_ms
+            .map(|ms| ms.to_string())
+            .unwrap_or_default(),
+        optional(&entry.event_type),
+        optional(&entry.severity),
+        optional(&entry.ip),
+        optional(&entry.details),
+        optional(&row.prev_hash),
+        optional(&row.entry_hash),
+        optional(&row.hash_alg),
+    ]
+    .join(",")
+}
+
 /// Execute intrusion command
 async fn execute_intrusion(
     ip: Option<String>,
@@ -919,3 +2010,127 @@ async fn execute_rate_limits(
 
     Ok(())
 }
+
+/// Execute quotas command
+async fn execute_quotas(
+    api_key_id: Option<String>,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let usage = repo.list_token_usage(&today, api_key_id.as_deref()).await?;
+
+    let service = SecurityService::new(repo);
+    let limits = match service.get_policy("default").await? {
+        Some(policy) => {
+            TokenLimits::from_policy(&PolicyDocument::parse_lenient(&policy.policy_json)?.0)
+        }
+        None => TokenLimits::default(),
+    };
+
+    // Every matching key is listed, including keys without usage this month
+    let keys = service.list_api_keys().await?;
+    let mut quotas: Vec<(String, String, i64, i64, i64, i64)> = keys
+        .into_iter()
+        .filter(|key| key.revoked_at.is_none())
+        .filter(|key| api_key_id.as_deref().is_none_or(|id| id == key.id))
+        .map(|key| (key.id, key.label, 0, 0, 0, 0))
+        .collect();
+    for (key_id, day_tokens, day_requests, month_tokens, month_requests) in usage {
+        match quotas.iter_mut().find(|entry| entry.0 == key_id) {
+            Some(entry) => {
+                (entry.2, entry.3, entry.4, entry.5) =
+                    (day_tokens, day_requests, month_tokens, month_requests)
+            }
+            None => quotas.push((
+                key_id,
+                String::new(),
+                day_tokens,
+                day_requests,
+                month_tokens,
+                month_requests,
+            )),
+        }
+    }
+
+    let remaining =
+        |limit: Option<u64>, used: i64| limit.map(|limit| limit.saturating_sub(used.max(0) as u64));
+
+    if format == "json" {
+        let quota_list: Vec<serde_json::Value> = quotas
+            .iter()
+            .map(
+                |(key_id, label, day_tokens, day_requests, month_tokens, month_requests)| {
+                    json!({
+                        "api_key_id": key_id,
+                        "label": label,
+                        "day_tokens": day_tokens,
+                        "day_requests": day_requests,
+                        "month_tokens": month_tokens,
+                        "month_requests": month_requests,
+                        "daily_remaining": remaining(limits.daily_tokens, *day_tokens),
+                        "monthly_remaining": remaining(limits.monthly_tokens, *month_tokens)
+                    })
+                },
+            )
+            .collect();
+
+        let output = json!({
+            "version": "1.0",
+            "data": {
+                "date": today,
+                "limits": {
+                    "tpm": limits.tpm,
+                    "daily_tokens": limits.daily_tokens,
+                    "monthly_tokens": limits.monthly_tokens
+                },
+                "quotas": quota_list
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let show_limit = |limit: Option<u64>| {
+        limit
+            .map(|limit| limit.to_string())
+            .unwrap_or_else(|| "unlimited".to_string())
+    };
+    println!("Token Limits (per API key):");
+    println!("  Tokens per minute: {}", show_limit(limits.tpm));
+    println!("  Daily: {}", show_limit(limits.daily_tokens));
+    println!("  Monthly: {}", show_limit(limits.monthly_tokens));
+    println!();
+
+    if quotas.is_empty() {
+        println!("No API keys found");
+        return Ok(());
+    }
+
+    println!("Token Usage ({today} UTC):");
+    for (key_id, label, day_tokens, day_requests, month_tokens, month_requests) in quotas {
+        println!("  API Key ID: {key_id}");
+        if !label.is_empty() {
+            println!("    Label: {label}");
+        }
+        print!("    Today: {day_tokens} tokens ({day_requests} requests)");
+        match remaining(limits.daily_tokens, day_tokens) {
+            Some(left) => println!(", {left} remaining"),
+            None => println!(),
+        }
+        print!("    This month: {month_tokens} tokens ({month_requests} requests)");
+        match remaining(limits.monthly_tokens, month_tokens) {
+            Some(left) => println!(", {left} remaining"),
+            None => println!(),
+        }
+        println!();
+    }
+
+    Ok(())
+}
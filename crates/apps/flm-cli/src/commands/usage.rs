@@ -0,0 +1,205 @@
+//! `flm usage` command implementation
+
+use crate::adapters::{SqliteSecurityRepository, UsageGroupBy, UsageReportRow};
+use crate::cli::usage::UsageSubcommand;
+use crate::commands::CliUserError;
+use crate::utils::get_security_db_path;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use flm_core::services::SecurityService;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub async fn execute(
+    subcommand: UsageSubcommand,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match subcommand {
+        UsageSubcommand::Report {
+            key,
+            since,
+            group_by,
+        } => execute_report(key, since, group_by, db_path, format).await,
+    }
+}
+
+/// Execute `flm usage report`
+async fn execute_report(
+    key: Option<String>,
+    since: Option<String>,
+    group_by: String,
+    db_path: Option<String>,
+    format: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let group_by_name = group_by;
+    let group_by = match group_by_name.as_str() {
+        "model" => UsageGroupBy::Model,
+        "day" => UsageGroupBy::Day,
+        _ => UsageGroupBy::Key,
+    };
+    let since = since
+        .as_deref()
+        .map(|value| parse_since(value, Utc::now()))
+        .transpose()?;
+
+    let db_path = db_path
+        .map(PathBuf::from)
+        .unwrap_or_else(get_security_db_path);
+    let repo = SqliteSecurityRepository::new(&db_path).await?;
+    let rows = repo
+        .usage_report(since.as_deref(), key.as_deref(), group_by)
+        .await?;
+
+    // Labels of revoked keys are kept so past usage stays attributable
+    let labels: HashMap<String, String> = SecurityService::new(repo)
+        .list_api_keys()
+        .await?
+        .into_iter()
+        .map(|key| (key.id, key.label))
+        .collect();
+    let label = |row: &UsageReportRow| labels.get(&row.api_key_id).cloned().unwrap_or_default();
+    let group_name = match group_by {
+        UsageGroupBy::Key => None,
+        UsageGroupBy::Model => Some("model_id"),
+        UsageGroupBy::Day => Some("day"),
+    };
+
+    match format.as_str() {
+        "json" => {
+            let rows: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|row| {
+                    let mut value = json!({
+                        "api_key_id": row.api_key_id,
+                        "label": label(row),
+                        "requests": row.requests,
+                        "prompt_tokens": row.prompt_tokens,
+                        "completion_tokens": row.completion_tokens,
+                        "total_tokens": row.total_tokens,
+                        "avg_latency_ms": row.avg_latency_ms,
+                        "estimated_requests": row.estimated_requests
+                    });
+                    if let Some(name) = group_name {
+                        value[name] = json!(row.group);
+                    }
+                    value
+                })
+                .collect();
+            let output = json!({
+                "version": "1.0",
+                "data": {
+                    "since": since,
+                    "group_by": group_by_name,
+                    "rows": rows,
+                    "total_tokens": rows_total(&rows, "total_tokens"),
+                    "requests": rows_total(&rows, "requests")
+                }
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        "csv" => {
+            let mut header = vec!["api_key_id", "label"];
+            header.extend(group_name);
+            header.extend([
+                "requests",
+                "prompt_tokens",
+                "completion_tokens",
+                "total_tokens",
+                "avg_latency_ms",
+                "estimated_requests",
+            ]);
+            println!("{}", header.join(","));
+            for row in &rows {
+                let mut fields = vec![csv_field(&row.api_key_id), csv_field(&label(row))];
+                if group_name.is_some() {
+                    fields.push(csv_field(row.group.as_deref().unwrap_or_default()));
+                }
+                fields.extend(
+                    [
+                        row.requests,
+                        row.prompt_tokens,
+                        row.completion_tokens,
+                        row.total_tokens,
+                        row.avg_latency_ms,
+                        row.estimated_requests,
+                    ]
+                    .map(|n| n.to_string()),
+                );
+                println!("{}", fields.join(","));
+            }
+        }
+        _ => {
+            if rows.is_empty() {
+                println!("No usage recorded");
+                return Ok(());
+            }
+            println!(
+                "Usage{}:",
+                since.map(|s| format!(" since {s}")).unwrap_or_default()
+            );
+            for row in &rows {
+                let label = label(row);
+                match &row.group {
+                    Some(group) => println!("  {} ({label}) {group}", row.api_key_id),
+                    None => println!("  {} ({label})", row.api_key_id),
+                }
+                println!("    Requests: {}", row.requests);
+                println!(
+                    "    Tokens: {} (prompt {}, completion {})",
+                    row.total_tokens, row.prompt_tokens, row.completion_tokens
+                );
+                println!("    Avg latency: {} ms", row.avg_latency_ms);
+                if row.estimated_requests > 0 {
+                    println!("    Estimated: {} requests", row.estimated_requests);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sum one numeric field over the JSON rows
+fn rows_total(rows: &[serde_json::Value], field: &str) -> i64 {
+    rows.iter().filter_map(|row| row[field].as_i64()).sum()
+}
+
+/// Parse `--since` into an RFC3339 UTC timestamp
+///
+/// Accepts a UTC day (`2026-10-01`), an RFC3339 timestamp, or an age in days
+/// or hours before `now` (`7d`, `12h`).
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<String, CliUserError> {
+    let invalid = || {
+        CliUserError::new(format!(
+            "Invalid --since '{value}': expected YYYY-MM-DD, an RFC3339 timestamp, or an age such as 7d or 12h"
+        ))
+    };
+
+    if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let start = day.and_hms_opt(0, 0, 0).ok_or_else(invalid)?;
+        return Ok(start.and_utc().to_rfc3339());
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc).to_rfc3339());
+    }
+    let age = if let Some(days) = value.strip_suffix('d') {
+        days.parse().ok().and_then(Duration::try_days)
+    } else if let Some(hours) = value.strip_suffix('h') {
+        hours.parse().ok().and_then(Duration::try_hours)
+    } else {
+        None
+    }
+    .filter(|age| *age >= Duration::zero())
+    .ok_or_else(invalid)?;
+    Ok((now - age).to_rfc3339())
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
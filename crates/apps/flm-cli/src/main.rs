@@ -32,6 +32,22 @@ async fn main() {
             commands::models::execute(subcommand.clone(), cli.db_path_config, cli.format.clone())
                 .await
         }
+        Commands::ModelGroups { subcommand } => {
+            commands::model_groups::execute(
+                subcommand.clone(),
+                cli.db_path_config,
+                cli.format.clone(),
+            )
+            .await
+        }
+        Commands::ConcurrencyLimits { subcommand } => {
+            commands::concurrency_limits::execute(
+                subcommand.clone(),
+                cli.db_path_config,
+                cli.format.clone(),
+            )
+            .await
+        }
         Commands::ModelProfiles { subcommand } => {
             commands::model_profiles::execute(
                 subcommand.clone(),
@@ -53,10 +69,15 @@ async fn main() {
             commands::security::execute(
                 subcommand.clone(),
                 cli.db_path_security,
+                cli.db_path_config,
                 cli.format.clone(),
             )
             .await
         }
+        Commands::Usage { subcommand } => {
+            commands::usage::execute(subcommand.clone(), cli.db_path_security, cli.format.clone())
+                .await
+        }
         Commands::Secrets { subcommand } => {
             commands::secrets::execute(subcommand.clone(), cli.db_path_security, cli.format.clone())
                 .await
@@ -4,6 +4,6 @@ pub mod paths;
 pub mod secrets;
 
 pub use paths::{
-    get_app_data_dir, get_config_db_path, get_daemon_state_path, get_runtime_dir,
+    get_app_data_dir, get_config_db_path, get_daemon_state_path, get_models_dir, get_runtime_dir,
     get_security_db_path,
 };
@@ -122,6 +122,14 @@ pub fn get_runtime_dir() -> PathBuf {
     runtime_dir
 }
 
+/// Directory of GGUF model files managed by `flm models pull`.
+pub fn get_models_dir() -> PathBuf {
+    match get_app_data_dir() {
+        Ok(dir) => dir.join("models"),
+        Err(_) => PathBuf::from("models"),
+    }
+}
+
 /// Location of the proxy daemon state file.
 pub fn get_daemon_state_path() -> PathBuf {
     get_runtime_dir().join("proxy-daemon.json")
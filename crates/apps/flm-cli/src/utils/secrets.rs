@@ -1,6 +1,13 @@
 use keyring::Entry;
 
 pub const DNS_KEYRING_SERVICE: &str = "flm.dns.credentials";
+pub const ENGINE_KEYRING_SERVICE: &str = "flm.engine.credentials";
+pub const ENGINE_HEADER_KEYRING_SERVICE: &str = "flm.engine.auth_headers";
+pub const BACKUP_KEYRING_SERVICE: &str = "flm.backup.key";
+const BACKUP_KEYRING_ACCOUNT: &str = "default";
+pub const AUDIT_HMAC_KEYRING_SERVICE: &str = "flm.audit.hmac";
+const AUDIT_HMAC_KEYRING_ACCOUNT: &str = "default";
+pub const AUDIT_SINK_KEYRING_SERVICE: &str = "flm.audit.sinks";
 
 pub fn keyring_disabled() -> bool {
     matches!(
@@ -15,23 +22,113 @@ pub fn store_dns_token(profile_id: &str, token: &str) -> Result<(), keyring::Err
     if keyring_disabled() {
         return Ok(());
     }
-    keyring_entry(profile_id)?.set_password(token)
+    keyring_entry(DNS_KEYRING_SERVICE, profile_id)?.set_password(token)
 }
 
 pub fn load_dns_token(profile_id: &str) -> Result<String, keyring::Error> {
     if keyring_disabled() {
         return Err(keyring::Error::NoEntry);
     }
-    keyring_entry(profile_id)?.get_password()
+    keyring_entry(DNS_KEYRING_SERVICE, profile_id)?.get_password()
 }
 
 pub fn delete_dns_token(profile_id: &str) -> Result<(), keyring::Error> {
     if keyring_disabled() {
         return Ok(());
     }
-    keyring_entry(profile_id)?.delete_password()
+    keyring_entry(DNS_KEYRING_SERVICE, profile_id)?.delete_password()
 }
 
-fn keyring_entry(profile_id: &str) -> Result<Entry, keyring::Error> {
-    Entry::new(DNS_KEYRING_SERVICE, profile_id)
+pub fn store_engine_token(engine_id: &str, token: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(ENGINE_KEYRING_SERVICE, engine_id)?.set_password(token)
+}
+
+pub fn load_engine_token(engine_id: &str) -> Result<String, keyring::Error> {
+    if keyring_disabled() {
+        return Err(keyring::Error::NoEntry);
+    }
+    keyring_entry(ENGINE_KEYRING_SERVICE, engine_id)?.get_password()
+}
+
+pub fn delete_engine_token(engine_id: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(ENGINE_KEYRING_SERVICE, engine_id)?.delete_password()
+}
+
+pub fn store_engine_auth_header(engine_id: &str, value: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(ENGINE_HEADER_KEYRING_SERVICE, engine_id)?.set_password(value)
+}
+
+pub fn load_engine_auth_header(engine_id: &str) -> Result<String, keyring::Error> {
+    if keyring_disabled() {
+        return Err(keyring::Error::NoEntry);
+    }
+    keyring_entry(ENGINE_HEADER_KEYRING_SERVICE, engine_id)?.get_password()
+}
+
+pub fn delete_engine_auth_header(engine_id: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(ENGINE_HEADER_KEYRING_SERVICE, engine_id)?.delete_password()
+}
+
+pub fn store_audit_sink_token(sink_id: &str, token: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(AUDIT_SINK_KEYRING_SERVICE, sink_id)?.set_password(token)
+}
+
+pub fn load_audit_sink_token(sink_id: &str) -> Result<String, keyring::Error> {
+    if keyring_disabled() {
+        return Err(keyring::Error::NoEntry);
+    }
+    keyring_entry(AUDIT_SINK_KEYRING_SERVICE, sink_id)?.get_password()
+}
+
+pub fn delete_audit_sink_token(sink_id: &str) -> Result<(), keyring::Error> {
+    if keyring_disabled() {
+        return Ok(());
+    }
+    keyring_entry(AUDIT_SINK_KEYRING_SERVICE, sink_id)?.delete_password()
+}
+
+/// Backup keys cannot be skipped like other secrets: a backup sealed with a
+/// key that was never stored could not be opened, so callers check
+/// `keyring_disabled()` before generating one.
+pub fn store_backup_key(key: &str) -> Result<(), keyring::Error> {
+    keyring_entry(BACKUP_KEYRING_SERVICE, BACKUP_KEYRING_ACCOUNT)?.set_password(key)
+}
+
+pub fn load_backup_key() -> Result<String, keyring::Error> {
+    if keyring_disabled() {
+        return Err(keyring::Error::NoEntry);
+    }
+    keyring_entry(BACKUP_KEYRING_SERVICE, BACKUP_KEYRING_ACCOUNT)?.get_password()
+}
+
+/// Like backup keys, the audit HMAC key is never skipped: entries signed
+/// with a key that was not stored could not be verified.
+pub fn store_audit_hmac_key(key: &str) -> Result<(), keyring::Error> {
+    keyring_entry(AUDIT_HMAC_KEYRING_SERVICE, AUDIT_HMAC_KEYRING_ACCOUNT)?.set_password(key)
+}
+
+pub fn load_audit_hmac_key() -> Result<String, keyring::Error> {
+    if keyring_disabled() {
+        return Err(keyring::Error::NoEntry);
+    }
+    keyring_entry(AUDIT_HMAC_KEYRING_SERVICE, AUDIT_HMAC_KEYRING_ACCOUNT)?.get_password()
+}
+
+fn keyring_entry(service: &str, id: &str) -> Result<Entry, keyring::Error> {
+    Entry::new(service, id)
 }
@@ -84,6 +84,7 @@ async fn test_api_keys_create_and_list() {
     // Create an API key
     let create_subcommand = ApiKeysSubcommand::Create {
         label: "test_key".to_string(),
+        scopes: Vec::new(),
     };
 
     let create_result = api_keys::execute(
@@ -123,6 +124,7 @@ async fn test_api_keys_text_format() {
     // Create an API key with text format
     let create_subcommand = ApiKeysSubcommand::Create {
         label: "test_key2".to_string(),
+        scopes: Vec::new(),
     };
 
     let _ = api_keys::execute(
@@ -148,3 +150,137 @@ async fn test_api_keys_text_format() {
         result.err()
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_api_keys_scopes_create_update_and_rotate() {
+    use flm_cli::adapters::SqliteSecurityRepository;
+    use flm_core::domain::security::EndpointScope;
+    use flm_core::services::SecurityService;
+
+    let (_temp_dir, security_db) = create_temp_db();
+    let db_path = Some(security_db.to_str().unwrap().to_string());
+
+    let result = api_keys::execute(
+        ApiKeysSubcommand::Create {
+            label: "demo".to_string(),
+            scopes: vec![
+                "endpoints=chat,models".to_string(),
+                "models=flm://ollama-default/llama3:8b*".to_string(),
+                "models=flm://lmstudio/*".to_string(),
+                "ips=10.0.0.0/8".to_string(),
+                "rpm=30".to_string(),
+                "daily_tokens=100000".to_string(),
+            ],
+        },
+        db_path.clone(),
+        "text".to_string(),
+    )
+    .await;
+    assert!(result.is_ok(), "scoped create should succeed: {result:?}");
+
+    let service = SecurityService::new(SqliteSecurityRepository::new(&security_db).await.unwrap());
+    let keys = service.list_api_keys().await.unwrap();
+    assert_eq!(keys.len(), 1);
+    let scopes = &keys[0].scopes;
+    assert_eq!(
+        scopes.endpoints,
+        vec![EndpointScope::Chat, EndpointScope::Models]
+    );
+    assert_eq!(
+        scopes.models,
+        vec!["flm://ollama-default/llama3:8b*", "flm://lmstudio/*"]
+    );
+    assert_eq!(scopes.ip_allowlist, vec!["10.0.0.0/8"]);
+    assert_eq!(scopes.rate_limit.rpm, Some(30));
+    assert_eq!(scopes.rate_limit.daily_tokens, Some(100000));
+    assert!(!scopes.allows_model("flm://ollama-default/llama3:70b"));
+    let id = keys[0].id.clone();
+
+    // Rotation keeps the scopes
+    let rotated = service.rotate_api_key(&id, None).await.unwrap();
+    assert_eq!(&rotated.record.scopes, scopes);
+
+    // `scope` replaces them; without --scope the key becomes unrestricted
+    let result = api_keys::execute(
+        ApiKeysSubcommand::Scope {
+            id: rotated.record.id.clone(),
+            scopes: vec!["endpoints=embeddings".to_string()],
+        },
+        db_path.clone(),
+        "json".to_string(),
+    )
+    .await;
+    assert!(result.is_ok(), "scope update should succeed: {result:?}");
+    let record = service
+        .verify_api_key(&rotated.plain)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.scopes.endpoints, vec![EndpointScope::Embeddings]);
+    assert!(record.scopes.models.is_empty());
+
+    let result = api_keys::execute(
+        ApiKeysSubcommand::Scope {
+            id: rotated.record.id.clone(),
+            scopes: Vec::new(),
+        },
+        db_path.clone(),
+        "text".to_string(),
+    )
+    .await;
+    assert!(result.is_ok(), "clearing scopes should succeed: {result:?}");
+    let record = service
+        .verify_api_key(&rotated.plain)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(record.scopes.is_unrestricted());
+
+    // The revoked key cannot be re-scoped
+    let result = api_keys::execute(
+        ApiKeysSubcommand::Scope {
+            id,
+            scopes: vec!["rpm=1".to_string()],
+        },
+        db_path,
+        "json".to_string(),
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_api_keys_create_rejects_invalid_scopes() {
+    let (_temp_dir, security_db) = create_temp_db();
+
+    for scope in [
+        "endpoints=chat,files",
+        "models=llama3*",
+        "ips=10.0.0.0/40",
+        "rpm=0",
+        "rpm=many",
+        "color=blue",
+        "endpoints",
+    ] {
+        let result = api_keys::execute(
+            ApiKeysSubcommand::Create {
+                label: "invalid".to_string(),
+                scopes: vec![scope.to_string()],
+            },
+            Some(security_db.to_str().unwrap().to_string()),
+            "json".to_string(),
+        )
+        .await;
+        assert!(result.is_err(), "--scope {scope} should be rejected");
+    }
+
+    // Nothing was created
+    let repo = flm_cli::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    let keys = flm_core::services::SecurityService::new(repo)
+        .list_api_keys()
+        .await
+        .unwrap();
+    assert!(keys.is_empty());
+}
@@ -0,0 +1,252 @@
+//! Tests for `flm security audit-logs sinks` and `flm security audit-logs export`
+
+use flm_cli::adapters::SqliteAuditSinkRepository;
+use flm_cli::cli::security::{
+    AuditLogsSubcommand, AuditSinkAddArgs, AuditSinksSubcommand, SecuritySubcommand,
+};
+use flm_cli::commands::security;
+use flm_core::domain::audit::{AuditSinkConfig, SyslogTransport};
+use flm_core::ports::AuditSinkRepository;
+use flm_proxy::adapters::AuditLogMetadata;
+use std::path::Path;
+
+fn audit_logs(subcommand: AuditLogsSubcommand) -> SecuritySubcommand {
+    SecuritySubcommand::AuditLogs {
+        event_type: None,
+        severity: None,
+        ip: None,
+        limit: 100,
+        offset: 0,
+        subcommand: Some(subcommand),
+    }
+}
+
+fn add_args(id: &str, kind: &str) -> Box<AuditSinkAddArgs> {
+    Box::new(AuditSinkAddArgs {
+        id: id.to_string(),
+        kind: kind.to_string(),
+        address: None,
+        transport: None,
+        facility: None,
+        app_name: None,
+        path: None,
+        max_bytes: None,
+        max_files: None,
+        url: None,
+        batch_size: None,
+        flush_interval_secs: None,
+        max_retries: None,
+        auth_scheme: None,
+        bearer_token: None,
+        bearer_token_stdin: false,
+    })
+}
+
+async fn sinks(
+    subcommand: AuditSinksSubcommand,
+    config_db: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    security::execute(
+        audit_logs(AuditLogsSubcommand::Sinks { subcommand }),
+        None,
+        Some(config_db.to_str().unwrap().to_string()),
+        "json".to_string(),
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_audit_sinks_add_list_remove() {
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config_db = temp_dir.path().join("config.db");
+
+    let mut syslog = add_args("siem", "syslog");
+    syslog.address = Some("siem.example.com:6514".to_string());
+    syslog.transport = Some("tcp".to_string());
+    sinks(AuditSinksSubcommand::Add(syslog), &config_db)
+        .await
+        .unwrap();
+
+    let mut jsonl = add_args("archive", "jsonl");
+    jsonl.path = Some(temp_dir.path().join("audit.jsonl").display().to_string());
+    jsonl.max_files = Some(3);
+    sinks(AuditSinksSubcommand::Add(jsonl), &config_db)
+        .await
+        .unwrap();
+
+    let mut webhook = add_args("hec", "webhook");
+    webhook.url = Some("https://hec.example.com/services/collector".to_string());
+    webhook.batch_size = Some(50);
+    webhook.auth_scheme = Some("Splunk".to_string());
+    sinks(AuditSinksSubcommand::Add(webhook), &config_db)
+        .await
+        .unwrap();
+
+    // Invalid settings are rejected before anything is stored
+    let mut missing_address = add_args("bad", "syslog");
+    missing_address.transport = Some("udp".to_string());
+    let mut bad_transport = add_args("bad", "syslog");
+    bad_transport.address = Some("127.0.0.1:514".to_string());
+    bad_transport.transport = Some("tls".to_string());
+    let mut bad_url = add_args("bad", "webhook");
+    bad_url.url = Some("ftp://example.com".to_string());
+    let mut token_on_jsonl = add_args("bad", "jsonl");
+    token_on_jsonl.path = Some("audit.jsonl".to_string());
+    token_on_jsonl.bearer_token = Some("secret".to_string());
+    for args in [
+        add_args("bad", "kafka"),
+        missing_address,
+        bad_transport,
+        bad_url,
+        token_on_jsonl,
+    ] {
+        assert!(sinks(AuditSinksSubcommand::Add(args), &config_db)
+            .await
+            .is_err());
+    }
+
+    let repo = SqliteAuditSinkRepository::new(&config_db).await.unwrap();
+    let listed = repo.list_sinks().await.unwrap();
+    let ids: Vec<&str> = listed.iter().map(|sink| sink.id.as_str()).collect();
+    assert_eq!(ids, vec!["archive", "hec", "siem"]);
+    assert_eq!(
+        listed[2].config,
+        AuditSinkConfig::Syslog {
+            address: "siem.example.com:6514".to_string(),
+            transport: SyslogTransport::Tcp,
+            facility: 13,
+            app_name: None,
+        }
+    );
+    assert!(matches!(
+        &listed[1].config,
+        AuditSinkConfig::Webhook { batch_size: 50, flush_interval_secs: 5, auth_scheme: Some(scheme), .. } if scheme == "Splunk"
+    ));
+
+    // The proxy reads the same rows
+    {
+        use flm_core::ports::AuditSinkRepository;
+        let repo = flm_core::adapters::SqliteAuditSinkRepository::new(&config_db)
+            .await
+            .unwrap();
+        assert_eq!(repo.list_sinks().await.unwrap(), listed);
+    }
+
+    // Adding an existing ID replaces it
+    let mut replaced = add_args("siem", "syslog");
+    replaced.address = Some("10.0.0.5:514".to_string());
+    replaced.facility = Some(4);
+    sinks(AuditSinksSubcommand::Add(replaced), &config_db)
+        .await
+        .unwrap();
+    assert!(matches!(
+        repo.find_sink("siem").await.unwrap().unwrap().config,
+        AuditSinkConfig::Syslog {
+            facility: 4,
+            transport: SyslogTransport::Udp,
+            ..
+        }
+    ));
+
+    sinks(AuditSinksSubcommand::List, &config_db).await.unwrap();
+    sinks(
+        AuditSinksSubcommand::Remove {
+            id: "siem".to_string(),
+        },
+        &config_db,
+    )
+    .await
+    .unwrap();
+    assert!(sinks(
+        AuditSinksSubcommand::Remove {
+            id: "siem".to_string(),
+        },
+        &config_db,
+    )
+    .await
+    .is_err());
+    assert_eq!(repo.list_sinks().await.unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_audit_logs_export_jsonl_and_csv() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let security_db = temp_dir.path().join("security.db");
+
+    let proxy_repo = flm_proxy::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    for (index, event_type) in ["auth_failure", "ip_blocked", "auth_success"]
+        .into_iter()
+        .enumerate()
+    {
+        proxy_repo
+            .save_audit_log(
+                &format!("req-{index}"),
+                None,
+                "/v1/chat/completions",
+                if index == 2 { 200 } else { 403 },
+                Some(5),
+                Some(event_type),
+                AuditLogMetadata {
+                    severity: "high",
+                    ip: Some("203.0.113.7"),
+                    details: Some("{\"reason\":\"a, \\\"quoted\\\" value\"}"),
+                },
+            )
+            .await
+            .unwrap();
+    }
+    // The first entry is older than the export period
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE audit_logs SET created_at = '2020-01-01 00:00:00' WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let export = |format: &str, since: Option<&str>, output: &Path| {
+        security::execute(
+            audit_logs(AuditLogsSubcommand::Export {
+                since: since.map(str::to_string),
+                output: Some(output.to_str().unwrap().to_string()),
+            }),
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            format.to_string(),
+        )
+    };
+
+    let jsonl = temp_dir.path().join("audit.jsonl");
+    export("jsonl", Some("30d"), &jsonl).await.unwrap();
+    let events: Vec<serde_json::Value> = std::fs::read_to_string(&jsonl)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["id"], 2);
+    assert_eq!(events[0]["event_type"], "ip_blocked");
+    assert_eq!(events[0]["hash_alg"], "sha256");
+    assert_eq!(events[1]["prev_hash"], events[0]["entry_hash"]);
+
+    let csv = temp_dir.path().join("audit.csv");
+    export("csv", None, &csv).await.unwrap();
+    let contents = std::fs::read_to_string(&csv).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("id,created_at,request_id,"));
+    assert!(lines[1].starts_with(
+        "1,2020-01-01 00:00:00,req-0,,/v1/chat/completions,403,5,auth_failure,high,203.0.113.7,"
+    ));
+    assert!(lines[1].contains(",\"{\"\"reason\"\":\"\"a, \\\"\"quoted\\\"\" value\"\"}\","));
+    assert!(lines[3].ends_with(&format!(
+        ",{},sha256",
+        events[1]["entry_hash"].as_str().unwrap()
+    )));
+
+    assert!(export("text", None, &csv).await.is_err());
+    assert!(export("csv", Some("yesterday"), &csv).await.is_err());
+}
@@ -39,6 +39,7 @@ impl LlmEngine for MockEngine {
             vision_inputs: true,
             audio_inputs: true,
             audio_outputs: false,
+            image_generation: false,
             max_image_bytes: Some(8 * 1024 * 1024),
             max_audio_bytes: Some(25 * 1024 * 1024),
         }
@@ -123,8 +124,11 @@ impl LlmEngine for MockEngine {
                 role: flm_core::domain::chat::ChatRole::Assistant,
                 content: response_content,
                 attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
             audio: Vec::new(),
+            logprobs: Vec::new(),
         })
     }
 
@@ -139,16 +143,22 @@ impl LlmEngine for MockEngine {
                     role: flm_core::domain::chat::ChatRole::Assistant,
                     content: content.clone(),
                     attachments: Vec::new(),
+                    tool_calls: Vec::new(),
+                    tool_call_id: None,
                 },
                 usage: None,
                 is_done: false,
                 audio: Vec::new(),
+                tool_calls: Vec::new(),
+                logprobs: Vec::new(),
             }),
             Ok(ChatStreamChunk {
                 delta: ChatMessage {
                     role: flm_core::domain::chat::ChatRole::Assistant,
                     content: String::new(),
                     attachments: Vec::new(),
+                    tool_calls: Vec::new(),
+                    tool_call_id: None,
                 },
                 usage: Some(UsageStats {
                     prompt_tokens: 10,
@@ -157,6 +167,8 @@ impl LlmEngine for MockEngine {
                 }),
                 is_done: true,
                 audio: Vec::new(),
+                tool_calls: Vec::new(),
+                logprobs: Vec::new(),
             }),
         ];
         Ok(Box::pin(stream::iter(chunks)))
@@ -313,12 +325,18 @@ async fn test_chat_with_mock_engine() {
             role: flm_core::domain::chat::ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: Vec::new(),
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: flm_core::domain::chat::SamplingParams::default(),
     };
 
     // Execute chat
@@ -439,12 +457,18 @@ async fn test_chat_with_image_attachment() {
                 detail: None,
                 duration_ms: None,
             }],
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: Vec::new(),
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: flm_core::domain::chat::SamplingParams::default(),
     };
 
     // Execute chat
@@ -538,6 +562,7 @@ impl LlmEngine for MockEngineNoVision {
             vision_inputs: false, // No vision support
             audio_inputs: false,
             audio_outputs: false,
+            image_generation: false,
             max_image_bytes: None,
             max_audio_bytes: None,
         }
@@ -575,8 +600,11 @@ impl LlmEngine for MockEngineNoVision {
                 role: flm_core::domain::chat::ChatRole::Assistant,
                 content: "Mock response".to_string(),
                 attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
             audio: Vec::new(),
+            logprobs: Vec::new(),
         })
     }
 
@@ -590,16 +618,22 @@ impl LlmEngine for MockEngineNoVision {
                     role: flm_core::domain::chat::ChatRole::Assistant,
                     content: "Mock stream".to_string(),
                     attachments: Vec::new(),
+                    tool_calls: Vec::new(),
+                    tool_call_id: None,
                 },
                 usage: None,
                 is_done: false,
                 audio: Vec::new(),
+                tool_calls: Vec::new(),
+                logprobs: Vec::new(),
             }),
             Ok(ChatStreamChunk {
                 delta: ChatMessage {
                     role: flm_core::domain::chat::ChatRole::Assistant,
                     content: String::new(),
                     attachments: Vec::new(),
+                    tool_calls: Vec::new(),
+                    tool_call_id: None,
                 },
                 usage: Some(UsageStats {
                     prompt_tokens: 10,
@@ -608,6 +642,8 @@ impl LlmEngine for MockEngineNoVision {
                 }),
                 is_done: true,
                 audio: Vec::new(),
+                tool_calls: Vec::new(),
+                logprobs: Vec::new(),
             }),
         ];
         Ok(Box::pin(stream::iter(chunks)))
@@ -78,3 +78,44 @@ async fn test_check_nonexistent_databases() {
     // Should succeed but with warnings
     assert!(result.is_ok());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_check_warns_about_tables_above_retention() {
+    let (_temp_dir, config_db, security_db) = create_temp_db_dir();
+    let _config_repo = SqliteConfigRepository::new(&config_db).await.unwrap();
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = SecurityService::new(security_repo);
+    security_service
+        .set_policy(flm_core::domain::security::SecurityPolicy {
+            id: "default".to_string(),
+            policy_json: r#"{"retention":{"intrusion_attempts":{"max_rows":1}}}"#.to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    for id in ["attempt-1", "attempt-2"] {
+        sqlx::query(
+            "INSERT INTO intrusion_attempts (id, ip, pattern, score) VALUES (?, '203.0.113.7', 'sqli', 50)",
+        )
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    // Warnings do not fail the check
+    let result = flm_cli::commands::check::execute(
+        true,
+        Some(config_db.to_str().unwrap().to_string()),
+        Some(security_db.to_str().unwrap().to_string()),
+        "text".to_string(),
+    )
+    .await;
+
+    assert!(result.is_ok());
+}
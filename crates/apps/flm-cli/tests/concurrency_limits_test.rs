@@ -0,0 +1,116 @@
+//! Tests for `flm concurrency-limits` command
+
+use flm_cli::cli::concurrency_limits::{ConcurrencyLimitSetArgs, ConcurrencyLimitsSubcommand};
+use flm_cli::commands::concurrency_limits;
+use flm_core::adapters::SqliteConcurrencyLimitRepository;
+use flm_core::ports::ConcurrencyLimitRepository;
+use tempfile::TempDir;
+
+fn create_temp_db() -> (TempDir, String) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("config.db");
+    (temp_dir, db_path.to_str().unwrap().to_string())
+}
+
+fn set_args(target: &str, max_concurrent: u32, max_queue: u32) -> ConcurrencyLimitSetArgs {
+    ConcurrencyLimitSetArgs {
+        target: target.to_string(),
+        max_concurrent,
+        max_queue,
+        queue_timeout_secs: 30,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrency_limits_set_replace_and_delete() {
+    let (_temp_dir, db_path) = create_temp_db();
+
+    for args in [
+        set_args("ollama-default", 2, 8),
+        set_args("flm://ollama-default/llama3", 1, 4),
+        // Setting again replaces the engine limit
+        set_args("ollama-default", 4, 16),
+    ] {
+        concurrency_limits::execute(
+            ConcurrencyLimitsSubcommand::Set(args),
+            Some(db_path.clone()),
+            "json".to_string(),
+        )
+        .await
+        .expect("set command works");
+    }
+
+    let repo = SqliteConcurrencyLimitRepository::new(&db_path)
+        .await
+        .expect("open repository");
+    let limits = repo.list_limits().await.expect("list limits");
+    assert_eq!(limits.len(), 2);
+    assert_eq!(limits[0].target, "flm://ollama-default/llama3");
+    assert!(limits[0].is_model_limit());
+    assert_eq!(limits[1].target, "ollama-default");
+    assert!(!limits[1].is_model_limit());
+    assert_eq!(limits[1].max_concurrent, 4);
+    assert_eq!(limits[1].max_queue, 16);
+
+    assert_eq!(
+        repo.find_limit("flm://ollama-default/llama3")
+            .await
+            .expect("find limit")
+            .map(|limit| limit.max_queue),
+        Some(4)
+    );
+
+    concurrency_limits::execute(
+        ConcurrencyLimitsSubcommand::Delete {
+            target: "ollama-default".to_string(),
+        },
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("delete limit");
+
+    let remaining = repo.list_limits().await.expect("list remaining");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].target, "flm://ollama-default/llama3");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrency_limits_rejects_invalid_input() {
+    let (_temp_dir, db_path) = create_temp_db();
+
+    let mut long_timeout = set_args("ollama-default", 1, 4);
+    long_timeout.queue_timeout_secs = 7200;
+    let cases = [
+        set_args("ollama-default", 0, 4),
+        set_args("flm://group/llama3", 1, 4),
+        set_args("flm://ollama-default", 1, 4),
+        set_args("bad engine", 1, 4),
+        long_timeout,
+    ];
+
+    for args in cases {
+        let result = concurrency_limits::execute(
+            ConcurrencyLimitsSubcommand::Set(args),
+            Some(db_path.clone()),
+            "json".to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    let repo = SqliteConcurrencyLimitRepository::new(&db_path)
+        .await
+        .expect("open repository");
+    assert!(repo.list_limits().await.expect("list").is_empty());
+
+    let result = concurrency_limits::execute(
+        ConcurrencyLimitsSubcommand::Delete {
+            target: "missing".to_string(),
+        },
+        Some(db_path),
+        "json".to_string(),
+    )
+    .await;
+    assert!(result.unwrap_err().to_string().contains("not found"));
+}
@@ -0,0 +1,285 @@
+//! Tests for `flm engines start/stop/restart/logs` and the engine supervisor
+
+#![cfg(unix)]
+
+use flm_cli::adapters::engine_supervisor::{run_supervisor, EngineRuntimeFiles, SupervisorSpec};
+use flm_cli::adapters::SqliteEngineProcessConfigRepository;
+use flm_cli::cli::engines::{EngineStartArgs, EnginesSubcommand};
+use flm_cli::commands::engines;
+use flm_core::domain::engine::EngineProcessState;
+use flm_core::ports::EngineProcessConfigRepository;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+fn shell_spec(engine_id: &str, script: &str, max_restarts: u32) -> SupervisorSpec {
+    SupervisorSpec {
+        engine_id: engine_id.to_string(),
+        program: "sh".to_string(),
+        args: vec!["-c".to_string(), script.to_string()],
+        env: BTreeMap::new(),
+        restart_on_crash: true,
+        max_restarts,
+    }
+}
+
+fn start_args(id: &str, kind: &str) -> EngineStartArgs {
+    EngineStartArgs {
+        id: id.to_string(),
+        kind: Some(kind.to_string()),
+        model: None,
+        binary: None,
+        host: None,
+        port: None,
+        args: Vec::new(),
+        env: Vec::new(),
+        no_restart: false,
+        max_restarts: None,
+    }
+}
+
+fn wait_for_state(files: &EngineRuntimeFiles, state: EngineProcessState) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if files.read_status().is_some_and(|s| s.state == state) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    panic!("engine did not reach {state:?}: {:?}", files.read_status());
+}
+
+fn run_flm(data_dir: &Path, config_db: &Path, args: &[&str]) -> (bool, Value, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_flm"))
+        .args(args)
+        .args([
+            "--db-path-config",
+            config_db.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .env("FLM_DATA_DIR", data_dir)
+        .output()
+        .expect("run flm");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let json = serde_json::from_str(&stdout).unwrap_or(Value::Null);
+    (output.status.success(), json, stderr)
+}
+
+/// A stand-in for llama-server that logs its arguments and stays up
+fn write_fake_engine(dir: &Path) -> PathBuf {
+    let path = dir.join("fake-llama-server");
+    std::fs::write(&path, "#!/bin/sh\necho \"fake llama $@\"\nexec sleep 60\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn test_supervisor_restarts_crashed_engine_then_gives_up() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let files = EngineRuntimeFiles::new(temp_dir.path(), "crashy");
+    files
+        .write_spec(&shell_spec("crashy", "echo boom; exit 3", 1))
+        .unwrap();
+
+    run_supervisor(&files).expect("supervisor exits cleanly");
+
+    let status = files.read_status().expect("status written");
+    assert_eq!(status.state, EngineProcessState::Failed);
+    assert_eq!(status.restarts, 1);
+    assert_eq!(status.last_exit_code, Some(3));
+    assert!(status.reason.unwrap().contains("giving up"));
+    let log = std::fs::read_to_string(files.log_path()).unwrap();
+    assert_eq!(log.lines().filter(|line| *line == "boom").count(), 2);
+    assert!(log.contains("engine exited with exit code 3"));
+}
+
+#[test]
+fn test_supervisor_stops_engine_on_request() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let files = EngineRuntimeFiles::new(temp_dir.path(), "sleepy");
+    files
+        .write_spec(&shell_spec("sleepy", "exec sleep 30", 5))
+        .unwrap();
+
+    let supervisor_files = files.clone();
+    let supervisor = std::thread::spawn(move || run_supervisor(&supervisor_files));
+    wait_for_state(&files, EngineProcessState::Running);
+    assert!(files.read_status().unwrap().pid.is_some());
+
+    std::fs::write(files.stop_path(), b"").unwrap();
+    supervisor
+        .join()
+        .unwrap()
+        .expect("supervisor exits cleanly");
+
+    let status = files.read_status().unwrap();
+    assert_eq!(status.state, EngineProcessState::Stopped);
+    assert_eq!(status.restarts, 0);
+    assert!(status.pid.is_none());
+    assert!(!files.stop_path().exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engines_start_rejects_invalid_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("config.db");
+
+    let invalid = [
+        EngineStartArgs {
+            kind: None,
+            ..start_args("local", "ollama")
+        },
+        start_args("local", "lmstudio"),
+        start_args("local", "vllm"),
+        EngineStartArgs {
+            model: Some("llama3".to_string()),
+            ..start_args("local", "ollama")
+        },
+        EngineStartArgs {
+            model: Some("/nonexistent/model.gguf".to_string()),
+            ..start_args("local", "llamacpp")
+        },
+        EngineStartArgs {
+            env: vec!["NOVALUE".to_string()],
+            ..start_args("local", "ollama")
+        },
+        EngineStartArgs {
+            port: Some(0),
+            ..start_args("local", "ollama")
+        },
+    ];
+    for args in invalid {
+        let result = engines::execute(
+            EnginesSubcommand::Start(args),
+            Some(db_path.to_str().unwrap().to_string()),
+            "json".to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    let processes = SqliteEngineProcessConfigRepository::new(&db_path)
+        .await
+        .unwrap();
+    assert!(processes.list().await.unwrap().is_empty());
+}
+
+#[test]
+fn test_engines_start_logs_and_stop() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let data_dir = temp_dir.path().join("data");
+    let config_db = temp_dir.path().join("config.db");
+    let binary = write_fake_engine(temp_dir.path());
+    let model = temp_dir.path().join("tiny.gguf");
+    std::fs::write(&model, b"GGUF").unwrap();
+
+    let (ok, json, stderr) = run_flm(
+        &data_dir,
+        &config_db,
+        &[
+            "engines",
+            "start",
+            "--id",
+            "local-llama",
+            "--kind",
+            "llamacpp",
+            "--binary",
+            binary.to_str().unwrap(),
+            "--model",
+            model.to_str().unwrap(),
+            "--port",
+            "18090",
+            "--arg",
+            "--ctx-size",
+            "--arg",
+            "4096",
+        ],
+    );
+    assert!(ok, "start failed: {stderr}");
+    assert_eq!(json["data"]["base_url"], "http://127.0.0.1:18090");
+    assert_eq!(json["data"]["process"]["state"], "running");
+
+    // Starting again while running is rejected
+    let (ok, _, _) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "start", "--id", "local-llama"],
+    );
+    assert!(!ok);
+
+    // The engine is registered and reports its process state
+    let (ok, json, stderr) = run_flm(&data_dir, &config_db, &["engines", "list"]);
+    assert!(ok, "list failed: {stderr}");
+    let engine = &json["data"]["engines"][0];
+    assert_eq!(engine["id"], "local-llama");
+    assert_eq!(engine["base_url"], "http://127.0.0.1:18090");
+    assert_eq!(engine["process"]["state"], "running");
+
+    let log_path = data_dir
+        .join("run")
+        .join("engines")
+        .join("local-llama")
+        .join("engine.log");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !std::fs::read_to_string(&log_path)
+        .unwrap_or_default()
+        .contains("fake llama")
+        && Instant::now() < deadline
+    {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_flm"))
+        .args(["engines", "logs", "--id", "local-llama", "--lines", "20"])
+        .env("FLM_DATA_DIR", &data_dir)
+        .output()
+        .unwrap();
+    let logs = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        logs.contains("--host 127.0.0.1 --port 18090 --ctx-size 4096"),
+        "unexpected log: {logs}"
+    );
+
+    // Running engines cannot be removed
+    let (ok, _, _) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "remove", "--id", "local-llama"],
+    );
+    assert!(!ok);
+
+    let (ok, json, stderr) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "restart", "--id", "local-llama"],
+    );
+    assert!(ok, "restart failed: {stderr}");
+    assert_eq!(json["data"]["process"]["state"], "running");
+
+    let (ok, json, stderr) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "stop", "--id", "local-llama"],
+    );
+    assert!(ok, "stop failed: {stderr}");
+    assert_eq!(json["data"]["process"]["state"], "stopped");
+
+    let (ok, _, _) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "stop", "--id", "local-llama"],
+    );
+    assert!(!ok);
+
+    let (ok, _, stderr) = run_flm(
+        &data_dir,
+        &config_db,
+        &["engines", "remove", "--id", "local-llama"],
+    );
+    assert!(ok, "remove failed: {stderr}");
+    assert!(!log_path.exists());
+}
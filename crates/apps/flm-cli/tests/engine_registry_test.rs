@@ -0,0 +1,314 @@
+//! Tests for `flm engines add/remove/list`
+
+use flm_cli::adapters::SqliteEngineRegistryRepository;
+use flm_cli::cli::engines::{EngineAddArgs, EnginesSubcommand};
+use flm_cli::commands::engines;
+use flm_core::domain::models::EngineKind;
+use flm_core::ports::EngineRegistryRepository;
+use tempfile::TempDir;
+
+fn create_temp_db() -> (TempDir, String) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("config.db");
+    (temp_dir, db_path.to_str().unwrap().to_string())
+}
+
+fn add_args(id: &str, kind: &str, base_url: &str) -> EngineAddArgs {
+    EngineAddArgs {
+        id: id.to_string(),
+        kind: kind.to_string(),
+        base_url: base_url.to_string(),
+        auth_header: None,
+        bearer_token: None,
+        bearer_token_stdin: false,
+        model_capabilities: Vec::new(),
+        timeout_secs: None,
+        tls_ca_cert: None,
+        tls_insecure: false,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engines_add_list_and_remove() {
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let (_temp_dir, db_path) = create_temp_db();
+
+    let mut args = add_args("gpu-01", "vllm", "https://gpu-01.internal:8000/");
+    args.auth_header = Some("Authorization: Bearer secret-token".to_string());
+    args.timeout_secs = Some(120);
+    engines::execute(
+        EnginesSubcommand::Add(args),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("add command works");
+
+    engines::execute(
+        EnginesSubcommand::List,
+        Some(db_path.clone()),
+        "text".to_string(),
+    )
+    .await
+    .expect("list command works");
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path)
+        .await
+        .expect("open registry");
+    let registration = registry
+        .get("gpu-01")
+        .await
+        .expect("get engine")
+        .expect("engine registered");
+    assert_eq!(registration.kind, EngineKind::Vllm);
+    assert_eq!(registration.base_url, "https://gpu-01.internal:8000");
+    assert_eq!(registration.timeout_secs, Some(120));
+    assert_eq!(
+        registration.auth_header_name.as_deref(),
+        Some("Authorization")
+    );
+
+    engines::execute(
+        EnginesSubcommand::Remove {
+            id: "gpu-01".to_string(),
+        },
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("remove command works");
+    assert!(registry.list().await.expect("list engines").is_empty());
+
+    let missing = engines::execute(
+        EnginesSubcommand::Remove {
+            id: "gpu-01".to_string(),
+        },
+        Some(db_path),
+        "json".to_string(),
+    )
+    .await;
+    assert!(missing.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engines_add_rejects_invalid_input() {
+    let (_temp_dir, db_path) = create_temp_db();
+
+    let invalid = [
+        add_args("group", "ollama", "http://localhost:11434"),
+        add_args("bad id", "ollama", "http://localhost:11434"),
+        add_args("remote", "tgi", "http://localhost:11434"),
+        add_args("remote", "ollama", "ftp://localhost:11434"),
+        EngineAddArgs {
+            auth_header: Some("Authorization".to_string()),
+            ..add_args("remote", "ollama", "http://localhost:11434")
+        },
+        EngineAddArgs {
+            tls_ca_cert: Some("/nonexistent/ca.pem".to_string()),
+            ..add_args("remote", "ollama", "https://localhost:11434")
+        },
+        // Capability overrides are only meaningful for the generic adapter
+        EngineAddArgs {
+            model_capabilities: vec!["*=tools".to_string()],
+            ..add_args("remote", "vllm", "http://localhost:8000")
+        },
+        EngineAddArgs {
+            model_capabilities: vec!["gpt-4o=telepathy".to_string()],
+            ..add_args("remote", "openai", "https://api.example.com/v1")
+        },
+        EngineAddArgs {
+            bearer_token: Some("sk-test".to_string()),
+            auth_header: Some("Authorization: Bearer other".to_string()),
+            ..add_args("remote", "openai", "https://api.example.com/v1")
+        },
+    ];
+    for args in invalid {
+        let result = engines::execute(
+            EnginesSubcommand::Add(args),
+            Some(db_path.clone()),
+            "json".to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    // Duplicate IDs are rejected instead of silently replaced
+    engines::execute(
+        EnginesSubcommand::Add(add_args("remote", "ollama", "http://10.0.0.5:11434")),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("first add works");
+    let duplicate = engines::execute(
+        EnginesSubcommand::Add(add_args("remote", "llamacpp", "http://10.0.0.6:8080")),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await;
+    assert!(duplicate.is_err());
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path)
+        .await
+        .expect("open registry");
+    let registrations = registry.list().await.expect("list engines");
+    assert_eq!(registrations.len(), 1);
+    assert_eq!(registrations[0].kind, EngineKind::Ollama);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engines_add_openai_compatible() {
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let (_temp_dir, db_path) = create_temp_db();
+
+    let args = EngineAddArgs {
+        bearer_token: Some("sk-test".to_string()),
+        model_capabilities: vec!["gpt-4o=tools,vision".to_string(), "*=none".to_string()],
+        ..add_args("hosted", "openai", "https://api.example.com/v1/")
+    };
+    engines::execute(
+        EnginesSubcommand::Add(args),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("add command works");
+
+    let registry = SqliteEngineRegistryRepository::new(&db_path)
+        .await
+        .expect("open registry");
+    let registration = registry
+        .get("hosted")
+        .await
+        .expect("get engine")
+        .expect("engine registered");
+    assert_eq!(registration.kind, EngineKind::OpenAiCompatible);
+    assert_eq!(registration.base_url, "https://api.example.com");
+    assert!(registration.auth_header_name.is_none());
+    assert!(registration.bearer_token_in_keyring);
+    let gpt = &registration.model_capabilities["gpt-4o"];
+    assert!(gpt.tools && gpt.vision && !gpt.reasoning);
+    let all = &registration.model_capabilities["*"];
+    assert!(!all.tools && !all.vision);
+
+    engines::execute(
+        EnginesSubcommand::Remove {
+            id: "hosted".to_string(),
+        },
+        Some(db_path),
+        "json".to_string(),
+    )
+    .await
+    .expect("remove command works");
+}
+
+#[test]
+fn test_engines_auth_header_value_stays_out_of_config_db() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config_db = temp_dir.path().join("config.db");
+    let run_flm = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_flm"))
+            .args(args)
+            .args([
+                "--db-path-config",
+                config_db.to_str().unwrap(),
+                "--format",
+                "json",
+            ])
+            .env("FLM_DATA_DIR", temp_dir.path())
+            .env("FLM_DISABLE_KEYRING", "1")
+            .output()
+            .expect("run flm");
+        assert!(output.status.success(), "flm {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    run_flm(&[
+        "engines",
+        "add",
+        "--id",
+        "gpu-01",
+        "--kind",
+        "vllm",
+        "--base-url",
+        "https://gpu-01.internal:8000",
+        "--auth-header",
+        "X-Api-Key: header-secret-value",
+    ]);
+    let listed = run_flm(&["engines", "list"]);
+    assert!(!listed.contains("header-secret-value"));
+    let listed: serde_json::Value = serde_json::from_str(&listed).unwrap();
+    let auth = &listed["data"]["engines"][0]["auth_header"];
+    assert_eq!(auth["name"], "X-Api-Key");
+    assert_eq!(auth["value"], "****");
+
+    // Neither the database nor its journal files hold the header value
+    for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_file() {
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(
+                !bytes
+                    .windows(b"header-secret-value".len())
+                    .any(|window| window == b"header-secret-value"),
+                "{} contains the auth header value",
+                path.display()
+            );
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_registry_clears_legacy_auth_header_values() {
+    let (_temp_dir, db_path) = create_temp_db();
+    let registry = SqliteEngineRegistryRepository::new(&db_path)
+        .await
+        .expect("open registry");
+    engines::execute(
+        EnginesSubcommand::Add(add_args("legacy", "vllm", "http://10.0.0.5:8000")),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("add command works");
+
+    // Earlier versions stored the header value next to its name
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{db_path}"))
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE engines SET auth_header_name = 'X-Api-Key', auth_header_value = 'old-secret' \
+         WHERE id = 'legacy'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(
+        registry.legacy_auth_header_values().await.unwrap(),
+        vec![("legacy".to_string(), "old-secret".to_string())]
+    );
+    registry
+        .clear_legacy_auth_header_value("legacy")
+        .await
+        .unwrap();
+    assert!(registry
+        .legacy_auth_header_values()
+        .await
+        .unwrap()
+        .is_empty());
+    let registration = registry.get("legacy").await.unwrap().unwrap();
+    assert_eq!(registration.auth_header_name.as_deref(), Some("X-Api-Key"));
+
+    // Saving a registration never writes a header value
+    sqlx::query("UPDATE engines SET auth_header_value = 'old-secret'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    registry.save(&registration).await.unwrap();
+    assert!(registry
+        .legacy_auth_header_values()
+        .await
+        .unwrap()
+        .is_empty());
+}
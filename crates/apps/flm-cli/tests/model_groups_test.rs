@@ -0,0 +1,141 @@
+//! Tests for `flm model-groups` command
+
+use flm_cli::cli::model_groups::{ModelGroupSaveArgs, ModelGroupsSubcommand};
+use flm_cli::commands::model_groups;
+use flm_core::adapters::SqliteModelGroupRepository;
+use flm_core::domain::models::LoadBalanceStrategy;
+use flm_core::ports::ModelGroupRepository;
+use tempfile::TempDir;
+
+fn create_temp_db() -> (TempDir, String) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("config.db");
+    (temp_dir, db_path.to_str().unwrap().to_string())
+}
+
+fn save_args(name: &str, strategy: &str, members: &[&str]) -> ModelGroupSaveArgs {
+    ModelGroupSaveArgs {
+        name: name.to_string(),
+        strategy: strategy.to_string(),
+        members: members.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_model_groups_save_replace_and_delete() {
+    let (_temp_dir, db_path) = create_temp_db();
+
+    model_groups::execute(
+        ModelGroupsSubcommand::Save(save_args(
+            "llama3",
+            "round-robin",
+            &["flm://ollama/llama3", "flm://vllm/llama3"],
+        )),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("save command works");
+
+    // Saving again replaces strategy and member list
+    model_groups::execute(
+        ModelGroupsSubcommand::Save(save_args(
+            "llama3",
+            "latency-weighted",
+            &[
+                "flm://vllm/llama3",
+                "flm://llamacpp/llama3",
+                "flm://ollama/llama3",
+            ],
+        )),
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("replace command works");
+
+    let store = SqliteModelGroupRepository::new(&db_path)
+        .await
+        .expect("open store");
+    let groups = store.list_groups().await.expect("list groups");
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].strategy, LoadBalanceStrategy::LatencyWeighted);
+    assert_eq!(
+        groups[0].members,
+        vec![
+            "flm://vllm/llama3".to_string(),
+            "flm://llamacpp/llama3".to_string(),
+            "flm://ollama/llama3".to_string(),
+        ]
+    );
+
+    // The proxy resolves groups through the same repository
+    let group = store
+        .find_group("llama3")
+        .await
+        .expect("find group")
+        .expect("group exists");
+    assert_eq!(group.members.len(), 3);
+    assert!(store.find_group("missing").await.unwrap().is_none());
+
+    model_groups::execute(
+        ModelGroupsSubcommand::Delete {
+            name: "llama3".to_string(),
+        },
+        Some(db_path.clone()),
+        "json".to_string(),
+    )
+    .await
+    .expect("delete group");
+
+    assert!(store.list_groups().await.expect("list all").is_empty());
+    assert!(store.find_group("llama3").await.unwrap().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_model_groups_rejects_invalid_input() {
+    let (_temp_dir, db_path) = create_temp_db();
+
+    let cases = [
+        save_args("llama3", "random", &["flm://ollama/llama3"]),
+        save_args("bad/name", "round-robin", &["flm://ollama/llama3"]),
+        save_args("llama3", "round-robin", &["llama3"]),
+        save_args("llama3", "round-robin", &["flm://group/other"]),
+        save_args(
+            "llama3",
+            "round-robin",
+            &["flm://ollama/llama3", "flm://ollama/llama3"],
+        ),
+    ];
+
+    for args in cases {
+        let result = model_groups::execute(
+            ModelGroupsSubcommand::Save(args),
+            Some(db_path.clone()),
+            "json".to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    let store = SqliteModelGroupRepository::new(&db_path)
+        .await
+        .expect("open store");
+    assert!(store.list_groups().await.expect("list").is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_model_groups_delete_nonexistent() {
+    let (_temp_dir, db_path) = create_temp_db();
+    let result = model_groups::execute(
+        ModelGroupsSubcommand::Delete {
+            name: "missing".to_string(),
+        },
+        Some(db_path),
+        "json".to_string(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("not found"));
+}
@@ -30,6 +30,7 @@ async fn test_proxy_start_local_http() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -189,6 +190,7 @@ async fn test_proxy_start_invalid_mode() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -227,6 +229,7 @@ async fn test_proxy_start_multiple_ports() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -260,6 +263,7 @@ async fn test_proxy_start_multiple_ports() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -341,6 +345,7 @@ async fn test_proxy_stop_by_handle_id() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -447,6 +452,7 @@ async fn test_proxy_start_rejects_dns01_when_disabled() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -495,6 +501,7 @@ async fn test_proxy_start_with_tor_egress() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -565,6 +572,7 @@ async fn test_proxy_start_with_socks5_egress() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -608,6 +616,7 @@ async fn test_proxy_start_with_egress_fail_open() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -671,6 +680,7 @@ async fn test_proxy_status_shows_egress() {
         acme_dns_profile: None,
         acme_dns_lego_path: None,
         acme_dns_propagation_wait: None,
+        ollama_api: false,
         no_daemon: true,
     };
 
@@ -2,8 +2,8 @@
 //!
 //! These tests verify that backup and restore operations work correctly.
 
-use flm_cli::adapters::SqliteSecurityRepository;
-use flm_core::services::SecurityService;
+use flm_cli::adapters::{SqliteConfigRepository, SqliteSecurityRepository};
+use flm_core::services::{ConfigService, SecurityService};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -15,6 +15,29 @@ fn create_temp_db_dir() -> (TempDir, PathBuf) {
     (temp_dir, security_db)
 }
 
+/// Helper to write a backup passphrase file
+fn passphrase_file(temp_dir: &TempDir, passphrase: &str) -> Option<String> {
+    let path = temp_dir
+        .path()
+        .join(format!("passphrase-{}", passphrase.len()));
+    fs::write(&path, format!("{passphrase}\n")).unwrap();
+    Some(path.to_str().unwrap().to_string())
+}
+
+/// config.db path inside the temporary directory (not created)
+fn config_db(temp_dir: &TempDir) -> Option<String> {
+    Some(
+        temp_dir
+            .path()
+            .join("config.db")
+            .to_str()
+            .unwrap()
+            .to_string(),
+    )
+}
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_backup_create() {
     let (_temp_dir, security_db) = create_temp_db_dir();
@@ -31,7 +54,11 @@ async fn test_backup_create() {
     // Execute backup create
     let result = flm_cli::commands::security::execute_backup_create(
         Some(backup_dir.to_str().unwrap().to_string()),
+        "all".to_string(),
+        None,
+        passphrase_file(&_temp_dir, PASSPHRASE),
         Some(security_db.to_str().unwrap().to_string()),
+        config_db(&_temp_dir),
         "json".to_string(),
     )
     .await;
@@ -73,7 +100,11 @@ async fn test_backup_restore() {
 
     flm_cli::commands::security::execute_backup_create(
         Some(backup_dir.to_str().unwrap().to_string()),
+        "all".to_string(),
+        None,
+        passphrase_file(&temp_dir, PASSPHRASE),
         Some(security_db.to_str().unwrap().to_string()),
+        config_db(&temp_dir),
         "json".to_string(),
     )
     .await
@@ -99,7 +130,10 @@ async fn test_backup_restore() {
     // Restore from backup
     let result = flm_cli::commands::security::execute_backup_restore(
         backup_file.to_str().unwrap().to_string(),
+        passphrase_file(&temp_dir, PASSPHRASE),
+        false,
         Some(security_db.to_str().unwrap().to_string()),
+        config_db(&temp_dir),
         "json".to_string(),
     )
     .await;
@@ -138,7 +172,11 @@ async fn test_backup_generation_management() {
 
         flm_cli::commands::security::execute_backup_create(
             Some(backup_dir.to_str().unwrap().to_string()),
+            "all".to_string(),
+            None,
+            passphrase_file(&_temp_dir, PASSPHRASE),
             Some(security_db.to_str().unwrap().to_string()),
+            config_db(&_temp_dir),
             "json".to_string(),
         )
         .await
@@ -168,3 +206,178 @@ async fn test_backup_generation_management() {
         "Should keep only 3 most recent backups"
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backup_is_encrypted_and_verified() {
+    let (temp_dir, security_db) = create_temp_db_dir();
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    SecurityService::new(repo)
+        .create_api_key("auditor-visible-label")
+        .await
+        .unwrap();
+
+    let backup_dir = temp_dir.path().join("backups");
+    flm_cli::commands::security::execute_backup_create(
+        Some(backup_dir.to_str().unwrap().to_string()),
+        "security".to_string(),
+        None,
+        passphrase_file(&temp_dir, PASSPHRASE),
+        Some(security_db.to_str().unwrap().to_string()),
+        config_db(&temp_dir),
+        "json".to_string(),
+    )
+    .await
+    .unwrap();
+    let backup_file = fs::read_dir(&backup_dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+
+    // Neither the SQLite header nor the data appear in the file
+    let contents = fs::read(&backup_file).unwrap();
+    assert!(contents.starts_with(b"FLMBAK01"));
+    for plaintext in [&b"SQLite format 3"[..], b"auditor-visible-label"] {
+        assert!(!contents
+            .windows(plaintext.len())
+            .any(|window| window == plaintext));
+    }
+
+    let verify = |passphrase: &'static str, file: PathBuf| {
+        let passphrase = passphrase_file(&temp_dir, passphrase);
+        async move {
+            flm_cli::commands::security::execute_backup_verify(
+                file.to_str().unwrap().to_string(),
+                passphrase,
+                "json".to_string(),
+            )
+            .await
+        }
+    };
+    assert!(verify(PASSPHRASE, backup_file.clone()).await.is_ok());
+    assert!(verify("not the right passphrase", backup_file.clone())
+        .await
+        .is_err());
+
+    // A single flipped byte fails authentication
+    let mut tampered = contents.clone();
+    let middle = tampered.len() / 2;
+    tampered[middle] ^= 0x01;
+    let tampered_file = temp_dir.path().join("tampered.bak");
+    fs::write(&tampered_file, &tampered).unwrap();
+    let error = verify(PASSPHRASE, tampered_file).await.unwrap_err();
+    assert!(
+        error.to_string().contains("could not be authenticated"),
+        "{error}"
+    );
+
+    // Too-short passphrases are refused for new backups
+    let result = flm_cli::commands::security::execute_backup_create(
+        Some(backup_dir.to_str().unwrap().to_string()),
+        "security".to_string(),
+        None,
+        passphrase_file(&temp_dir, "short"),
+        Some(security_db.to_str().unwrap().to_string()),
+        config_db(&temp_dir),
+        "json".to_string(),
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backup_covers_config_db_with_retention() {
+    let (temp_dir, security_db) = create_temp_db_dir();
+    SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let config_db_path = temp_dir.path().join("config.db");
+    let config_service =
+        ConfigService::new(SqliteConfigRepository::new(&config_db_path).await.unwrap());
+    config_service.set("proxy.port", "8123").await.unwrap();
+    config_service
+        .set("security.backup.retention", "2")
+        .await
+        .unwrap();
+    drop(config_service);
+
+    let backup_dir = temp_dir.path().join("backups");
+    for _ in 0..3 {
+        flm_cli::commands::security::execute_backup_create(
+            Some(backup_dir.to_str().unwrap().to_string()),
+            "all".to_string(),
+            None,
+            passphrase_file(&temp_dir, PASSPHRASE),
+            Some(security_db.to_str().unwrap().to_string()),
+            config_db(&temp_dir),
+            "json".to_string(),
+        )
+        .await
+        .unwrap();
+    }
+
+    // The retention setting keeps two generations of each database
+    let mut names: Vec<String> = fs::read_dir(&backup_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 4, "{names:?}");
+    assert!(names[..2]
+        .iter()
+        .all(|name| name.starts_with("config.db.bak.")));
+    assert!(names[2..]
+        .iter()
+        .all(|name| name.starts_with("security.db.bak.")));
+
+    // Restoring a config.db backup writes config.db, not security.db
+    fs::remove_file(&config_db_path).unwrap();
+    flm_cli::commands::security::execute_backup_restore(
+        backup_dir.join(&names[1]).to_str().unwrap().to_string(),
+        passphrase_file(&temp_dir, PASSPHRASE),
+        false,
+        Some(security_db.to_str().unwrap().to_string()),
+        config_db(&temp_dir),
+        "json".to_string(),
+    )
+    .await
+    .unwrap();
+    let restored = ConfigService::new(SqliteConfigRepository::new(&config_db_path).await.unwrap());
+    assert_eq!(
+        restored.get("proxy.port").await.unwrap().as_deref(),
+        Some("8123")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_plaintext_backup_restore_requires_allow_plaintext() {
+    let (temp_dir, security_db) = create_temp_db_dir();
+    let service = SecurityService::new(SqliteSecurityRepository::new(&security_db).await.unwrap());
+    service.create_api_key("legacy-key").await.unwrap();
+    drop(service);
+
+    // Backups taken before encryption were plain copies of security.db
+    let legacy_backup = temp_dir.path().join("security.db.bak.legacy");
+    fs::copy(&security_db, &legacy_backup).unwrap();
+    fs::remove_file(&security_db).unwrap();
+
+    let restore = |allow_plaintext| {
+        flm_cli::commands::security::execute_backup_restore(
+            legacy_backup.to_str().unwrap().to_string(),
+            None,
+            allow_plaintext,
+            Some(security_db.to_str().unwrap().to_string()),
+            config_db(&temp_dir),
+            "json".to_string(),
+        )
+    };
+
+    let error = restore(false).await.unwrap_err().to_string();
+    assert!(error.contains("--allow-plaintext"), "{error}");
+    assert!(!security_db.exists());
+
+    restore(true).await.unwrap();
+    let restored = SecurityService::new(SqliteSecurityRepository::new(&security_db).await.unwrap());
+    let keys = restored.list_api_keys().await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].label, "legacy-key");
+}
@@ -210,6 +210,7 @@ async fn test_security_policy_file_not_found() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -239,6 +240,7 @@ async fn test_security_policy_invalid_json_string() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -247,6 +249,124 @@ async fn test_security_policy_invalid_json_string() {
     assert!(result.is_err(), "Policy set with invalid JSON should fail");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_security_policy_set_rejects_unknown_fields() {
+    use flm_cli::cli::security::{PolicySubcommand, SecuritySubcommand};
+    use flm_cli::commands::security;
+    use flm_core::domain::policy::{PolicyDocument, POLICY_SCHEMA_VERSION};
+
+    let (_temp_dir, security_db) = create_temp_db_dir();
+    let set = |json: &str| {
+        security::execute(
+            SecuritySubcommand::Policy {
+                subcommand: PolicySubcommand::Set {
+                    json: Some(json.to_string()),
+                    file: None,
+                },
+            },
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            "json".to_string(),
+        )
+    };
+
+    let err = set(r#"{"rate_limit": {"rpmm": 60}}"#).await.unwrap_err();
+    assert!(
+        err.to_string().contains("rate_limit.rpmm"),
+        "error should name the field path: {err}"
+    );
+    assert!(set(r#"{"ip_whitelist": ["10.0.0.1", 5]}"#).await.is_err());
+
+    // A version 1 policy is migrated and stored with the current schema version
+    set(r#"{"cors": {"allowed_origins": "https://app.example"}}"#)
+        .await
+        .unwrap();
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let stored = SecurityService::new(repo)
+        .get_policy("default")
+        .await
+        .unwrap()
+        .unwrap();
+    let document = PolicyDocument::parse(&stored.policy_json).unwrap();
+    assert_eq!(document.schema_version, POLICY_SCHEMA_VERSION);
+    assert_eq!(
+        document.cors.unwrap().allowed_origins,
+        Some(vec!["https://app.example".to_string()])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_security_policy_diff_and_test() {
+    use flm_cli::cli::security::{PolicySubcommand, SecuritySubcommand};
+    use flm_cli::commands::security;
+    use flm_core::domain::security::{ApiKeyRateLimit, ApiKeyScopes};
+
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let (_temp_dir, security_db) = create_temp_db_dir();
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let service = SecurityService::new(repo);
+    let key = service
+        .create_scoped_api_key(
+            "limited",
+            ApiKeyScopes {
+                ip_allowlist: vec!["10.0.0.0/8".to_string()],
+                rate_limit: ApiKeyRateLimit {
+                    rpm: Some(5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let run = |subcommand: PolicySubcommand| {
+        security::execute(
+            SecuritySubcommand::Policy { subcommand },
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            "json".to_string(),
+        )
+    };
+
+    run(PolicySubcommand::Diff {
+        json: Some(r#"{"ip_whitelist": ["10.0.0.0/8"], "rate_limit": {"rpm": 60}}"#.to_string()),
+        file: None,
+    })
+    .await
+    .unwrap();
+    assert!(run(PolicySubcommand::Diff {
+        json: Some(r#"{"ip_whitelist": "10.0.0.1"}"#.to_string()),
+        file: None,
+    })
+    .await
+    .is_err());
+
+    run(PolicySubcommand::Test {
+        ip: "10.1.2.3".to_string(),
+        key: Some(key.record.id.clone()),
+        json: None,
+        file: None,
+    })
+    .await
+    .unwrap();
+    assert!(run(PolicySubcommand::Test {
+        ip: "not-an-ip".to_string(),
+        key: None,
+        json: None,
+        file: None,
+    })
+    .await
+    .is_err());
+    assert!(run(PolicySubcommand::Test {
+        ip: "10.1.2.3".to_string(),
+        key: Some("missing-key".to_string()),
+        json: None,
+        file: None,
+    })
+    .await
+    .is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_security_ip_blocklist_list_empty() {
     use flm_cli::cli::security::SecuritySubcommand;
@@ -261,6 +381,7 @@ async fn test_security_ip_blocklist_list_empty() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -305,6 +426,7 @@ async fn test_security_ip_blocklist_unblock() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -328,6 +450,7 @@ async fn test_security_ip_blocklist_unblock_invalid_ip() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -352,6 +475,7 @@ async fn test_security_ip_blocklist_clear() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -372,11 +496,13 @@ async fn test_security_audit_logs_list() {
         ip: None,
         limit: 10,
         offset: 0,
+        subcommand: None,
     };
 
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -397,11 +523,13 @@ async fn test_security_audit_logs_with_filters() {
         ip: Some("127.0.0.1".to_string()),
         limit: 20,
         offset: 0,
+        subcommand: None,
     };
 
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -429,6 +557,7 @@ async fn test_security_intrusion_list() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -453,6 +582,7 @@ async fn test_security_intrusion_with_filters() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -477,6 +607,7 @@ async fn test_security_anomaly_list() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
@@ -501,9 +632,261 @@ async fn test_security_anomaly_with_filters() {
     let result = security::execute(
         subcommand,
         Some(security_db.to_str().unwrap().to_string()),
+        None,
         "json".to_string(),
     )
     .await;
 
     assert!(result.is_ok(), "Anomaly list with filters should succeed");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_security_quotas_reports_token_usage() {
+    use flm_cli::cli::security::SecuritySubcommand;
+    use flm_cli::commands::security;
+    use flm_core::domain::chat::UsageStats;
+
+    let (_temp_dir, security_db) = create_temp_db_dir();
+
+    // The proxy records usage per key and UTC day
+    let proxy_repo = flm_proxy::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let usage = UsageStats {
+        prompt_tokens: 30,
+        completion_tokens: 20,
+        total_tokens: 50,
+    };
+    for usage_date in [today.as_str(), today.as_str(), "2000-01-01"] {
+        proxy_repo
+            .record_token_usage("key-1", usage_date, &usage)
+            .await
+            .unwrap();
+    }
+
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let rows = repo.list_token_usage(&today, None).await.unwrap();
+    assert_eq!(rows, vec![("key-1".to_string(), 100, 2, 100, 2)]);
+    assert!(repo
+        .list_token_usage(&today, Some("key-2"))
+        .await
+        .unwrap()
+        .is_empty());
+
+    let service = SecurityService::new(repo);
+    service
+        .set_policy(SecurityPolicy {
+            id: "default".to_string(),
+            policy_json: r#"{"rate_limit":{"rpm":60,"tpm":1000,"daily_tokens":500}}"#.to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    for format in ["json", "text"] {
+        let result = security::execute(
+            SecuritySubcommand::Quotas { api_key_id: None },
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            format.to_string(),
+        )
+        .await;
+        assert!(result.is_ok(), "Quotas should succeed ({format})");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_security_audit_logs_verify_detects_tampering() {
+    use base64::Engine as _;
+    use flm_cli::cli::security::{AuditLogsSubcommand, SecuritySubcommand};
+    use flm_cli::commands::security;
+    use flm_core::domain::audit::{AuditChainBreakKind, AuditChainVerifier};
+    use flm_proxy::adapters::AuditLogMetadata;
+
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let (temp_dir, security_db) = create_temp_db_dir();
+    let key = b"audit-log-hmac-key-for-the-tests".to_vec();
+    let key_file = temp_dir.path().join("audit.key");
+    std::fs::write(
+        &key_file,
+        base64::engine::general_purpose::STANDARD.encode(&key),
+    )
+    .unwrap();
+
+    // Two entries before the key was set up, then four signed ones
+    let proxy_repo = flm_proxy::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    let signed_repo = proxy_repo.clone().with_audit_hmac_key(key.clone());
+    for (index, repo) in [
+        &proxy_repo,
+        &proxy_repo,
+        &signed_repo,
+        &signed_repo,
+        &signed_repo,
+        &signed_repo,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        repo.save_audit_log(
+            &format!("req-{index}"),
+            None,
+            "/v1/chat/completions",
+            200,
+            Some(12),
+            Some("auth_success"),
+            AuditLogMetadata {
+                severity: "low",
+                ip: Some("127.0.0.1"),
+                details: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let verify = |hmac_key_file: Option<String>| {
+        security::execute(
+            SecuritySubcommand::AuditLogs {
+                event_type: None,
+                severity: None,
+                ip: None,
+                limit: 100,
+                offset: 0,
+                subcommand: Some(AuditLogsSubcommand::Verify { hmac_key_file }),
+            },
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            "json".to_string(),
+        )
+    };
+    let key_file_arg = Some(key_file.to_str().unwrap().to_string());
+    assert!(verify(key_file_arg.clone()).await.is_ok());
+    // Signed entries cannot be checked without the key
+    assert!(verify(None).await.is_err());
+
+    let first_break = || async {
+        let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+        let rows = repo.list_chained_audit_logs(0, None, 100).await.unwrap();
+        let mut verifier = AuditChainVerifier::new(Some(&key));
+        for row in &rows {
+            if !verifier.push(row) {
+                break;
+            }
+        }
+        verifier
+            .finish()
+            .first_break
+            .map(|first_break| (first_break.id, first_break.kind))
+    };
+
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE audit_logs SET status = 401 WHERE id = 4")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(verify(key_file_arg.clone()).await.is_err());
+    assert_eq!(first_break().await, Some((4, AuditChainBreakKind::Altered)));
+
+    sqlx::query("UPDATE audit_logs SET status = 200 WHERE id = 4")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(verify(key_file_arg.clone()).await.is_ok());
+    sqlx::query("DELETE FROM audit_logs WHERE id = 5")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(verify(key_file_arg).await.is_err());
+    assert_eq!(first_break().await, Some((6, AuditChainBreakKind::Gap)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_security_audit_logs_verify_after_retention_prune() {
+    use flm_cli::cli::security::{AuditLogsSubcommand, SecuritySubcommand};
+    use flm_cli::commands::security;
+    use flm_core::domain::security::{RetentionTable, TableRetention};
+    use flm_proxy::adapters::AuditLogMetadata;
+
+    std::env::set_var("FLM_DISABLE_KEYRING", "1");
+    let (_temp_dir, security_db) = create_temp_db_dir();
+    let proxy_repo = flm_proxy::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    let save = |index: usize| {
+        let proxy_repo = proxy_repo.clone();
+        async move {
+            proxy_repo
+                .save_audit_log(
+                    &format!("req-{index}"),
+                    None,
+                    "/v1/models",
+                    200,
+                    Some(3),
+                    Some("auth_success"),
+                    AuditLogMetadata {
+                        severity: "low",
+                        ip: Some("127.0.0.1"),
+                        details: None,
+                    },
+                )
+                .await
+                .unwrap()
+        }
+    };
+    for index in 0..6 {
+        save(index).await;
+    }
+
+    let outcome = flm_proxy::retention::prune_table(
+        &proxy_repo,
+        RetentionTable::AuditLogs,
+        TableRetention {
+            max_age_days: None,
+            max_rows: Some(2),
+        },
+        None,
+        chrono::Utc::now(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(outcome.deleted, 4);
+    save(6).await;
+
+    let verify = || {
+        security::execute(
+            SecuritySubcommand::AuditLogs {
+                event_type: None,
+                severity: None,
+                ip: None,
+                limit: 100,
+                offset: 0,
+                subcommand: Some(AuditLogsSubcommand::Verify {
+                    hmac_key_file: None,
+                }),
+            },
+            Some(security_db.to_str().unwrap().to_string()),
+            None,
+            "json".to_string(),
+        )
+    };
+    assert!(verify().await.is_ok());
+
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let anchor = repo.latest_audit_chain_anchor().await.unwrap().unwrap();
+    assert_eq!(anchor.pruned_through_id, 4);
+
+    // Deleting the oldest remaining entry without an anchor is still caught
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM audit_logs WHERE id = 5")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(verify().await.is_err());
+}
@@ -0,0 +1,139 @@
+//! Tests for `flm usage report`
+
+use flm_cli::adapters::{SqliteSecurityRepository, UsageGroupBy};
+use flm_cli::cli::usage::UsageSubcommand;
+use flm_cli::commands::usage;
+use flm_core::domain::chat::UsageStats;
+use flm_proxy::adapters::UsageRecord;
+
+fn record(api_key_id: &str, model: &str, total: u32, created_at: &str) -> UsageRecord {
+    UsageRecord {
+        api_key_id: api_key_id.to_string(),
+        endpoint: "/v1/chat/completions".to_string(),
+        engine_id: Some("ollama-default".to_string()),
+        model_id: Some(format!("flm://ollama-default/{model}")),
+        usage: UsageStats {
+            prompt_tokens: total / 2,
+            completion_tokens: total - total / 2,
+            total_tokens: total,
+        },
+        latency_ms: u64::from(total),
+        estimated: total == 30,
+        created_at: created_at.to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_usage_report_groups_by_model_and_day() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let security_db = temp_dir.path().join("security.db");
+
+    // The proxy records one row per metered request
+    let proxy_repo = flm_proxy::adapters::SqliteSecurityRepository::new(&security_db)
+        .await
+        .unwrap();
+    for record in [
+        record("key-1", "llama3", 100, "2026-10-01T10:00:00+00:00"),
+        record("key-1", "llama3", 50, "2026-10-02T10:00:00+00:00"),
+        record("key-1", "qwen2.5", 30, "2026-10-02T11:00:00+00:00"),
+        record("key-2", "llama3", 10, "2026-09-30T23:59:59+00:00"),
+    ] {
+        proxy_repo.save_usage_record(&record).await.unwrap();
+    }
+
+    let repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+
+    let per_key = repo
+        .usage_report(None, None, UsageGroupBy::Key)
+        .await
+        .unwrap();
+    assert_eq!(per_key.len(), 2);
+    assert_eq!(per_key[0].api_key_id, "key-1");
+    assert_eq!(per_key[0].group, None);
+    assert_eq!(per_key[0].requests, 3);
+    assert_eq!(per_key[0].total_tokens, 180);
+    assert_eq!(per_key[0].prompt_tokens, 90);
+    assert_eq!(per_key[0].avg_latency_ms, 60);
+    assert_eq!(per_key[0].estimated_requests, 1);
+
+    let since = usage::parse_since("2026-10-01", chrono::Utc::now()).unwrap();
+    let by_model = repo
+        .usage_report(Some(&since), Some("key-1"), UsageGroupBy::Model)
+        .await
+        .unwrap();
+    let models: Vec<(Option<&str>, i64, i64)> = by_model
+        .iter()
+        .map(|row| (row.group.as_deref(), row.requests, row.total_tokens))
+        .collect();
+    assert_eq!(
+        models,
+        vec![
+            (Some("flm://ollama-default/llama3"), 2, 150),
+            (Some("flm://ollama-default/qwen2.5"), 1, 30),
+        ]
+    );
+
+    // key-2's only request is before the period
+    let by_day = repo
+        .usage_report(Some(&since), None, UsageGroupBy::Day)
+        .await
+        .unwrap();
+    let days: Vec<(&str, Option<&str>, i64)> = by_day
+        .iter()
+        .map(|row| {
+            (
+                row.api_key_id.as_str(),
+                row.group.as_deref(),
+                row.total_tokens,
+            )
+        })
+        .collect();
+    assert_eq!(
+        days,
+        vec![
+            ("key-1", Some("2026-10-01"), 100),
+            ("key-1", Some("2026-10-02"), 80),
+        ]
+    );
+
+    for format in ["json", "csv", "text"] {
+        let result = usage::execute(
+            UsageSubcommand::Report {
+                key: Some("key-1".to_string()),
+                since: Some("30d".to_string()),
+                group_by: "day".to_string(),
+            },
+            Some(security_db.to_str().unwrap().to_string()),
+            format.to_string(),
+        )
+        .await;
+        assert!(result.is_ok(), "usage report ({format}) should succeed");
+    }
+}
+
+#[test]
+fn test_usage_report_parse_since() {
+    let now = chrono::DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    assert_eq!(
+        usage::parse_since("2026-10-01", now).unwrap(),
+        "2026-10-01T00:00:00+00:00"
+    );
+    assert_eq!(
+        usage::parse_since("2026-10-01T09:00:00+09:00", now).unwrap(),
+        "2026-10-01T00:00:00+00:00"
+    );
+    assert_eq!(
+        usage::parse_since("7d", now).unwrap(),
+        "2026-10-11T12:00:00+00:00"
+    );
+    assert_eq!(
+        usage::parse_since("12h", now).unwrap(),
+        "2026-10-18T00:00:00+00:00"
+    );
+    for invalid in ["yesterday", "-1d", "7w", "d", "7日"] {
+        assert!(usage::parse_since(invalid, now).is_err(), "{invalid}");
+    }
+}
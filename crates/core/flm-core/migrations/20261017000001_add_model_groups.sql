@@ -0,0 +1,18 @@
+-- Migration: add model_groups and model_group_members tables
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+CREATE TABLE IF NOT EXISTS model_groups (
+    name TEXT PRIMARY KEY,
+    strategy TEXT NOT NULL DEFAULT 'round-robin',
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS model_group_members (
+    group_name TEXT NOT NULL REFERENCES model_groups(name) ON DELETE CASCADE,
+    model_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (group_name, model_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_model_group_members_group
+    ON model_group_members(group_name, position);
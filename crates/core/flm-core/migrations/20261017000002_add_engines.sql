@@ -0,0 +1,15 @@
+-- Migration: add engines table (explicit engine registry)
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+CREATE TABLE IF NOT EXISTS engines (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    base_url TEXT NOT NULL,
+    auth_header_name TEXT,
+    auth_header_value TEXT,
+    timeout_secs INTEGER,
+    tls_ca_cert_path TEXT,
+    tls_insecure_skip_verify INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
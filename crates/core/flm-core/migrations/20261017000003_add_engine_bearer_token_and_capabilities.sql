@@ -0,0 +1,6 @@
+-- Migration: bearer token flag and per-model capability overrides for registered engines
+-- Bearer tokens themselves are stored in the OS keyring, not in config.db.
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+ALTER TABLE engines ADD COLUMN bearer_token_in_keyring INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE engines ADD COLUMN model_capabilities TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,17 @@
+-- Migration: add engine_processes table (launch settings for `flm engines start`)
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+CREATE TABLE IF NOT EXISTS engine_processes (
+    engine_id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    binary_path TEXT,
+    model TEXT,
+    host TEXT NOT NULL DEFAULT '127.0.0.1',
+    port INTEGER NOT NULL,
+    args TEXT NOT NULL DEFAULT '[]',
+    env TEXT NOT NULL DEFAULT '{}',
+    restart_on_crash INTEGER NOT NULL DEFAULT 1,
+    max_restarts INTEGER NOT NULL DEFAULT 5,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
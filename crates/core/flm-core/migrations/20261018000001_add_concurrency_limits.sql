@@ -0,0 +1,10 @@
+-- Migration: add concurrency_limits table (per-engine / per-model request caps)
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+CREATE TABLE IF NOT EXISTS concurrency_limits (
+    target TEXT PRIMARY KEY,
+    max_concurrent INTEGER NOT NULL,
+    max_queue INTEGER NOT NULL DEFAULT 32,
+    queue_timeout_secs INTEGER NOT NULL DEFAULT 30,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
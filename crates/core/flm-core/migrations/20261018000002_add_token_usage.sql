@@ -0,0 +1,16 @@
+-- Migration: add token_usage table (per-API-key daily token consumption)
+-- See docs/specs/DB_SCHEMA.md section 2 (security.db)
+-- Monthly totals are the sum of the month's daily rows (UTC)
+
+CREATE TABLE IF NOT EXISTS token_usage (
+    api_key_id TEXT NOT NULL,
+    usage_date TEXT NOT NULL, -- UTC day, YYYY-MM-DD
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER NOT NULL DEFAULT 0,
+    requests INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (api_key_id, usage_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_usage_usage_date ON token_usage(usage_date);
@@ -0,0 +1,20 @@
+-- Migration: add usage_records table (per-request token usage per API key)
+-- See docs/specs/DB_SCHEMA.md section 2 (security.db)
+-- token_usage keeps the daily totals used for quotas; these rows feed `flm usage report`
+
+CREATE TABLE IF NOT EXISTS usage_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    api_key_id TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    engine_id TEXT,
+    model_id TEXT,
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER NOT NULL DEFAULT 0,
+    latency_ms INTEGER NOT NULL DEFAULT 0,
+    estimated INTEGER NOT NULL DEFAULT 0, -- 1 when the engine reported no usage
+    created_at TEXT NOT NULL -- RFC3339, UTC
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_records_api_key_created ON usage_records(api_key_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_usage_records_created_at ON usage_records(created_at);
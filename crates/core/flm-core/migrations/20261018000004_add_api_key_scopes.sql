@@ -0,0 +1,5 @@
+-- Migration: add api_keys.scopes_json (per-key endpoint, model, IP and rate limit scopes)
+-- See docs/specs/DB_SCHEMA.md section 2 (security.db)
+-- NULL or '{}' means the key is unrestricted beyond the security policy
+
+ALTER TABLE api_keys ADD COLUMN scopes_json TEXT;
@@ -0,0 +1,8 @@
+-- Migration: hash chain over audit_logs (tamper evidence)
+-- See docs/specs/DB_SCHEMA.md section 2 (security.db)
+-- entry_hash = SHA-256 or HMAC-SHA256 over prev_hash and the row's fields;
+-- rows written before this migration keep NULL and precede the chain
+
+ALTER TABLE audit_logs ADD COLUMN prev_hash TEXT;
+ALTER TABLE audit_logs ADD COLUMN entry_hash TEXT;
+ALTER TABLE audit_logs ADD COLUMN hash_alg TEXT;
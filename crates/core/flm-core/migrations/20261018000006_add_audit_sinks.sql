@@ -0,0 +1,9 @@
+-- Migration: add audit_sinks table (external destinations for audit log entries)
+-- See docs/specs/DB_SCHEMA.md section 2 (config.db)
+
+CREATE TABLE IF NOT EXISTS audit_sinks (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    config_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
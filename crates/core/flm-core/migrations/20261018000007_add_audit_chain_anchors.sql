@@ -0,0 +1,14 @@
+-- Migration: audit log retention anchors
+-- See docs/specs/DB_SCHEMA.md section 2 (security.db)
+-- Retention pruning deletes the oldest audit_logs rows; each pruning run
+-- records the hash of the last deleted entry so the chain stays verifiable
+
+CREATE TABLE IF NOT EXISTS audit_chain_anchors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pruned_through_id INTEGER NOT NULL,
+    entry_hash TEXT,
+    hash_alg TEXT,
+    pruned_rows INTEGER NOT NULL,
+    archive_path TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
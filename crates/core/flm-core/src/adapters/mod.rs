@@ -1,5 +1,21 @@
 //! Adapter implementations
 //!
+pub mod sqlite_api_prompt_repository;
+pub mod sqlite_audit_sink_repository;
+pub mod sqlite_concurrency_limit_repository;
+pub mod sqlite_engine_health_log_repository;
+pub mod sqlite_engine_process_repository;
+pub mod sqlite_engine_registry_repository;
+pub mod sqlite_model_group_repository;
+pub mod sqlite_model_profile_repository;
 pub mod sqlite_proxy_repository;
 
+pub use sqlite_api_prompt_repository::SqliteApiPromptRepository;
+pub use sqlite_audit_sink_repository::SqliteAuditSinkRepository;
+pub use sqlite_concurrency_limit_repository::SqliteConcurrencyLimitRepository;
+pub use sqlite_engine_health_log_repository::SqliteEngineHealthLogRepository;
+pub use sqlite_engine_process_repository::SqliteEngineProcessConfigRepository;
+pub use sqlite_engine_registry_repository::SqliteEngineRegistryRepository;
+pub use sqlite_model_group_repository::SqliteModelGroupRepository;
+pub use sqlite_model_profile_repository::SqliteModelProfileRepository;
 pub use sqlite_proxy_repository::SqliteProxyRepository;
@@ -0,0 +1,69 @@
+//! SQLite-backed ApiPromptRepository implementation (config.db).
+
+use crate::domain::models::ApiPrompt;
+use crate::error::RepoError;
+use crate::ports::ApiPromptRepository;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+/// SQLite-based ApiPromptRepository implementation.
+pub struct SqliteApiPromptRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteApiPromptRepository {
+    /// Create a new ApiPromptRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiPromptRepository for SqliteApiPromptRepository {
+    async fn get(&self, api_id: &str) -> Result<Option<ApiPrompt>, RepoError> {
+        let row = sqlx::query_as::<_, (String, String, i64, String)>(
+            "SELECT api_id, template_text, version, updated_at FROM api_prompts WHERE api_id = ?",
+        )
+        .bind(api_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load API prompt: {e}"),
+        })?;
+
+        Ok(
+            row.map(|(api_id, template_text, version, updated_at)| ApiPrompt {
+                api_id,
+                template_text,
+                version,
+                updated_at,
+            }),
+        )
+    }
+}
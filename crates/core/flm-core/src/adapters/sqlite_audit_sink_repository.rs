@@ -0,0 +1,129 @@
+//! SQLite-backed AuditSinkRepository implementation (config.db).
+
+use crate::domain::audit::{AuditSink, AuditSinkConfig};
+use crate::error::RepoError;
+use crate::ports::AuditSinkRepository;
+use chrono::Utc;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+/// SQLite-based AuditSinkRepository implementation.
+#[derive(Clone)]
+pub struct SqliteAuditSinkRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditSinkRepository {
+    /// Create a new AuditSinkRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+type SinkRow = (String, String, String);
+
+#[async_trait::async_trait]
+impl AuditSinkRepository for SqliteAuditSinkRepository {
+    async fn list_sinks(&self) -> Result<Vec<AuditSink>, RepoError> {
+        let rows = sqlx::query_as::<_, SinkRow>(
+            "SELECT id, config_json, updated_at FROM audit_sinks ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load audit sinks: {e}"),
+        })?;
+
+        rows.into_iter().map(sink_from_row).collect()
+    }
+
+    async fn find_sink(&self, id: &str) -> Result<Option<AuditSink>, RepoError> {
+        let row = sqlx::query_as::<_, SinkRow>(
+            "SELECT id, config_json, updated_at FROM audit_sinks WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to fetch audit sink: {e}"),
+        })?;
+
+        row.map(sink_from_row).transpose()
+    }
+
+    async fn save_sink(&self, id: &str, config: &AuditSinkConfig) -> Result<AuditSink, RepoError> {
+        let now = Utc::now().to_rfc3339();
+        let config_json = serde_json::to_string(config).map_err(|e| RepoError::IoError {
+            reason: format!("Failed to serialize audit sink config: {e}"),
+        })?;
+
+        sqlx::query(
+            "INSERT INTO audit_sinks (id, kind, config_json, updated_at) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET kind = excluded.kind, \
+             config_json = excluded.config_json, updated_at = excluded.updated_at",
+        )
+        .bind(id)
+        .bind(config.kind())
+        .bind(&config_json)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save audit sink: {e}"),
+        })?;
+
+        self.find_sink(id)
+            .await?
+            .ok_or_else(|| RepoError::NotFound {
+                key: id.to_string(),
+            })
+    }
+
+    async fn delete_sink(&self, id: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM audit_sinks WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to delete audit sink: {e}"),
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn sink_from_row((id, config_json, updated_at): SinkRow) -> Result<AuditSink, RepoError> {
+    let config = serde_json::from_str(&config_json).map_err(|e| RepoError::IoError {
+        reason: format!("Invalid config for audit sink {id}: {e}"),
+    })?;
+    Ok(AuditSink {
+        id,
+        config,
+        updated_at,
+    })
+}
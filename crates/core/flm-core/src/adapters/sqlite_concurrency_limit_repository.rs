@@ -0,0 +1,138 @@
+//! SQLite-backed ConcurrencyLimitRepository implementation (config.db).
+
+use crate::domain::models::ConcurrencyLimit;
+use crate::error::RepoError;
+use crate::ports::ConcurrencyLimitRepository;
+use chrono::Utc;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+/// SQLite-based ConcurrencyLimitRepository implementation.
+#[derive(Clone)]
+pub struct SqliteConcurrencyLimitRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteConcurrencyLimitRepository {
+    /// Create a new ConcurrencyLimitRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+type LimitRow = (String, i64, i64, i64, String);
+
+#[async_trait::async_trait]
+impl ConcurrencyLimitRepository for SqliteConcurrencyLimitRepository {
+    async fn list_limits(&self) -> Result<Vec<ConcurrencyLimit>, RepoError> {
+        let rows = sqlx::query_as::<_, LimitRow>(
+            "SELECT target, max_concurrent, max_queue, queue_timeout_secs, updated_at \
+             FROM concurrency_limits ORDER BY target",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load concurrency limits: {e}"),
+        })?;
+
+        Ok(rows.into_iter().map(limit_from_row).collect())
+    }
+
+    async fn find_limit(&self, target: &str) -> Result<Option<ConcurrencyLimit>, RepoError> {
+        let row = sqlx::query_as::<_, LimitRow>(
+            "SELECT target, max_concurrent, max_queue, queue_timeout_secs, updated_at \
+             FROM concurrency_limits WHERE target = ?",
+        )
+        .bind(target)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to fetch concurrency limit: {e}"),
+        })?;
+
+        Ok(row.map(limit_from_row))
+    }
+
+    async fn save_limit(
+        &self,
+        target: &str,
+        max_concurrent: u32,
+        max_queue: u32,
+        queue_timeout_secs: u32,
+    ) -> Result<ConcurrencyLimit, RepoError> {
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT INTO concurrency_limits (target, max_concurrent, max_queue, queue_timeout_secs, updated_at) \
+             VALUES (?, ?, ?, ?, ?) \
+             ON CONFLICT(target) DO UPDATE SET max_concurrent = excluded.max_concurrent, \
+             max_queue = excluded.max_queue, queue_timeout_secs = excluded.queue_timeout_secs, \
+             updated_at = excluded.updated_at",
+        )
+        .bind(target)
+        .bind(max_concurrent as i64)
+        .bind(max_queue as i64)
+        .bind(queue_timeout_secs as i64)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save concurrency limit: {e}"),
+        })?;
+
+        self.find_limit(target)
+            .await?
+            .ok_or_else(|| RepoError::NotFound {
+                key: target.to_string(),
+            })
+    }
+
+    async fn delete_limit(&self, target: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM concurrency_limits WHERE target = ?")
+            .bind(target)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to delete concurrency limit: {e}"),
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn limit_from_row(
+    (target, max_concurrent, max_queue, queue_timeout_secs, updated_at): LimitRow,
+) -> ConcurrencyLimit {
+    ConcurrencyLimit {
+        target,
+        max_concurrent: max_concurrent.clamp(0, u32::MAX as i64) as u32,
+        max_queue: max_queue.clamp(0, u32::MAX as i64) as u32,
+        queue_timeout_secs: queue_timeout_secs.clamp(0, u32::MAX as i64) as u32,
+        updated_at,
+    }
+}
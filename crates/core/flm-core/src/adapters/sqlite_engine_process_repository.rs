@@ -0,0 +1,178 @@
+//! SQLite-backed EngineProcessConfigRepository implementation (config.db).
+
+use crate::domain::engine::EngineProcessConfig;
+use crate::error::RepoError;
+use crate::ports::EngineProcessConfigRepository;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+#[derive(sqlx::FromRow)]
+struct EngineProcessRow {
+    engine_id: String,
+    kind: String,
+    binary_path: Option<String>,
+    model: Option<String>,
+    host: String,
+    port: i64,
+    args: String,
+    env: String,
+    restart_on_crash: bool,
+    max_restarts: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+const SELECT_ENGINE_PROCESSES: &str = "SELECT engine_id, kind, binary_path, model, host, port, \
+     args, env, restart_on_crash, max_restarts, created_at, updated_at FROM engine_processes";
+
+/// SQLite-based EngineProcessConfigRepository implementation.
+pub struct SqliteEngineProcessConfigRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteEngineProcessConfigRepository {
+    /// Create a new EngineProcessConfigRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+fn config_from_row(row: EngineProcessRow) -> Result<EngineProcessConfig, RepoError> {
+    let kind = row
+        .kind
+        .parse()
+        .map_err(|reason| RepoError::ValidationError { reason })?;
+    let port = u16::try_from(row.port).map_err(|_| RepoError::ValidationError {
+        reason: format!(
+            "Invalid port for engine process {}: {}",
+            row.engine_id, row.port
+        ),
+    })?;
+    let args = serde_json::from_str(&row.args).map_err(|e| RepoError::ValidationError {
+        reason: format!("Invalid args for engine process {}: {e}", row.engine_id),
+    })?;
+    let env = serde_json::from_str(&row.env).map_err(|e| RepoError::ValidationError {
+        reason: format!("Invalid env for engine process {}: {e}", row.engine_id),
+    })?;
+
+    Ok(EngineProcessConfig {
+        engine_id: row.engine_id,
+        kind,
+        binary_path: row.binary_path,
+        model: row.model,
+        host: row.host,
+        port,
+        args,
+        env,
+        restart_on_crash: row.restart_on_crash,
+        max_restarts: row.max_restarts.clamp(0, u32::MAX as i64) as u32,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
+
+#[async_trait::async_trait]
+impl EngineProcessConfigRepository for SqliteEngineProcessConfigRepository {
+    async fn list(&self) -> Result<Vec<EngineProcessConfig>, RepoError> {
+        let rows = sqlx::query_as::<_, EngineProcessRow>(&format!(
+            "{SELECT_ENGINE_PROCESSES} ORDER BY engine_id"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to list engine processes: {e}"),
+        })?;
+
+        rows.into_iter().map(config_from_row).collect()
+    }
+
+    async fn get(&self, engine_id: &str) -> Result<Option<EngineProcessConfig>, RepoError> {
+        let row = sqlx::query_as::<_, EngineProcessRow>(&format!(
+            "{SELECT_ENGINE_PROCESSES} WHERE engine_id = ?"
+        ))
+        .bind(engine_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load engine process: {e}"),
+        })?;
+
+        row.map(config_from_row).transpose()
+    }
+
+    async fn save(&self, config: &EngineProcessConfig) -> Result<(), RepoError> {
+        let args = serde_json::to_string(&config.args).map_err(|e| RepoError::ValidationError {
+            reason: format!("Failed to serialize engine args: {e}"),
+        })?;
+        let env = serde_json::to_string(&config.env).map_err(|e| RepoError::ValidationError {
+            reason: format!("Failed to serialize engine env: {e}"),
+        })?;
+
+        sqlx::query(
+            "INSERT INTO engine_processes (engine_id, kind, binary_path, model, host, port, \
+             args, env, restart_on_crash, max_restarts, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(engine_id) DO UPDATE SET kind = excluded.kind, \
+             binary_path = excluded.binary_path, model = excluded.model, \
+             host = excluded.host, port = excluded.port, args = excluded.args, \
+             env = excluded.env, restart_on_crash = excluded.restart_on_crash, \
+             max_restarts = excluded.max_restarts, updated_at = excluded.updated_at",
+        )
+        .bind(&config.engine_id)
+        .bind(config.kind.as_str())
+        .bind(config.binary_path.as_deref())
+        .bind(config.model.as_deref())
+        .bind(&config.host)
+        .bind(config.port as i64)
+        .bind(args)
+        .bind(env)
+        .bind(config.restart_on_crash)
+        .bind(config.max_restarts as i64)
+        .bind(&config.created_at)
+        .bind(&config.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save engine process: {e}"),
+        })?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, engine_id: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM engine_processes WHERE engine_id = ?")
+            .bind(engine_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to remove engine process: {e}"),
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
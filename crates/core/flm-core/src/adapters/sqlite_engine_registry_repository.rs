@@ -0,0 +1,202 @@
+//! SQLite-backed EngineRegistryRepository implementation (config.db).
+
+use crate::domain::engine::{EngineRegistration, EngineTlsConfig};
+use crate::domain::models::EngineId;
+use crate::error::RepoError;
+use crate::ports::EngineRegistryRepository;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+#[derive(sqlx::FromRow)]
+struct EngineRow {
+    id: String,
+    kind: String,
+    base_url: String,
+    auth_header_name: Option<String>,
+    timeout_secs: Option<i64>,
+    tls_ca_cert_path: Option<String>,
+    tls_insecure_skip_verify: bool,
+    bearer_token_in_keyring: bool,
+    model_capabilities: String,
+    created_at: String,
+    updated_at: String,
+}
+
+const SELECT_ENGINES: &str = "SELECT id, kind, base_url, auth_header_name, timeout_secs, \
+     tls_ca_cert_path, tls_insecure_skip_verify, bearer_token_in_keyring, model_capabilities, \
+     created_at, updated_at FROM engines";
+
+/// SQLite-based EngineRegistryRepository implementation.
+pub struct SqliteEngineRegistryRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteEngineRegistryRepository {
+    /// Create a new EngineRegistryRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+
+    /// Auth header values that earlier versions saved in plaintext, by engine ID
+    ///
+    /// Header values now live in the OS keyring. The CLI moves these there and
+    /// then drops them with [`Self::clear_legacy_auth_header_value`].
+    pub async fn legacy_auth_header_values(&self) -> Result<Vec<(EngineId, String)>, RepoError> {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT id, auth_header_value FROM engines WHERE auth_header_value IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load engine auth headers: {e}"),
+        })
+    }
+
+    /// Drop the plaintext auth header value of an engine
+    pub async fn clear_legacy_auth_header_value(&self, id: &str) -> Result<(), RepoError> {
+        sqlx::query("UPDATE engines SET auth_header_value = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to clear engine auth header: {e}"),
+            })?;
+        Ok(())
+    }
+}
+
+fn registration_from_row(row: EngineRow) -> Result<EngineRegistration, RepoError> {
+    let kind = row
+        .kind
+        .parse()
+        .map_err(|reason| RepoError::ValidationError { reason })?;
+    let model_capabilities =
+        serde_json::from_str(&row.model_capabilities).map_err(|e| RepoError::ValidationError {
+            reason: format!("Invalid model capabilities for engine {}: {e}", row.id),
+        })?;
+
+    Ok(EngineRegistration {
+        id: row.id,
+        kind,
+        base_url: row.base_url,
+        auth_header_name: row.auth_header_name,
+        timeout_secs: row.timeout_secs.map(|t| t.max(0) as u64),
+        tls: EngineTlsConfig {
+            ca_cert_path: row.tls_ca_cert_path,
+            insecure_skip_verify: row.tls_insecure_skip_verify,
+        },
+        bearer_token_in_keyring: row.bearer_token_in_keyring,
+        model_capabilities,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
+
+#[async_trait::async_trait]
+impl EngineRegistryRepository for SqliteEngineRegistryRepository {
+    async fn list(&self) -> Result<Vec<EngineRegistration>, RepoError> {
+        let rows = sqlx::query_as::<_, EngineRow>(&format!("{SELECT_ENGINES} ORDER BY id"))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to list engines: {e}"),
+            })?;
+
+        rows.into_iter().map(registration_from_row).collect()
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<EngineRegistration>, RepoError> {
+        let row = sqlx::query_as::<_, EngineRow>(&format!("{SELECT_ENGINES} WHERE id = ?"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to load engine: {e}"),
+            })?;
+
+        row.map(registration_from_row).transpose()
+    }
+
+    async fn save(&self, registration: &EngineRegistration) -> Result<(), RepoError> {
+        let model_capabilities =
+            serde_json::to_string(&registration.model_capabilities).map_err(|e| {
+                RepoError::ValidationError {
+                    reason: format!("Failed to serialize model capabilities: {e}"),
+                }
+            })?;
+
+        sqlx::query(
+            "INSERT INTO engines (id, kind, base_url, auth_header_name, auth_header_value, \
+             timeout_secs, tls_ca_cert_path, tls_insecure_skip_verify, bearer_token_in_keyring, \
+             model_capabilities, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, NULL, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET kind = excluded.kind, base_url = excluded.base_url, \
+             auth_header_name = excluded.auth_header_name, \
+             auth_header_value = NULL, \
+             timeout_secs = excluded.timeout_secs, tls_ca_cert_path = excluded.tls_ca_cert_path, \
+             tls_insecure_skip_verify = excluded.tls_insecure_skip_verify, \
+             bearer_token_in_keyring = excluded.bearer_token_in_keyring, \
+             model_capabilities = excluded.model_capabilities, \
+             updated_at = excluded.updated_at",
+        )
+        .bind(&registration.id)
+        .bind(registration.kind.as_str())
+        .bind(&registration.base_url)
+        .bind(registration.auth_header_name.as_deref())
+        .bind(
+            registration
+                .timeout_secs
+                .map(|t| t.min(i64::MAX as u64) as i64),
+        )
+        .bind(registration.tls.ca_cert_path.as_deref())
+        .bind(registration.tls.insecure_skip_verify)
+        .bind(registration.bearer_token_in_keyring)
+        .bind(model_capabilities)
+        .bind(&registration.created_at)
+        .bind(&registration.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save engine: {e}"),
+        })?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM engines WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to remove engine: {e}"),
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
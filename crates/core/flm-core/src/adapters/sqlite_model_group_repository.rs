@@ -0,0 +1,181 @@
+//! SQLite-backed ModelGroupRepository implementation (config.db).
+
+use crate::domain::models::{LoadBalanceStrategy, ModelGroup};
+use crate::error::RepoError;
+use crate::ports::ModelGroupRepository;
+use chrono::Utc;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+/// SQLite-based ModelGroupRepository implementation.
+#[derive(Clone)]
+pub struct SqliteModelGroupRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteModelGroupRepository {
+    /// Create a new ModelGroupRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelGroupRepository for SqliteModelGroupRepository {
+    async fn find_group(&self, name: &str) -> Result<Option<ModelGroup>, RepoError> {
+        let row = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT name, strategy, updated_at FROM model_groups WHERE name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load model group: {e}"),
+        })?;
+
+        let Some((name, strategy, updated_at)) = row else {
+            return Ok(None);
+        };
+
+        let strategy = strategy
+            .parse()
+            .map_err(|reason| RepoError::ValidationError { reason })?;
+
+        let members = sqlx::query_scalar::<_, String>(
+            "SELECT model_id FROM model_group_members WHERE group_name = ? ORDER BY position",
+        )
+        .bind(&name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load model group members: {e}"),
+        })?;
+
+        Ok(Some(ModelGroup {
+            name,
+            strategy,
+            members,
+            updated_at,
+        }))
+    }
+
+    async fn list_groups(&self) -> Result<Vec<ModelGroup>, RepoError> {
+        let names = sqlx::query_scalar::<_, String>("SELECT name FROM model_groups ORDER BY name")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to list model groups: {e}"),
+            })?;
+
+        let mut groups = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(group) = self.find_group(&name).await? {
+                groups.push(group);
+            }
+        }
+        Ok(groups)
+    }
+
+    async fn save_group(
+        &self,
+        name: &str,
+        strategy: LoadBalanceStrategy,
+        members: &[String],
+    ) -> Result<ModelGroup, RepoError> {
+        let now = Utc::now().to_rfc3339();
+
+        let mut tx = self.pool.begin().await.map_err(|e| RepoError::IoError {
+            reason: format!("Failed to begin transaction: {e}"),
+        })?;
+
+        sqlx::query(
+            "INSERT INTO model_groups (name, strategy, updated_at) VALUES (?, ?, ?) \
+             ON CONFLICT(name) DO UPDATE SET strategy = excluded.strategy, updated_at = excluded.updated_at",
+        )
+        .bind(name)
+        .bind(strategy.as_str())
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save model group: {e}"),
+        })?;
+
+        sqlx::query("DELETE FROM model_group_members WHERE group_name = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to replace model group members: {e}"),
+            })?;
+
+        for (position, model_id) in members.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO model_group_members (group_name, model_id, position) VALUES (?, ?, ?)",
+            )
+            .bind(name)
+            .bind(model_id)
+            .bind(position as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to insert model group member: {e}"),
+            })?;
+        }
+
+        tx.commit().await.map_err(|e| RepoError::IoError {
+            reason: format!("Failed to commit model group: {e}"),
+        })?;
+
+        self.find_group(name)
+            .await?
+            .ok_or_else(|| RepoError::NotFound {
+                key: name.to_string(),
+            })
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<bool, RepoError> {
+        sqlx::query("DELETE FROM model_group_members WHERE group_name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to delete model group members: {e}"),
+            })?;
+
+        let result = sqlx::query("DELETE FROM model_groups WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to delete model group: {e}"),
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,103 @@
+//! SQLite-backed ModelProfileRepository implementation (config.db).
+
+use crate::domain::models::ModelProfile;
+use crate::error::RepoError;
+use crate::ports::ModelProfileRepository;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+
+type ModelProfileRow = (String, String, String, String, String, i64, String);
+
+/// SQLite-based ModelProfileRepository implementation.
+pub struct SqliteModelProfileRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteModelProfileRepository {
+    /// Create a new ModelProfileRepository with a SQLite connection.
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, RepoError> {
+        let path_str = db_path
+            .as_ref()
+            .to_str()
+            .ok_or_else(|| RepoError::IoError {
+                reason: "Invalid database path (non-UTF8)".to_string(),
+            })?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path_str)
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .map_err(|e| RepoError::IoError {
+                reason: format!("Failed to connect to config.db: {e}"),
+            })?;
+
+        sqlx::migrate!("../flm-core/migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| RepoError::MigrationFailed {
+                reason: format!("Config DB migration failed: {e}"),
+            })?;
+
+        Ok(Self { pool })
+    }
+}
+
+fn profile_from_row(row: ModelProfileRow) -> Result<ModelProfile, RepoError> {
+    let (id, engine_id, model_id, label, parameters_json, version, updated_at) = row;
+    let parameters = serde_json::from_str(&parameters_json).map_err(|e| RepoError::IoError {
+        reason: format!("Failed to parse profile parameters: {e}"),
+    })?;
+
+    Ok(ModelProfile {
+        id,
+        engine_id,
+        model_id,
+        label,
+        parameters,
+        version,
+        updated_at,
+    })
+}
+
+#[async_trait::async_trait]
+impl ModelProfileRepository for SqliteModelProfileRepository {
+    async fn find_by_label(&self, label: &str) -> Result<Option<ModelProfile>, RepoError> {
+        let row = sqlx::query_as::<_, ModelProfileRow>(
+            "SELECT id, engine_id, model_id, label, parameters_json, version, updated_at \
+             FROM model_profiles WHERE label = ? ORDER BY updated_at DESC LIMIT 1",
+        )
+        .bind(label)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load model profile: {e}"),
+        })?;
+
+        row.map(profile_from_row).transpose()
+    }
+
+    async fn find_for_model(
+        &self,
+        engine_id: &str,
+        model_id: &str,
+    ) -> Result<Option<ModelProfile>, RepoError> {
+        let row = sqlx::query_as::<_, ModelProfileRow>(
+            "SELECT id, engine_id, model_id, label, parameters_json, version, updated_at \
+             FROM model_profiles WHERE engine_id = ? AND model_id = ? \
+             ORDER BY (label = 'default') DESC, updated_at DESC LIMIT 1",
+        )
+        .bind(engine_id)
+        .bind(model_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to load model profile: {e}"),
+        })?;
+
+        row.map(profile_from_row).transpose()
+    }
+}
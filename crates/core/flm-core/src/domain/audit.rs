@@ -0,0 +1,765 @@
+//! Audit log hash chain
+//!
+//! Every `audit_logs` row carries the hash of the previous chained row
+//! (`prev_hash`) and a hash over its own fields and `prev_hash`
+//! (`entry_hash`), so editing, deleting or reordering rows breaks the chain.
+//! With an HMAC key kept outside the database, rows cannot be re-hashed by
+//! someone who can only write the file.
+//!
+//! Entries can also be forwarded to external sinks ([`AuditSink`]).
+//!
+//! See `docs/CORE_API.md` section 2 and `docs/specs/DB_SCHEMA.md`.
+
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+
+/// `prev_hash` of the first chained row
+pub const AUDIT_CHAIN_GENESIS: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Hash algorithm recorded in `audit_logs.hash_alg`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditChainAlgorithm {
+    /// Plain SHA-256 (detects edits unless the whole chain suffix is rewritten)
+    Sha256,
+    /// HMAC-SHA256 with a key that is not stored in security.db
+    HmacSha256,
+}
+
+impl AuditChainAlgorithm {
+    /// Algorithm used when writing with the given key
+    pub fn for_key(key: Option<&[u8]>) -> Self {
+        if key.is_some() {
+            Self::HmacSha256
+        } else {
+            Self::Sha256
+        }
+    }
+
+    /// Wire name stored in `security.db`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::HmacSha256 => "hmac-sha256",
+        }
+    }
+}
+
+impl std::str::FromStr for AuditChainAlgorithm {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sha256" => Ok(Self::Sha256),
+            "hmac-sha256" => Ok(Self::HmacSha256),
+            other => Err(format!("unknown audit chain algorithm '{other}'")),
+        }
+    }
+}
+
+/// Fields of one `audit_logs` row covered by the chain hash
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub request_id: String,
+    pub api_key_id: Option<String>,
+    pub endpoint: String,
+    pub status: i64,
+    pub latency_ms: Option<i64>,
+    pub event_type: Option<String>,
+    pub severity: Option<String>,
+    pub ip: Option<String>,
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+impl AuditLogEntry {
+    /// Hash of this entry linked to `prev_hash` (lowercase hex)
+    ///
+    /// The fields are encoded as a JSON array so that no two different rows
+    /// share an encoding.
+    pub fn chain_hash(&self, prev_hash: &str, key: Option<&[u8]>) -> String {
+        let fields = serde_json::json!([
+            self.id,
+            self.request_id,
+            self.api_key_id,
+            self.endpoint,
+            self.status,
+            self.latency_ms,
+            self.event_type,
+            self.severity,
+            self.ip,
+            self.details,
+            self.created_at,
+        ]);
+        let mut message = Vec::with_capacity(prev_hash.len() + 256);
+        message.extend_from_slice(prev_hash.as_bytes());
+        message.push(b'\n');
+        message.extend_from_slice(fields.to_string().as_bytes());
+
+        match key {
+            Some(key) => {
+                hex(hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), &message).as_ref())
+            }
+            None => hex(digest::digest(&digest::SHA256, &message).as_ref()),
+        }
+    }
+}
+
+/// An `audit_logs` row with its chain columns (`None` for rows written
+/// before the chain was introduced)
+///
+/// Serialized flat (entry fields followed by the chain columns); this is the
+/// event format of audit sinks and `flm security audit-logs export`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainedAuditLog {
+    #[serde(flatten)]
+    pub entry: AuditLogEntry,
+    pub prev_hash: Option<String>,
+    pub entry_hash: Option<String>,
+    pub hash_alg: Option<String>,
+}
+
+/// Why the chain is broken at a row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditChainBreakKind {
+    /// The row's fields no longer match its hash
+    Altered,
+    /// Rows between the previous chained row and this one are missing
+    Gap,
+    /// `prev_hash` does not match the previous row's hash
+    LinkMismatch,
+    /// A row without a hash after the chain started
+    Unchained,
+    /// A plain SHA-256 row after HMAC rows
+    Downgraded,
+    /// An HMAC row but no key to check it with
+    HmacKeyRequired,
+}
+
+impl AuditChainBreakKind {
+    /// Wire name used in CLI output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Altered => "altered",
+            Self::Gap => "gap",
+            Self::LinkMismatch => "link_mismatch",
+            Self::Unchained => "unchained",
+            Self::Downgraded => "downgraded",
+            Self::HmacKeyRequired => "hmac_key_required",
+        }
+    }
+}
+
+/// First broken link found by [`AuditChainVerifier`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChainBreak {
+    /// `audit_logs.id` of the first row that fails
+    pub id: i64,
+    pub kind: AuditChainBreakKind,
+    /// Last row that verified, if any
+    pub previous_id: Option<i64>,
+    pub reason: String,
+}
+
+/// Result of verifying the audit log chain
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChainReport {
+    /// Rows examined (up to and including the first broken one)
+    pub rows: u64,
+    /// Rows written before the chain was introduced
+    pub legacy_rows: u64,
+    /// Rows whose hash and link verified
+    pub verified_rows: u64,
+    /// Verified rows hashed with HMAC-SHA256
+    pub hmac_rows: u64,
+    /// Last verified row and its hash
+    pub last_id: Option<i64>,
+    pub last_hash: Option<String>,
+    /// Entries up to this id were removed by retention pruning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned_through_id: Option<i64>,
+    pub first_break: Option<AuditChainBreak>,
+}
+
+impl AuditChainReport {
+    /// No broken link was found
+    pub fn is_intact(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+/// Where the chain continues after old entries were pruned
+///
+/// Written by retention pruning (`audit_chain_anchors` table) with the hash
+/// of the last deleted entry, so the first remaining entry can still be
+/// checked against it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChainAnchor {
+    /// Entries up to and including this `audit_logs.id` were pruned
+    pub pruned_through_id: i64,
+    /// Hash of that entry (`None` if it predates the chain)
+    pub entry_hash: Option<String>,
+    pub hash_alg: Option<String>,
+}
+
+/// Checks audit log rows in ascending `id` order, stopping at the first
+/// broken link
+pub struct AuditChainVerifier<'a> {
+    key: Option<&'a [u8]>,
+    report: AuditChainReport,
+    hmac_seen: bool,
+}
+
+impl<'a> AuditChainVerifier<'a> {
+    /// Verifier for a chain starting at [`AUDIT_CHAIN_GENESIS`]
+    pub fn new(key: Option<&'a [u8]>) -> Self {
+        Self {
+            key,
+            report: AuditChainReport::default(),
+            hmac_seen: false,
+        }
+    }
+
+    /// Verifier for the entries left after retention pruning
+    pub fn from_anchor(key: Option<&'a [u8]>, anchor: &AuditChainAnchor) -> Self {
+        let mut verifier = Self::new(key);
+        verifier.report.pruned_through_id = Some(anchor.pruned_through_id);
+        if let Some(entry_hash) = &anchor.entry_hash {
+            verifier.report.last_id = Some(anchor.pruned_through_id);
+            verifier.report.last_hash = Some(entry_hash.clone());
+            verifier.hmac_seen =
+                anchor.hash_alg.as_deref() == Some(AuditChainAlgorithm::HmacSha256.as_str());
+        }
+        verifier
+    }
+
+    /// Check the next row; returns `false` once the chain is broken
+    pub fn push(&mut self, row: &ChainedAuditLog) -> bool {
+        if self.report.first_break.is_some() {
+            return false;
+        }
+        self.report.rows += 1;
+        let id = row.entry.id;
+        let previous_id = self.report.last_id;
+
+        let Some(entry_hash) = row.entry_hash.as_deref() else {
+            if previous_id.is_none() {
+                self.report.legacy_rows += 1;
+                return true;
+            }
+            return self.fail(
+                id,
+                AuditChainBreakKind::Unchained,
+                "entry has no hash after the chain started".to_string(),
+            );
+        };
+
+        let algorithm = match row.hash_alg.as_deref().unwrap_or_default().parse() {
+            Ok(algorithm) => algorithm,
+            Err(reason) => return self.fail(id, AuditChainBreakKind::Altered, reason),
+        };
+        let key = match (algorithm, self.key) {
+            (AuditChainAlgorithm::HmacSha256, None) => {
+                return self.fail(
+                    id,
+                    AuditChainBreakKind::HmacKeyRequired,
+                    "entry is HMAC-signed; an HMAC key is required to verify it".to_string(),
+                )
+            }
+            (AuditChainAlgorithm::HmacSha256, key) => key,
+            (AuditChainAlgorithm::Sha256, _) if self.hmac_seen => {
+                return self.fail(
+                    id,
+                    AuditChainBreakKind::Downgraded,
+                    "entry is hashed without HMAC after HMAC-signed entries".to_string(),
+                )
+            }
+            (AuditChainAlgorithm::Sha256, _) => None,
+        };
+
+        let expected_prev = self
+            .report
+            .last_hash
+            .as_deref()
+            .unwrap_or(AUDIT_CHAIN_GENESIS);
+        if let Some(previous_id) = previous_id.filter(|previous| id > previous + 1) {
+            return self.fail(
+                id,
+                AuditChainBreakKind::Gap,
+                format!("entries {} to {} are missing", previous_id + 1, id - 1),
+            );
+        }
+        if row.prev_hash.as_deref() != Some(expected_prev) {
+            return self.fail(
+                id,
+                AuditChainBreakKind::LinkMismatch,
+                match previous_id {
+                    Some(previous_id) => {
+                        format!("previous hash does not match entry {previous_id}")
+                    }
+                    None => "first chained entry does not start the chain".to_string(),
+                },
+            );
+        }
+        if row.entry.chain_hash(expected_prev, key) != entry_hash {
+            return self.fail(
+                id,
+                AuditChainBreakKind::Altered,
+                "entry does not match its hash".to_string(),
+            );
+        }
+
+        if algorithm == AuditChainAlgorithm::HmacSha256 {
+            self.hmac_seen = true;
+            self.report.hmac_rows += 1;
+        }
+        self.report.verified_rows += 1;
+        self.report.last_id = Some(id);
+        self.report.last_hash = Some(entry_hash.to_string());
+        true
+    }
+
+    /// Report for the rows pushed so far
+    pub fn finish(self) -> AuditChainReport {
+        self.report
+    }
+
+    fn fail(&mut self, id: i64, kind: AuditChainBreakKind, reason: String) -> bool {
+        self.report.first_break = Some(AuditChainBreak {
+            id,
+            kind,
+            previous_id: self.report.last_id,
+            reason,
+        });
+        false
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// External destination for audit log entries (`audit_sinks` in config.db)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSink {
+    /// Sink ID (also the keyring account of a webhook token)
+    pub id: String,
+    pub config: AuditSinkConfig,
+    pub updated_at: String,
+}
+
+/// Transport of a syslog sink
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogTransport {
+    #[default]
+    Udp,
+    /// Octet-counted frames (RFC 6587)
+    Tcp,
+}
+
+impl SyslogTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Udp => "udp",
+            Self::Tcp => "tcp",
+        }
+    }
+}
+
+impl std::str::FromStr for SyslogTransport {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "udp" => Ok(Self::Udp),
+            "tcp" => Ok(Self::Tcp),
+            other => Err(format!("unknown syslog transport '{other}' (udp or tcp)")),
+        }
+    }
+}
+
+/// Where and how a sink delivers entries (stored as JSON in
+/// `audit_sinks.config_json`)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AuditSinkConfig {
+    /// RFC 5424 messages with the JSON entry as MSG
+    Syslog {
+        /// `host:port`
+        address: String,
+        #[serde(default)]
+        transport: SyslogTransport,
+        /// Syslog facility (default 13, log audit)
+        #[serde(default = "default_syslog_facility")]
+        facility: u8,
+        /// APP-NAME header (default `flm-proxy`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        app_name: Option<String>,
+    },
+    /// One JSON entry per line, rotated to `path.1` … `path.{max_files}`
+    Jsonl {
+        path: String,
+        #[serde(default = "default_jsonl_max_bytes")]
+        max_bytes: u64,
+        #[serde(default = "default_jsonl_max_files")]
+        max_files: u32,
+    },
+    /// JSON arrays of entries POSTed to `url`
+    Webhook {
+        url: String,
+        #[serde(default = "default_webhook_batch_size")]
+        batch_size: u32,
+        #[serde(default = "default_webhook_flush_interval_secs")]
+        flush_interval_secs: u32,
+        /// Retries of a failed batch (connection errors, 408, 429, 5xx)
+        #[serde(default = "default_webhook_max_retries")]
+        max_retries: u32,
+        /// Authorization scheme for the keyring token (default `Bearer`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_scheme: Option<String>,
+    },
+}
+
+pub const DEFAULT_SYSLOG_FACILITY: u8 = 13;
+pub const DEFAULT_JSONL_MAX_BYTES: u64 = 10 * 1024 * 1024;
+pub const DEFAULT_JSONL_MAX_FILES: u32 = 5;
+pub const DEFAULT_WEBHOOK_BATCH_SIZE: u32 = 100;
+pub const DEFAULT_WEBHOOK_FLUSH_INTERVAL_SECS: u32 = 5;
+pub const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 5;
+
+fn default_syslog_facility() -> u8 {
+    DEFAULT_SYSLOG_FACILITY
+}
+
+fn default_jsonl_max_bytes() -> u64 {
+    DEFAULT_JSONL_MAX_BYTES
+}
+
+fn default_jsonl_max_files() -> u32 {
+    DEFAULT_JSONL_MAX_FILES
+}
+
+fn default_webhook_batch_size() -> u32 {
+    DEFAULT_WEBHOOK_BATCH_SIZE
+}
+
+fn default_webhook_flush_interval_secs() -> u32 {
+    DEFAULT_WEBHOOK_FLUSH_INTERVAL_SECS
+}
+
+fn default_webhook_max_retries() -> u32 {
+    DEFAULT_WEBHOOK_MAX_RETRIES
+}
+
+impl AuditSinkConfig {
+    /// Value of the `audit_sinks.kind` column
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Syslog { .. } => "syslog",
+            Self::Jsonl { .. } => "jsonl",
+            Self::Webhook { .. } => "webhook",
+        }
+    }
+
+    /// Reject settings the proxy could not use
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Syslog {
+                address, facility, ..
+            } => {
+                let Some((host, port)) = address.rsplit_once(':') else {
+                    return Err(format!("syslog address '{address}' must be host:port"));
+                };
+                if host.is_empty() || port.parse::<u16>().map_or(true, |port| port == 0) {
+                    return Err(format!("syslog address '{address}' must be host:port"));
+                }
+                if *facility > 23 {
+                    return Err(format!("syslog facility {facility} must be 0-23"));
+                }
+            }
+            Self::Jsonl {
+                path,
+                max_bytes,
+                max_files,
+            } => {
+                if path.trim().is_empty() {
+                    return Err("jsonl path must not be empty".to_string());
+                }
+                if *max_bytes == 0 {
+                    return Err("jsonl max_bytes must be greater than 0".to_string());
+                }
+                if *max_files > 100 {
+                    return Err(format!("jsonl max_files {max_files} must be 0-100"));
+                }
+            }
+            Self::Webhook {
+                url,
+                batch_size,
+                flush_interval_secs,
+                ..
+            } => {
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(format!("webhook url '{url}' must be http(s)"));
+                }
+                if *batch_size == 0 {
+                    return Err("webhook batch_size must be greater than 0".to_string());
+                }
+                if *flush_interval_secs == 0 {
+                    return Err("webhook flush_interval_secs must be greater than 0".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn chain(count: i64, key: Option<&[u8]>) -> Vec<ChainedAuditLog> {
+        chain_from(1, count, key)
+    }
+
+    fn chain_from(first_id: i64, count: i64, key: Option<&[u8]>) -> Vec<ChainedAuditLog> {
+        let mut prev = AUDIT_CHAIN_GENESIS.to_string();
+        (first_id..first_id + count)
+            .map(|id| {
+                let entry = AuditLogEntry {
+                    id,
+                    request_id: format!("req-{id}"),
+                    endpoint: "/v1/chat/completions".to_string(),
+                    status: 200,
+                    severity: Some("low".to_string()),
+                    created_at: "2026-10-18 09:00:00".to_string(),
+                    ..Default::default()
+                };
+                let hash = entry.chain_hash(&prev, key);
+                let row = ChainedAuditLog {
+                    entry,
+                    prev_hash: Some(prev.clone()),
+                    entry_hash: Some(hash.clone()),
+                    hash_alg: Some(AuditChainAlgorithm::for_key(key).as_str().to_string()),
+                };
+                prev = hash;
+                row
+            })
+            .collect()
+    }
+
+    fn verify(rows: &[ChainedAuditLog], key: Option<&[u8]>) -> AuditChainReport {
+        let mut verifier = AuditChainVerifier::new(key);
+        for row in rows {
+            if !verifier.push(row) {
+                break;
+            }
+        }
+        verifier.finish()
+    }
+
+    fn break_at(report: &AuditChainReport) -> (i64, AuditChainBreakKind) {
+        let first_break = report.first_break.as_ref().expect("chain should be broken");
+        (first_break.id, first_break.kind)
+    }
+
+    #[test]
+    fn test_audit_chain_intact() {
+        let report = verify(&chain(5, None), None);
+        assert!(report.is_intact());
+        assert_eq!(report.verified_rows, 5);
+        assert_eq!(report.last_id, Some(5));
+
+        let report = verify(&chain(3, Some(KEY)), Some(KEY));
+        assert!(report.is_intact());
+        assert_eq!(report.hmac_rows, 3);
+
+        // Rows from before the migration precede the chain
+        let legacy = ChainedAuditLog {
+            entry: AuditLogEntry {
+                id: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rows: Vec<_> = std::iter::once(legacy)
+            .chain(chain_from(2, 2, None))
+            .collect();
+        let report = verify(&rows, None);
+        assert!(report.is_intact(), "{report:?}");
+        assert_eq!((report.legacy_rows, report.verified_rows), (1, 2));
+    }
+
+    #[test]
+    fn test_audit_chain_detects_tampering() {
+        // Edited field
+        let mut rows = chain(5, None);
+        rows[2].entry.status = 500;
+        assert_eq!(
+            break_at(&verify(&rows, None)),
+            (3, AuditChainBreakKind::Altered)
+        );
+
+        // Edited and re-hashed row breaks the next link
+        let mut rows = chain(5, None);
+        rows[2].entry.status = 500;
+        let prev = rows[2].prev_hash.clone().unwrap();
+        rows[2].entry_hash = Some(rows[2].entry.chain_hash(&prev, None));
+        assert_eq!(
+            break_at(&verify(&rows, None)),
+            (4, AuditChainBreakKind::LinkMismatch)
+        );
+
+        // Deleted row
+        let mut rows = chain(5, None);
+        rows.remove(1);
+        let report = verify(&rows, None);
+        assert_eq!(break_at(&report), (3, AuditChainBreakKind::Gap));
+        assert_eq!(report.verified_rows, 1);
+
+        // Deleted first rows
+        let rows = chain(5, None);
+        assert_eq!(
+            break_at(&verify(&rows[2..], None)),
+            (3, AuditChainBreakKind::LinkMismatch)
+        );
+
+        // Hash removed
+        let mut rows = chain(3, None);
+        rows[1].entry_hash = None;
+        assert_eq!(
+            break_at(&verify(&rows, None)),
+            (2, AuditChainBreakKind::Unchained)
+        );
+    }
+
+    #[test]
+    fn test_audit_chain_hmac() {
+        let rows = chain(3, Some(KEY));
+        assert_eq!(
+            break_at(&verify(&rows, None)),
+            (1, AuditChainBreakKind::HmacKeyRequired)
+        );
+        assert_eq!(
+            break_at(&verify(&rows, Some(b"another key"))),
+            (1, AuditChainBreakKind::Altered)
+        );
+
+        // A suffix rewritten without the key is a downgrade
+        let mut rows = chain(3, Some(KEY));
+        let prev = rows[1].entry_hash.clone().unwrap();
+        rows[2].entry.status = 500;
+        rows[2].entry_hash = Some(rows[2].entry.chain_hash(&prev, None));
+        rows[2].hash_alg = Some("sha256".to_string());
+        assert_eq!(
+            break_at(&verify(&rows, Some(KEY))),
+            (3, AuditChainBreakKind::Downgraded)
+        );
+    }
+
+    #[test]
+    fn test_audit_chain_continues_from_anchor() {
+        let rows = chain(5, Some(KEY));
+        let anchor = AuditChainAnchor {
+            pruned_through_id: 2,
+            entry_hash: rows[1].entry_hash.clone(),
+            hash_alg: rows[1].hash_alg.clone(),
+        };
+        let verify_after = |anchor: &AuditChainAnchor, rows: &[ChainedAuditLog]| {
+            let mut verifier = AuditChainVerifier::from_anchor(Some(KEY), anchor);
+            for row in rows {
+                if !verifier.push(row) {
+                    break;
+                }
+            }
+            verifier.finish()
+        };
+
+        let report = verify_after(&anchor, &rows[2..]);
+        assert!(report.is_intact(), "{report:?}");
+        assert_eq!(report.verified_rows, 3);
+        assert_eq!(report.pruned_through_id, Some(2));
+
+        // More rows missing than the anchor accounts for
+        let report = verify_after(&anchor, &rows[3..]);
+        assert_eq!(break_at(&report), (4, AuditChainBreakKind::Gap));
+
+        // The pruned entries were HMAC-signed
+        let unsigned = chain_from(3, 1, None);
+        let report = verify_after(&anchor, &unsigned);
+        assert_eq!(break_at(&report), (3, AuditChainBreakKind::Downgraded));
+    }
+
+    #[test]
+    fn test_audit_sink_config_serde_and_validation() {
+        let config: AuditSinkConfig =
+            serde_json::from_str(r#"{"kind":"syslog","address":"127.0.0.1:514"}"#).unwrap();
+        assert_eq!(
+            config,
+            AuditSinkConfig::Syslog {
+                address: "127.0.0.1:514".to_string(),
+                transport: SyslogTransport::Udp,
+                facility: DEFAULT_SYSLOG_FACILITY,
+                app_name: None,
+            }
+        );
+        assert_eq!(config.kind(), "syslog");
+        assert!(config.validate().is_ok());
+
+        let webhook: AuditSinkConfig =
+            serde_json::from_str(r#"{"kind":"webhook","url":"https://siem.example.com/in"}"#)
+                .unwrap();
+        let json = serde_json::to_value(&webhook).unwrap();
+        assert_eq!(json["kind"], "webhook");
+        assert_eq!(json["batch_size"], DEFAULT_WEBHOOK_BATCH_SIZE);
+        assert!(json.get("auth_scheme").is_none());
+
+        for (invalid, reason) in [
+            (r#"{"kind":"syslog","address":"localhost"}"#, "host:port"),
+            (
+                r#"{"kind":"syslog","address":"localhost:514","facility":24}"#,
+                "0-23",
+            ),
+            (r#"{"kind":"jsonl","path":" "}"#, "empty"),
+            (
+                r#"{"kind":"jsonl","path":"a.jsonl","max_bytes":0}"#,
+                "max_bytes",
+            ),
+            (r#"{"kind":"webhook","url":"ftp://example.com"}"#, "http(s)"),
+            (
+                r#"{"kind":"webhook","url":"http://x","batch_size":0}"#,
+                "batch_size",
+            ),
+        ] {
+            let config: AuditSinkConfig = serde_json::from_str(invalid).unwrap();
+            let error = config.validate().unwrap_err();
+            assert!(error.contains(reason), "{invalid}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_chained_audit_log_serializes_flat() {
+        let row = &chain(1, None)[0];
+        let json = serde_json::to_value(row).unwrap();
+        assert_eq!(json["id"], 1);
+        assert_eq!(json["prev_hash"], AUDIT_CHAIN_GENESIS);
+        assert_eq!(json["hash_alg"], "sha256");
+        assert!(json.get("entry").is_none());
+        assert_eq!(
+            serde_json::from_value::<ChainedAuditLog>(json).unwrap(),
+            *row
+        );
+    }
+}
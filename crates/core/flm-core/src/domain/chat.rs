@@ -4,6 +4,7 @@
 
 use super::models::{EngineId, ModelId};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Chat message role
 ///
@@ -35,6 +36,78 @@ pub struct ChatMessage {
     /// Optional multimodal attachments associated with this message
     #[serde(default)]
     pub attachments: Vec<MultimodalAttachment>,
+    /// Tool calls requested by the assistant (assistant messages only)
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    /// ID of the tool call this message answers (tool messages only)
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+/// Tool (function) definition
+///
+/// Describes a function the model may call.
+/// Used in `ChatRequest::tools`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ToolDefinition {
+    /// Function name (`[a-zA-Z0-9_-]`, max 64 characters)
+    pub name: String,
+    /// Optional human-readable description shown to the model
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema describing the function arguments
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// Tool choice
+///
+/// Controls whether and which tool the model should call.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides whether to call a tool
+    Auto,
+    /// The model must not call any tool
+    None,
+    /// The model must call at least one tool
+    Required,
+    /// The model must call the named function
+    Function {
+        /// Function name (must match a `ToolDefinition::name`)
+        name: String,
+    },
+}
+
+/// Tool call
+///
+/// A complete function call emitted by the assistant.
+/// Carried in `ChatMessage::tool_calls`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolCall {
+    /// Call identifier, echoed back in the tool result message
+    pub id: String,
+    /// Function name
+    pub name: String,
+    /// Function arguments as a JSON-encoded string
+    pub arguments: String,
+}
+
+/// Tool call delta
+///
+/// Incremental fragment of a tool call in a streaming response.
+/// Fragments with the same `index` belong to the same call; `id` and `name`
+/// are usually only present in the first fragment.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolCallDelta {
+    /// Position of the call within the assistant message
+    pub index: u32,
+    /// Call identifier (first fragment only)
+    pub id: Option<String>,
+    /// Function name (first fragment only)
+    pub name: Option<String>,
+    /// Fragment of the JSON-encoded arguments
+    pub arguments: Option<String>,
 }
 
 /// Multimodal attachment kind
@@ -105,6 +178,123 @@ pub struct ChatRequest {
     /// Requested output modalities (text default, audio optional)
     #[serde(default)]
     pub requested_modalities: Vec<ResponseModality>,
+    /// Tools the model may call
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
+    /// Tool selection strategy (engine default when `None`)
+    #[serde(default)]
+    pub tool_choice: Option<ToolChoice>,
+    /// Constrains the output format (free text when `None`)
+    #[serde(default)]
+    pub response_format: Option<ResponseFormat>,
+    /// Additional sampling controls (engine defaults when unset)
+    #[serde(default)]
+    pub sampling: SamplingParams,
+}
+
+/// Sampling parameters
+///
+/// Generation controls beyond `temperature` / `max_tokens`. Unset fields use
+/// the engine default. Adapters translate these to native option names and
+/// return `EngineError::UnsupportedOperation` for any they cannot honour.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SamplingParams {
+    /// Nucleus sampling probability mass (0.0-1.0)
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    /// Sample only from the K most likely tokens
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    /// Seed for reproducible sampling
+    #[serde(default)]
+    pub seed: Option<i64>,
+    /// Penalty for tokens already present in the output (-2.0-2.0)
+    #[serde(default)]
+    pub presence_penalty: Option<f32>,
+    /// Penalty proportional to token frequency in the output (-2.0-2.0)
+    #[serde(default)]
+    pub frequency_penalty: Option<f32>,
+    /// Multiplicative repetition penalty (1.0 = disabled)
+    #[serde(default)]
+    pub repeat_penalty: Option<f32>,
+    /// Number of choices to generate (1 when `None`)
+    #[serde(default)]
+    pub n: Option<u32>,
+    /// Token ID to bias (-100-100) applied to its logit
+    #[serde(default)]
+    pub logit_bias: BTreeMap<String, f32>,
+    /// Whether to return log probabilities of the output tokens
+    #[serde(default)]
+    pub logprobs: bool,
+    /// Number of most likely alternatives returned per token (requires `logprobs`)
+    #[serde(default)]
+    pub top_logprobs: Option<u32>,
+}
+
+impl SamplingParams {
+    /// Fill every unset field from `defaults`, keeping values already set here
+    pub fn or_defaults(self, defaults: &SamplingParams) -> SamplingParams {
+        SamplingParams {
+            top_p: self.top_p.or(defaults.top_p),
+            top_k: self.top_k.or(defaults.top_k),
+            seed: self.seed.or(defaults.seed),
+            presence_penalty: self.presence_penalty.or(defaults.presence_penalty),
+            frequency_penalty: self.frequency_penalty.or(defaults.frequency_penalty),
+            repeat_penalty: self.repeat_penalty.or(defaults.repeat_penalty),
+            n: self.n.or(defaults.n),
+            logit_bias: if self.logit_bias.is_empty() {
+                defaults.logit_bias.clone()
+            } else {
+                self.logit_bias
+            },
+            logprobs: self.logprobs || defaults.logprobs,
+            top_logprobs: self.top_logprobs.or(defaults.top_logprobs),
+        }
+    }
+}
+
+/// Log probability of a generated token
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TokenLogprob {
+    /// Token text
+    pub token: String,
+    /// Natural log probability of the token
+    pub logprob: f32,
+    /// Most likely alternatives at this position (when `top_logprobs` was requested)
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// Alternative token at a given position
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TopLogprob {
+    /// Token text
+    pub token: String,
+    /// Natural log probability of the token
+    pub logprob: f32,
+}
+
+/// Structured output format
+///
+/// Mirrors OpenAI's `response_format`. Adapters map this to the engine's
+/// native mechanism (Ollama `format`, vLLM guided decoding, llama.cpp grammar).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text (no constraint)
+    Text,
+    /// Any syntactically valid JSON object
+    JsonObject,
+    /// JSON conforming to the given schema
+    JsonSchema {
+        /// Schema name (informational)
+        name: String,
+        /// JSON Schema the output must satisfy
+        schema: serde_json::Value,
+        /// Whether the engine should enforce the schema strictly
+        #[serde(default)]
+        strict: bool,
+    },
 }
 
 /// Requested response modality
@@ -124,13 +314,45 @@ pub enum ResponseModality {
 }
 
 /// Audio response format options
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AudioResponseFormat {
     Wav,
     Mp3,
     Ogg,
     Flac,
+    /// Opus in an Ogg container
+    Opus,
+    Aac,
+    /// Raw 16-bit little-endian PCM (24kHz mono for OpenAI-compatible servers)
+    Pcm,
+}
+
+impl AudioResponseFormat {
+    /// Wire name used by OpenAI-compatible audio APIs (`response_format`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioResponseFormat::Wav => "wav",
+            AudioResponseFormat::Mp3 => "mp3",
+            AudioResponseFormat::Ogg => "ogg",
+            AudioResponseFormat::Flac => "flac",
+            AudioResponseFormat::Opus => "opus",
+            AudioResponseFormat::Aac => "aac",
+            AudioResponseFormat::Pcm => "pcm",
+        }
+    }
+
+    /// HTTP content type for audio in this format
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            AudioResponseFormat::Wav => "audio/wav",
+            AudioResponseFormat::Mp3 => "audio/mpeg",
+            AudioResponseFormat::Ogg | AudioResponseFormat::Opus => "audio/ogg",
+            AudioResponseFormat::Flac => "audio/flac",
+            AudioResponseFormat::Aac => "audio/aac",
+            AudioResponseFormat::Pcm => "audio/pcm",
+        }
+    }
 }
 
 /// Usage statistics
@@ -141,7 +363,8 @@ pub enum AudioResponseFormat {
 pub struct UsageStats {
     /// Number of tokens in the prompt
     pub prompt_tokens: u32,
-    /// Number of tokens in the completion
+    /// Number of tokens in the completion (absent from OpenAI embedding responses)
+    #[serde(default)]
     pub completion_tokens: u32,
     /// Total tokens used
     pub total_tokens: u32,
@@ -155,11 +378,14 @@ pub struct UsageStats {
 pub struct ChatResponse {
     /// Token usage statistics
     pub usage: UsageStats,
-    /// Generated messages (typically one assistant message)
+    /// Generated messages (one assistant message per requested choice)
     pub messages: Vec<ChatMessage>,
     /// Optional audio outputs (Responses API)
     #[serde(default)]
     pub audio: Vec<AudioResponseChunk>,
+    /// Token log probabilities, index-aligned with `messages` (empty unless requested)
+    #[serde(default)]
+    pub logprobs: Vec<Vec<TokenLogprob>>,
 }
 
 /// Chat stream chunk
@@ -177,6 +403,12 @@ pub struct ChatStreamChunk {
     /// Optional audio output emitted during streaming
     #[serde(default)]
     pub audio: Vec<AudioResponseChunk>,
+    /// Tool call fragments emitted during streaming
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallDelta>,
+    /// Log probabilities of the tokens in this delta (empty unless requested)
+    #[serde(default)]
+    pub logprobs: Vec<TokenLogprob>,
 }
 
 /// Audio response chunk (non-streaming or streaming)
@@ -190,6 +422,76 @@ pub struct AudioResponseChunk {
     pub transcript: Option<String>,
 }
 
+/// Text completion request
+///
+/// Raw prompt completion without a chat template (legacy OpenAI `/v1/completions`).
+/// Used by `LlmEngine::complete()` and `LlmEngine::complete_stream()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionRequest {
+    /// Target engine ID
+    pub engine_id: EngineId,
+    /// Model identifier (normalized as `flm://{engine_id}/{model_name}`)
+    pub model_id: ModelId,
+    /// Prompt text, sent to the model as-is
+    pub prompt: String,
+    /// Text that follows the insertion point (fill-in-the-middle)
+    #[serde(default)]
+    pub suffix: Option<String>,
+    /// Whether to stream the response
+    pub stream: bool,
+    /// Sampling temperature (0.0-2.0, higher = more creative)
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate
+    pub max_tokens: Option<u32>,
+    /// Stop sequences
+    pub stop: Vec<String>,
+    /// Candidates generated server-side to pick the best `n` from (engine default when `None`)
+    #[serde(default)]
+    pub best_of: Option<u32>,
+    /// Additional sampling controls (engine defaults when unset)
+    #[serde(default)]
+    pub sampling: SamplingParams,
+}
+
+/// Text completion choice
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionChoice {
+    /// Generated text
+    pub text: String,
+    /// Why generation stopped (`stop`, `length`, ...) when the engine reports it
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Text completion response
+///
+/// Response from a non-streaming text completion request.
+/// Returned by `LlmEngine::complete()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionResponse {
+    /// Token usage statistics
+    pub usage: UsageStats,
+    /// Generated completions (one per requested choice)
+    pub choices: Vec<CompletionChoice>,
+}
+
+/// Text completion stream chunk
+///
+/// Incremental update in a streaming text completion.
+/// Emitted by `LlmEngine::complete_stream()` stream.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionStreamChunk {
+    /// Text delta
+    pub text: String,
+    /// Usage statistics (present in final chunk)
+    pub usage: Option<UsageStats>,
+    /// Whether this is the final chunk
+    pub is_done: bool,
+    /// Why generation stopped (final chunk only, when the engine reports it)
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
 /// Embedding request
 ///
 /// Request to generate embeddings for text input(s).
@@ -262,6 +564,81 @@ pub struct TranscriptionResponse {
     pub language: Option<String>,
 }
 
+/// Speech synthesis request
+///
+/// Request to convert text to spoken audio.
+/// Used by `LlmEngine::synthesize_speech()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpeechRequest {
+    /// Target engine ID
+    pub engine_id: EngineId,
+    /// Model identifier (normalized as `flm://{engine_id}/{model_name}`)
+    pub model_id: ModelId,
+    /// Text to speak
+    pub input: String,
+    /// Voice name (engine-specific, e.g., "alloy", "af_bella")
+    pub voice: String,
+    /// Output audio format
+    pub format: AudioResponseFormat,
+    /// Optional playback speed (0.25-4.0, 1.0 is normal)
+    pub speed: Option<f32>,
+}
+
+/// Image generation output format
+///
+/// Mirrors the OpenAI `response_format` values for `/v1/images/generations`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    /// Base64-encoded image bytes
+    B64Json,
+    /// URL the client can fetch the image from
+    Url,
+}
+
+/// Image generation request
+///
+/// Request to generate images from a text prompt.
+/// Used by `LlmEngine::generate_images()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImageGenerationRequest {
+    /// Target engine ID
+    pub engine_id: EngineId,
+    /// Model identifier (normalized as `flm://{engine_id}/{model_name}`)
+    pub model_id: ModelId,
+    /// Text prompt describing the image
+    pub prompt: String,
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Number of images to generate
+    pub n: u32,
+    /// Requested output format
+    pub response_format: ImageResponseFormat,
+}
+
+/// Generated image
+///
+/// Exactly one representation, matching the requested `ImageResponseFormat`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratedImage {
+    /// Base64-encoded image bytes
+    B64Json(String),
+    /// Image URL hosted by the backend
+    Url(String),
+}
+
+/// Image generation response
+///
+/// Returned by `LlmEngine::generate_images()`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImageGenerationResponse {
+    /// Generated images, in generation order
+    pub images: Vec<GeneratedImage>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,6 +665,8 @@ mod tests {
             role: ChatRole::User,
             content: "Hello, world!".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -304,6 +683,8 @@ mod tests {
             role: ChatRole::User,
             content: "Look at this image".to_string(),
             attachments: vec![attachment.clone()],
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -356,12 +737,18 @@ mod tests {
                 role: ChatRole::User,
                 content: "Hello".to_string(),
                 attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
             stream: false,
             temperature: Some(0.7),
             max_tokens: Some(100),
             stop: Vec::new(),
             requested_modalities: Vec::new(),
+            tools: Vec::new(),
+            tool_choice: None,
+            response_format: None,
+            sampling: SamplingParams::default(),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -372,6 +759,91 @@ mod tests {
         assert_eq!(request.temperature, deserialized.temperature);
     }
 
+    #[test]
+    fn test_tool_choice_serialization() {
+        let choice = ToolChoice::Function {
+            name: "get_weather".to_string(),
+        };
+        let json = serde_json::to_string(&choice).unwrap();
+        assert_eq!(json, r#"{"type":"function","name":"get_weather"}"#);
+
+        let auto: ToolChoice = serde_json::from_str(r#"{"type":"auto"}"#).unwrap();
+        assert_eq!(auto, ToolChoice::Auto);
+    }
+
+    #[test]
+    fn test_chat_message_with_tool_calls() {
+        let message = ChatMessage {
+            role: ChatRole::Assistant,
+            content: String::new(),
+            attachments: Vec::new(),
+            tool_calls: vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                arguments: r#"{"city":"Tokyo"}"#.to_string(),
+            }],
+            tool_call_id: None,
+        };
+
+        let json = serde_json::to_string(&message).unwrap();
+        let deserialized: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(message.tool_calls, deserialized.tool_calls);
+
+        // Messages serialized before tool support must still deserialize
+        let legacy: ChatMessage =
+            serde_json::from_str(r#"{"role":"user","content":"Hi","attachments":[]}"#).unwrap();
+        assert!(legacy.tool_calls.is_empty());
+        assert!(legacy.tool_call_id.is_none());
+    }
+
+    #[test]
+    fn test_sampling_params_or_defaults() {
+        let defaults = SamplingParams {
+            top_p: Some(0.9),
+            seed: Some(7),
+            logit_bias: BTreeMap::from([("50256".to_string(), -100.0)]),
+            ..SamplingParams::default()
+        };
+        let request = SamplingParams {
+            top_p: Some(0.5),
+            top_k: Some(20),
+            ..SamplingParams::default()
+        };
+
+        let merged = request.or_defaults(&defaults);
+        assert_eq!(merged.top_p, Some(0.5));
+        assert_eq!(merged.top_k, Some(20));
+        assert_eq!(merged.seed, Some(7));
+        assert_eq!(merged.logit_bias, defaults.logit_bias);
+        assert!(!merged.logprobs);
+    }
+
+    #[test]
+    fn test_chat_request_sampling_defaults() {
+        // Requests serialized before sampling support must still deserialize
+        let json = r#"{
+            "engine_id": "ollama",
+            "model_id": "flm://ollama/llama2:latest",
+            "messages": [],
+            "stream": false,
+            "temperature": null,
+            "max_tokens": null,
+            "stop": []
+        }"#;
+        let request: ChatRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.sampling, SamplingParams::default());
+
+        let sampling = SamplingParams {
+            seed: Some(42),
+            top_k: Some(40),
+            logit_bias: BTreeMap::from([("50256".to_string(), -100.0)]),
+            ..SamplingParams::default()
+        };
+        let json = serde_json::to_string(&sampling).unwrap();
+        let deserialized: SamplingParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(sampling, deserialized);
+    }
+
     #[test]
     fn test_response_modality_serialization() {
         let text = ResponseModality::Text;
@@ -394,14 +866,20 @@ mod tests {
             AudioResponseFormat::Mp3,
             AudioResponseFormat::Ogg,
             AudioResponseFormat::Flac,
+            AudioResponseFormat::Opus,
+            AudioResponseFormat::Aac,
+            AudioResponseFormat::Pcm,
         ];
 
         for format in formats {
             let json = serde_json::to_string(&format).unwrap();
             let deserialized: AudioResponseFormat = serde_json::from_str(&json).unwrap();
-            // Compare by serializing again since we can't derive PartialEq
-            assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+            assert_eq!(format, deserialized);
+            // The serde name doubles as the OpenAI `response_format` value
+            assert_eq!(json, format!("\"{}\"", format.as_str()));
         }
+        assert_eq!(AudioResponseFormat::Mp3.mime_type(), "audio/mpeg");
+        assert_eq!(AudioResponseFormat::Opus.mime_type(), "audio/ogg");
     }
 
     #[test]
@@ -431,8 +909,11 @@ mod tests {
                 role: ChatRole::Assistant,
                 content: "Hello!".to_string(),
                 attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
             audio: Vec::new(),
+            logprobs: Vec::new(),
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -448,6 +929,8 @@ mod tests {
                 role: ChatRole::Assistant,
                 content: "Hello".to_string(),
                 attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             },
             usage: Some(UsageStats {
                 prompt_tokens: 10,
@@ -456,6 +939,8 @@ mod tests {
             }),
             is_done: false,
             audio: Vec::new(),
+            tool_calls: Vec::new(),
+            logprobs: Vec::new(),
         };
 
         let json = serde_json::to_string(&chunk).unwrap();
@@ -4,6 +4,7 @@
 
 use super::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities, ModelId};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Health status of an engine
 ///
@@ -98,6 +99,84 @@ pub struct ModelInfo {
     pub capabilities: Option<ModelCapabilities>,
 }
 
+/// Request to download a model (see `ModelManager::pull`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelPullRequest {
+    /// Model name (e.g. Ollama `llama3.2:3b`) or download URL (GGUF)
+    pub model: String,
+    /// Expected SHA-256 of the downloaded file (hex); verified when set
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// File name to save a download as (default: last URL path segment)
+    #[serde(default)]
+    pub file_name: Option<String>,
+}
+
+/// Progress event emitted while a model is pulled
+///
+/// `status` is engine-defined (e.g. Ollama's `pulling manifest`); the last
+/// event of a successful pull has status `success`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelPullProgress {
+    pub status: String,
+    /// Layer or file digest being transferred (if known)
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Total bytes of the current transfer (if known)
+    #[serde(default)]
+    pub total: Option<u64>,
+    /// Bytes transferred so far
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+impl ModelPullProgress {
+    /// Status of the final event of a successful pull
+    pub const SUCCESS: &'static str = "success";
+
+    pub fn is_success(&self) -> bool {
+        self.status == Self::SUCCESS
+    }
+}
+
+/// Installed model details (see `ModelManager::show` / `list_installed`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelDetails {
+    /// Engine (or model store) that holds the model
+    pub engine_id: EngineId,
+    /// Model name as used by the engine (file name for GGUF files)
+    pub name: String,
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization: Option<String>,
+    /// Weight format (e.g. `gguf`)
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+    /// Content digest (`sha256:<hex>` when known)
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Local file path (GGUF files)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Last modification time (RFC3339)
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    /// Maximum context length in tokens (if reported)
+    #[serde(default)]
+    pub context_length: Option<u32>,
+    /// Capabilities derived from the model metadata (if available)
+    #[serde(default)]
+    pub capabilities: Option<ModelCapabilities>,
+    /// Engine-specific metadata (e.g. the Ollama `/api/show` response)
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
 /// Engine binary information (for process detection)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EngineBinaryInfo {
@@ -116,6 +195,249 @@ pub struct EngineRuntimeInfo {
     pub port: Option<u16>,
 }
 
+/// Engine registered explicitly in `config.db` (`engines`)
+///
+/// Managed by `flm engines add/remove/list`. Registered engines are loaded by
+/// the CLI and the proxy in addition to (and ahead of) auto-detected ones, so
+/// remote servers that localhost detection never finds can be used.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EngineRegistration {
+    /// Engine ID used in `flm://{engine_id}/{model}`
+    pub id: EngineId,
+    /// Adapter used to talk to the engine
+    pub kind: EngineKind,
+    /// Base URL of the engine API (e.g. `https://gpu-01.internal:8000`)
+    pub base_url: String,
+    /// Name of a header sent with every request (e.g. `X-Api-Key`); its value is stored in the OS keyring
+    #[serde(default)]
+    pub auth_header_name: Option<String>,
+    /// Request timeout in seconds (None uses the adapter default)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// TLS settings for `https` base URLs
+    #[serde(default)]
+    pub tls: EngineTlsConfig,
+    /// Whether a bearer token for this engine is stored in the OS keyring
+    #[serde(default)]
+    pub bearer_token_in_keyring: bool,
+    /// Per-model capability overrides (`*` applies to every model); OpenAI-compatible engines only
+    #[serde(default)]
+    pub model_capabilities: BTreeMap<String, ModelCapabilities>,
+    /// Creation timestamp (RFC3339)
+    pub created_at: String,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+/// Secrets of a registered engine, resolved from the OS keyring by the CLI
+///
+/// Runtime only: neither value is ever written to `config.db`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineCredentials {
+    /// Token sent as `Authorization: Bearer ...` (`bearer_token_in_keyring`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+    /// Value of the `auth_header_name` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_header_value: Option<String>,
+}
+
+/// TLS settings for a registered engine
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EngineTlsConfig {
+    /// Extra PEM CA certificate to trust (for engines behind a private CA)
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    /// Skip certificate verification (development only)
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+}
+
+/// Launch settings for an engine process managed by `flm engines start`
+///
+/// Stored in `config.db` (`engine_processes`). Only engines that run as a
+/// local server binary can be managed: Ollama, llama.cpp and vLLM.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EngineProcessConfig {
+    /// Engine ID (also used for the `engines` registry entry)
+    pub engine_id: EngineId,
+    /// Engine kind
+    pub kind: EngineKind,
+    /// Binary to run (None resolves the kind's default binary from PATH)
+    #[serde(default)]
+    pub binary_path: Option<String>,
+    /// GGUF file (llama.cpp) or model name (vLLM); not used for Ollama
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Listen host passed to the engine
+    pub host: String,
+    /// Listen port passed to the engine
+    pub port: u16,
+    /// Extra arguments appended to the generated command line
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Restart the process when it exits without being stopped
+    pub restart_on_crash: bool,
+    /// Consecutive restarts allowed before giving up
+    pub max_restarts: u32,
+    /// Creation timestamp (RFC3339)
+    pub created_at: String,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+impl EngineProcessConfig {
+    /// Default host for managed engines (localhost only)
+    pub const DEFAULT_HOST: &'static str = "127.0.0.1";
+    /// Default consecutive restart limit
+    pub const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+    /// Binary looked up in PATH when `binary_path` is not set
+    pub fn default_binary(kind: &EngineKind) -> Option<&'static str> {
+        match kind {
+            EngineKind::Ollama => Some("ollama"),
+            EngineKind::LlamaCpp => Some("llama-server"),
+            EngineKind::Vllm => Some("vllm"),
+            _ => None,
+        }
+    }
+
+    /// Port the engine listens on by default (matches auto-detection)
+    pub fn default_port(kind: &EngineKind) -> Option<u16> {
+        match kind {
+            EngineKind::Ollama => Some(11434),
+            EngineKind::LlamaCpp => Some(8080),
+            EngineKind::Vllm => Some(8000),
+            _ => None,
+        }
+    }
+
+    /// Command-line arguments for the engine binary
+    ///
+    /// - Ollama: `serve` (host/port are passed through `OLLAMA_HOST`)
+    /// - llama.cpp: `-m <gguf> --host <host> --port <port>`
+    /// - vLLM: `serve <model> --host <host> --port <port>`
+    ///
+    /// `args` are appended in every case.
+    pub fn command_args(&self) -> Result<Vec<String>, String> {
+        let mut command = match self.kind {
+            EngineKind::Ollama => {
+                if self.model.is_some() {
+                    return Err("Ollama does not take a model at startup".to_string());
+                }
+                vec!["serve".to_string()]
+            }
+            EngineKind::LlamaCpp => vec![
+                "-m".to_string(),
+                self.required_model()?.to_string(),
+                "--host".to_string(),
+                self.host.clone(),
+                "--port".to_string(),
+                self.port.to_string(),
+            ],
+            EngineKind::Vllm => vec![
+                "serve".to_string(),
+                self.required_model()?.to_string(),
+                "--host".to_string(),
+                self.host.clone(),
+                "--port".to_string(),
+                self.port.to_string(),
+            ],
+            _ => {
+                return Err(format!(
+                    "Engine kind '{}' cannot be started by flm (supported: ollama, llamacpp, vllm)",
+                    self.kind.as_str()
+                ))
+            }
+        };
+        command.extend(self.args.iter().cloned());
+        Ok(command)
+    }
+
+    /// Environment for the engine process (`env` plus kind-specific variables)
+    pub fn command_env(&self) -> BTreeMap<String, String> {
+        let mut env = self.env.clone();
+        if self.kind == EngineKind::Ollama {
+            env.entry("OLLAMA_HOST".to_string())
+                .or_insert_with(|| format!("{}:{}", self.host, self.port));
+        }
+        env
+    }
+
+    /// Base URL clients use to reach the engine
+    pub fn base_url(&self) -> String {
+        let host = match self.host.as_str() {
+            "0.0.0.0" => "127.0.0.1",
+            "::" => "::1",
+            host => host,
+        };
+        if host.contains(':') {
+            format!("http://[{host}]:{}", self.port)
+        } else {
+            format!("http://{host}:{}", self.port)
+        }
+    }
+
+    fn required_model(&self) -> Result<&str, String> {
+        self.model
+            .as_deref()
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| format!("{} requires a model (--model)", self.kind.as_str()))
+    }
+}
+
+/// Lifecycle state of a managed engine process
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EngineProcessState {
+    /// Supervisor launched, process not yet spawned
+    Starting,
+    /// Process is running
+    Running,
+    /// Process exited unexpectedly and will be restarted
+    Restarting,
+    /// Process was stopped on request (or never started)
+    Stopped,
+    /// Process could not be (re)started or exceeded the restart limit
+    Failed,
+}
+
+impl EngineProcessState {
+    /// Whether a supervisor is expected to be alive in this state
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Starting | Self::Running | Self::Restarting)
+    }
+}
+
+/// Status of a managed engine process, as reported by its supervisor
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EngineProcessStatus {
+    pub engine_id: EngineId,
+    pub state: EngineProcessState,
+    /// PID of the engine process (while running)
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Total restarts since `flm engines start`
+    #[serde(default)]
+    pub restarts: u32,
+    /// When the current process was spawned (RFC3339)
+    #[serde(default)]
+    pub started_at: Option<String>,
+    /// Exit code of the last process that exited
+    #[serde(default)]
+    pub last_exit_code: Option<i32>,
+    /// Why the process is stopped or failed (if known)
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// File the process stdout/stderr is written to
+    pub log_path: String,
+    /// Last time the supervisor wrote this status (RFC3339)
+    pub updated_at: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,4 +646,72 @@ mod tests {
         assert_eq!(info.base_url, deserialized.base_url);
         assert_eq!(info.port, deserialized.port);
     }
+
+    fn process_config(kind: EngineKind, model: Option<&str>) -> EngineProcessConfig {
+        EngineProcessConfig {
+            engine_id: "local".to_string(),
+            port: EngineProcessConfig::default_port(&kind).unwrap_or(9000),
+            kind,
+            binary_path: None,
+            model: model.map(str::to_string),
+            host: EngineProcessConfig::DEFAULT_HOST.to_string(),
+            args: vec!["--extra".to_string()],
+            env: BTreeMap::new(),
+            restart_on_crash: true,
+            max_restarts: EngineProcessConfig::DEFAULT_MAX_RESTARTS,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_engine_process_config_command_line() {
+        let ollama = process_config(EngineKind::Ollama, None);
+        assert_eq!(ollama.command_args().unwrap(), vec!["serve", "--extra"]);
+        assert_eq!(ollama.command_env()["OLLAMA_HOST"], "127.0.0.1:11434");
+        assert_eq!(ollama.base_url(), "http://127.0.0.1:11434");
+
+        let llama = process_config(EngineKind::LlamaCpp, Some("/models/q4.gguf"));
+        assert_eq!(
+            llama.command_args().unwrap(),
+            vec![
+                "-m",
+                "/models/q4.gguf",
+                "--host",
+                "127.0.0.1",
+                "--port",
+                "8080",
+                "--extra"
+            ]
+        );
+        assert!(!llama.command_env().contains_key("OLLAMA_HOST"));
+
+        let vllm = process_config(EngineKind::Vllm, Some("Qwen/Qwen2.5-7B"));
+        assert_eq!(
+            vllm.command_args().unwrap(),
+            vec![
+                "serve",
+                "Qwen/Qwen2.5-7B",
+                "--host",
+                "127.0.0.1",
+                "--port",
+                "8000",
+                "--extra"
+            ]
+        );
+
+        assert!(process_config(EngineKind::Vllm, None)
+            .command_args()
+            .is_err());
+        assert!(process_config(EngineKind::Ollama, Some("llama3"))
+            .command_args()
+            .is_err());
+        assert!(process_config(EngineKind::LmStudio, None)
+            .command_args()
+            .is_err());
+
+        let mut wildcard = process_config(EngineKind::Ollama, None);
+        wildcard.host = "0.0.0.0".to_string();
+        assert_eq!(wildcard.base_url(), "http://127.0.0.1:11434");
+    }
 }
@@ -5,14 +5,18 @@
 //! This module contains the core domain models that are shared across
 //! CLI, Proxy, and UI adapters.
 
+pub mod audit;
 pub mod chat;
 pub mod engine;
 pub mod models;
+pub mod policy;
 pub mod proxy;
 pub mod security;
 
+pub use audit::*;
 pub use chat::*;
 pub use engine::*;
 pub use models::*;
+pub use policy::*;
 pub use proxy::*;
 pub use security::*;
@@ -3,6 +3,7 @@
 //! These types are the foundation of the FLM domain layer.
 //! All types must match the specification in `docs/CORE_API.md` section 2.
 
+use crate::domain::chat::{ChatMessage, ChatRole, SamplingParams};
 use serde::{Deserialize, Serialize};
 
 /// Engine identifier
@@ -32,6 +33,44 @@ pub enum EngineKind {
     LmStudio,
     /// llama.cpp engine
     LlamaCpp,
+    /// Stable Diffusion web UI (AUTOMATIC1111-compatible API)
+    StableDiffusion,
+    /// Any server exposing the OpenAI `/v1` API (TGI, SGLang, LocalAI, hosted APIs)
+    #[serde(rename = "openai-compatible")]
+    OpenAiCompatible,
+}
+
+impl EngineKind {
+    /// Wire name (matches the serde representation)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ollama => "ollama",
+            Self::Vllm => "vllm",
+            Self::LmStudio => "lm-studio",
+            Self::LlamaCpp => "llama-cpp",
+            Self::StableDiffusion => "stable-diffusion",
+            Self::OpenAiCompatible => "openai-compatible",
+        }
+    }
+}
+
+impl std::str::FromStr for EngineKind {
+    type Err = String;
+
+    /// Accepts the wire names plus the short CLI aliases (`lmstudio`, `llamacpp`, `sdwebui`, `openai`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ollama" => Ok(Self::Ollama),
+            "vllm" => Ok(Self::Vllm),
+            "lm-studio" | "lmstudio" => Ok(Self::LmStudio),
+            "llama-cpp" | "llamacpp" => Ok(Self::LlamaCpp),
+            "stable-diffusion" | "sdwebui" => Ok(Self::StableDiffusion),
+            "openai-compatible" | "openai-compat" | "openai" => Ok(Self::OpenAiCompatible),
+            other => Err(format!(
+                "Unknown engine kind '{other}' (expected ollama, vllm, lmstudio, llamacpp, sdwebui or openai)"
+            )),
+        }
+    }
 }
 
 /// Engine capabilities
@@ -58,6 +97,9 @@ pub struct EngineCapabilities {
     pub audio_inputs: bool,
     /// Whether the engine can emit audio outputs (Responses/audio.speech)
     pub audio_outputs: bool,
+    /// Whether the engine can generate images from text prompts (images.generations)
+    #[serde(default)]
+    pub image_generation: bool,
     /// Maximum image payload size accepted by the engine (bytes)
     pub max_image_bytes: Option<u64>,
     /// Maximum audio payload size accepted by the engine (bytes)
@@ -83,6 +125,199 @@ pub struct ModelCapabilities {
     pub audio_outputs: bool,
 }
 
+/// Model profile
+///
+/// Named set of default generation parameters for a model, stored in
+/// `config.db` (`model_profiles`) and managed by `flm model-profiles`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelProfile {
+    /// Profile ID
+    pub id: String,
+    /// Engine the profile applies to
+    pub engine_id: EngineId,
+    /// Model the profile applies to (normalized as `flm://{engine_id}/{model_name}`)
+    pub model_id: ModelId,
+    /// Human-readable label (addressable as `flm://profile/{label}`)
+    pub label: String,
+    /// Raw parameters as saved by the user
+    pub parameters: serde_json::Value,
+    /// Monotonic version, bumped on every save
+    pub version: i64,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+impl ModelProfile {
+    /// Parse the generation defaults out of `parameters`
+    ///
+    /// Unknown keys are ignored so profiles can carry settings for other consumers.
+    pub fn defaults(&self) -> Result<ModelProfileDefaults, serde_json::Error> {
+        serde_json::from_value(self.parameters.clone())
+    }
+}
+
+/// Generation defaults carried by a model profile
+///
+/// Values here only fill in what a request leaves unset; anything the caller
+/// sends explicitly takes precedence.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModelProfileDefaults {
+    /// Default sampling temperature
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Default maximum tokens to generate
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Default stop sequences
+    #[serde(default)]
+    pub stop: Vec<String>,
+    /// Default sampling controls
+    #[serde(flatten)]
+    pub sampling: SamplingParams,
+}
+
+/// API prompt template
+///
+/// Managed system prompt for one proxy endpoint, stored in `config.db`
+/// (`api_prompts`) and managed by `flm api prompts`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiPrompt {
+    /// Endpoint the template applies to (e.g. `chat_completions`)
+    pub api_id: String,
+    /// Template text with `{{variable}}` placeholders
+    pub template_text: String,
+    /// Monotonic version, bumped on every save
+    pub version: i64,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+/// Values substituted into an API prompt template
+#[derive(Clone, Debug, Default)]
+pub struct ApiPromptVariables {
+    /// `{{api_key_label}}`: label of the calling API key
+    pub api_key_label: String,
+    /// `{{date}}`: current UTC date (`YYYY-MM-DD`)
+    pub date: String,
+    /// `{{model_id}}`: normalized model ID the request targets
+    pub model_id: String,
+}
+
+impl ApiPrompt {
+    /// Render the template, leaving unknown placeholders untouched
+    pub fn render(&self, variables: &ApiPromptVariables) -> String {
+        self.template_text
+            .replace("{{api_key_label}}", &variables.api_key_label)
+            .replace("{{date}}", &variables.date)
+            .replace("{{model_id}}", &variables.model_id)
+    }
+
+    /// Inject the rendered template as the leading system prompt
+    ///
+    /// A leading system message from the client is kept and placed after the
+    /// managed prompt; otherwise a new system message is prepended.
+    pub fn apply(&self, messages: &mut Vec<ChatMessage>, variables: &ApiPromptVariables) {
+        let rendered = self.render(variables);
+        match messages.first_mut() {
+            Some(first) if first.role == ChatRole::System => {
+                first.content = format!("{rendered}\n\n{}", first.content);
+            }
+            _ => messages.insert(
+                0,
+                ChatMessage {
+                    role: ChatRole::System,
+                    content: rendered,
+                    attachments: Vec::new(),
+                    tool_calls: Vec::new(),
+                    tool_call_id: None,
+                },
+            ),
+        }
+    }
+}
+
+/// Backend selection strategy for a model group
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoadBalanceStrategy {
+    /// Rotate through members in order
+    #[default]
+    RoundRobin,
+    /// Prefer the member with the fewest requests in flight on this proxy
+    LeastInFlight,
+    /// Prefer members with lower recent health-check latency
+    LatencyWeighted,
+}
+
+impl LoadBalanceStrategy {
+    /// Wire name stored in `config.db` and accepted by the CLI
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round-robin",
+            Self::LeastInFlight => "least-in-flight",
+            Self::LatencyWeighted => "latency-weighted",
+        }
+    }
+}
+
+impl std::str::FromStr for LoadBalanceStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(Self::RoundRobin),
+            "least-in-flight" => Ok(Self::LeastInFlight),
+            "latency-weighted" => Ok(Self::LatencyWeighted),
+            other => Err(format!(
+                "Unknown strategy '{other}' (expected round-robin, least-in-flight or latency-weighted)"
+            )),
+        }
+    }
+}
+
+/// Model group
+///
+/// One public model name served by several engine/model backends, stored in
+/// `config.db` (`model_groups`) and managed by `flm model-groups`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelGroup {
+    /// Public name (addressable as `flm://group/{name}`)
+    pub name: String,
+    /// How the proxy picks a member for each request
+    pub strategy: LoadBalanceStrategy,
+    /// Member models in priority order (normalized as `flm://{engine_id}/{model_name}`)
+    pub members: Vec<ModelId>,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+/// Concurrency cap for one engine or one model
+///
+/// Stored in `config.db` (`concurrency_limits`) and managed by
+/// `flm concurrency-limits`. The proxy lets at most `max_concurrent` chat
+/// requests run against the target; further requests wait in a queue of up
+/// to `max_queue` entries for at most `queue_timeout_secs`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyLimit {
+    /// Engine ID (e.g. `ollama-default`) or model ID (`flm://{engine_id}/{model_name}`)
+    pub target: String,
+    /// Maximum number of requests running at once
+    pub max_concurrent: u32,
+    /// Maximum number of requests waiting for a slot (0 rejects immediately)
+    pub max_queue: u32,
+    /// How long a queued request waits before it is rejected
+    pub queue_timeout_secs: u32,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+impl ConcurrencyLimit {
+    /// Whether the limit applies to a single model rather than a whole engine
+    pub fn is_model_limit(&self) -> bool {
+        self.target.starts_with("flm://")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +330,8 @@ mod tests {
             EngineKind::Vllm,
             EngineKind::LmStudio,
             EngineKind::LlamaCpp,
+            EngineKind::StableDiffusion,
+            EngineKind::OpenAiCompatible,
         ];
 
         for kind in kinds {
@@ -104,6 +341,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_engine_kind_wire_names() {
+        for kind in [
+            EngineKind::Ollama,
+            EngineKind::Vllm,
+            EngineKind::LmStudio,
+            EngineKind::LlamaCpp,
+            EngineKind::StableDiffusion,
+            EngineKind::OpenAiCompatible,
+        ] {
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.as_str()));
+            assert_eq!(kind.as_str().parse::<EngineKind>().unwrap(), kind);
+        }
+        assert_eq!(
+            "LMStudio".parse::<EngineKind>().unwrap(),
+            EngineKind::LmStudio
+        );
+        assert_eq!(
+            "sdwebui".parse::<EngineKind>().unwrap(),
+            EngineKind::StableDiffusion
+        );
+        assert_eq!(
+            "openai".parse::<EngineKind>().unwrap(),
+            EngineKind::OpenAiCompatible
+        );
+        assert!("tgi".parse::<EngineKind>().is_err());
+    }
+
+    #[test]
+    fn test_load_balance_strategy_round_trip() {
+        for strategy in [
+            LoadBalanceStrategy::RoundRobin,
+            LoadBalanceStrategy::LeastInFlight,
+            LoadBalanceStrategy::LatencyWeighted,
+        ] {
+            let json = serde_json::to_string(&strategy).unwrap();
+            assert_eq!(json, format!("\"{}\"", strategy.as_str()));
+            assert_eq!(
+                strategy.as_str().parse::<LoadBalanceStrategy>().unwrap(),
+                strategy
+            );
+        }
+        assert!("random".parse::<LoadBalanceStrategy>().is_err());
+    }
+
     #[test]
     fn test_engine_capabilities_default() {
         let caps = EngineCapabilities::default();
@@ -116,6 +399,7 @@ mod tests {
         assert!(!caps.vision_inputs);
         assert!(!caps.audio_inputs);
         assert!(!caps.audio_outputs);
+        assert!(!caps.image_generation);
         assert!(caps.max_image_bytes.is_none());
         assert!(caps.max_audio_bytes.is_none());
     }
@@ -152,6 +436,7 @@ mod tests {
             vision_inputs: true,
             audio_inputs: true,
             audio_outputs: true,
+            image_generation: true,
             max_image_bytes: Some(20_000_000),
             max_audio_bytes: Some(10_000_000),
         };
@@ -167,6 +452,7 @@ mod tests {
         assert!(deserialized.vision_inputs);
         assert!(deserialized.audio_inputs);
         assert!(deserialized.audio_outputs);
+        assert!(deserialized.image_generation);
         assert_eq!(deserialized.max_image_bytes, Some(20_000_000));
         assert_eq!(deserialized.max_audio_bytes, Some(10_000_000));
     }
@@ -401,4 +687,88 @@ mod tests {
         assert_eq!(model.engine_id, "test-engine");
         assert!(model.capabilities.is_none());
     }
+
+    #[test]
+    fn test_model_profile_defaults_ignore_unknown_keys() {
+        let profile = ModelProfile {
+            id: "profile-1".to_string(),
+            engine_id: "ollama".to_string(),
+            model_id: "flm://ollama/llama3:8b".to_string(),
+            label: "creative".to_string(),
+            parameters: serde_json::json!({
+                "temperature": 1.1,
+                "top_p": 0.95,
+                "stop": ["###"],
+                "ui_color": "blue"
+            }),
+            version: 3,
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+
+        let defaults = profile.defaults().unwrap();
+        assert_eq!(defaults.temperature, Some(1.1));
+        assert_eq!(defaults.max_tokens, None);
+        assert_eq!(defaults.stop, vec!["###".to_string()]);
+        assert_eq!(defaults.sampling.top_p, Some(0.95));
+    }
+
+    #[test]
+    fn test_api_prompt_render_substitutes_variables() {
+        let prompt = ApiPrompt {
+            api_id: "chat_completions".to_string(),
+            template_text:
+                "You serve {{api_key_label}} using {{model_id}} on {{date}}. {{unknown}}"
+                    .to_string(),
+            version: 1,
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        let variables = ApiPromptVariables {
+            api_key_label: "billing-team".to_string(),
+            date: "2025-01-02".to_string(),
+            model_id: "flm://ollama/llama3:8b".to_string(),
+        };
+
+        assert_eq!(
+            prompt.render(&variables),
+            "You serve billing-team using flm://ollama/llama3:8b on 2025-01-02. {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_api_prompt_apply_merges_with_client_system_prompt() {
+        let prompt = ApiPrompt {
+            api_id: "chat_completions".to_string(),
+            template_text: "House rules for {{api_key_label}}".to_string(),
+            version: 2,
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        let variables = ApiPromptVariables {
+            api_key_label: "ops".to_string(),
+            ..ApiPromptVariables::default()
+        };
+        let message = |role: ChatRole, content: &str| ChatMessage {
+            role,
+            content: content.to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        };
+
+        let mut messages = vec![message(ChatRole::User, "Hello")];
+        prompt.apply(&mut messages, &variables);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, ChatRole::System);
+        assert_eq!(messages[0].content, "House rules for ops");
+
+        let mut messages = vec![
+            message(ChatRole::System, "Answer in French"),
+            message(ChatRole::User, "Hello"),
+        ];
+        prompt.apply(&mut messages, &variables);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].content,
+            "House rules for ops\n\nAnswer in French"
+        );
+    }
 }
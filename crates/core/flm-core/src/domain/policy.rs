@@ -0,0 +1,696 @@
+//! Typed security policy
+//!
+//! `SecurityPolicy.policy_json` is parsed into [`PolicyDocument`], which
+//! upgrades documents written for older schema versions. Policies being set
+//! are rejected for unknown fields; stored policies read at request time
+//! drop them instead. See `docs/CORE_API.md` section 2.
+
+use super::security::{ApiKeyRateLimit, ApiKeyScopes, RetentionPolicy};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+/// Schema version of policies written by this build
+///
+/// - 1: no `schema_version`; `cors.allowed_origins` may be a single string
+/// - 2: `schema_version` is set and `cors.allowed_origins` is always a list
+pub const POLICY_SCHEMA_VERSION: u32 = 2;
+
+/// Requests per minute per client IP without `ip_rate_limit`, and the most it allows
+pub const DEFAULT_IP_RATE_LIMIT_RPM: u32 = 1000;
+
+/// Security policy (`SecurityPolicy.policy_json`)
+///
+/// ```json
+/// {
+///   "schema_version": 2,
+///   "ip_whitelist": ["127.0.0.1", "10.0.0.0/8"],
+///   "cors": { "allowed_origins": ["https://example.com"] },
+///   "rate_limit": { "rpm": 60, "burst": 10, "tpm": 100000 },
+///   "ip_rate_limit": { "rpm": 600 }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyDocument {
+    /// Version of the layout below (see [`POLICY_SCHEMA_VERSION`])
+    pub schema_version: u32,
+    /// Client IP addresses or CIDR ranges allowed to call the proxy (empty allows all)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_whitelist: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsPolicy>,
+    /// Limits per API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitPolicy>,
+    /// Limits per client IP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_rate_limit: Option<IpRateLimitPolicy>,
+    /// Domain of the ACME certificate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acme_domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<RetentionPolicy>,
+}
+
+/// `cors` section of the security policy
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsPolicy {
+    /// Origins allowed to call the proxy from a browser (empty denies all)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_origins: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_methods: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_headers: Option<Vec<String>>,
+}
+
+/// `rate_limit` section of the security policy
+///
+/// Limits apply per API key; a missing or zero value means no limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitPolicy {
+    /// Requests per minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u64>,
+    /// Requests allowed at once (defaults to `rpm`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u64>,
+    /// Tokens per minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tpm: Option<u64>,
+    /// Tokens per UTC day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_tokens: Option<u64>,
+    /// Tokens per UTC month
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_tokens: Option<u64>,
+}
+
+impl RateLimitPolicy {
+    /// Copy with a key's own limits replacing the policy's
+    pub fn with_key_overrides(mut self, limits: &ApiKeyRateLimit) -> Self {
+        if let Some(rpm) = limits.rpm {
+            self.rpm = Some(u64::from(rpm));
+        }
+        if let Some(burst) = limits.burst {
+            self.burst = Some(u64::from(burst));
+        }
+        self.tpm = limits.tpm.or(self.tpm);
+        self.daily_tokens = limits.daily_tokens.or(self.daily_tokens);
+        self.monthly_tokens = limits.monthly_tokens.or(self.monthly_tokens);
+        self
+    }
+
+    /// Requests per minute and burst, when a request limit is set
+    pub fn request_limit(&self) -> Option<(u64, u64)> {
+        let rpm = self.rpm.filter(|rpm| *rpm > 0)?;
+        Some((rpm, self.burst.unwrap_or(rpm)))
+    }
+}
+
+/// `ip_rate_limit` section of the security policy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IpRateLimitPolicy {
+    /// Requests per minute (at most 1000, the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u64>,
+    /// Requests allowed at once (at most `rpm`, the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u64>,
+}
+
+impl Default for PolicyDocument {
+    fn default() -> Self {
+        Self {
+            schema_version: POLICY_SCHEMA_VERSION,
+            ip_whitelist: None,
+            cors: None,
+            rate_limit: None,
+            ip_rate_limit: None,
+            acme_domain: None,
+            retention: None,
+        }
+    }
+}
+
+impl PolicyDocument {
+    /// Parse a policy JSON, migrating it to [`POLICY_SCHEMA_VERSION`]
+    ///
+    /// Errors name the offending field, e.g. `rate_limit.rpm: invalid type: ...`.
+    pub fn parse(policy_json: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(policy_json).map_err(|e| format!("Invalid JSON: {e}"))?;
+        Self::from_value(value)
+    }
+
+    /// Parse an already decoded policy JSON
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        let value = migrate_policy(value)?;
+        let document: Self = serde_path_to_error::deserialize(value).map_err(describe_error)?;
+        document.validated()
+    }
+
+    /// Parse a stored policy, dropping fields this build does not know
+    ///
+    /// The proxy reads the stored policy with this so a policy written by
+    /// another build keeps working; `flm security policy set` and
+    /// `policy test` still use the strict [`Self::parse`]. Also returns the
+    /// dropped fields (e.g. `cors.max_age`) so callers can log them.
+    pub fn parse_lenient(policy_json: &str) -> Result<(Self, Vec<String>), String> {
+        let value: Value =
+            serde_json::from_str(policy_json).map_err(|e| format!("Invalid JSON: {e}"))?;
+        let mut value = migrate_policy(value)?;
+        let mut ignored = Vec::new();
+        loop {
+            match serde_path_to_error::deserialize::<_, Self>(value.clone()) {
+                Ok(document) => return Ok((document.validated()?, ignored)),
+                Err(e) if e.inner().to_string().starts_with("unknown field") => {
+                    // Each pass removes one field, so this ends
+                    if !remove_path(&mut value, e.path()) {
+                        return Err(describe_error(e));
+                    }
+                    ignored.push(e.path().to_string());
+                }
+                Err(e) => return Err(describe_error(e)),
+            }
+        }
+    }
+
+    fn validated(self) -> Result<Self, String> {
+        if let Some(retention) = &self.retention {
+            retention.validate()?;
+        }
+        Ok(self)
+    }
+
+    /// Serialize to JSON (the form `flm security policy set` stores)
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Requests per minute and burst per client IP
+    pub fn ip_request_limit(&self) -> (u32, u32) {
+        let Some(limits) = self.ip_rate_limit else {
+            return (DEFAULT_IP_RATE_LIMIT_RPM, DEFAULT_IP_RATE_LIMIT_RPM);
+        };
+        let clamp =
+            |value: Option<u64>| value.map_or(u32::MAX, |v| u32::try_from(v).unwrap_or(u32::MAX));
+        let rpm = clamp(limits.rpm).min(DEFAULT_IP_RATE_LIMIT_RPM);
+        (rpm, clamp(limits.burst).min(rpm))
+    }
+
+    /// Rules a request from `ip` would hit, in the order the proxy applies them
+    ///
+    /// `key` holds the scopes of the API key the request authenticates with.
+    pub fn explain(&self, ip: IpAddr, key: Option<&ApiKeyScopes>) -> Vec<RuleMatch> {
+        let mut rules = Vec::new();
+
+        if let Some(scopes) = key {
+            rules.push(match_ip_list(
+                "api_key.ip_allowlist",
+                &scopes.ip_allowlist,
+                ip,
+                "key has no IP allowlist",
+            ));
+        }
+
+        rules.push(match_ip_list(
+            "ip_whitelist",
+            self.ip_whitelist.as_deref().unwrap_or_default(),
+            ip,
+            "no IP whitelist",
+        ));
+
+        let (rpm, burst) = self.ip_request_limit();
+        let source = if self.ip_rate_limit.is_some() {
+            "policy"
+        } else {
+            "default"
+        };
+        rules.push(RuleMatch::new(
+            "ip_rate_limit",
+            RuleVerdict::Limit,
+            format!("{rpm} requests/min, burst {burst} per client IP ({source})"),
+        ));
+
+        let Some(scopes) = key else {
+            let detail = "applies to requests with an API key";
+            rules.push(RuleMatch::new("rate_limit", RuleVerdict::Skip, detail));
+            rules.push(RuleMatch::new("token_limits", RuleVerdict::Skip, detail));
+            return rules;
+        };
+
+        let overridden = !scopes.rate_limit.is_empty();
+        let rate_limit = self
+            .rate_limit
+            .unwrap_or_default()
+            .with_key_overrides(&scopes.rate_limit);
+        let source = if overridden { "key override" } else { "policy" };
+        rules.push(match rate_limit.request_limit() {
+            Some((rpm, burst)) => RuleMatch::new(
+                "rate_limit",
+                RuleVerdict::Limit,
+                format!("{rpm} requests/min, burst {burst} per API key ({source})"),
+            ),
+            None => RuleMatch::new("rate_limit", RuleVerdict::Skip, "no request limit"),
+        });
+
+        let tokens: Vec<String> = [
+            ("tpm", rate_limit.tpm),
+            ("daily_tokens", rate_limit.daily_tokens),
+            ("monthly_tokens", rate_limit.monthly_tokens),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value
+                .filter(|value| *value > 0)
+                .map(|value| format!("{name} {value}"))
+        })
+        .collect();
+        rules.push(if tokens.is_empty() {
+            RuleMatch::new("token_limits", RuleVerdict::Skip, "no token limit")
+        } else {
+            RuleMatch::new(
+                "token_limits",
+                RuleVerdict::Limit,
+                format!("{} per API key ({source})", tokens.join(", ")),
+            )
+        });
+
+        rules
+    }
+
+    /// Fields that differ between `self` and `other`, by dotted path
+    ///
+    /// Lists are compared as a whole.
+    pub fn diff(&self, other: &Self) -> Vec<PolicyChange> {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        flatten(
+            "",
+            serde_json::to_value(self).unwrap_or_default(),
+            &mut before,
+        );
+        flatten(
+            "",
+            serde_json::to_value(other).unwrap_or_default(),
+            &mut after,
+        );
+
+        let paths: BTreeSet<&String> = before.iter().chain(&after).map(|(path, _)| path).collect();
+        let lookup = |fields: &[(String, Value)], path: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == path)
+                .map(|(_, value)| value.clone())
+        };
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let old = lookup(&before, path);
+                let new = lookup(&after, path);
+                (old != new).then(|| PolicyChange {
+                    path: path.clone(),
+                    before: old,
+                    after: new,
+                })
+            })
+            .collect()
+    }
+}
+
+/// `path: reason`, or just the reason for errors at the root
+fn describe_error(e: serde_path_to_error::Error<serde_json::Error>) -> String {
+    let path = e.path().to_string();
+    if path == "." {
+        e.into_inner().to_string()
+    } else {
+        format!("{path}: {}", e.into_inner())
+    }
+}
+
+/// Remove the object field at `path`; false when there is nothing to remove
+fn remove_path(value: &mut Value, path: &serde_path_to_error::Path) -> bool {
+    let mut keys = Vec::new();
+    for segment in path.iter() {
+        match segment {
+            serde_path_to_error::Segment::Map { key } => keys.push(key.as_str()),
+            _ => return false,
+        }
+    }
+    let Some((field, parents)) = keys.split_last() else {
+        return false;
+    };
+    let mut current = value;
+    for key in parents {
+        match current.get_mut(*key) {
+            Some(next) => current = next,
+            None => return false,
+        }
+    }
+    current
+        .as_object_mut()
+        .is_some_and(|object| object.remove(*field).is_some())
+}
+
+/// Upgrade a policy JSON to [`POLICY_SCHEMA_VERSION`]
+///
+/// Fails for documents written by a newer build.
+pub fn migrate_policy(mut value: Value) -> Result<Value, String> {
+    let Some(root) = value.as_object_mut() else {
+        return Err("policy must be a JSON object".to_string());
+    };
+    let version = match root.get("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("schema_version: expected a positive integer, got {version}"))?,
+    };
+    if version > POLICY_SCHEMA_VERSION {
+        return Err(format!(
+            "schema_version: {version} is newer than the supported version {POLICY_SCHEMA_VERSION}"
+        ));
+    }
+    if version < 2 {
+        // Version 1 readers accepted a single origin as a plain string
+        if let Some(Value::Object(cors)) = root.get_mut("cors") {
+            if let Some(Value::String(origin)) = cors.get("allowed_origins") {
+                let origins = Value::Array(vec![Value::String(origin.clone())]);
+                cors.insert("allowed_origins".to_string(), origins);
+            }
+        }
+    }
+    root.insert(
+        "schema_version".to_string(),
+        Value::from(POLICY_SCHEMA_VERSION),
+    );
+    Ok(value)
+}
+
+/// Verdict of one policy rule for a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleVerdict {
+    /// The rule lets the request through
+    Allow,
+    /// The rule rejects the request
+    Deny,
+    /// The request counts against a limit
+    Limit,
+    /// The rule does not apply
+    Skip,
+}
+
+impl RuleVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Limit => "limit",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+/// A policy rule and what it does to a request (see [`PolicyDocument::explain`])
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RuleMatch {
+    /// Policy field or key scope the rule comes from
+    pub rule: &'static str,
+    pub verdict: RuleVerdict,
+    pub detail: String,
+}
+
+impl RuleMatch {
+    fn new(rule: &'static str, verdict: RuleVerdict, detail: impl Into<String>) -> Self {
+        Self {
+            rule,
+            verdict,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A field changed between two policies (see [`PolicyDocument::diff`])
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PolicyChange {
+    /// Dotted field path, e.g. `rate_limit.rpm`
+    pub path: String,
+    /// Old value (`None` when added)
+    pub before: Option<Value>,
+    /// New value (`None` when removed)
+    pub after: Option<Value>,
+}
+
+fn match_ip_list(rule: &'static str, entries: &[String], ip: IpAddr, empty: &str) -> RuleMatch {
+    if entries.is_empty() {
+        return RuleMatch::new(rule, RuleVerdict::Skip, empty);
+    }
+    match entries.iter().find(|entry| ip_matches(entry, ip)) {
+        Some(entry) => RuleMatch::new(rule, RuleVerdict::Allow, format!("{ip} matches {entry}")),
+        None => RuleMatch::new(
+            rule,
+            RuleVerdict::Deny,
+            format!("{ip} matches none of {} entries", entries.len()),
+        ),
+    }
+}
+
+/// Whether `ip` is the address or inside the CIDR range `entry`
+fn ip_matches(entry: &str, ip: IpAddr) -> bool {
+    let Some((network, prefix)) = entry.split_once('/') else {
+        return entry.parse::<IpAddr>().is_ok_and(|entry| entry == ip);
+    };
+    let Ok(prefix) = prefix.parse::<u32>() else {
+        return false;
+    };
+    match (network.parse::<IpAddr>(), ip) {
+        (Ok(IpAddr::V4(network)), IpAddr::V4(ip)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (Ok(IpAddr::V6(network)), IpAddr::V6(ip)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+fn flatten(prefix: &str, value: Value, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (name, value) in fields {
+                let path = if prefix.is_empty() {
+                    name
+                } else {
+                    format!("{prefix}.{name}")
+                };
+                flatten(&path, value, out);
+            }
+        }
+        value => out.push((prefix.to_string(), value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_migrates_version_1() {
+        let document = PolicyDocument::parse(
+            r#"{"ip_whitelist":null,"cors":{"allowed_origins":"https://example.com"}}"#,
+        )
+        .unwrap();
+        assert_eq!(document.schema_version, POLICY_SCHEMA_VERSION);
+        assert_eq!(document.ip_whitelist, None);
+        assert_eq!(
+            document.cors.unwrap().allowed_origins,
+            Some(vec!["https://example.com".to_string()])
+        );
+
+        let error = PolicyDocument::parse(r#"{"schema_version":3}"#).unwrap_err();
+        assert!(
+            error.contains("newer than the supported version"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_parse_names_invalid_fields() {
+        for (policy, expected) in [
+            (r#"{"ip_whitelsit":[]}"#, "unknown field `ip_whitelsit`"),
+            (
+                r#"{"rate_limit":{"rpmm":60}}"#,
+                "rate_limit.rpmm: unknown field `rpmm`",
+            ),
+            (
+                r#"{"rate_limit":{"rpm":"60"}}"#,
+                "rate_limit.rpm: invalid type: string \"60\"",
+            ),
+            (
+                r#"{"retention":{"audit_log":{"max_rows":10}}}"#,
+                "retention.audit_log: unknown field `audit_log`",
+            ),
+            (
+                r#"{"retention":{"audit_logs":{"max_rows":0}}}"#,
+                "retention.audit_logs.max_rows must be at least 1",
+            ),
+            ("[]", "policy must be a JSON object"),
+        ] {
+            let error = PolicyDocument::parse(policy).unwrap_err();
+            assert!(error.contains(expected), "{policy}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_drops_unknown_fields() {
+        let policy = r#"{"ip_whitelist":["10.0.0.0/8"],"log_level":"debug","cors":{"allowed_origins":["https://example.com"],"max_age":600},"retention":{"audit_logs":{"max_rows":10,"compress":true}}}"#;
+        assert!(PolicyDocument::parse(policy).is_err());
+
+        let (document, ignored) = PolicyDocument::parse_lenient(policy).unwrap();
+        assert_eq!(
+            ignored,
+            vec!["cors.max_age", "log_level", "retention.audit_logs.compress"]
+        );
+        assert_eq!(document.ip_whitelist, Some(vec!["10.0.0.0/8".to_string()]));
+        assert_eq!(
+            document.cors.unwrap().allowed_origins,
+            Some(vec!["https://example.com".to_string()])
+        );
+        assert_eq!(
+            document.retention.unwrap().audit_logs.unwrap().max_rows,
+            Some(10)
+        );
+
+        // Fields it knows still have to be valid
+        for policy in [
+            r#"{"extra":1,"rate_limit":{"rpm":"60"}}"#,
+            r#"{"extra":1,"retention":{"audit_logs":{"max_rows":0}}}"#,
+            r#"{"schema_version":3,"extra":1}"#,
+        ] {
+            assert!(PolicyDocument::parse_lenient(policy).is_err(), "{policy}");
+        }
+    }
+
+    #[test]
+    fn test_ip_request_limit() {
+        assert_eq!(PolicyDocument::default().ip_request_limit(), (1000, 1000));
+        let document =
+            PolicyDocument::parse(r#"{"ip_rate_limit":{"rpm":5000,"burst":20}}"#).unwrap();
+        assert_eq!(document.ip_request_limit(), (1000, 20));
+        let document = PolicyDocument::parse(r#"{"ip_rate_limit":{"rpm":60}}"#).unwrap();
+        assert_eq!(document.ip_request_limit(), (60, 60));
+    }
+
+    #[test]
+    fn test_explain_rules_for_request() {
+        let document = PolicyDocument::parse(
+            r#"{"ip_whitelist":["10.0.0.0/8"],"rate_limit":{"rpm":60,"tpm":1000}}"#,
+        )
+        .unwrap();
+        let verdicts = |rules: Vec<RuleMatch>| {
+            rules
+                .into_iter()
+                .map(|rule| (rule.rule, rule.verdict))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            verdicts(document.explain("10.1.2.3".parse().unwrap(), None)),
+            vec![
+                ("ip_whitelist", RuleVerdict::Allow),
+                ("ip_rate_limit", RuleVerdict::Limit),
+                ("rate_limit", RuleVerdict::Skip),
+                ("token_limits", RuleVerdict::Skip),
+            ]
+        );
+
+        let scopes = ApiKeyScopes {
+            ip_allowlist: vec!["192.168.0.0/16".to_string()],
+            rate_limit: ApiKeyRateLimit {
+                rpm: Some(5),
+                ..ApiKeyRateLimit::default()
+            },
+            ..ApiKeyScopes::default()
+        };
+        let rules = document.explain("192.168.1.1".parse().unwrap(), Some(&scopes));
+        assert_eq!(
+            verdicts(rules.clone()),
+            vec![
+                ("api_key.ip_allowlist", RuleVerdict::Allow),
+                ("ip_whitelist", RuleVerdict::Deny),
+                ("ip_rate_limit", RuleVerdict::Limit),
+                ("rate_limit", RuleVerdict::Limit),
+                ("token_limits", RuleVerdict::Limit),
+            ]
+        );
+        assert_eq!(
+            rules[3].detail,
+            "5 requests/min, burst 5 per API key (key override)"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_key_overrides() {
+        let policy = RateLimitPolicy {
+            rpm: Some(60),
+            burst: Some(10),
+            ..RateLimitPolicy::default()
+        };
+        let limits = ApiKeyRateLimit {
+            rpm: Some(5),
+            tpm: Some(1000),
+            ..ApiKeyRateLimit::default()
+        };
+        let merged = policy.with_key_overrides(&limits);
+        assert_eq!(merged.request_limit(), Some((5, 10)));
+        assert_eq!(merged.tpm, Some(1000));
+        assert_eq!(
+            policy.with_key_overrides(&ApiKeyRateLimit::default()),
+            policy
+        );
+    }
+
+    #[test]
+    fn test_diff_lists_changed_fields() {
+        let before =
+            PolicyDocument::parse(r#"{"ip_whitelist":["127.0.0.1"],"rate_limit":{"rpm":60}}"#)
+                .unwrap();
+        let after = PolicyDocument::parse(
+            r#"{"ip_whitelist":["127.0.0.1"],"rate_limit":{"rpm":120,"burst":20},"acme_domain":"example.com"}"#,
+        )
+        .unwrap();
+        let changes = after.diff(&after);
+        assert!(changes.is_empty());
+
+        let changes = before.diff(&after);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["acme_domain", "rate_limit.burst", "rate_limit.rpm"]);
+        assert_eq!(changes[0].before, None);
+        assert_eq!(changes[2].before, Some(Value::from(60)));
+        assert_eq!(changes[2].after, Some(Value::from(120)));
+    }
+
+    #[test]
+    fn test_ip_matches() {
+        let ip: IpAddr = "192.168.1.100".parse().unwrap();
+        assert!(ip_matches("192.168.1.100", ip));
+        assert!(ip_matches("192.168.0.0/16", ip));
+        assert!(ip_matches("0.0.0.0/0", ip));
+        assert!(!ip_matches("10.0.0.0/8", ip));
+        assert!(!ip_matches("192.168.1.0/99", ip));
+        assert!(!ip_matches("::/0", ip));
+        let ip: IpAddr = "2001:db8::1".parse().unwrap();
+        assert!(ip_matches("2001:db8::/32", ip));
+        assert!(!ip_matches("2001:db9::/32", ip));
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! See `docs/CORE_API.md` section 2 for the complete specification.
 
+use super::engine::EngineCredentials;
+use super::models::EngineId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Proxy mode enumeration
 ///
@@ -71,12 +74,30 @@ pub struct ProxyConfig {
     /// Resolved DNS credential secret payload (not persisted; runtime only). Ignored unless the `dns01-preview` feature is enabled.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resolved_dns_credential: Option<ResolvedDnsCredential>,
+    /// Bearer tokens and auth header values of registered engines, resolved from the OS keyring by the CLI (not persisted; runtime only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub resolved_engine_credentials: HashMap<EngineId, EngineCredentials>,
     /// Optional override for the lego binary used to fulfill DNS-01 challenges (dns01-preview only)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub acme_dns_lego_path: Option<String>,
     /// Optional propagation wait (seconds) before resuming lego manual workflow (dns01-preview only)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub acme_dns_propagation_secs: Option<u64>,
+    /// Expose the Ollama-compatible API (`/api/chat`, `/api/generate`, `/api/tags`, `/api/show`)
+    #[serde(default)]
+    pub ollama_api: bool,
+    /// Periodic encrypted backups of security.db and config.db (disabled when `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_schedule: Option<BackupScheduleConfig>,
+    /// Backup key from the OS keyring, resolved by the CLI (not persisted; runtime only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_backup_key: Option<String>,
+    /// Audit log HMAC key (base64) from the OS keyring, resolved by the CLI (not persisted; runtime only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_audit_hmac_key: Option<String>,
+    /// Webhook tokens of audit sinks by sink ID, resolved from the OS keyring by the CLI (not persisted; runtime only)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub resolved_audit_sink_tokens: HashMap<String, String>,
     /// Path to config.db (for EngineService, internal use)
     #[serde(skip)]
     pub config_db_path: Option<String>,
@@ -103,8 +124,14 @@ impl Default for ProxyConfig {
             acme_challenge: None,
             acme_dns_profile_id: None,
             resolved_dns_credential: None,
+            resolved_engine_credentials: HashMap::new(),
             acme_dns_lego_path: None,
             acme_dns_propagation_secs: None,
+            ollama_api: false,
+            backup_schedule: None,
+            resolved_backup_key: None,
+            resolved_audit_hmac_key: None,
+            resolved_audit_sink_tokens: HashMap::new(),
             config_db_path: None,
             security_db_path: None,
         }
@@ -116,10 +143,28 @@ impl ProxyConfig {
     pub fn without_secrets(&self) -> Self {
         let mut clone = self.clone();
         clone.resolved_dns_credential = None;
+        clone.resolved_engine_credentials.clear();
+        clone.resolved_backup_key = None;
+        clone.resolved_audit_hmac_key = None;
+        clone.resolved_audit_sink_tokens.clear();
         clone
     }
 }
 
+/// Periodic backups taken by the running proxy
+///
+/// Backups are encrypted with the keyring-held backup key
+/// (`ProxyConfig::resolved_backup_key`); a passphrase is never kept.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupScheduleConfig {
+    /// Hours between backups
+    pub interval_hours: u32,
+    /// Directory the backups are written to
+    pub output_dir: String,
+    /// Backups kept per database; older ones are removed
+    pub retention: u32,
+}
+
 /// Runtime DNS credential bundle passed to the proxy process.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResolvedDnsCredential {
@@ -263,11 +308,34 @@ mod tests {
                 zone_name: Some("example.com".to_string()),
                 token: "secret-token".to_string(),
             }),
+            resolved_engine_credentials: HashMap::from([(
+                "remote-gpu".to_string(),
+                EngineCredentials {
+                    bearer_token: Some("engine-token".to_string()),
+                    auth_header_value: Some("header-secret".to_string()),
+                },
+            )]),
+            backup_schedule: Some(BackupScheduleConfig {
+                interval_hours: 24,
+                output_dir: "/var/backups/flm".to_string(),
+                retention: 7,
+            }),
+            resolved_backup_key: Some("backup-key".to_string()),
+            resolved_audit_hmac_key: Some("audit-key".to_string()),
+            resolved_audit_sink_tokens: HashMap::from([(
+                "siem".to_string(),
+                "webhook-token".to_string(),
+            )]),
             ..Default::default()
         };
 
         let without_secrets = config.without_secrets();
         assert!(without_secrets.resolved_dns_credential.is_none());
+        assert!(without_secrets.resolved_engine_credentials.is_empty());
+        assert!(without_secrets.resolved_backup_key.is_none());
+        assert!(without_secrets.resolved_audit_hmac_key.is_none());
+        assert!(without_secrets.resolved_audit_sink_tokens.is_empty());
+        assert_eq!(without_secrets.backup_schedule, config.backup_schedule);
         assert_eq!(config.mode, without_secrets.mode);
         assert_eq!(config.port, without_secrets.port);
     }
@@ -21,6 +21,191 @@ pub struct ApiKeyRecord {
     pub created_at: String,
     /// Revocation timestamp (ISO8601, None if not revoked)
     pub revoked_at: Option<String>,
+    /// Restrictions on what the key may do (unrestricted by default)
+    #[serde(default)]
+    pub scopes: ApiKeyScopes,
+}
+
+/// Endpoint class an API key can be scoped to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointScope {
+    /// `/v1/chat/completions`, `/v1/completions`, `/v1/messages`, `/api/chat`, `/api/generate`
+    Chat,
+    /// `/v1/embeddings`
+    Embeddings,
+    /// `/v1/audio/transcriptions`, `/v1/audio/speech`
+    Audio,
+    /// `/v1/images/generations`
+    Images,
+    /// `/v1/models`, `/api/tags`, `/api/show`
+    Models,
+    /// `/metrics`
+    Metrics,
+}
+
+impl EndpointScope {
+    /// Wire name stored in `security.db` and accepted by the CLI
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Embeddings => "embeddings",
+            Self::Audio => "audio",
+            Self::Images => "images",
+            Self::Models => "models",
+            Self::Metrics => "metrics",
+        }
+    }
+}
+
+impl std::str::FromStr for EndpointScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chat" => Ok(Self::Chat),
+            "embeddings" => Ok(Self::Embeddings),
+            "audio" => Ok(Self::Audio),
+            "images" => Ok(Self::Images),
+            "models" => Ok(Self::Models),
+            "metrics" => Ok(Self::Metrics),
+            other => Err(format!(
+                "Unknown endpoint scope '{other}' (expected chat, embeddings, audio, images, models or metrics)"
+            )),
+        }
+    }
+}
+
+/// Per-key rate limits
+///
+/// Each set value replaces the matching `rate_limit` value of the security
+/// policy for this key; unset values fall back to the policy.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyRateLimit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tpm: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_tokens: Option<u64>,
+}
+
+impl ApiKeyRateLimit {
+    /// Whether no limit is set
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Scopes of an API key
+///
+/// Stored as JSON in `api_keys.scopes_json` and enforced by the proxy. An
+/// empty list leaves that dimension unrestricted, so a key without scopes can
+/// do everything the security policy allows.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyScopes {
+    /// Endpoint classes the key may call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointScope>,
+    /// Glob patterns (`*`, `?`) over `flm://{engine_id}/{model}` the key may use
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    /// Client IP addresses or CIDR ranges the key may be used from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_allowlist: Vec<String>,
+    /// Rate limits overriding the policy for this key
+    #[serde(default, skip_serializing_if = "ApiKeyRateLimit::is_empty")]
+    pub rate_limit: ApiKeyRateLimit,
+}
+
+impl ApiKeyScopes {
+    /// Whether the key has no restrictions beyond the security policy
+    pub fn is_unrestricted(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether the key may call endpoints of `scope`
+    pub fn allows_endpoint(&self, scope: EndpointScope) -> bool {
+        self.endpoints.is_empty() || self.endpoints.contains(&scope)
+    }
+
+    /// Whether the key may use `model_id` (`flm://{engine_id}/{model}`)
+    pub fn allows_model(&self, model_id: &str) -> bool {
+        self.models.is_empty()
+            || self
+                .models
+                .iter()
+                .any(|pattern| glob_matches(pattern, model_id))
+    }
+
+    /// Check model patterns and IP allowlist entries
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in &self.models {
+            if !pattern.starts_with("flm://") {
+                return Err(format!(
+                    "Model scope '{pattern}' must match flm://{{engine_id}}/{{model}} IDs"
+                ));
+            }
+        }
+        for entry in &self.ip_allowlist {
+            if !is_ip_or_cidr(entry) {
+                return Err(format!(
+                    "IP allowlist entry '{entry}' is not an IP address or CIDR range"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Match `text` against a glob where `*` matches any run and `?` one character
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn is_ip_or_cidr(entry: &str) -> bool {
+    match entry.split_once('/') {
+        Some((ip, prefix)) => {
+            let max_prefix = match ip.parse::<std::net::IpAddr>() {
+                Ok(std::net::IpAddr::V4(_)) => 32,
+                Ok(std::net::IpAddr::V6(_)) => 128,
+                Err(_) => return false,
+            };
+            prefix
+                .parse::<u8>()
+                .is_ok_and(|prefix| prefix <= max_prefix)
+        }
+        None => entry.parse::<std::net::IpAddr>().is_ok(),
+    }
 }
 
 /// API key metadata (without hash, for listing)
@@ -37,6 +222,9 @@ pub struct ApiKeyMetadata {
     pub created_at: String,
     /// Revocation timestamp (ISO8601, None if not revoked)
     pub revoked_at: Option<String>,
+    /// Restrictions on what the key may do (unrestricted by default)
+    #[serde(default)]
+    pub scopes: ApiKeyScopes,
 }
 
 /// Plain text API key with record (returned only on creation)
@@ -90,6 +278,127 @@ pub struct DnsCredentialProfile {
     pub updated_at: String,
 }
 
+/// security.db table that retention rules apply to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionTable {
+    AuditLogs,
+    IntrusionAttempts,
+    AnomalyDetections,
+    ResourceAlerts,
+}
+
+impl RetentionTable {
+    /// All tables, in pruning order
+    pub const ALL: [Self; 4] = [
+        Self::AuditLogs,
+        Self::IntrusionAttempts,
+        Self::AnomalyDetections,
+        Self::ResourceAlerts,
+    ];
+
+    /// Table name in security.db (also the key in the `retention` policy section)
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            Self::AuditLogs => "audit_logs",
+            Self::IntrusionAttempts => "intrusion_attempts",
+            Self::AnomalyDetections => "anomaly_detections",
+            Self::ResourceAlerts => "resource_alerts",
+        }
+    }
+}
+
+/// Limits for one table; rows beyond either limit are pruned oldest first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableRetention {
+    /// Delete rows older than this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    /// Keep at most this many rows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<u64>,
+}
+
+/// Default minutes between pruning runs
+pub const DEFAULT_RETENTION_INTERVAL_MINUTES: u32 = 60;
+
+/// `retention` section of the security policy
+///
+/// ```json
+/// "retention": {
+///   "audit_logs": { "max_age_days": 90, "max_rows": 1000000 },
+///   "intrusion_attempts": { "max_age_days": 30 },
+///   "archive_dir": "/var/lib/flm/archive",
+///   "interval_minutes": 60
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_logs: Option<TableRetention>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intrusion_attempts: Option<TableRetention>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_detections: Option<TableRetention>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_alerts: Option<TableRetention>,
+    /// Write pruned rows to gzip-compressed JSONL files here before deleting them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_dir: Option<String>,
+    /// Minutes between pruning runs (default 60)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_minutes: Option<u32>,
+}
+
+impl RetentionPolicy {
+    /// Limits configured for a table
+    pub fn table(&self, table: RetentionTable) -> Option<TableRetention> {
+        match table {
+            RetentionTable::AuditLogs => self.audit_logs,
+            RetentionTable::IntrusionAttempts => self.intrusion_attempts,
+            RetentionTable::AnomalyDetections => self.anomaly_detections,
+            RetentionTable::ResourceAlerts => self.resource_alerts,
+        }
+    }
+
+    /// Time between pruning runs
+    pub fn interval(&self) -> std::time::Duration {
+        let minutes = self
+            .interval_minutes
+            .unwrap_or(DEFAULT_RETENTION_INTERVAL_MINUTES);
+        std::time::Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    /// Reject limits that would delete everything or never run
+    pub fn validate(&self) -> Result<(), String> {
+        for table in RetentionTable::ALL {
+            let Some(limits) = self.table(table) else {
+                continue;
+            };
+            let name = table.table_name();
+            if limits.max_age_days == Some(0) {
+                return Err(format!("retention.{name}.max_age_days must be at least 1"));
+            }
+            if limits.max_rows == Some(0) {
+                return Err(format!("retention.{name}.max_rows must be at least 1"));
+            }
+        }
+        if self.interval_minutes == Some(0) {
+            return Err("retention.interval_minutes must be at least 1".to_string());
+        }
+        if self
+            .archive_dir
+            .as_deref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err("retention.archive_dir must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +411,7 @@ mod tests {
             hash: "$argon2id$v=19$m=65536,t=3,p=4$hash".to_string(),
             created_at: "2025-01-27T00:00:00Z".to_string(),
             revoked_at: None,
+            scopes: ApiKeyScopes::default(),
         };
 
         let json = serde_json::to_string(&record).unwrap();
@@ -121,6 +431,7 @@ mod tests {
             hash: "$argon2id$v=19$m=65536,t=3,p=4$hash".to_string(),
             created_at: "2025-01-27T00:00:00Z".to_string(),
             revoked_at: Some("2025-01-28T00:00:00Z".to_string()),
+            scopes: ApiKeyScopes::default(),
         };
 
         assert!(record.revoked_at.is_some());
@@ -136,6 +447,7 @@ mod tests {
             label: "Test Key".to_string(),
             created_at: "2025-01-27T00:00:00Z".to_string(),
             revoked_at: None,
+            scopes: ApiKeyScopes::default(),
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -156,6 +468,7 @@ mod tests {
                 hash: "$argon2id$v=19$m=65536,t=3,p=4$hash".to_string(),
                 created_at: "2025-01-27T00:00:00Z".to_string(),
                 revoked_at: None,
+                scopes: ApiKeyScopes::default(),
             },
         };
 
@@ -218,4 +531,139 @@ mod tests {
         let deserialized: DnsCredentialProfile = serde_json::from_str(&json).unwrap();
         assert_eq!(profile.zone_name, deserialized.zone_name);
     }
+
+    #[test]
+    fn test_api_key_record_without_scopes_is_unrestricted() {
+        let json = r#"{"id":"key-1","label":"Old Key","hash":"h","created_at":"2025-01-27T00:00:00Z","revoked_at":null}"#;
+        let record: ApiKeyRecord = serde_json::from_str(json).unwrap();
+        assert!(record.scopes.is_unrestricted());
+        assert!(record.scopes.allows_endpoint(EndpointScope::Audio));
+        assert!(record.scopes.allows_model("flm://vllm/llama3:70b"));
+    }
+
+    #[test]
+    fn test_api_key_scopes_match_endpoints_and_model_globs() {
+        let scopes = ApiKeyScopes {
+            endpoints: vec![EndpointScope::Chat, EndpointScope::Models],
+            models: vec![
+                "flm://ollama-default/llama3:8b*".to_string(),
+                "flm://lmstudio/*".to_string(),
+                "flm://vllm/qwen2.5-?b".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert!(scopes.allows_endpoint(EndpointScope::Chat));
+        assert!(!scopes.allows_endpoint(EndpointScope::Embeddings));
+        assert!(scopes.allows_model("flm://ollama-default/llama3:8b"));
+        assert!(scopes.allows_model("flm://ollama-default/llama3:8b-instruct-q4"));
+        assert!(scopes.allows_model("flm://lmstudio/any/model"));
+        assert!(scopes.allows_model("flm://vllm/qwen2.5-7b"));
+        assert!(!scopes.allows_model("flm://vllm/qwen2.5-14b"));
+        assert!(!scopes.allows_model("flm://ollama-default/llama3:70b"));
+        assert!(!scopes.allows_model("flm://other/llama3:8b"));
+    }
+
+    #[test]
+    fn test_api_key_scopes_validate() {
+        let scopes = ApiKeyScopes {
+            models: vec!["flm://ollama-default/*".to_string()],
+            ip_allowlist: vec![
+                "192.168.1.10".to_string(),
+                "10.0.0.0/8".to_string(),
+                "2001:db8::/32".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert!(scopes.validate().is_ok());
+
+        for invalid in [
+            ApiKeyScopes {
+                models: vec!["llama3*".to_string()],
+                ..Default::default()
+            },
+            ApiKeyScopes {
+                ip_allowlist: vec!["10.0.0.0/33".to_string()],
+                ..Default::default()
+            },
+            ApiKeyScopes {
+                ip_allowlist: vec!["example.com".to_string()],
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_api_key_scopes_serialization_omits_unset_fields() {
+        let scopes = ApiKeyScopes {
+            endpoints: vec![EndpointScope::Chat],
+            rate_limit: ApiKeyRateLimit {
+                rpm: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&scopes).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"endpoints": ["chat"], "rate_limit": {"rpm": 10}})
+        );
+        assert_eq!(
+            serde_json::to_value(ApiKeyScopes::default()).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn test_retention_policy_section() {
+        use crate::domain::policy::PolicyDocument;
+
+        let policy = serde_json::json!({
+            "ip_whitelist": [],
+            "retention": {
+                "audit_logs": { "max_age_days": 90, "max_rows": 1000000 },
+                "resource_alerts": { "max_rows": 10 },
+                "archive_dir": "/var/lib/flm/archive"
+            }
+        });
+        let retention = PolicyDocument::from_value(policy)
+            .unwrap()
+            .retention
+            .unwrap();
+        assert_eq!(
+            retention.table(RetentionTable::AuditLogs),
+            Some(TableRetention {
+                max_age_days: Some(90),
+                max_rows: Some(1_000_000),
+            })
+        );
+        assert_eq!(retention.table(RetentionTable::IntrusionAttempts), None);
+        assert_eq!(retention.interval(), std::time::Duration::from_secs(3600));
+
+        assert_eq!(PolicyDocument::parse("{}").unwrap().retention, None);
+        for (retention, expected) in [
+            (
+                serde_json::json!({ "audit_log": { "max_rows": 10 } }),
+                "unknown field `audit_log`",
+            ),
+            (
+                serde_json::json!({ "audit_logs": { "max_days": 10 } }),
+                "unknown field `max_days`",
+            ),
+            (
+                serde_json::json!({ "intrusion_attempts": { "max_age_days": 0 } }),
+                "retention.intrusion_attempts.max_age_days must be at least 1",
+            ),
+            (
+                serde_json::json!({ "interval_minutes": 0 }),
+                "retention.interval_minutes must be at least 1",
+            ),
+        ] {
+            let error = PolicyDocument::from_value(serde_json::json!({ "retention": retention }))
+                .unwrap_err();
+            assert!(error.contains(expected), "{error}");
+        }
+    }
 }
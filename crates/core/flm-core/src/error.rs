@@ -27,6 +27,9 @@ pub enum EngineError {
 
     #[error("Unsupported operation: {operation} ({reason})")]
     UnsupportedOperation { operation: String, reason: String },
+
+    #[error("Engine process error ({engine_id}): {reason}")]
+    ProcessError { engine_id: String, reason: String },
 }
 
 /// Proxy-related errors
@@ -0,0 +1,15 @@
+//! API prompt repository trait
+
+use crate::domain::models::ApiPrompt;
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// API prompt repository trait
+///
+/// Read side used by the proxy to inject managed system prompts.
+/// Templates are written by `flm api prompts set`.
+#[async_trait]
+pub trait ApiPromptRepository: Send + Sync {
+    /// Get the template for an endpoint (e.g. `chat_completions`)
+    async fn get(&self, api_id: &str) -> Result<Option<ApiPrompt>, RepoError>;
+}
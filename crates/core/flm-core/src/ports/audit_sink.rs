@@ -0,0 +1,24 @@
+//! Audit sink repository trait
+
+use crate::domain::audit::{AuditSink, AuditSinkConfig};
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Audit sink repository trait
+///
+/// The proxy lists the sinks to start its audit forwarders; the CLI manages
+/// them with `flm security audit-logs sinks`.
+#[async_trait]
+pub trait AuditSinkRepository: Send + Sync {
+    /// List all configured sinks ordered by ID
+    async fn list_sinks(&self) -> Result<Vec<AuditSink>, RepoError>;
+
+    /// Find a sink by ID
+    async fn find_sink(&self, id: &str) -> Result<Option<AuditSink>, RepoError>;
+
+    /// Insert or replace a sink
+    async fn save_sink(&self, id: &str, config: &AuditSinkConfig) -> Result<AuditSink, RepoError>;
+
+    /// Remove a sink. Returns true if a row was removed.
+    async fn delete_sink(&self, id: &str) -> Result<bool, RepoError>;
+}
@@ -0,0 +1,30 @@
+//! Concurrency limit repository trait
+
+use crate::domain::models::ConcurrencyLimit;
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Concurrency limit repository trait
+///
+/// The proxy lists the limits to build its request queues at startup; the
+/// CLI manages them with `flm concurrency-limits`.
+#[async_trait]
+pub trait ConcurrencyLimitRepository: Send + Sync {
+    /// List all configured limits ordered by target
+    async fn list_limits(&self) -> Result<Vec<ConcurrencyLimit>, RepoError>;
+
+    /// Find the limit for an engine or model ID
+    async fn find_limit(&self, target: &str) -> Result<Option<ConcurrencyLimit>, RepoError>;
+
+    /// Insert or replace the limit for a target
+    async fn save_limit(
+        &self,
+        target: &str,
+        max_concurrent: u32,
+        max_queue: u32,
+        queue_timeout_secs: u32,
+    ) -> Result<ConcurrencyLimit, RepoError>;
+
+    /// Remove the limit for a target. Returns true if a row was removed.
+    async fn delete_limit(&self, target: &str) -> Result<bool, RepoError>;
+}
@@ -1,13 +1,18 @@
 //! Engine-related port traits
 
 use crate::domain::chat::{
-    ChatRequest, ChatResponse, ChatStreamChunk, EmbeddingRequest, EmbeddingResponse,
-    TranscriptionRequest, TranscriptionResponse,
+    ChatRequest, ChatResponse, ChatStreamChunk, CompletionRequest, CompletionResponse,
+    CompletionStreamChunk, EmbeddingRequest, EmbeddingResponse, ImageGenerationRequest,
+    ImageGenerationResponse, SpeechRequest, TranscriptionRequest, TranscriptionResponse,
 };
 #[allow(unused_imports)]
-use crate::domain::engine::{EngineBinaryInfo, EngineRuntimeInfo, EngineState, ModelInfo};
+use crate::domain::engine::{
+    EngineBinaryInfo, EngineProcessConfig, EngineProcessStatus, EngineRuntimeInfo, EngineState,
+    ModelInfo,
+};
 use crate::domain::models::EngineCapabilities;
 use crate::error::EngineError;
+use crate::ports::ModelManager;
 use async_trait::async_trait;
 use futures::Stream;
 use std::pin::Pin;
@@ -16,6 +21,13 @@ use std::sync::Arc;
 /// Type alias for chat stream (used in LlmEngine trait)
 pub type ChatStream = Pin<Box<dyn Stream<Item = Result<ChatStreamChunk, EngineError>> + Send>>;
 
+/// Type alias for text completion stream (used in LlmEngine trait)
+pub type CompletionStream =
+    Pin<Box<dyn Stream<Item = Result<CompletionStreamChunk, EngineError>> + Send>>;
+
+/// Type alias for synthesized audio byte stream (used in LlmEngine trait)
+pub type AudioStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>, EngineError>> + Send>>;
+
 /// LLM Engine trait
 ///
 /// All engine adapters must implement this trait.
@@ -34,6 +46,30 @@ pub trait LlmEngine: Send + Sync {
 
     async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError>;
 
+    /// Complete a raw text prompt (legacy completions API)
+    ///
+    /// Engines without a raw completion endpoint keep the default, which
+    /// returns `EngineError::UnsupportedOperation`.
+    async fn complete(&self, _req: CompletionRequest) -> Result<CompletionResponse, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "complete".to_string(),
+            reason: "Engine does not support raw text completion".to_string(),
+        })
+    }
+
+    /// Stream a raw text completion (legacy completions API)
+    ///
+    /// See `complete` for the default behaviour.
+    async fn complete_stream(
+        &self,
+        _req: CompletionRequest,
+    ) -> Result<CompletionStream, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "complete_stream".to_string(),
+            reason: "Engine does not support raw text completion".to_string(),
+        })
+    }
+
     /// Transcribe audio to text
     ///
     /// This method is optional and should only be implemented by engines
@@ -50,6 +86,40 @@ pub trait LlmEngine: Send + Sync {
             reason: "Engine does not support audio transcription".to_string(),
         })
     }
+
+    /// Synthesize speech from text
+    ///
+    /// Returns the encoded audio (in `req.format`) as a byte stream so callers
+    /// can forward it while the engine is still generating. Only engines that
+    /// advertise `EngineCapabilities::audio_outputs` implement this.
+    async fn synthesize_speech(&self, _req: SpeechRequest) -> Result<AudioStream, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "synthesize_speech".to_string(),
+            reason: "Engine does not support speech synthesis".to_string(),
+        })
+    }
+
+    /// Generate images from a text prompt
+    ///
+    /// Only engines that advertise `EngineCapabilities::image_generation`
+    /// implement this; the default returns `EngineError::UnsupportedOperation`.
+    async fn generate_images(
+        &self,
+        _req: ImageGenerationRequest,
+    ) -> Result<ImageGenerationResponse, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "generate_images".to_string(),
+            reason: "Engine does not support image generation".to_string(),
+        })
+    }
+
+    /// Model management (pull/delete/show/copy) for this engine
+    ///
+    /// Engines that can install models (Ollama) return themselves; the
+    /// default is `None`.
+    fn model_manager(&self) -> Option<&dyn ModelManager> {
+        None
+    }
 }
 
 /// Engine repository trait
@@ -60,7 +130,35 @@ pub trait EngineRepository: Send + Sync {
 }
 
 /// Engine process controller trait
+///
+/// Detection is required; lifecycle management (`start`/`stop`/`status`) is
+/// optional and only implemented by controllers that can spawn local engine
+/// processes (the CLI's supervisor-based controller).
 pub trait EngineProcessController: Send + Sync {
     fn detect_binaries(&self) -> Vec<EngineBinaryInfo>;
     fn detect_running(&self) -> Vec<EngineRuntimeInfo>;
+
+    /// Launch and supervise an engine process
+    ///
+    /// Returns once the process is running (or has failed to start).
+    /// Starting an engine that is already running is an error.
+    fn start(&self, _config: &EngineProcessConfig) -> Result<EngineProcessStatus, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "start".to_string(),
+            reason: "Process controller cannot start engines".to_string(),
+        })
+    }
+
+    /// Stop a supervised engine process and wait for it to exit
+    fn stop(&self, _engine_id: &str) -> Result<EngineProcessStatus, EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "stop".to_string(),
+            reason: "Process controller cannot stop engines".to_string(),
+        })
+    }
+
+    /// Current status of a supervised engine process (None if never started)
+    fn status(&self, _engine_id: &str) -> Option<EngineProcessStatus> {
+        None
+    }
 }
@@ -0,0 +1,24 @@
+//! Engine process configuration repository trait
+
+use crate::domain::engine::EngineProcessConfig;
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Engine process configuration repository trait
+///
+/// Stores launch settings for engines started with `flm engines start`.
+/// The running state itself is owned by the `EngineProcessController`.
+#[async_trait]
+pub trait EngineProcessConfigRepository: Send + Sync {
+    /// List all process configurations ordered by engine ID
+    async fn list(&self) -> Result<Vec<EngineProcessConfig>, RepoError>;
+
+    /// Get the process configuration for an engine
+    async fn get(&self, engine_id: &str) -> Result<Option<EngineProcessConfig>, RepoError>;
+
+    /// Insert or replace a process configuration
+    async fn save(&self, config: &EngineProcessConfig) -> Result<(), RepoError>;
+
+    /// Remove a process configuration. Returns true if a row was removed.
+    async fn remove(&self, engine_id: &str) -> Result<bool, RepoError>;
+}
@@ -0,0 +1,24 @@
+//! Engine registry repository trait
+
+use crate::domain::engine::EngineRegistration;
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Engine registry repository trait
+///
+/// Stores engines registered with `flm engines add`. The CLI and the proxy
+/// build adapters from these entries at startup.
+#[async_trait]
+pub trait EngineRegistryRepository: Send + Sync {
+    /// List all registered engines ordered by ID
+    async fn list(&self) -> Result<Vec<EngineRegistration>, RepoError>;
+
+    /// Get a registered engine by ID
+    async fn get(&self, id: &str) -> Result<Option<EngineRegistration>, RepoError>;
+
+    /// Insert or replace a registration
+    async fn save(&self, registration: &EngineRegistration) -> Result<(), RepoError>;
+
+    /// Remove a registration. Returns true if a row was removed.
+    async fn remove(&self, id: &str) -> Result<bool, RepoError>;
+}
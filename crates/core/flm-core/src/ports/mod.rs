@@ -5,16 +5,32 @@
 //!
 //! See `docs/CORE_API.md` section 4 for the complete specification.
 
+pub mod api_prompt;
+pub mod audit_sink;
+pub mod concurrency_limit;
 pub mod config;
 pub mod engine;
 pub mod engine_health_log;
+pub mod engine_process;
+pub mod engine_registry;
 pub mod http;
+pub mod model_group;
+pub mod model_manager;
+pub mod model_profile;
 pub mod proxy;
 pub mod security;
 
+pub use api_prompt::*;
+pub use audit_sink::*;
+pub use concurrency_limit::*;
 pub use config::*;
 pub use engine::*;
 pub use engine_health_log::*;
+pub use engine_process::*;
+pub use engine_registry::*;
 pub use http::*;
+pub use model_group::*;
+pub use model_manager::*;
+pub use model_profile::*;
 pub use proxy::*;
 pub use security::*;
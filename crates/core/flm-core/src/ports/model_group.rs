@@ -0,0 +1,29 @@
+//! Model group repository trait
+
+use crate::domain::models::{LoadBalanceStrategy, ModelGroup};
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Model group repository trait
+///
+/// The proxy resolves groups at request time through `find_group`; the CLI
+/// manages them with `flm model-groups`.
+#[async_trait]
+pub trait ModelGroupRepository: Send + Sync {
+    /// Find a group by its public name
+    async fn find_group(&self, name: &str) -> Result<Option<ModelGroup>, RepoError>;
+
+    /// List all groups ordered by name
+    async fn list_groups(&self) -> Result<Vec<ModelGroup>, RepoError>;
+
+    /// Insert or replace a group and its ordered members
+    async fn save_group(
+        &self,
+        name: &str,
+        strategy: LoadBalanceStrategy,
+        members: &[String],
+    ) -> Result<ModelGroup, RepoError>;
+
+    /// Remove a group. Returns true if a row was removed.
+    async fn delete_group(&self, name: &str) -> Result<bool, RepoError>;
+}
@@ -0,0 +1,46 @@
+//! Model management port trait
+
+use crate::domain::engine::{ModelDetails, ModelPullProgress, ModelPullRequest};
+use crate::domain::models::EngineId;
+use crate::error::EngineError;
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+
+/// Type alias for model pull progress stream (used in ModelManager trait)
+pub type ModelPullStream =
+    Pin<Box<dyn Stream<Item = Result<ModelPullProgress, EngineError>> + Send>>;
+
+/// Model management trait
+///
+/// Installs and removes models on an engine (Ollama) or in a local model
+/// store (the managed GGUF directory used by llama.cpp). Engines expose it
+/// through `LlmEngine::model_manager()`; the CLI (`flm models pull|delete|show|copy`)
+/// and the UI use this trait rather than engine-specific APIs.
+#[async_trait]
+pub trait ModelManager: Send + Sync {
+    /// ID of the engine (or store) whose models are managed
+    fn engine_id(&self) -> EngineId;
+
+    /// List installed models with their details
+    async fn list_installed(&self) -> Result<Vec<ModelDetails>, EngineError>;
+
+    /// Download a model, streaming progress events
+    ///
+    /// The stream ends after an event with status `success`, or with an error.
+    async fn pull(&self, req: ModelPullRequest) -> Result<ModelPullStream, EngineError>;
+
+    /// Delete an installed model
+    async fn delete(&self, model: &str) -> Result<(), EngineError>;
+
+    /// Show details of an installed model
+    async fn show(&self, model: &str) -> Result<ModelDetails, EngineError>;
+
+    /// Copy an installed model under a new name
+    async fn copy(&self, _source: &str, _destination: &str) -> Result<(), EngineError> {
+        Err(EngineError::UnsupportedOperation {
+            operation: "copy".to_string(),
+            reason: "Model store does not support copying models".to_string(),
+        })
+    }
+}
@@ -0,0 +1,25 @@
+//! Model profile repository trait
+
+use crate::domain::models::ModelProfile;
+use crate::error::RepoError;
+use async_trait::async_trait;
+
+/// Model profile repository trait
+///
+/// Read side used by the proxy to resolve profiles at request time.
+/// Profiles are written by `flm model-profiles save`.
+#[async_trait]
+pub trait ModelProfileRepository: Send + Sync {
+    /// Find the most recently updated profile with the given label
+    async fn find_by_label(&self, label: &str) -> Result<Option<ModelProfile>, RepoError>;
+
+    /// Find the profile applied implicitly to `model_id`
+    ///
+    /// A profile labelled `default` wins; otherwise the most recently updated
+    /// profile for the model is returned.
+    async fn find_for_model(
+        &self,
+        engine_id: &str,
+        model_id: &str,
+    ) -> Result<Option<ModelProfile>, RepoError>;
+}
@@ -3,15 +3,18 @@
 //! See `docs/CORE_API.md` section 5 for the complete specification.
 
 use crate::domain::chat::{
-    ChatRequest, ChatResponse, ChatStreamChunk, EmbeddingRequest, EmbeddingResponse,
+    ChatRequest, ChatResponse, ChatStreamChunk, CompletionRequest, CompletionResponse,
+    EmbeddingRequest, EmbeddingResponse,
 };
 use crate::domain::engine::{
-    EngineBinaryInfo, EngineRuntimeInfo, EngineState, EngineStatus, HealthStatus, ModelInfo,
+    EngineBinaryInfo, EngineRegistration, EngineRuntimeInfo, EngineState, EngineStatus,
+    HealthStatus, ModelInfo,
 };
 use crate::domain::models::{EngineCapabilities, EngineId, EngineKind};
 use crate::error::EngineError;
 use crate::ports::{
-    EngineHealthLogRepository, EngineProcessController, EngineRepository, HttpClient,
+    CompletionStream, EngineHealthLogRepository, EngineProcessController, EngineRegistryRepository,
+    EngineRepository, HttpClient, LlmEngine,
 };
 use futures::Stream;
 use std::pin::Pin;
@@ -42,6 +45,7 @@ pub struct EngineService {
     http_client: Box<dyn HttpClient + Send + Sync>,
     engine_repo: Box<dyn EngineRepository + Send + Sync>,
     health_log_repo: Option<Arc<dyn EngineHealthLogRepository + Send + Sync>>,
+    engine_registry: Option<Arc<dyn EngineRegistryRepository>>,
 }
 
 impl EngineService {
@@ -57,6 +61,7 @@ impl EngineService {
             http_client,
             engine_repo,
             health_log_repo: None,
+            engine_registry: None,
         }
     }
 
@@ -72,9 +77,21 @@ impl EngineService {
             http_client,
             engine_repo,
             health_log_repo: Some(health_log_repo),
+            engine_registry: None,
         }
     }
 
+    /// Include engines registered in `config.db` (`flm engines add`) in detection
+    ///
+    /// Registered engines are reported ahead of auto-detected ones and win on
+    /// ID collisions. Engines already present in the engine repository are
+    /// probed through their adapter (so auth headers and TLS settings apply);
+    /// others fall back to a plain API ping.
+    pub fn with_engine_registry(mut self, registry: Arc<dyn EngineRegistryRepository>) -> Self {
+        self.engine_registry = Some(registry);
+        self
+    }
+
     /// Detect all available engines
     ///
     /// This method follows the detection steps in ENGINE_DETECT.md:
@@ -84,9 +101,43 @@ impl EngineService {
     pub async fn detect_engines(&self) -> Result<Vec<EngineState>, EngineError> {
         let mut states = Vec::new();
 
+        // Step 0: Engines registered explicitly in config.db
+        if let Some(registry) = &self.engine_registry {
+            match registry.list().await {
+                Ok(registrations) => {
+                    let registered = self.engine_repo.list_registered().await;
+                    for registration in registrations {
+                        let state = match registered.iter().find(|e| e.id() == registration.id) {
+                            Some(engine) => {
+                                Self::detect_engine_from_adapter(engine.as_ref(), &registration)
+                                    .await
+                            }
+                            None => {
+                                let runtime = EngineRuntimeInfo {
+                                    engine_id: registration.id.clone(),
+                                    kind: registration.kind.clone(),
+                                    base_url: registration.base_url.clone(),
+                                    port: None,
+                                };
+                                self.detect_engine_from_runtime(&runtime).await?
+                            }
+                        };
+                        states.push(state);
+                    }
+                }
+                Err(e) => {
+                    // Log error but fall back to auto-detection only
+                    eprintln!("Warning: Failed to load registered engines: {e}");
+                }
+            }
+        }
+
         // Step 1: Detect binaries
         let binaries = self.process_controller.detect_binaries();
         for binary in binaries {
+            if states.iter().any(|s| s.id == binary.engine_id) {
+                continue;
+            }
             // For binaries, try to detect if they're running via API
             let state = self.detect_engine_from_binary(&binary).await?;
             states.push(state);
@@ -147,6 +198,36 @@ impl EngineService {
         Ok(states)
     }
 
+    /// Detect engine state by health-checking a registered adapter
+    async fn detect_engine_from_adapter(
+        engine: &dyn LlmEngine,
+        registration: &EngineRegistration,
+    ) -> EngineState {
+        let status = match engine.health_check().await {
+            Ok(HealthStatus::Healthy { latency_ms }) => EngineStatus::RunningHealthy { latency_ms },
+            Ok(HealthStatus::Degraded { latency_ms, reason }) => {
+                EngineStatus::RunningDegraded { latency_ms, reason }
+            }
+            Ok(HealthStatus::Unreachable { reason }) => EngineStatus::ErrorNetwork {
+                reason,
+                consecutive_failures: 1,
+            },
+            Err(e) => EngineStatus::ErrorNetwork {
+                reason: format!("Health check failed: {e}"),
+                consecutive_failures: 1,
+            },
+        };
+
+        EngineState {
+            id: registration.id.clone(),
+            kind: registration.kind.clone(),
+            name: format!("{:?}", registration.kind),
+            version: None,
+            status,
+            capabilities: engine.capabilities(),
+        }
+    }
+
     /// Detect engine state from binary info
     async fn detect_engine_from_binary(
         &self,
@@ -220,7 +301,9 @@ impl EngineService {
             EngineKind::Vllm => join_api_url(&runtime.base_url, "v1/models"),
             EngineKind::LmStudio => join_api_url(&runtime.base_url, "v1/models"),
             EngineKind::LlamaCpp => join_api_url(&runtime.base_url, "v1/models"),
+            EngineKind::OpenAiCompatible => join_api_url(&runtime.base_url, "v1/models"),
             EngineKind::Ollama => join_api_url(&runtime.base_url, "/api/tags"),
+            EngineKind::StableDiffusion => join_api_url(&runtime.base_url, "sdapi/v1/sd-models"),
         };
 
         // Try API ping with latency measurement
@@ -235,6 +318,10 @@ impl EngineService {
                     EngineKind::Vllm => json.get("data").and_then(|v| v.as_array()).is_some(),
                     EngineKind::LmStudio => json.get("models").is_some(),
                     EngineKind::LlamaCpp => true, // Accept any valid JSON
+                    EngineKind::OpenAiCompatible => {
+                        json.get("data").and_then(|v| v.as_array()).is_some()
+                    }
+                    EngineKind::StableDiffusion => json.is_array(),
                 };
 
                 if !is_valid {
@@ -342,6 +429,42 @@ impl EngineService {
         engine.chat_stream(req).await
     }
 
+    /// Send a raw text completion request
+    pub async fn complete(
+        &self,
+        req: CompletionRequest,
+    ) -> Result<CompletionResponse, EngineError> {
+        // Find the registered engine with matching ID
+        let engines = self.engine_repo.list_registered().await;
+        let engine = engines
+            .into_iter()
+            .find(|e| e.id() == req.engine_id)
+            .ok_or_else(|| EngineError::NotFound {
+                engine_id: req.engine_id.clone(),
+            })?;
+
+        // Delegate to the engine's complete method
+        engine.complete(req).await
+    }
+
+    /// Send a streaming raw text completion request
+    pub async fn complete_stream(
+        &self,
+        req: CompletionRequest,
+    ) -> Result<CompletionStream, EngineError> {
+        // Find the registered engine with matching ID
+        let engines = self.engine_repo.list_registered().await;
+        let engine = engines
+            .into_iter()
+            .find(|e| e.id() == req.engine_id)
+            .ok_or_else(|| EngineError::NotFound {
+                engine_id: req.engine_id.clone(),
+            })?;
+
+        // Delegate to the engine's complete_stream method
+        engine.complete_stream(req).await
+    }
+
     /// Generate embeddings
     pub async fn embeddings(
         &self,
@@ -364,6 +487,7 @@ impl EngineService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::chat::SamplingParams;
     use crate::error::HttpError;
     use crate::ports::{EngineRepository, HttpClient, HttpRequest, HttpStream, LlmEngine};
     use serde_json::Value;
@@ -792,6 +916,10 @@ mod tests {
             max_tokens: None,
             stop: vec![],
             requested_modalities: Vec::new(),
+            tools: Vec::new(),
+            tool_choice: None,
+            response_format: None,
+            sampling: SamplingParams::default(),
         };
 
         let err = service.chat(req).await.unwrap_err();
@@ -816,6 +944,10 @@ mod tests {
             max_tokens: None,
             stop: vec![],
             requested_modalities: Vec::new(),
+            tools: Vec::new(),
+            tool_choice: None,
+            response_format: None,
+            sampling: SamplingParams::default(),
         };
 
         let result = service.chat_stream(req).await;
@@ -990,4 +1122,76 @@ mod tests {
             .unwrap();
         assert!(without_caps.capabilities.is_none());
     }
+
+    struct MockEngineRegistry(Vec<EngineRegistration>);
+
+    #[async_trait::async_trait]
+    impl EngineRegistryRepository for MockEngineRegistry {
+        async fn list(&self) -> Result<Vec<EngineRegistration>, crate::error::RepoError> {
+            Ok(self.0.clone())
+        }
+
+        async fn get(
+            &self,
+            id: &str,
+        ) -> Result<Option<EngineRegistration>, crate::error::RepoError> {
+            Ok(self.0.iter().find(|r| r.id == id).cloned())
+        }
+
+        async fn save(
+            &self,
+            _registration: &EngineRegistration,
+        ) -> Result<(), crate::error::RepoError> {
+            Ok(())
+        }
+
+        async fn remove(&self, _id: &str) -> Result<bool, crate::error::RepoError> {
+            Ok(false)
+        }
+    }
+
+    fn registration(id: &str, kind: EngineKind) -> EngineRegistration {
+        EngineRegistration {
+            id: id.to_string(),
+            kind,
+            base_url: format!("http://{id}.internal:8000"),
+            auth_header_name: None,
+            timeout_secs: None,
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn detect_engines_includes_registered_engines() {
+        let engine: Arc<dyn LlmEngine> = Arc::new(MockEngine {
+            id: "gpu-01".to_string(),
+            models: Vec::new(),
+        });
+        let registry = Arc::new(MockEngineRegistry(vec![
+            registration("gpu-01", EngineKind::Ollama),
+            registration("gpu-02", EngineKind::Vllm),
+        ]));
+        let service = make_service(vec![engine]).with_engine_registry(registry);
+
+        let states = service.detect_engines().await.unwrap();
+        let ids: Vec<&str> = states.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["gpu-01", "gpu-02"]);
+
+        // Registered adapter is probed directly and reports its capabilities
+        assert!(states[0].capabilities.chat);
+        assert!(matches!(
+            states[0].status,
+            EngineStatus::ErrorNetwork { .. }
+        ));
+        // Unbuilt registration falls back to a plain API ping
+        assert_eq!(states[1].kind, EngineKind::Vllm);
+        assert!(matches!(
+            states[1].status,
+            EngineStatus::ErrorNetwork { .. }
+        ));
+    }
 }
@@ -2,8 +2,10 @@
 //!
 //! See `docs/CORE_API.md` section 5 for the complete specification.
 
+use crate::domain::policy::PolicyDocument;
 use crate::domain::security::{
-    ApiKeyMetadata, ApiKeyRecord, DnsCredentialProfile, PlainAndHashedApiKey, SecurityPolicy,
+    ApiKeyMetadata, ApiKeyRecord, ApiKeyScopes, DnsCredentialProfile, PlainAndHashedApiKey,
+    SecurityPolicy,
 };
 use crate::error::RepoError;
 use crate::ports::SecurityRepository;
@@ -69,13 +71,24 @@ where
     /// * `Err(RepoError)` if an error occurs (including validation errors)
     ///
     /// # Errors
-    /// Returns `RepoError::ValidationError` if the policy JSON contains invalid IP addresses or CIDR notation
+    /// Returns `RepoError::ValidationError` if the policy JSON has unknown or
+    /// mistyped fields, or invalid IP addresses, CIDR notation or domains
     pub async fn set_policy(&self, policy: SecurityPolicy) -> Result<(), RepoError> {
-        // Validate IP whitelist in policy_json
         validate_security_policy(&policy.policy_json)?;
         self.repo.save_policy(policy).await
     }
 
+    /// Parse and validate a policy JSON without saving it
+    ///
+    /// Applies the same checks as `set_policy` and returns the typed policy,
+    /// migrated to the current schema version.
+    ///
+    /// # Errors
+    /// Returns `RepoError::ValidationError` naming the offending field
+    pub fn parse_policy(policy_json: &str) -> Result<PolicyDocument, RepoError> {
+        validate_security_policy(policy_json)
+    }
+
     /// Validate a domain name
     ///
     /// # Arguments
@@ -149,6 +162,24 @@ where
     /// The plain text key is only returned once on creation.
     /// It should be displayed to the user and then discarded.
     pub async fn create_api_key(&self, label: &str) -> Result<PlainAndHashedApiKey, RepoError> {
+        self.create_scoped_api_key(label, ApiKeyScopes::default())
+            .await
+    }
+
+    /// Create a new API key restricted to `scopes`
+    ///
+    /// # Returns
+    /// * `Ok(PlainAndHashedApiKey)` containing the plain text key and record
+    /// * `Err(RepoError::ValidationError)` if a scope is invalid
+    pub async fn create_scoped_api_key(
+        &self,
+        label: &str,
+        scopes: ApiKeyScopes,
+    ) -> Result<PlainAndHashedApiKey, RepoError> {
+        scopes
+            .validate()
+            .map_err(|reason| RepoError::ValidationError { reason })?;
+
         // Generate a random API key
         let plain_key = generate_api_key();
 
@@ -164,6 +195,7 @@ where
             hash,
             created_at,
             revoked_at: None,
+            scopes,
         };
 
         // Save to repository
@@ -202,13 +234,45 @@ where
                 label: record.label,
                 created_at: record.created_at,
                 revoked_at: record.revoked_at,
+                scopes: record.scopes,
             })
             .collect())
     }
 
+    /// Replace the scopes of an active API key
+    ///
+    /// Takes effect on the key's next request; an empty `ApiKeyScopes` lifts
+    /// all restrictions.
+    pub async fn set_api_key_scopes(
+        &self,
+        id: &str,
+        scopes: ApiKeyScopes,
+    ) -> Result<ApiKeyMetadata, RepoError> {
+        scopes
+            .validate()
+            .map_err(|reason| RepoError::ValidationError { reason })?;
+        let mut record = self
+            .repo
+            .fetch_api_key(id)
+            .await?
+            .filter(|record| record.revoked_at.is_none())
+            .ok_or_else(|| RepoError::NotFound {
+                key: id.to_string(),
+            })?;
+        record.scopes = scopes;
+        self.repo.save_api_key(record.clone()).await?;
+        Ok(ApiKeyMetadata {
+            id: record.id,
+            label: record.label,
+            created_at: record.created_at,
+            revoked_at: record.revoked_at,
+            scopes: record.scopes,
+        })
+    }
+
     /// Rotate an API key
     ///
-    /// This revokes the old key and creates a new one.
+    /// This revokes the old key and creates a new one with the same scopes.
     ///
     /// # Arguments
     /// * `id` - The API key ID to rotate
@@ -222,23 +286,19 @@ where
         id: &str,
         new_label: Option<&str>,
     ) -> Result<PlainAndHashedApiKey, RepoError> {
-        // Fetch the old key to get the label
-        let old_key = self.repo.fetch_api_key(id).await?;
-        let label = if let Some(new_label) = new_label {
-            new_label.to_string()
-        } else if let Some(ref old) = old_key {
-            old.label.clone()
-        } else {
+        // Fetch the old key to get the label and scopes
+        let Some(old_key) = self.repo.fetch_api_key(id).await? else {
             return Err(RepoError::NotFound {
                 key: id.to_string(),
             });
         };
+        let label = new_label.map_or(old_key.label, str::to_string);
 
         // Revoke the old key
         self.revoke_api_key(id).await?;
 
-        // Create a new key with the same or new label
-        self.create_api_key(&label).await
+        // Create a new key with the same or new label and the same scopes
+        self.create_scoped_api_key(&label, old_key.scopes).await
     }
 
     /// Verify an API key
@@ -486,101 +546,61 @@ fn generate_dns_profile_id(provider: &str) -> String {
 
 /// Validate security policy JSON
 ///
-/// Validates IP whitelist entries (IP addresses and CIDR notation).
+/// Parses it into a `PolicyDocument`, then validates IP whitelist entries
+/// (IP addresses and CIDR notation), the ACME domain and CORS origins.
 /// Returns `RepoError::ValidationError` if validation fails.
-fn validate_security_policy(policy_json: &str) -> Result<(), RepoError> {
-    use serde_json::Value;
-
-    let policy: Value =
-        serde_json::from_str(policy_json).map_err(|e| RepoError::ValidationError {
-            reason: format!("Invalid JSON: {e}"),
-        })?;
+fn validate_security_policy(policy_json: &str) -> Result<PolicyDocument, RepoError> {
+    // Unknown fields, wrong types and retention limits
+    let policy = PolicyDocument::parse(policy_json)
+        .map_err(|reason| RepoError::ValidationError { reason })?;
 
     // Validate ip_whitelist if present
-    if let Some(ip_whitelist) = policy.get("ip_whitelist") {
-        if let Some(ip_list) = ip_whitelist.as_array() {
-            for (idx, ip_entry) in ip_list.iter().enumerate() {
-                if let Some(ip_str) = ip_entry.as_str() {
-                    validate_ip_or_cidr(ip_str).map_err(|e| RepoError::ValidationError {
-                        reason: format!("Invalid IP whitelist entry at index {idx}: {e}"),
-                    })?;
-                } else {
-                    return Err(RepoError::ValidationError {
-                        reason: format!(
-                            "Invalid IP whitelist entry at index {idx}: expected string, got {ip_entry}"
-                        ),
-                    });
-                }
-            }
-        } else if !ip_whitelist.is_null() {
-            return Err(RepoError::ValidationError {
-                reason: "ip_whitelist must be an array or null".to_string(),
-            });
-        }
+    for (idx, ip_str) in policy.ip_whitelist.iter().flatten().enumerate() {
+        validate_ip_or_cidr(ip_str).map_err(|e| RepoError::ValidationError {
+            reason: format!("Invalid IP whitelist entry at index {idx}: {e}"),
+        })?;
     }
 
     // Validate acme_domain if present
-    if let Some(acme_domain) = policy.get("acme_domain") {
-        if let Some(domain_str) = acme_domain.as_str() {
-            if !domain_str.is_empty() {
-                // Use validate_domain_name for validation (same as CORS validation)
-                validate_domain_name(domain_str).map_err(|e| RepoError::ValidationError {
-                    reason: format!("Invalid ACME domain name: {e}"),
-                })?;
-            }
-        } else if !acme_domain.is_null() {
-            return Err(RepoError::ValidationError {
-                reason: "acme_domain must be a string or null".to_string(),
-            });
+    if let Some(domain_str) = policy.acme_domain.as_deref() {
+        if !domain_str.is_empty() {
+            // Use validate_domain_name for validation (same as CORS validation)
+            validate_domain_name(domain_str).map_err(|e| RepoError::ValidationError {
+                reason: format!("Invalid ACME domain name: {e}"),
+            })?;
         }
     }
 
     // Validate CORS allowed_origins if present
-    if let Some(cors) = policy.get("cors") {
-        if let Some(allowed_origins) = cors.get("allowed_origins") {
-            if let Some(origins_array) = allowed_origins.as_array() {
-                for (idx, origin_entry) in origins_array.iter().enumerate() {
-                    if let Some(origin_str) = origin_entry.as_str() {
-                        // Validate domain name format (basic validation)
-                        if !origin_str.is_empty() && origin_str != "*" {
-                            // CORS origin can be a URL (http://example.com:3000) or domain (example.com)
-                            // Extract domain part for validation
-                            let domain_part = origin_str
-                                .strip_prefix("http://")
-                                .or_else(|| origin_str.strip_prefix("https://"))
-                                .unwrap_or(origin_str)
-                                .split('/')
-                                .next()
-                                .unwrap_or(origin_str)
-                                .split(':')
-                                .next()
-                                .unwrap_or(origin_str);
-
-                            if !domain_part.is_empty() && domain_part != "*" {
-                                validate_domain_name(domain_part).map_err(|e| {
-                                    RepoError::ValidationError {
-                                        reason: format!("Invalid CORS origin at index {idx}: {e}"),
-                                    }
-                                })?;
-                            }
-                        }
-                    } else {
-                        return Err(RepoError::ValidationError {
-                            reason: format!(
-                                "Invalid CORS origin at index {idx}: expected string, got {origin_entry}"
-                            ),
-                        });
-                    }
-                }
-            } else if !allowed_origins.is_null() {
-                return Err(RepoError::ValidationError {
-                    reason: "cors.allowed_origins must be an array or null".to_string(),
-                });
+    let origins = policy
+        .cors
+        .as_ref()
+        .and_then(|cors| cors.allowed_origins.as_ref());
+    for (idx, origin_str) in origins.into_iter().flatten().enumerate() {
+        // Validate domain name format (basic validation)
+        if !origin_str.is_empty() && origin_str != "*" {
+            // CORS origin can be a URL (http://example.com:3000) or domain (example.com)
+            // Extract domain part for validation
+            let domain_part = origin_str
+                .strip_prefix("http://")
+                .or_else(|| origin_str.strip_prefix("https://"))
+                .unwrap_or(origin_str)
+                .split('/')
+                .next()
+                .unwrap_or(origin_str)
+                .split(':')
+                .next()
+                .unwrap_or(origin_str);
+
+            if !domain_part.is_empty() && domain_part != "*" {
+                validate_domain_name(domain_part).map_err(|e| RepoError::ValidationError {
+                    reason: format!("Invalid CORS origin at index {idx}: {e}"),
+                })?;
             }
         }
     }
 
-    Ok(())
+    Ok(policy)
 }
 
 /// Validate a domain name
@@ -734,8 +754,8 @@ fn validate_ip_or_cidr(ip_or_cidr: &str) -> Result<(), String> {
                     let prefix_bits = prefix % 8;
 
                     // Check full bytes
-                    for i in (prefix_bytes + 1)..16 {
-                        if octets[i] != 0 {
+                    for octet in octets.iter().skip(prefix_bytes + 1) {
+                        if *octet != 0 {
                             return Err(format!(
                                 "Invalid IPv6 network address in CIDR '{ip_or_cidr}': host bits must be zero for prefix length {prefix}"
                             ));
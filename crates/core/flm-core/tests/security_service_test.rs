@@ -1,5 +1,6 @@
 //! Tests for SecurityService
 
+use flm_core::domain::policy::POLICY_SCHEMA_VERSION;
 use flm_core::domain::security::{ApiKeyRecord, DnsCredentialProfile, SecurityPolicy};
 use flm_core::error::RepoError;
 use flm_core::ports::SecurityRepository;
@@ -366,6 +367,73 @@ async fn test_set_policy_invalid_ipv6_prefix_length() {
     }
 }
 
+#[tokio::test]
+async fn test_set_policy_invalid_retention() {
+    let repo = MockSecurityRepository::new();
+    let service = SecurityService::new(repo);
+
+    // Typo in a table name must not silently disable pruning
+    let policy_json = serde_json::json!({
+        "ip_whitelist": [],
+        "retention": { "audit_log": { "max_age_days": 90 } }
+    });
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&policy_json).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    match service.set_policy(policy).await.unwrap_err() {
+        RepoError::ValidationError { reason } => {
+            assert!(reason.contains("audit_log"), "{reason}");
+        }
+        _ => panic!("Expected ValidationError"),
+    }
+}
+
+#[tokio::test]
+async fn test_set_policy_rejects_unknown_fields() {
+    let repo = MockSecurityRepository::new();
+    let service = SecurityService::new(repo);
+
+    // A typo must be rejected here rather than make the proxy fail closed
+    for (policy_json, expected) in [
+        (
+            serde_json::json!({ "ip_whitelist": [], "rate_limt": { "rpm": 60 } }),
+            "rate_limt: unknown field `rate_limt`",
+        ),
+        (
+            serde_json::json!({ "rate_limit": { "rpm": "60" } }),
+            "rate_limit.rpm: invalid type",
+        ),
+        (
+            serde_json::json!({ "schema_version": 99 }),
+            "schema_version: 99 is newer",
+        ),
+    ] {
+        let policy = SecurityPolicy {
+            id: "default".to_string(),
+            policy_json: serde_json::to_string(&policy_json).unwrap(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        match service.set_policy(policy).await.unwrap_err() {
+            RepoError::ValidationError { reason } => {
+                assert!(reason.contains(expected), "{reason}");
+            }
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    // Policies without a schema version are still accepted
+    let document = SecurityService::<MockSecurityRepository>::parse_policy(
+        r#"{"cors":{"allowed_origins":"https://example.com"}}"#,
+    )
+    .unwrap();
+    assert_eq!(document.schema_version, POLICY_SCHEMA_VERSION);
+}
+
 #[tokio::test]
 async fn test_create_api_key_empty_name() {
     let repo = MockSecurityRepository::new();
@@ -0,0 +1,421 @@
+//! Local GGUF model store
+//!
+//! llama.cpp has no model management API; `llama-server` loads a GGUF file
+//! given on its command line. `GgufModelStore` manages those files in a
+//! directory: resumable HTTP downloads with SHA-256 verification, listing,
+//! deletion and copies.
+//!
+//! A verified digest is kept next to each model as `<file>.sha256` so that
+//! `show` does not need to re-hash multi-gigabyte files. In-flight downloads
+//! are written to `<file>.partial` and resumed with an HTTP `Range` request.
+
+use async_trait::async_trait;
+use flm_core::domain::engine::{ModelDetails, ModelPullProgress, ModelPullRequest};
+use flm_core::domain::models::EngineId;
+use flm_core::error::EngineError;
+use flm_core::ports::{ModelManager, ModelPullStream};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_stream::StreamExt;
+
+/// Default ID of the GGUF model store
+pub const GGUF_STORE_ID: &str = "gguf";
+
+const GGUF_EXTENSION: &str = "gguf";
+const PARTIAL_SUFFIX: &str = ".partial";
+const DIGEST_SUFFIX: &str = ".sha256";
+
+/// Emit a progress event at most every this many bytes (or 1% of the total)
+const PROGRESS_STEP_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Manages GGUF files in a local directory
+pub struct GgufModelStore {
+    engine_id: EngineId,
+    models_dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl GgufModelStore {
+    /// Create a store for `models_dir` (created on first download)
+    pub fn new(engine_id: EngineId, models_dir: impl Into<PathBuf>) -> Result<Self, EngineError> {
+        // Downloads can take hours, so only connecting is bounded
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Failed to create HTTP client: {e}"),
+            })?;
+        Ok(Self {
+            engine_id,
+            models_dir: models_dir.into(),
+            client,
+        })
+    }
+
+    /// Directory holding the models
+    pub fn models_dir(&self) -> &Path {
+        &self.models_dir
+    }
+
+    /// Path of a model file in the store (the name is validated)
+    pub fn model_path(&self, name: &str) -> Result<PathBuf, EngineError> {
+        validate_file_name(name)?;
+        Ok(self.models_dir.join(name))
+    }
+
+    async fn details(&self, name: &str, path: &Path) -> Result<ModelDetails, EngineError> {
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| io_error("read model file", path, e))?;
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+        let digest = tokio::fs::read_to_string(with_suffix(path, DIGEST_SUFFIX))
+            .await
+            .ok()
+            .map(|hex| format!("sha256:{}", hex.trim()));
+        Ok(ModelDetails {
+            engine_id: self.engine_id.clone(),
+            name: name.to_string(),
+            format: Some(GGUF_EXTENSION.to_string()),
+            size_bytes: Some(metadata.len()),
+            digest,
+            path: Some(path.display().to_string()),
+            modified_at,
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl ModelManager for GgufModelStore {
+    fn engine_id(&self) -> EngineId {
+        self.engine_id.clone()
+    }
+
+    async fn list_installed(&self) -> Result<Vec<ModelDetails>, EngineError> {
+        let mut entries = match tokio::fs::read_dir(&self.models_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error("read models directory", &self.models_dir, e)),
+        };
+
+        let mut models = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| io_error("read models directory", &self.models_dir, e))?
+        {
+            let path = entry.path();
+            let is_gguf = path.extension().and_then(|e| e.to_str()) == Some(GGUF_EXTENSION);
+            if !is_gguf || !path.is_file() {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                models.push(self.details(name, &path).await?);
+            }
+        }
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(models)
+    }
+
+    async fn pull(&self, req: ModelPullRequest) -> Result<ModelPullStream, EngineError> {
+        let url = reqwest::Url::parse(&req.model).map_err(|e| unsupported_pull(e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(unsupported_pull(format!(
+                "only http(s) URLs can be downloaded, got {}",
+                url.scheme()
+            )));
+        }
+        let file_name = match req.file_name {
+            Some(name) => name,
+            None => url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .unwrap_or_default()
+                .to_string(),
+        };
+        let target = self.model_path(&file_name)?;
+        let expected = req.sha256.as_deref().map(normalize_sha256).transpose()?;
+
+        tokio::fs::create_dir_all(&self.models_dir)
+            .await
+            .map_err(|e| io_error("create models directory", &self.models_dir, e))?;
+
+        let client = self.client.clone();
+        let stream = async_stream::try_stream! {
+            if tokio::fs::try_exists(&target).await.unwrap_or(false) {
+                // Already downloaded; only the checksum needs checking
+                let actual = hash_file(&target).await?;
+                if let Some(expected) = &expected {
+                    ensure_checksum(expected, &actual, &file_name)?;
+                }
+                write_digest(&target, &actual).await?;
+                yield success(&actual);
+                return;
+            }
+
+            let partial = with_suffix(&target, PARTIAL_SUFFIX);
+            let (mut hasher, mut offset) = hash_partial(&partial).await?;
+
+            let mut request = client.get(url.clone());
+            if offset > 0 {
+                request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+            }
+            let response = request.send().await.map_err(|e| EngineError::NetworkError {
+                reason: format!("Download failed: {e}"),
+            })?;
+
+            let status = response.status();
+            let already_complete = status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0;
+            if !status.is_success() && !already_complete {
+                Err(EngineError::ApiError {
+                    reason: format!("Download of {url} failed with HTTP {status}"),
+                    status_code: Some(status.as_u16()),
+                })?;
+            }
+
+            let mut total = None;
+            let mut file = if status == StatusCode::PARTIAL_CONTENT {
+                total = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(content_range_total);
+                open_append(&partial).await?
+            } else if already_complete {
+                // The partial file already holds the whole body
+                None
+            } else {
+                // The server ignored the range request; start over
+                hasher = Sha256::new();
+                offset = 0;
+                total = response.content_length();
+                Some(
+                    tokio::fs::File::create(&partial)
+                        .await
+                        .map_err(|e| io_error("create download file", &partial, e))?,
+                )
+            };
+
+            if let Some(file) = file.as_mut() {
+                let step = total
+                    .map(|t| (t / 100).clamp(1, PROGRESS_STEP_BYTES))
+                    .unwrap_or(PROGRESS_STEP_BYTES);
+                let mut reported = offset;
+                yield ModelPullProgress {
+                    status: format!("downloading {file_name}"),
+                    digest: None,
+                    total,
+                    completed: Some(offset),
+                };
+
+                let mut body = response.bytes_stream();
+                while let Some(chunk) = body.next().await {
+                    let chunk = chunk.map_err(|e| EngineError::NetworkError {
+                        reason: format!("Download interrupted: {e}"),
+                    })?;
+                    file.write_all(&chunk)
+                        .await
+                        .map_err(|e| io_error("write download file", &partial, e))?;
+                    hasher.update(&chunk);
+                    offset += chunk.len() as u64;
+                    if offset - reported >= step {
+                        reported = offset;
+                        yield ModelPullProgress {
+                            status: format!("downloading {file_name}"),
+                            digest: None,
+                            total,
+                            completed: Some(offset),
+                        };
+                    }
+                }
+                file.flush()
+                    .await
+                    .map_err(|e| io_error("write download file", &partial, e))?;
+            }
+
+            yield ModelPullProgress {
+                status: "verifying sha256 digest".to_string(),
+                digest: None,
+                total: Some(offset),
+                completed: Some(offset),
+            };
+            let actual = format!("{:x}", hasher.finalize());
+            if let Some(expected) = &expected {
+                if let Err(e) = ensure_checksum(expected, &actual, &file_name) {
+                    // A corrupt partial file would poison every later resume
+                    let _ = tokio::fs::remove_file(&partial).await;
+                    Err(e)?;
+                }
+            }
+
+            tokio::fs::rename(&partial, &target)
+                .await
+                .map_err(|e| io_error("move downloaded file", &target, e))?;
+            write_digest(&target, &actual).await?;
+            yield success(&actual);
+        };
+        Ok(Box::pin(stream))
+    }
+
+    async fn delete(&self, model: &str) -> Result<(), EngineError> {
+        let path = self.model_path(model)?;
+        tokio::fs::remove_file(&path)
+            .await
+            .map_err(|e| io_error("delete model", &path, e))?;
+        for suffix in [DIGEST_SUFFIX, PARTIAL_SUFFIX] {
+            let _ = tokio::fs::remove_file(with_suffix(&path, suffix)).await;
+        }
+        Ok(())
+    }
+
+    async fn show(&self, model: &str) -> Result<ModelDetails, EngineError> {
+        let path = self.model_path(model)?;
+        self.details(model, &path).await
+    }
+
+    async fn copy(&self, source: &str, destination: &str) -> Result<(), EngineError> {
+        let source_path = self.model_path(source)?;
+        let destination_path = self.model_path(destination)?;
+        if tokio::fs::try_exists(&destination_path)
+            .await
+            .unwrap_or(false)
+        {
+            return Err(EngineError::InvalidResponse {
+                reason: format!("Model {destination} already exists"),
+            });
+        }
+        tokio::fs::copy(&source_path, &destination_path)
+            .await
+            .map_err(|e| io_error("copy model", &source_path, e))?;
+        let _ = tokio::fs::copy(
+            with_suffix(&source_path, DIGEST_SUFFIX),
+            with_suffix(&destination_path, DIGEST_SUFFIX),
+        )
+        .await;
+        Ok(())
+    }
+}
+
+/// Model names are plain `*.gguf` file names inside the store
+fn validate_file_name(name: &str) -> Result<(), EngineError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+        && Path::new(name).extension().and_then(|e| e.to_str()) == Some(GGUF_EXTENSION);
+    if valid {
+        Ok(())
+    } else {
+        Err(EngineError::InvalidResponse {
+            reason: format!("Invalid GGUF model name '{name}' (expected a *.gguf file name)"),
+        })
+    }
+}
+
+fn normalize_sha256(value: &str) -> Result<String, EngineError> {
+    let hex = value
+        .strip_prefix("sha256:")
+        .unwrap_or(value)
+        .to_lowercase();
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex)
+    } else {
+        Err(EngineError::InvalidResponse {
+            reason: format!("Invalid SHA-256 checksum '{value}' (expected 64 hex characters)"),
+        })
+    }
+}
+
+fn ensure_checksum(expected: &str, actual: &str, file_name: &str) -> Result<(), EngineError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(EngineError::InvalidResponse {
+            reason: format!("Checksum mismatch for {file_name}: expected {expected}, got {actual}"),
+        })
+    }
+}
+
+/// Total size from a `Content-Range: bytes <start>-<end>/<total>` header
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+fn success(sha256: &str) -> ModelPullProgress {
+    ModelPullProgress {
+        status: ModelPullProgress::SUCCESS.to_string(),
+        digest: Some(format!("sha256:{sha256}")),
+        total: None,
+        completed: None,
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+async fn open_append(path: &Path) -> Result<Option<tokio::fs::File>, EngineError> {
+    tokio::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .await
+        .map(Some)
+        .map_err(|e| io_error("open download file", path, e))
+}
+
+async fn write_digest(path: &Path, sha256: &str) -> Result<(), EngineError> {
+    let digest_path = with_suffix(path, DIGEST_SUFFIX);
+    tokio::fs::write(&digest_path, format!("{sha256}\n"))
+        .await
+        .map_err(|e| io_error("write checksum file", &digest_path, e))
+}
+
+/// Hash an interrupted download so a resumed transfer can continue the digest
+async fn hash_partial(path: &Path) -> Result<(Sha256, u64), EngineError> {
+    let mut hasher = Sha256::new();
+    let mut file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((hasher, 0)),
+        Err(e) => return Err(io_error("read download file", path, e)),
+    };
+    let mut length = 0u64;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| io_error("read download file", path, e))?;
+        if read == 0 {
+            return Ok((hasher, length));
+        }
+        hasher.update(&buffer[..read]);
+        length += read as u64;
+    }
+}
+
+async fn hash_file(path: &Path) -> Result<String, EngineError> {
+    let (hasher, _) = hash_partial(path).await?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn unsupported_pull(reason: String) -> EngineError {
+    EngineError::UnsupportedOperation {
+        operation: "pull".to_string(),
+        reason,
+    }
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> EngineError {
+    EngineError::InvalidResponse {
+        reason: format!("Failed to {action} {}: {e}", path.display()),
+    }
+}
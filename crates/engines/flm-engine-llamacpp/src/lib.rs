@@ -3,60 +3,114 @@
 //! This crate implements the `LlmEngine` trait for llama.cpp.
 //! See `docs/ENGINE_DETECT.md` for detection specification.
 //!
-//! llama.cpp provides an OpenAI-compatible API when running in server mode,
-//! so the implementation is similar to vLLM and LM Studio.
+//! llama-server speaks the OpenAI API for chat and embeddings, which go
+//! through the shared OpenAI backend with llama.cpp's extensions (`top_k`,
+//! `repeat_penalty`, JSON schemas compiled to a grammar via `json_schema`).
+//! A server runs exactly one model, so `n > 1` is rejected and the model's
+//! context size and capabilities come from the native `/props` endpoint rather
+//! than from `/v1/models`. Raw completions use the native `/completion` and
+//! `/infill` endpoints.
 
 use async_trait::async_trait;
-use base64::{engine::general_purpose, Engine as _};
 use flm_core::domain::chat::{
-    ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, EmbeddingRequest,
-    EmbeddingResponse, EmbeddingVector, MultimodalAttachmentKind, UsageStats,
+    ChatRequest, ChatResponse, CompletionChoice, CompletionRequest, CompletionResponse,
+    CompletionStreamChunk, EmbeddingRequest, EmbeddingResponse, UsageStats,
 };
 use flm_core::domain::engine::{HealthStatus, ModelInfo};
 use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities};
 use flm_core::error::EngineError;
-use flm_core::ports::LlmEngine;
-use futures::Stream;
+use flm_core::ports::{ChatStream, CompletionStream, LlmEngine};
+use flm_engine_openai::backend::{
+    JsonSchemaField, MultipleChoices, OpenAiBackend, OpenAiDialect, OpenAiSamplingOptions,
+    RepeatPenaltyField,
+};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::pin::Pin;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio_stream::StreamExt;
 
+mod gguf_store;
+pub use gguf_store::{GgufModelStore, GGUF_STORE_ID};
+
+/// Default request timeout for engines created with `new`
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// What llama-server accepts beyond the OpenAI API
+const DIALECT: OpenAiDialect = OpenAiDialect {
+    name: "llama.cpp",
+    health_path: None,
+    top_k: true,
+    repeat_penalty: Some(RepeatPenaltyField::RepeatPenalty),
+    choices: MultipleChoices::Unsupported,
+    logit_bias: true,
+    logprobs: true,
+    json_schema: JsonSchemaField::Field("json_schema"),
+};
+
 /// llama.cpp engine implementation
 pub struct LlamaCppEngine {
-    engine_id: EngineId,
-    base_url: String,
-    client: reqwest::Client,
+    backend: OpenAiBackend,
+    /// `/props` results keyed by the model ID the server reports
+    props_cache: Mutex<HashMap<String, Option<Arc<LlamaCppProps>>>>,
 }
 
 impl LlamaCppEngine {
     /// Create a new LlamaCppEngine instance
     pub fn new(engine_id: EngineId, base_url: String) -> Result<Self, EngineError> {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()
             .map_err(|e| EngineError::NetworkError {
                 reason: format!("Failed to create HTTP client: {e}"),
             })?;
 
-        Ok(Self {
-            engine_id,
-            base_url,
-            client,
-        })
+        Ok(Self::with_client(engine_id, base_url, client))
     }
 
-    /// Get the base URL for API requests
-    fn api_url(&self, endpoint: &str) -> String {
-        format!("{}/v1/{}", self.base_url, endpoint)
+    /// Create a LlamaCppEngine that uses a preconfigured HTTP client
+    ///
+    /// Used for registered engines that need their own timeout, auth header or TLS settings.
+    pub fn with_client(engine_id: EngineId, base_url: String, client: reqwest::Client) -> Self {
+        Self {
+            backend: OpenAiBackend::new(engine_id, base_url, client, DIALECT),
+            props_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Server properties for the loaded model; `None` when the server does not expose `/props`
+    ///
+    /// A server instance keeps its properties until it is restarted, which also
+    /// changes the reported model ID when another model is loaded, so answers are
+    /// cached per model ID. Unreachable servers are asked again next time.
+    async fn props(&self, model_id: &str) -> Option<Arc<LlamaCppProps>> {
+        if let Some(cached) = self.props_cache.lock().unwrap().get(model_id) {
+            return cached.clone();
+        }
+
+        let response = self
+            .backend
+            .client()
+            .get(self.backend.native_url("props"))
+            .send()
+            .await
+            .ok()?;
+        let props = if response.status().is_success() {
+            response.json().await.ok().map(Arc::new)
+        } else {
+            None
+        };
+        self.props_cache
+            .lock()
+            .unwrap()
+            .insert(model_id.to_string(), props.clone());
+        props
     }
 }
 
 #[async_trait]
 impl LlmEngine for LlamaCppEngine {
     fn id(&self) -> EngineId {
-        self.engine_id.clone()
+        self.backend.engine_id().clone()
     }
 
     fn kind(&self) -> EngineKind {
@@ -69,220 +123,86 @@ impl LlmEngine for LlamaCppEngine {
             chat_stream: true,
             embeddings: true,
             moderation: false,
-            tools: false,         // Model-specific
+            tools: true,          // Model-specific
             reasoning: false,     // Model-specific
             vision_inputs: false, // Model-specific
             audio_inputs: false,  // Model-specific
             audio_outputs: false,
+            image_generation: false,
             max_image_bytes: None,
             max_audio_bytes: None,
         }
     }
 
     async fn health_check(&self) -> Result<HealthStatus, EngineError> {
-        let start = Instant::now();
-        let url = self.api_url("models");
-
-        let response =
-            self.client
-                .get(&url)
-                .send()
-                .await
-                .map_err(|e| EngineError::NetworkError {
-                    reason: format!("Request failed: {e}"),
-                })?;
-
-        let latency_ms = start.elapsed().as_millis() as u64;
-        if response.status().is_success() {
-            if latency_ms < 1500 {
-                Ok(HealthStatus::Healthy { latency_ms })
-            } else {
-                Ok(HealthStatus::Degraded {
-                    latency_ms,
-                    reason: "High latency".to_string(),
-                })
-            }
-        } else {
-            Ok(HealthStatus::Unreachable {
-                reason: format!("HTTP {}", response.status().as_u16()),
-            })
-        }
+        self.backend.health_check().await
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, EngineError> {
-        let url = self.api_url("models");
-
-        let response: OpenAiModelsResponse = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| EngineError::NetworkError {
-                reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
-            })?;
+        let models = self.backend.models().await?;
 
-        let models = response
-            .data
-            .into_iter()
+        // llama-server serves a single model, described by `/props`
+        let props = match models.first() {
+            Some(model) => self.props(&model.id).await,
+            None => None,
+        };
+        Ok(models
+            .iter()
             .map(|model| {
-                let model_name = &model.id;
-                let capabilities = Some(ModelCapabilities {
-                    reasoning: detect_reasoning_support(model_name),
-                    tools: detect_tool_use_support(model_name),
-                    vision: detect_vision_support(model_name),
-                    audio_inputs: detect_audio_support(model_name),
-                    audio_outputs: detect_audio_support(model_name),
-                });
-
-                ModelInfo {
-                    engine_id: self.engine_id.clone(),
-                    model_id: format!("flm://{}/{}", self.engine_id, model.id),
-                    display_name: model.id.clone(),
-                    context_length: None, // llama.cpp API doesn't always provide this
-                    supports_streaming: true,
-                    supports_embeddings: true,
-                    capabilities,
-                }
+                self.backend.model_info(
+                    model,
+                    props.as_deref().and_then(LlamaCppProps::context_length),
+                    props.as_deref().map(LlamaCppProps::capabilities),
+                )
             })
-            .collect();
-
-        Ok(models)
+            .collect())
     }
 
     async fn chat(&self, req: ChatRequest) -> Result<ChatResponse, EngineError> {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
-
-        // Extract model name from model_id (format: flm://{engine_id}/{model_name})
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
+        self.backend.chat(req).await
+    }
 
-        let openai_req = OpenAiChatRequest {
-            model: model.to_string(),
-            messages: req
-                .messages
-                .into_iter()
-                .map(convert_to_openai_message)
-                .collect(),
-            stream: false,
-            temperature: req.temperature.map(|t| t as f64),
-            max_tokens: req.max_tokens,
-        };
+    async fn chat_stream(&self, req: ChatRequest) -> Result<ChatStream, EngineError> {
+        self.backend.chat_stream(req)
+    }
 
-        let url = self.api_url("chat/completions");
-        let response: OpenAiChatResponse = self
-            .client
-            .post(&url)
-            .json(&openai_req)
-            .send()
-            .await
-            .map_err(|e| EngineError::NetworkError {
-                reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
-            })?;
+    async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
+        self.backend.embeddings(req).await
+    }
 
-        // Extract the first choice
-        let choice = response
-            .choices
-            .first()
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: "No choices in response".to_string(),
-            })?;
+    async fn complete(&self, req: CompletionRequest) -> Result<CompletionResponse, EngineError> {
+        // llama-server serves a single model, so the name is only validated
+        self.backend.model_name(&req.engine_id, &req.model_id)?;
 
-        let content = if let Some(ref c) = choice.message.content {
-            c.clone()
-        } else {
-            eprintln!("Warning: message content is missing in llama.cpp response");
-            String::new()
-        };
+        let url = self.backend.native_url(completion_endpoint(&req));
+        let llama_req = convert_completion_request(&self.backend, req, false)?;
 
-        let usage = if let Some(u) = response.usage {
-            u
-        } else {
-            eprintln!("Warning: usage stats are missing in llama.cpp response");
-            UsageStats {
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                total_tokens: 0,
-            }
-        };
+        let response: LlamaCppCompletionResponse = self.backend.post_json(&url, &llama_req).await?;
 
-        Ok(ChatResponse {
-            usage,
-            messages: vec![ChatMessage {
-                role: ChatRole::Assistant,
-                content,
-                attachments: Vec::new(),
+        Ok(CompletionResponse {
+            usage: response.usage(),
+            choices: vec![CompletionChoice {
+                finish_reason: Some(response.finish_reason().to_string()),
+                text: response.content,
             }],
-            audio: Vec::new(),
         })
     }
 
-    async fn chat_stream(
+    async fn complete_stream(
         &self,
-        req: ChatRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatStreamChunk, EngineError>> + Send>>, EngineError>
-    {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
-
-        // Extract model name from model_id
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
+        req: CompletionRequest,
+    ) -> Result<CompletionStream, EngineError> {
+        // llama-server serves a single model, so the name is only validated
+        self.backend.model_name(&req.engine_id, &req.model_id)?;
 
-        let openai_req = OpenAiChatRequest {
-            model: model.to_string(),
-            messages: req
-                .messages
-                .into_iter()
-                .map(convert_to_openai_message)
-                .collect(),
-            stream: true,
-            temperature: req.temperature.map(|t| t as f64),
-            max_tokens: req.max_tokens,
-        };
+        let url = self.backend.native_url(completion_endpoint(&req));
+        let llama_req = convert_completion_request(&self.backend, req, true)?;
+        let client = self.backend.client().clone();
 
-        let url = self.api_url("chat/completions");
-        let client = self.client.clone();
-
-        // Create a stream from the response
         let stream = async_stream::stream! {
             let response = client
                 .post(&url)
-                .json(&openai_req)
+                .json(&llama_req)
                 .send()
                 .await
                 .map_err(|e| EngineError::NetworkError {
@@ -290,10 +210,7 @@ impl LlmEngine for LlamaCppEngine {
                 })?;
 
             let mut stream = response.bytes_stream();
-            let mut accumulated_content = String::new();
             let mut is_done = false;
-            #[allow(unused_assignments)]
-            let mut final_usage: Option<UsageStats> = None;
 
             while let Some(chunk_result) = stream.next().await {
                 let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
@@ -309,34 +226,20 @@ impl LlmEngine for LlamaCppEngine {
                             break;
                         }
 
-                        match serde_json::from_str::<OpenAiChatChunk>(json_str) {
+                        match serde_json::from_str::<LlamaCppCompletionResponse>(json_str) {
                             Ok(chunk) => {
-                                if let Some(delta) = chunk.choices.first() {
-                                    if let Some(delta_content) = delta.delta.content.as_ref() {
-                                        if !delta_content.is_empty() {
-                                            accumulated_content.push_str(delta_content);
-                                            yield Ok(ChatStreamChunk {
-                                                delta: ChatMessage {
-                                                    role: ChatRole::Assistant,
-                                                    content: delta_content.clone(),
-                                                    attachments: Vec::new(),
-                                                },
-                                                usage: if delta.finish_reason.is_some() {
-                                                    final_usage = chunk.usage.clone();
-                                                    final_usage.clone()
-                                                } else {
-                                                    None
-                                                },
-                                                is_done: delta.finish_reason.is_some(),
-                                                audio: Vec::new(),
-                                            });
-                                        }
-
-                                        if delta.finish_reason.is_some() {
-                                            is_done = true;
-                                            break;
-                                        }
-                                    }
+                                let finished = chunk.stop;
+                                yield Ok(CompletionStreamChunk {
+                                    usage: finished.then(|| chunk.usage()),
+                                    finish_reason: finished
+                                        .then(|| chunk.finish_reason().to_string()),
+                                    text: chunk.content,
+                                    is_done: finished,
+                                });
+
+                                if finished {
+                                    is_done = true;
+                                    break;
                                 }
                             }
                             Err(e) => {
@@ -356,245 +259,166 @@ impl LlmEngine for LlamaCppEngine {
 
         Ok(Box::pin(stream))
     }
+}
 
-    async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
-
-        // Extract model name from model_id
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
-
-        let openai_req = OpenAiEmbeddingRequest {
-            model: model.to_string(),
-            input: req.input,
-        };
-
-        let url = self.api_url("embeddings");
-        let response: OpenAiEmbeddingResponse = self
-            .client
-            .post(&url)
-            .json(&openai_req)
-            .send()
-            .await
-            .map_err(|e| EngineError::NetworkError {
-                reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
-            })?;
-
-        let usage = if let Some(u) = response.usage {
-            u
-        } else {
-            eprintln!("Warning: usage stats are missing in llama.cpp embedding response");
-            UsageStats {
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                total_tokens: 0,
-            }
-        };
-
-        Ok(EmbeddingResponse {
-            usage,
-            vectors: response
-                .data
-                .into_iter()
-                .enumerate()
-                .map(|(idx, item)| EmbeddingVector {
-                    index: idx,
-                    values: item.embedding,
-                })
-                .collect(),
-        })
+/// Native endpoint for a completion: `/infill` when a suffix is given, `/completion` otherwise
+fn completion_endpoint(req: &CompletionRequest) -> &'static str {
+    if req.suffix.is_some() {
+        "infill"
+    } else {
+        "completion"
     }
 }
 
-// OpenAI-compatible API request/response types
-
-#[derive(Serialize)]
-struct OpenAiChatRequest {
-    model: String,
-    messages: Vec<OpenAiMessageRequest>,
+/// Build a native `/completion` (or `/infill`) request for a raw text completion
+fn convert_completion_request(
+    backend: &OpenAiBackend,
+    req: CompletionRequest,
     stream: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    temperature: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    max_tokens: Option<u32>,
-}
-
-#[derive(Serialize)]
-struct OpenAiMessageRequest {
-    role: String,
-    content: Value, // String or array of content objects
-}
-
-/// Convert ChatMessage (OpenAI-compatible format) to OpenAiMessageRequest format
-///
-/// This function converts the abstract ChatRequest format to OpenAI's API format.
-/// Image attachments are converted to OpenAI's `image_url` format.
-/// Audio attachments are converted to OpenAI's `input_audio` format.
-fn convert_to_openai_message(msg: ChatMessage) -> OpenAiMessageRequest {
-    let role = match msg.role {
-        ChatRole::User => "user".to_string(),
-        ChatRole::Assistant => "assistant".to_string(),
-        ChatRole::System => "system".to_string(),
-        ChatRole::Tool => "user".to_string(), // Fallback to user
-    };
-
-    // If there are no attachments, use simple string content
-    if msg.attachments.is_empty() {
-        return OpenAiMessageRequest {
-            role,
-            content: Value::String(msg.content),
-        };
+) -> Result<LlamaCppCompletionRequest, EngineError> {
+    backend.check_sampling(&req.sampling, stream, "complete")?;
+    let mut unsupported = Vec::new();
+    if req.best_of.is_some_and(|best_of| best_of > 1) {
+        unsupported.push("best_of");
     }
-
-    // Build content array with text and attachments
-    let mut content_parts: Vec<Value> = Vec::new();
-
-    // Add text content if not empty
-    if !msg.content.is_empty() {
-        content_parts.push(serde_json::json!({
-            "type": "text",
-            "text": msg.content
-        }));
+    if req.sampling.logprobs || req.sampling.top_logprobs.is_some() {
+        unsupported.push("logprobs");
     }
-
-    // Add image attachments
-    for att in &msg.attachments {
-        if matches!(att.kind, MultimodalAttachmentKind::InputImage) {
-            let base64_data = general_purpose::STANDARD.encode(&att.data);
-            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
-            content_parts.push(serde_json::json!({
-                "type": "image_url",
-                "image_url": {
-                    "url": data_url
-                }
-            }));
-        } else if matches!(att.kind, MultimodalAttachmentKind::InputAudio) {
-            let base64_data = general_purpose::STANDARD.encode(&att.data);
-            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
-            content_parts.push(serde_json::json!({
-                "type": "input_audio",
-                "audio_url": {
-                    "url": data_url
-                }
-            }));
-        }
+    if !unsupported.is_empty() {
+        return Err(EngineError::UnsupportedOperation {
+            operation: "complete".to_string(),
+            reason: format!(
+                "llama.cpp does not support completion parameter(s): {}",
+                unsupported.join(", ")
+            ),
+        });
     }
 
-    OpenAiMessageRequest {
-        role,
-        content: Value::Array(content_parts),
-    }
+    let sampling = backend.sampling_options(&req.sampling);
+    // `/infill` wraps the prefix and suffix in the model's FIM tokens
+    let (prompt, input_prefix, input_suffix) = match req.suffix {
+        Some(suffix) => (None, Some(req.prompt), Some(suffix)),
+        None => (Some(req.prompt), None, None),
+    };
+    Ok(LlamaCppCompletionRequest {
+        prompt,
+        input_prefix,
+        input_suffix,
+        stream,
+        temperature: req.temperature.map(|t| t as f64),
+        n_predict: req.max_tokens,
+        stop: req.stop,
+        sampling,
+    })
 }
 
-#[derive(Deserialize)]
-struct OpenAiMessageResponse {
-    #[allow(dead_code)]
+/// Native llama-server completion request (`/completion` and `/infill`)
+#[derive(Serialize)]
+struct LlamaCppCompletionRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    role: Option<String>,
-    content: Option<String>,
+    input_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_suffix: Option<String>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    /// Native option names match the OpenAI extension fields llama.cpp accepts
+    #[serde(flatten)]
+    sampling: OpenAiSamplingOptions,
 }
 
+/// Native completion response (also each SSE chunk when streaming)
 #[derive(Deserialize)]
-struct OpenAiChatResponse {
-    choices: Vec<OpenAiChoice>,
-    usage: Option<UsageStats>,
+struct LlamaCppCompletionResponse {
+    #[serde(default)]
+    content: String,
+    /// `true` on the final object
+    #[serde(default)]
+    stop: bool,
+    /// `eos`, `word` or `limit` (newer servers)
+    #[serde(default)]
+    stop_type: Option<String>,
+    /// Set when `n_predict` was reached (older servers)
+    #[serde(default)]
+    stopped_limit: bool,
+    #[serde(default)]
+    tokens_evaluated: Option<u32>,
+    #[serde(default)]
+    tokens_predicted: Option<u32>,
 }
 
-#[derive(Deserialize)]
-struct OpenAiChoice {
-    message: OpenAiMessageResponse,
-    #[serde(rename = "finish_reason")]
-    #[allow(dead_code)]
-    finish_reason: Option<String>,
-}
+impl LlamaCppCompletionResponse {
+    fn finish_reason(&self) -> &'static str {
+        if self.stopped_limit || self.stop_type.as_deref() == Some("limit") {
+            "length"
+        } else {
+            "stop"
+        }
+    }
 
-#[derive(Deserialize)]
-struct OpenAiChatChunk {
-    choices: Vec<OpenAiChunkChoice>,
-    usage: Option<UsageStats>,
+    fn usage(&self) -> UsageStats {
+        let prompt_tokens = self.tokens_evaluated.unwrap_or(0);
+        let completion_tokens = self.tokens_predicted.unwrap_or(0);
+        UsageStats {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens.saturating_add(completion_tokens),
+        }
+    }
 }
 
+/// `/props` response of llama-server (the loaded model and its settings)
 #[derive(Deserialize)]
-struct OpenAiChunkChoice {
-    delta: OpenAiMessageResponse,
-    #[serde(rename = "finish_reason")]
-    finish_reason: Option<String>,
+struct LlamaCppProps {
+    #[serde(default)]
+    default_generation_settings: Option<LlamaCppGenerationSettings>,
+    #[serde(default)]
+    chat_template: Option<String>,
+    /// Input modalities enabled by a multimodal projector (`--mmproj`)
+    #[serde(default)]
+    modalities: Option<LlamaCppModalities>,
 }
 
 #[derive(Deserialize)]
-struct OpenAiModelsResponse {
-    data: Vec<OpenAiModel>,
+struct LlamaCppGenerationSettings {
+    #[serde(default)]
+    n_ctx: Option<u32>,
 }
 
 #[derive(Deserialize)]
-struct OpenAiModel {
-    id: String,
-}
-
-/// Detect if a model supports reasoning capabilities based on model name
-fn detect_reasoning_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("reasoning")
-        || name.contains("o1")
-        || name.contains("deepseek-r1")
-        || name.contains("deepseek-r")
-        || name.contains("qwen-reasoning")
-        || name.contains("qwen2.5-reasoning")
-        || name.contains("reason")
-        || name.contains("cot")
-}
-
-/// Detect if a model supports tool use/function calling based on model name
-fn detect_tool_use_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("tool")
-        || name.contains("function")
-        || name.contains("agent")
-        || name.contains("api")
-        || (name.contains("mistral") && (name.contains("large") || name.contains("small")))
-        || name.contains("claude")
-        || name.contains("gpt-4")
-        || name.contains("gpt-3.5-turbo")
+struct LlamaCppModalities {
+    #[serde(default)]
+    vision: bool,
+    #[serde(default)]
+    audio: bool,
 }
 
-/// Detect if a model supports vision capabilities based on model name
-fn detect_vision_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("vision")
-        || name.contains("llava")
-        || name.contains("clip")
-        || name.contains("blip")
-        || name.contains("multimodal")
-        || (name.contains("llama") && name.contains("vision"))
-}
+impl LlamaCppProps {
+    /// Context window the server was started with (`--ctx-size`)
+    fn context_length(&self) -> Option<u32> {
+        self.default_generation_settings
+            .as_ref()
+            .and_then(|settings| settings.n_ctx)
+            .filter(|n_ctx| *n_ctx > 0)
+    }
 
-/// Detect if a model supports audio capabilities based on model name
-fn detect_audio_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("whisper")
-        || name.contains("audio")
-        || name.contains("speech")
-        || name.contains("tts")
-        || name.contains("asr")
-        || name.contains("transcription")
+    /// Capabilities of the loaded model, read from its chat template and projector
+    fn capabilities(&self) -> ModelCapabilities {
+        let template = self.chat_template.as_deref().unwrap_or("");
+        ModelCapabilities {
+            reasoning: template.contains("<think>") || template.contains("reasoning_content"),
+            tools: template.contains("tools"),
+            vision: self.modalities.as_ref().is_some_and(|m| m.vision),
+            audio_inputs: self.modalities.as_ref().is_some_and(|m| m.audio),
+            audio_outputs: false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -602,127 +426,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_detect_reasoning_support() {
-        // Positive cases
-        assert!(detect_reasoning_support("o1"));
-        assert!(detect_reasoning_support("O1")); // Case insensitive
-        assert!(detect_reasoning_support("deepseek-r1"));
-        assert!(detect_reasoning_support("deepseek-r"));
-        assert!(detect_reasoning_support("qwen-reasoning"));
-        assert!(detect_reasoning_support("qwen2.5-reasoning"));
-        assert!(detect_reasoning_support("model-cot"));
-        assert!(detect_reasoning_support("model-reasoning"));
-
-        // Negative cases
-        assert!(!detect_reasoning_support("llama2"));
-        assert!(!detect_reasoning_support("gpt-3.5"));
-        assert!(!detect_reasoning_support(""));
-    }
-
-    #[test]
-    fn test_detect_tool_use_support() {
-        // Positive cases
-        assert!(detect_tool_use_support("mistral-large"));
-        assert!(detect_tool_use_support("mistral-small"));
-        assert!(detect_tool_use_support("gpt-4"));
-        assert!(detect_tool_use_support("gpt-3.5-turbo"));
-        assert!(detect_tool_use_support("claude-3"));
-        assert!(detect_tool_use_support("model-tool"));
-        assert!(detect_tool_use_support("model-function"));
-        assert!(detect_tool_use_support("model-agent"));
-
-        // Negative cases
-        assert!(!detect_tool_use_support("llama2"));
-        assert!(!detect_tool_use_support("mistral-tiny"));
-        assert!(!detect_tool_use_support(""));
-    }
-
-    #[test]
-    fn test_detect_vision_support() {
-        // Positive cases
-        assert!(detect_vision_support("llava"));
-        assert!(detect_vision_support("LLAVA")); // Case insensitive
-        assert!(detect_vision_support("clip"));
-        assert!(detect_vision_support("blip"));
-        assert!(detect_vision_support("vision-model"));
-        assert!(detect_vision_support("llama-vision"));
-        assert!(detect_vision_support("multimodal-model"));
-
-        // Negative cases
-        assert!(!detect_vision_support("llama2"));
-        assert!(!detect_vision_support("gpt-3.5"));
-        assert!(!detect_vision_support(""));
+    fn test_props_capabilities() {
+        let props: LlamaCppProps = serde_json::from_value(serde_json::json!({
+            "default_generation_settings": {"n_ctx": 8192},
+            "chat_template": "{%- if tools %}{{ tools | tojson }}{%- endif %}<think>",
+            "modalities": {"vision": true, "audio": false}
+        }))
+        .unwrap();
+        assert_eq!(props.context_length(), Some(8192));
+        let caps = props.capabilities();
+        assert!(caps.tools && caps.vision && caps.reasoning);
+        assert!(!caps.audio_inputs && !caps.audio_outputs);
+
+        // Older servers report neither modalities nor a template
+        let props: LlamaCppProps = serde_json::from_value(serde_json::json!({
+            "default_generation_settings": {"n_ctx": 0}
+        }))
+        .unwrap();
+        assert_eq!(props.context_length(), None);
+        assert_eq!(props.capabilities(), ModelCapabilities::default());
     }
-
-    #[test]
-    fn test_detect_audio_support() {
-        // Positive cases
-        assert!(detect_audio_support("whisper"));
-        assert!(detect_audio_support("WHISPER")); // Case insensitive
-        assert!(detect_audio_support("audio-model"));
-        assert!(detect_audio_support("speech-model"));
-        assert!(detect_audio_support("tts-model"));
-        assert!(detect_audio_support("asr-model"));
-        assert!(detect_audio_support("transcription-model"));
-
-        // Negative cases
-        assert!(!detect_audio_support("llama2"));
-        assert!(!detect_audio_support("gpt-3.5"));
-        assert!(!detect_audio_support(""));
-    }
-
-    #[test]
-    fn test_detect_edge_cases() {
-        // Edge cases for model name detection
-        assert!(!detect_reasoning_support(""));
-        assert!(!detect_reasoning_support("   "));
-        assert!(!detect_reasoning_support("model"));
-        assert!(!detect_reasoning_support("123"));
-
-        // Partial matches should not trigger
-        assert!(!detect_reasoning_support("model-o"));
-        assert!(!detect_reasoning_support("o-model"));
-
-        // Case variations
-        assert!(detect_reasoning_support("O1"));
-        assert!(detect_reasoning_support("O1-PREVIEW"));
-        assert!(detect_reasoning_support("DEEPSEEK-R1"));
-
-        // Special characters
-        assert!(detect_reasoning_support("o1:latest"));
-        assert!(detect_reasoning_support("o1-v2.0"));
-        assert!(detect_reasoning_support("deepseek-r1:7b"));
-    }
-
-    #[test]
-    fn test_detect_all_capabilities_combinations() {
-        // Test models that might have multiple capabilities
-        let multimodal_model = "gpt-4-vision-tool";
-        assert!(detect_tool_use_support(multimodal_model));
-        assert!(detect_vision_support(multimodal_model));
-
-        // Test that detection is independent
-        let reasoning_only = "o1";
-        assert!(detect_reasoning_support(reasoning_only));
-        assert!(!detect_tool_use_support(reasoning_only));
-        assert!(!detect_vision_support(reasoning_only));
-        assert!(!detect_audio_support(reasoning_only));
-    }
-}
-
-#[derive(Serialize)]
-struct OpenAiEmbeddingRequest {
-    model: String,
-    input: Vec<String>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiEmbeddingResponse {
-    data: Vec<OpenAiEmbeddingData>,
-    usage: Option<UsageStats>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiEmbeddingData {
-    embedding: Vec<f32>,
 }
@@ -0,0 +1,174 @@
+//! Tests for the GGUF model store (downloads, resume and checksums)
+
+use flm_core::domain::engine::{ModelPullProgress, ModelPullRequest};
+use flm_core::error::EngineError;
+use flm_core::ports::ModelManager;
+use flm_engine_llamacpp::GgufModelStore;
+use futures::StreamExt;
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const BODY: &[u8] = b"GGUF fake model weights for download tests";
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+async fn pull(
+    store: &GgufModelStore,
+    req: ModelPullRequest,
+) -> Result<Vec<ModelPullProgress>, EngineError> {
+    let mut stream = store.pull(req).await?;
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        events.push(event?);
+    }
+    Ok(events)
+}
+
+#[tokio::test]
+async fn test_gguf_store_download_verifies_checksum() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models/tiny-q4.gguf"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_bytes(BODY))
+        .mount(&mock_server)
+        .await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = GgufModelStore::new("gguf".to_string(), temp_dir.path()).unwrap();
+
+    let events = pull(
+        &store,
+        ModelPullRequest {
+            model: format!("{}/models/tiny-q4.gguf", mock_server.uri()),
+            sha256: Some(sha256_hex(BODY).to_uppercase()),
+            file_name: None,
+        },
+    )
+    .await
+    .unwrap();
+    let last = events.last().unwrap();
+    assert!(last.is_success());
+    assert_eq!(last.digest, Some(format!("sha256:{}", sha256_hex(BODY))));
+    assert_eq!(
+        std::fs::read(temp_dir.path().join("tiny-q4.gguf")).unwrap(),
+        BODY
+    );
+
+    let models = store.list_installed().await.unwrap();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].name, "tiny-q4.gguf");
+    assert_eq!(models[0].size_bytes, Some(BODY.len() as u64));
+    assert_eq!(models[0].digest, last.digest);
+
+    store.copy("tiny-q4.gguf", "copy.gguf").await.unwrap();
+    assert_eq!(store.show("copy.gguf").await.unwrap().digest, last.digest);
+    assert!(store.copy("tiny-q4.gguf", "copy.gguf").await.is_err());
+
+    store.delete("tiny-q4.gguf").await.unwrap();
+    assert!(!temp_dir.path().join("tiny-q4.gguf.sha256").exists());
+    let names: Vec<_> = store
+        .list_installed()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+    assert_eq!(names, vec!["copy.gguf"]);
+}
+
+#[tokio::test]
+async fn test_gguf_store_resumes_partial_download() {
+    let mock_server = MockServer::start().await;
+    let split = 10;
+    Mock::given(method("GET"))
+        .and(path("/tiny.gguf"))
+        .and(header("range", format!("bytes={split}-").as_str()))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::PARTIAL_CONTENT)
+                .insert_header(
+                    "content-range",
+                    format!("bytes {split}-{}/{}", BODY.len() - 1, BODY.len()).as_str(),
+                )
+                .set_body_bytes(&BODY[split..]),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("tiny.gguf.partial"), &BODY[..split]).unwrap();
+    let store = GgufModelStore::new("gguf".to_string(), temp_dir.path()).unwrap();
+
+    let events = pull(
+        &store,
+        ModelPullRequest {
+            model: format!("{}/tiny.gguf", mock_server.uri()),
+            sha256: Some(sha256_hex(BODY)),
+            file_name: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(events.last().unwrap().is_success());
+    assert_eq!(events[0].completed, Some(split as u64));
+    assert_eq!(events[0].total, Some(BODY.len() as u64));
+    assert_eq!(
+        std::fs::read(temp_dir.path().join("tiny.gguf")).unwrap(),
+        BODY
+    );
+    assert!(!temp_dir.path().join("tiny.gguf.partial").exists());
+}
+
+#[tokio::test]
+async fn test_gguf_store_rejects_checksum_mismatch_and_bad_input() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/download"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_bytes(BODY))
+        .mount(&mock_server)
+        .await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = GgufModelStore::new("gguf".to_string(), temp_dir.path()).unwrap();
+
+    let result = pull(
+        &store,
+        ModelPullRequest {
+            model: format!("{}/download", mock_server.uri()),
+            sha256: Some("0".repeat(64)),
+            file_name: Some("named.gguf".to_string()),
+        },
+    )
+    .await;
+    match result {
+        Err(EngineError::InvalidResponse { reason }) => {
+            assert!(reason.contains("Checksum mismatch"))
+        }
+        other => panic!("expected checksum mismatch, got {other:?}"),
+    }
+    assert!(!temp_dir.path().join("named.gguf").exists());
+    assert!(!temp_dir.path().join("named.gguf.partial").exists());
+
+    let invalid = [
+        ("ftp://example.com/model.gguf", None, None),
+        ("not a url", None, None),
+        ("http://example.com/model.bin", None, None),
+        (
+            "http://example.com/model.gguf",
+            None,
+            Some("../escape.gguf"),
+        ),
+        ("http://example.com/model.gguf", Some("abc"), None),
+    ];
+    for (model, sha256, file_name) in invalid {
+        let req = ModelPullRequest {
+            model: model.to_string(),
+            sha256: sha256.map(str::to_string),
+            file_name: file_name.map(str::to_string),
+        };
+        assert!(store.pull(req).await.is_err(), "{model} should be rejected");
+    }
+    assert!(store.show("missing.gguf").await.is_err());
+    assert!(store.delete("../config.db").await.is_err());
+}
@@ -2,12 +2,14 @@
 //!
 //! These tests verify that the llama.cpp engine adapter works correctly with mock HTTP servers.
 
-use flm_core::domain::chat::{ChatMessage, ChatRequest, ChatRole, EmbeddingRequest};
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatRole, CompletionRequest, EmbeddingRequest, SamplingParams,
+};
 use flm_core::ports::LlmEngine;
 use flm_engine_llamacpp::LlamaCppEngine;
 use futures::StreamExt;
 use reqwest::StatusCode;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_partial_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -51,6 +53,92 @@ async fn test_llamacpp_engine_list_models() {
     assert_eq!(models.len(), 1);
     assert_eq!(models[0].model_id, "flm://llamacpp-test/llama-2-7b-chat");
     assert_eq!(models[0].display_name, "llama-2-7b-chat");
+    // Servers without `/props` report nothing rather than a guess
+    assert_eq!(models[0].context_length, None);
+    assert!(models[0].capabilities.is_none());
+}
+
+#[tokio::test]
+async fn test_llamacpp_engine_list_models_reads_props() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{"id": "qwen2.5-vl-7b-q4_k_m.gguf", "object": "model"}]
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/props"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "default_generation_settings": {"n_ctx": 16384, "n_predict": -1},
+                "total_slots": 1,
+                "model_path": "/models/qwen2.5-vl-7b-q4_k_m.gguf",
+                "chat_template": "{%- if tools %}<tools>{{ tools | tojson }}</tools>{%- endif %}",
+                "modalities": {"vision": true, "audio": false}
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = LlamaCppEngine::new("llamacpp-test".to_string(), mock_server.uri()).unwrap();
+
+    let models = engine.list_models().await.unwrap();
+    assert_eq!(models[0].context_length, Some(16384));
+    let caps = models[0].capabilities.as_ref().unwrap();
+    assert!(caps.tools && caps.vision);
+    assert!(!caps.reasoning && !caps.audio_inputs);
+}
+
+#[tokio::test]
+async fn test_llamacpp_engine_list_models_caches_props_per_model() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{"id": "llama-3-8b.gguf", "object": "model"}]
+            })),
+        )
+        .up_to_n_times(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{"id": "qwen2.5-7b.gguf", "object": "model"}]
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+    // Fetched once for each model the server reports
+    Mock::given(method("GET"))
+        .and(path("/props"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "default_generation_settings": {"n_ctx": 8192}
+            })),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let engine = LlamaCppEngine::new("llamacpp-test".to_string(), mock_server.uri()).unwrap();
+
+    for expected_model in ["llama-3-8b.gguf", "llama-3-8b.gguf", "qwen2.5-7b.gguf"] {
+        let models = engine.list_models().await.unwrap();
+        assert_eq!(models[0].display_name, expected_model);
+        assert_eq!(models[0].context_length, Some(8192));
+    }
 }
 
 #[tokio::test]
@@ -118,12 +206,18 @@ async fn test_llamacpp_engine_chat() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let response = engine.chat(req).await.unwrap();
@@ -191,12 +285,18 @@ async fn test_llamacpp_engine_chat_stream() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: true,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let mut stream = engine.chat_stream(req).await.unwrap();
@@ -213,3 +313,93 @@ async fn test_llamacpp_engine_chat_stream() {
     assert!(!chunks.is_empty());
     assert!(chunks.last().unwrap().is_done);
 }
+
+#[tokio::test]
+async fn test_llamacpp_engine_complete_with_suffix() {
+    let mock_server = MockServer::start().await;
+
+    // A suffix turns the request into fill-in-the-middle via /infill
+    Mock::given(method("POST"))
+        .and(path("/infill"))
+        .and(body_partial_json(serde_json::json!({
+            "input_prefix": "fn add(a: i32, b: i32) -> i32 {",
+            "input_suffix": "}",
+            "n_predict": 16
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "content": " a + b ",
+                "stop": true,
+                "stopped_limit": false,
+                "tokens_evaluated": 12,
+                "tokens_predicted": 4
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = LlamaCppEngine::new("llamacpp-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "llamacpp-test".to_string(),
+        model_id: "flm://llamacpp-test/codellama".to_string(),
+        prompt: "fn add(a: i32, b: i32) -> i32 {".to_string(),
+        suffix: Some("}".to_string()),
+        stream: false,
+        temperature: None,
+        max_tokens: Some(16),
+        stop: vec![],
+        best_of: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.complete(req).await.unwrap();
+    assert_eq!(response.choices[0].text, " a + b ");
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("stop"));
+    assert_eq!(response.usage.total_tokens, 16);
+}
+
+#[tokio::test]
+async fn test_llamacpp_engine_complete_stream() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/completion"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(
+            "data: {\"content\":\" there\",\"stop\":false}\n\ndata: {\"content\":\" was\",\"stop\":true,\"stopped_limit\":true,\"tokens_evaluated\":4,\"tokens_predicted\":2}\n\n",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let engine = LlamaCppEngine::new("llamacpp-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "llamacpp-test".to_string(),
+        model_id: "flm://llamacpp-test/llama".to_string(),
+        prompt: "Once upon a time".to_string(),
+        suffix: None,
+        stream: true,
+        temperature: None,
+        max_tokens: Some(2),
+        stop: vec![],
+        best_of: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let mut stream = engine.complete_stream(req).await.unwrap();
+    let mut text = String::new();
+    let mut last = None;
+    while let Some(result) = stream.next().await {
+        let chunk = result.unwrap();
+        text.push_str(&chunk.text);
+        if chunk.is_done {
+            last = Some(chunk);
+            break;
+        }
+    }
+
+    let last = last.expect("stream should finish");
+    assert_eq!(text, " there was");
+    assert_eq!(last.finish_reason.as_deref(), Some("length"));
+    assert_eq!(last.usage.map(|u| u.completion_tokens), Some(2));
+}
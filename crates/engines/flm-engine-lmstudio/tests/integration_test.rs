@@ -2,7 +2,7 @@
 //!
 //! These tests verify that the LM Studio engine adapter works correctly with mock HTTP servers.
 
-use flm_core::domain::chat::{ChatMessage, ChatRequest, ChatRole, EmbeddingRequest};
+use flm_core::domain::chat::{ChatMessage, ChatRequest, ChatRole, EmbeddingRequest, SamplingParams};
 use flm_core::ports::LlmEngine;
 use flm_engine_lmstudio::LmStudioEngine;
 use futures::StreamExt;
@@ -118,12 +118,18 @@ async fn test_lmstudio_engine_chat() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let response = engine.chat(req).await.unwrap();
@@ -191,12 +197,18 @@ async fn test_lmstudio_engine_chat_stream() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: true,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let mut stream = engine.chat_stream(req).await.unwrap();
@@ -6,48 +6,96 @@
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
 use flm_core::domain::chat::{
-    ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, EmbeddingRequest,
-    EmbeddingResponse, EmbeddingVector, MultimodalAttachmentKind, TranscriptionRequest,
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, CompletionChoice,
+    CompletionRequest, CompletionResponse, CompletionStreamChunk, EmbeddingRequest,
+    EmbeddingResponse, EmbeddingVector, MultimodalAttachmentKind, ResponseFormat, SamplingParams,
+    ToolCall, ToolCallDelta, ToolChoice, ToolDefinition, TranscriptionRequest,
     TranscriptionResponse, UsageStats,
 };
 use flm_core::domain::engine::{HealthStatus, ModelInfo};
-use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities};
+use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind};
 use flm_core::error::EngineError;
-use flm_core::ports::LlmEngine;
+use flm_core::ports::{CompletionStream, LlmEngine, ModelManager};
 use futures::Stream;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::Mutex;
 use std::time::Instant;
 use tokio_stream::StreamExt;
 
+mod metadata;
+mod model_manager;
+
+use metadata::ModelMetadata;
+
+/// Default request timeout for engines created with `new`
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
 /// Ollama engine implementation
 pub struct OllamaEngine {
     engine_id: EngineId,
     base_url: String,
     client: reqwest::Client,
+    /// `/api/show` results keyed by model digest
+    metadata_cache: Mutex<HashMap<String, ModelMetadata>>,
 }
 
 impl OllamaEngine {
     /// Create a new OllamaEngine instance
     pub fn new(engine_id: EngineId, base_url: String) -> Result<Self, EngineError> {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()
             .map_err(|e| EngineError::NetworkError {
                 reason: format!("Failed to create HTTP client: {e}"),
             })?;
 
-        Ok(Self {
+        Ok(Self::with_client(engine_id, base_url, client))
+    }
+
+    /// Create a OllamaEngine that uses a preconfigured HTTP client
+    ///
+    /// Used for registered engines that need their own timeout, auth header or TLS settings.
+    pub fn with_client(engine_id: EngineId, base_url: String, client: reqwest::Client) -> Self {
+        Self {
             engine_id,
             base_url,
             client,
-        })
+            metadata_cache: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Get the base URL for API requests
     fn api_url(&self, endpoint: &str) -> String {
         format!("{}/api/{}", self.base_url, endpoint)
     }
+
+    /// Capabilities and context length of an installed model
+    ///
+    /// Digests identify the model contents, so a result stays valid until the
+    /// model is re-pulled. Models whose metadata cannot be read yield `None`.
+    async fn model_metadata(&self, name: &str, digest: Option<&str>) -> Option<ModelMetadata> {
+        if let Some(digest) = digest {
+            if let Some(cached) = self.metadata_cache.lock().unwrap().get(digest) {
+                return Some(cached.clone());
+            }
+        }
+
+        let show = self
+            .post_model_api("show", serde_json::json!({"model": name}))
+            .await
+            .ok()?;
+        let metadata = ModelMetadata::from_show(&show);
+        if let Some(digest) = digest {
+            self.metadata_cache
+                .lock()
+                .unwrap()
+                .insert(digest.to_string(), metadata.clone());
+        }
+        Some(metadata)
+    }
 }
 
 #[async_trait]
@@ -66,11 +114,12 @@ impl LlmEngine for OllamaEngine {
             chat_stream: true,
             embeddings: true,
             moderation: false,
-            tools: false,        // Model-specific
+            tools: true,         // Model-specific
             reasoning: false,    // Model-specific
             vision_inputs: true, // Model-specific
             audio_inputs: true,  // Model-specific
             audio_outputs: false,
+            image_generation: false,
             max_image_bytes: Some(8 * 1024 * 1024),
             max_audio_bytes: Some(25 * 1024 * 1024),
         }
@@ -124,28 +173,26 @@ impl LlmEngine for OllamaEngine {
                 status_code: None,
             })?;
 
+        let metadata = futures::future::join_all(
+            response
+                .models
+                .iter()
+                .map(|model| self.model_metadata(&model.name, model.digest.as_deref())),
+        )
+        .await;
+
         let models = response
             .models
             .into_iter()
-            .map(|model| {
-                let model_name = &model.name;
-                let capabilities = Some(ModelCapabilities {
-                    reasoning: detect_reasoning_support(model_name),
-                    tools: detect_tool_use_support(model_name),
-                    vision: detect_vision_support(model_name),
-                    audio_inputs: detect_audio_support(model_name),
-                    audio_outputs: detect_audio_support(model_name),
-                });
-
-                ModelInfo {
-                    engine_id: self.engine_id.clone(),
-                    model_id: format!("flm://{}/{}", self.engine_id, model.name),
-                    display_name: model.name.clone(),
-                    context_length: None, // Ollama API doesn't provide this
-                    supports_streaming: true,
-                    supports_embeddings: true,
-                    capabilities,
-                }
+            .zip(metadata)
+            .map(|(model, metadata)| ModelInfo {
+                engine_id: self.engine_id.clone(),
+                model_id: format!("flm://{}/{}", self.engine_id, model.name),
+                display_name: model.name.clone(),
+                context_length: metadata.as_ref().and_then(|m| m.context_length),
+                supports_streaming: true,
+                supports_embeddings: metadata.as_ref().is_none_or(|m| m.supports_embeddings),
+                capabilities: metadata.map(|m| m.capabilities),
             })
             .collect();
 
@@ -171,19 +218,29 @@ impl LlmEngine for OllamaEngine {
                 reason: format!("Invalid model ID: {}", req.model_id),
             })?;
 
+        check_sampling_support(&req.sampling, "chat")?;
+        let tool_names = collect_tool_call_names(&req.messages);
         let ollama_req = OllamaChatRequest {
             model: model.to_string(),
             messages: req
                 .messages
                 .into_iter()
-                .map(convert_to_ollama_message)
+                .map(|msg| convert_to_ollama_message(msg, &tool_names))
                 .collect(),
             stream: false,
             options: OllamaOptions {
                 temperature: req.temperature.map(|t| t as f64),
-                top_p: None,
+                top_p: req.sampling.top_p,
+                top_k: req.sampling.top_k,
+                seed: req.sampling.seed,
+                presence_penalty: req.sampling.presence_penalty,
+                frequency_penalty: req.sampling.frequency_penalty,
+                repeat_penalty: req.sampling.repeat_penalty,
                 max_tokens: req.max_tokens,
+                stop: req.stop,
             },
+            tools: select_ollama_tools(&req.tools, req.tool_choice.as_ref()),
+            format: convert_response_format(req.response_format.as_ref()),
         };
 
         let url = self.api_url("chat");
@@ -226,8 +283,11 @@ impl LlmEngine for OllamaEngine {
                 role: ChatRole::Assistant,
                 content: response.message.content,
                 attachments: Vec::new(),
+                tool_calls: convert_tool_calls_from_ollama(response.message.tool_calls),
+                tool_call_id: None,
             }],
             audio: Vec::new(),
+            logprobs: Vec::new(),
         })
     }
 
@@ -254,19 +314,29 @@ impl LlmEngine for OllamaEngine {
                 reason: format!("Invalid model ID: {}", req.model_id),
             })?;
 
+        check_sampling_support(&req.sampling, "chat")?;
+        let tool_names = collect_tool_call_names(&req.messages);
         let ollama_req = OllamaChatRequest {
             model: model.to_string(),
             messages: req
                 .messages
                 .into_iter()
-                .map(convert_to_ollama_message)
+                .map(|msg| convert_to_ollama_message(msg, &tool_names))
                 .collect(),
             stream: true,
             options: OllamaOptions {
                 temperature: req.temperature.map(|t| t as f64),
-                top_p: None,
+                top_p: req.sampling.top_p,
+                top_k: req.sampling.top_k,
+                seed: req.sampling.seed,
+                presence_penalty: req.sampling.presence_penalty,
+                frequency_penalty: req.sampling.frequency_penalty,
+                repeat_penalty: req.sampling.repeat_penalty,
                 max_tokens: req.max_tokens,
+                stop: req.stop,
             },
+            tools: select_ollama_tools(&req.tools, req.tool_choice.as_ref()),
+            format: convert_response_format(req.response_format.as_ref()),
         };
 
         let url = self.api_url("chat");
@@ -286,71 +356,99 @@ impl LlmEngine for OllamaEngine {
 
             let mut stream = response.bytes_stream();
             let mut accumulated_content = String::new();
+            let mut buffer = String::new();
+            let mut next_tool_call_index: u32 = 0;
             let mut is_done = false;
 
-            while let Some(chunk_result) = stream.next().await {
-                let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
-                    reason: format!("Stream error: {e}"),
-                })?;
+            // Ollama streams newline-delimited JSON objects (not SSE)
+            loop {
+                let next = stream.next().await;
+                let end_of_stream = next.is_none();
+                match next {
+                    Some(chunk_result) => {
+                        let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
+                            reason: format!("Stream error: {e}"),
+                        })?;
+                        buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    }
+                    // Flush a trailing object that was not newline-terminated
+                    None => buffer.push('\n'),
+                }
 
-                // Parse SSE format
-                let text = String::from_utf8_lossy(&bytes);
-                for line in text.lines() {
-                    if let Some(json_str) = line.strip_prefix("data: ") {
-                        if json_str == "[DONE]" {
-                            is_done = true;
-                            break;
-                        }
+                while let Some(newline) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=newline).collect();
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
 
-                        match serde_json::from_str::<OllamaChatChunk>(json_str) {
-                            Ok(chunk) => {
-                                accumulated_content.push_str(&chunk.message.content);
-                                yield Ok(ChatStreamChunk {
-                                    delta: ChatMessage {
-                                        role: ChatRole::Assistant,
-                                        content: chunk.message.content.clone(),
-                                        attachments: Vec::new(),
-                                    },
-                                    usage: if chunk.done {
-                                        let prompt_tokens = chunk.prompt_eval_count.unwrap_or_else(|| {
-                                            eprintln!("Warning: prompt_eval_count is missing in Ollama stream chunk");
-                                            0
-                                        });
-                                        let completion_tokens = chunk.eval_count.unwrap_or_else(|| {
-                                            eprintln!("Warning: eval_count is missing in Ollama stream chunk");
-                                            0
-                                        });
-                                        let total_tokens = chunk
-                                            .prompt_eval_count
-                                            .unwrap_or(0)
-                                            .saturating_add(chunk.eval_count.unwrap_or(0));
-                                        Some(UsageStats {
-                                            prompt_tokens,
-                                            completion_tokens,
-                                            total_tokens,
-                                        })
-                                    } else {
-                                        None
-                                    },
-                                    is_done: chunk.done,
-                                    audio: Vec::new(),
-                                });
-
-                                if chunk.done {
-                                    is_done = true;
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                yield Err(EngineError::InvalidResponse {
-                                    reason: format!("Failed to parse chunk: {e}"),
-                                });
+                    match serde_json::from_str::<OllamaChatChunk>(line) {
+                        Ok(chunk) => {
+                            accumulated_content.push_str(&chunk.message.content);
+                            let tool_calls: Vec<ToolCallDelta> =
+                                convert_tool_calls_from_ollama(chunk.message.tool_calls)
+                                    .into_iter()
+                                    .map(|call| {
+                                        let index = next_tool_call_index;
+                                        next_tool_call_index += 1;
+                                        // Ollama emits each call complete, so one delta carries it all
+                                        ToolCallDelta {
+                                            index,
+                                            id: Some(call.id),
+                                            name: Some(call.name),
+                                            arguments: Some(call.arguments),
+                                        }
+                                    })
+                                    .collect();
+                            yield Ok(ChatStreamChunk {
+                                delta: ChatMessage {
+                                    role: ChatRole::Assistant,
+                                    content: chunk.message.content,
+                                    attachments: Vec::new(),
+                                    tool_calls: Vec::new(),
+                                    tool_call_id: None,
+                                },
+                                usage: if chunk.done {
+                                    let prompt_tokens = chunk.prompt_eval_count.unwrap_or_else(|| {
+                                        eprintln!("Warning: prompt_eval_count is missing in Ollama stream chunk");
+                                        0
+                                    });
+                                    let completion_tokens = chunk.eval_count.unwrap_or_else(|| {
+                                        eprintln!("Warning: eval_count is missing in Ollama stream chunk");
+                                        0
+                                    });
+                                    let total_tokens = chunk
+                                        .prompt_eval_count
+                                        .unwrap_or(0)
+                                        .saturating_add(chunk.eval_count.unwrap_or(0));
+                                    Some(UsageStats {
+                                        prompt_tokens,
+                                        completion_tokens,
+                                        total_tokens,
+                                    })
+                                } else {
+                                    None
+                                },
+                                is_done: chunk.done,
+                                audio: Vec::new(),
+                                tool_calls,
+                                logprobs: Vec::new(),
+                            });
+
+                            if chunk.done {
+                                is_done = true;
+                                break;
                             }
                         }
+                        Err(e) => {
+                            yield Err(EngineError::InvalidResponse {
+                                reason: format!("Failed to parse chunk: {e}"),
+                            });
+                        }
                     }
                 }
 
-                if is_done {
+                if is_done || end_of_stream {
                     break;
                 }
             }
@@ -420,6 +518,150 @@ impl LlmEngine for OllamaEngine {
         })
     }
 
+    async fn complete(&self, req: CompletionRequest) -> Result<CompletionResponse, EngineError> {
+        // Verify engine_id matches
+        if req.engine_id != self.engine_id {
+            return Err(EngineError::InvalidResponse {
+                reason: format!(
+                    "Engine ID mismatch: expected {}, got {}",
+                    self.engine_id, req.engine_id
+                ),
+            });
+        }
+
+        // Extract model name from model_id
+        let model = req
+            .model_id
+            .strip_prefix(&format!("flm://{}/", self.engine_id))
+            .ok_or_else(|| EngineError::InvalidResponse {
+                reason: format!("Invalid model ID: {}", req.model_id),
+            })?
+            .to_string();
+
+        let ollama_req = convert_completion_request(&model, req, false)?;
+
+        let url = self.api_url("generate");
+        let response: OllamaGenerateResponse = self
+            .client
+            .post(&url)
+            .json(&ollama_req)
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?
+            .json()
+            .await
+            .map_err(|e| EngineError::ApiError {
+                reason: format!("Failed to parse JSON: {e}"),
+                status_code: None,
+            })?;
+
+        Ok(CompletionResponse {
+            usage: generate_usage(&response),
+            choices: vec![CompletionChoice {
+                text: response.response,
+                finish_reason: response.done_reason,
+            }],
+        })
+    }
+
+    async fn complete_stream(
+        &self,
+        req: CompletionRequest,
+    ) -> Result<CompletionStream, EngineError> {
+        // Verify engine_id matches
+        if req.engine_id != self.engine_id {
+            return Err(EngineError::InvalidResponse {
+                reason: format!(
+                    "Engine ID mismatch: expected {}, got {}",
+                    self.engine_id, req.engine_id
+                ),
+            });
+        }
+
+        // Extract model name from model_id
+        let model = req
+            .model_id
+            .strip_prefix(&format!("flm://{}/", self.engine_id))
+            .ok_or_else(|| EngineError::InvalidResponse {
+                reason: format!("Invalid model ID: {}", req.model_id),
+            })?
+            .to_string();
+
+        let ollama_req = convert_completion_request(&model, req, true)?;
+
+        let url = self.api_url("generate");
+        let client = self.client.clone();
+
+        let stream = async_stream::stream! {
+            let response = client
+                .post(&url)
+                .json(&ollama_req)
+                .send()
+                .await
+                .map_err(|e| EngineError::NetworkError {
+                    reason: format!("Request failed: {e}"),
+                })?;
+
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut is_done = false;
+
+            // Ollama streams newline-delimited JSON objects (not SSE)
+            loop {
+                let next = stream.next().await;
+                let end_of_stream = next.is_none();
+                match next {
+                    Some(chunk_result) => {
+                        let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
+                            reason: format!("Stream error: {e}"),
+                        })?;
+                        buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    }
+                    // Flush a trailing object that was not newline-terminated
+                    None => buffer.push('\n'),
+                }
+
+                while let Some(newline) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=newline).collect();
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    match serde_json::from_str::<OllamaGenerateResponse>(line) {
+                        Ok(chunk) => {
+                            let usage = chunk.done.then(|| generate_usage(&chunk));
+                            yield Ok(CompletionStreamChunk {
+                                text: chunk.response,
+                                usage,
+                                is_done: chunk.done,
+                                finish_reason: chunk.done_reason,
+                            });
+
+                            if chunk.done {
+                                is_done = true;
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            yield Err(EngineError::InvalidResponse {
+                                reason: format!("Failed to parse chunk: {e}"),
+                            });
+                        }
+                    }
+                }
+
+                if is_done || end_of_stream {
+                    break;
+                }
+            }
+        };
+
+        Ok(Box::pin(stream))
+    }
+
     async fn transcribe_audio(
         &self,
         req: TranscriptionRequest,
@@ -475,11 +717,19 @@ impl LlmEngine for OllamaEngine {
         let ollama_req = OllamaGenerateRequest {
             model: model.to_string(),
             prompt,
+            suffix: None,
+            raw: false,
             stream: false,
             options: OllamaOptions {
                 temperature: req.temperature,
                 top_p: None,
+                top_k: None,
+                seed: None,
+                presence_penalty: None,
+                frequency_penalty: None,
+                repeat_penalty: None,
                 max_tokens: None,
+                stop: Vec::new(),
             },
         };
 
@@ -505,6 +755,10 @@ impl LlmEngine for OllamaEngine {
             language: req.language,
         })
     }
+
+    fn model_manager(&self) -> Option<&dyn ModelManager> {
+        Some(self)
+    }
 }
 
 // Ollama API request/response types
@@ -515,14 +769,38 @@ struct OllamaChatRequest {
     messages: Vec<OllamaMessage>,
     stream: bool,
     options: OllamaOptions,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Value>,
+    /// `"json"` or a JSON schema constraining the output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<Value>,
 }
 
 #[derive(Serialize, Deserialize)]
 struct OllamaMessage {
     role: String,
+    #[serde(default)]
     content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     images: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<OllamaToolCall>>,
+    /// Name of the function whose result this `tool` message carries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OllamaFunctionCall {
+    name: String,
+    /// Ollama uses a JSON object here rather than OpenAI's encoded string
+    #[serde(default)]
+    arguments: Value,
 }
 
 /// Convert ChatMessage (OpenAI-compatible format) to OllamaMessage format
@@ -530,12 +808,16 @@ struct OllamaMessage {
 /// This function converts the abstract ChatRequest format to Ollama's specific API format.
 /// Image attachments are converted to Base64 strings for Ollama's `images` field.
 /// Audio attachments are not supported by Ollama API and will be ignored.
-fn convert_to_ollama_message(msg: ChatMessage) -> OllamaMessage {
+/// Tool results are matched to their function name via `tool_names` (call ID -> name).
+fn convert_to_ollama_message(
+    msg: ChatMessage,
+    tool_names: &HashMap<String, String>,
+) -> OllamaMessage {
     let role = match msg.role {
         ChatRole::User => "user".to_string(),
         ChatRole::Assistant => "assistant".to_string(),
         ChatRole::System => "system".to_string(),
-        ChatRole::Tool => "user".to_string(), // Fallback to user
+        ChatRole::Tool => "tool".to_string(),
     };
 
     // Extract image attachments and convert to Base64 strings for Ollama's images field
@@ -553,6 +835,24 @@ fn convert_to_ollama_message(msg: ChatMessage) -> OllamaMessage {
         })
         .collect();
 
+    let tool_calls: Vec<OllamaToolCall> = msg
+        .tool_calls
+        .iter()
+        .map(|call| OllamaToolCall {
+            function: OllamaFunctionCall {
+                name: call.name.clone(),
+                // Fall back to the raw string if the arguments are not valid JSON
+                arguments: serde_json::from_str(&call.arguments)
+                    .unwrap_or_else(|_| Value::String(call.arguments.clone())),
+            },
+        })
+        .collect();
+
+    let tool_name = msg
+        .tool_call_id
+        .as_ref()
+        .and_then(|id| tool_names.get(id).cloned());
+
     OllamaMessage {
         role,
         content: msg.content,
@@ -561,17 +861,126 @@ fn convert_to_ollama_message(msg: ChatMessage) -> OllamaMessage {
         } else {
             Some(images)
         },
+        tool_calls: if tool_calls.is_empty() {
+            None
+        } else {
+            Some(tool_calls)
+        },
+        tool_name,
+    }
+}
+
+/// Map tool call IDs to function names from earlier assistant messages
+fn collect_tool_call_names(messages: &[ChatMessage]) -> HashMap<String, String> {
+    messages
+        .iter()
+        .flat_map(|msg| msg.tool_calls.iter())
+        .map(|call| (call.id.clone(), call.name.clone()))
+        .collect()
+}
+
+/// Reject sampling parameters Ollama cannot honour instead of silently dropping them
+fn check_sampling_support(sampling: &SamplingParams, operation: &str) -> Result<(), EngineError> {
+    let mut unsupported = Vec::new();
+    if sampling.n.is_some_and(|n| n > 1) {
+        unsupported.push("n");
+    }
+    if !sampling.logit_bias.is_empty() {
+        unsupported.push("logit_bias");
+    }
+    if sampling.logprobs || sampling.top_logprobs.is_some() {
+        unsupported.push("logprobs");
+    }
+    if unsupported.is_empty() {
+        Ok(())
+    } else {
+        Err(EngineError::UnsupportedOperation {
+            operation: operation.to_string(),
+            reason: format!(
+                "Ollama does not support sampling parameter(s): {}",
+                unsupported.join(", ")
+            ),
+        })
     }
 }
 
+/// Map the requested output format to Ollama's `format` field
+fn convert_response_format(format: Option<&ResponseFormat>) -> Option<Value> {
+    match format {
+        Some(ResponseFormat::JsonObject) => Some(Value::String("json".to_string())),
+        Some(ResponseFormat::JsonSchema { schema, .. }) => Some(schema.clone()),
+        Some(ResponseFormat::Text) | None => None,
+    }
+}
+
+/// Build Ollama's `tools` array, honouring the tool choice where possible
+///
+/// Ollama has no `tool_choice` parameter: `None` drops the tools entirely and
+/// `Function` narrows them to the named function. `Required` cannot be enforced
+/// and behaves like `Auto`.
+fn select_ollama_tools(tools: &[ToolDefinition], choice: Option<&ToolChoice>) -> Vec<Value> {
+    tools
+        .iter()
+        .filter(|tool| match choice {
+            Some(ToolChoice::None) => false,
+            Some(ToolChoice::Function { name }) => &tool.name == name,
+            _ => true,
+        })
+        .map(|tool| {
+            let mut function = serde_json::json!({ "name": tool.name });
+            if let Some(description) = &tool.description {
+                function["description"] = Value::String(description.clone());
+            }
+            if let Some(parameters) = &tool.parameters {
+                function["parameters"] = parameters.clone();
+            }
+            serde_json::json!({
+                "type": "function",
+                "function": function
+            })
+        })
+        .collect()
+}
+
+/// Convert Ollama tool calls to domain tool calls
+///
+/// Ollama does not assign call IDs, so one is generated per call.
+fn convert_tool_calls_from_ollama(calls: Option<Vec<OllamaToolCall>>) -> Vec<ToolCall> {
+    calls
+        .unwrap_or_default()
+        .into_iter()
+        .map(|call| ToolCall {
+            id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+            name: call.function.name,
+            arguments: match call.function.arguments {
+                Value::String(raw) => raw,
+                Value::Null => "{}".to_string(),
+                other => other.to_string(),
+            },
+        })
+        .collect()
+}
+
 #[derive(Serialize)]
 struct OllamaOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    top_p: Option<f64>,
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repeat_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -603,167 +1012,8 @@ struct OllamaTagsResponse {
 #[derive(Deserialize)]
 struct OllamaModel {
     name: String,
-}
-
-/// Detect if a model supports reasoning capabilities based on model name
-fn detect_reasoning_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("reasoning")
-        || name.contains("o1")
-        || name.contains("deepseek-r1")
-        || name.contains("deepseek-r")
-        || name.contains("qwen-reasoning")
-        || name.contains("qwen2.5-reasoning")
-        || name.contains("reason")
-        || name.contains("cot")
-}
-
-/// Detect if a model supports tool use/function calling based on model name
-fn detect_tool_use_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("tool")
-        || name.contains("function")
-        || name.contains("agent")
-        || name.contains("api")
-        || (name.contains("mistral") && (name.contains("large") || name.contains("small")))
-        || name.contains("claude")
-        || name.contains("gpt-4")
-        || name.contains("gpt-3.5-turbo")
-}
-
-/// Detect if a model supports vision capabilities based on model name
-fn detect_vision_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("vision")
-        || name.contains("llava")
-        || name.contains("clip")
-        || name.contains("blip")
-        || name.contains("multimodal")
-        || (name.contains("llama") && name.contains("vision"))
-}
-
-/// Detect if a model supports audio capabilities based on model name
-fn detect_audio_support(model_name: &str) -> bool {
-    let name = model_name.to_lowercase();
-    name.contains("whisper")
-        || name.contains("audio")
-        || name.contains("speech")
-        || name.contains("tts")
-        || name.contains("asr")
-        || name.contains("transcription")
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_detect_reasoning_support() {
-        // Positive cases
-        assert!(detect_reasoning_support("o1"));
-        assert!(detect_reasoning_support("O1")); // Case insensitive
-        assert!(detect_reasoning_support("deepseek-r1"));
-        assert!(detect_reasoning_support("deepseek-r"));
-        assert!(detect_reasoning_support("qwen-reasoning"));
-        assert!(detect_reasoning_support("qwen2.5-reasoning"));
-        assert!(detect_reasoning_support("model-cot"));
-        assert!(detect_reasoning_support("model-reasoning"));
-
-        // Negative cases
-        assert!(!detect_reasoning_support("llama2"));
-        assert!(!detect_reasoning_support("gpt-3.5"));
-        assert!(!detect_reasoning_support(""));
-    }
-
-    #[test]
-    fn test_detect_tool_use_support() {
-        // Positive cases
-        assert!(detect_tool_use_support("mistral-large"));
-        assert!(detect_tool_use_support("mistral-small"));
-        assert!(detect_tool_use_support("gpt-4"));
-        assert!(detect_tool_use_support("gpt-3.5-turbo"));
-        assert!(detect_tool_use_support("claude-3"));
-        assert!(detect_tool_use_support("model-tool"));
-        assert!(detect_tool_use_support("model-function"));
-        assert!(detect_tool_use_support("model-agent"));
-
-        // Negative cases
-        assert!(!detect_tool_use_support("llama2"));
-        assert!(!detect_tool_use_support("mistral-tiny"));
-        assert!(!detect_tool_use_support(""));
-    }
-
-    #[test]
-    fn test_detect_vision_support() {
-        // Positive cases
-        assert!(detect_vision_support("llava"));
-        assert!(detect_vision_support("LLAVA")); // Case insensitive
-        assert!(detect_vision_support("clip"));
-        assert!(detect_vision_support("blip"));
-        assert!(detect_vision_support("vision-model"));
-        assert!(detect_vision_support("llama-vision"));
-        assert!(detect_vision_support("multimodal-model"));
-
-        // Negative cases
-        assert!(!detect_vision_support("llama2"));
-        assert!(!detect_vision_support("gpt-3.5"));
-        assert!(!detect_vision_support(""));
-    }
-
-    #[test]
-    fn test_detect_audio_support() {
-        // Positive cases
-        assert!(detect_audio_support("whisper"));
-        assert!(detect_audio_support("WHISPER")); // Case insensitive
-        assert!(detect_audio_support("audio-model"));
-        assert!(detect_audio_support("speech-model"));
-        assert!(detect_audio_support("tts-model"));
-        assert!(detect_audio_support("asr-model"));
-        assert!(detect_audio_support("transcription-model"));
-
-        // Negative cases
-        assert!(!detect_audio_support("llama2"));
-        assert!(!detect_audio_support("gpt-3.5"));
-        assert!(!detect_audio_support(""));
-    }
-
-    #[test]
-    fn test_detect_edge_cases() {
-        // Edge cases for model name detection
-        assert!(!detect_reasoning_support(""));
-        assert!(!detect_reasoning_support("   "));
-        assert!(!detect_reasoning_support("model"));
-        assert!(!detect_reasoning_support("123"));
-
-        // Partial matches should not trigger
-        assert!(!detect_reasoning_support("model-o"));
-        assert!(!detect_reasoning_support("o-model"));
-
-        // Case variations
-        assert!(detect_reasoning_support("O1"));
-        assert!(detect_reasoning_support("O1-PREVIEW"));
-        assert!(detect_reasoning_support("DEEPSEEK-R1"));
-
-        // Special characters
-        assert!(detect_reasoning_support("o1:latest"));
-        assert!(detect_reasoning_support("o1-v2.0"));
-        assert!(detect_reasoning_support("deepseek-r1:7b"));
-    }
-
-    #[test]
-    fn test_detect_all_capabilities_combinations() {
-        // Test models that might have multiple capabilities
-        let multimodal_model = "gpt-4-vision-tool";
-        assert!(detect_tool_use_support(multimodal_model));
-        assert!(detect_vision_support(multimodal_model));
-
-        // Test that detection is independent
-        let reasoning_only = "o1";
-        assert!(detect_reasoning_support(reasoning_only));
-        assert!(!detect_tool_use_support(reasoning_only));
-        assert!(!detect_vision_support(reasoning_only));
-        assert!(!detect_audio_support(reasoning_only));
-    }
+    #[serde(default)]
+    digest: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -781,13 +1031,73 @@ struct OllamaEmbeddingResponse {
 struct OllamaGenerateRequest {
     model: String,
     prompt: String,
+    /// Fill-in-the-middle suffix (applied through the model's template)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suffix: Option<String>,
+    /// Bypass the model's prompt template
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    raw: bool,
     stream: bool,
     options: OllamaOptions,
 }
 
+/// `/api/generate` response (also each NDJSON line when streaming)
 #[derive(Deserialize)]
 struct OllamaGenerateResponse {
     response: String,
-    #[allow(dead_code)]
     done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+}
+
+/// Build an `/api/generate` request for a raw text completion
+///
+/// Without a suffix the prompt is sent `raw` so no chat template is applied;
+/// fill-in-the-middle needs the template, which is where Ollama places the suffix.
+fn convert_completion_request(
+    model: &str,
+    req: CompletionRequest,
+    stream: bool,
+) -> Result<OllamaGenerateRequest, EngineError> {
+    check_sampling_support(&req.sampling, "complete")?;
+    if req.best_of.is_some_and(|best_of| best_of > 1) {
+        return Err(EngineError::UnsupportedOperation {
+            operation: "complete".to_string(),
+            reason: "Ollama does not support best_of".to_string(),
+        });
+    }
+
+    Ok(OllamaGenerateRequest {
+        model: model.to_string(),
+        raw: req.suffix.is_none(),
+        prompt: req.prompt,
+        suffix: req.suffix,
+        stream,
+        options: OllamaOptions {
+            temperature: req.temperature.map(|t| t as f64),
+            top_p: req.sampling.top_p,
+            top_k: req.sampling.top_k,
+            seed: req.sampling.seed,
+            presence_penalty: req.sampling.presence_penalty,
+            frequency_penalty: req.sampling.frequency_penalty,
+            repeat_penalty: req.sampling.repeat_penalty,
+            max_tokens: req.max_tokens,
+            stop: req.stop,
+        },
+    })
+}
+
+/// Usage statistics from the final `/api/generate` object
+fn generate_usage(response: &OllamaGenerateResponse) -> UsageStats {
+    let prompt_tokens = response.prompt_eval_count.unwrap_or(0);
+    let completion_tokens = response.eval_count.unwrap_or(0);
+    UsageStats {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens.saturating_add(completion_tokens),
+    }
 }
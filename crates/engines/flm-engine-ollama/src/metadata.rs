@@ -0,0 +1,124 @@
+//! Per-model capabilities from `/api/show`
+//!
+//! Newer Ollama versions list a model's features in `capabilities`; older
+//! versions only expose the prompt template, the GGUF `model_info` keys and
+//! `projector_info` for vision models, so those are inspected instead.
+
+use flm_core::domain::models::ModelCapabilities;
+use serde_json::Value;
+
+/// Capabilities and context window of one model (cached per digest)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ModelMetadata {
+    pub capabilities: ModelCapabilities,
+    pub context_length: Option<u32>,
+    pub supports_embeddings: bool,
+}
+
+impl ModelMetadata {
+    /// Derive model metadata from an `/api/show` response
+    pub fn from_show(show: &Value) -> Self {
+        let context_length = context_length_from_model_info(show);
+
+        if let Some(features) = show.get("capabilities").and_then(Value::as_array) {
+            let has = |feature: &str| features.iter().any(|f| f.as_str() == Some(feature));
+            return Self {
+                capabilities: ModelCapabilities {
+                    reasoning: has("thinking"),
+                    tools: has("tools"),
+                    vision: has("vision"),
+                    audio_inputs: has("audio"),
+                    audio_outputs: false,
+                },
+                context_length,
+                supports_embeddings: has("embedding"),
+            };
+        }
+
+        let template = show.get("template").and_then(Value::as_str).unwrap_or("");
+        let model_info = show.get("model_info").and_then(Value::as_object);
+        let has_info_key = |needle: &str| {
+            model_info.is_some_and(|info| info.keys().any(|key| key.contains(needle)))
+        };
+        let has_projector = show
+            .get("projector_info")
+            .is_some_and(|projector| !projector.is_null());
+
+        Self {
+            capabilities: ModelCapabilities {
+                reasoning: template.contains(".Thinking") || template.contains("<think>"),
+                tools: template.contains(".Tools"),
+                vision: has_projector || has_info_key(".vision."),
+                audio_inputs: has_info_key(".audio."),
+                audio_outputs: false,
+            },
+            context_length,
+            // Without a feature list every model is assumed to embed
+            supports_embeddings: true,
+        }
+    }
+}
+
+/// `<architecture>.context_length` from the `/api/show` `model_info` map
+fn context_length_from_model_info(show: &Value) -> Option<u32> {
+    let model_info = show.get("model_info")?;
+    let architecture = model_info
+        .get("general.architecture")
+        .and_then(Value::as_str)?;
+    model_info
+        .get(format!("{architecture}.context_length"))
+        .and_then(Value::as_u64)
+        .and_then(|n| u32::try_from(n).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_metadata_from_capabilities_list() {
+        let metadata = ModelMetadata::from_show(&json!({
+            "capabilities": ["completion", "tools", "vision", "thinking"],
+            "model_info": {
+                "general.architecture": "gemma3",
+                "gemma3.context_length": 131072
+            }
+        }));
+        assert!(metadata.capabilities.tools);
+        assert!(metadata.capabilities.vision);
+        assert!(metadata.capabilities.reasoning);
+        assert!(!metadata.capabilities.audio_inputs);
+        assert!(!metadata.supports_embeddings);
+        assert_eq!(metadata.context_length, Some(131072));
+
+        let embedding = ModelMetadata::from_show(&json!({"capabilities": ["embedding"]}));
+        assert!(embedding.supports_embeddings);
+        assert_eq!(embedding.context_length, None);
+    }
+
+    #[test]
+    fn test_metadata_from_template_and_model_info() {
+        let metadata = ModelMetadata::from_show(&json!({
+            "template": "{{- if .Tools }}{{ range .Tools }}{{ . }}{{ end }}{{ end }}{{ .Prompt }}",
+            "projector_info": {"clip.has_vision_encoder": true},
+            "model_info": {
+                "general.architecture": "llama",
+                "llama.context_length": 8192
+            }
+        }));
+        assert!(metadata.capabilities.tools);
+        assert!(metadata.capabilities.vision);
+        assert!(!metadata.capabilities.reasoning);
+        assert_eq!(metadata.context_length, Some(8192));
+
+        let plain = ModelMetadata::from_show(&json!({
+            "template": "{{ .Prompt }}",
+            "model_info": {"general.architecture": "qwen2", "qwen2.audio.block_count": 32}
+        }));
+        assert!(!plain.capabilities.tools);
+        assert!(!plain.capabilities.vision);
+        assert!(plain.capabilities.audio_inputs);
+        assert_eq!(plain.context_length, None);
+    }
+}
@@ -0,0 +1,287 @@
+//! Model management (`/api/pull`, `/api/delete`, `/api/show`, `/api/copy`)
+
+use super::OllamaEngine;
+use crate::metadata::ModelMetadata;
+use async_trait::async_trait;
+use flm_core::domain::engine::{ModelDetails, ModelPullProgress, ModelPullRequest};
+use flm_core::domain::models::EngineId;
+use flm_core::error::EngineError;
+use flm_core::ports::{ModelManager, ModelPullStream};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// Pulls can take hours for large models; the client's request timeout is too short
+const PULL_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// One NDJSON line of `/api/pull`
+#[derive(Deserialize)]
+struct OllamaPullLine {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OllamaInstalledModels {
+    #[serde(default)]
+    models: Vec<OllamaInstalledModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaInstalledModel {
+    name: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    modified_at: Option<String>,
+    #[serde(default)]
+    details: OllamaModelDetails,
+}
+
+#[derive(Default, Deserialize)]
+struct OllamaModelDetails {
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    parameter_size: Option<String>,
+    #[serde(default)]
+    quantization_level: Option<String>,
+}
+
+impl OllamaEngine {
+    pub(crate) async fn post_model_api(
+        &self,
+        endpoint: &str,
+        body: Value,
+    ) -> Result<Value, EngineError> {
+        let response = self
+            .client
+            .post(self.api_url(endpoint))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Failed to read response: {e}"),
+            })?;
+        if !status.is_success() {
+            return Err(api_error(status.as_u16(), &text));
+        }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| EngineError::InvalidResponse {
+            reason: format!("Failed to parse JSON: {e}"),
+        })
+    }
+}
+
+#[async_trait]
+impl ModelManager for OllamaEngine {
+    fn engine_id(&self) -> EngineId {
+        self.engine_id.clone()
+    }
+
+    async fn list_installed(&self) -> Result<Vec<ModelDetails>, EngineError> {
+        let response: OllamaInstalledModels = self
+            .client
+            .get(self.api_url("tags"))
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?
+            .json()
+            .await
+            .map_err(|e| EngineError::InvalidResponse {
+                reason: format!("Failed to parse JSON: {e}"),
+            })?;
+
+        Ok(response
+            .models
+            .into_iter()
+            .map(|model| ModelDetails {
+                engine_id: self.engine_id.clone(),
+                name: model.name,
+                family: model.details.family,
+                parameter_size: model.details.parameter_size,
+                quantization: model.details.quantization_level,
+                format: model.details.format,
+                size_bytes: model.size,
+                digest: model.digest.map(|d| normalize_digest(&d)),
+                modified_at: model.modified_at,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn pull(&self, req: ModelPullRequest) -> Result<ModelPullStream, EngineError> {
+        let response = self
+            .client
+            .post(self.api_url("pull"))
+            .timeout(PULL_TIMEOUT)
+            .json(&json!({"model": req.model, "stream": true}))
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(api_error(status.as_u16(), &text));
+        }
+
+        let stream = async_stream::try_stream! {
+            let mut bytes = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut succeeded = false;
+            loop {
+                let next = bytes.next().await;
+                let end_of_stream = next.is_none();
+                match next {
+                    Some(chunk) => {
+                        let chunk = chunk.map_err(|e| EngineError::NetworkError {
+                            reason: format!("Stream error: {e}"),
+                        })?;
+                        buffer.push_str(&String::from_utf8_lossy(&chunk));
+                    }
+                    // Flush a trailing object that was not newline-terminated
+                    None => buffer.push('\n'),
+                }
+
+                while let Some(newline) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=newline).collect();
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let parsed: OllamaPullLine =
+                        serde_json::from_str(line).map_err(|e| EngineError::InvalidResponse {
+                            reason: format!("Invalid pull progress line: {e}"),
+                        })?;
+                    if let Some(error) = parsed.error {
+                        Err(EngineError::ApiError {
+                            reason: error,
+                            status_code: None,
+                        })?;
+                    }
+                    let progress = ModelPullProgress {
+                        status: parsed.status.unwrap_or_default(),
+                        digest: parsed.digest,
+                        total: parsed.total,
+                        completed: parsed.completed,
+                    };
+                    succeeded |= progress.is_success();
+                    yield progress;
+                }
+
+                if end_of_stream {
+                    break;
+                }
+            }
+            if !succeeded {
+                Err(EngineError::InvalidResponse {
+                    reason: "Pull ended without a success status".to_string(),
+                })?;
+            }
+        };
+        Ok(Box::pin(stream))
+    }
+
+    async fn delete(&self, model: &str) -> Result<(), EngineError> {
+        let response = self
+            .client
+            .delete(self.api_url("delete"))
+            .json(&json!({"model": model}))
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let text = response.text().await.unwrap_or_default();
+            Err(api_error(status.as_u16(), &text))
+        }
+    }
+
+    async fn show(&self, model: &str) -> Result<ModelDetails, EngineError> {
+        let metadata = self.post_model_api("show", json!({"model": model})).await?;
+        let show = ModelMetadata::from_show(&metadata);
+        let details = metadata.get("details");
+        let detail = |key: &str| {
+            details
+                .and_then(|d| d.get(key))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        Ok(ModelDetails {
+            engine_id: self.engine_id.clone(),
+            name: model.to_string(),
+            family: detail("family"),
+            parameter_size: detail("parameter_size"),
+            quantization: detail("quantization_level"),
+            format: detail("format"),
+            modified_at: metadata
+                .get("modified_at")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            context_length: show.context_length,
+            capabilities: Some(show.capabilities),
+            metadata,
+            ..Default::default()
+        })
+    }
+
+    async fn copy(&self, source: &str, destination: &str) -> Result<(), EngineError> {
+        self.post_model_api(
+            "copy",
+            json!({"source": source, "destination": destination}),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Ollama reports bare hex digests in `/api/tags`
+fn normalize_digest(digest: &str) -> String {
+    if digest.contains(':') {
+        digest.to_string()
+    } else {
+        format!("sha256:{digest}")
+    }
+}
+
+fn api_error(status_code: u16, body: &str) -> EngineError {
+    let reason = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("error").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_else(|| format!("HTTP {status_code}: {body}"));
+    EngineError::ApiError {
+        reason,
+        status_code: Some(status_code),
+    }
+}
@@ -2,11 +2,14 @@
 //!
 //! These tests verify that the Ollama engine adapter works correctly with mock HTTP servers.
 
-use flm_core::domain::chat::{ChatMessage, ChatRequest, ChatRole};
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatRole, CompletionRequest, ResponseFormat, SamplingParams,
+    ToolDefinition,
+};
 use flm_core::ports::LlmEngine;
 use flm_engine_ollama::OllamaEngine;
 use reqwest::StatusCode;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_partial_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -52,7 +55,78 @@ async fn test_ollama_engine_list_models() {
 
     let models = engine.list_models().await.unwrap();
     assert_eq!(models.len(), 1);
-    assert_eq!(models[0].model_id, "llama2:latest");
+    assert_eq!(models[0].model_id, "flm://ollama-test/llama2");
+    // Without `/api/show` metadata nothing is guessed from the name
+    assert!(models[0].capabilities.is_none());
+    assert_eq!(models[0].context_length, None);
+}
+
+#[tokio::test]
+async fn test_ollama_engine_list_models_uses_show_metadata() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "models": [
+                    {"name": "gemma3:4b", "digest": "a2af6cc3eb7f"},
+                    {"name": "nomic-embed-text:latest", "digest": "0a109f422b47"}
+                ]
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+    // Each digest is looked up once; later listings come from the cache
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .and(body_partial_json(serde_json::json!({"model": "gemma3:4b"})))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "capabilities": ["completion", "vision"],
+                "model_info": {
+                    "general.architecture": "gemma3",
+                    "gemma3.context_length": 131072
+                }
+            })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .and(body_partial_json(
+            serde_json::json!({"model": "nomic-embed-text:latest"}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "capabilities": ["embedding"],
+                "model_info": {
+                    "general.architecture": "nomic-bert",
+                    "nomic-bert.context_length": 2048
+                }
+            })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+
+    for _ in 0..2 {
+        let models = engine.list_models().await.unwrap();
+        assert_eq!(models.len(), 2);
+
+        let gemma = &models[0];
+        assert_eq!(gemma.context_length, Some(131072));
+        let caps = gemma.capabilities.as_ref().unwrap();
+        assert!(caps.vision && !caps.tools && !caps.reasoning);
+        assert!(!gemma.supports_embeddings);
+
+        let embed = &models[1];
+        assert_eq!(embed.context_length, Some(2048));
+        assert!(embed.supports_embeddings);
+    }
 }
 
 #[tokio::test]
@@ -115,12 +189,18 @@ async fn test_ollama_engine_chat() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let response = engine.chat(req).await.unwrap();
@@ -181,12 +261,18 @@ async fn test_ollama_engine_chat_stream() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: true,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let mut stream = engine.chat_stream(req).await.unwrap();
@@ -205,3 +291,420 @@ async fn test_ollama_engine_chat_stream() {
     assert!(!chunks.is_empty());
     assert!(chunks.last().unwrap().is_done);
 }
+
+#[tokio::test]
+async fn test_ollama_engine_chat_with_tools() {
+    let mock_server = MockServer::start().await;
+
+    // Ollama returns arguments as a JSON object rather than a string
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{
+                "type": "function",
+                "function": { "name": "get_weather" }
+            }]
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "model": "llama3.1:latest",
+                "created_at": "2024-07-22T20:33:28.123648Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "function": {
+                            "name": "get_weather",
+                            "arguments": { "city": "Tokyo" }
+                        }
+                    }]
+                },
+                "done": true,
+                "prompt_eval_count": 10,
+                "eval_count": 5
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "ollama-test".to_string(),
+        model_id: "flm://ollama-test/llama3.1:latest".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "What is the weather in Tokyo?".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: Some("Get the current weather".to_string()),
+            parameters: None,
+        }],
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.chat(req).await.unwrap();
+    let tool_calls = &response.messages[0].tool_calls;
+    assert_eq!(tool_calls.len(), 1);
+    assert_eq!(tool_calls[0].name, "get_weather");
+    assert!(tool_calls[0].id.starts_with("call_"));
+    let arguments: serde_json::Value = serde_json::from_str(&tool_calls[0].arguments).unwrap();
+    assert_eq!(arguments, serde_json::json!({ "city": "Tokyo" }));
+}
+
+#[tokio::test]
+async fn test_ollama_engine_chat_with_json_schema() {
+    let mock_server = MockServer::start().await;
+
+    // The schema must be forwarded through the native `format` field
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(serde_json::json!({
+            "format": { "type": "object", "required": ["total"] }
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "model": "llama3.1:latest",
+                "created_at": "2024-07-22T20:33:28.123648Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "{\"total\": 42}"
+                },
+                "done": true,
+                "prompt_eval_count": 10,
+                "eval_count": 5
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "ollama-test".to_string(),
+        model_id: "flm://ollama-test/llama3.1:latest".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Return the invoice total".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: Some(ResponseFormat::JsonSchema {
+            name: "invoice".to_string(),
+            schema: serde_json::json!({ "type": "object", "required": ["total"] }),
+            strict: true,
+        }),
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.chat(req).await.unwrap();
+    assert_eq!(response.messages[0].content, "{\"total\": 42}");
+}
+
+#[tokio::test]
+async fn test_ollama_engine_chat_with_sampling_params() {
+    let mock_server = MockServer::start().await;
+
+    // Sampling parameters are sent as Ollama options
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(serde_json::json!({
+            "options": { "seed": 42, "top_k": 40, "repeat_penalty": 1.5 }
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "model": "llama2:latest",
+                "created_at": "2023-08-04T19:22:45.499127Z",
+                "message": { "role": "assistant", "content": "Hello!" },
+                "done": true,
+                "prompt_eval_count": 10,
+                "eval_count": 5
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+
+    let mut req = ChatRequest {
+        engine_id: "ollama-test".to_string(),
+        model_id: "flm://ollama-test/llama2:latest".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Hello".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams {
+            seed: Some(42),
+            top_k: Some(40),
+            repeat_penalty: Some(1.5),
+            ..SamplingParams::default()
+        },
+    };
+
+    let response = engine.chat(req.clone()).await.unwrap();
+    assert_eq!(response.messages[0].content, "Hello!");
+
+    // Parameters Ollama cannot honour are reported rather than dropped
+    req.sampling.n = Some(2);
+    req.sampling.logprobs = true;
+    match engine.chat(req).await {
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. }) => {
+            assert!(reason.contains("n"));
+            assert!(reason.contains("logprobs"));
+        }
+        other => panic!("expected UnsupportedOperation, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_ollama_engine_complete() {
+    let mock_server = MockServer::start().await;
+
+    // Without a suffix the prompt is sent raw so no chat template is applied
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "llama2",
+            "prompt": "Once upon a time",
+            "raw": true,
+            "stream": false,
+            "options": { "stop": ["\n"] }
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "model": "llama2",
+                "created_at": "2023-08-04T19:22:45.499127Z",
+                "response": " there was a llama",
+                "done": true,
+                "done_reason": "length",
+                "prompt_eval_count": 4,
+                "eval_count": 5
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "ollama-test".to_string(),
+        model_id: "flm://ollama-test/llama2".to_string(),
+        prompt: "Once upon a time".to_string(),
+        suffix: None,
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec!["\n".to_string()],
+        best_of: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.complete(req).await.unwrap();
+    assert_eq!(response.choices.len(), 1);
+    assert_eq!(response.choices[0].text, " there was a llama");
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("length"));
+    assert_eq!(response.usage.prompt_tokens, 4);
+    assert_eq!(response.usage.completion_tokens, 5);
+}
+
+#[tokio::test]
+async fn test_ollama_engine_complete_rejects_best_of() {
+    let engine = OllamaEngine::new(
+        "ollama-test".to_string(),
+        "http://localhost:11434".to_string(),
+    )
+    .unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "ollama-test".to_string(),
+        model_id: "flm://ollama-test/llama2".to_string(),
+        prompt: "Once upon a time".to_string(),
+        suffix: None,
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        best_of: Some(2),
+        sampling: SamplingParams::default(),
+    };
+
+    let result = engine.complete(req).await;
+    assert!(matches!(
+        result,
+        Err(flm_core::error::EngineError::UnsupportedOperation { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_ollama_model_manager_pull_reports_progress() {
+    use flm_core::domain::engine::ModelPullRequest;
+    use tokio_stream::StreamExt;
+
+    let mock_server = MockServer::start().await;
+    let ndjson = concat!(
+        "{\"status\":\"pulling manifest\"}\n",
+        "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":100,\"completed\":40}\n",
+        "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":100,\"completed\":100}\n",
+        "{\"status\":\"success\"}\n"
+    );
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .and(body_partial_json(
+            serde_json::json!({"model": "llama3.2:3b", "stream": true}),
+        ))
+        .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(ndjson))
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+    let manager = engine.model_manager().expect("Ollama manages models");
+
+    let mut stream = manager
+        .pull(ModelPullRequest {
+            model: "llama3.2:3b".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        events.push(event.unwrap());
+    }
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[1].completed, Some(40));
+    assert_eq!(events[1].total, Some(100));
+    assert!(events.last().unwrap().is_success());
+}
+
+#[tokio::test]
+async fn test_ollama_model_manager_pull_surfaces_errors() {
+    use flm_core::domain::engine::ModelPullRequest;
+    use tokio_stream::StreamExt;
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(
+            "{\"status\":\"pulling manifest\"}\n{\"error\":\"pull model manifest: file does not exist\"}\n",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+    let mut stream = engine
+        .model_manager()
+        .unwrap()
+        .pull(ModelPullRequest {
+            model: "missing".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert!(stream.next().await.unwrap().is_ok());
+    match stream.next().await {
+        Some(Err(flm_core::error::EngineError::ApiError { reason, .. })) => {
+            assert!(reason.contains("file does not exist"));
+        }
+        other => panic!("expected ApiError, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_ollama_model_manager_show_delete_and_copy() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .and(body_partial_json(serde_json::json!({"model": "llama3.2"})))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "template": "{{ .Prompt }}",
+                "details": {
+                    "format": "gguf",
+                    "family": "llama",
+                    "parameter_size": "3.2B",
+                    "quantization_level": "Q4_K_M"
+                },
+                "model_info": {
+                    "general.architecture": "llama",
+                    "llama.context_length": 131072
+                }
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/delete"))
+        .and(body_partial_json(serde_json::json!({"model": "llama3.2"})))
+        .respond_with(ResponseTemplate::new(StatusCode::OK))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/copy"))
+        .and(body_partial_json(
+            serde_json::json!({"source": "llama3.2", "destination": "my-llama"}),
+        ))
+        .respond_with(ResponseTemplate::new(StatusCode::OK))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/delete"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::NOT_FOUND)
+                .set_body_json(serde_json::json!({"error": "model 'gone' not found"})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OllamaEngine::new("ollama-test".to_string(), mock_server.uri()).unwrap();
+    let manager = engine.model_manager().unwrap();
+
+    let details = manager.show("llama3.2").await.unwrap();
+    assert_eq!(details.family.as_deref(), Some("llama"));
+    assert_eq!(details.quantization.as_deref(), Some("Q4_K_M"));
+    assert_eq!(details.context_length, Some(131072));
+    assert_eq!(details.metadata["template"], "{{ .Prompt }}");
+
+    manager.delete("llama3.2").await.unwrap();
+    manager.copy("llama3.2", "my-llama").await.unwrap();
+    match manager.delete("gone").await {
+        Err(flm_core::error::EngineError::ApiError {
+            reason,
+            status_code,
+        }) => {
+            assert_eq!(status_code, Some(404));
+            assert_eq!(reason, "model 'gone' not found");
+        }
+        other => panic!("expected ApiError, got {other:?}"),
+    }
+}
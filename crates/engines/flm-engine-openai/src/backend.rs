@@ -0,0 +1,693 @@
+//! Shared request flow for OpenAI-compatible servers
+//!
+//! [`OpenAiBackend`] sends `/v1/chat/completions` (plain and SSE),
+//! `/v1/embeddings` and `/v1/models` requests for the generic engine and the
+//! vLLM, LM Studio and llama.cpp adapters. What differs between those servers
+//! is described by an [`OpenAiDialect`]: which sampling extensions they accept,
+//! how a JSON schema is passed and whether a native health endpoint exists.
+//! Authentication and TLS settings travel in the `reqwest::Client` the backend
+//! is built with.
+
+use crate::wire::{
+    chat_completion_stream, convert_logprobs_from_openai, convert_to_openai_message,
+    convert_tool_calls_from_openai, convert_tool_choice_to_openai, convert_tools_to_openai,
+    OpenAiChatResponse, OpenAiEmbeddingRequest, OpenAiEmbeddingResponse, OpenAiMessageRequest,
+    OpenAiModel, OpenAiModelsResponse, OpenAiStreamOptions,
+};
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, EmbeddingRequest, EmbeddingResponse,
+    EmbeddingVector, ResponseFormat, SamplingParams, UsageStats,
+};
+use flm_core::domain::engine::{HealthStatus, ModelInfo};
+use flm_core::domain::models::{EngineId, ModelCapabilities};
+use flm_core::error::EngineError;
+use flm_core::ports::ChatStream;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// Per-engine differences from the plain OpenAI API
+#[derive(Debug, Clone, Copy)]
+pub struct OpenAiDialect {
+    /// Server name used in error messages
+    pub name: &'static str,
+    /// Native health endpoint tried before `/v1/models` (e.g. vLLM's `/health`)
+    pub health_path: Option<&'static str>,
+    /// Whether `top_k` is accepted
+    pub top_k: bool,
+    /// Request field carrying `repeat_penalty`; `None` rejects the parameter
+    pub repeat_penalty: Option<RepeatPenaltyField>,
+    /// Whether `n > 1` is accepted
+    pub choices: MultipleChoices,
+    /// Whether `logit_bias` is accepted
+    pub logit_bias: bool,
+    /// Whether `logprobs` and `top_logprobs` are accepted
+    pub logprobs: bool,
+    /// How a JSON schema for structured output is passed
+    pub json_schema: JsonSchemaField,
+}
+
+impl OpenAiDialect {
+    /// The OpenAI API without vendor extensions
+    pub const OPENAI: Self = Self {
+        name: "OpenAI-compatible API",
+        health_path: None,
+        top_k: false,
+        repeat_penalty: None,
+        choices: MultipleChoices::NotStreamed,
+        logit_bias: true,
+        logprobs: true,
+        json_schema: JsonSchemaField::ResponseFormat,
+    };
+}
+
+/// Name of the repeat penalty extension field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatPenaltyField {
+    /// `repeat_penalty` (llama.cpp, LM Studio)
+    RepeatPenalty,
+    /// `repetition_penalty` (vLLM)
+    RepetitionPenalty,
+}
+
+/// Support for several choices per request (`n`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultipleChoices {
+    /// Accepted for plain requests, rejected for streaming
+    NotStreamed,
+    /// Rejected; the server always answers with one choice
+    Unsupported,
+}
+
+/// Where a `ResponseFormat::JsonSchema` goes in the chat request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonSchemaField {
+    /// OpenAI `response_format` of type `json_schema`
+    ResponseFormat,
+    /// Bare schema in a top-level vendor field (`guided_json`, `json_schema`)
+    Field(&'static str),
+}
+
+/// Client for one OpenAI-compatible server
+pub struct OpenAiBackend {
+    engine_id: EngineId,
+    base_url: String,
+    client: reqwest::Client,
+    dialect: OpenAiDialect,
+}
+
+impl OpenAiBackend {
+    /// Create a backend for the server at `base_url`
+    ///
+    /// `base_url` may include the `/v1` suffix (e.g. `https://api.example.com/v1`).
+    pub fn new(
+        engine_id: EngineId,
+        base_url: String,
+        client: reqwest::Client,
+        dialect: OpenAiDialect,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url).to_string();
+        Self {
+            engine_id,
+            base_url,
+            client,
+            dialect,
+        }
+    }
+
+    pub fn engine_id(&self) -> &EngineId {
+        &self.engine_id
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// URL of an OpenAI endpoint (`{base_url}/v1/{endpoint}`)
+    pub fn api_url(&self, endpoint: &str) -> String {
+        format!("{}/v1/{}", self.base_url, endpoint)
+    }
+
+    /// URL of a native (non-OpenAI) endpoint (`{base_url}/{endpoint}`)
+    pub fn native_url(&self, endpoint: &str) -> String {
+        format!("{}/{}", self.base_url, endpoint)
+    }
+
+    /// Extract the upstream model name from `flm://{engine_id}/{model_name}`
+    pub fn model_name<'a>(
+        &self,
+        engine_id: &str,
+        model_id: &'a str,
+    ) -> Result<&'a str, EngineError> {
+        if engine_id != self.engine_id {
+            return Err(EngineError::InvalidResponse {
+                reason: format!(
+                    "Engine ID mismatch: expected {}, got {engine_id}",
+                    self.engine_id
+                ),
+            });
+        }
+        model_id
+            .strip_prefix(&format!("flm://{}/", self.engine_id))
+            .ok_or_else(|| EngineError::InvalidResponse {
+                reason: format!("Invalid model ID: {model_id}"),
+            })
+    }
+
+    /// Reject sampling parameters the server cannot honour instead of silently dropping them
+    pub fn check_sampling(
+        &self,
+        sampling: &SamplingParams,
+        stream: bool,
+        operation: &str,
+    ) -> Result<(), EngineError> {
+        let dialect = &self.dialect;
+        let mut unsupported = Vec::new();
+        if !dialect.top_k && sampling.top_k.is_some() {
+            unsupported.push("top_k");
+        }
+        if dialect.repeat_penalty.is_none() && sampling.repeat_penalty.is_some() {
+            unsupported.push("repeat_penalty");
+        }
+        if sampling.n.is_some_and(|n| n > 1) {
+            match dialect.choices {
+                MultipleChoices::Unsupported => unsupported.push("n"),
+                MultipleChoices::NotStreamed if stream => unsupported.push("n (streaming)"),
+                MultipleChoices::NotStreamed => {}
+            }
+        }
+        if !dialect.logit_bias && !sampling.logit_bias.is_empty() {
+            unsupported.push("logit_bias");
+        }
+        if !dialect.logprobs && (sampling.logprobs || sampling.top_logprobs.is_some()) {
+            unsupported.push("logprobs");
+        }
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(EngineError::UnsupportedOperation {
+                operation: operation.to_string(),
+                reason: format!(
+                    "{} does not support sampling parameter(s): {}",
+                    dialect.name,
+                    unsupported.join(", ")
+                ),
+            })
+        }
+    }
+
+    /// Sampling fields in the names this server expects
+    pub fn sampling_options(&self, sampling: &SamplingParams) -> OpenAiSamplingOptions {
+        let dialect = &self.dialect;
+        let repeat_penalty = |field| {
+            (dialect.repeat_penalty == Some(field))
+                .then_some(sampling.repeat_penalty)
+                .flatten()
+        };
+        OpenAiSamplingOptions {
+            top_p: sampling.top_p,
+            top_k: sampling.top_k.filter(|_| dialect.top_k),
+            seed: sampling.seed,
+            presence_penalty: sampling.presence_penalty,
+            frequency_penalty: sampling.frequency_penalty,
+            repeat_penalty: repeat_penalty(RepeatPenaltyField::RepeatPenalty),
+            repetition_penalty: repeat_penalty(RepeatPenaltyField::RepetitionPenalty),
+            n: sampling
+                .n
+                .filter(|_| dialect.choices != MultipleChoices::Unsupported),
+            logit_bias: if dialect.logit_bias {
+                sampling.logit_bias.clone()
+            } else {
+                BTreeMap::new()
+            },
+            logprobs: dialect.logprobs && sampling.logprobs,
+            top_logprobs: sampling.top_logprobs.filter(|_| dialect.logprobs),
+        }
+    }
+
+    pub async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<T, EngineError> {
+        let response =
+            self.client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| EngineError::NetworkError {
+                    reason: format!("Request failed: {e}"),
+                })?;
+        parse_json_response(response).await
+    }
+
+    pub async fn post_json<B: Serialize, T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<T, EngineError> {
+        let response = self.client.post(url).json(body).send().await.map_err(|e| {
+            EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            }
+        })?;
+        parse_json_response(response).await
+    }
+
+    /// Probe the dialect's health endpoint, falling back to `/v1/models`
+    pub async fn health_check(&self) -> Result<HealthStatus, EngineError> {
+        let start = Instant::now();
+        let native = match self.dialect.health_path {
+            Some(path) => match self.client.get(self.native_url(path)).send().await {
+                Ok(resp) if resp.status().is_success() => Some(resp),
+                _ => None,
+            },
+            None => None,
+        };
+        let response = match native {
+            Some(resp) => resp,
+            None => self
+                .client
+                .get(self.api_url("models"))
+                .send()
+                .await
+                .map_err(|e| EngineError::NetworkError {
+                    reason: format!("Request failed: {e}"),
+                })?,
+        };
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        if response.status().is_success() {
+            if latency_ms < 1500 {
+                Ok(HealthStatus::Healthy { latency_ms })
+            } else {
+                Ok(HealthStatus::Degraded {
+                    latency_ms,
+                    reason: "High latency".to_string(),
+                })
+            }
+        } else {
+            Ok(HealthStatus::Unreachable {
+                reason: format!("HTTP {}", response.status().as_u16()),
+            })
+        }
+    }
+
+    /// Models reported by `/v1/models`
+    pub async fn models(&self) -> Result<Vec<OpenAiModel>, EngineError> {
+        let response: OpenAiModelsResponse = self.get_json(&self.api_url("models")).await?;
+        Ok(response.data)
+    }
+
+    pub fn model_info(
+        &self,
+        model: &OpenAiModel,
+        context_length: Option<u32>,
+        capabilities: Option<ModelCapabilities>,
+    ) -> ModelInfo {
+        ModelInfo {
+            engine_id: self.engine_id.clone(),
+            model_id: format!("flm://{}/{}", self.engine_id, model.id),
+            display_name: model.id.clone(),
+            context_length,
+            supports_streaming: true,
+            supports_embeddings: true,
+            capabilities,
+        }
+    }
+
+    /// List `/v1/models`, asking `capabilities` about each model
+    ///
+    /// The context length is taken from `max_model_len` when the server reports it.
+    pub async fn list_models(
+        &self,
+        capabilities: impl Fn(&OpenAiModel) -> Option<ModelCapabilities>,
+    ) -> Result<Vec<ModelInfo>, EngineError> {
+        Ok(self
+            .models()
+            .await?
+            .iter()
+            .map(|model| self.model_info(model, model.max_model_len, capabilities(model)))
+            .collect())
+    }
+
+    pub async fn chat(&self, req: ChatRequest) -> Result<ChatResponse, EngineError> {
+        let model = self.model_name(&req.engine_id, &req.model_id)?.to_string();
+        self.check_sampling(&req.sampling, false, "chat")?;
+        let logprobs_requested = req.sampling.logprobs;
+        let openai_req = self.chat_request(&model, req, false);
+
+        let response: OpenAiChatResponse = self
+            .post_json(&self.api_url("chat/completions"), &openai_req)
+            .await?;
+
+        if response.choices.is_empty() {
+            return Err(EngineError::InvalidResponse {
+                reason: "No choices in response".to_string(),
+            });
+        }
+
+        // One assistant message per choice (more than one when `n > 1`)
+        let messages = response
+            .choices
+            .iter()
+            .map(|choice| ChatMessage {
+                role: ChatRole::Assistant,
+                content: choice.message.content.clone().unwrap_or_default(),
+                attachments: Vec::new(),
+                tool_calls: convert_tool_calls_from_openai(&choice.message.tool_calls),
+                tool_call_id: None,
+            })
+            .collect();
+        let logprobs = if logprobs_requested {
+            response
+                .choices
+                .iter()
+                .map(|choice| convert_logprobs_from_openai(&choice.logprobs))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(ChatResponse {
+            usage: response.usage.unwrap_or(UsageStats {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            }),
+            messages,
+            audio: Vec::new(),
+            logprobs,
+        })
+    }
+
+    /// Start a streaming chat; the request is sent when the stream is first polled
+    pub fn chat_stream(&self, req: ChatRequest) -> Result<ChatStream, EngineError> {
+        let model = self.model_name(&req.engine_id, &req.model_id)?.to_string();
+        self.check_sampling(&req.sampling, true, "chat")?;
+        let openai_req = self.chat_request(&model, req, true);
+
+        Ok(chat_completion_stream(
+            self.client.clone(),
+            self.api_url("chat/completions"),
+            openai_req,
+        ))
+    }
+
+    pub async fn embeddings(
+        &self,
+        req: EmbeddingRequest,
+    ) -> Result<EmbeddingResponse, EngineError> {
+        let model = self.model_name(&req.engine_id, &req.model_id)?;
+        let openai_req = OpenAiEmbeddingRequest {
+            model: model.to_string(),
+            input: req.input,
+        };
+
+        let response: OpenAiEmbeddingResponse = self
+            .post_json(&self.api_url("embeddings"), &openai_req)
+            .await?;
+
+        Ok(EmbeddingResponse {
+            usage: response.usage.unwrap_or(UsageStats {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            }),
+            vectors: response
+                .data
+                .into_iter()
+                .enumerate()
+                .map(|(idx, item)| EmbeddingVector {
+                    index: idx,
+                    values: item.embedding,
+                })
+                .collect(),
+        })
+    }
+
+    fn chat_request(&self, model: &str, req: ChatRequest, stream: bool) -> OpenAiChatRequest {
+        let (response_format, schema_field) = self.response_format(req.response_format.as_ref());
+        OpenAiChatRequest {
+            model: model.to_string(),
+            messages: req
+                .messages
+                .into_iter()
+                .map(convert_to_openai_message)
+                .collect(),
+            stream,
+            stream_options: OpenAiStreamOptions::for_stream(stream),
+            temperature: req.temperature.map(|t| t as f64),
+            max_tokens: req.max_tokens,
+            stop: req.stop,
+            sampling: self.sampling_options(&req.sampling),
+            tools: convert_tools_to_openai(&req.tools),
+            tool_choice: req.tool_choice.as_ref().map(convert_tool_choice_to_openai),
+            response_format,
+            schema_field,
+        }
+    }
+
+    /// Map the requested output format to `response_format` or the dialect's schema field
+    fn response_format(
+        &self,
+        format: Option<&ResponseFormat>,
+    ) -> (Option<Value>, BTreeMap<&'static str, Value>) {
+        match (format, self.dialect.json_schema) {
+            (Some(ResponseFormat::JsonObject), _) => (
+                Some(serde_json::json!({ "type": "json_object" })),
+                BTreeMap::new(),
+            ),
+            (
+                Some(ResponseFormat::JsonSchema {
+                    name,
+                    schema,
+                    strict,
+                }),
+                JsonSchemaField::ResponseFormat,
+            ) => (
+                Some(serde_json::json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": name,
+                        "schema": schema,
+                        "strict": strict
+                    }
+                })),
+                BTreeMap::new(),
+            ),
+            (Some(ResponseFormat::JsonSchema { schema, .. }), JsonSchemaField::Field(field)) => {
+                (None, BTreeMap::from([(field, schema.clone())]))
+            }
+            (Some(ResponseFormat::Text) | None, _) => (None, BTreeMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OpenAiChatRequest {
+    model: String,
+    messages: Vec<OpenAiMessageRequest>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAiStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(flatten)]
+    sampling: OpenAiSamplingOptions,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+    /// Vendor field holding a bare JSON schema (see [`JsonSchemaField::Field`])
+    #[serde(flatten)]
+    schema_field: BTreeMap<&'static str, Value>,
+}
+
+/// Sampling options flattened into chat and completion requests
+///
+/// Built by [`OpenAiBackend::sampling_options`], which leaves out the fields
+/// the dialect does not accept.
+#[derive(Serialize)]
+pub struct OpenAiSamplingOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repeat_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repetition_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    logit_bias: BTreeMap<String, f32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    logprobs: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u32>,
+}
+
+/// Parse a JSON body, reporting non-2xx statuses as `ApiError` with the upstream message
+async fn parse_json_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, EngineError> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(EngineError::ApiError {
+            reason: format!(
+                "HTTP {}: {}",
+                status.as_u16(),
+                upstream_error_message(&body)
+            ),
+            status_code: Some(status.as_u16()),
+        });
+    }
+
+    response.json().await.map_err(|e| EngineError::ApiError {
+        reason: format!("Failed to parse JSON: {e}"),
+        status_code: None,
+    })
+}
+
+/// Pull `error.message` out of an OpenAI-style error body, falling back to the raw body
+fn upstream_error_message(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend(dialect: OpenAiDialect) -> OpenAiBackend {
+        OpenAiBackend::new(
+            "remote".into(),
+            "http://localhost:1".into(),
+            reqwest::Client::new(),
+            dialect,
+        )
+    }
+
+    fn schema_format() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            name: "answer".to_string(),
+            schema: serde_json::json!({"type": "object"}),
+            strict: true,
+        }
+    }
+
+    #[test]
+    fn test_base_url_accepts_v1_suffix() {
+        let client = reqwest::Client::new();
+        for base in [
+            "https://api.example.com",
+            "https://api.example.com/",
+            "https://api.example.com/v1",
+            "https://api.example.com/v1/",
+        ] {
+            let backend = OpenAiBackend::new(
+                "remote".into(),
+                base.into(),
+                client.clone(),
+                OpenAiDialect::OPENAI,
+            );
+            assert_eq!(
+                backend.api_url("models"),
+                "https://api.example.com/v1/models"
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_schema_response_format() {
+        let backend = backend(OpenAiDialect::OPENAI);
+        let (value, field) = backend.response_format(Some(&schema_format()));
+        let value = value.unwrap();
+        assert_eq!(value["type"], "json_schema");
+        assert_eq!(value["json_schema"]["name"], "answer");
+        assert_eq!(value["json_schema"]["strict"], true);
+        assert!(field.is_empty());
+        assert!(backend
+            .response_format(Some(&ResponseFormat::Text))
+            .0
+            .is_none());
+    }
+
+    #[test]
+    fn test_json_schema_vendor_field() {
+        let backend = backend(OpenAiDialect {
+            json_schema: JsonSchemaField::Field("guided_json"),
+            ..OpenAiDialect::OPENAI
+        });
+        let (value, field) = backend.response_format(Some(&schema_format()));
+        assert!(value.is_none());
+        assert_eq!(field["guided_json"], serde_json::json!({"type": "object"}));
+    }
+
+    #[test]
+    fn test_vendor_sampling_parameters_rejected() {
+        let backend = backend(OpenAiDialect::OPENAI);
+        let sampling = SamplingParams {
+            top_k: Some(40),
+            ..Default::default()
+        };
+        assert!(backend.check_sampling(&sampling, false, "chat").is_err());
+        assert!(backend
+            .check_sampling(&SamplingParams::default(), true, "chat")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sampling_options_follow_dialect() {
+        let sampling = SamplingParams {
+            top_k: Some(40),
+            repeat_penalty: Some(1.1),
+            n: Some(1),
+            ..Default::default()
+        };
+        let vllm = backend(OpenAiDialect {
+            top_k: true,
+            repeat_penalty: Some(RepeatPenaltyField::RepetitionPenalty),
+            ..OpenAiDialect::OPENAI
+        });
+        let value = serde_json::to_value(vllm.sampling_options(&sampling)).unwrap();
+        assert_eq!(value["top_k"], 40);
+        assert!(value["repetition_penalty"].is_number());
+        assert!(value.get("repeat_penalty").is_none());
+        assert_eq!(value["n"], 1);
+
+        let single = backend(OpenAiDialect {
+            choices: MultipleChoices::Unsupported,
+            ..OpenAiDialect::OPENAI
+        });
+        let value = serde_json::to_value(single.sampling_options(&sampling)).unwrap();
+        assert!(value.get("top_k").is_none());
+        assert!(value.get("n").is_none());
+    }
+
+    #[test]
+    fn test_upstream_error_message() {
+        assert_eq!(
+            upstream_error_message(r#"{"error":{"message":"Invalid API key"}}"#),
+            "Invalid API key"
+        );
+        assert_eq!(upstream_error_message("Bad Gateway"), "Bad Gateway");
+    }
+}
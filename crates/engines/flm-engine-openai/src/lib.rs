@@ -0,0 +1,165 @@
+//! Generic OpenAI-compatible engine adapter
+//!
+//! This crate implements the `LlmEngine` trait for any server exposing
+//! `/v1/chat/completions`, `/v1/embeddings` and `/v1/models` (TGI, SGLang,
+//! LocalAI, Jan, koboldcpp, hosted APIs). Nothing is assumed about the server
+//! beyond the OpenAI contract: model capabilities come from per-model
+//! overrides instead of name heuristics, and vendor-specific sampling
+//! parameters are rejected.
+//!
+//! The request flow itself lives in [`backend`] and the wire types in
+//! [`wire`]; the vLLM, LM Studio and llama.cpp adapters build on both with
+//! their own [`backend::OpenAiDialect`].
+
+pub mod backend;
+pub mod wire;
+
+use async_trait::async_trait;
+use backend::{OpenAiBackend, OpenAiDialect};
+use flm_core::domain::chat::{ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse};
+use flm_core::domain::engine::{HealthStatus, ModelInfo};
+use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities};
+use flm_core::error::EngineError;
+use flm_core::ports::{ChatStream, LlmEngine};
+use std::collections::BTreeMap;
+
+/// Default request timeout for engines created with `new`
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Capability override key that applies to every model without its own entry
+pub const ALL_MODELS: &str = "*";
+
+/// Generic OpenAI-compatible engine implementation
+pub struct OpenAiCompatibleEngine {
+    backend: OpenAiBackend,
+    model_capabilities: BTreeMap<String, ModelCapabilities>,
+}
+
+impl OpenAiCompatibleEngine {
+    /// Create a new OpenAiCompatibleEngine instance
+    pub fn new(engine_id: EngineId, base_url: String) -> Result<Self, EngineError> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Failed to create HTTP client: {e}"),
+            })?;
+
+        Ok(Self::with_client(engine_id, base_url, client))
+    }
+
+    /// Create an OpenAiCompatibleEngine that uses a preconfigured HTTP client
+    ///
+    /// `base_url` may include the `/v1` suffix (e.g. `https://api.example.com/v1`).
+    pub fn with_client(engine_id: EngineId, base_url: String, client: reqwest::Client) -> Self {
+        Self {
+            backend: OpenAiBackend::new(engine_id, base_url, client, OpenAiDialect::OPENAI),
+            model_capabilities: BTreeMap::new(),
+        }
+    }
+
+    /// Set per-model capabilities, keyed by upstream model name
+    ///
+    /// The [`ALL_MODELS`] key applies to models without their own entry.
+    /// Models without any matching entry report no per-model capabilities.
+    pub fn with_model_capabilities(
+        mut self,
+        model_capabilities: BTreeMap<String, ModelCapabilities>,
+    ) -> Self {
+        self.model_capabilities = model_capabilities;
+        self
+    }
+
+    fn model_capabilities_for(&self, model: &str) -> Option<ModelCapabilities> {
+        self.model_capabilities
+            .get(model)
+            .or_else(|| self.model_capabilities.get(ALL_MODELS))
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl LlmEngine for OpenAiCompatibleEngine {
+    fn id(&self) -> EngineId {
+        self.backend.engine_id().clone()
+    }
+
+    fn kind(&self) -> EngineKind {
+        EngineKind::OpenAiCompatible
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        // Multimodal input is only advertised when some model is configured for it
+        let any = |f: fn(&ModelCapabilities) -> bool| self.model_capabilities.values().any(f);
+        EngineCapabilities {
+            chat: true,
+            chat_stream: true,
+            embeddings: true,
+            moderation: false,
+            tools: true,
+            reasoning: any(|c| c.reasoning),
+            vision_inputs: any(|c| c.vision),
+            audio_inputs: any(|c| c.audio_inputs),
+            audio_outputs: false,
+            image_generation: false,
+            max_image_bytes: None,
+            max_audio_bytes: None,
+        }
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, EngineError> {
+        // `/v1/models` is the only endpoint every compatible server is expected to have
+        self.backend.health_check().await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, EngineError> {
+        self.backend
+            .list_models(|model| self.model_capabilities_for(&model.id))
+            .await
+    }
+
+    async fn chat(&self, req: ChatRequest) -> Result<ChatResponse, EngineError> {
+        self.backend.chat(req).await
+    }
+
+    async fn chat_stream(&self, req: ChatRequest) -> Result<ChatStream, EngineError> {
+        self.backend.chat_stream(req)
+    }
+
+    async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
+        self.backend.embeddings(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(vision: bool) -> ModelCapabilities {
+        ModelCapabilities {
+            vision,
+            tools: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_model_capability_overrides() {
+        let engine = OpenAiCompatibleEngine::new("remote".into(), "http://localhost:1".into())
+            .unwrap()
+            .with_model_capabilities(BTreeMap::from([
+                ("gpt-4o".to_string(), caps(true)),
+                (ALL_MODELS.to_string(), caps(false)),
+            ]));
+
+        assert_eq!(engine.model_capabilities_for("gpt-4o"), Some(caps(true)));
+        assert_eq!(engine.model_capabilities_for("other"), Some(caps(false)));
+        assert!(engine.capabilities().vision_inputs);
+        assert!(!engine.capabilities().audio_inputs);
+
+        let plain =
+            OpenAiCompatibleEngine::new("remote".into(), "http://localhost:1".into()).unwrap();
+        assert_eq!(plain.model_capabilities_for("gpt-4o"), None);
+        assert!(!plain.capabilities().vision_inputs);
+    }
+}
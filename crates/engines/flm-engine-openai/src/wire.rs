@@ -0,0 +1,465 @@
+//! OpenAI wire format shared by the OpenAI-compatible adapters
+//!
+//! vLLM, LM Studio, llama.cpp and the generic OpenAI-compatible engine all
+//! speak `/v1/chat/completions`, `/v1/embeddings` and `/v1/models`. Message
+//! conversion, response types and SSE chunk parsing live here; the request
+//! flow built on them is in [`crate::backend`].
+
+use base64::{engine::general_purpose, Engine as _};
+use flm_core::domain::chat::{
+    ChatMessage, ChatRole, ChatStreamChunk, MultimodalAttachmentKind, TokenLogprob, ToolCall,
+    ToolCallDelta, ToolChoice, ToolDefinition, TopLogprob, UsageStats,
+};
+use flm_core::error::EngineError;
+use flm_core::ports::ChatStream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_stream::StreamExt;
+
+#[derive(Serialize)]
+pub struct OpenAiMessageRequest {
+    pub role: String,
+    pub content: Value, // String or array of content objects
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Convert ChatMessage (OpenAI-compatible format) to OpenAiMessageRequest format
+///
+/// This function converts the abstract ChatRequest format to OpenAI's API format.
+/// Image attachments are converted to OpenAI's `image_url` format.
+/// Audio attachments are converted to OpenAI's `input_audio` format.
+pub fn convert_to_openai_message(msg: ChatMessage) -> OpenAiMessageRequest {
+    let role = match msg.role {
+        ChatRole::User => "user".to_string(),
+        ChatRole::Assistant => "assistant".to_string(),
+        ChatRole::System => "system".to_string(),
+        ChatRole::Tool => "tool".to_string(),
+    };
+
+    let tool_calls: Vec<Value> = msg
+        .tool_calls
+        .iter()
+        .map(|call| {
+            serde_json::json!({
+                "id": call.id,
+                "type": "function",
+                "function": {
+                    "name": call.name,
+                    "arguments": call.arguments
+                }
+            })
+        })
+        .collect();
+
+    // If there are no attachments, use simple string content
+    if msg.attachments.is_empty() {
+        // Assistant tool-call messages carry null content when there is no text
+        let content = if msg.content.is_empty() && !tool_calls.is_empty() {
+            Value::Null
+        } else {
+            Value::String(msg.content)
+        };
+        return OpenAiMessageRequest {
+            role,
+            content,
+            tool_calls,
+            tool_call_id: msg.tool_call_id,
+        };
+    }
+
+    // Build content array with text and attachments
+    let mut content_parts: Vec<Value> = Vec::new();
+
+    // Add text content if not empty
+    if !msg.content.is_empty() {
+        content_parts.push(serde_json::json!({
+            "type": "text",
+            "text": msg.content
+        }));
+    }
+
+    // Add image attachments
+    for att in &msg.attachments {
+        if matches!(att.kind, MultimodalAttachmentKind::InputImage) {
+            let base64_data = general_purpose::STANDARD.encode(&att.data);
+            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
+            content_parts.push(serde_json::json!({
+                "type": "image_url",
+                "image_url": {
+                    "url": data_url
+                }
+            }));
+        } else if matches!(att.kind, MultimodalAttachmentKind::InputAudio) {
+            let base64_data = general_purpose::STANDARD.encode(&att.data);
+            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
+            content_parts.push(serde_json::json!({
+                "type": "input_audio",
+                "audio_url": {
+                    "url": data_url
+                }
+            }));
+        }
+    }
+
+    OpenAiMessageRequest {
+        role,
+        content: Value::Array(content_parts),
+        tool_calls,
+        tool_call_id: msg.tool_call_id,
+    }
+}
+
+/// Convert tool definitions to OpenAI's `tools` array
+pub fn convert_tools_to_openai(tools: &[ToolDefinition]) -> Vec<Value> {
+    tools
+        .iter()
+        .map(|tool| {
+            let mut function = serde_json::json!({ "name": tool.name });
+            if let Some(description) = &tool.description {
+                function["description"] = Value::String(description.clone());
+            }
+            if let Some(parameters) = &tool.parameters {
+                function["parameters"] = parameters.clone();
+            }
+            serde_json::json!({
+                "type": "function",
+                "function": function
+            })
+        })
+        .collect()
+}
+
+/// Convert a tool choice to OpenAI's `tool_choice` value
+pub fn convert_tool_choice_to_openai(choice: &ToolChoice) -> Value {
+    match choice {
+        ToolChoice::Auto => Value::String("auto".to_string()),
+        ToolChoice::None => Value::String("none".to_string()),
+        ToolChoice::Required => Value::String("required".to_string()),
+        ToolChoice::Function { name } => serde_json::json!({
+            "type": "function",
+            "function": { "name": name }
+        }),
+    }
+}
+
+/// Convert OpenAI `logprobs.content` entries
+pub fn convert_logprobs_from_openai(logprobs: &Option<OpenAiLogprobs>) -> Vec<TokenLogprob> {
+    logprobs
+        .iter()
+        .flat_map(|l| l.content.iter().flatten())
+        .map(|entry| TokenLogprob {
+            token: entry.token.clone(),
+            logprob: entry.logprob,
+            top_logprobs: entry
+                .top_logprobs
+                .iter()
+                .map(|top| TopLogprob {
+                    token: top.token.clone(),
+                    logprob: top.logprob,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Convert complete tool calls from a non-streaming response
+pub fn convert_tool_calls_from_openai(calls: &Option<Vec<OpenAiToolCall>>) -> Vec<ToolCall> {
+    calls
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(idx, call)| ToolCall {
+            id: call.id.clone().unwrap_or_else(|| format!("call_{idx}")),
+            name: call.function.name.clone().unwrap_or_default(),
+            arguments: call
+                .function
+                .arguments
+                .clone()
+                .unwrap_or_else(|| "{}".to_string()),
+        })
+        .collect()
+}
+
+/// Convert tool call fragments from a streaming chunk
+pub fn convert_tool_call_deltas_from_openai(
+    calls: &Option<Vec<OpenAiToolCall>>,
+) -> Vec<ToolCallDelta> {
+    calls
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(position, call)| ToolCallDelta {
+            index: call.index.unwrap_or(position as u32),
+            id: call.id.clone(),
+            name: call.function.name.clone(),
+            arguments: call.function.arguments.clone(),
+        })
+        .collect()
+}
+
+/// `stream_options` of a streaming request
+///
+/// `include_usage` makes the server send token usage on a trailing chunk with
+/// no choices, after the chunk carrying `finish_reason`.
+#[derive(Serialize)]
+pub struct OpenAiStreamOptions {
+    pub include_usage: bool,
+}
+
+impl OpenAiStreamOptions {
+    /// Request usage reporting when `stream` is set
+    pub fn for_stream(stream: bool) -> Option<Self> {
+        stream.then_some(Self {
+            include_usage: true,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiMessageResponse {
+    #[serde(default)]
+    pub role: Option<String>,
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<OpenAiToolCall>>,
+}
+
+/// Tool call as returned by OpenAI-compatible servers (complete or streamed fragment)
+#[derive(Deserialize)]
+pub struct OpenAiToolCall {
+    #[serde(default)]
+    pub index: Option<u32>,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: OpenAiFunctionCall,
+}
+
+#[derive(Deserialize, Default)]
+pub struct OpenAiFunctionCall {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiChatResponse {
+    pub choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    pub usage: Option<UsageStats>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiChoice {
+    pub message: OpenAiMessageResponse,
+    #[serde(default)]
+    pub logprobs: Option<OpenAiLogprobs>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiChatChunk {
+    #[serde(default)]
+    pub choices: Vec<OpenAiChunkChoice>,
+    #[serde(default)]
+    pub usage: Option<UsageStats>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiChunkChoice {
+    pub delta: OpenAiMessageResponse,
+    #[serde(default)]
+    pub logprobs: Option<OpenAiLogprobs>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiLogprobs {
+    #[serde(default)]
+    pub content: Option<Vec<OpenAiTokenLogprob>>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiTokenLogprob {
+    pub token: String,
+    pub logprob: f32,
+    #[serde(default)]
+    pub top_logprobs: Vec<OpenAiTopLogprob>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiTopLogprob {
+    pub token: String,
+    pub logprob: f32,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiModelsResponse {
+    pub data: Vec<OpenAiModel>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiModel {
+    pub id: String,
+    /// Context window reported by vLLM (not part of the OpenAI schema)
+    #[serde(default)]
+    pub max_model_len: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct OpenAiEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiEmbeddingResponse {
+    pub data: Vec<OpenAiEmbeddingData>,
+    #[serde(default)]
+    pub usage: Option<UsageStats>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAiEmbeddingData {
+    pub embedding: Vec<f32>,
+}
+
+/// Send a streaming `/chat/completions` request and parse the SSE response
+///
+/// The request is sent when the stream is first polled. A non-2xx status is
+/// reported as an `ApiError` carrying the status code. When the finishing
+/// chunk has no usage, it is held back until the trailing usage chunk (see
+/// [`OpenAiStreamOptions`]), `[DONE]` or the end of the response.
+pub fn chat_completion_stream<T>(client: reqwest::Client, url: String, body: T) -> ChatStream
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    let stream = async_stream::stream! {
+        let response = client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            yield Err(EngineError::ApiError {
+                reason: format!("Chat request failed: {body}"),
+                status_code: Some(status.as_u16()),
+            });
+            return;
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut is_done = false;
+        // Finishing chunk waiting for the trailing usage chunk
+        let mut pending: Option<ChatStreamChunk> = None;
+
+        while let Some(chunk_result) = stream.next().await {
+            let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
+                reason: format!("Stream error: {e}"),
+            })?;
+
+            // Parse SSE format
+            let text = String::from_utf8_lossy(&bytes);
+            for line in text.lines() {
+                if let Some(json_str) = line.strip_prefix("data: ") {
+                    if json_str == "[DONE]" {
+                        is_done = true;
+                        break;
+                    }
+
+                    match serde_json::from_str::<OpenAiChatChunk>(json_str) {
+                        Ok(chunk) if chunk.choices.is_empty() => {
+                            // Usage-only chunk sent after the finishing chunk
+                            if let Some(usage) = chunk.usage {
+                                let mut last = pending.take().unwrap_or_else(|| ChatStreamChunk {
+                                    delta: ChatMessage {
+                                        role: ChatRole::Assistant,
+                                        content: String::new(),
+                                        attachments: Vec::new(),
+                                        tool_calls: Vec::new(),
+                                        tool_call_id: None,
+                                    },
+                                    usage: None,
+                                    is_done: true,
+                                    audio: Vec::new(),
+                                    tool_calls: Vec::new(),
+                                    logprobs: Vec::new(),
+                                });
+                                last.usage = Some(usage);
+                                yield Ok(last);
+                                is_done = true;
+                                break;
+                            }
+                        }
+                        Ok(chunk) => {
+                            if let Some(delta) = chunk.choices.first() {
+                                let delta_content = delta.delta.content.clone().unwrap_or_default();
+                                let tool_call_deltas =
+                                    convert_tool_call_deltas_from_openai(&delta.delta.tool_calls);
+                                let logprobs = convert_logprobs_from_openai(&delta.logprobs);
+                                let finished = delta.finish_reason.is_some();
+
+                                if !delta_content.is_empty()
+                                    || !tool_call_deltas.is_empty()
+                                    || !logprobs.is_empty()
+                                    || finished
+                                {
+                                    let out = ChatStreamChunk {
+                                        delta: ChatMessage {
+                                            role: ChatRole::Assistant,
+                                            content: delta_content,
+                                            attachments: Vec::new(),
+                                            tool_calls: Vec::new(),
+                                            tool_call_id: None,
+                                        },
+                                        usage: if finished { chunk.usage.clone() } else { None },
+                                        is_done: finished,
+                                        audio: Vec::new(),
+                                        tool_calls: tool_call_deltas,
+                                        logprobs,
+                                    };
+                                    if finished && out.usage.is_none() {
+                                        pending = Some(out);
+                                    } else {
+                                        yield Ok(out);
+                                        if finished {
+                                            is_done = true;
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            yield Err(EngineError::InvalidResponse {
+                                reason: format!("Failed to parse chunk: {e}"),
+                            });
+                        }
+                    }
+                }
+            }
+
+            if is_done {
+                break;
+            }
+        }
+
+        if let Some(last) = pending {
+            yield Ok(last);
+        }
+    };
+
+    Box::pin(stream)
+}
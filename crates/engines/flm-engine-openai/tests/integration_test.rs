@@ -0,0 +1,251 @@
+//! Integration tests for the OpenAI-compatible engine adapter
+//!
+//! These tests verify that the adapter works correctly with mock HTTP servers.
+
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatRole, EmbeddingRequest, SamplingParams,
+};
+use flm_core::domain::models::{EngineKind, ModelCapabilities};
+use flm_core::error::EngineError;
+use flm_core::ports::LlmEngine;
+use flm_engine_openai::{OpenAiCompatibleEngine, ALL_MODELS};
+use futures::StreamExt;
+use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn chat_request(stream: bool) -> ChatRequest {
+    ChatRequest {
+        engine_id: "remote".to_string(),
+        model_id: "flm://remote/gpt-4o-mini".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Hello".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
+    }
+}
+
+fn bearer_client(token: &str) -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+    );
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_openai_engine_id() {
+    let mock_server = MockServer::start().await;
+    let engine = OpenAiCompatibleEngine::new("remote".to_string(), mock_server.uri()).unwrap();
+
+    assert_eq!(engine.id(), "remote".to_string());
+    assert_eq!(engine.kind(), EngineKind::OpenAiCompatible);
+}
+
+#[tokio::test]
+async fn test_openai_engine_list_models_applies_overrides() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [
+                    {"id": "gpt-4o-mini", "object": "model", "owned_by": "system"},
+                    {"id": "text-embedding-3-small", "object": "model", "owned_by": "system"}
+                ]
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let mut overrides = BTreeMap::new();
+    overrides.insert(
+        "gpt-4o-mini".to_string(),
+        ModelCapabilities {
+            tools: true,
+            vision: true,
+            ..Default::default()
+        },
+    );
+    overrides.insert(ALL_MODELS.to_string(), ModelCapabilities::default());
+    let engine = OpenAiCompatibleEngine::new("remote".to_string(), mock_server.uri())
+        .unwrap()
+        .with_model_capabilities(overrides);
+
+    let models = engine.list_models().await.unwrap();
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].model_id, "flm://remote/gpt-4o-mini");
+    let caps = models[0].capabilities.as_ref().unwrap();
+    assert!(caps.tools && caps.vision && !caps.reasoning);
+    let caps = models[1].capabilities.as_ref().unwrap();
+    assert!(!caps.tools && !caps.vision);
+    assert!(engine.capabilities().vision_inputs);
+}
+
+#[tokio::test]
+async fn test_openai_engine_chat_sends_bearer_token() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("authorization", "Bearer sk-test"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "gpt-4o-mini",
+            "stream": false
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "gpt-4o-mini",
+                "choices": [
+                    {
+                        "index": 0,
+                        "message": {"role": "assistant", "content": "Hello!"},
+                        "finish_reason": "stop"
+                    }
+                ],
+                "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    // The `/v1` suffix of hosted API URLs is accepted
+    let engine = OpenAiCompatibleEngine::with_client(
+        "remote".to_string(),
+        format!("{}/v1", mock_server.uri()),
+        bearer_client("sk-test"),
+    );
+
+    let response = engine.chat(chat_request(false)).await.unwrap();
+    assert_eq!(response.messages.len(), 1);
+    assert_eq!(response.messages[0].content, "Hello!");
+    assert_eq!(response.usage.total_tokens, 2);
+}
+
+#[tokio::test]
+async fn test_openai_engine_chat_stream() {
+    let mock_server = MockServer::start().await;
+
+    let sse = concat!(
+        "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"finish_reason\":null}]}\n\n",
+        "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: [DONE]\n\n"
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({"stream": true})))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(sse),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OpenAiCompatibleEngine::new("remote".to_string(), mock_server.uri()).unwrap();
+
+    let mut stream = engine.chat_stream(chat_request(true)).await.unwrap();
+    let mut content = String::new();
+    let mut done = false;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        content.push_str(&chunk.delta.content);
+        done |= chunk.is_done;
+    }
+    assert_eq!(content, "Hello");
+    assert!(done);
+}
+
+#[tokio::test]
+async fn test_openai_engine_embeddings() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "text-embedding-3-small",
+            "input": ["Hello"]
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{"object": "embedding", "index": 0, "embedding": [0.1, 0.2, 0.3]}],
+                "model": "text-embedding-3-small",
+                "usage": {"prompt_tokens": 1, "total_tokens": 1}
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OpenAiCompatibleEngine::new("remote".to_string(), mock_server.uri()).unwrap();
+
+    let response = engine
+        .embeddings(EmbeddingRequest {
+            engine_id: "remote".to_string(),
+            model_id: "flm://remote/text-embedding-3-small".to_string(),
+            input: vec!["Hello".to_string()],
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.vectors.len(), 1);
+    assert_eq!(response.vectors[0].values.len(), 3);
+}
+
+#[tokio::test]
+async fn test_openai_engine_reports_upstream_errors() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::UNAUTHORIZED).set_body_json(serde_json::json!({
+                "error": {"message": "Incorrect API key provided", "type": "invalid_request_error"}
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = OpenAiCompatibleEngine::new("remote".to_string(), mock_server.uri()).unwrap();
+
+    match engine.chat(chat_request(false)).await {
+        Err(EngineError::ApiError {
+            reason,
+            status_code,
+        }) => {
+            assert_eq!(status_code, Some(401));
+            assert!(reason.contains("Incorrect API key provided"));
+        }
+        other => panic!("expected ApiError, got {other:?}"),
+    }
+
+    let mut stream = engine.chat_stream(chat_request(true)).await.unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(EngineError::ApiError {
+            status_code: Some(401),
+            ..
+        }))
+    ));
+}
@@ -0,0 +1,296 @@
+//! Stable Diffusion web UI engine adapter
+//!
+//! This crate implements the `LlmEngine` trait for image backends that expose
+//! the AUTOMATIC1111 `sdapi/v1` HTTP API (AUTOMATIC1111, SD.Next, Forge).
+//!
+//! The backend only generates images: chat, streaming and embeddings return
+//! `EngineError::UnsupportedOperation`.
+
+use async_trait::async_trait;
+use flm_core::domain::chat::{
+    ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, GeneratedImage,
+    ImageGenerationRequest, ImageGenerationResponse, ImageResponseFormat,
+};
+use flm_core::domain::engine::{HealthStatus, ModelInfo};
+use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities};
+use flm_core::error::EngineError;
+use flm_core::ports::{ChatStream, LlmEngine};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Default request timeout for engines created with `new`
+// Diffusion runs take far longer than a chat turn
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Stable Diffusion web UI engine implementation
+pub struct SdWebUiEngine {
+    engine_id: EngineId,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl SdWebUiEngine {
+    /// Create a new SdWebUiEngine instance
+    pub fn new(engine_id: EngineId, base_url: String) -> Result<Self, EngineError> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Failed to create HTTP client: {e}"),
+            })?;
+
+        Ok(Self::with_client(engine_id, base_url, client))
+    }
+
+    /// Create a SdWebUiEngine that uses a preconfigured HTTP client
+    ///
+    /// Used for registered engines that need their own timeout, auth header or TLS settings.
+    pub fn with_client(engine_id: EngineId, base_url: String, client: reqwest::Client) -> Self {
+        Self {
+            engine_id,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    /// Get the URL of an `sdapi/v1` endpoint
+    fn api_url(&self, endpoint: &str) -> String {
+        format!("{}/sdapi/v1/{}", self.base_url, endpoint)
+    }
+
+    async fn fetch_models(&self) -> Result<Vec<SdModel>, EngineError> {
+        let response = self
+            .client
+            .get(self.api_url("sd-models"))
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+        let response = check_status(response).await?;
+
+        response.json().await.map_err(|e| EngineError::ApiError {
+            reason: format!("Failed to parse JSON: {e}"),
+            status_code: None,
+        })
+    }
+}
+
+#[async_trait]
+impl LlmEngine for SdWebUiEngine {
+    fn id(&self) -> EngineId {
+        self.engine_id.clone()
+    }
+
+    fn kind(&self) -> EngineKind {
+        EngineKind::StableDiffusion
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            image_generation: true,
+            ..EngineCapabilities::default()
+        }
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus, EngineError> {
+        let start = Instant::now();
+        let response = self
+            .client
+            .get(self.api_url("sd-models"))
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        if response.status().is_success() {
+            if latency_ms < 1500 {
+                Ok(HealthStatus::Healthy { latency_ms })
+            } else {
+                Ok(HealthStatus::Degraded {
+                    latency_ms,
+                    reason: "High latency".to_string(),
+                })
+            }
+        } else {
+            Ok(HealthStatus::Unreachable {
+                reason: format!("HTTP {}", response.status().as_u16()),
+            })
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, EngineError> {
+        let models = self
+            .fetch_models()
+            .await?
+            .into_iter()
+            .map(|model| ModelInfo {
+                engine_id: self.engine_id.clone(),
+                model_id: format!("flm://{}/{}", self.engine_id, model.model_name),
+                display_name: model.title,
+                context_length: None,
+                supports_streaming: false,
+                supports_embeddings: false,
+                capabilities: Some(ModelCapabilities::default()),
+            })
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn chat(&self, _req: ChatRequest) -> Result<ChatResponse, EngineError> {
+        Err(unsupported("chat"))
+    }
+
+    async fn chat_stream(&self, _req: ChatRequest) -> Result<ChatStream, EngineError> {
+        Err(unsupported("chat_stream"))
+    }
+
+    async fn embeddings(&self, _req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
+        Err(unsupported("embeddings"))
+    }
+
+    async fn generate_images(
+        &self,
+        req: ImageGenerationRequest,
+    ) -> Result<ImageGenerationResponse, EngineError> {
+        // Verify engine_id matches
+        if req.engine_id != self.engine_id {
+            return Err(EngineError::InvalidResponse {
+                reason: format!(
+                    "Engine ID mismatch: expected {}, got {}",
+                    self.engine_id, req.engine_id
+                ),
+            });
+        }
+
+        // Extract model name from model_id (format: flm://{engine_id}/{model_name})
+        let model = req
+            .model_id
+            .strip_prefix(&format!("flm://{}/", self.engine_id))
+            .ok_or_else(|| EngineError::InvalidResponse {
+                reason: format!("Invalid model ID: {}", req.model_id),
+            })?;
+
+        // The web UI returns PNG bytes only and has nowhere to host them
+        if req.response_format == ImageResponseFormat::Url {
+            return Err(EngineError::UnsupportedOperation {
+                operation: "generate_images".to_string(),
+                reason: "Stable Diffusion web UI cannot serve image URLs; \
+                         use response_format \"b64_json\""
+                    .to_string(),
+            });
+        }
+
+        let txt2img_req = Txt2ImgRequest {
+            prompt: req.prompt,
+            width: req.width,
+            height: req.height,
+            batch_size: req.n,
+            n_iter: 1,
+            override_settings: OverrideSettings {
+                sd_model_checkpoint: model.to_string(),
+            },
+            // Keep the previously loaded checkpoint for the next caller
+            override_settings_restore_afterwards: false,
+        };
+
+        let response = self
+            .client
+            .post(self.api_url("txt2img"))
+            .json(&txt2img_req)
+            .send()
+            .await
+            .map_err(|e| EngineError::NetworkError {
+                reason: format!("Request failed: {e}"),
+            })?;
+        let response: Txt2ImgResponse =
+            check_status(response)
+                .await?
+                .json()
+                .await
+                .map_err(|e| EngineError::ApiError {
+                    reason: format!("Failed to parse JSON: {e}"),
+                    status_code: None,
+                })?;
+
+        if response.images.is_empty() {
+            return Err(EngineError::InvalidResponse {
+                reason: "txt2img returned no images".to_string(),
+            });
+        }
+
+        let images = response
+            .images
+            .into_iter()
+            .map(GeneratedImage::B64Json)
+            .collect();
+
+        Ok(ImageGenerationResponse { images })
+    }
+}
+
+fn unsupported(operation: &str) -> EngineError {
+    EngineError::UnsupportedOperation {
+        operation: operation.to_string(),
+        reason: "Stable Diffusion web UI only supports image generation".to_string(),
+    }
+}
+
+/// Map a non-2xx response to `EngineError::ApiError`, keeping the web UI's `detail`
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, EngineError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let reason = serde_json::from_str::<SdErrorResponse>(&body)
+        .ok()
+        .and_then(|e| e.detail.or(e.error))
+        .unwrap_or_else(|| format!("HTTP {}", status.as_u16()));
+    Err(EngineError::ApiError {
+        reason,
+        status_code: Some(status.as_u16()),
+    })
+}
+
+// Stable Diffusion web UI API types
+
+#[derive(Deserialize)]
+struct SdModel {
+    title: String,
+    model_name: String,
+}
+
+#[derive(Serialize)]
+struct Txt2ImgRequest {
+    prompt: String,
+    width: u32,
+    height: u32,
+    batch_size: u32,
+    n_iter: u32,
+    override_settings: OverrideSettings,
+    override_settings_restore_afterwards: bool,
+}
+
+#[derive(Serialize)]
+struct OverrideSettings {
+    sd_model_checkpoint: String,
+}
+
+#[derive(Deserialize)]
+struct Txt2ImgResponse {
+    #[serde(default)]
+    images: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SdErrorResponse {
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
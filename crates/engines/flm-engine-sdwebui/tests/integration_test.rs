@@ -0,0 +1,183 @@
+//! Integration tests for the Stable Diffusion web UI engine adapter
+//!
+//! These tests run the adapter against a stub `sdapi/v1` server.
+
+use flm_core::domain::chat::{GeneratedImage, ImageGenerationRequest, ImageResponseFormat};
+use flm_core::domain::engine::HealthStatus;
+use flm_core::domain::models::EngineKind;
+use flm_core::error::EngineError;
+use flm_core::ports::LlmEngine;
+use flm_engine_sdwebui::SdWebUiEngine;
+use reqwest::StatusCode;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn image_request(response_format: ImageResponseFormat) -> ImageGenerationRequest {
+    ImageGenerationRequest {
+        engine_id: "sd-test".to_string(),
+        model_id: "flm://sd-test/sd_xl_base_1.0".to_string(),
+        prompt: "a lighthouse at dusk".to_string(),
+        width: 512,
+        height: 768,
+        n: 2,
+        response_format,
+    }
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_id_and_capabilities() {
+    let mock_server = MockServer::start().await;
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+
+    assert_eq!(engine.id(), "sd-test".to_string());
+    assert_eq!(engine.kind(), EngineKind::StableDiffusion);
+    let caps = engine.capabilities();
+    assert!(caps.image_generation);
+    assert!(!caps.chat);
+    assert!(!caps.embeddings);
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_list_models_and_health() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/sdapi/v1/sd-models"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!([
+                {
+                    "title": "sd_xl_base_1.0.safetensors [31e35c80fc]",
+                    "model_name": "sd_xl_base_1.0",
+                    "hash": "31e35c80fc",
+                    "filename": "/models/Stable-diffusion/sd_xl_base_1.0.safetensors"
+                }
+            ])),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+
+    let models = engine.list_models().await.unwrap();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].model_id, "flm://sd-test/sd_xl_base_1.0");
+    assert_eq!(
+        models[0].display_name,
+        "sd_xl_base_1.0.safetensors [31e35c80fc]"
+    );
+
+    let health = engine.health_check().await.unwrap();
+    assert!(matches!(health, HealthStatus::Healthy { .. }));
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_generate_images_b64() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/sdapi/v1/txt2img"))
+        .and(body_partial_json(serde_json::json!({
+            "prompt": "a lighthouse at dusk",
+            "width": 512,
+            "height": 768,
+            "batch_size": 2,
+            "override_settings": { "sd_model_checkpoint": "sd_xl_base_1.0" }
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "images": ["aW1hZ2Ux", "aW1hZ2Uy"],
+                "parameters": {},
+                "info": "{}"
+            })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+    let response = engine
+        .generate_images(image_request(ImageResponseFormat::B64Json))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.images,
+        vec![
+            GeneratedImage::B64Json("aW1hZ2Ux".to_string()),
+            GeneratedImage::B64Json("aW1hZ2Uy".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_rejects_url_response_format() {
+    let mock_server = MockServer::start().await;
+
+    // The web UI is never called: it cannot host the generated images
+    Mock::given(method("POST"))
+        .and(path("/sdapi/v1/txt2img"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+    let err = engine
+        .generate_images(image_request(ImageResponseFormat::Url))
+        .await
+        .unwrap_err();
+
+    match err {
+        EngineError::UnsupportedOperation { reason, .. } => {
+            assert!(reason.contains("b64_json"), "reason: {reason}");
+        }
+        other => panic!("expected UnsupportedOperation, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_generate_images_api_error() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/sdapi/v1/txt2img"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::UNPROCESSABLE_ENTITY)
+                .set_body_json(serde_json::json!({ "detail": "Invalid checkpoint" })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+    let err = engine
+        .generate_images(image_request(ImageResponseFormat::B64Json))
+        .await
+        .unwrap_err();
+
+    match err {
+        EngineError::ApiError {
+            reason,
+            status_code,
+        } => {
+            assert_eq!(reason, "Invalid checkpoint");
+            assert_eq!(status_code, Some(422));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_sdwebui_engine_rejects_text_operations() {
+    let mock_server = MockServer::start().await;
+    let engine = SdWebUiEngine::new("sd-test".to_string(), mock_server.uri()).unwrap();
+
+    let err = engine
+        .embeddings(flm_core::domain::chat::EmbeddingRequest {
+            engine_id: "sd-test".to_string(),
+            model_id: "flm://sd-test/sd_xl_base_1.0".to_string(),
+            input: vec!["hello".to_string()],
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, EngineError::UnsupportedOperation { .. }));
+}
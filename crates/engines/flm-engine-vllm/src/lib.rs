@@ -3,59 +3,77 @@
 //! This crate implements the `LlmEngine` trait for vLLM.
 //! See `docs/ENGINE_DETECT.md` for detection specification.
 //!
-//! vLLM provides an OpenAI-compatible API, so the implementation is simpler than Ollama.
+//! Chat, streaming chat and embeddings go through the shared OpenAI backend
+//! with vLLM's extensions switched on: `top_k`, `repetition_penalty` and JSON
+//! schemas as `guided_json` for guided decoding. Health checks try the native
+//! `/health` endpoint first, and `/v1/models` reports each model's
+//! `max_model_len`. Raw completions (`/v1/completions`) and text-to-speech
+//! (`/v1/audio/speech`) are handled here.
 
 use async_trait::async_trait;
-use base64::{engine::general_purpose, Engine as _};
 use flm_core::domain::chat::{
-    ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, EmbeddingRequest,
-    EmbeddingResponse, EmbeddingVector, MultimodalAttachmentKind, UsageStats,
+    ChatRequest, ChatResponse, CompletionChoice, CompletionRequest, CompletionResponse,
+    CompletionStreamChunk, EmbeddingRequest, EmbeddingResponse, SpeechRequest, UsageStats,
 };
 use flm_core::domain::engine::{HealthStatus, ModelInfo};
 use flm_core::domain::models::{EngineCapabilities, EngineId, EngineKind, ModelCapabilities};
 use flm_core::error::EngineError;
-use flm_core::ports::LlmEngine;
-use futures::Stream;
+use flm_core::ports::{AudioStream, ChatStream, CompletionStream, LlmEngine};
+use flm_engine_openai::backend::{
+    JsonSchemaField, MultipleChoices, OpenAiBackend, OpenAiDialect, OpenAiSamplingOptions,
+    RepeatPenaltyField,
+};
+use flm_engine_openai::wire::OpenAiStreamOptions;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::pin::Pin;
-use std::time::Instant;
 use tokio_stream::StreamExt;
 
+/// Default request timeout for engines created with `new`
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// What vLLM accepts beyond the OpenAI API
+const DIALECT: OpenAiDialect = OpenAiDialect {
+    name: "vLLM",
+    health_path: Some("health"),
+    top_k: true,
+    repeat_penalty: Some(RepeatPenaltyField::RepetitionPenalty),
+    choices: MultipleChoices::NotStreamed,
+    logit_bias: true,
+    logprobs: true,
+    json_schema: JsonSchemaField::Field("guided_json"),
+};
+
 /// vLLM engine implementation
 pub struct VllmEngine {
-    engine_id: EngineId,
-    base_url: String,
-    client: reqwest::Client,
+    backend: OpenAiBackend,
 }
 
 impl VllmEngine {
     /// Create a new VllmEngine instance
     pub fn new(engine_id: EngineId, base_url: String) -> Result<Self, EngineError> {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()
             .map_err(|e| EngineError::NetworkError {
                 reason: format!("Failed to create HTTP client: {e}"),
             })?;
 
-        Ok(Self {
-            engine_id,
-            base_url,
-            client,
-        })
+        Ok(Self::with_client(engine_id, base_url, client))
     }
 
-    /// Get the base URL for API requests
-    fn api_url(&self, endpoint: &str) -> String {
-        format!("{}/v1/{}", self.base_url, endpoint)
+    /// Create a VllmEngine that uses a preconfigured HTTP client
+    ///
+    /// Used for registered engines that need their own timeout, auth header or TLS settings.
+    pub fn with_client(engine_id: EngineId, base_url: String, client: reqwest::Client) -> Self {
+        Self {
+            backend: OpenAiBackend::new(engine_id, base_url, client, DIALECT),
+        }
     }
 }
 
 #[async_trait]
 impl LlmEngine for VllmEngine {
     fn id(&self) -> EngineId {
-        self.engine_id.clone()
+        self.backend.engine_id().clone()
     }
 
     fn kind(&self) -> EngineKind {
@@ -68,210 +86,97 @@ impl LlmEngine for VllmEngine {
             chat_stream: true,
             embeddings: true,
             moderation: false,
-            tools: false,        // Model-specific
+            tools: true,         // Model-specific
             reasoning: false,    // Model-specific
             vision_inputs: true, // Model-specific
             audio_inputs: true,  // Model-specific
             audio_outputs: true,
+            image_generation: false,
             max_image_bytes: None,
             max_audio_bytes: None,
         }
     }
 
     async fn health_check(&self) -> Result<HealthStatus, EngineError> {
-        let start = Instant::now();
-        // Use /health endpoint if available, fallback to /v1/models
-        let health_url = format!("{}/health", self.base_url);
-        let models_url = self.api_url("models");
-
-        // Try /health first, fallback to /v1/models if /health is not available
-        let response = match self.client.get(&health_url).send().await {
-            Ok(resp) if resp.status().is_success() => resp,
-            _ => {
-                // Fallback to /v1/models
-                self.client.get(&models_url).send().await.map_err(|e| {
-                    EngineError::NetworkError {
-                        reason: format!("Request failed: {e}"),
-                    }
-                })?
-            }
-        };
-
-        let latency_ms = start.elapsed().as_millis() as u64;
-        if response.status().is_success() {
-            if latency_ms < 1500 {
-                Ok(HealthStatus::Healthy { latency_ms })
-            } else {
-                Ok(HealthStatus::Degraded {
-                    latency_ms,
-                    reason: "High latency".to_string(),
-                })
-            }
-        } else {
-            Ok(HealthStatus::Unreachable {
-                reason: format!("HTTP {}", response.status().as_u16()),
-            })
-        }
+        self.backend.health_check().await
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, EngineError> {
-        let url = self.api_url("models");
-
-        let response: OpenAiModelsResponse = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| EngineError::NetworkError {
-                reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
-            })?;
-
-        let models = response
-            .data
-            .into_iter()
-            .map(|model| {
+        // vLLM reports no per-model features, so the name is the only hint
+        self.backend
+            .list_models(|model| {
                 let model_name = &model.id;
-                let capabilities = Some(ModelCapabilities {
+                Some(ModelCapabilities {
                     reasoning: detect_reasoning_support(model_name),
                     tools: detect_tool_use_support(model_name),
                     vision: detect_vision_support(model_name),
                     audio_inputs: detect_audio_support(model_name),
                     audio_outputs: detect_audio_support(model_name),
-                });
-
-                ModelInfo {
-                    engine_id: self.engine_id.clone(),
-                    model_id: format!("flm://{}/{}", self.engine_id, model.id),
-                    display_name: model.id.clone(),
-                    context_length: None, // vLLM API doesn't always provide this
-                    supports_streaming: true,
-                    supports_embeddings: true,
-                    capabilities,
-                }
+                })
             })
-            .collect();
-
-        Ok(models)
+            .await
     }
 
     async fn chat(&self, req: ChatRequest) -> Result<ChatResponse, EngineError> {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
+        self.backend.chat(req).await
+    }
 
-        // Extract model name from model_id (format: flm://{engine_id}/{model_name})
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
+    async fn chat_stream(&self, req: ChatRequest) -> Result<ChatStream, EngineError> {
+        self.backend.chat_stream(req)
+    }
 
-        let openai_req = OpenAiChatRequest {
-            model: model.to_string(),
-            messages: req
-                .messages
-                .into_iter()
-                .map(convert_to_openai_message)
-                .collect(),
-            stream: false,
-            temperature: req.temperature.map(|t| t as f64),
-            max_tokens: req.max_tokens,
-        };
+    async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
+        self.backend.embeddings(req).await
+    }
 
-        let url = self.api_url("chat/completions");
-        let response: OpenAiChatResponse = self
-            .client
-            .post(&url)
-            .json(&openai_req)
-            .send()
-            .await
-            .map_err(|e| EngineError::NetworkError {
-                reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
-            })?;
+    async fn complete(&self, req: CompletionRequest) -> Result<CompletionResponse, EngineError> {
+        let model = self
+            .backend
+            .model_name(&req.engine_id, &req.model_id)?
+            .to_string();
+        let openai_req = convert_completion_request(&self.backend, &model, req, false)?;
 
-        // Extract the first choice
-        let choice = response
-            .choices
-            .first()
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: "No choices in response".to_string(),
-            })?;
+        let response: OpenAiCompletionResponse = self
+            .backend
+            .post_json(&self.backend.api_url("completions"), &openai_req)
+            .await?;
 
-        let content = choice.message.content.clone().unwrap_or_default();
+        if response.choices.is_empty() {
+            return Err(EngineError::InvalidResponse {
+                reason: "No choices in response".to_string(),
+            });
+        }
 
-        Ok(ChatResponse {
+        Ok(CompletionResponse {
             usage: response.usage.unwrap_or(UsageStats {
                 prompt_tokens: 0,
                 completion_tokens: 0,
                 total_tokens: 0,
             }),
-            messages: vec![ChatMessage {
-                role: ChatRole::Assistant,
-                content,
-                attachments: Vec::new(),
-            }],
-            audio: Vec::new(),
+            choices: response
+                .choices
+                .into_iter()
+                .map(|choice| CompletionChoice {
+                    text: choice.text,
+                    finish_reason: choice.finish_reason,
+                })
+                .collect(),
         })
     }
 
-    async fn chat_stream(
+    async fn complete_stream(
         &self,
-        req: ChatRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatStreamChunk, EngineError>> + Send>>, EngineError>
-    {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
+        req: CompletionRequest,
+    ) -> Result<CompletionStream, EngineError> {
+        let model = self
+            .backend
+            .model_name(&req.engine_id, &req.model_id)?
+            .to_string();
+        let openai_req = convert_completion_request(&self.backend, &model, req, true)?;
 
-        // Extract model name from model_id
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
+        let url = self.backend.api_url("completions");
+        let client = self.backend.client().clone();
 
-        let openai_req = OpenAiChatRequest {
-            model: model.to_string(),
-            messages: req
-                .messages
-                .into_iter()
-                .map(convert_to_openai_message)
-                .collect(),
-            stream: true,
-            temperature: req.temperature.map(|t| t as f64),
-            max_tokens: req.max_tokens,
-        };
-
-        let url = self.api_url("chat/completions");
-        let client = self.client.clone();
-
-        // Create a stream from the response
         let stream = async_stream::stream! {
             let response = client
                 .post(&url)
@@ -283,10 +188,9 @@ impl LlmEngine for VllmEngine {
                 })?;
 
             let mut stream = response.bytes_stream();
-            let mut accumulated_content = String::new();
             let mut is_done = false;
-            #[allow(unused_assignments)]
-            let mut final_usage: Option<UsageStats> = None;
+            // Finishing chunk waiting for the trailing usage chunk (`include_usage`)
+            let mut pending: Option<CompletionStreamChunk> = None;
 
             while let Some(chunk_result) = stream.next().await {
                 let bytes = chunk_result.map_err(|e| EngineError::NetworkError {
@@ -302,34 +206,39 @@ impl LlmEngine for VllmEngine {
                             break;
                         }
 
-                        match serde_json::from_str::<OpenAiChatChunk>(json_str) {
+                        match serde_json::from_str::<OpenAiCompletionResponse>(json_str) {
                             Ok(chunk) => {
-                                if let Some(delta) = chunk.choices.first() {
-                                    if let Some(delta_content) = delta.delta.content.as_ref() {
-                                        if !delta_content.is_empty() {
-                                            accumulated_content.push_str(delta_content);
-                                            yield Ok(ChatStreamChunk {
-                                                delta: ChatMessage {
-                                                    role: ChatRole::Assistant,
-                                                    content: delta_content.clone(),
-                                                    attachments: Vec::new(),
-                                                },
-                                                usage: if delta.finish_reason.is_some() {
-                                                    final_usage = chunk.usage.clone();
-                                                    final_usage.clone()
-                                                } else {
-                                                    None
-                                                },
-                                                is_done: delta.finish_reason.is_some(),
-                                                audio: Vec::new(),
-                                            });
-                                        }
-
-                                        if delta.finish_reason.is_some() {
-                                            is_done = true;
-                                            break;
+                                if let Some(choice) = chunk.choices.into_iter().next() {
+                                    let finished = choice.finish_reason.is_some();
+                                    if !choice.text.is_empty() || finished {
+                                        let out = CompletionStreamChunk {
+                                            text: choice.text,
+                                            usage: if finished { chunk.usage } else { None },
+                                            is_done: finished,
+                                            finish_reason: choice.finish_reason,
+                                        };
+                                        if finished && out.usage.is_none() {
+                                            pending = Some(out);
+                                        } else {
+                                            yield Ok(out);
+                                            if finished {
+                                                is_done = true;
+                                                break;
+                                            }
                                         }
                                     }
+                                } else if let Some(usage) = chunk.usage {
+                                    // Usage-only chunk sent after the finishing chunk
+                                    let mut last = pending.take().unwrap_or(CompletionStreamChunk {
+                                        text: String::new(),
+                                        usage: None,
+                                        is_done: true,
+                                        finish_reason: None,
+                                    });
+                                    last.usage = Some(usage);
+                                    yield Ok(last);
+                                    is_done = true;
+                                    break;
                                 }
                             }
                             Err(e) => {
@@ -345,203 +254,150 @@ impl LlmEngine for VllmEngine {
                     break;
                 }
             }
+
+            if let Some(last) = pending {
+                yield Ok(last);
+            }
         };
 
         Ok(Box::pin(stream))
     }
 
-    async fn embeddings(&self, req: EmbeddingRequest) -> Result<EmbeddingResponse, EngineError> {
-        // Verify engine_id matches
-        if req.engine_id != self.engine_id {
-            return Err(EngineError::InvalidResponse {
-                reason: format!(
-                    "Engine ID mismatch: expected {}, got {}",
-                    self.engine_id, req.engine_id
-                ),
-            });
-        }
-
-        // Extract model name from model_id
-        let model = req
-            .model_id
-            .strip_prefix(&format!("flm://{}/", self.engine_id))
-            .ok_or_else(|| EngineError::InvalidResponse {
-                reason: format!("Invalid model ID: {}", req.model_id),
-            })?;
+    /// Text-to-speech via `/v1/audio/speech`
+    ///
+    /// Works against any OpenAI-compatible TTS server (e.g. Kokoro-FastAPI,
+    /// openedai-speech) registered with this adapter. The request is sent up
+    /// front so HTTP errors surface before any audio is streamed.
+    async fn synthesize_speech(&self, req: SpeechRequest) -> Result<AudioStream, EngineError> {
+        let model = self.backend.model_name(&req.engine_id, &req.model_id)?;
 
-        let openai_req = OpenAiEmbeddingRequest {
+        let openai_req = OpenAiSpeechRequest {
             model: model.to_string(),
             input: req.input,
+            voice: req.voice,
+            response_format: req.format.as_str(),
+            speed: req.speed,
         };
 
-        let url = self.api_url("embeddings");
-        let response: OpenAiEmbeddingResponse = self
-            .client
+        let url = self.backend.api_url("audio/speech");
+        let response = self
+            .backend
+            .client()
             .post(&url)
             .json(&openai_req)
             .send()
             .await
             .map_err(|e| EngineError::NetworkError {
                 reason: format!("Request failed: {e}"),
-            })?
-            .json()
-            .await
-            .map_err(|e| EngineError::ApiError {
-                reason: format!("Failed to parse JSON: {e}"),
-                status_code: None,
             })?;
 
-        let usage = if let Some(u) = response.usage {
-            u
-        } else {
-            eprintln!("Warning: usage stats are missing in vLLM embedding response");
-            UsageStats {
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                total_tokens: 0,
-            }
-        };
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(EngineError::ApiError {
+                reason: format!("Speech request failed: {body}"),
+                status_code: Some(status.as_u16()),
+            });
+        }
 
-        Ok(EmbeddingResponse {
-            usage,
-            vectors: response
-                .data
-                .into_iter()
-                .enumerate()
-                .map(|(idx, item)| EmbeddingVector {
-                    index: idx,
-                    values: item.embedding,
+        let stream = response.bytes_stream().map(|chunk| {
+            chunk
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| EngineError::NetworkError {
+                    reason: format!("Stream error: {e}"),
                 })
-                .collect(),
-        })
+        });
+
+        Ok(Box::pin(stream))
     }
 }
 
-// OpenAI-compatible API request/response types
+// Completion and speech request/response types
 
 #[derive(Serialize)]
-struct OpenAiChatRequest {
+struct OpenAiSpeechRequest {
     model: String,
-    messages: Vec<OpenAiMessageRequest>,
+    input: String,
+    voice: String,
+    response_format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct OpenAiCompletionRequest {
+    model: String,
+    prompt: String,
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAiStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_of: Option<u32>,
+    #[serde(flatten)]
+    sampling: OpenAiSamplingOptions,
 }
 
-#[derive(Serialize)]
-struct OpenAiMessageRequest {
-    role: String,
-    content: Value, // String or array of content objects
-}
-
-/// Convert ChatMessage (OpenAI-compatible format) to OpenAiMessageRequest format
-///
-/// This function converts the abstract ChatRequest format to OpenAI's API format.
-/// Image attachments are converted to OpenAI's `image_url` format.
-/// Audio attachments are converted to OpenAI's `input_audio` format.
-fn convert_to_openai_message(msg: ChatMessage) -> OpenAiMessageRequest {
-    let role = match msg.role {
-        ChatRole::User => "user".to_string(),
-        ChatRole::Assistant => "assistant".to_string(),
-        ChatRole::System => "system".to_string(),
-        ChatRole::Tool => "user".to_string(), // Fallback to user
-    };
-
-    // If there are no attachments, use simple string content
-    if msg.attachments.is_empty() {
-        return OpenAiMessageRequest {
-            role,
-            content: Value::String(msg.content),
-        };
+/// Build a `/v1/completions` request for a raw text completion
+fn convert_completion_request(
+    backend: &OpenAiBackend,
+    model: &str,
+    req: CompletionRequest,
+    stream: bool,
+) -> Result<OpenAiCompletionRequest, EngineError> {
+    backend.check_sampling(&req.sampling, stream, "complete")?;
+    // The legacy API takes an integer `logprobs` and vLLM rejects `suffix`
+    let mut unsupported = Vec::new();
+    if req.sampling.logprobs || req.sampling.top_logprobs.is_some() {
+        unsupported.push("logprobs");
     }
-
-    // Build content array with text and attachments
-    let mut content_parts: Vec<Value> = Vec::new();
-
-    // Add text content if not empty
-    if !msg.content.is_empty() {
-        content_parts.push(serde_json::json!({
-            "type": "text",
-            "text": msg.content
-        }));
+    if req.suffix.is_some() {
+        unsupported.push("suffix");
     }
-
-    // Add image attachments
-    for att in &msg.attachments {
-        if matches!(att.kind, MultimodalAttachmentKind::InputImage) {
-            let base64_data = general_purpose::STANDARD.encode(&att.data);
-            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
-            content_parts.push(serde_json::json!({
-                "type": "image_url",
-                "image_url": {
-                    "url": data_url
-                }
-            }));
-        } else if matches!(att.kind, MultimodalAttachmentKind::InputAudio) {
-            let base64_data = general_purpose::STANDARD.encode(&att.data);
-            let data_url = format!("data:{};base64,{}", att.mime_type, base64_data);
-            content_parts.push(serde_json::json!({
-                "type": "input_audio",
-                "audio_url": {
-                    "url": data_url
-                }
-            }));
-        }
+    if !unsupported.is_empty() {
+        return Err(EngineError::UnsupportedOperation {
+            operation: "complete".to_string(),
+            reason: format!(
+                "vLLM does not support completion parameter(s): {}",
+                unsupported.join(", ")
+            ),
+        });
     }
 
-    OpenAiMessageRequest {
-        role,
-        content: Value::Array(content_parts),
-    }
+    Ok(OpenAiCompletionRequest {
+        model: model.to_string(),
+        prompt: req.prompt,
+        stream,
+        stream_options: OpenAiStreamOptions::for_stream(stream),
+        temperature: req.temperature.map(|t| t as f64),
+        max_tokens: req.max_tokens,
+        stop: req.stop,
+        best_of: req.best_of,
+        sampling: backend.sampling_options(&req.sampling),
+    })
 }
 
+/// `/v1/completions` response (also each SSE chunk when streaming)
 #[derive(Deserialize)]
-struct OpenAiMessageResponse {
-    #[allow(dead_code)]
+struct OpenAiCompletionResponse {
+    choices: Vec<OpenAiCompletionChoice>,
     #[serde(default)]
-    role: String,
-    content: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiChatResponse {
-    choices: Vec<OpenAiChoice>,
     usage: Option<UsageStats>,
 }
 
 #[derive(Deserialize)]
-struct OpenAiChoice {
-    message: OpenAiMessageResponse,
-    #[serde(rename = "finish_reason")]
-    #[allow(dead_code)]
-    finish_reason: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiChatChunk {
-    choices: Vec<OpenAiChunkChoice>,
-    usage: Option<UsageStats>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiChunkChoice {
-    delta: OpenAiMessageResponse,
-    #[serde(rename = "finish_reason")]
+struct OpenAiCompletionChoice {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
     finish_reason: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct OpenAiModelsResponse {
-    data: Vec<OpenAiModel>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiModel {
-    id: String,
-}
-
 /// Detect if a model supports reasoning capabilities based on model name
 fn detect_reasoning_support(model_name: &str) -> bool {
     let name = model_name.to_lowercase();
@@ -702,20 +558,3 @@ mod tests {
         assert!(!detect_audio_support(reasoning_only));
     }
 }
-
-#[derive(Serialize)]
-struct OpenAiEmbeddingRequest {
-    model: String,
-    input: Vec<String>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiEmbeddingResponse {
-    data: Vec<OpenAiEmbeddingData>,
-    usage: Option<UsageStats>,
-}
-
-#[derive(Deserialize)]
-struct OpenAiEmbeddingData {
-    embedding: Vec<f32>,
-}
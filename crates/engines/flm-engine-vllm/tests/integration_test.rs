@@ -2,11 +2,14 @@
 //!
 //! These tests verify that the vLLM engine adapter works correctly with mock HTTP servers.
 
-use flm_core::domain::chat::{ChatMessage, ChatRequest, ChatRole};
+use flm_core::domain::chat::{
+    AudioResponseFormat, ChatMessage, ChatRequest, ChatRole, CompletionRequest, ResponseFormat,
+    SamplingParams, SpeechRequest, ToolCall, ToolChoice, ToolDefinition,
+};
 use flm_core::ports::LlmEngine;
 use flm_engine_vllm::VllmEngine;
 use reqwest::StatusCode;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_partial_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -33,7 +36,8 @@ async fn test_vllm_engine_list_models() {
                         "id": "meta-llama/Llama-2-7b-chat-hf",
                         "object": "model",
                         "created": 1686935002,
-                        "owned_by": "meta-llama"
+                        "owned_by": "meta-llama",
+                        "max_model_len": 4096
                     }
                 ]
             })),
@@ -50,6 +54,7 @@ async fn test_vllm_engine_list_models() {
         models[0].model_id,
         "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf"
     );
+    assert_eq!(models[0].context_length, Some(4096));
 }
 
 #[tokio::test]
@@ -136,12 +141,18 @@ async fn test_vllm_engine_chat() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: false,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let response = engine.chat(req).await.unwrap();
@@ -215,12 +226,18 @@ async fn test_vllm_engine_chat_stream() {
             role: ChatRole::User,
             content: "Hello".to_string(),
             attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
         stream: true,
         temperature: None,
         max_tokens: None,
         stop: vec![],
         requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
     };
 
     let mut stream = engine.chat_stream(req).await.unwrap();
@@ -240,6 +257,56 @@ async fn test_vllm_engine_chat_stream() {
     assert!(chunks.last().unwrap().is_done);
 }
 
+#[tokio::test]
+async fn test_vllm_engine_chat_stream_reads_trailing_usage() {
+    let mock_server = MockServer::start().await;
+
+    // With stream_options.include_usage, usage arrives on a chunk with no choices
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "stream": true,
+            "stream_options": { "include_usage": true }
+        })))
+        .respond_with(ResponseTemplate::new(StatusCode::OK)
+            .set_body_string("data: {\"id\":\"chatcmpl-123\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"finish_reason\":null}]}\n\ndata: {\"id\":\"chatcmpl-123\",\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":null}\n\ndata: {\"id\":\"chatcmpl-123\",\"choices\":[],\"usage\":{\"prompt_tokens\":9,\"completion_tokens\":2,\"total_tokens\":11}}\n\ndata: [DONE]\n\n"))
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Hello".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: true,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let stream = engine.chat_stream(req).await.unwrap();
+    use futures::StreamExt;
+    let chunks: Vec<_> = stream.map(|chunk| chunk.unwrap()).collect().await;
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].delta.content, "Hi");
+    let last = chunks.last().unwrap();
+    assert!(last.is_done);
+    assert_eq!(last.usage.as_ref().map(|u| u.total_tokens), Some(11));
+}
+
 #[tokio::test]
 async fn test_vllm_engine_health_check_degraded() {
     use std::time::Duration;
@@ -344,3 +411,490 @@ async fn test_vllm_engine_health_check_fallback() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_vllm_engine_chat_with_tools() {
+    let mock_server = MockServer::start().await;
+
+    // The request must carry the tool definitions and the previous tool result
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{
+                "type": "function",
+                "function": { "name": "get_weather" }
+            }],
+            "tool_choice": "required"
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "meta-llama/Llama-2-7b-chat-hf",
+                "choices": [
+                    {
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": null,
+                            "tool_calls": [{
+                                "id": "call_abc",
+                                "type": "function",
+                                "function": {
+                                    "name": "get_weather",
+                                    "arguments": "{\"city\":\"Tokyo\"}"
+                                }
+                            }]
+                        },
+                        "finish_reason": "tool_calls"
+                    }
+                ],
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 5,
+                    "total_tokens": 15
+                }
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "What is the weather in Tokyo?".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: Some("Get the current weather".to_string()),
+            parameters: Some(serde_json::json!({
+                "type": "object",
+                "properties": { "city": { "type": "string" } }
+            })),
+        }],
+        tool_choice: Some(ToolChoice::Required),
+        response_format: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.chat(req).await.unwrap();
+    assert_eq!(response.messages.len(), 1);
+    assert!(response.messages[0].content.is_empty());
+    assert_eq!(
+        response.messages[0].tool_calls,
+        vec![ToolCall {
+            id: "call_abc".to_string(),
+            name: "get_weather".to_string(),
+            arguments: "{\"city\":\"Tokyo\"}".to_string(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_vllm_engine_chat_stream_with_tool_calls() {
+    let mock_server = MockServer::start().await;
+
+    // Tool call arguments arrive in fragments sharing the same index
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK)
+            .set_body_string("data: {\"id\":\"chatcmpl-123\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"tool_calls\":[{\"index\":0,\"id\":\"call_abc\",\"type\":\"function\",\"function\":{\"name\":\"get_weather\",\"arguments\":\"{\\\"city\"}}]},\"finish_reason\":null}]}\n\ndata: {\"id\":\"chatcmpl-123\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\":\\\"Tokyo\\\"}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\ndata: [DONE]\n\n"))
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "What is the weather in Tokyo?".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: true,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: None,
+            parameters: None,
+        }],
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let mut stream = engine.chat_stream(req).await.unwrap();
+    use futures::StreamExt;
+
+    let mut deltas = Vec::new();
+    while let Some(result) = stream.next().await {
+        let chunk = result.unwrap();
+        deltas.extend(chunk.tool_calls);
+        if chunk.is_done {
+            break;
+        }
+    }
+
+    assert_eq!(deltas.len(), 2);
+    assert_eq!(deltas[0].id.as_deref(), Some("call_abc"));
+    assert_eq!(deltas[0].name.as_deref(), Some("get_weather"));
+    let arguments: String = deltas.iter().filter_map(|d| d.arguments.clone()).collect();
+    assert_eq!(arguments, "{\"city\":\"Tokyo\"}");
+}
+
+#[tokio::test]
+async fn test_vllm_engine_chat_with_json_schema() {
+    let mock_server = MockServer::start().await;
+
+    // The schema must be forwarded through guided decoding
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "guided_json": { "type": "object", "required": ["total"] }
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "meta-llama/Llama-2-7b-chat-hf",
+                "choices": [
+                    {
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": "{\"total\": 42}"
+                        },
+                        "finish_reason": "stop"
+                    }
+                ],
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 5,
+                    "total_tokens": 15
+                }
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Return the invoice total".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: Some(ResponseFormat::JsonSchema {
+            name: "invoice".to_string(),
+            schema: serde_json::json!({ "type": "object", "required": ["total"] }),
+            strict: true,
+        }),
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.chat(req).await.unwrap();
+    assert_eq!(response.messages[0].content, "{\"total\": 42}");
+}
+
+#[tokio::test]
+async fn test_vllm_engine_chat_with_sampling_params() {
+    let mock_server = MockServer::start().await;
+
+    // Sampling parameters use vLLM's native names; each choice carries logprobs
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "seed": 42,
+            "top_k": 40,
+            "repetition_penalty": 1.1,
+            "n": 2,
+            "logprobs": true,
+            "top_logprobs": 1
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "meta-llama/Llama-2-7b-chat-hf",
+                "choices": [
+                    {
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hi" },
+                        "logprobs": {
+                            "content": [{
+                                "token": "Hi",
+                                "logprob": -0.1,
+                                "top_logprobs": [{ "token": "Hi", "logprob": -0.1 }]
+                            }]
+                        },
+                        "finish_reason": "stop"
+                    },
+                    {
+                        "index": 1,
+                        "message": { "role": "assistant", "content": "Hello" },
+                        "logprobs": {
+                            "content": [{
+                                "token": "Hello",
+                                "logprob": -0.5,
+                                "top_logprobs": [{ "token": "Hello", "logprob": -0.5 }]
+                            }]
+                        },
+                        "finish_reason": "stop"
+                    }
+                ],
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 2,
+                    "total_tokens": 12
+                }
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = ChatRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-chat-hf".to_string(),
+        messages: vec![ChatMessage {
+            role: ChatRole::User,
+            content: "Hello".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }],
+        stream: false,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        requested_modalities: Vec::new(),
+        tools: Vec::new(),
+        tool_choice: None,
+        response_format: None,
+        sampling: SamplingParams {
+            seed: Some(42),
+            top_k: Some(40),
+            repeat_penalty: Some(1.1),
+            n: Some(2),
+            logprobs: true,
+            top_logprobs: Some(1),
+            ..SamplingParams::default()
+        },
+    };
+
+    let response = engine.chat(req).await.unwrap();
+    assert_eq!(response.messages.len(), 2);
+    assert_eq!(response.messages[1].content, "Hello");
+    assert_eq!(response.logprobs.len(), 2);
+    assert_eq!(response.logprobs[0][0].token, "Hi");
+    assert_eq!(response.logprobs[1][0].top_logprobs.len(), 1);
+}
+
+#[tokio::test]
+async fn test_vllm_engine_complete() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "meta-llama/Llama-2-7b-hf",
+            "prompt": "Once upon a time",
+            "best_of": 2,
+            "stop": ["\n"]
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK).set_body_json(serde_json::json!({
+                "id": "cmpl-123",
+                "object": "text_completion",
+                "created": 1677652288,
+                "model": "meta-llama/Llama-2-7b-hf",
+                "choices": [{
+                    "index": 0,
+                    "text": " there was a llama",
+                    "logprobs": null,
+                    "finish_reason": "length"
+                }],
+                "usage": {
+                    "prompt_tokens": 4,
+                    "completion_tokens": 5,
+                    "total_tokens": 9
+                }
+            })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-hf".to_string(),
+        prompt: "Once upon a time".to_string(),
+        suffix: None,
+        stream: false,
+        temperature: None,
+        max_tokens: Some(5),
+        stop: vec!["\n".to_string()],
+        best_of: Some(2),
+        sampling: SamplingParams::default(),
+    };
+
+    let response = engine.complete(req).await.unwrap();
+    assert_eq!(response.choices.len(), 1);
+    assert_eq!(response.choices[0].text, " there was a llama");
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("length"));
+    assert_eq!(response.usage.total_tokens, 9);
+}
+
+#[tokio::test]
+async fn test_vllm_engine_complete_stream() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/completions"))
+        .respond_with(ResponseTemplate::new(StatusCode::OK)
+            .set_body_string("data: {\"id\":\"cmpl-123\",\"object\":\"text_completion\",\"created\":1677652288,\"model\":\"meta-llama/Llama-2-7b-hf\",\"choices\":[{\"index\":0,\"text\":\" there\",\"finish_reason\":null}]}\n\ndata: {\"id\":\"cmpl-123\",\"object\":\"text_completion\",\"created\":1677652288,\"model\":\"meta-llama/Llama-2-7b-hf\",\"choices\":[{\"index\":0,\"text\":\" was\",\"finish_reason\":\"stop\"}]}\n\ndata: [DONE]\n\n"))
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+
+    let req = CompletionRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/meta-llama/Llama-2-7b-hf".to_string(),
+        prompt: "Once upon a time".to_string(),
+        suffix: None,
+        stream: true,
+        temperature: None,
+        max_tokens: None,
+        stop: vec![],
+        best_of: None,
+        sampling: SamplingParams::default(),
+    };
+
+    let mut stream = engine.complete_stream(req).await.unwrap();
+    use futures::StreamExt;
+
+    let mut text = String::new();
+    let mut finished = false;
+    while let Some(result) = stream.next().await {
+        let chunk = result.unwrap();
+        text.push_str(&chunk.text);
+        if chunk.is_done {
+            finished = true;
+            assert_eq!(chunk.finish_reason.as_deref(), Some("stop"));
+            break;
+        }
+    }
+
+    assert!(finished);
+    assert_eq!(text, " there was");
+}
+
+#[tokio::test]
+async fn test_vllm_engine_synthesize_speech() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/audio/speech"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "kokoro",
+            "input": "Hello there",
+            "voice": "af_bella",
+            "response_format": "opus",
+            "speed": 1.5
+        })))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::OK)
+                .insert_header("content-type", "audio/ogg")
+                .set_body_bytes(b"OggS-fake-audio".to_vec()),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+    let req = SpeechRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/kokoro".to_string(),
+        input: "Hello there".to_string(),
+        voice: "af_bella".to_string(),
+        format: AudioResponseFormat::Opus,
+        speed: Some(1.5),
+    };
+
+    let mut stream = engine.synthesize_speech(req).await.unwrap();
+    use futures::StreamExt;
+
+    let mut audio = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        audio.extend(chunk.unwrap());
+    }
+    assert_eq!(audio, b"OggS-fake-audio");
+}
+
+#[tokio::test]
+async fn test_vllm_engine_synthesize_speech_api_error() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/audio/speech"))
+        .respond_with(
+            ResponseTemplate::new(StatusCode::BAD_REQUEST)
+                .set_body_json(serde_json::json!({"detail": "Unknown voice"})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let engine = VllmEngine::new("vllm-test".to_string(), mock_server.uri()).unwrap();
+    let req = SpeechRequest {
+        engine_id: "vllm-test".to_string(),
+        model_id: "flm://vllm-test/kokoro".to_string(),
+        input: "Hello there".to_string(),
+        voice: "nobody".to_string(),
+        format: AudioResponseFormat::Mp3,
+        speed: None,
+    };
+
+    match engine.synthesize_speech(req).await {
+        Err(flm_core::error::EngineError::ApiError { status_code, .. }) => {
+            assert_eq!(status_code, Some(400));
+        }
+        Err(other) => panic!("unexpected error: {other:?}"),
+        Ok(_) => panic!("expected an API error"),
+    }
+}
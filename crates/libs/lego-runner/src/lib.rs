@@ -66,7 +66,7 @@ pub struct LegoRequest<'a> {
     pub propagation_wait: Duration,
 }
 
-impl<'a> LegoRequest<'a> {
+impl LegoRequest<'_> {
     pub fn primary_domain(&self) -> &str {
         &self.domains[0]
     }
@@ -55,7 +55,7 @@ async fn main() {
     unreachable!()
 }
 
-const HELLO: &'static [u8] = br#"HTTP/1.1 200 OK
+const HELLO: &[u8] = br#"HTTP/1.1 200 OK
 Content-Length: 10
 Content-Type: text/plain; charset=utf-8
 
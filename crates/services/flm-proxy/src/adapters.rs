@@ -3,15 +3,22 @@
 //! This module contains concrete implementations of port traits
 //! needed by the proxy server, without depending on flm-cli.
 
+use crate::audit_sink::AuditForwarder;
 use async_trait::async_trait;
-use flm_core::domain::security::{ApiKeyRecord, DnsCredentialProfile, SecurityPolicy};
+use flm_core::domain::audit::{
+    AuditChainAlgorithm, AuditLogEntry, ChainedAuditLog, AUDIT_CHAIN_GENESIS,
+};
+use flm_core::domain::security::{
+    ApiKeyRecord, ApiKeyScopes, DnsCredentialProfile, SecurityPolicy,
+};
 use flm_core::error::RepoError;
 use flm_core::ports::SecurityRepository;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
-use tracing::error;
+use std::sync::{Arc, OnceLock};
+use tracing::{error, warn};
 
 /// Metadata for audit log entries (reduces clippy argument count).
 pub struct AuditLogMetadata<'a> {
@@ -27,6 +34,21 @@ pub struct IntrusionRequestContext<'a> {
     pub method: Option<&'a str>,
 }
 
+/// One metered request, stored in `usage_records`
+#[derive(Clone, Debug)]
+pub struct UsageRecord {
+    pub api_key_id: String,
+    pub endpoint: String,
+    pub engine_id: Option<String>,
+    pub model_id: Option<String>,
+    pub usage: flm_core::domain::chat::UsageStats,
+    pub latency_ms: u64,
+    /// Whether `usage` is an estimate (the engine reported none)
+    pub estimated: bool,
+    /// RFC3339, UTC
+    pub created_at: String,
+}
+
 /// Metadata about stored TLS certificates (ACME, packaged, etc.)
 #[derive(Clone, Debug)]
 pub struct CertificateMetadata {
@@ -42,6 +64,10 @@ pub struct CertificateMetadata {
 #[derive(Clone)]
 pub struct SqliteSecurityRepository {
     pool: SqlitePool,
+    /// HMAC key for the audit log hash chain (plain SHA-256 when `None`)
+    audit_hmac_key: Option<Arc<[u8]>>,
+    /// External audit sinks, shared by all clones (set once at startup)
+    audit_forwarder: Arc<OnceLock<AuditForwarder>>,
 }
 
 impl SqliteSecurityRepository {
@@ -91,7 +117,7 @@ impl SqliteSecurityRepository {
         // On Windows, file permissions are managed differently and default permissions are usually sufficient
         #[cfg(unix)]
         {
-            if let Err(_) = set_db_file_permissions(&db_path) {
+            if set_db_file_permissions(&db_path).is_err() {
                 warn!(
                     error_type = "db_permissions_failed",
                     "Failed to set database file permissions. Continuing anyway."
@@ -99,7 +125,32 @@ impl SqliteSecurityRepository {
             }
         }
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            audit_hmac_key: None,
+            audit_forwarder: Arc::default(),
+        })
+    }
+
+    /// Sign new audit log entries with HMAC-SHA256 under `key`
+    pub fn with_audit_hmac_key(mut self, key: Vec<u8>) -> Self {
+        self.audit_hmac_key = Some(key.into());
+        self
+    }
+
+    /// Forward audit log entries saved from now on (by this repository and
+    /// all its clones) to external sinks
+    ///
+    /// Only the first forwarder set is used.
+    pub fn set_audit_forwarder(&self, forwarder: AuditForwarder) {
+        if self.audit_forwarder.set(forwarder).is_err() {
+            warn!("Audit forwarder is already set; ignoring the new one");
+        }
+    }
+
+    /// Connection pool, for maintenance tasks such as retention pruning
+    pub(crate) fn pool(&self) -> &SqlitePool {
+        &self.pool
     }
 }
 
@@ -107,13 +158,14 @@ impl SqliteSecurityRepository {
 impl SecurityRepository for SqliteSecurityRepository {
     async fn save_api_key(&self, key: ApiKeyRecord) -> Result<(), RepoError> {
         sqlx::query(
-            "INSERT OR REPLACE INTO api_keys (id, label, hash, created_at, revoked_at) VALUES (?, ?, ?, ?, ?)",
+            "INSERT OR REPLACE INTO api_keys (id, label, hash, created_at, revoked_at, scopes_json) VALUES (?, ?, ?, ?, ?, ?)",
         )
         .bind(&key.id)
         .bind(&key.label)
         .bind(&key.hash)
         .bind(&key.created_at)
         .bind(&key.revoked_at)
+        .bind(api_key_scopes_json(&key.scopes)?)
         .execute(&self.pool)
         .await
         .map_err(|e| RepoError::IoError {
@@ -123,8 +175,8 @@ impl SecurityRepository for SqliteSecurityRepository {
     }
 
     async fn fetch_api_key(&self, id: &str) -> Result<Option<ApiKeyRecord>, RepoError> {
-        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
-            "SELECT id, label, hash, created_at, revoked_at FROM api_keys WHERE id = ?",
+        let row = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT id, label, hash, created_at, revoked_at, scopes_json FROM api_keys WHERE id = ?",
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -133,22 +185,12 @@ impl SecurityRepository for SqliteSecurityRepository {
             reason: format!("Failed to fetch API key: {e}"),
         })?;
 
-        if let Some((id, label, hash, created_at, revoked_at)) = row {
-            Ok(Some(ApiKeyRecord {
-                id,
-                label,
-                hash,
-                created_at,
-                revoked_at,
-            }))
-        } else {
-            Ok(None)
-        }
+        row.map(api_key_from_row).transpose()
     }
 
     async fn list_api_keys(&self) -> Result<Vec<ApiKeyRecord>, RepoError> {
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
-            "SELECT id, label, hash, created_at, revoked_at FROM api_keys ORDER BY created_at DESC",
+        let rows = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT id, label, hash, created_at, revoked_at, scopes_json FROM api_keys ORDER BY created_at DESC",
         )
         .fetch_all(&self.pool)
         .await
@@ -156,16 +198,7 @@ impl SecurityRepository for SqliteSecurityRepository {
             reason: format!("Failed to list API keys: {e}"),
         })?;
 
-        Ok(rows
-            .into_iter()
-            .map(|(id, label, hash, created_at, revoked_at)| ApiKeyRecord {
-                id,
-                label,
-                hash,
-                created_at,
-                revoked_at,
-            })
-            .collect())
+        rows.into_iter().map(api_key_from_row).collect()
     }
 
     async fn mark_api_key_revoked(&self, id: &str, revoked_at: &str) -> Result<(), RepoError> {
@@ -429,8 +462,99 @@ impl SqliteSecurityRepository {
             .collect())
     }
 
+    /// Add one request's token usage to the API key's daily total
+    ///
+    /// # Arguments
+    /// * `api_key_id` - The API key ID
+    /// * `usage_date` - UTC day (`YYYY-MM-DD`)
+    /// * `usage` - Token counts reported by the engine
+    pub async fn record_token_usage(
+        &self,
+        api_key_id: &str,
+        usage_date: &str,
+        usage: &flm_core::domain::chat::UsageStats,
+    ) -> Result<(), RepoError> {
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            "INSERT INTO token_usage (api_key_id, usage_date, prompt_tokens, completion_tokens, total_tokens, requests, updated_at) \
+             VALUES (?, ?, ?, ?, ?, 1, ?) \
+             ON CONFLICT(api_key_id, usage_date) DO UPDATE SET \
+             prompt_tokens = prompt_tokens + excluded.prompt_tokens, \
+             completion_tokens = completion_tokens + excluded.completion_tokens, \
+             total_tokens = total_tokens + excluded.total_tokens, \
+             requests = requests + 1, updated_at = excluded.updated_at",
+        )
+        .bind(api_key_id)
+        .bind(usage_date)
+        .bind(usage.prompt_tokens as i64)
+        .bind(usage.completion_tokens as i64)
+        .bind(usage.total_tokens as i64)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to record token usage: {e}"),
+        })?;
+        Ok(())
+    }
+
+    /// Save one request's usage for `flm usage report`
+    pub async fn save_usage_record(&self, record: &UsageRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            "INSERT INTO usage_records (api_key_id, endpoint, engine_id, model_id, prompt_tokens, completion_tokens, total_tokens, latency_ms, estimated, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&record.api_key_id)
+        .bind(&record.endpoint)
+        .bind(&record.engine_id)
+        .bind(&record.model_id)
+        .bind(record.usage.prompt_tokens as i64)
+        .bind(record.usage.completion_tokens as i64)
+        .bind(record.usage.total_tokens as i64)
+        .bind(record.latency_ms.min(i64::MAX as u64) as i64)
+        .bind(record.estimated)
+        .bind(&record.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to save usage record: {e}"),
+        })?;
+        Ok(())
+    }
+
+    /// Fetch an API key's total tokens for one UTC day and for that day's month
+    ///
+    /// # Returns
+    /// * `(day_tokens, month_tokens)`
+    pub async fn fetch_token_usage_totals(
+        &self,
+        api_key_id: &str,
+        usage_date: &str,
+    ) -> Result<(u64, u64), RepoError> {
+        let month_prefix = format!("{}%", usage_date.get(..8).unwrap_or(usage_date));
+        let (day, month) = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT \
+             COALESCE(SUM(CASE WHEN usage_date = ? THEN total_tokens ELSE 0 END), 0), \
+             COALESCE(SUM(total_tokens), 0) \
+             FROM token_usage WHERE api_key_id = ? AND usage_date LIKE ?",
+        )
+        .bind(usage_date)
+        .bind(api_key_id)
+        .bind(&month_prefix)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to fetch token usage: {e}"),
+        })?;
+        Ok((day.max(0) as u64, month.max(0) as u64))
+    }
+
     /// Save audit log entry
     ///
+    /// The entry is linked into the audit log hash chain
+    /// (`flm_core::domain::audit`), signed with the HMAC key if one is set,
+    /// and handed to the audit forwarder (if any) once committed.
+    ///
     /// # Arguments
     /// * `request_id` - Unique request identifier
     /// * `api_key_id` - API key ID (if authenticated)
@@ -452,23 +576,71 @@ impl SqliteSecurityRepository {
         event_type: Option<&str>,
         metadata: AuditLogMetadata<'_>,
     ) -> Result<(), RepoError> {
-        sqlx::query(
-            "INSERT INTO audit_logs (request_id, api_key_id, endpoint, status, latency_ms, event_type, severity, ip, details) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        )
-        .bind(request_id)
-        .bind(api_key_id)
-        .bind(endpoint)
-        .bind(status as i64)
-        .bind(latency_ms.map(|v| v as i64))
-        .bind(event_type)
-        .bind(metadata.severity)
-        .bind(metadata.ip)
-        .bind(metadata.details)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| RepoError::IoError {
+        let io_error = |e: sqlx::Error| RepoError::IoError {
             reason: format!("Failed to save audit log: {e}"),
-        })?;
+        };
+        let algorithm = AuditChainAlgorithm::for_key(self.audit_hmac_key.as_deref());
+
+        // BEGIN IMMEDIATE takes the write lock up front so that concurrent
+        // writers (including other processes) cannot link to the same entry
+        let mut conn = self.pool.acquire().await.map_err(io_error)?;
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut *conn)
+            .await
+            .map_err(io_error)?;
+        let result: Result<ChainedAuditLog, sqlx::Error> = async {
+            // After retention pruned every chained entry, continue from the
+            // hash of the last pruned one
+            let prev_hash = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT COALESCE((SELECT entry_hash FROM audit_logs WHERE entry_hash IS NOT NULL ORDER BY id DESC LIMIT 1), (SELECT entry_hash FROM audit_chain_anchors ORDER BY pruned_through_id DESC LIMIT 1))",
+            )
+            .fetch_one(&mut *conn)
+            .await?
+            .unwrap_or_else(|| AUDIT_CHAIN_GENESIS.to_string());
+
+            let id = sqlx::query(
+                "INSERT INTO audit_logs (request_id, api_key_id, endpoint, status, latency_ms, event_type, severity, ip, details, prev_hash, hash_alg) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(request_id)
+            .bind(api_key_id)
+            .bind(endpoint)
+            .bind(status as i64)
+            .bind(latency_ms.map(|v| v as i64))
+            .bind(event_type)
+            .bind(metadata.severity)
+            .bind(metadata.ip)
+            .bind(metadata.details)
+            .bind(&prev_hash)
+            .bind(algorithm.as_str())
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid();
+
+            // Hash the row as stored so defaults (created_at) are covered
+            let entry = fetch_audit_log_entry(&mut conn, id).await?;
+            let entry_hash = entry.chain_hash(&prev_hash, self.audit_hmac_key.as_deref());
+            sqlx::query("UPDATE audit_logs SET entry_hash = ? WHERE id = ?")
+                .bind(&entry_hash)
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+            Ok(ChainedAuditLog {
+                entry,
+                prev_hash: Some(prev_hash),
+                entry_hash: Some(entry_hash),
+                hash_alg: Some(algorithm.as_str().to_string()),
+            })
+        }
+        .await;
+
+        let end = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+        let ended = sqlx::query(end).execute(&mut *conn).await;
+        let saved = result
+            .and_then(|saved| ended.map(|_| saved))
+            .map_err(io_error)?;
+        if let Some(forwarder) = self.audit_forwarder.get() {
+            forwarder.forward(saved);
+        }
         Ok(())
     }
 
@@ -908,6 +1080,74 @@ impl SqliteSecurityRepository {
     }
 }
 
+/// `api_keys` row: id, label, hash, created_at, revoked_at, scopes_json
+type ApiKeyRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+fn api_key_from_row(row: ApiKeyRow) -> Result<ApiKeyRecord, RepoError> {
+    let (id, label, hash, created_at, revoked_at, scopes_json) = row;
+    // A key whose scopes cannot be read must not become unrestricted
+    let scopes = match scopes_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| RepoError::IoError {
+            reason: format!("Invalid scopes for API key {id}: {e}"),
+        })?,
+        None => ApiKeyScopes::default(),
+    };
+    Ok(ApiKeyRecord {
+        id,
+        label,
+        hash,
+        created_at,
+        revoked_at,
+        scopes,
+    })
+}
+
+/// Serialize scopes for `api_keys.scopes_json` (NULL when unrestricted)
+fn api_key_scopes_json(scopes: &ApiKeyScopes) -> Result<Option<String>, RepoError> {
+    if scopes.is_unrestricted() {
+        return Ok(None);
+    }
+    serde_json::to_string(scopes)
+        .map(Some)
+        .map_err(|e| RepoError::IoError {
+            reason: format!("Failed to serialize API key scopes: {e}"),
+        })
+}
+
+/// Read the chained fields of one audit log entry
+async fn fetch_audit_log_entry(
+    conn: &mut sqlx::SqliteConnection,
+    id: i64,
+) -> Result<AuditLogEntry, sqlx::Error> {
+    let (request_id, api_key_id, endpoint, status, latency_ms, event_type, severity, ip, details, created_at) =
+        sqlx::query_as::<_, (String, Option<String>, String, i64, Option<i64>, Option<String>, Option<String>, Option<String>, Option<String>, String)>(
+            "SELECT request_id, api_key_id, endpoint, status, latency_ms, event_type, severity, ip, details, created_at FROM audit_logs WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(conn)
+        .await?;
+    Ok(AuditLogEntry {
+        id,
+        request_id,
+        api_key_id,
+        endpoint,
+        status,
+        latency_ms,
+        event_type,
+        severity,
+        ip,
+        details,
+        created_at,
+    })
+}
+
 /// Set restrictive file permissions for database file (Unix only)
 ///
 /// Sets permissions to 600 (owner read+write, group/others no access).
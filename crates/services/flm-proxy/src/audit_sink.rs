@@ -0,0 +1,701 @@
+//! Forwarding of audit log entries to external sinks
+//!
+//! Entries saved by `SqliteSecurityRepository::save_audit_log` are also
+//! handed to an [`AuditForwarder`], which fans them out to the sinks
+//! configured in config.db (`audit_sinks`): RFC 5424 syslog over UDP/TCP,
+//! rotating JSONL files and batched HTTP webhooks. Each sink has its own
+//! task and bounded queue, so a slow or unreachable sink never delays
+//! request handling; entries that do not fit in a sink's queue are dropped
+//! for that sink (security.db still has them).
+//!
+//! See `docs/specs/PROXY_SPEC.md` for the wire formats.
+
+use flm_core::domain::audit::{AuditSink, AuditSinkConfig, ChainedAuditLog, SyslogTransport};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Entries queued per sink before new ones are dropped
+const SINK_QUEUE_CAPACITY: usize = 10_000;
+
+/// Longest wait between webhook retries
+const MAX_WEBHOOK_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Syslog APP-NAME when the sink does not set one
+const DEFAULT_SYSLOG_APP_NAME: &str = "flm-proxy";
+
+/// Fans audit log entries out to the configured sinks
+pub struct AuditForwarder {
+    sinks: Vec<SinkHandle>,
+}
+
+struct SinkHandle {
+    id: String,
+    sender: mpsc::Sender<Arc<ChainedAuditLog>>,
+    dropped: AtomicU64,
+}
+
+impl AuditForwarder {
+    /// Start one task per sink
+    ///
+    /// `tokens` holds webhook credentials by sink ID; `http_client` is used
+    /// for webhooks (built with the proxy's egress settings).
+    pub fn spawn(
+        sinks: Vec<AuditSink>,
+        tokens: &HashMap<String, String>,
+        http_client: reqwest::Client,
+    ) -> Self {
+        let sinks = sinks
+            .into_iter()
+            .map(|sink| {
+                let (sender, receiver) = mpsc::channel(SINK_QUEUE_CAPACITY);
+                match sink.config {
+                    AuditSinkConfig::Syslog {
+                        address,
+                        transport,
+                        facility,
+                        app_name,
+                    } => {
+                        let format = SyslogFormat {
+                            facility,
+                            hostname: syslog_hostname(),
+                            app_name: app_name
+                                .unwrap_or_else(|| DEFAULT_SYSLOG_APP_NAME.to_string()),
+                        };
+                        tokio::spawn(run_syslog_sink(
+                            sink.id.clone(),
+                            receiver,
+                            address,
+                            transport,
+                            format,
+                        ));
+                    }
+                    AuditSinkConfig::Jsonl {
+                        path,
+                        max_bytes,
+                        max_files,
+                    } => {
+                        let file = RotatingFile::new(PathBuf::from(path), max_bytes, max_files);
+                        tokio::spawn(run_jsonl_sink(sink.id.clone(), receiver, file));
+                    }
+                    AuditSinkConfig::Webhook {
+                        url,
+                        batch_size,
+                        flush_interval_secs,
+                        max_retries,
+                        auth_scheme,
+                    } => {
+                        let authorization = tokens.get(&sink.id).map(|token| {
+                            format!("{} {token}", auth_scheme.as_deref().unwrap_or("Bearer"))
+                        });
+                        let webhook = Webhook {
+                            client: http_client.clone(),
+                            url,
+                            authorization,
+                            max_retries,
+                            base_backoff: Duration::from_secs(1),
+                        };
+                        tokio::spawn(run_webhook_sink(
+                            sink.id.clone(),
+                            receiver,
+                            webhook,
+                            batch_size.max(1) as usize,
+                            Duration::from_secs(u64::from(flush_interval_secs.max(1))),
+                        ));
+                    }
+                }
+                SinkHandle {
+                    id: sink.id,
+                    sender,
+                    dropped: AtomicU64::new(0),
+                }
+            })
+            .collect();
+        Self { sinks }
+    }
+
+    /// Queue an entry for every sink without waiting
+    pub fn forward(&self, entry: ChainedAuditLog) {
+        let entry = Arc::new(entry);
+        for sink in &self.sinks {
+            if sink.sender.try_send(Arc::clone(&entry)).is_err() {
+                let dropped = sink.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped == 1 || dropped % 1000 == 0 {
+                    warn!(
+                        sink_id = %sink.id,
+                        dropped,
+                        "Audit sink queue is full or closed; dropping audit log entries"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// JSON representation shared by all sinks and `flm security audit-logs export`
+fn event_json(entry: &ChainedAuditLog) -> String {
+    serde_json::to_string(entry).unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Syslog (RFC 5424)
+// ---------------------------------------------------------------------------
+
+struct SyslogFormat {
+    facility: u8,
+    hostname: String,
+    app_name: String,
+}
+
+impl SyslogFormat {
+    /// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID - MSG` with the JSON
+    /// entry as MSG
+    fn format(&self, entry: &ChainedAuditLog) -> String {
+        let severity = match entry.entry.severity.as_deref() {
+            Some("critical") => 2,
+            Some("high") => 3,
+            Some("medium") => 4,
+            Some("low") => 5,
+            _ => 6,
+        };
+        let pri = u16::from(self.facility) * 8 + severity;
+        let timestamp =
+            chrono::NaiveDateTime::parse_from_str(&entry.entry.created_at, "%Y-%m-%d %H:%M:%S")
+                .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+                .unwrap_or_else(|_| "-".to_string());
+        let msgid = syslog_field(entry.entry.event_type.as_deref().unwrap_or("-"), 32);
+        format!(
+            "<{pri}>1 {timestamp} {} {} {} {msgid} - {}",
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            event_json(entry)
+        )
+    }
+}
+
+/// Header field limited to printable ASCII without spaces (RFC 5424 6.2)
+fn syslog_field(value: &str, max_len: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_len)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+fn syslog_hostname() -> String {
+    syslog_field(&sysinfo::System::host_name().unwrap_or_default(), 255)
+}
+
+enum SyslogConnection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+impl SyslogConnection {
+    async fn connect(address: &str, transport: SyslogTransport) -> std::io::Result<Self> {
+        match transport {
+            SyslogTransport::Udp => {
+                let target = tokio::net::lookup_host(address)
+                    .await?
+                    .next()
+                    .ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::NotFound, "address did not resolve")
+                    })?;
+                let bind = if target.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(bind).await?;
+                socket.connect(target).await?;
+                Ok(Self::Udp(socket))
+            }
+            SyslogTransport::Tcp => Ok(Self::Tcp(TcpStream::connect(address).await?)),
+        }
+    }
+
+    async fn send(&mut self, message: &str) -> std::io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.send(message.as_bytes()).await.map(|_| ()),
+            // Octet-counting framing (RFC 6587 3.4.1)
+            Self::Tcp(stream) => {
+                stream
+                    .write_all(format!("{} {message}", message.len()).as_bytes())
+                    .await
+            }
+        }
+    }
+}
+
+async fn run_syslog_sink(
+    id: String,
+    mut receiver: mpsc::Receiver<Arc<ChainedAuditLog>>,
+    address: String,
+    transport: SyslogTransport,
+    format: SyslogFormat,
+) {
+    let mut connection: Option<SyslogConnection> = None;
+    while let Some(entry) = receiver.recv().await {
+        let message = format.format(&entry);
+        // One reconnect per entry: a dropped TCP connection is re-established
+        for attempt in 0..2 {
+            if connection.is_none() {
+                match SyslogConnection::connect(&address, transport).await {
+                    Ok(connected) => connection = Some(connected),
+                    Err(e) => {
+                        warn!(sink_id = %id, error = %e, "Failed to connect to syslog sink {address}");
+                        break;
+                    }
+                }
+            }
+            let Some(open) = connection.as_mut() else {
+                break;
+            };
+            match open.send(&message).await {
+                Ok(()) => break,
+                Err(e) => {
+                    connection = None;
+                    if attempt == 1 {
+                        warn!(sink_id = %id, error = %e, "Failed to send audit log entry to syslog sink {address}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rotating JSONL file
+// ---------------------------------------------------------------------------
+
+/// Append-only file rotated to `path.1` … `path.{max_files}` at `max_bytes`
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: u32,
+    file: Option<tokio::fs::File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn new(path: PathBuf, max_bytes: u64, max_files: u32) -> Self {
+        Self {
+            path,
+            max_bytes,
+            max_files,
+            file: None,
+            size: 0,
+        }
+    }
+
+    async fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            self.size = file.metadata().await?.len();
+            self.file = Some(file);
+        }
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate().await?;
+            return Box::pin(self.write_line(line)).await;
+        }
+
+        let file = self.file.as_mut().expect("file opened above");
+        file.write_all(format!("{line}\n").as_bytes()).await?;
+        // tokio completes writes in the background until flushed
+        file.flush().await?;
+        self.size += len;
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        let rotated = |index: u32| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{index}"));
+            PathBuf::from(name)
+        };
+        remove_if_exists(&rotated(self.max_files)).await?;
+        for index in (1..self.max_files).rev() {
+            rename_if_exists(&rotated(index), &rotated(index + 1)).await?;
+        }
+        if self.max_files > 0 {
+            rename_if_exists(&self.path, &rotated(1)).await
+        } else {
+            remove_if_exists(&self.path).await
+        }
+    }
+}
+
+async fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+async fn rename_if_exists(from: &Path, to: &Path) -> std::io::Result<()> {
+    match tokio::fs::rename(from, to).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+async fn run_jsonl_sink(
+    id: String,
+    mut receiver: mpsc::Receiver<Arc<ChainedAuditLog>>,
+    mut file: RotatingFile,
+) {
+    while let Some(entry) = receiver.recv().await {
+        if let Err(e) = file.write_line(&event_json(&entry)).await {
+            file.file = None;
+            warn!(
+                sink_id = %id,
+                error = %e,
+                "Failed to write audit log entry to {}",
+                file.path.display()
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webhook
+// ---------------------------------------------------------------------------
+
+struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    authorization: Option<String>,
+    max_retries: u32,
+    base_backoff: Duration,
+}
+
+impl Webhook {
+    /// POST one batch as a JSON array, retrying connection errors, 408, 429
+    /// and 5xx with exponential backoff
+    async fn deliver(&self, batch: &[Arc<ChainedAuditLog>]) -> Result<(), String> {
+        let body = format!(
+            "[{}]",
+            batch
+                .iter()
+                .map(|entry| event_json(entry))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(authorization) = &self.authorization {
+                request = request.header(reqwest::header::AUTHORIZATION, authorization);
+            }
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status.is_server_error()
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT;
+                    if !retryable {
+                        return Err(format!("webhook answered {status}"));
+                    }
+                    format!("webhook answered {status}")
+                }
+                Err(e) => e.to_string(),
+            };
+            if attempt >= self.max_retries {
+                return Err(format!("{error} (after {} attempts)", attempt + 1));
+            }
+            let backoff = self
+                .base_backoff
+                .saturating_mul(1 << attempt.min(16))
+                .min(MAX_WEBHOOK_BACKOFF);
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+async fn run_webhook_sink(
+    id: String,
+    mut receiver: mpsc::Receiver<Arc<ChainedAuditLog>>,
+    webhook: Webhook,
+    batch_size: usize,
+    flush_interval: Duration,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    while let Some(first) = receiver.recv().await {
+        batch.push(first);
+        let deadline = tokio::time::Instant::now() + flush_interval;
+        while batch.len() < batch_size {
+            tokio::select! {
+                next = receiver.recv() => match next {
+                    Some(entry) => batch.push(entry),
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(deadline) => break,
+            }
+        }
+        if let Err(e) = webhook.deliver(&batch).await {
+            warn!(
+                sink_id = %id,
+                entries = batch.len(),
+                "Dropping audit log batch for webhook {}: {e}",
+                webhook.url
+            );
+        }
+        batch.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flm_core::domain::audit::AuditLogEntry;
+
+    fn entry(id: i64) -> ChainedAuditLog {
+        ChainedAuditLog {
+            entry: AuditLogEntry {
+                id,
+                request_id: format!("req-{id}"),
+                endpoint: "/v1/chat/completions".to_string(),
+                status: 403,
+                event_type: Some("ip_blocked".to_string()),
+                severity: Some("high".to_string()),
+                ip: Some("203.0.113.7".to_string()),
+                created_at: "2026-10-18 09:30:00".to_string(),
+                ..Default::default()
+            },
+            prev_hash: Some("0".repeat(64)),
+            entry_hash: Some("ab".repeat(32)),
+            hash_alg: Some("sha256".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_syslog_format_rfc5424() {
+        let format = SyslogFormat {
+            facility: 13,
+            hostname: "gateway".to_string(),
+            app_name: "flm-proxy".to_string(),
+        };
+        let message = format.format(&entry(7));
+        // facility 13 (log audit) * 8 + severity 3 (error) for "high"
+        let prefix = format!(
+            "<107>1 2026-10-18T09:30:00Z gateway flm-proxy {} ip_blocked - {{",
+            std::process::id()
+        );
+        assert!(message.starts_with(&prefix), "{message}");
+        let json: serde_json::Value =
+            serde_json::from_str(message.split_once(" - ").unwrap().1).unwrap();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["entry_hash"], "ab".repeat(32));
+
+        assert_eq!(syslog_field("auth failure\u{e9}", 32), "authfailure");
+        assert_eq!(syslog_field("", 32), "-");
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_sink_rotates_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("audit").join("audit.jsonl");
+        let line_len = event_json(&entry(1)).len() as u64 + 1;
+        let mut file = RotatingFile::new(path.clone(), line_len * 2, 2);
+        for id in 1..=7 {
+            file.write_line(&event_json(&entry(id))).await.unwrap();
+        }
+
+        let ids = |name: &str| -> Vec<i64> {
+            std::fs::read_to_string(temp_dir.path().join("audit").join(name))
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    serde_json::from_str::<serde_json::Value>(line).unwrap()["id"]
+                        .as_i64()
+                        .unwrap()
+                })
+                .collect()
+        };
+        assert_eq!(ids("audit.jsonl"), vec![7]);
+        assert_eq!(ids("audit.jsonl.1"), vec![5, 6]);
+        assert_eq!(ids("audit.jsonl.2"), vec![3, 4]);
+        assert!(!temp_dir.path().join("audit").join("audit.jsonl.3").exists());
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_sends_syslog_over_udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let forwarder = AuditForwarder::spawn(
+            vec![AuditSink {
+                id: "siem".to_string(),
+                config: AuditSinkConfig::Syslog {
+                    address: receiver.local_addr().unwrap().to_string(),
+                    transport: SyslogTransport::Udp,
+                    facility: 13,
+                    app_name: Some("flm-test".to_string()),
+                },
+                updated_at: String::new(),
+            }],
+            &HashMap::new(),
+            reqwest::Client::new(),
+        );
+        forwarder.forward(entry(1));
+
+        let mut buf = vec![0u8; 8192];
+        let len = tokio::time::timeout(Duration::from_secs(5), receiver.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        let message = String::from_utf8_lossy(&buf[..len]);
+        assert!(message.starts_with("<107>1 "), "{message}");
+        assert!(message.contains(" flm-test "), "{message}");
+        assert!(message.contains("\"request_id\":\"req-1\""), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_batches_and_retries() {
+        use axum::http::{HeaderMap, StatusCode};
+        use std::sync::Mutex;
+
+        // Authorization header and entry IDs of each accepted request
+        type Delivery = (Option<String>, Vec<i64>);
+        let received: Arc<Mutex<Vec<Delivery>>> = Arc::default();
+        let attempts = Arc::new(AtomicU64::new(0));
+        let app = axum::Router::new().route(
+            "/ingest",
+            axum::routing::post({
+                let received = Arc::clone(&received);
+                let attempts = Arc::clone(&attempts);
+                move |headers: HeaderMap, body: String| async move {
+                    // The first delivery fails and must be retried
+                    if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    let events: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+                    let authorization = headers
+                        .get("authorization")
+                        .map(|value| value.to_str().unwrap().to_string());
+                    received.lock().unwrap().push((
+                        authorization,
+                        events.iter().map(|e| e["id"].as_i64().unwrap()).collect(),
+                    ));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ingest", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let (sender, receiver) = mpsc::channel(16);
+        let webhook = Webhook {
+            client: reqwest::Client::new(),
+            url,
+            authorization: Some("Splunk token-123".to_string()),
+            max_retries: 2,
+            base_backoff: Duration::from_millis(10),
+        };
+        let task = tokio::spawn(run_webhook_sink(
+            "hec".to_string(),
+            receiver,
+            webhook,
+            2,
+            Duration::from_millis(200),
+        ));
+        for id in 1..=3 {
+            sender.send(Arc::new(entry(id))).await.unwrap();
+        }
+        drop(sender);
+        tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        let token = Some("Splunk token-123".to_string());
+        assert_eq!(
+            *received,
+            vec![(token.clone(), vec![1, 2]), (token, vec![3])]
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_saved_entries_reach_sinks() {
+        use crate::adapters::{AuditLogMetadata, SqliteSecurityRepository};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = SqliteSecurityRepository::new(temp_dir.path().join("security.db"))
+            .await
+            .unwrap();
+        let path = temp_dir.path().join("audit.jsonl");
+        // Set on a clone: every clone of the repository forwards
+        repo.clone().set_audit_forwarder(AuditForwarder::spawn(
+            vec![AuditSink {
+                id: "archive".to_string(),
+                config: AuditSinkConfig::Jsonl {
+                    path: path.display().to_string(),
+                    max_bytes: 1024 * 1024,
+                    max_files: 1,
+                },
+                updated_at: String::new(),
+            }],
+            &HashMap::new(),
+            reqwest::Client::new(),
+        ));
+        repo.save_audit_log(
+            "req-1",
+            None,
+            "/v1/models",
+            401,
+            Some(3),
+            Some("auth_failure"),
+            AuditLogMetadata {
+                severity: "medium",
+                ip: Some("198.51.100.2"),
+                details: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut line = String::new();
+        for _ in 0..50 {
+            line = std::fs::read_to_string(&path).unwrap_or_default();
+            if !line.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let event: ChainedAuditLog = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(event.entry.id, 1);
+        assert_eq!(event.entry.event_type.as_deref(), Some("auth_failure"));
+        assert_eq!(
+            event.entry_hash,
+            Some(event.entry.chain_hash(&"0".repeat(64), None))
+        );
+    }
+}
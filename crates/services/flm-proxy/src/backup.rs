@@ -0,0 +1,831 @@
+//! Encrypted, authenticated backups of security.db and config.db
+//!
+//! A snapshot is taken with SQLite's online backup API into an in-memory
+//! database, so a proxy writing to the file cannot produce a torn copy and the
+//! plaintext never touches the disk. The serialized image is sealed with
+//! AES-256-GCM; its tag authenticates the header as well as the data, so a
+//! backup with any modified byte does not open. The key is either derived from
+//! a passphrase with Argon2id (salt and cost parameters are in the header) or a
+//! random 256-bit key held in the OS keyring.
+//!
+//! File layout: `FLMBAK01`, the header length (u32, big endian), the header
+//! JSON, then the ciphertext followed by the tag.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use rusqlite::backup::Backup;
+use rusqlite::serialize::OwnedData;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+/// First bytes of every backup file
+const MAGIC: &[u8; 8] = b"FLMBAK01";
+
+/// First bytes of an SQLite database (legacy plaintext backups)
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// Backup format written by this version
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Generations kept per database when no retention is configured
+pub const DEFAULT_RETENTION: usize = 3;
+
+/// Length of a keyring-held backup key
+pub const KEY_LEN: usize = 32;
+
+const SALT_LEN: usize = 16;
+const MAX_HEADER_LEN: usize = 64 * 1024;
+
+/// Upper bounds for Argon2id parameters read from a backup header
+///
+/// The header is only authenticated after the key is derived, so a crafted
+/// file could otherwise ask for gigabytes of memory or hours of work.
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 16;
+const MAX_KDF_PARALLELISM: u32 = 16;
+const CIPHER: &str = "aes-256-gcm";
+
+/// Backup-related errors
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Not an encrypted FLM backup: {reason}")]
+    InvalidFormat { reason: String },
+
+    #[error("Backup could not be authenticated: wrong key, or the file was modified")]
+    AuthenticationFailed,
+
+    #[error("Backup was encrypted with a {expected} key, not a {actual} key")]
+    KeySourceMismatch {
+        expected: KeySource,
+        actual: KeySource,
+    },
+
+    #[error("Backed-up database failed its integrity check: {reason}")]
+    IntegrityCheckFailed { reason: String },
+
+    #[error("Key error: {reason}")]
+    Key { reason: String },
+
+    #[error("Legacy plaintext backups cannot be authenticated and are only restored when explicitly allowed")]
+    PlaintextNotAllowed,
+}
+
+/// Where the encryption key of a backup comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    Passphrase,
+    Keyring,
+}
+
+impl KeySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeySource::Passphrase => "passphrase",
+            KeySource::Keyring => "keyring",
+        }
+    }
+}
+
+impl std::fmt::Display for KeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Key material for sealing or opening a backup
+pub enum BackupKey {
+    /// A passphrase, stretched with Argon2id per backup
+    Passphrase(String),
+    /// A random key held in the OS keyring
+    Keyring([u8; KEY_LEN]),
+}
+
+impl BackupKey {
+    /// Generate a new random key for the keyring
+    pub fn generate() -> Result<Self, BackupError> {
+        let mut key = [0u8; KEY_LEN];
+        random_fill(&mut key)?;
+        Ok(BackupKey::Keyring(key))
+    }
+
+    /// Decode a keyring key stored with [`BackupKey::encode`]
+    pub fn decode(encoded: &str) -> Result<Self, BackupError> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| BackupError::Key {
+                reason: format!("stored backup key is not valid base64: {e}"),
+            })?;
+        let key: [u8; KEY_LEN] = bytes.try_into().map_err(|_| BackupError::Key {
+            reason: format!("stored backup key must be {KEY_LEN} bytes"),
+        })?;
+        Ok(BackupKey::Keyring(key))
+    }
+
+    /// Encode a keyring key for storage; passphrases are never stored
+    pub fn encode(&self) -> Option<String> {
+        match self {
+            BackupKey::Keyring(key) => Some(BASE64.encode(key)),
+            BackupKey::Passphrase(_) => None,
+        }
+    }
+
+    pub fn source(&self) -> KeySource {
+        match self {
+            BackupKey::Passphrase(_) => KeySource::Passphrase,
+            BackupKey::Keyring(_) => KeySource::Keyring,
+        }
+    }
+}
+
+impl std::fmt::Debug for BackupKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BackupKey::{}(<redacted>)", self.source())
+    }
+}
+
+/// Argon2id parameters of a passphrase-encrypted backup
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+/// Unencrypted (but authenticated) header of a backup file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format_version: u32,
+    /// File name of the backed-up database (`security.db` or `config.db`)
+    pub database: String,
+    pub created_at: String,
+    pub key_source: KeySource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kdf: Option<KdfParams>,
+    pub cipher: String,
+    pub nonce: String,
+    /// Size of the database image in bytes
+    pub size_bytes: u64,
+}
+
+/// What a backup file holds, or a legacy plaintext copy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackupKind {
+    Encrypted(BackupHeader),
+    LegacyPlaintext,
+}
+
+/// Result of a successful [`verify_backup`]
+#[derive(Clone, Debug)]
+pub struct VerifiedBackup {
+    pub header: BackupHeader,
+    /// Number of tables in the backed-up database
+    pub tables: u64,
+}
+
+/// Snapshot a live database with the online backup API
+///
+/// Returns the serialized database image. Pages are copied from a consistent
+/// read snapshot, so concurrent writers (including WAL checkpoints) cannot
+/// tear the copy.
+pub fn snapshot_database(path: &Path) -> Result<Vec<u8>, BackupError> {
+    if !path.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} does not exist", path.display()),
+        )
+        .into());
+    }
+    let source = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    source.busy_timeout(Duration::from_secs(5))?;
+    let mut memory = Connection::open_in_memory()?;
+    Backup::new(&source, &mut memory)?.run_to_completion(256, Duration::from_millis(25), None)?;
+
+    let image = memory.serialize(DatabaseName::Main)?.to_vec();
+    Ok(image)
+}
+
+/// Seal a database image into backup file contents
+pub fn seal_backup(database: &str, image: &[u8], key: &BackupKey) -> Result<Vec<u8>, BackupError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    random_fill(&mut nonce)?;
+    let (kdf, key_bytes) = match key {
+        BackupKey::Passphrase(passphrase) => {
+            let mut salt = [0u8; SALT_LEN];
+            random_fill(&mut salt)?;
+            let defaults = Params::default();
+            let kdf = KdfParams {
+                algorithm: "argon2id".to_string(),
+                salt: BASE64.encode(salt),
+                memory_kib: defaults.m_cost(),
+                iterations: defaults.t_cost(),
+                parallelism: defaults.p_cost(),
+            };
+            let key_bytes = derive_key(passphrase, &kdf)?;
+            (Some(kdf), key_bytes)
+        }
+        BackupKey::Keyring(key_bytes) => (None, *key_bytes),
+    };
+    let header = BackupHeader {
+        format_version: FORMAT_VERSION,
+        database: database.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        key_source: key.source(),
+        kdf,
+        cipher: CIPHER.to_string(),
+        nonce: BASE64.encode(nonce),
+        size_bytes: image.len() as u64,
+    };
+    let header_json = serde_json::to_vec(&header).map_err(|e| BackupError::InvalidFormat {
+        reason: format!("failed to encode header: {e}"),
+    })?;
+    let header_len = u32::try_from(header_json.len()).map_err(|_| BackupError::InvalidFormat {
+        reason: "header too large".to_string(),
+    })?;
+
+    let mut contents = Vec::with_capacity(12 + header_json.len() + image.len() + 16);
+    contents.extend_from_slice(MAGIC);
+    contents.extend_from_slice(&header_len.to_be_bytes());
+    contents.extend_from_slice(&header_json);
+    let mut sealed = image.to_vec();
+    aead_key(&key_bytes)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&contents),
+            &mut sealed,
+        )
+        .map_err(|_| BackupError::Key {
+            reason: "encryption failed".to_string(),
+        })?;
+    contents.extend_from_slice(&sealed);
+    Ok(contents)
+}
+
+/// Read the header of backup file contents without decrypting them
+pub fn read_header(contents: &[u8]) -> Result<BackupKind, BackupError> {
+    split_backup(contents).map(|parsed| match parsed {
+        Some(parsed) => BackupKind::Encrypted(parsed.header),
+        None => BackupKind::LegacyPlaintext,
+    })
+}
+
+/// Authenticate and decrypt backup file contents into a database image
+pub fn open_backup(
+    contents: &[u8],
+    key: &BackupKey,
+) -> Result<(BackupHeader, Vec<u8>), BackupError> {
+    let Some(SealedBackup {
+        header,
+        aad,
+        sealed,
+    }) = split_backup(contents)?
+    else {
+        return Err(BackupError::InvalidFormat {
+            reason: "this is a legacy plaintext backup".to_string(),
+        });
+    };
+    if header.key_source != key.source() {
+        return Err(BackupError::KeySourceMismatch {
+            expected: header.key_source,
+            actual: key.source(),
+        });
+    }
+    let key_bytes = match (key, &header.kdf) {
+        (BackupKey::Passphrase(passphrase), Some(kdf)) => derive_key(passphrase, kdf)?,
+        (BackupKey::Keyring(key_bytes), None) => *key_bytes,
+        _ => {
+            return Err(BackupError::InvalidFormat {
+                reason: "key derivation parameters do not match the key source".to_string(),
+            })
+        }
+    };
+    let nonce: [u8; NONCE_LEN] = BASE64
+        .decode(&header.nonce)
+        .ok()
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or_else(|| BackupError::InvalidFormat {
+            reason: "invalid nonce".to_string(),
+        })?;
+
+    let mut image = sealed.to_vec();
+    let plain_len = aead_key(&key_bytes)?
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut image,
+        )
+        .map_err(|_| BackupError::AuthenticationFailed)?
+        .len();
+    image.truncate(plain_len);
+    if image.len() as u64 != header.size_bytes {
+        return Err(BackupError::InvalidFormat {
+            reason: "database size does not match the header".to_string(),
+        });
+    }
+    Ok((header, image))
+}
+
+/// Authenticate a backup and check the database inside it
+pub fn verify_backup(contents: &[u8], key: &BackupKey) -> Result<VerifiedBackup, BackupError> {
+    let (header, image) = open_backup(contents, key)?;
+    let database = open_image(&image)?;
+    check_integrity(&database)?;
+    let tables = database.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(VerifiedBackup { header, tables })
+}
+
+/// Restore a backup over `target` with the online backup API
+///
+/// The backup is authenticated and integrity-checked before `target` is
+/// touched. Legacy plaintext backups carry nothing to authenticate and are
+/// rejected unless `allow_plaintext` is set.
+pub fn restore_backup(
+    contents: &[u8],
+    key: Option<&BackupKey>,
+    allow_plaintext: bool,
+    target: &Path,
+) -> Result<BackupKind, BackupError> {
+    let (kind, source) = match (read_header(contents)?, key) {
+        (BackupKind::LegacyPlaintext, _) if !allow_plaintext => {
+            return Err(BackupError::PlaintextNotAllowed)
+        }
+        (BackupKind::LegacyPlaintext, _) => (BackupKind::LegacyPlaintext, open_image(contents)?),
+        (BackupKind::Encrypted(_), Some(key)) => {
+            let (header, image) = open_backup(contents, key)?;
+            (BackupKind::Encrypted(header), open_image(&image)?)
+        }
+        (BackupKind::Encrypted(header), None) => {
+            return Err(BackupError::Key {
+                reason: format!("backup is encrypted with a {} key", header.key_source),
+            })
+        }
+    };
+    check_integrity(&source)?;
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut destination = Connection::open(target)?;
+    destination.busy_timeout(Duration::from_secs(5))?;
+    Backup::new(&source, &mut destination)?.run_to_completion(
+        256,
+        Duration::from_millis(25),
+        None,
+    )?;
+    Ok(kind)
+}
+
+/// File name of a backup of `database` taken at `timestamp`
+pub fn backup_file_name(database: &str, timestamp: &chrono::DateTime<chrono::Utc>) -> String {
+    format!("{database}.bak.{}", timestamp.format("%Y%m%dT%H%M%S%.3fZ"))
+}
+
+/// Snapshot, seal and write a backup of `db_path` into `dir`
+///
+/// Older backups of the same database beyond `retention` are removed; their
+/// paths are returned with the new backup's path.
+pub fn create_backup(
+    db_path: &Path,
+    dir: &Path,
+    key: &BackupKey,
+    retention: usize,
+) -> Result<(PathBuf, Vec<PathBuf>), BackupError> {
+    let database = db_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| BackupError::InvalidFormat {
+            reason: format!("invalid database path {}", db_path.display()),
+        })?;
+    let image = snapshot_database(db_path)?;
+    let contents = seal_backup(database, &image, key)?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(backup_file_name(database, &chrono::Utc::now()));
+    write_private(&path, &contents)?;
+    let removed = prune_backups(dir, database, retention.max(1))?;
+    Ok((path, removed))
+}
+
+/// Remove the oldest backups of `database` in `dir` beyond `retention`
+pub fn prune_backups(
+    dir: &Path,
+    database: &str,
+    retention: usize,
+) -> Result<Vec<PathBuf>, BackupError> {
+    let prefix = format!("{database}.bak.");
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .collect();
+    // Timestamps in the names sort chronologically
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(retention);
+    let mut removed = Vec::new();
+    for path in backups.into_iter().take(excess) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed.push(path),
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to remove old backup"),
+        }
+    }
+    Ok(removed)
+}
+
+/// Settings of the proxy's periodic backups
+#[derive(Debug)]
+pub struct BackupSchedule {
+    pub interval: Duration,
+    pub dir: PathBuf,
+    pub retention: usize,
+    pub databases: Vec<PathBuf>,
+    pub key: BackupKey,
+}
+
+/// Back up the scheduled databases every `schedule.interval`
+///
+/// The first backup is taken one interval after start, so restarting the
+/// proxy does not multiply backups. Failures are logged and retried at the
+/// next interval.
+pub fn spawn_scheduled_backups(schedule: BackupSchedule) -> tokio::task::JoinHandle<()> {
+    let schedule = std::sync::Arc::new(schedule);
+    tokio::spawn(async move {
+        let start = tokio::time::Instant::now() + schedule.interval;
+        let mut interval = tokio::time::interval_at(start, schedule.interval);
+        loop {
+            interval.tick().await;
+            for index in 0..schedule.databases.len() {
+                let task_schedule = schedule.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    let schedule = task_schedule.as_ref();
+                    create_backup(
+                        &schedule.databases[index],
+                        &schedule.dir,
+                        &schedule.key,
+                        schedule.retention,
+                    )
+                })
+                .await;
+                let database = schedule.databases[index].display();
+                match result {
+                    Ok(Ok((path, removed))) => info!(
+                        database = %database,
+                        backup = %path.display(),
+                        removed = removed.len(),
+                        "Scheduled backup created"
+                    ),
+                    Ok(Err(e)) => {
+                        error!(database = %database, error = %e, "Scheduled backup failed")
+                    }
+                    Err(e) => error!(error = %e, "Scheduled backup task panicked"),
+                }
+            }
+        }
+    })
+}
+
+/// Encrypted backup file split into its parts
+struct SealedBackup<'a> {
+    header: BackupHeader,
+    /// Signature, length and header, authenticated with the ciphertext
+    aad: &'a [u8],
+    /// Ciphertext followed by the GCM tag
+    sealed: &'a [u8],
+}
+
+fn split_backup(contents: &[u8]) -> Result<Option<SealedBackup<'_>>, BackupError> {
+    if contents.starts_with(SQLITE_MAGIC) {
+        return Ok(None);
+    }
+    let invalid = |reason: &str| BackupError::InvalidFormat {
+        reason: reason.to_string(),
+    };
+    if !contents.starts_with(MAGIC) {
+        return Err(invalid("missing FLMBAK01 signature"));
+    }
+    let len_bytes: [u8; 4] = contents
+        .get(8..12)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("truncated header"))?;
+    let header_len = u32::from_be_bytes(len_bytes) as usize;
+    if header_len > MAX_HEADER_LEN || contents.len() < 12 + header_len {
+        return Err(invalid("truncated header"));
+    }
+    let (aad, sealed) = contents.split_at(12 + header_len);
+    let header: BackupHeader = serde_json::from_slice(&aad[12..])
+        .map_err(|e| invalid(&format!("unreadable header: {e}")))?;
+    if header.format_version != FORMAT_VERSION {
+        return Err(invalid(&format!(
+            "unsupported format version {}",
+            header.format_version
+        )));
+    }
+    if header.cipher != CIPHER {
+        return Err(invalid(&format!("unsupported cipher {}", header.cipher)));
+    }
+    Ok(Some(SealedBackup {
+        header,
+        aad,
+        sealed,
+    }))
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> Result<[u8; KEY_LEN], BackupError> {
+    if kdf.algorithm != "argon2id" {
+        return Err(BackupError::InvalidFormat {
+            reason: format!("unsupported key derivation {}", kdf.algorithm),
+        });
+    }
+    if kdf.memory_kib > MAX_KDF_MEMORY_KIB
+        || kdf.iterations > MAX_KDF_ITERATIONS
+        || kdf.parallelism > MAX_KDF_PARALLELISM
+    {
+        return Err(BackupError::InvalidFormat {
+            reason: format!(
+                "key derivation parameters exceed the limits (memory {} KiB, {} iterations, parallelism {})",
+                kdf.memory_kib, kdf.iterations, kdf.parallelism
+            ),
+        });
+    }
+    let salt = BASE64
+        .decode(&kdf.salt)
+        .map_err(|_| BackupError::InvalidFormat {
+            reason: "invalid salt".to_string(),
+        })?;
+    let params = Params::new(
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| BackupError::InvalidFormat {
+        reason: format!("invalid key derivation parameters: {e}"),
+    })?;
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| BackupError::Key {
+            reason: format!("key derivation failed: {e}"),
+        })?;
+    Ok(key)
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> Result<LessSafeKey, BackupError> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| BackupError::Key {
+            reason: "invalid key length".to_string(),
+        })
+}
+
+fn random_fill(buf: &mut [u8]) -> Result<(), BackupError> {
+    SystemRandom::new().fill(buf).map_err(|_| BackupError::Key {
+        reason: "system random number generator failed".to_string(),
+    })
+}
+
+/// Open a database image in memory
+fn open_image(image: &[u8]) -> Result<Connection, BackupError> {
+    let Some(ptr) = (!image.is_empty())
+        .then(|| {
+            // SAFETY: sqlite3_malloc64 returns either null or a buffer of
+            // `image.len()` bytes, which OwnedData hands back to SQLite
+            unsafe { rusqlite::ffi::sqlite3_malloc64(image.len() as u64) }
+        })
+        .and_then(|ptr| NonNull::new(ptr.cast::<u8>()))
+    else {
+        return Err(BackupError::InvalidFormat {
+            reason: "empty database image".to_string(),
+        });
+    };
+    // SAFETY: `ptr` was allocated by sqlite3_malloc64 with room for the image
+    let data = unsafe {
+        std::ptr::copy_nonoverlapping(image.as_ptr(), ptr.as_ptr(), image.len());
+        // Images of WAL databases must be switched to rollback-journal mode
+        // (header bytes 18 and 19) to open in memory
+        if image.len() >= 20 {
+            *ptr.as_ptr().add(18) = 1;
+            *ptr.as_ptr().add(19) = 1;
+        }
+        OwnedData::from_raw_nonnull(ptr, image.len())
+    };
+    let mut connection = Connection::open_in_memory()?;
+    connection.deserialize(DatabaseName::Main, data, false)?;
+    Ok(connection)
+}
+
+fn check_integrity(database: &Connection) -> Result<(), BackupError> {
+    let result: String = database.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(BackupError::IntegrityCheckFailed { reason: result })
+    }
+}
+
+/// Write a file readable by its owner only
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), BackupError> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_database(dir: &Path) -> PathBuf {
+        let path = dir.join("security.db");
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 CREATE TABLE api_keys (id TEXT PRIMARY KEY, label TEXT);
+                 INSERT INTO api_keys VALUES ('k1', 'first'), ('k2', 'second');",
+            )
+            .unwrap();
+        path
+    }
+
+    fn passphrase() -> BackupKey {
+        BackupKey::Passphrase("correct horse battery staple".to_string())
+    }
+
+    #[test]
+    fn test_backup_round_trip_with_both_key_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = sample_database(dir.path());
+        let image = snapshot_database(&db_path).unwrap();
+
+        for key in [passphrase(), BackupKey::generate().unwrap()] {
+            let contents = seal_backup("security.db", &image, &key).unwrap();
+            assert!(!contents
+                .windows(b"second".len())
+                .any(|window| window == b"second"));
+
+            let verified = verify_backup(&contents, &key).unwrap();
+            assert_eq!(verified.header.database, "security.db");
+            assert_eq!(verified.header.key_source, key.source());
+            assert_eq!(verified.tables, 1);
+
+            let target = dir.path().join(format!("restored-{}.db", key.source()));
+            restore_backup(&contents, Some(&key), false, &target).unwrap();
+            let restored = Connection::open(&target).unwrap();
+            let count: i64 = restored
+                .query_row("SELECT COUNT(*) FROM api_keys", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 2);
+        }
+    }
+
+    #[test]
+    fn test_backup_rejects_tampering_and_wrong_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = snapshot_database(&sample_database(dir.path())).unwrap();
+        let contents = seal_backup("security.db", &image, &passphrase()).unwrap();
+
+        let wrong = BackupKey::Passphrase("wrong".to_string());
+        assert!(matches!(
+            verify_backup(&contents, &wrong),
+            Err(BackupError::AuthenticationFailed)
+        ));
+        assert!(matches!(
+            verify_backup(&contents, &BackupKey::generate().unwrap()),
+            Err(BackupError::KeySourceMismatch { .. })
+        ));
+
+        // Flipping a ciphertext byte or rewriting the header both break the tag
+        let mut flipped = contents.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            verify_backup(&flipped, &passphrase()),
+            Err(BackupError::AuthenticationFailed)
+        ));
+        let mut relabeled = contents.clone();
+        let at = relabeled
+            .windows(b"security.db".len())
+            .position(|window| window == b"security.db")
+            .unwrap();
+        relabeled[at..at + b"config.db!!".len()].copy_from_slice(b"config.db!!");
+        assert!(matches!(
+            verify_backup(&relabeled, &passphrase()),
+            Err(BackupError::AuthenticationFailed)
+        ));
+
+        let truncated = &contents[..contents.len() - 1];
+        assert!(verify_backup(truncated, &passphrase()).is_err());
+        assert!(matches!(
+            read_header(b"not a backup at all"),
+            Err(BackupError::InvalidFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plaintext_restore_needs_explicit_permission() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = snapshot_database(&sample_database(dir.path())).unwrap();
+        assert_eq!(read_header(&contents).unwrap(), BackupKind::LegacyPlaintext);
+
+        let target = dir.path().join("restored.db");
+        assert!(matches!(
+            restore_backup(&contents, None, false, &target),
+            Err(BackupError::PlaintextNotAllowed)
+        ));
+        assert!(!target.exists());
+        assert_eq!(
+            restore_backup(&contents, None, true, &target).unwrap(),
+            BackupKind::LegacyPlaintext
+        );
+    }
+
+    #[test]
+    fn test_derive_key_rejects_oversized_parameters() {
+        let defaults = Params::default();
+        let kdf = KdfParams {
+            algorithm: "argon2id".to_string(),
+            salt: BASE64.encode([0u8; SALT_LEN]),
+            memory_kib: defaults.m_cost(),
+            iterations: defaults.t_cost(),
+            parallelism: defaults.p_cost(),
+        };
+        for oversized in [
+            KdfParams {
+                memory_kib: u32::MAX,
+                ..kdf.clone()
+            },
+            KdfParams {
+                iterations: u32::MAX,
+                ..kdf.clone()
+            },
+            KdfParams {
+                parallelism: MAX_KDF_PARALLELISM + 1,
+                ..kdf.clone()
+            },
+        ] {
+            assert!(matches!(
+                derive_key("passphrase", &oversized),
+                Err(BackupError::InvalidFormat { .. })
+            ));
+        }
+        assert!(derive_key("passphrase", &kdf).is_ok());
+    }
+
+    #[test]
+    fn test_prune_backups_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "security.db.bak.20261001T000000.000Z",
+            "security.db.bak.20261003T000000.000Z",
+            "security.db.bak.20261002T000000.000Z",
+            "config.db.bak.20261001T000000.000Z",
+        ] {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+        let removed = prune_backups(dir.path(), "security.db", 2).unwrap();
+        assert_eq!(
+            removed,
+            vec![dir.path().join("security.db.bak.20261001T000000.000Z")]
+        );
+        assert!(dir
+            .path()
+            .join("config.db.bak.20261001T000000.000Z")
+            .exists());
+    }
+}
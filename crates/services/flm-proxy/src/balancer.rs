@@ -0,0 +1,311 @@
+//! Backend selection for model groups
+//!
+//! A model group (`flm://group/{name}`) maps one public model name to several
+//! engine/model backends. The balancer orders the registered members for each
+//! request according to the group's strategy; the controller tries them in
+//! that order and fails over on connection errors or 5xx responses.
+
+use chrono::{Duration as ChronoDuration, Utc};
+use flm_core::domain::models::{EngineId, LoadBalanceStrategy, ModelId};
+use flm_core::error::EngineError;
+use flm_core::ports::EngineHealthLogRepository;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// How long a latency weight is reused before the health log is queried again
+const LATENCY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Health-check window used for latency weighting
+const LATENCY_WINDOW_MINUTES: i64 = 60;
+
+/// Latency assumed for engines without recent health checks
+const DEFAULT_LATENCY_MS: f64 = 1000.0;
+
+/// Floor for the success rate so an engine with failing checks keeps a small share
+const MIN_SUCCESS_RATE: f64 = 0.05;
+
+/// Per-proxy load balancing state
+pub struct Balancer {
+    health_logs: Option<Arc<dyn EngineHealthLogRepository>>,
+    round_robin: Mutex<HashMap<String, usize>>,
+    in_flight: Mutex<HashMap<ModelId, usize>>,
+    latency_weights: Mutex<HashMap<EngineId, (Instant, f64)>>,
+}
+
+impl Balancer {
+    /// Create a balancer; without health logs every engine gets the same latency weight
+    pub fn new(health_logs: Option<Arc<dyn EngineHealthLogRepository>>) -> Self {
+        Self {
+            health_logs,
+            round_robin: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            latency_weights: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Order `members` (in configured priority order) for one request
+    ///
+    /// The first entry is the preferred backend; the rest are failover targets.
+    pub async fn order(
+        &self,
+        group: &str,
+        strategy: LoadBalanceStrategy,
+        members: &[(EngineId, ModelId)],
+    ) -> Vec<(EngineId, ModelId)> {
+        match strategy {
+            LoadBalanceStrategy::RoundRobin => self.rotate(group, members),
+            LoadBalanceStrategy::LeastInFlight => {
+                // Rotate first so ties are spread instead of always hitting the first member
+                let mut ordered = self.rotate(group, members);
+                let in_flight = lock(&self.in_flight);
+                ordered.sort_by_key(|(_, model_id)| in_flight.get(model_id).copied().unwrap_or(0));
+                ordered
+            }
+            LoadBalanceStrategy::LatencyWeighted => {
+                let mut weights = Vec::with_capacity(members.len());
+                for (engine_id, _) in members {
+                    weights.push(self.latency_weight(engine_id).await);
+                }
+                let roll = rand::thread_rng().gen::<f64>();
+                weighted_order(members, &weights, roll)
+            }
+        }
+    }
+
+    /// Count a request against `model_id` until the returned guard is dropped
+    pub fn acquire(self: &Arc<Self>, model_id: &str) -> InFlightGuard {
+        *lock(&self.in_flight)
+            .entry(model_id.to_string())
+            .or_insert(0) += 1;
+        InFlightGuard {
+            balancer: Arc::clone(self),
+            model_id: model_id.to_string(),
+        }
+    }
+
+    /// Number of requests currently in flight for `model_id`
+    pub fn in_flight(&self, model_id: &str) -> usize {
+        lock(&self.in_flight).get(model_id).copied().unwrap_or(0)
+    }
+
+    fn rotate(&self, group: &str, members: &[(EngineId, ModelId)]) -> Vec<(EngineId, ModelId)> {
+        if members.is_empty() {
+            return Vec::new();
+        }
+        let start = {
+            let mut counters = lock(&self.round_robin);
+            let counter = counters.entry(group.to_string()).or_insert(0);
+            let start = *counter % members.len();
+            *counter = counter.wrapping_add(1);
+            start
+        };
+        let mut ordered = members.to_vec();
+        ordered.rotate_left(start);
+        ordered
+    }
+
+    /// Weight proportional to success rate over average latency in the last hour
+    async fn latency_weight(&self, engine_id: &str) -> f64 {
+        if let Some((at, weight)) = lock(&self.latency_weights).get(engine_id) {
+            if at.elapsed() < LATENCY_CACHE_TTL {
+                return *weight;
+            }
+        }
+
+        let weight = match &self.health_logs {
+            Some(repo) => {
+                let end = Utc::now();
+                let start = end - ChronoDuration::minutes(LATENCY_WINDOW_MINUTES);
+                match repo
+                    .get_engine_summary(engine_id, Some(start), Some(end))
+                    .await
+                {
+                    Ok(summary) if summary.total_checks > 0 => latency_weight_from(
+                        summary.average_latency_ms.unwrap_or(DEFAULT_LATENCY_MS),
+                        summary.success_rate,
+                    ),
+                    Ok(_) => latency_weight_from(DEFAULT_LATENCY_MS, 1.0),
+                    Err(e) => {
+                        debug!(engine_id, error = %e, "No health summary, using default weight");
+                        latency_weight_from(DEFAULT_LATENCY_MS, 1.0)
+                    }
+                }
+            }
+            None => latency_weight_from(DEFAULT_LATENCY_MS, 1.0),
+        };
+
+        lock(&self.latency_weights).insert(engine_id.to_string(), (Instant::now(), weight));
+        weight
+    }
+}
+
+/// Decrements the in-flight count for a backend when dropped
+pub struct InFlightGuard {
+    balancer: Arc<Balancer>,
+    model_id: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = lock(&self.balancer.in_flight);
+        if let Some(count) = in_flight.get_mut(&self.model_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                in_flight.remove(&self.model_id);
+            }
+        }
+    }
+}
+
+/// Whether a failed attempt should be retried on the next backend
+///
+/// Only failures that say nothing about the request itself qualify: the
+/// backend was unreachable or answered with a 5xx.
+pub fn is_failover_error(error: &EngineError) -> bool {
+    match error {
+        EngineError::NetworkError { .. } => true,
+        EngineError::ApiError {
+            status_code: Some(status),
+            ..
+        } => *status >= 500,
+        _ => false,
+    }
+}
+
+fn latency_weight_from(latency_ms: f64, success_rate: f64) -> f64 {
+    success_rate.max(MIN_SUCCESS_RATE) / latency_ms.max(1.0)
+}
+
+/// Pick the first member by weighted draw (`roll` in `[0, 1)`), then the rest by weight
+fn weighted_order(
+    members: &[(EngineId, ModelId)],
+    weights: &[f64],
+    roll: f64,
+) -> Vec<(EngineId, ModelId)> {
+    let total: f64 = weights.iter().sum();
+    let mut first = 0;
+    if total > 0.0 {
+        let mut target = roll * total;
+        for (index, weight) in weights.iter().enumerate() {
+            first = index;
+            if target < *weight {
+                break;
+            }
+            target -= weight;
+        }
+    }
+
+    let mut rest: Vec<usize> = (0..members.len()).filter(|i| *i != first).collect();
+    rest.sort_by(|a, b| weights[*b].total_cmp(&weights[*a]));
+
+    std::iter::once(first)
+        .chain(rest)
+        .filter_map(|i| members.get(i).cloned())
+        .collect()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members() -> Vec<(EngineId, ModelId)> {
+        ["ollama", "vllm", "llamacpp"]
+            .iter()
+            .map(|e| (e.to_string(), format!("flm://{e}/llama3")))
+            .collect()
+    }
+
+    fn engines(ordered: &[(EngineId, ModelId)]) -> Vec<&str> {
+        ordered.iter().map(|(e, _)| e.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_rotates_per_group() {
+        let balancer = Balancer::new(None);
+        let members = members();
+
+        let first = balancer
+            .order("g", LoadBalanceStrategy::RoundRobin, &members)
+            .await;
+        let second = balancer
+            .order("g", LoadBalanceStrategy::RoundRobin, &members)
+            .await;
+        let other = balancer
+            .order("h", LoadBalanceStrategy::RoundRobin, &members)
+            .await;
+
+        assert_eq!(engines(&first), vec!["ollama", "vllm", "llamacpp"]);
+        assert_eq!(engines(&second), vec!["vllm", "llamacpp", "ollama"]);
+        assert_eq!(engines(&other), vec!["ollama", "vllm", "llamacpp"]);
+    }
+
+    #[tokio::test]
+    async fn test_least_in_flight_prefers_idle_backend() {
+        let balancer = Arc::new(Balancer::new(None));
+        let members = members();
+
+        let _a = balancer.acquire("flm://ollama/llama3");
+        let _b = balancer.acquire("flm://vllm/llama3");
+        let ordered = balancer
+            .order("g", LoadBalanceStrategy::LeastInFlight, &members)
+            .await;
+        assert_eq!(ordered[0].0, "llamacpp");
+
+        drop(_a);
+        assert_eq!(balancer.in_flight("flm://ollama/llama3"), 0);
+        assert_eq!(balancer.in_flight("flm://vllm/llama3"), 1);
+    }
+
+    #[test]
+    fn test_weighted_order_uses_roll_then_weight() {
+        let members = members();
+        let weights = [1.0, 3.0, 6.0];
+
+        assert_eq!(
+            engines(&weighted_order(&members, &weights, 0.05)),
+            vec!["ollama", "llamacpp", "vllm"]
+        );
+        assert_eq!(
+            engines(&weighted_order(&members, &weights, 0.2)),
+            vec!["vllm", "llamacpp", "ollama"]
+        );
+        assert_eq!(
+            engines(&weighted_order(&members, &weights, 0.99)),
+            vec!["llamacpp", "vllm", "ollama"]
+        );
+    }
+
+    #[test]
+    fn test_latency_weight_favours_fast_healthy_engines() {
+        assert!(latency_weight_from(100.0, 1.0) > latency_weight_from(1000.0, 1.0));
+        assert!(latency_weight_from(100.0, 1.0) > latency_weight_from(100.0, 0.5));
+        assert!(latency_weight_from(100.0, 0.0) > 0.0);
+    }
+
+    #[test]
+    fn test_is_failover_error() {
+        assert!(is_failover_error(&EngineError::NetworkError {
+            reason: "connection refused".to_string()
+        }));
+        assert!(is_failover_error(&EngineError::ApiError {
+            reason: "overloaded".to_string(),
+            status_code: Some(503),
+        }));
+        assert!(!is_failover_error(&EngineError::ApiError {
+            reason: "bad request".to_string(),
+            status_code: Some(400),
+        }));
+        assert!(!is_failover_error(&EngineError::InvalidResponse {
+            reason: "bad json".to_string()
+        }));
+    }
+}
@@ -0,0 +1,470 @@
+//! Concurrency caps and request queueing for engines and models
+//!
+//! Limits come from `config.db` (`concurrency_limits`) and are keyed either by
+//! engine ID or by model ID (`flm://{engine_id}/{model_name}`). Every request
+//! that reaches an engine (chat, completions, embeddings, images and audio)
+//! takes a slot from its model limit first and then from its engine limit.
+//! When a limit is saturated the request waits in that limit's bounded queue;
+//! freed slots are handed out round-robin across API keys so one busy client
+//! cannot starve the others, and in FIFO order within one key.
+
+use crate::metrics::Metrics;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::Response;
+use flm_core::domain::models::ConcurrencyLimit;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Why a request could not get a concurrency slot
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueueRejection {
+    /// The target's queue already holds `max_queue` requests
+    QueueFull {
+        target: String,
+        retry_after: Duration,
+    },
+    /// The request waited `queue_timeout_secs` without getting a slot
+    Timeout {
+        target: String,
+        retry_after: Duration,
+    },
+}
+
+impl QueueRejection {
+    /// Engine or model ID whose limit rejected the request
+    pub fn target(&self) -> &str {
+        match self {
+            Self::QueueFull { target, .. } | Self::Timeout { target, .. } => target,
+        }
+    }
+
+    /// Value for the `Retry-After` header (whole seconds, at least 1)
+    pub fn retry_after_secs(&self) -> u64 {
+        let (Self::QueueFull { retry_after, .. } | Self::Timeout { retry_after, .. }) = self;
+        retry_after.as_secs().max(1)
+    }
+
+    /// 429 when the queue is full, 503 when the wait timed out
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::QueueFull { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Timeout { .. } => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Client-facing error message
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::QueueFull { .. } => "Too many concurrent requests, the request queue is full",
+            Self::Timeout { .. } => "Timed out waiting for a free engine slot",
+        }
+    }
+
+    /// Add the `Retry-After` header to an error response for this rejection
+    pub fn with_retry_after(&self, mut response: Response) -> Response {
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(self.retry_after_secs()),
+        );
+        response
+    }
+}
+
+/// Queue fairness key: the request's API key ID, or one shared key without authentication
+pub fn client_key(api_key_id: Option<&String>) -> &str {
+    api_key_id.map(String::as_str).unwrap_or("anonymous")
+}
+
+/// Per-proxy concurrency limiter
+pub struct ConcurrencyLimiter {
+    gates: HashMap<String, Arc<Gate>>,
+    metrics: Arc<Metrics>,
+}
+
+impl ConcurrencyLimiter {
+    /// Build one gate per configured limit
+    pub fn new(limits: Vec<ConcurrencyLimit>, metrics: Arc<Metrics>) -> Self {
+        let gates = limits
+            .into_iter()
+            .filter(|limit| limit.max_concurrent > 0)
+            .map(|limit| {
+                let gate = Arc::new(Gate {
+                    limit: limit.clone(),
+                    metrics: Arc::clone(&metrics),
+                    state: Mutex::new(GateState::default()),
+                });
+                (limit.target, gate)
+            })
+            .collect();
+        Self { gates, metrics }
+    }
+
+    /// Whether any limit is configured
+    pub fn is_empty(&self) -> bool {
+        self.gates.is_empty()
+    }
+
+    /// Wait for a slot on `model_id` and `engine_id`, queueing fairly per `client`
+    ///
+    /// Targets without a configured limit are not restricted. The slots are
+    /// held until the returned permit is dropped.
+    pub async fn acquire(
+        &self,
+        engine_id: &str,
+        model_id: &str,
+        client: &str,
+    ) -> Result<ConcurrencyPermit, QueueRejection> {
+        let mut slots = Vec::new();
+        for target in [model_id, engine_id] {
+            if let Some(gate) = self.gates.get(target) {
+                match Gate::acquire(gate, client).await {
+                    Ok(slot) => slots.push(slot),
+                    Err(rejection) => {
+                        match rejection {
+                            QueueRejection::QueueFull { .. } => {
+                                self.metrics.increment_queue_rejected()
+                            }
+                            QueueRejection::Timeout { .. } => {
+                                self.metrics.increment_queue_timeouts()
+                            }
+                        }
+                        return Err(rejection);
+                    }
+                }
+            }
+        }
+        Ok(ConcurrencyPermit { _slots: slots })
+    }
+
+    /// Number of requests running against `target` on this proxy
+    pub fn in_flight(&self, target: &str) -> usize {
+        self.gates
+            .get(target)
+            .map(|gate| lock(&gate.state).in_flight as usize)
+            .unwrap_or(0)
+    }
+
+    /// Number of requests waiting for a slot on `target`
+    pub fn queued(&self, target: &str) -> usize {
+        self.gates
+            .get(target)
+            .map(|gate| lock(&gate.state).queued as usize)
+            .unwrap_or(0)
+    }
+}
+
+/// Holds the request's concurrency slots until dropped
+pub struct ConcurrencyPermit {
+    _slots: Vec<Slot>,
+}
+
+struct Gate {
+    limit: ConcurrencyLimit,
+    metrics: Arc<Metrics>,
+    state: Mutex<GateState>,
+}
+
+#[derive(Default)]
+struct GateState {
+    in_flight: u32,
+    queued: u32,
+    next_ticket: u64,
+    /// Clients with waiting requests, in the order they are served next
+    clients: VecDeque<String>,
+    waiters: HashMap<String, VecDeque<Waiter>>,
+}
+
+struct Waiter {
+    ticket: u64,
+    granted: oneshot::Sender<()>,
+}
+
+impl GateState {
+    fn remove_waiter(&mut self, client: &str, ticket: u64) -> bool {
+        let Some(queue) = self.waiters.get_mut(client) else {
+            return false;
+        };
+        let Some(position) = queue.iter().position(|w| w.ticket == ticket) else {
+            return false;
+        };
+        queue.remove(position);
+        if queue.is_empty() {
+            self.waiters.remove(client);
+            self.clients.retain(|c| c != client);
+        }
+        self.queued -= 1;
+        true
+    }
+
+    /// Next waiter in round-robin order across clients
+    fn pop_waiter(&mut self) -> Option<Waiter> {
+        let client = self.clients.pop_front()?;
+        let queue = self.waiters.get_mut(&client)?;
+        let waiter = queue.pop_front()?;
+        if queue.is_empty() {
+            self.waiters.remove(&client);
+        } else {
+            self.clients.push_back(client);
+        }
+        self.queued -= 1;
+        Some(waiter)
+    }
+}
+
+impl Gate {
+    fn retry_after(&self) -> Duration {
+        Duration::from_secs(u64::from(self.limit.queue_timeout_secs))
+    }
+
+    async fn acquire(gate: &Arc<Gate>, client: &str) -> Result<Slot, QueueRejection> {
+        let (ticket, receiver) = {
+            let mut guard = lock(&gate.state);
+            let state = &mut *guard;
+            if state.in_flight < gate.limit.max_concurrent && state.queued == 0 {
+                state.in_flight += 1;
+                return Ok(Slot {
+                    gate: Arc::clone(gate),
+                });
+            }
+            if state.queued >= gate.limit.max_queue {
+                return Err(QueueRejection::QueueFull {
+                    target: gate.limit.target.clone(),
+                    retry_after: gate.retry_after(),
+                });
+            }
+
+            let ticket = state.next_ticket;
+            state.next_ticket = state.next_ticket.wrapping_add(1);
+            let (granted, receiver) = oneshot::channel();
+            let queue = state.waiters.entry(client.to_string()).or_default();
+            if queue.is_empty() {
+                state.clients.push_back(client.to_string());
+            }
+            queue.push_back(Waiter { ticket, granted });
+            state.queued += 1;
+            gate.metrics.increment_queue_depth();
+            (ticket, receiver)
+        };
+
+        let started = Instant::now();
+        let mut waiting = Waiting {
+            gate: Arc::clone(gate),
+            client: client.to_string(),
+            ticket,
+            receiver,
+            admitted: false,
+        };
+        match tokio::time::timeout(gate.retry_after(), &mut waiting.receiver).await {
+            Ok(Ok(())) => {
+                waiting.admitted = true;
+                gate.metrics.record_queue_wait(started.elapsed());
+                Ok(Slot {
+                    gate: Arc::clone(gate),
+                })
+            }
+            _ => Err(QueueRejection::Timeout {
+                target: gate.limit.target.clone(),
+                retry_after: gate.retry_after(),
+            }),
+        }
+    }
+
+    /// Hand the slot to the next waiter, or free it when nobody is waiting
+    fn release(&self) {
+        let mut state = lock(&self.state);
+        while let Some(waiter) = state.pop_waiter() {
+            self.metrics.decrement_queue_depth();
+            // A closed receiver means the waiter gave up; try the next one
+            if waiter.granted.send(()).is_ok() {
+                return;
+            }
+        }
+        state.in_flight = state.in_flight.saturating_sub(1);
+    }
+}
+
+/// One slot on a gate, released when dropped
+struct Slot {
+    gate: Arc<Gate>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.gate.release();
+    }
+}
+
+/// A queued request; leaves the queue when dropped before being admitted
+struct Waiting {
+    gate: Arc<Gate>,
+    client: String,
+    ticket: u64,
+    receiver: oneshot::Receiver<()>,
+    admitted: bool,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        if self.admitted {
+            return;
+        }
+        let removed = lock(&self.gate.state).remove_waiter(&self.client, self.ticket);
+        if removed {
+            self.gate.metrics.decrement_queue_depth();
+        } else {
+            // The slot was handed over (the receiver is still open) after the
+            // wait ended; pass it on instead of leaking it
+            self.gate.release();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(target: &str, max_concurrent: u32, max_queue: u32, timeout: u32) -> ConcurrencyLimit {
+        ConcurrencyLimit {
+            target: target.to_string(),
+            max_concurrent,
+            max_queue,
+            queue_timeout_secs: timeout,
+            updated_at: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_targets_pass_through() {
+        let limiter = ConcurrencyLimiter::new(Vec::new(), Arc::new(Metrics::new()));
+        assert!(limiter.is_empty());
+        let _a = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-a")
+            .await
+            .unwrap();
+        let _b = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-a")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_full_rejects_with_retry_after() {
+        let metrics = Arc::new(Metrics::new());
+        let limiter = ConcurrencyLimiter::new(vec![limit("ollama", 1, 0, 7)], metrics.clone());
+
+        let permit = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-a")
+            .await
+            .unwrap();
+        assert_eq!(limiter.in_flight("ollama"), 1);
+
+        let rejection = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-b")
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(rejection, QueueRejection::QueueFull { .. }));
+        assert_eq!(rejection.target(), "ollama");
+        assert_eq!(rejection.retry_after_secs(), 7);
+        assert_eq!(
+            metrics
+                .queue_rejected
+                .load(std::sync::atomic::Ordering::Relaxed),
+            1
+        );
+
+        drop(permit);
+        assert_eq!(limiter.in_flight("ollama"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_times_out() {
+        let metrics = Arc::new(Metrics::new());
+        let limiter =
+            ConcurrencyLimiter::new(vec![limit("flm://ollama/llama3", 1, 4, 1)], metrics.clone());
+
+        let _permit = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-a")
+            .await
+            .unwrap();
+        let rejection = limiter
+            .acquire("ollama", "flm://ollama/llama3", "key-a")
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(rejection, QueueRejection::Timeout { .. }));
+        assert_eq!(limiter.queued("flm://ollama/llama3"), 0);
+        assert_eq!(
+            metrics
+                .queue_depth
+                .load(std::sync::atomic::Ordering::Relaxed),
+            0
+        );
+        assert_eq!(
+            metrics
+                .queue_timeouts
+                .load(std::sync::atomic::Ordering::Relaxed),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slots_are_shared_round_robin_across_keys() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(
+            vec![limit("ollama", 1, 16, 30)],
+            Arc::new(Metrics::new()),
+        ));
+        let first = limiter
+            .acquire("ollama", "flm://ollama/llama3", "busy")
+            .await
+            .unwrap();
+
+        // "busy" queues three requests before "quiet" queues one
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        for (client, label) in [
+            ("busy", "busy-1"),
+            ("busy", "busy-2"),
+            ("busy", "busy-3"),
+            ("quiet", "quiet-1"),
+        ] {
+            let task_limiter = Arc::clone(&limiter);
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _permit = task_limiter
+                    .acquire("ollama", "flm://ollama/llama3", client)
+                    .await
+                    .unwrap();
+                order_tx.send(label).unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            });
+            while !queued_label(&limiter, label) {
+                tokio::task::yield_now().await;
+            }
+        }
+        drop(order_tx);
+        drop(first);
+
+        let mut order = Vec::new();
+        while let Some(label) = order_rx.recv().await {
+            order.push(label);
+        }
+        assert_eq!(order, vec!["busy-1", "quiet-1", "busy-2", "busy-3"]);
+    }
+
+    /// Whether the request spawned as `label` (the n-th of its client) is queued
+    fn queued_label(limiter: &ConcurrencyLimiter, label: &str) -> bool {
+        let (client, n) = label.split_once('-').unwrap();
+        let n: usize = n.parse().unwrap();
+        let gate = limiter.gates.get("ollama").unwrap();
+        let state = lock(&gate.state);
+        state.waiters.get(client).map(VecDeque::len).unwrap_or(0) >= n
+    }
+}
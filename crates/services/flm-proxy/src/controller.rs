@@ -2,6 +2,9 @@
 //!
 //! This module implements the ProxyController trait using Axum.
 
+// why: error messages document the `flm://{engine_id}/{model}` format literally
+#![allow(clippy::literal_string_with_formatting_args)]
+
 use crate::certificate::{ensure_root_ca_artifacts, ensure_server_cert_artifacts};
 #[cfg(feature = "dns01-preview")]
 use crate::dns::dns_hook_from_credential;
@@ -16,13 +19,19 @@ use axum::{
 use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
 use flm_core::domain::chat::{
-    ChatMessage, ChatRole, MultimodalAttachment, MultimodalAttachmentKind,
+    ChatMessage, ChatRole, MultimodalAttachment, MultimodalAttachmentKind, ResponseFormat,
+    SamplingParams, TokenLogprob, ToolCall, ToolChoice, ToolDefinition,
+};
+use flm_core::domain::models::{
+    ApiPrompt, ApiPromptVariables, EngineCapabilities, ModelGroup, ModelId, ModelProfile,
+    ModelProfileDefaults,
 };
-use flm_core::domain::models::EngineCapabilities;
+use flm_core::domain::policy::PolicyDocument;
 use flm_core::domain::proxy::{
     AcmeChallengeKind, ProxyConfig, ProxyEgressConfig, ProxyEgressMode, ProxyHandle, ProxyMode,
     DEFAULT_TOR_SOCKS_ENDPOINT,
 };
+use flm_core::domain::security::ApiKeyScopes;
 use flm_core::error::ProxyError;
 use flm_core::ports::ProxyController;
 use futures::StreamExt;
@@ -46,6 +55,7 @@ use rustls_acme::{
 };
 use serde_json::json;
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::env;
 use std::io::Cursor;
 use std::net::{IpAddr, SocketAddr};
@@ -87,6 +97,9 @@ const DEFAULT_MAX_IMAGE_BYTES: usize = 8 * 1024 * 1024;
 const DEFAULT_MAX_REMOTE_IMAGE_BYTES: usize = 10 * 1024 * 1024;
 const DEFAULT_MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
 
+mod anthropic;
+mod ollama;
+
 use crate::adapters::{AuditLogMetadata, CertificateMetadata, SqliteSecurityRepository};
 use crate::metrics::{metrics_handler, Metrics};
 use crate::middleware::AppState;
@@ -442,14 +455,82 @@ async fn prepare_proxy_router(
         .unwrap_or_else(|| PathBuf::from("security.db"));
 
     // Create repositories
-    let security_repo = crate::adapters::SqliteSecurityRepository::new(&security_db_path)
+    let mut security_repo = crate::adapters::SqliteSecurityRepository::new(&security_db_path)
         .await
         .map_err(|e| ProxyError::InvalidConfig {
             reason: format!("Failed to create security repository: {e}"),
         })?;
+    if let Some(encoded_key) = config.resolved_audit_hmac_key.as_deref() {
+        let key = general_purpose::STANDARD.decode(encoded_key).map_err(|e| {
+            ProxyError::InvalidConfig {
+                reason: format!("Invalid audit log HMAC key: {e}"),
+            }
+        })?;
+        security_repo = security_repo.with_audit_hmac_key(key);
+        info!("Audit log entries are signed with HMAC-SHA256");
+    }
 
     let security_repo_for_state = Arc::new(security_repo.clone());
 
+    // Model profiles, API prompts and model groups live in config.db; without it requests run unmodified
+    let mut model_profiles: Option<Arc<dyn flm_core::ports::ModelProfileRepository>> = None;
+    let mut api_prompts: Option<Arc<dyn flm_core::ports::ApiPromptRepository>> = None;
+    let mut model_groups: Option<Arc<dyn flm_core::ports::ModelGroupRepository>> = None;
+    let mut health_logs: Option<Arc<dyn flm_core::ports::EngineHealthLogRepository>> = None;
+    let mut engine_registry: Option<Arc<dyn flm_core::ports::EngineRegistryRepository>> = None;
+    let mut concurrency_limits = Vec::new();
+    let mut audit_sinks = Vec::new();
+    if let Some(path) = config.config_db_path.as_ref() {
+        match flm_core::adapters::SqliteModelProfileRepository::new(path).await {
+            Ok(repo) => model_profiles = Some(Arc::new(repo)),
+            Err(e) => warn!(error = %e, "Model profiles unavailable, continuing without them"),
+        }
+        match flm_core::adapters::SqliteApiPromptRepository::new(path).await {
+            Ok(repo) => api_prompts = Some(Arc::new(repo)),
+            Err(e) => warn!(error = %e, "API prompts unavailable, continuing without them"),
+        }
+        match flm_core::adapters::SqliteModelGroupRepository::new(path).await {
+            Ok(repo) => model_groups = Some(Arc::new(repo)),
+            Err(e) => warn!(error = %e, "Model groups unavailable, continuing without them"),
+        }
+        match flm_core::adapters::SqliteEngineHealthLogRepository::new(path).await {
+            Ok(repo) => health_logs = Some(Arc::new(repo)),
+            Err(e) => {
+                warn!(error = %e, "Engine health logs unavailable, latency weighting disabled")
+            }
+        }
+        match flm_core::adapters::SqliteEngineRegistryRepository::new(path).await {
+            Ok(repo) => engine_registry = Some(Arc::new(repo)),
+            Err(e) => warn!(error = %e, "Engine registry unavailable, continuing without it"),
+        }
+        match flm_core::adapters::SqliteConcurrencyLimitRepository::new(path).await {
+            Ok(repo) => {
+                use flm_core::ports::ConcurrencyLimitRepository;
+                match repo.list_limits().await {
+                    Ok(limits) => concurrency_limits = limits,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to load concurrency limits, requests are not queued")
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "Concurrency limits unavailable, requests are not queued"),
+        }
+        match flm_core::adapters::SqliteAuditSinkRepository::new(path).await {
+            Ok(repo) => {
+                use flm_core::ports::AuditSinkRepository;
+                match repo.list_sinks().await {
+                    Ok(sinks) => audit_sinks = sinks,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to load audit sinks, audit logs stay in security.db only")
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Audit sinks unavailable, audit logs stay in security.db only")
+            }
+        }
+    }
+
     // Resolve egress connectivity (may mutate config and log audit events)
     let resolved_egress =
         resolve_egress_runtime(config.egress.clone(), &security_repo_for_state).await?;
@@ -460,6 +541,26 @@ async fn prepare_proxy_router(
     let process_controller: Box<dyn flm_core::ports::EngineProcessController + Send + Sync> =
         Box::new(crate::process_controller::NoopProcessController);
     let http_client_builder = http_client_builder_for_egress(&config.egress)?;
+
+    // Webhook sinks send through the same egress as engine requests
+    if !audit_sinks.is_empty() {
+        let webhook_client = http_client_builder_for_egress(&config.egress)?
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ProxyError::InvalidConfig {
+                reason: format!("Failed to create audit webhook HTTP client: {e}"),
+            })?;
+        let count = audit_sinks.len();
+        security_repo_for_state.set_audit_forwarder(crate::audit_sink::AuditForwarder::spawn(
+            audit_sinks,
+            &config.resolved_audit_sink_tokens,
+            webhook_client,
+        ));
+        info!(count, "Forwarding audit log entries to external sinks");
+    }
+    // The tokens now live in the webhook requests only
+    config.resolved_audit_sink_tokens.clear();
+
     let http_client: Box<dyn flm_core::ports::HttpClient + Send + Sync> = Box::new(
         crate::http_client::ReqwestHttpClient::from_builder(http_client_builder).map_err(|e| {
             ProxyError::InvalidConfig {
@@ -473,9 +574,73 @@ async fn prepare_proxy_router(
     let engine_repo: Box<dyn flm_core::ports::EngineRepository + Send + Sync> =
         Box::new(EngineRepositoryWrapper(engine_repo_impl.clone()));
 
-    let engine_service =
+    let mut engine_service =
         flm_core::services::EngineService::new(process_controller, http_client, engine_repo);
 
+    // Registered (e.g. remote) engines are served without any detection step
+    if let Some(registry) = engine_registry {
+        let registered = crate::engine_repo::register_from_registry(
+            engine_repo_impl.as_ref(),
+            registry.as_ref(),
+            &config.resolved_engine_credentials,
+        )
+        .await;
+        if registered > 0 {
+            info!(count = registered, "Loaded engines from registry");
+        }
+        engine_service = engine_service.with_engine_registry(registry);
+    }
+    // The secrets now live in the engines' HTTP clients only
+    config.resolved_engine_credentials.clear();
+
+    // Periodic backups are sealed with the keyring key the CLI resolved
+    if let (Some(schedule), Some(encoded_key)) = (
+        config.backup_schedule.clone(),
+        config.resolved_backup_key.take(),
+    ) {
+        match crate::backup::BackupKey::decode(&encoded_key) {
+            Ok(key) => {
+                let mut databases = vec![security_db_path.clone()];
+                databases.extend(config.config_db_path.as_ref().map(PathBuf::from));
+                crate::backup::spawn_scheduled_backups(crate::backup::BackupSchedule {
+                    interval: std::time::Duration::from_secs(
+                        u64::from(schedule.interval_hours.max(1)) * 3600,
+                    ),
+                    dir: PathBuf::from(&schedule.output_dir),
+                    retention: schedule.retention.max(1) as usize,
+                    databases,
+                    key,
+                });
+                info!(
+                    interval_hours = schedule.interval_hours,
+                    output_dir = %schedule.output_dir,
+                    "Periodic backups enabled"
+                );
+            }
+            Err(e) => warn!(error = %e, "Invalid backup key, periodic backups disabled"),
+        }
+    }
+
+    // Event tables are pruned per the policy's `retention` section
+    crate::retention::spawn_retention_task(security_repo_for_state.clone());
+
+    // A policy the proxy cannot read makes every request fail closed;
+    // fields it does not know are ignored
+    if let Ok(Some(policy)) = security_service.get_policy("default").await {
+        match PolicyDocument::parse_lenient(&policy.policy_json) {
+            Ok((_, ignored)) if !ignored.is_empty() => warn!(
+                ignored_fields = %ignored.join(", "),
+                "Security policy has fields this build does not know; they are ignored until the policy is replaced with `flm security policy set`"
+            ),
+            Ok(_) => {}
+            Err(reason) => error!(
+                error_type = "invalid_policy_json",
+                reason = %reason,
+                "Security policy is invalid; requests are denied until it is replaced with `flm security policy set`"
+            ),
+        }
+    }
+
     // Create IP blocklist and intrusion detection
     let ip_blocklist = Arc::new(IpBlocklist::new());
     let intrusion_detection = Arc::new(IntrusionDetection::new());
@@ -579,6 +744,13 @@ async fn prepare_proxy_router(
     };
 
     let metrics = Arc::new(Metrics::new());
+    let concurrency = Arc::new(crate::concurrency::ConcurrencyLimiter::new(
+        concurrency_limits,
+        metrics.clone(),
+    ));
+    if !concurrency.is_empty() {
+        info!("Concurrency limits enabled");
+    }
 
     // Load rate limit states from database on startup
     let rate_limit_state = Arc::new(RwLock::new(std::collections::HashMap::new()));
@@ -634,6 +806,14 @@ async fn prepare_proxy_router(
         security_repo: security_repo_for_state.clone(),
         engine_service: Arc::new(engine_service),
         engine_repo: engine_repo_impl,
+        model_profiles,
+        api_prompts,
+        model_groups,
+        balancer: Arc::new(crate::balancer::Balancer::new(health_logs)),
+        concurrency,
+        token_quotas: Arc::new(crate::token_quota::TokenQuotas::new(
+            security_repo_for_state.clone(),
+        )),
         rate_limit_state,
         ip_rate_limit_state,
         trusted_proxy_ips: config.trusted_proxy_ips.clone(),
@@ -662,7 +842,7 @@ async fn handle_metrics(
 
 /// Create the Axum router
 async fn create_router(
-    _config: ProxyConfig,
+    config: ProxyConfig,
     app_state: AppState,
 ) -> Result<axum::Router, ProxyError> {
     use axum::middleware as axum_middleware;
@@ -674,8 +854,17 @@ async fn create_router(
 
     // Create separate router for streaming endpoint (with 30-minute timeout)
     // Streaming requests can take longer, but we still need a timeout to prevent resource exhaustion
-    let streaming_router = Router::new()
+    let mut streaming_routes = Router::new()
         .route("/v1/chat/completions", post(handle_chat_completions))
+        .route("/v1/completions", post(handle_completions))
+        .route("/v1/messages", post(anthropic::handle_messages));
+    // Ollama-compatible API (opt-in via ProxyConfig::ollama_api)
+    if config.ollama_api {
+        streaming_routes = streaming_routes
+            .route("/api/chat", post(ollama::handle_chat))
+            .route("/api/generate", post(ollama::handle_generate));
+    }
+    let streaming_router = streaming_routes
         // Timeout middleware for streaming (30 minutes = 1800 seconds)
         .layer(axum::middleware::from_fn(
             crate::middleware::streaming_timeout_middleware,
@@ -740,7 +929,7 @@ async fn create_router(
         .with_state(app_state.clone());
 
     // Create router for non-streaming endpoints (with timeout)
-    let protected_router = Router::new()
+    let mut protected_routes = Router::new()
         .route("/health", get(handle_health))
         .route("/metrics", get(handle_metrics))
         .route("/v1/models", get(handle_models))
@@ -750,7 +939,13 @@ async fn create_router(
             "/v1/audio/transcriptions",
             post(handle_audio_transcriptions),
         )
-        .route("/v1/audio/speech", post(handle_audio_speech))
+        .route("/v1/audio/speech", post(handle_audio_speech));
+    if config.ollama_api {
+        protected_routes = protected_routes
+            .route("/api/tags", get(ollama::handle_tags))
+            .route("/api/show", post(ollama::handle_show));
+    }
+    let protected_router = protected_routes
         // Apply layers in order (outermost to innermost)
         // Audit logging (outermost layer to capture all requests)
         .layer(axum_middleware::from_fn_with_state(
@@ -1013,8 +1208,8 @@ async fn create_cors_layer(app_state: &AppState) -> tower_http::cors::CorsLayer
     };
 
     // Parse policy JSON
-    let policy_json: serde_json::Value = match serde_json::from_str(&policy.policy_json) {
-        Ok(json) => json,
+    let policy = match PolicyDocument::parse_lenient(&policy.policy_json) {
+        Ok((policy, _)) => policy,
         _ => {
             // Invalid policy JSON - deny all origins for security
             // Use predicate that always returns false to deny all origins
@@ -1026,45 +1221,40 @@ async fn create_cors_layer(app_state: &AppState) -> tower_http::cors::CorsLayer
     };
 
     // Get allowed origins from policy
-    if let Some(cors) = policy_json.get("cors") {
-        if let Some(allowed_origins) = cors.get("allowed_origins") {
-            if let Some(origins_array) = allowed_origins.as_array() {
-                if origins_array.is_empty() {
-                    // Empty array means deny all (fail closed for security)
-                    return TowerCorsLayer::new()
-                        .allow_origin(tower_http::cors::AllowOrigin::predicate(|_, _| false))
-                        .allow_methods([])
-                        .allow_headers([]);
-                }
+    if let Some(origins_array) = policy.cors.and_then(|cors| cors.allowed_origins) {
+        if origins_array.is_empty() {
+            // Empty array means deny all (fail closed for security)
+            return TowerCorsLayer::new()
+                .allow_origin(tower_http::cors::AllowOrigin::predicate(|_, _| false))
+                .allow_methods([])
+                .allow_headers([]);
+        }
 
-                // Convert to HeaderValue list
-                let origins: Vec<HeaderValue> = origins_array
-                    .iter()
-                    .filter_map(|v| v.as_str())
-                    .filter_map(|s| HeaderValue::from_str(s).ok())
-                    .collect();
-
-                if origins.is_empty() {
-                    // No valid origins - deny all (fail closed for security)
-                    return TowerCorsLayer::new()
-                        .allow_origin(tower_http::cors::AllowOrigin::predicate(|_, _| false))
-                        .allow_methods([])
-                        .allow_headers([]);
-                }
+        // Convert to HeaderValue list
+        let origins: Vec<HeaderValue> = origins_array
+            .iter()
+            .filter_map(|s| HeaderValue::from_str(s).ok())
+            .collect();
 
-                return TowerCorsLayer::new()
-                    .allow_origin(AllowOrigin::list(origins))
-                    .allow_methods([
-                        axum::http::Method::GET,
-                        axum::http::Method::POST,
-                        axum::http::Method::OPTIONS,
-                    ])
-                    .allow_headers([
-                        axum::http::header::AUTHORIZATION,
-                        axum::http::header::CONTENT_TYPE,
-                    ]);
-            }
+        if origins.is_empty() {
+            // No valid origins - deny all (fail closed for security)
+            return TowerCorsLayer::new()
+                .allow_origin(tower_http::cors::AllowOrigin::predicate(|_, _| false))
+                .allow_methods([])
+                .allow_headers([]);
         }
+
+        return TowerCorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods([
+                axum::http::Method::GET,
+                axum::http::Method::POST,
+                axum::http::Method::OPTIONS,
+            ])
+            .allow_headers([
+                axum::http::header::AUTHORIZATION,
+                axum::http::header::CONTENT_TYPE,
+            ]);
     }
 
     // Default: deny all (fail closed for security)
@@ -1088,6 +1278,7 @@ async fn handle_health() -> axum::Json<serde_json::Value> {
 #[axum::debug_handler]
 async fn handle_models(
     axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
 ) -> axum::Json<serde_json::Value> {
     // Get all registered engines
     let engines = state.engine_repo.list_registered().await;
@@ -1098,12 +1289,16 @@ async fn handle_models(
     for engine in engines {
         match engine.list_models().await {
             Ok(models) => {
+                // Models outside the API key's model scopes are not listed
+                let models = models
+                    .into_iter()
+                    .filter(|model| in_model_scope(api_key_scopes.as_deref(), &model.model_id));
                 for model in models {
                     let engine_id = model.engine_id;
                     let owned_by = format!("flm-{engine_id}");
                     let root_id = model.model_id.clone();
                     // Convert to OpenAI-compatible format
-                    let openai_model = serde_json::json!({
+                    let mut openai_model = serde_json::json!({
                         "id": model.model_id,
                         "object": "model",
                         "created": 0, // We don't track creation time
@@ -1112,6 +1307,14 @@ async fn handle_models(
                         "root": root_id,
                         "parent": null
                     });
+                    // Extension fields, only present when the engine reports them
+                    if let Some(context_length) = model.context_length {
+                        openai_model["context_length"] = context_length.into();
+                    }
+                    if let Some(capabilities) = model.capabilities {
+                        openai_model["capabilities"] =
+                            serde_json::to_value(capabilities).unwrap_or_default();
+                    }
                     all_models.push(openai_model);
                 }
             }
@@ -1141,6 +1344,64 @@ struct OpenAiChatRequest {
     max_tokens: Option<u32>,
     #[serde(default)]
     stop: Vec<String>,
+    #[serde(default)]
+    tools: Vec<OpenAiTool>,
+    #[serde(default)]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(default)]
+    response_format: Option<serde_json::Value>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    top_k: Option<u32>,
+    #[serde(default)]
+    seed: Option<i64>,
+    #[serde(default)]
+    presence_penalty: Option<f32>,
+    #[serde(default)]
+    frequency_penalty: Option<f32>,
+    #[serde(default, alias = "repetition_penalty")]
+    repeat_penalty: Option<f32>,
+    #[serde(default)]
+    n: Option<u32>,
+    #[serde(default)]
+    logit_bias: Option<BTreeMap<String, f32>>,
+    #[serde(default)]
+    logprobs: bool,
+    #[serde(default)]
+    top_logprobs: Option<u32>,
+}
+
+/// Request header selecting proxy-side validation of structured output
+const RESPONSE_VALIDATION_HEADER: &str = "x-flm-response-validation";
+
+/// Extra attempts made in `retry` validation mode before giving up
+const RESPONSE_VALIDATION_MAX_RETRIES: usize = 2;
+
+/// Proxy-side handling of completions that violate `response_format`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResponseValidation {
+    /// Trust the engine's constrained decoding (default)
+    Off,
+    /// Return an error when the completion does not conform
+    Reject,
+    /// Re-run the completion, then reject if it still does not conform
+    Retry,
+}
+
+/// Parse the response validation header
+fn parse_response_validation(
+    headers: &axum::http::HeaderMap,
+) -> Result<ResponseValidation, &'static str> {
+    let Some(value) = headers.get(RESPONSE_VALIDATION_HEADER) else {
+        return Ok(ResponseValidation::Off);
+    };
+    match value.to_str().map(|v| v.trim().to_ascii_lowercase()) {
+        Ok(v) if v == "off" => Ok(ResponseValidation::Off),
+        Ok(v) if v == "reject" => Ok(ResponseValidation::Reject),
+        Ok(v) if v == "retry" => Ok(ResponseValidation::Retry),
+        _ => Err("X-FLM-Response-Validation must be one of: off, reject, retry"),
+    }
 }
 
 /// Validate engine ID
@@ -1180,7 +1441,7 @@ fn validate_messages(messages: &[OpenAiMessage]) -> Result<(), &'static str> {
         return Err("Too many messages");
     }
     for msg in messages {
-        if msg.content.text_length() > 1_048_576 {
+        if msg.content.as_ref().map_or(0, |c| c.text_length()) > 1_048_576 {
             return Err("Message content too long");
         }
     }
@@ -1226,6 +1487,199 @@ fn validate_max_tokens(max_tokens: Option<u32>) -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Validate sampling parameters
+fn validate_sampling(sampling: &SamplingParams, stream: bool) -> Result<(), &'static str> {
+    if let Some(top_p) = sampling.top_p {
+        if !(0.0..=1.0).contains(&top_p) {
+            return Err("top_p must be between 0.0 and 1.0");
+        }
+    }
+    for penalty in [sampling.presence_penalty, sampling.frequency_penalty]
+        .into_iter()
+        .flatten()
+    {
+        if !(-2.0..=2.0).contains(&penalty) {
+            return Err("presence_penalty and frequency_penalty must be between -2.0 and 2.0");
+        }
+    }
+    if let Some(repeat_penalty) = sampling.repeat_penalty {
+        if repeat_penalty <= 0.0 || repeat_penalty > 10.0 {
+            return Err("repeat_penalty must be greater than 0.0 and at most 10.0");
+        }
+    }
+    if let Some(n) = sampling.n {
+        if n == 0 || n > 16 {
+            return Err("n must be between 1 and 16");
+        }
+        if n > 1 && stream {
+            return Err("n greater than 1 is not supported for streaming requests");
+        }
+    }
+    if sampling.logit_bias.len() > 300 {
+        return Err("logit_bias may contain at most 300 entries");
+    }
+    for (token, bias) in &sampling.logit_bias {
+        if token.parse::<u32>().is_err() {
+            return Err("logit_bias keys must be token IDs");
+        }
+        if !(-100.0..=100.0).contains(bias) {
+            return Err("logit_bias values must be between -100 and 100");
+        }
+    }
+    if let Some(top_logprobs) = sampling.top_logprobs {
+        if top_logprobs > 20 {
+            return Err("top_logprobs must be between 0 and 20");
+        }
+        if !sampling.logprobs {
+            return Err("top_logprobs requires logprobs to be true");
+        }
+    }
+    Ok(())
+}
+
+/// Validate tool definitions
+fn validate_tools(tools: &[OpenAiTool]) -> Result<(), &'static str> {
+    if tools.len() > 128 {
+        return Err("Too many tools");
+    }
+    for tool in tools {
+        if tool.kind != "function" {
+            return Err("Only function tools are supported");
+        }
+        let name = &tool.function.name;
+        if name.is_empty() || name.len() > 64 {
+            return Err("Tool name must be 1-64 characters");
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err("Tool name contains invalid characters");
+        }
+    }
+    Ok(())
+}
+
+/// Parse the OpenAI `tool_choice` field
+///
+/// Accepts `"auto"`, `"none"`, `"required"` or
+/// `{"type": "function", "function": {"name": ...}}` naming a declared tool.
+fn parse_tool_choice(
+    value: Option<serde_json::Value>,
+    tools: &[ToolDefinition],
+) -> Result<Option<ToolChoice>, &'static str> {
+    let value = match value {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(value) => value,
+    };
+    let choice = match value.as_str() {
+        Some("auto") => ToolChoice::Auto,
+        Some("none") => ToolChoice::None,
+        Some("required") => ToolChoice::Required,
+        Some(_) => return Err("tool_choice must be one of: auto, none, required"),
+        None => {
+            let name = value
+                .get("function")
+                .and_then(|f| f.get("name"))
+                .and_then(|n| n.as_str())
+                .ok_or("tool_choice object must name a function")?;
+            if !tools.iter().any(|tool| tool.name == name) {
+                return Err("tool_choice references an undeclared tool");
+            }
+            ToolChoice::Function {
+                name: name.to_string(),
+            }
+        }
+    };
+    if tools.is_empty() && matches!(choice, ToolChoice::Required) {
+        return Err("tool_choice requires at least one tool");
+    }
+    Ok(Some(choice))
+}
+
+/// Parse the OpenAI `response_format` field
+///
+/// Accepts `{"type": "text"}`, `{"type": "json_object"}` and
+/// `{"type": "json_schema", "json_schema": {"name": ..., "schema": {...}, "strict": ...}}`.
+/// The schema must compile so that proxy-side validation can use it.
+fn parse_response_format(
+    value: Option<serde_json::Value>,
+) -> Result<Option<ResponseFormat>, &'static str> {
+    let value = match value {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(value) => value,
+    };
+    match value.get("type").and_then(|t| t.as_str()) {
+        Some("text") => Ok(Some(ResponseFormat::Text)),
+        Some("json_object") => Ok(Some(ResponseFormat::JsonObject)),
+        Some("json_schema") => {
+            let spec = value
+                .get("json_schema")
+                .ok_or("json_schema response_format requires a json_schema object")?;
+            let name = spec
+                .get("name")
+                .and_then(|n| n.as_str())
+                .ok_or("json_schema.name is required")?;
+            if name.is_empty() || name.len() > 64 {
+                return Err("json_schema.name must be 1-64 characters");
+            }
+            if !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err("json_schema.name contains invalid characters");
+            }
+            let schema = spec
+                .get("schema")
+                .filter(|schema| schema.is_object())
+                .ok_or("json_schema.schema must be an object")?;
+            if jsonschema::JSONSchema::compile(schema).is_err() {
+                return Err("json_schema.schema is not a valid JSON Schema");
+            }
+            let strict = spec
+                .get("strict")
+                .and_then(|s| s.as_bool())
+                .unwrap_or(false);
+            Ok(Some(ResponseFormat::JsonSchema {
+                name: name.to_string(),
+                schema: schema.clone(),
+                strict,
+            }))
+        }
+        _ => Err("response_format.type must be one of: text, json_object, json_schema"),
+    }
+}
+
+/// Check a completion against the requested structured output format
+///
+/// Returns a short description of the first violation.
+fn check_structured_output(content: &str, format: &ResponseFormat) -> Result<(), String> {
+    let schema = match format {
+        ResponseFormat::Text => return Ok(()),
+        ResponseFormat::JsonObject => None,
+        ResponseFormat::JsonSchema { schema, .. } => Some(schema),
+    };
+    let instance: serde_json::Value = serde_json::from_str(content.trim())
+        .map_err(|e| format!("output is not valid JSON: {e}"))?;
+    let Some(schema) = schema else {
+        return if instance.is_object() {
+            Ok(())
+        } else {
+            Err("output is not a JSON object".to_string())
+        };
+    };
+    let compiled = jsonschema::JSONSchema::compile(schema)
+        .map_err(|e| format!("schema failed to compile: {e}"))?;
+    let result = compiled.validate(&instance);
+    match result {
+        Ok(()) => Ok(()),
+        Err(mut errors) => Err(errors
+            .next()
+            .map(|e| format!("{} (at '{}')", e, e.instance_path))
+            .unwrap_or_else(|| "output does not match schema".to_string())),
+    }
+}
+
 /// Validate embedding input
 fn validate_embedding_input(input: &serde_json::Value) -> Result<(), &'static str> {
     match input {
@@ -1257,8 +1711,44 @@ fn validate_embedding_input(input: &serde_json::Value) -> Result<(), &'static st
 #[derive(serde::Deserialize)]
 struct OpenAiMessage {
     role: String,
+    /// `null` is allowed for assistant messages that only carry tool calls
     #[serde(default)]
-    content: OpenAiMessageContent,
+    content: Option<OpenAiMessageContent>,
+    #[serde(default)]
+    tool_calls: Option<Vec<OpenAiToolCall>>,
+    #[serde(default)]
+    tool_call_id: Option<String>,
+}
+
+/// OpenAI-compatible tool definition
+#[derive(serde::Deserialize)]
+struct OpenAiTool {
+    #[serde(rename = "type")]
+    kind: String,
+    function: OpenAiFunctionDefinition,
+}
+
+#[derive(serde::Deserialize)]
+struct OpenAiFunctionDefinition {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    parameters: Option<serde_json::Value>,
+}
+
+/// OpenAI-compatible tool call (assistant message history)
+#[derive(serde::Deserialize)]
+struct OpenAiToolCall {
+    id: String,
+    function: OpenAiFunctionCall,
+}
+
+#[derive(serde::Deserialize)]
+struct OpenAiFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -1336,6 +1826,8 @@ struct OpenAiEmbeddingRequest {
 #[axum::debug_handler]
 async fn handle_embeddings(
     axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_id: Option<axum::Extension<String>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
     axum::Json(req): axum::Json<OpenAiEmbeddingRequest>,
 ) -> axum::response::Response {
     use flm_core::domain::chat::EmbeddingRequest;
@@ -1421,6 +1913,9 @@ async fn handle_embeddings(
         )
             .into_response();
     }
+    if let Err(response) = check_model_scope(api_key_scopes.as_ref(), &model_id) {
+        return response;
+    }
 
     // Validate embedding input
     if validate_embedding_input(&input).is_err() {
@@ -1503,6 +1998,12 @@ async fn handle_embeddings(
         input: input_strings,
     };
 
+    let _permit =
+        match acquire_engine_slots(&state, api_key_id.as_ref(), &engine_id, &model_id).await {
+            Ok(permit) => permit,
+            Err(response) => return response,
+        };
+
     // Call engine's embeddings method
     match engine.embeddings(embedding_req).await {
         Ok(response) => {
@@ -1608,11 +2109,30 @@ async fn convert_messages(
     let mut converted = Vec::with_capacity(raw_messages.len());
     for raw in raw_messages {
         let role = convert_role(&raw.role)?;
-        let (content, attachments) = convert_content(raw.content, client, limits).await?;
+        let (content, attachments) =
+            convert_content(raw.content.unwrap_or_default(), client, limits).await?;
+        let tool_calls = raw
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| ToolCall {
+                id: call.id,
+                name: call.function.name,
+                arguments: call.function.arguments,
+            })
+            .collect();
+        if role == ChatRole::Tool && raw.tool_call_id.is_none() {
+            return Err(invalid_request_error(
+                "Tool messages must include tool_call_id",
+                "invalid_messages",
+            ));
+        }
         converted.push(ChatMessage {
             role,
             content,
             attachments,
+            tool_calls,
+            tool_call_id: raw.tool_call_id,
         });
     }
     Ok(converted)
@@ -1879,6 +2399,41 @@ fn unsupported_modalities_response(modality: &str) -> axum::response::Response {
         .into_response()
 }
 
+/// Response for a parameter the selected engine cannot honour
+fn unsupported_parameter_response(reason: &str) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(json!({
+            "error": {
+                "message": reason,
+                "type": "invalid_request_error",
+                "code": "unsupported_parameter"
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Convert token log probabilities to OpenAI's `logprobs` object
+fn logprobs_to_openai(logprobs: &[TokenLogprob]) -> serde_json::Value {
+    json!({
+        "content": logprobs
+            .iter()
+            .map(|entry| {
+                json!({
+                    "token": entry.token,
+                    "logprob": entry.logprob,
+                    "top_logprobs": entry
+                        .top_logprobs
+                        .iter()
+                        .map(|top| json!({ "token": top.token, "logprob": top.logprob }))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
 fn has_image_attachments(messages: &[ChatMessage]) -> bool {
     messages.iter().any(|message| {
         message
@@ -1958,6 +2513,11 @@ fn extract_mime_type_from_filename(filename: &str) -> Option<String> {
 #[axum::debug_handler]
 async fn handle_chat_completions(
     axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    api_key_label: Option<axum::Extension<crate::middleware::ApiKeyLabel>>,
+    api_key_id: Option<axum::Extension<String>>,
+    token_budget: Option<axum::Extension<crate::token_quota::TokenBudget>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
     axum::Json(req): axum::Json<OpenAiChatRequest>,
 ) -> axum::response::Response {
     use flm_core::domain::chat::ChatRequest;
@@ -1969,158 +2529,222 @@ async fn handle_chat_completions(
         temperature,
         max_tokens,
         stop,
+        tools,
+        tool_choice,
+        response_format,
+        top_p,
+        top_k,
+        seed,
+        presence_penalty,
+        frequency_penalty,
+        repeat_penalty,
+        n,
+        logit_bias,
+        logprobs,
+        top_logprobs,
     } = req;
 
-    // Parse model ID (must be in flm://{engine_id}/{model} format)
-    if !model.starts_with("flm://") {
+    // Resolve the model profile (flm://profile/{label} or the model's own profile)
+    let target = match resolve_chat_target(&state, model).await {
+        Ok(target) => target,
+        Err((status, body)) => return (status, axum::Json(body)).into_response(),
+    };
+    let profile_defaults = &target.defaults;
+
+    // Explicit request values win over profile defaults, which win over engine defaults
+    let temperature = temperature.or(profile_defaults.temperature.map(f64::from));
+    let max_tokens = max_tokens.or(profile_defaults.max_tokens);
+    let stop = if stop.is_empty() {
+        profile_defaults.stop.clone()
+    } else {
+        stop
+    };
+
+    // Validate messages before processing
+    if validate_messages(&messages).is_err() {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid model ID format. Expected flm://{engine_id}/{model}",
+                    "message": "Invalid messages",
                     "type": "invalid_request_error",
-                    "code": "invalid_model"
+                    "code": "invalid_messages"
                 }
             })),
         )
             .into_response();
     }
 
-    // Extract engine_id and model_name from flm://{engine_id}/{model}
-    let model_without_prefix = match model.strip_prefix("flm://") {
-        Some(prefix) => prefix,
-        None => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                axum::Json(serde_json::json!({
-                    "error": {
-                        "message": "Invalid model ID format. Expected flm://{engine_id}/{model}",
-                        "type": "invalid_request_error",
-                        "code": "invalid_model"
-                    }
-                })),
-            )
-                .into_response();
-        }
-    };
-    let model_parts: Vec<&str> = model_without_prefix.splitn(2, '/').collect();
-    if model_parts.len() != 2 {
+    // Validate stop sequences
+    if validate_stop_sequences(&stop).is_err() {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid model ID format. Expected flm://{engine_id}/{model}",
+                    "message": "Invalid stop sequences",
                     "type": "invalid_request_error",
-                    "code": "invalid_model"
+                    "code": "invalid_stop"
                 }
             })),
         )
             .into_response();
     }
 
-    let engine_id = model_parts[0].to_string();
-    let model_name = model_parts[1].to_string();
-
-    // Validate engine_id and model_name
-    if validate_engine_id(&engine_id).is_err() {
+    // Validate temperature
+    if validate_temperature(temperature).is_err() {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid engine ID",
+                    "message": "Temperature must be between 0.0 and 2.0",
                     "type": "invalid_request_error",
-                    "code": "invalid_engine_id"
+                    "code": "invalid_temperature"
                 }
             })),
         )
             .into_response();
     }
 
-    if validate_model_name(&model_name).is_err() {
+    // Validate max_tokens
+    if validate_max_tokens(max_tokens).is_err() {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid model name",
+                    "message": "max_tokens exceeds maximum limit or is invalid",
                     "type": "invalid_request_error",
-                    "code": "invalid_model_name"
+                    "code": "invalid_max_tokens"
                 }
             })),
         )
             .into_response();
     }
 
-    // Validate messages before processing
-    if validate_messages(&messages).is_err() {
+    // Validate sampling parameters
+    let sampling = SamplingParams {
+        top_p,
+        top_k,
+        seed,
+        presence_penalty,
+        frequency_penalty,
+        repeat_penalty,
+        n,
+        logit_bias: logit_bias.unwrap_or_default(),
+        logprobs,
+        top_logprobs,
+    }
+    .or_defaults(&profile_defaults.sampling);
+    if let Err(message) = validate_sampling(&sampling, stream) {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid messages",
+                    "message": message,
                     "type": "invalid_request_error",
-                    "code": "invalid_messages"
+                    "code": "invalid_sampling"
                 }
             })),
         )
             .into_response();
     }
 
-    // Validate stop sequences
-    if validate_stop_sequences(&stop).is_err() {
+    // Validate tools and tool_choice
+    if let Err(message) = validate_tools(&tools) {
         return (
             axum::http::StatusCode::BAD_REQUEST,
             axum::Json(serde_json::json!({
                 "error": {
-                    "message": "Invalid stop sequences",
+                    "message": message,
                     "type": "invalid_request_error",
-                    "code": "invalid_stop"
-                }
-            })),
-        )
-            .into_response();
-    }
-
-    // Validate temperature
-    if validate_temperature(temperature).is_err() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(serde_json::json!({
-                "error": {
-                    "message": "Temperature must be between 0.0 and 2.0",
-                    "type": "invalid_request_error",
-                    "code": "invalid_temperature"
-                }
-            })),
-        )
-            .into_response();
-    }
-
-    // Validate max_tokens
-    if validate_max_tokens(max_tokens).is_err() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            axum::Json(serde_json::json!({
-                "error": {
-                    "message": "max_tokens exceeds maximum limit or is invalid",
-                    "type": "invalid_request_error",
-                    "code": "invalid_max_tokens"
+                    "code": "invalid_tools"
                 }
             })),
         )
             .into_response();
     }
+    let tools: Vec<ToolDefinition> = tools
+        .into_iter()
+        .map(|tool| ToolDefinition {
+            name: tool.function.name,
+            description: tool.function.description,
+            parameters: tool.function.parameters,
+        })
+        .collect();
+    let tool_choice = match parse_tool_choice(tool_choice, &tools) {
+        Ok(choice) => choice,
+        Err(message) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": {
+                        "message": message,
+                        "type": "invalid_request_error",
+                        "code": "invalid_tool_choice"
+                    }
+                })),
+            )
+                .into_response();
+        }
+    };
 
-    let engines = state.engine_repo.list_registered().await;
-    let engine = match engines.iter().find(|e| e.id() == engine_id) {
-        Some(e) => e,
-        None => {
+    // Validate response_format and the proxy-side validation mode
+    let response_format = match parse_response_format(response_format) {
+        Ok(format) => format,
+        Err(message) => {
             return (
-                axum::http::StatusCode::NOT_FOUND,
+                axum::http::StatusCode::BAD_REQUEST,
                 axum::Json(serde_json::json!({
                     "error": {
-                        "message": "Engine not found",
+                        "message": message,
                         "type": "invalid_request_error",
-                        "code": "engine_not_found"
+                        "code": "invalid_response_format"
+                    }
+                })),
+            )
+                .into_response();
+        }
+    };
+    let validation = match parse_response_validation(&headers) {
+        Ok(ResponseValidation::Off) => ResponseValidation::Off,
+        // A streamed completion has already been sent by the time it can be checked
+        Ok(_) if stream => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": {
+                        "message": "Response validation is not available for streaming requests",
+                        "type": "invalid_request_error",
+                        "code": "invalid_response_validation"
+                    }
+                })),
+            )
+                .into_response();
+        }
+        Ok(mode) => mode,
+        Err(message) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": {
+                        "message": message,
+                        "type": "invalid_request_error",
+                        "code": "invalid_response_validation"
+                    }
+                })),
+            )
+                .into_response();
+        }
+    };
+    let skip_api_prompt = match parse_api_prompt_opt_out(&headers) {
+        Ok(skip) => skip,
+        Err(message) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "error": {
+                        "message": message,
+                        "type": "invalid_request_error",
+                        "code": "invalid_api_prompt_header"
                     }
                 })),
             )
@@ -2128,11 +2752,21 @@ async fn handle_chat_completions(
         }
     };
 
+    // Resolve the backends to try: one engine, or the members of a model group
+    let (group, backends) =
+        match resolve_chat_backends(&state, &target, api_key_scopes.as_deref()).await {
+            Ok(resolved) => resolved,
+            Err((status, body)) => return (status, axum::Json(body)).into_response(),
+        };
+    let primary = &backends[0];
+    let engine = primary.engine.clone();
+    let primary_model_id = primary.model_id.clone();
+
     // Get model-specific capabilities if available
     let model_capabilities = match engine.list_models().await {
         Ok(models) => models
             .iter()
-            .find(|m| m.model_id == model)
+            .find(|m| m.model_id == primary_model_id)
             .and_then(|m| m.capabilities.clone()),
         Err(_) => None,
     };
@@ -2150,11 +2784,18 @@ async fn handle_chat_completions(
 
     let attachment_limits = AttachmentLimits::from_capabilities(&capabilities);
     let binary_client = HttpClient::new();
-    let messages = match convert_messages(messages, &binary_client, &attachment_limits).await {
+    let mut messages = match convert_messages(messages, &binary_client, &attachment_limits).await {
         Ok(parsed) => parsed,
         Err((status, body)) => return (status, axum::Json(body)).into_response(),
     };
 
+    // Inject the managed system prompt for this endpoint
+    let api_prompt = if skip_api_prompt {
+        None
+    } else {
+        apply_api_prompt(&state, &mut messages, api_key_label, &target.model).await
+    };
+
     if has_image_attachments(&messages) && !vision_supported {
         return unsupported_modalities_response("vision").into_response();
     }
@@ -2163,50 +2804,661 @@ async fn handle_chat_completions(
         return unsupported_modalities_response("audio").into_response();
     }
 
-    // Create ChatRequest
+    if !tools.is_empty() && !capabilities.tools {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(json!({
+                "error": {
+                    "message": "Tool calling is not supported by the selected engine",
+                    "type": "invalid_request_error",
+                    "code": "unsupported_tools"
+                }
+            })),
+        )
+            .into_response();
+    }
+
+    // Failover targets must accept the same inputs; the primary was checked above
+    let needs_vision = has_image_attachments(&messages);
+    let needs_audio = has_audio_attachments(&messages);
+    let needs_tools = !tools.is_empty();
+    let backends: Vec<ChatBackend> = backends
+        .into_iter()
+        .enumerate()
+        .filter(|(index, backend)| {
+            let caps = backend.engine.capabilities();
+            *index == 0
+                || ((!needs_vision || caps.vision_inputs)
+                    && (!needs_audio || caps.audio_inputs)
+                    && (!needs_tools || caps.tools))
+        })
+        .map(|(_, backend)| backend)
+        .collect();
+
+    // Create ChatRequest (engine and model are set per backend attempt)
     let chat_req = ChatRequest {
-        engine_id: engine_id.clone(),
-        model_id: model.clone(),
+        engine_id: engine.id(),
+        model_id: primary_model_id,
         messages,
         stream,
         temperature: temperature.map(|t| t as f32),
         max_tokens,
         stop,
         requested_modalities: Vec::new(),
+        tools,
+        tool_choice,
+        response_format,
+        sampling,
+    };
+
+    let tokens = match crate::token_quota::reserve_tokens(
+        token_budget.as_ref().map(|budget| &budget.0),
+        crate::token_quota::estimate_prompt_tokens(&chat_req.messages),
+    )
+    .await
+    {
+        Ok(tokens) => tokens,
+        Err(rejection) => return rejection.to_response(),
     };
 
     // Handle streaming vs non-streaming
-    if stream {
-        handle_chat_stream(engine, chat_req).await
+    let dispatch = ChatDispatch {
+        balancer: &state.balancer,
+        concurrency: &state.concurrency,
+        client: crate::concurrency::client_key(api_key_id.as_ref().map(|id| &id.0)),
+        tokens,
+    };
+    let mut response = if stream {
+        handle_chat_stream(&dispatch, &backends, chat_req, target.model.clone()).await
     } else {
-        handle_chat_non_stream(engine, chat_req).await
+        handle_chat_non_stream(
+            &dispatch,
+            &backends,
+            chat_req,
+            target.model.clone(),
+            validation,
+        )
+        .await
+    };
+    attach_chat_audit_details(&mut response, group, target.profile, api_prompt);
+    response
+}
+
+/// `api_prompts` ID of the chat completions endpoint
+const CHAT_COMPLETIONS_API_ID: &str = "chat_completions";
+
+/// Request header that opts out of the managed system prompt (`off`)
+const API_PROMPT_HEADER: &str = "x-flm-api-prompt";
+
+/// Parse the API prompt header, returning `true` when the client opted out
+fn parse_api_prompt_opt_out(headers: &axum::http::HeaderMap) -> Result<bool, &'static str> {
+    let Some(value) = headers.get(API_PROMPT_HEADER) else {
+        return Ok(false);
+    };
+    match value.to_str().map(|v| v.trim().to_ascii_lowercase()) {
+        Ok(v) if v == "on" => Ok(false),
+        Ok(v) if v == "off" => Ok(true),
+        _ => Err("X-FLM-API-Prompt must be one of: on, off"),
+    }
+}
+
+/// Load the managed system prompt for an endpoint
+///
+/// Lookup failures are logged and the request proceeds without a prompt.
+async fn load_api_prompt(state: &AppState, api_id: &str) -> Option<ApiPrompt> {
+    let repo = state.api_prompts.as_ref()?;
+    match repo.get(api_id).await {
+        Ok(prompt) => prompt,
+        Err(e) => {
+            warn!(error = %e, api_id, "Failed to load API prompt, continuing without it");
+            None
+        }
+    }
+}
+
+/// Engine segment that addresses a model profile by label (`flm://profile/{label}`)
+const MODEL_PROFILE_ALIAS: &str = "profile";
+
+/// Split a normalized `flm://{engine_id}/{model}` ID into its parts
+fn split_model_id(model_id: &str) -> Option<(String, String)> {
+    let (engine_id, model_name) = model_id.strip_prefix("flm://")?.split_once('/')?;
+    if engine_id.is_empty() || model_name.is_empty() {
+        return None;
+    }
+    Some((engine_id.to_string(), model_name.to_string()))
+}
+
+fn invalid_model_profile_error(label: &str, problem: &str) -> JsonError {
+    (
+        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+        serde_json::json!({
+            "error": {
+                "message": format!("Model profile '{label}' {problem}"),
+                "type": "server_error",
+                "code": "invalid_model_profile"
+            }
+        }),
+    )
+}
+
+/// Look up the model profile that applies to a chat request
+///
+/// `flm://profile/{label}` must name an existing profile (404 otherwise).
+/// For a plain `flm://{engine_id}/{model}` the model's `default` profile, or
+/// its most recently updated one, is applied when present. Lookup failures
+/// for plain model IDs are logged and the request proceeds without a profile.
+async fn resolve_model_profile(
+    state: &AppState,
+    engine_id: &str,
+    model_name: &str,
+    model: &str,
+) -> Result<Option<ModelProfile>, JsonError> {
+    let not_found = || {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            serde_json::json!({
+                "error": {
+                    "message": format!("Model profile '{model_name}' not found"),
+                    "type": "invalid_request_error",
+                    "code": "model_profile_not_found"
+                }
+            }),
+        )
+    };
+
+    let Some(repo) = state.model_profiles.as_ref() else {
+        return if engine_id == MODEL_PROFILE_ALIAS {
+            Err(not_found())
+        } else {
+            Ok(None)
+        };
+    };
+
+    if engine_id == MODEL_PROFILE_ALIAS {
+        return match repo.find_by_label(model_name).await {
+            Ok(Some(profile)) => Ok(Some(profile)),
+            Ok(None) => Err(not_found()),
+            Err(e) => {
+                error!(error = %e, "Failed to load model profile");
+                Err((
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    serde_json::json!({
+                        "error": {
+                            "message": "Failed to load model profile",
+                            "type": "server_error",
+                            "code": "model_profile_error"
+                        }
+                    }),
+                ))
+            }
+        };
     }
+
+    match repo.find_for_model(engine_id, model).await {
+        Ok(profile) => Ok(profile),
+        Err(e) => {
+            warn!(error = %e, model_id = %model, "Failed to load model profile, continuing without it");
+            Ok(None)
+        }
+    }
+}
+
+/// Engine segment that addresses a model group by name (`flm://group/{name}`)
+const MODEL_GROUP_ALIAS: &str = "group";
+
+/// One engine/model pair a chat request can be sent to
+struct ChatBackend {
+    engine: Arc<dyn flm_core::ports::LlmEngine>,
+    model_id: ModelId,
+}
+
+/// Look up the model group addressed by `flm://group/{name}`
+///
+/// Returns `Ok(None)` for any other model ID. A missing group is a 404.
+async fn resolve_model_group(
+    state: &AppState,
+    engine_id: &str,
+    name: &str,
+) -> Result<Option<ModelGroup>, JsonError> {
+    if engine_id != MODEL_GROUP_ALIAS {
+        return Ok(None);
+    }
+
+    let found = match state.model_groups.as_ref() {
+        Some(repo) => repo.find_group(name).await,
+        None => Ok(None),
+    };
+    match found {
+        Ok(Some(group)) => Ok(Some(group)),
+        Ok(None) => Err((
+            axum::http::StatusCode::NOT_FOUND,
+            serde_json::json!({
+                "error": {
+                    "message": format!("Model group '{name}' not found"),
+                    "type": "invalid_request_error",
+                    "code": "model_group_not_found"
+                }
+            }),
+        )),
+        Err(e) => {
+            error!(error = %e, "Failed to load model group");
+            Err((
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                serde_json::json!({
+                    "error": {
+                        "message": "Failed to load model group",
+                        "type": "server_error",
+                        "code": "model_group_error"
+                    }
+                }),
+            ))
+        }
+    }
+}
+
+/// Model a chat request addresses, with its model profile applied
+///
+/// Shared by every chat front-end (`/v1/chat/completions`, `/v1/messages`,
+/// `/api/chat`, `/api/generate`) so profiles, model groups and the managed
+/// system prompt apply whichever wire format the client speaks.
+struct ChatTarget {
+    /// `flm://{engine_id}/{model}` (or `flm://group/{name}`) after profile aliasing
+    model: String,
+    engine_id: String,
+    model_name: String,
+    profile: Option<ModelProfile>,
+    /// Profile defaults (empty without a profile)
+    defaults: ModelProfileDefaults,
+}
+
+/// Parse a chat model ID and resolve its model profile
+///
+/// `flm://profile/{label}` is replaced by the profile's target model. Errors
+/// are OpenAI-style bodies; other front-ends re-shape them.
+async fn resolve_chat_target(state: &AppState, model: String) -> Result<ChatTarget, JsonError> {
+    let Some((engine_id, model_name)) = split_model_id(&model) else {
+        return Err(invalid_request_error(
+            "Invalid model ID format. Expected flm://{engine_id}/{model}",
+            "invalid_model",
+        ));
+    };
+
+    let profile = resolve_model_profile(state, &engine_id, &model_name, &model).await?;
+    let defaults = match &profile {
+        Some(profile) => profile.defaults().map_err(|e| {
+            error!(profile_id = %profile.id, error = %e, "Invalid model profile parameters");
+            invalid_model_profile_error(&profile.label, "has invalid parameters")
+        })?,
+        None => ModelProfileDefaults::default(),
+    };
+    let (model, engine_id, model_name) = match profile
+        .as_ref()
+        .filter(|_| engine_id == MODEL_PROFILE_ALIAS)
+    {
+        Some(profile) => match split_model_id(&profile.model_id) {
+            Some((profile_engine, profile_model)) => {
+                (profile.model_id.clone(), profile_engine, profile_model)
+            }
+            None => {
+                return Err(invalid_model_profile_error(
+                    &profile.label,
+                    "targets an invalid model ID",
+                ))
+            }
+        },
+        None => (model, engine_id, model_name),
+    };
+
+    if validate_engine_id(&engine_id).is_err() {
+        return Err(invalid_request_error(
+            "Invalid engine ID",
+            "invalid_engine_id",
+        ));
+    }
+    if validate_model_name(&model_name).is_err() {
+        return Err(invalid_request_error(
+            "Invalid model name",
+            "invalid_model_name",
+        ));
+    }
+
+    Ok(ChatTarget {
+        model,
+        engine_id,
+        model_name,
+        profile,
+        defaults,
+    })
+}
+
+/// Resolve the backends to try: the target engine, or the members of its model group
+///
+/// The first backend is the primary; a 404 is returned when there is none.
+/// Group members outside the API key's model scopes are skipped unless the
+/// scopes allow the group itself, and a 403 is returned when none is left.
+async fn resolve_chat_backends(
+    state: &AppState,
+    target: &ChatTarget,
+    scopes: Option<&ApiKeyScopes>,
+) -> Result<(Option<ModelGroup>, Vec<ChatBackend>), JsonError> {
+    let group = resolve_model_group(state, &target.engine_id, &target.model_name).await?;
+    let engines = state.engine_repo.list_registered().await;
+    let mut backends = match &group {
+        Some(group) => order_group_backends(state, group, &engines).await,
+        None => engines
+            .iter()
+            .find(|e| e.id() == target.engine_id)
+            .map(|engine| {
+                vec![ChatBackend {
+                    engine: engine.clone(),
+                    model_id: target.model.clone(),
+                }]
+            })
+            .unwrap_or_default(),
+    };
+    if backends.is_empty() {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            serde_json::json!({
+                "error": {
+                    "message": "Engine not found",
+                    "type": "invalid_request_error",
+                    "code": "engine_not_found"
+                }
+            }),
+        ));
+    }
+    if let Some(scopes) = scopes.filter(|scopes| !scopes.allows_model(&target.model)) {
+        backends.retain(|backend| scopes.allows_model(&backend.model_id));
+        if backends.is_empty() {
+            return Err(model_scope_error(&target.model));
+        }
+    }
+    Ok((group, backends))
+}
+
+/// 403 for a model outside the API key's model scopes
+fn model_scope_error(model: &str) -> JsonError {
+    (
+        StatusCode::FORBIDDEN,
+        json!({
+            "error": {
+                "message": format!("API key is not allowed to use model '{model}'"),
+                "type": "access_denied",
+                "code": "model_not_allowed"
+            }
+        }),
+    )
+}
+
+/// Whether the API key's model scopes (if any) cover `model_id`
+fn in_model_scope(scopes: Option<&ApiKeyScopes>, model_id: &str) -> bool {
+    scopes.is_none_or(|scopes| scopes.allows_model(model_id))
+}
+
+/// Check a single-model request against the API key's model scopes
+fn check_model_scope(
+    scopes: Option<&axum::Extension<ApiKeyScopes>>,
+    model: &str,
+) -> Result<(), axum::response::Response> {
+    match scopes {
+        Some(axum::Extension(scopes)) if !scopes.allows_model(model) => {
+            let (status, body) = model_scope_error(model);
+            Err((status, axum::Json(body)).into_response())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Inject the managed system prompt of the chat endpoints, if one is configured
+///
+/// Returns the applied template for the audit log.
+async fn apply_api_prompt(
+    state: &AppState,
+    messages: &mut Vec<ChatMessage>,
+    api_key_label: Option<axum::Extension<crate::middleware::ApiKeyLabel>>,
+    model: &str,
+) -> Option<ApiPrompt> {
+    let prompt = load_api_prompt(state, CHAT_COMPLETIONS_API_ID).await?;
+    let variables = ApiPromptVariables {
+        api_key_label: api_key_label
+            .map(|axum::Extension(label)| label.0)
+            .unwrap_or_default(),
+        date: Utc::now().format("%Y-%m-%d").to_string(),
+        model_id: model.to_string(),
+    };
+    prompt.apply(messages, &variables);
+    Some(prompt)
+}
+
+/// Record the model group, model profile and managed prompt a chat request used
+fn attach_chat_audit_details(
+    response: &mut axum::response::Response,
+    group: Option<ModelGroup>,
+    profile: Option<ModelProfile>,
+    api_prompt: Option<ApiPrompt>,
+) {
+    let mut details = serde_json::Map::new();
+    if let Some(group) = group {
+        details.insert(
+            "model_group".to_string(),
+            serde_json::json!({
+                "name": group.name,
+                "strategy": group.strategy.as_str(),
+            }),
+        );
+    }
+    if let Some(profile) = profile {
+        details.insert(
+            "model_profile".to_string(),
+            serde_json::json!({
+                "id": profile.id,
+                "label": profile.label,
+                "version": profile.version,
+            }),
+        );
+    }
+    if let Some(prompt) = api_prompt {
+        details.insert(
+            "api_prompt".to_string(),
+            serde_json::json!({
+                "api_id": prompt.api_id,
+                "version": prompt.version,
+            }),
+        );
+    }
+    if !details.is_empty() {
+        response
+            .extensions_mut()
+            .insert(crate::middleware::AuditDetails(details.into()));
+    }
+}
+
+/// Order a group's registered members by its strategy
+///
+/// Members whose engine is not registered (or whose ID is malformed) are skipped.
+async fn order_group_backends(
+    state: &AppState,
+    group: &ModelGroup,
+    engines: &[Arc<dyn flm_core::ports::LlmEngine>],
+) -> Vec<ChatBackend> {
+    let members: Vec<(String, ModelId)> = group
+        .members
+        .iter()
+        .filter_map(|member| {
+            let (engine_id, _) = split_model_id(member)?;
+            engines
+                .iter()
+                .any(|e| e.id() == engine_id)
+                .then(|| (engine_id, member.clone()))
+        })
+        .collect();
+
+    state
+        .balancer
+        .order(&group.name, group.strategy, &members)
+        .await
+        .into_iter()
+        .filter_map(|(engine_id, model_id)| {
+            let engine = engines.iter().find(|e| e.id() == engine_id)?.clone();
+            Some(ChatBackend { engine, model_id })
+        })
+        .collect()
+}
+
+/// Per-request state shared by the chat dispatch helpers
+struct ChatDispatch<'a> {
+    balancer: &'a Arc<crate::balancer::Balancer>,
+    concurrency: &'a crate::concurrency::ConcurrencyLimiter,
+    /// Queue fairness key (API key ID)
+    client: &'a str,
+    /// Prompt-token reservation of the API key (authenticated requests)
+    tokens: Option<crate::token_quota::TokenReservation>,
+}
+
+impl ChatDispatch<'_> {
+    /// Attribute the request's usage to the backend it is about to be sent to
+    fn use_backend(&self, req: &flm_core::domain::chat::ChatRequest) {
+        if let Some(tokens) = &self.tokens {
+            tokens.set_target(&req.engine_id, &req.model_id);
+        }
+    }
+}
+
+/// Take the concurrency slots of a request sent straight to one engine
+///
+/// Completions, embeddings, images and audio take their slots here and chat
+/// requests in [`ChatDispatch`], so a limit covers every request its engine
+/// or model serves. The permit is held until the engine has answered, or
+/// until a streamed response ends.
+async fn acquire_engine_slots(
+    state: &AppState,
+    api_key_id: Option<&axum::Extension<String>>,
+    engine_id: &str,
+    model_id: &str,
+) -> Result<crate::concurrency::ConcurrencyPermit, axum::response::Response> {
+    let client = crate::concurrency::client_key(api_key_id.map(|id| &id.0));
+    state
+        .concurrency
+        .acquire(engine_id, model_id, client)
+        .await
+        .map_err(|rejection| queue_rejection_response(&rejection))
+}
+
+/// OpenAI-style error for a request that could not get a concurrency slot
+fn queue_rejection_response(
+    rejection: &crate::concurrency::QueueRejection,
+) -> axum::response::Response {
+    let (error_type, code) = match rejection {
+        crate::concurrency::QueueRejection::QueueFull { .. } => ("rate_limit_error", "queue_full"),
+        crate::concurrency::QueueRejection::Timeout { .. } => ("server_error", "queue_timeout"),
+    };
+    rejection.with_retry_after(
+        (
+            rejection.status(),
+            axum::Json(serde_json::json!({
+                "error": {
+                    "message": rejection.message(),
+                    "type": error_type,
+                    "code": code
+                }
+            })),
+        )
+            .into_response(),
+    )
+}
+
+/// OpenAI-style error for a completion that does not conform to `response_format`
+fn response_format_violation_response(reason: &str) -> axum::response::Response {
+    (
+        axum::http::StatusCode::BAD_GATEWAY,
+        axum::Json(serde_json::json!({
+            "error": {
+                "message": format!("Completion does not conform to response_format: {reason}"),
+                "type": "server_error",
+                "code": "response_format_violation"
+            }
+        })),
+    )
+        .into_response()
 }
 
 /// Handle non-streaming chat completion
 async fn handle_chat_non_stream(
-    engine: &std::sync::Arc<dyn flm_core::ports::LlmEngine>,
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
     req: flm_core::domain::chat::ChatRequest,
+    model_id: String,
+    validation: ResponseValidation,
 ) -> axum::response::Response {
-    let model_id = req.model_id.clone();
-    match engine.chat(req).await {
+    let result = match dispatch_chat(dispatch, backends, req, validation).await {
+        Ok(response) => Ok(response),
+        Err(ChatDispatchError::Queue(rejection)) => return queue_rejection_response(&rejection),
+        Err(ChatDispatchError::Violation(reason)) => {
+            return response_format_violation_response(&reason)
+        }
+        Err(ChatDispatchError::Engine(e)) => Err(e),
+    };
+    match result {
         Ok(response) => {
-            // Convert to OpenAI-compatible format
-            let choice = serde_json::json!({
-                "index": 0,
-                "message": {
-                    "role": "assistant",
-                    "content": response.messages.first().map(|m| &m.content).unwrap_or(&String::new())
-                },
-                "finish_reason": "stop"
-            });
+            // Convert to OpenAI-compatible format (one choice per generated message)
+            let mut choices: Vec<serde_json::Value> = response
+                .messages
+                .iter()
+                .enumerate()
+                .map(|(index, m)| {
+                    let mut message = serde_json::json!({
+                        "role": "assistant",
+                        "content": m.content
+                    });
+                    let finish_reason = if m.tool_calls.is_empty() {
+                        "stop"
+                    } else {
+                        if m.content.is_empty() {
+                            message["content"] = serde_json::Value::Null;
+                        }
+                        message["tool_calls"] = serde_json::Value::Array(
+                            m.tool_calls
+                                .iter()
+                                .map(|call| {
+                                    serde_json::json!({
+                                        "id": call.id,
+                                        "type": "function",
+                                        "function": {
+                                            "name": call.name,
+                                            "arguments": call.arguments
+                                        }
+                                    })
+                                })
+                                .collect(),
+                        );
+                        "tool_calls"
+                    };
+                    let mut choice = serde_json::json!({
+                        "index": index,
+                        "message": message,
+                        "finish_reason": finish_reason
+                    });
+                    if let Some(logprobs) = response.logprobs.get(index) {
+                        choice["logprobs"] = logprobs_to_openai(logprobs);
+                    }
+                    choice
+                })
+                .collect();
+            if choices.is_empty() {
+                choices.push(serde_json::json!({
+                    "index": 0,
+                    "message": { "role": "assistant", "content": "" },
+                    "finish_reason": "stop"
+                }));
+            }
 
             let openai_response = serde_json::json!({
                 "id": "chatcmpl-unknown",
                 "object": "chat.completion",
                 "created": 0,
                 "model": model_id,
-                "choices": [choice],
+                "choices": choices,
                 "usage": {
                     "prompt_tokens": response.usage.prompt_tokens,
                     "completion_tokens": response.usage.completion_tokens,
@@ -2216,6 +3468,9 @@ async fn handle_chat_non_stream(
 
             axum::Json(openai_response).into_response()
         }
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. }) => {
+            unsupported_parameter_response(&reason)
+        }
         Err(_) => (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             axum::Json(serde_json::json!({
@@ -2230,16 +3485,227 @@ async fn handle_chat_non_stream(
     }
 }
 
+/// Why no backend could serve a chat request
+enum ChatDispatchError {
+    Engine(flm_core::error::EngineError),
+    Queue(crate::concurrency::QueueRejection),
+    /// The completion still violated `response_format` after the allowed attempts
+    Violation(String),
+}
+
+/// Send a non-streaming chat request, failing over like [`start_chat_stream`]
+///
+/// Backends are tried in order; the next one is used only when the previous
+/// attempt failed with a connection error or a 5xx, or when its concurrency
+/// queue turned the request away. With proxy-side `validation` the completion
+/// is checked against `response_format` and, in `retry` mode, re-run on the
+/// same backend. Usage of every attempt that reached an engine is charged,
+/// including attempts discarded by validation.
+async fn dispatch_chat(
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
+    req: flm_core::domain::chat::ChatRequest,
+    validation: ResponseValidation,
+) -> Result<flm_core::domain::chat::ChatResponse, ChatDispatchError> {
+    let mut usage = None;
+    let result = dispatch_chat_attempts(dispatch, backends, req, validation, &mut usage).await;
+    if let (Some(tokens), Some(usage)) = (&dispatch.tokens, &usage) {
+        tokens.record(usage);
+    }
+    result
+}
+
+/// Attempt loop of [`dispatch_chat`], summing the usage of each engine response into `usage`
+async fn dispatch_chat_attempts(
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
+    mut req: flm_core::domain::chat::ChatRequest,
+    validation: ResponseValidation,
+    usage: &mut Option<flm_core::domain::chat::UsageStats>,
+) -> Result<flm_core::domain::chat::ChatResponse, ChatDispatchError> {
+    let max_attempts = match validation {
+        ResponseValidation::Retry => 1 + RESPONSE_VALIDATION_MAX_RETRIES,
+        _ => 1,
+    };
+    let format = req
+        .response_format
+        .clone()
+        .filter(|_| validation != ResponseValidation::Off);
+    let mut remaining = backends.iter().peekable();
+    'backends: while let Some(backend) = remaining.next() {
+        req.engine_id = backend.engine.id();
+        req.model_id = backend.model_id.clone();
+        dispatch.use_backend(&req);
+        let mut attempt = 0;
+        loop {
+            let permit = match dispatch
+                .concurrency
+                .acquire(&req.engine_id, &backend.model_id, dispatch.client)
+                .await
+            {
+                Ok(permit) => permit,
+                Err(rejection) if remaining.peek().is_some() => {
+                    warn!(
+                        engine_id = %req.engine_id,
+                        target = rejection.target(),
+                        "Backend queue rejected the request, failing over to next model group member"
+                    );
+                    continue 'backends;
+                }
+                Err(rejection) => return Err(ChatDispatchError::Queue(rejection)),
+            };
+            attempt += 1;
+            let result = {
+                let _permit = permit;
+                let _in_flight = dispatch.balancer.acquire(&backend.model_id);
+                backend.engine.chat(req.clone()).await
+            };
+            let response = match result {
+                Ok(response) => response,
+                Err(e) if crate::balancer::is_failover_error(&e) && remaining.peek().is_some() => {
+                    warn!(
+                        engine_id = %req.engine_id,
+                        error_type = engine_error_type(&e),
+                        "Backend failed, failing over to next model group member"
+                    );
+                    continue 'backends;
+                }
+                Err(e) => return Err(ChatDispatchError::Engine(e)),
+            };
+            *usage = Some(match usage.take() {
+                Some(total) => add_usage(total, &response.usage),
+                None => response.usage.clone(),
+            });
+
+            let violation = format.as_ref().and_then(|format| {
+                response
+                    .messages
+                    .iter()
+                    .filter(|m| m.tool_calls.is_empty())
+                    .find_map(|m| check_structured_output(&m.content, format).err())
+            });
+            match violation {
+                None => return Ok(response),
+                Some(_) if attempt < max_attempts => {
+                    warn!(
+                        attempt,
+                        "Completion did not match response_format, retrying"
+                    );
+                }
+                Some(reason) => return Err(ChatDispatchError::Violation(reason)),
+            }
+        }
+    }
+
+    Err(ChatDispatchError::Engine(
+        flm_core::error::EngineError::NotFound {
+            engine_id: req.engine_id,
+        },
+    ))
+}
+
+/// Sum the usage of two engine responses
+fn add_usage(
+    total: flm_core::domain::chat::UsageStats,
+    usage: &flm_core::domain::chat::UsageStats,
+) -> flm_core::domain::chat::UsageStats {
+    flm_core::domain::chat::UsageStats {
+        prompt_tokens: total.prompt_tokens.saturating_add(usage.prompt_tokens),
+        completion_tokens: total
+            .completion_tokens
+            .saturating_add(usage.completion_tokens),
+        total_tokens: total.total_tokens.saturating_add(usage.total_tokens),
+    }
+}
+
+/// Start a chat stream, failing over until a backend yields its first chunk
+///
+/// Once a chunk has been received the stream is committed to that backend.
+/// The returned stream keeps the backend counted as in flight, and holds its
+/// concurrency slots, until dropped.
+async fn start_chat_stream(
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
+    mut req: flm_core::domain::chat::ChatRequest,
+) -> Result<flm_core::ports::ChatStream, ChatDispatchError> {
+    let mut remaining = backends.iter().peekable();
+    while let Some(backend) = remaining.next() {
+        req.engine_id = backend.engine.id();
+        req.model_id = backend.model_id.clone();
+        dispatch.use_backend(&req);
+        let permit = match dispatch
+            .concurrency
+            .acquire(&req.engine_id, &backend.model_id, dispatch.client)
+            .await
+        {
+            Ok(permit) => permit,
+            Err(rejection) if remaining.peek().is_some() => {
+                warn!(
+                    engine_id = %req.engine_id,
+                    target = rejection.target(),
+                    "Backend queue rejected the request, failing over to next model group member"
+                );
+                continue;
+            }
+            Err(rejection) => return Err(ChatDispatchError::Queue(rejection)),
+        };
+        let in_flight = dispatch.balancer.acquire(&backend.model_id);
+
+        let error = match backend.engine.chat_stream(req.clone()).await {
+            Ok(mut stream) => match stream.next().await {
+                Some(Err(e)) if crate::balancer::is_failover_error(&e) => e,
+                first => {
+                    let stream = futures::stream::iter(first).chain(stream).map(move |item| {
+                        let _ = (&permit, &in_flight);
+                        item
+                    });
+                    return Ok(Box::pin(stream));
+                }
+            },
+            Err(e) => e,
+        };
+
+        if crate::balancer::is_failover_error(&error) && remaining.peek().is_some() {
+            warn!(
+                engine_id = %req.engine_id,
+                error_type = engine_error_type(&error),
+                "Backend failed before streaming, failing over to next model group member"
+            );
+            continue;
+        }
+        return Err(ChatDispatchError::Engine(error));
+    }
+
+    Err(ChatDispatchError::Engine(
+        flm_core::error::EngineError::NotFound {
+            engine_id: req.engine_id,
+        },
+    ))
+}
+
 /// Handle streaming chat completion
 async fn handle_chat_stream(
-    engine: &std::sync::Arc<dyn flm_core::ports::LlmEngine>,
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
     req: flm_core::domain::chat::ChatRequest,
+    model_id: String,
 ) -> axum::response::Response {
     use axum::response::sse::{Event, Sse};
     use futures::StreamExt;
 
-    let stream = match engine.chat_stream(req.clone()).await {
+    let started = match start_chat_stream(dispatch, backends, req).await {
+        Ok(s) => Ok(s),
+        Err(ChatDispatchError::Queue(rejection)) => return queue_rejection_response(&rejection),
+        Err(ChatDispatchError::Violation(reason)) => {
+            return response_format_violation_response(&reason)
+        }
+        Err(ChatDispatchError::Engine(e)) => Err(e),
+    };
+    let stream = match started {
         Ok(s) => s,
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. }) => {
+            return unsupported_parameter_response(&reason);
+        }
         Err(e) => {
             // Log error type only (mask sensitive information)
             let (status, message) = match e {
@@ -2299,24 +3765,64 @@ async fn handle_chat_stream(
     };
 
     // Convert ChatStreamChunk to OpenAI SSE format
-    let model_id = req.model_id.clone();
-    let sse_stream = stream.map(move |chunk_result| {
-        match chunk_result {
+    let mut saw_tool_calls = false;
+    let tokens = dispatch.tokens.clone();
+    let sse_stream = stream.flat_map(move |chunk_result| {
+        let events: Vec<Result<Event, axum::Error>> = match chunk_result {
             Ok(chunk) => {
-                // Handle final chunk
-                if chunk.is_done {
-                    // Send [DONE] marker
-                    return Ok(Event::default().data("[DONE]"));
+                if let Some(tokens) = &tokens {
+                    tokens.observe_chat_chunk(&chunk);
+                }
+                let mut delta = serde_json::json!({
+                    "role": "assistant",
+                    "content": chunk.delta.content
+                });
+                if !chunk.tool_calls.is_empty() {
+                    saw_tool_calls = true;
+                    delta["tool_calls"] = serde_json::Value::Array(
+                        chunk
+                            .tool_calls
+                            .iter()
+                            .map(|call| {
+                                let mut function = serde_json::json!({});
+                                if let Some(name) = &call.name {
+                                    function["name"] = serde_json::Value::String(name.clone());
+                                }
+                                if let Some(arguments) = &call.arguments {
+                                    function["arguments"] =
+                                        serde_json::Value::String(arguments.clone());
+                                }
+                                let mut value = serde_json::json!({
+                                    "index": call.index,
+                                    "type": "function",
+                                    "function": function
+                                });
+                                if let Some(id) = &call.id {
+                                    value["id"] = serde_json::Value::String(id.clone());
+                                }
+                                value
+                            })
+                            .collect(),
+                    );
                 }
 
-                let choice = serde_json::json!({
-                    "delta": {
-                        "role": "assistant",
-                        "content": chunk.delta.content
-                    },
+                // Final chunk carries the finish reason and is followed by [DONE]
+                let finish_reason = if !chunk.is_done {
+                    None
+                } else if saw_tool_calls {
+                    Some("tool_calls")
+                } else {
+                    Some("stop")
+                };
+
+                let mut choice = serde_json::json!({
+                    "delta": delta,
                     "index": 0,
-                    "finish_reason": None::<String>
+                    "finish_reason": finish_reason
                 });
+                if !chunk.logprobs.is_empty() {
+                    choice["logprobs"] = logprobs_to_openai(&chunk.logprobs);
+                }
 
                 let mut data = serde_json::json!({
                     "id": "chatcmpl-unknown",
@@ -2336,11 +3842,18 @@ async fn handle_chat_stream(
                 }
 
                 // Convert to SSE event
-                match Event::default().json_data(data) {
+                let event = match Event::default().json_data(data) {
                     Ok(event) => Ok(event),
                     Err(e) => Err(axum::Error::new(std::io::Error::other(format!(
                         "Failed to serialize SSE event: {e}"
                     )))),
+                };
+
+                if chunk.is_done {
+                    // Send [DONE] marker
+                    vec![event, Ok(Event::default().data("[DONE]"))]
+                } else {
+                    vec![event]
                 }
             }
             Err(e) => {
@@ -2365,31 +3878,490 @@ async fn handle_chat_stream(
                         error!(error_type = "unknown_error", "Unknown stream error");
                         ("Stream error", "unknown_error")
                     }
-                };
+                };
+
+                // Send error as SSE event so client can handle it gracefully
+                let error_data = serde_json::json!({
+                    "error": {
+                        "message": error_msg,
+                        "type": "server_error",
+                        "code": error_code
+                    }
+                });
+
+                match Event::default().json_data(error_data) {
+                    Ok(event) => vec![Ok(event)],
+                    Err(_) => {
+                        // Fallback if JSON serialization fails
+                        vec![Err(axum::Error::new(std::io::Error::other(error_msg)))]
+                    }
+                }
+            }
+        };
+        futures::stream::iter(events)
+    });
+
+    Sse::new(sse_stream).into_response()
+}
+
+/// OpenAI-compatible legacy text completion request
+#[derive(serde::Deserialize)]
+struct OpenAiCompletionRequest {
+    model: String,
+    prompt: OneOrMany,
+    #[serde(default)]
+    suffix: Option<String>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    echo: bool,
+    #[serde(default)]
+    best_of: Option<u32>,
+    #[serde(default)]
+    temperature: Option<f64>,
+    #[serde(default)]
+    max_tokens: Option<u32>,
+    #[serde(default)]
+    stop: Option<OneOrMany>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    top_k: Option<u32>,
+    #[serde(default)]
+    seed: Option<i64>,
+    #[serde(default)]
+    presence_penalty: Option<f32>,
+    #[serde(default)]
+    frequency_penalty: Option<f32>,
+    #[serde(default, alias = "repetition_penalty")]
+    repeat_penalty: Option<f32>,
+    #[serde(default)]
+    n: Option<u32>,
+    #[serde(default)]
+    logit_bias: Option<BTreeMap<String, f32>>,
+    /// Legacy integer form; not supported
+    #[serde(default)]
+    logprobs: Option<u32>,
+}
+
+/// A string or a list of strings (`prompt`, `stop` in the legacy completions API)
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+/// Validate `best_of` against `n` and streaming
+fn validate_best_of(
+    best_of: Option<u32>,
+    n: Option<u32>,
+    stream: bool,
+) -> Result<(), &'static str> {
+    let Some(best_of) = best_of else {
+        return Ok(());
+    };
+    if best_of == 0 || best_of > 16 {
+        return Err("best_of must be between 1 and 16");
+    }
+    if best_of < n.unwrap_or(1) {
+        return Err("best_of must be greater than or equal to n");
+    }
+    if best_of > 1 && stream {
+        return Err("best_of greater than 1 cannot be combined with stream");
+    }
+    Ok(())
+}
+
+/// 400 response for a malformed request
+fn invalid_request_response(message: &str, code: &str) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(json!({
+            "error": {
+                "message": message,
+                "type": "invalid_request_error",
+                "code": code
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Handle legacy text completion requests (`/v1/completions`)
+async fn handle_completions(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_id: Option<axum::Extension<String>>,
+    token_budget: Option<axum::Extension<crate::token_quota::TokenBudget>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OpenAiCompletionRequest>,
+) -> axum::response::Response {
+    use flm_core::domain::chat::CompletionRequest;
+
+    let OpenAiCompletionRequest {
+        model,
+        prompt,
+        suffix,
+        stream,
+        echo,
+        best_of,
+        temperature,
+        max_tokens,
+        stop,
+        top_p,
+        top_k,
+        seed,
+        presence_penalty,
+        frequency_penalty,
+        repeat_penalty,
+        n,
+        logit_bias,
+        logprobs,
+    } = req;
+
+    // Parse model ID (must be in flm://{engine_id}/{model} format)
+    let Some((engine_id, model_name)) = split_model_id(&model) else {
+        return invalid_request_response(
+            "Invalid model ID format. Expected flm://{engine_id}/{model}",
+            "invalid_model",
+        );
+    };
+    if validate_engine_id(&engine_id).is_err() {
+        return invalid_request_response("Invalid engine ID", "invalid_engine_id");
+    }
+    if validate_model_name(&model_name).is_err() {
+        return invalid_request_response("Invalid model name", "invalid_model_name");
+    }
+    if let Err(response) = check_model_scope(api_key_scopes.as_ref(), &model) {
+        return response;
+    }
+
+    // Batched prompts would need one engine call per prompt; only one is accepted
+    let prompt = match prompt.into_vec().as_mut_slice() {
+        [single] => std::mem::take(single),
+        _ => {
+            return invalid_request_response(
+                "prompt must be a string or an array with exactly one string",
+                "invalid_prompt",
+            );
+        }
+    };
+    if prompt.len() > MAX_COMPLETION_PROMPT_BYTES
+        || suffix
+            .as_ref()
+            .is_some_and(|s| s.len() > MAX_COMPLETION_PROMPT_BYTES)
+    {
+        return invalid_request_response("prompt or suffix is too long", "invalid_prompt");
+    }
+
+    let stop = stop.map(OneOrMany::into_vec).unwrap_or_default();
+    if validate_stop_sequences(&stop).is_err() {
+        return invalid_request_response("Invalid stop sequences", "invalid_stop");
+    }
+    if validate_temperature(temperature).is_err() {
+        return invalid_request_response(
+            "Temperature must be between 0.0 and 2.0",
+            "invalid_temperature",
+        );
+    }
+    if validate_max_tokens(max_tokens).is_err() {
+        return invalid_request_response(
+            "max_tokens exceeds maximum limit or is invalid",
+            "invalid_max_tokens",
+        );
+    }
+    if logprobs.is_some() {
+        return unsupported_parameter_response("logprobs is not supported for completions");
+    }
+
+    let sampling = SamplingParams {
+        top_p,
+        top_k,
+        seed,
+        presence_penalty,
+        frequency_penalty,
+        repeat_penalty,
+        n,
+        logit_bias: logit_bias.unwrap_or_default(),
+        logprobs: false,
+        top_logprobs: None,
+    };
+    if let Err(message) = validate_sampling(&sampling, stream) {
+        return invalid_request_response(message, "invalid_sampling");
+    }
+    if let Err(message) = validate_best_of(best_of, n, stream) {
+        return invalid_request_response(message, "invalid_best_of");
+    }
+
+    let engines = state.engine_repo.list_registered().await;
+    let Some(engine) = engines.iter().find(|e| e.id() == engine_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine not found",
+                    "type": "invalid_request_error",
+                    "code": "engine_not_found"
+                }
+            })),
+        )
+            .into_response();
+    };
+
+    let echo_prefix = echo.then(|| prompt.clone());
+    let completion_req = CompletionRequest {
+        engine_id,
+        model_id: model,
+        prompt,
+        suffix,
+        stream,
+        temperature: temperature.map(|t| t as f32),
+        max_tokens,
+        stop,
+        best_of,
+        sampling,
+    };
+
+    let estimated = crate::token_quota::estimate_text_tokens(&completion_req.prompt)
+        + completion_req
+            .suffix
+            .as_deref()
+            .map(crate::token_quota::estimate_text_tokens)
+            .unwrap_or(0);
+    let tokens = match crate::token_quota::reserve_tokens(
+        token_budget.as_ref().map(|budget| &budget.0),
+        estimated,
+    )
+    .await
+    {
+        Ok(tokens) => tokens,
+        Err(rejection) => return rejection.to_response(),
+    };
+    if let Some(tokens) = &tokens {
+        tokens.set_target(&completion_req.engine_id, &completion_req.model_id);
+    }
+    let permit = match acquire_engine_slots(
+        &state,
+        api_key_id.as_ref(),
+        &completion_req.engine_id,
+        &completion_req.model_id,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(response) => return response,
+    };
+
+    if stream {
+        handle_completion_stream(engine, completion_req, echo_prefix, tokens, permit).await
+    } else {
+        let _permit = permit;
+        handle_completion_non_stream(engine, completion_req, echo_prefix, tokens).await
+    }
+}
+
+/// Upper bound on `prompt` / `suffix` size for text completions (bytes)
+const MAX_COMPLETION_PROMPT_BYTES: usize = 1024 * 1024;
+
+/// Handle non-streaming text completion
+async fn handle_completion_non_stream(
+    engine: &std::sync::Arc<dyn flm_core::ports::LlmEngine>,
+    req: flm_core::domain::chat::CompletionRequest,
+    echo_prefix: Option<String>,
+    tokens: Option<crate::token_quota::TokenReservation>,
+) -> axum::response::Response {
+    let model_id = req.model_id.clone();
+    match engine.complete(req).await {
+        Ok(response) => {
+            if let Some(tokens) = &tokens {
+                tokens.record(&response.usage);
+            }
+
+            let choices: Vec<serde_json::Value> = response
+                .choices
+                .into_iter()
+                .enumerate()
+                .map(|(index, choice)| {
+                    let text = match &echo_prefix {
+                        Some(prefix) => format!("{prefix}{}", choice.text),
+                        None => choice.text,
+                    };
+                    json!({
+                        "text": text,
+                        "index": index,
+                        "logprobs": null,
+                        "finish_reason": choice.finish_reason.unwrap_or_else(|| "stop".to_string())
+                    })
+                })
+                .collect();
+
+            axum::Json(json!({
+                "id": "cmpl-unknown",
+                "object": "text_completion",
+                "created": 0,
+                "model": model_id,
+                "choices": choices,
+                "usage": {
+                    "prompt_tokens": response.usage.prompt_tokens,
+                    "completion_tokens": response.usage.completion_tokens,
+                    "total_tokens": response.usage.total_tokens
+                }
+            }))
+            .into_response()
+        }
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. }) => {
+            unsupported_parameter_response(&reason)
+        }
+        Err(e) => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Completion request failed"
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(json!({
+                    "error": {
+                        "message": "Failed to process completion request",
+                        "type": "server_error",
+                        "code": "completion_error"
+                    }
+                })),
+            )
+                .into_response()
+        }
+    }
+}
 
-                // Send error as SSE event so client can handle it gracefully
-                let error_data = serde_json::json!({
+/// Handle streaming text completion
+///
+/// With `echo`, the prompt is sent as the first event before any generated text.
+/// The concurrency `permit` is held until the stream ends.
+async fn handle_completion_stream(
+    engine: &std::sync::Arc<dyn flm_core::ports::LlmEngine>,
+    req: flm_core::domain::chat::CompletionRequest,
+    echo_prefix: Option<String>,
+    tokens: Option<crate::token_quota::TokenReservation>,
+    permit: crate::concurrency::ConcurrencyPermit,
+) -> axum::response::Response {
+    use axum::response::sse::{Event, Sse};
+    use futures::StreamExt;
+
+    let model_id = req.model_id.clone();
+    let stream = match engine.complete_stream(req).await {
+        Ok(s) => s,
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. }) => {
+            return unsupported_parameter_response(&reason);
+        }
+        Err(e) => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Failed to start completion stream"
+            );
+            return (
+                StatusCode::BAD_GATEWAY,
+                axum::Json(json!({
                     "error": {
-                        "message": error_msg,
+                        "message": "Failed to start streaming completion",
                         "type": "server_error",
-                        "code": error_code
+                        "code": "stream_error"
                     }
-                });
+                })),
+            )
+                .into_response();
+        }
+    };
 
-                match Event::default().json_data(error_data) {
-                    Ok(event) => Ok(event),
-                    Err(_) => {
-                        // Fallback if JSON serialization fails
-                        Err(axum::Error::new(std::io::Error::other(error_msg)))
+    let completion_event = move |text: String, finish_reason: Option<String>, usage| {
+        let mut data = json!({
+            "id": "cmpl-unknown",
+            "object": "text_completion",
+            "created": 0,
+            "model": model_id.clone(),
+            "choices": [{
+                "text": text,
+                "index": 0,
+                "logprobs": null,
+                "finish_reason": finish_reason
+            }]
+        });
+        if let Some(usage) = usage {
+            let usage: flm_core::domain::chat::UsageStats = usage;
+            data["usage"] = json!({
+                "prompt_tokens": usage.prompt_tokens,
+                "completion_tokens": usage.completion_tokens,
+                "total_tokens": usage.total_tokens
+            });
+        }
+        Event::default().json_data(data).map_err(|e| {
+            axum::Error::new(std::io::Error::other(format!(
+                "Failed to serialize SSE event: {e}"
+            )))
+        })
+    };
+
+    let echo_events: Vec<Result<Event, axum::Error>> = echo_prefix
+        .map(|prefix| completion_event(prefix, None, None))
+        .into_iter()
+        .collect();
+    let sse_stream =
+        futures::stream::iter(echo_events).chain(stream.flat_map(move |chunk_result| {
+            let _ = &permit;
+            let events: Vec<Result<Event, axum::Error>> = match chunk_result {
+                Ok(chunk) => {
+                    if let Some(tokens) = &tokens {
+                        tokens.observe_output(&chunk.text, chunk.usage.as_ref());
+                    }
+                    let finish_reason = chunk
+                        .is_done
+                        .then(|| chunk.finish_reason.unwrap_or_else(|| "stop".to_string()));
+                    let event = completion_event(chunk.text, finish_reason, chunk.usage);
+                    if chunk.is_done {
+                        vec![event, Ok(Event::default().data("[DONE]"))]
+                    } else {
+                        vec![event]
                     }
                 }
-            }
-        }
-    });
+                Err(e) => {
+                    let error_type = engine_error_type(&e);
+                    error!(error_type, "Error in completion stream");
+                    let error_data = json!({
+                        "error": {
+                            "message": "Stream error",
+                            "type": "server_error",
+                            "code": error_type
+                        }
+                    });
+                    vec![Event::default()
+                        .json_data(error_data)
+                        .map_err(|_| axum::Error::new(std::io::Error::other("Stream error")))]
+                }
+            };
+            futures::stream::iter(events)
+        }));
 
     Sse::new(sse_stream).into_response()
 }
 
+/// Loggable error category of an engine error (never includes the reason text)
+fn engine_error_type(error: &flm_core::error::EngineError) -> &'static str {
+    match error {
+        flm_core::error::EngineError::NetworkError { .. } => "network_error",
+        flm_core::error::EngineError::InvalidResponse { .. } => "invalid_response",
+        flm_core::error::EngineError::ApiError { .. } => "api_error",
+        flm_core::error::EngineError::NotFound { .. } => "engine_not_found",
+        _ => "unknown_error",
+    }
+}
+
 /// Start a packaged-ca HTTPS server
 ///
 /// This function starts an HTTPS server using a server certificate signed by
@@ -4245,25 +6217,204 @@ fn http_client_builder_for_egress(
     }
 }
 
+/// OpenAI images.generations request body
+///
+/// `quality`, `style` and `user` are accepted and ignored.
+#[derive(serde::Deserialize)]
+struct OpenAiImageGenerationRequest {
+    model: String,
+    prompt: String,
+    #[serde(default)]
+    n: Option<u32>,
+    #[serde(default)]
+    size: Option<String>,
+    #[serde(default)]
+    response_format: Option<String>,
+}
+
+/// Upper bound on the image generation prompt (bytes)
+const MAX_IMAGE_PROMPT_BYTES: usize = 32 * 1024;
+
+/// Maximum number of images per request
+const MAX_IMAGES_PER_REQUEST: u32 = 10;
+
+/// Parse an OpenAI `size` value (`{width}x{height}`)
+///
+/// Each side must be a multiple of 8 between 64 and 2048 pixels, which is what
+/// diffusion backends accept without silently resizing.
+fn parse_image_size(size: &str) -> Result<(u32, u32), &'static str> {
+    const INVALID: &str = "size must be formatted as {width}x{height}";
+    let (width, height) = size.split_once('x').ok_or(INVALID)?;
+    let width: u32 = width.parse().map_err(|_| INVALID)?;
+    let height: u32 = height.parse().map_err(|_| INVALID)?;
+    for side in [width, height] {
+        if !(64..=2048).contains(&side) || side % 8 != 0 {
+            return Err("size dimensions must be multiples of 8 between 64 and 2048");
+        }
+    }
+    Ok((width, height))
+}
+
 /// Handle /v1/images/generations endpoint
 ///
-/// Currently returns 501 Not Implemented as image generation is not yet supported by engines.
-#[axum::debug_handler]
+/// Routed to engines that advertise `EngineCapabilities::image_generation`.
+/// Returns OpenAI-compatible JSON: `{"created":<unix>,"data":[{"b64_json"|"url":...}]}`;
+/// `url` is rejected for engines that cannot host their images.
 async fn handle_images_generations(
-    axum::extract::State(_state): axum::extract::State<AppState>,
-    axum::Json(_req): axum::Json<serde_json::Value>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_id: Option<axum::Extension<String>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OpenAiImageGenerationRequest>,
 ) -> axum::response::Response {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        axum::Json(json!({
-            "error": {
-                "message": "Image generation is not yet implemented",
-                "type": "invalid_request_error",
-                "code": "not_implemented"
-            }
-        })),
+    use flm_core::domain::chat::{GeneratedImage, ImageGenerationRequest, ImageResponseFormat};
+
+    // Parse model ID (must be in flm://{engine_id}/{model} format)
+    let Some((engine_id, model_name)) = split_model_id(&req.model) else {
+        return invalid_request_response(
+            "Invalid model ID format. Expected flm://{engine_id}/{model}",
+            "invalid_model",
+        );
+    };
+    if validate_engine_id(&engine_id).is_err() {
+        return invalid_request_response("Invalid engine ID", "invalid_engine_id");
+    }
+    if validate_model_name(&model_name).is_err() {
+        return invalid_request_response("Invalid model name", "invalid_model_name");
+    }
+    if let Err(response) = check_model_scope(api_key_scopes.as_ref(), &req.model) {
+        return response;
+    }
+
+    if req.prompt.trim().is_empty() || req.prompt.len() > MAX_IMAGE_PROMPT_BYTES {
+        return invalid_request_response(
+            "prompt must be a non-empty string of at most 32KB",
+            "invalid_prompt",
+        );
+    }
+    let n = req.n.unwrap_or(1);
+    if n == 0 || n > MAX_IMAGES_PER_REQUEST {
+        return invalid_request_response("n must be between 1 and 10", "invalid_n");
+    }
+    let (width, height) = match parse_image_size(req.size.as_deref().unwrap_or("1024x1024")) {
+        Ok(size) => size,
+        Err(message) => return invalid_request_response(message, "invalid_size"),
+    };
+    let response_format = match req.response_format.as_deref().unwrap_or("url") {
+        "url" => ImageResponseFormat::Url,
+        "b64_json" => ImageResponseFormat::B64Json,
+        _ => {
+            return invalid_request_response(
+                "response_format must be 'url' or 'b64_json'",
+                "invalid_response_format",
+            );
+        }
+    };
+
+    let engines = state.engine_repo.list_registered().await;
+    let Some(engine) = engines.iter().find(|e| e.id() == engine_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine not found",
+                    "type": "invalid_request_error",
+                    "code": "engine_not_found"
+                }
+            })),
+        )
+            .into_response();
+    };
+
+    if !engine.capabilities().image_generation {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine does not support image generation",
+                    "type": "invalid_request_error",
+                    "code": "unsupported_modalities"
+                }
+            })),
+        )
+            .into_response();
+    }
+
+    let image_req = ImageGenerationRequest {
+        engine_id,
+        model_id: req.model,
+        prompt: req.prompt,
+        width,
+        height,
+        n,
+        response_format,
+    };
+
+    let _permit = match acquire_engine_slots(
+        &state,
+        api_key_id.as_ref(),
+        &image_req.engine_id,
+        &image_req.model_id,
     )
-        .into_response()
+    .await
+    {
+        Ok(permit) => permit,
+        Err(response) => return response,
+    };
+
+    match engine.generate_images(image_req).await {
+        Ok(response) => {
+            let data: Vec<serde_json::Value> = response
+                .images
+                .into_iter()
+                .map(|image| match image {
+                    GeneratedImage::B64Json(b64) => {
+                        json!({ "b64_json": b64, "revised_prompt": null })
+                    }
+                    GeneratedImage::Url(url) => json!({ "url": url, "revised_prompt": null }),
+                })
+                .collect();
+
+            axum::Json(json!({
+                "created": chrono::Utc::now().timestamp(),
+                "data": data
+            }))
+            .into_response()
+        }
+        // Backends that only return bytes cannot hand out image URLs
+        Err(flm_core::error::EngineError::UnsupportedOperation { reason, .. })
+            if response_format == ImageResponseFormat::Url =>
+        {
+            invalid_request_response(&reason, "unsupported_response_format")
+        }
+        Err(flm_core::error::EngineError::UnsupportedOperation { .. }) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine does not support image generation",
+                    "type": "invalid_request_error",
+                    "code": "unsupported_modalities"
+                }
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Image generation request failed"
+            );
+            (
+                StatusCode::BAD_GATEWAY,
+                axum::Json(json!({
+                    "error": {
+                        "message": "Failed to generate images",
+                        "type": "server_error",
+                        "code": "image_generation_error"
+                    }
+                })),
+            )
+                .into_response()
+        }
+    }
 }
 
 /// Handle /v1/audio/transcriptions endpoint
@@ -4281,6 +6432,8 @@ async fn handle_images_generations(
 #[axum::debug_handler]
 async fn handle_audio_transcriptions(
     axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_id: Option<axum::Extension<String>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
     mut multipart: axum::extract::Multipart,
 ) -> axum::response::Response {
     // Extract fields from multipart/form-data
@@ -4464,6 +6617,9 @@ async fn handle_audio_transcriptions(
     }
 
     let engine_id = parts[0].to_string();
+    if let Err(response) = check_model_scope(api_key_scopes.as_ref(), &model_id) {
+        return response;
+    }
 
     // Get engine from repository
     let engine_repo = &state.engine_repo;
@@ -4531,6 +6687,12 @@ async fn handle_audio_transcriptions(
         prompt,
     };
 
+    let _permit =
+        match acquire_engine_slots(&state, api_key_id.as_ref(), &engine_id, &model_id).await {
+            Ok(permit) => permit,
+            Err(response) => return response,
+        };
+
     match engine.transcribe_audio(transcription_req).await {
         Ok(transcription_resp) => {
             // Return OpenAI-compatible JSON response
@@ -4582,23 +6744,176 @@ async fn handle_audio_transcriptions(
     }
 }
 
+/// OpenAI audio.speech request body
+///
+/// `instructions` is accepted and ignored.
+#[derive(serde::Deserialize)]
+struct OpenAiSpeechRequest {
+    model: String,
+    input: String,
+    voice: String,
+    #[serde(default)]
+    response_format: Option<String>,
+    #[serde(default)]
+    speed: Option<f32>,
+}
+
+/// Maximum speech input length (characters), matching the OpenAI API
+const MAX_SPEECH_INPUT_CHARS: usize = 4096;
+
+/// Parse an OpenAI speech `response_format` value
+fn parse_speech_format(format: &str) -> Option<flm_core::domain::chat::AudioResponseFormat> {
+    use flm_core::domain::chat::AudioResponseFormat;
+    match format {
+        "mp3" => Some(AudioResponseFormat::Mp3),
+        "opus" => Some(AudioResponseFormat::Opus),
+        "aac" => Some(AudioResponseFormat::Aac),
+        "flac" => Some(AudioResponseFormat::Flac),
+        "wav" => Some(AudioResponseFormat::Wav),
+        "pcm" => Some(AudioResponseFormat::Pcm),
+        _ => None,
+    }
+}
+
 /// Handle /v1/audio/speech endpoint
 ///
-/// Currently disabled (returns 404) until audio output engines are available.
-#[axum::debug_handler]
+/// Routed to engines that advertise `EngineCapabilities::audio_outputs`.
+/// The synthesized audio is streamed back as it arrives, with the content
+/// type of the requested `response_format` (default `mp3`).
 async fn handle_audio_speech(
-    axum::extract::State(_state): axum::extract::State<AppState>,
-    axum::Json(_req): axum::Json<serde_json::Value>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_id: Option<axum::Extension<String>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OpenAiSpeechRequest>,
 ) -> axum::response::Response {
-    (
-        StatusCode::NOT_FOUND,
-        axum::Json(json!({
-            "error": {
-                "message": "Audio speech endpoint is not available",
-                "type": "invalid_request_error",
-                "code": "route_disabled"
-            }
-        })),
+    use flm_core::domain::chat::SpeechRequest;
+    use futures::StreamExt;
+
+    // Parse model ID (must be in flm://{engine_id}/{model} format)
+    let Some((engine_id, model_name)) = split_model_id(&req.model) else {
+        return invalid_request_response(
+            "Invalid model ID format. Expected flm://{engine_id}/{model}",
+            "invalid_model",
+        );
+    };
+    if validate_engine_id(&engine_id).is_err() {
+        return invalid_request_response("Invalid engine ID", "invalid_engine_id");
+    }
+    if validate_model_name(&model_name).is_err() {
+        return invalid_request_response("Invalid model name", "invalid_model_name");
+    }
+    if let Err(response) = check_model_scope(api_key_scopes.as_ref(), &req.model) {
+        return response;
+    }
+
+    if req.input.trim().is_empty() || req.input.chars().count() > MAX_SPEECH_INPUT_CHARS {
+        return invalid_request_response(
+            "input must be a non-empty string of at most 4096 characters",
+            "invalid_input",
+        );
+    }
+    if req.voice.trim().is_empty() || req.voice.len() > 128 {
+        return invalid_request_response("voice must be a non-empty string", "invalid_voice");
+    }
+    let Some(format) = parse_speech_format(req.response_format.as_deref().unwrap_or("mp3")) else {
+        return invalid_request_response(
+            "response_format must be one of mp3, opus, aac, flac, wav, pcm",
+            "invalid_response_format",
+        );
+    };
+    if let Some(speed) = req.speed {
+        if !(0.25..=4.0).contains(&speed) {
+            return invalid_request_response("speed must be between 0.25 and 4.0", "invalid_speed");
+        }
+    }
+
+    let engines = state.engine_repo.list_registered().await;
+    let Some(engine) = engines.iter().find(|e| e.id() == engine_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine not found",
+                    "type": "invalid_request_error",
+                    "code": "engine_not_found"
+                }
+            })),
+        )
+            .into_response();
+    };
+
+    let unsupported = || {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(json!({
+                "error": {
+                    "message": "Engine does not support speech synthesis",
+                    "type": "invalid_request_error",
+                    "code": "unsupported_modalities"
+                }
+            })),
+        )
+            .into_response()
+    };
+    if !engine.capabilities().audio_outputs {
+        return unsupported();
+    }
+
+    let content_type = format.mime_type();
+    let speech_req = SpeechRequest {
+        engine_id,
+        model_id: req.model,
+        input: req.input,
+        voice: req.voice,
+        format,
+        speed: req.speed,
+    };
+
+    let permit = match acquire_engine_slots(
+        &state,
+        api_key_id.as_ref(),
+        &speech_req.engine_id,
+        &speech_req.model_id,
     )
-        .into_response()
+    .await
+    {
+        Ok(permit) => permit,
+        Err(response) => return response,
+    };
+
+    let stream = match engine.synthesize_speech(speech_req).await {
+        Ok(stream) => stream,
+        Err(flm_core::error::EngineError::UnsupportedOperation { .. }) => return unsupported(),
+        Err(e) => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Speech synthesis request failed"
+            );
+            return (
+                StatusCode::BAD_GATEWAY,
+                axum::Json(json!({
+                    "error": {
+                        "message": "Failed to synthesize speech",
+                        "type": "server_error",
+                        "code": "speech_error"
+                    }
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    // Headers are already sent once audio flows, so a mid-stream failure can
+    // only abort the response; the slot is held until the audio ends
+    let body = axum::body::Body::from_stream(stream.map(move |chunk| {
+        let _ = &permit;
+        chunk.map_err(|e| {
+            error!(
+                error_type = engine_error_type(&e),
+                "Speech stream interrupted"
+            );
+            std::io::Error::other(e.to_string())
+        })
+    }));
+    ([(axum::http::header::CONTENT_TYPE, content_type)], body).into_response()
 }
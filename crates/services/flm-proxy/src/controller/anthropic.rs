@@ -0,0 +1,1041 @@
+//! Anthropic Messages API front-end (`/v1/messages`)
+//!
+//! Translates the Anthropic wire format (top-level `system`, content blocks,
+//! `stop_reason`, typed SSE events) to `ChatRequest` / `ChatStream` and back,
+//! so clients that only speak that format can use any registered engine.
+//! The route sits behind the same auth, policy and audit middleware as the
+//! OpenAI-compatible endpoints, and shares their model profile, model group
+//! and managed system prompt resolution.
+
+use super::{
+    apply_api_prompt, attach_chat_audit_details, dispatch_chat, engine_error_type,
+    invalid_request_error, load_image_attachment, parse_api_prompt_opt_out, resolve_chat_backends,
+    resolve_chat_target, start_chat_stream, validate_max_tokens, validate_sampling,
+    validate_stop_sequences, validate_tools, AttachmentLimits, ChatBackend, ChatDispatch,
+    ChatDispatchError, JsonError, OpenAiFunctionDefinition, OpenAiImageUrl, OpenAiTool,
+    ResponseValidation,
+};
+use crate::concurrency::client_key;
+use crate::middleware::{ApiKeyLabel, AppState};
+use crate::token_quota::{estimate_prompt_tokens, reserve_tokens, TokenBudget};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, Sse};
+use axum::response::IntoResponse;
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, MultimodalAttachmentKind,
+    SamplingParams, ToolCall, ToolChoice, ToolDefinition,
+};
+use flm_core::domain::security::ApiKeyScopes;
+use flm_core::error::EngineError;
+use futures::StreamExt;
+use reqwest::Client as HttpClient;
+use serde_json::json;
+use tracing::error;
+
+/// Anthropic Messages API request
+#[derive(serde::Deserialize)]
+pub(super) struct MessagesRequest {
+    model: String,
+    /// Required by the Anthropic API; checked explicitly for a proper error body
+    #[serde(default)]
+    max_tokens: Option<u32>,
+    messages: Vec<AnthropicMessage>,
+    #[serde(default)]
+    system: Option<AnthropicSystem>,
+    #[serde(default)]
+    stop_sequences: Vec<String>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    temperature: Option<f64>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    top_k: Option<u32>,
+    #[serde(default)]
+    tools: Vec<AnthropicTool>,
+    #[serde(default)]
+    tool_choice: Option<serde_json::Value>,
+}
+
+#[derive(serde::Deserialize)]
+struct AnthropicMessage {
+    role: String,
+    content: AnthropicContent,
+}
+
+/// Message content: a plain string or a list of content blocks
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum AnthropicContent {
+    Text(String),
+    Blocks(Vec<ContentBlock>),
+}
+
+/// Top-level `system` field: a plain string or a list of text blocks
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum AnthropicSystem {
+    Text(String),
+    Blocks(Vec<TextBlock>),
+}
+
+#[derive(serde::Deserialize)]
+struct TextBlock {
+    text: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default)]
+        content: Option<ToolResultContent>,
+        #[serde(default)]
+        is_error: bool,
+    },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+/// `tool_result` content: a plain string or a list of text blocks
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ToolResultContent {
+    Text(String),
+    Blocks(Vec<TextBlock>),
+}
+
+#[derive(serde::Deserialize)]
+struct AnthropicTool {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    input_schema: Option<serde_json::Value>,
+}
+
+impl AnthropicSystem {
+    fn into_text(self) -> String {
+        match self {
+            AnthropicSystem::Text(text) => text,
+            AnthropicSystem::Blocks(blocks) => join_text_blocks(blocks),
+        }
+    }
+}
+
+impl ToolResultContent {
+    fn into_text(self) -> String {
+        match self {
+            ToolResultContent::Text(text) => text,
+            ToolResultContent::Blocks(blocks) => join_text_blocks(blocks),
+        }
+    }
+}
+
+fn join_text_blocks(blocks: Vec<TextBlock>) -> String {
+    blocks
+        .into_iter()
+        .map(|block| block.text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl AnthropicContent {
+    fn text_length(&self) -> usize {
+        match self {
+            AnthropicContent::Text(text) => text.len(),
+            AnthropicContent::Blocks(blocks) => blocks
+                .iter()
+                .map(|block| match block {
+                    ContentBlock::Text { text } => text.len(),
+                    _ => 0,
+                })
+                .sum(),
+        }
+    }
+}
+
+/// Validate the `messages` array (same limits as `/v1/chat/completions`)
+fn validate_messages(messages: &[AnthropicMessage]) -> Result<(), &'static str> {
+    if messages.is_empty() {
+        return Err("messages cannot be empty");
+    }
+    if messages.len() > 100 {
+        return Err("Too many messages");
+    }
+    for msg in messages {
+        if msg.content.text_length() > 1_048_576 {
+            return Err("Message content too long");
+        }
+    }
+    Ok(())
+}
+
+/// Validate temperature (the Anthropic API accepts 0.0 to 1.0)
+fn validate_temperature(temperature: Option<f64>) -> Result<(), &'static str> {
+    match temperature {
+        Some(temp) if !(0.0..=1.0).contains(&temp) => {
+            Err("temperature must be between 0.0 and 1.0")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse the Anthropic `tool_choice` field
+///
+/// Accepts `{"type": "auto" | "any" | "none"}` and
+/// `{"type": "tool", "name": ...}` naming a declared tool.
+fn parse_tool_choice(
+    value: Option<serde_json::Value>,
+    tools: &[ToolDefinition],
+) -> Result<Option<ToolChoice>, &'static str> {
+    let value = match value {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(value) => value,
+    };
+    let choice = match value.get("type").and_then(|t| t.as_str()) {
+        Some("auto") => ToolChoice::Auto,
+        Some("any") => ToolChoice::Required,
+        Some("none") => ToolChoice::None,
+        Some("tool") => {
+            let name = value
+                .get("name")
+                .and_then(|n| n.as_str())
+                .ok_or("tool_choice of type tool must include a name")?;
+            if !tools.iter().any(|tool| tool.name == name) {
+                return Err("tool_choice references an undeclared tool");
+            }
+            ToolChoice::Function {
+                name: name.to_string(),
+            }
+        }
+        _ => return Err("tool_choice type must be one of: auto, any, tool, none"),
+    };
+    if tools.is_empty() && matches!(choice, ToolChoice::Required) {
+        return Err("tool_choice requires at least one tool");
+    }
+    Ok(Some(choice))
+}
+
+/// Convert the `system` field and messages to core chat messages
+///
+/// `tool_result` blocks become `Tool` messages placed before the rest of the
+/// user turn, matching how OpenAI-style histories order tool output.
+async fn convert_messages(
+    system: Option<AnthropicSystem>,
+    raw_messages: Vec<AnthropicMessage>,
+    client: &HttpClient,
+    limits: &AttachmentLimits,
+) -> Result<Vec<ChatMessage>, JsonError> {
+    let mut converted = Vec::with_capacity(raw_messages.len() + 1);
+    if let Some(system) = system {
+        let content = system.into_text();
+        if !content.is_empty() {
+            converted.push(text_message(ChatRole::System, content));
+        }
+    }
+
+    for raw in raw_messages {
+        let role = match raw.role.as_str() {
+            "user" => ChatRole::User,
+            "assistant" => ChatRole::Assistant,
+            _ => {
+                return Err(invalid_request_error(
+                    "Invalid message role. Must be one of: user, assistant",
+                    "invalid_message_role",
+                ))
+            }
+        };
+        let blocks = match raw.content {
+            AnthropicContent::Text(text) => {
+                converted.push(text_message(role, text));
+                continue;
+            }
+            AnthropicContent::Blocks(blocks) => blocks,
+        };
+
+        let mut message = text_message(role, String::new());
+        let mut text_segments = Vec::new();
+        for block in blocks {
+            match block {
+                ContentBlock::Text { text } => text_segments.push(text),
+                ContentBlock::Image { source } if message.role == ChatRole::User => {
+                    let image_url = match source {
+                        ImageSource::Base64 { media_type, data } => OpenAiImageUrl {
+                            url: format!("data:{media_type};base64,{data}"),
+                            mime_type: Some(media_type),
+                            detail: None,
+                        },
+                        ImageSource::Url { url } => OpenAiImageUrl {
+                            url,
+                            mime_type: None,
+                            detail: None,
+                        },
+                    };
+                    message
+                        .attachments
+                        .push(load_image_attachment(image_url, None, client, limits).await?);
+                }
+                ContentBlock::ToolUse { id, name, input }
+                    if message.role == ChatRole::Assistant =>
+                {
+                    message.tool_calls.push(ToolCall {
+                        id,
+                        name,
+                        arguments: input.to_string(),
+                    });
+                }
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } if message.role == ChatRole::User => {
+                    let mut content = content
+                        .map(ToolResultContent::into_text)
+                        .unwrap_or_default();
+                    if is_error {
+                        content = format!("Error: {content}");
+                    }
+                    converted.push(ChatMessage {
+                        role: ChatRole::Tool,
+                        content,
+                        attachments: Vec::new(),
+                        tool_calls: Vec::new(),
+                        tool_call_id: Some(tool_use_id),
+                    });
+                }
+                _ => {
+                    return Err(invalid_request_error(
+                        "Content block type is not allowed for this message role",
+                        "invalid_messages",
+                    ))
+                }
+            }
+        }
+        message.content = text_segments.join("\n");
+
+        // A user turn made only of tool results needs no separate user message
+        if !message.content.is_empty()
+            || !message.attachments.is_empty()
+            || !message.tool_calls.is_empty()
+        {
+            converted.push(message);
+        }
+    }
+    Ok(converted)
+}
+
+fn text_message(role: ChatRole, content: String) -> ChatMessage {
+    ChatMessage {
+        role,
+        content,
+        attachments: Vec::new(),
+        tool_calls: Vec::new(),
+        tool_call_id: None,
+    }
+}
+
+/// Anthropic `stop_reason` for a finished generation
+///
+/// Engines do not report which stop sequence matched, so `stop_sequence`
+/// is never produced.
+fn stop_reason(used_tools: bool, output_tokens: u32, max_tokens: u32) -> &'static str {
+    if used_tools {
+        "tool_use"
+    } else if output_tokens >= max_tokens {
+        "max_tokens"
+    } else {
+        "end_turn"
+    }
+}
+
+/// Tool arguments as a JSON object (`tool_use.input`)
+fn tool_input(arguments: &str) -> serde_json::Value {
+    match serde_json::from_str::<serde_json::Value>(arguments) {
+        Ok(value @ serde_json::Value::Object(_)) => value,
+        _ => json!({}),
+    }
+}
+
+/// Convert a core chat response to an Anthropic `message` object
+fn message_response(model: &str, response: &ChatResponse, max_tokens: u32) -> serde_json::Value {
+    let mut content = Vec::new();
+    let mut used_tools = false;
+    if let Some(message) = response.messages.first() {
+        if !message.content.is_empty() {
+            content.push(json!({ "type": "text", "text": message.content }));
+        }
+        for call in &message.tool_calls {
+            used_tools = true;
+            content.push(json!({
+                "type": "tool_use",
+                "id": call.id,
+                "name": call.name,
+                "input": tool_input(&call.arguments)
+            }));
+        }
+    }
+
+    json!({
+        "id": "msg_unknown",
+        "type": "message",
+        "role": "assistant",
+        "model": model,
+        "content": content,
+        "stop_reason": stop_reason(used_tools, response.usage.completion_tokens, max_tokens),
+        "stop_sequence": null,
+        "usage": {
+            "input_tokens": response.usage.prompt_tokens,
+            "output_tokens": response.usage.completion_tokens
+        }
+    })
+}
+
+/// Content block currently open in a streamed message
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OpenBlock {
+    Text,
+    /// Tool call with the given `ToolCallDelta::index`
+    ToolUse(u32),
+}
+
+/// Turns `ChatStreamChunk`s into Anthropic SSE events
+///
+/// Emits `message_start`, then `content_block_start` / `content_block_delta` /
+/// `content_block_stop` per text or tool-use block, and finally
+/// `message_delta` (with `stop_reason`) and `message_stop`.
+struct StreamTranslator {
+    model: String,
+    max_tokens: u32,
+    open: Option<OpenBlock>,
+    next_index: u32,
+    used_tools: bool,
+    output_tokens: u32,
+}
+
+type SseEvent = (&'static str, serde_json::Value);
+
+impl StreamTranslator {
+    fn new(model: String, max_tokens: u32) -> Self {
+        Self {
+            model,
+            max_tokens,
+            open: None,
+            next_index: 0,
+            used_tools: false,
+            output_tokens: 0,
+        }
+    }
+
+    fn message_start(&self) -> SseEvent {
+        (
+            "message_start",
+            json!({
+                "type": "message_start",
+                "message": {
+                    "id": "msg_unknown",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": self.model,
+                    "content": [],
+                    "stop_reason": null,
+                    "stop_sequence": null,
+                    "usage": { "input_tokens": 0, "output_tokens": 0 }
+                }
+            }),
+        )
+    }
+
+    fn on_chunk(&mut self, chunk: ChatStreamChunk) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        if !chunk.delta.content.is_empty() {
+            if self.open != Some(OpenBlock::Text) {
+                self.start_block(
+                    OpenBlock::Text,
+                    json!({ "type": "text", "text": "" }),
+                    &mut events,
+                );
+            }
+            events.push(self.block_delta(json!({
+                "type": "text_delta",
+                "text": chunk.delta.content
+            })));
+        }
+        for call in chunk.tool_calls {
+            if self.open != Some(OpenBlock::ToolUse(call.index)) {
+                self.used_tools = true;
+                let id = call
+                    .id
+                    .clone()
+                    .unwrap_or_else(|| format!("toolu_{}", call.index));
+                self.start_block(
+                    OpenBlock::ToolUse(call.index),
+                    json!({
+                        "type": "tool_use",
+                        "id": id,
+                        "name": call.name.clone().unwrap_or_default(),
+                        "input": {}
+                    }),
+                    &mut events,
+                );
+            }
+            if let Some(arguments) = call.arguments.filter(|a| !a.is_empty()) {
+                events.push(self.block_delta(json!({
+                    "type": "input_json_delta",
+                    "partial_json": arguments
+                })));
+            }
+        }
+        if let Some(usage) = chunk.usage {
+            self.output_tokens = usage.completion_tokens;
+        }
+        if chunk.is_done {
+            self.close_block(&mut events);
+            events.push((
+                "message_delta",
+                json!({
+                    "type": "message_delta",
+                    "delta": {
+                        "stop_reason": stop_reason(self.used_tools, self.output_tokens, self.max_tokens),
+                        "stop_sequence": null
+                    },
+                    "usage": { "output_tokens": self.output_tokens }
+                }),
+            ));
+            events.push(("message_stop", json!({ "type": "message_stop" })));
+        }
+        events
+    }
+
+    fn start_block(
+        &mut self,
+        block: OpenBlock,
+        content_block: serde_json::Value,
+        events: &mut Vec<SseEvent>,
+    ) {
+        self.close_block(events);
+        events.push((
+            "content_block_start",
+            json!({
+                "type": "content_block_start",
+                "index": self.next_index,
+                "content_block": content_block
+            }),
+        ));
+        self.open = Some(block);
+    }
+
+    fn block_delta(&self, delta: serde_json::Value) -> SseEvent {
+        (
+            "content_block_delta",
+            json!({
+                "type": "content_block_delta",
+                "index": self.next_index,
+                "delta": delta
+            }),
+        )
+    }
+
+    fn close_block(&mut self, events: &mut Vec<SseEvent>) {
+        if self.open.take().is_some() {
+            events.push((
+                "content_block_stop",
+                json!({ "type": "content_block_stop", "index": self.next_index }),
+            ));
+            self.next_index += 1;
+        }
+    }
+}
+
+/// Anthropic-style error response (`{"type":"error","error":{...}}`)
+fn error_response(status: StatusCode, message: &str) -> axum::response::Response {
+    let error_type = match status {
+        StatusCode::UNAUTHORIZED => "authentication_error",
+        StatusCode::FORBIDDEN => "permission_error",
+        StatusCode::NOT_FOUND => "not_found_error",
+        StatusCode::PAYLOAD_TOO_LARGE => "request_too_large",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limit_error",
+        status if status.is_client_error() => "invalid_request_error",
+        _ => "api_error",
+    };
+    (
+        status,
+        axum::Json(json!({
+            "type": "error",
+            "error": {
+                "type": error_type,
+                "message": message
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Re-shape an OpenAI-style error from the shared conversion helpers
+fn from_json_error((status, body): JsonError) -> axum::response::Response {
+    let message = body["error"]["message"]
+        .as_str()
+        .unwrap_or("Invalid request");
+    error_response(status, message)
+}
+
+fn sse_event((name, data): SseEvent) -> Result<Event, axum::Error> {
+    Event::default().event(name).json_data(data).map_err(|e| {
+        axum::Error::new(std::io::Error::other(format!(
+            "Failed to serialize SSE event: {e}"
+        )))
+    })
+}
+
+/// Handle Anthropic Messages API requests (`/v1/messages`)
+///
+/// Model profiles, model groups and the managed system prompt apply as on
+/// `/v1/chat/completions`.
+pub(super) async fn handle_messages(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    api_key_label: Option<axum::Extension<ApiKeyLabel>>,
+    api_key_id: Option<axum::Extension<String>>,
+    token_budget: Option<axum::Extension<TokenBudget>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<MessagesRequest>,
+) -> axum::response::Response {
+    let MessagesRequest {
+        model,
+        max_tokens,
+        messages,
+        system,
+        stop_sequences,
+        stream,
+        temperature,
+        top_p,
+        top_k,
+        tools,
+        tool_choice,
+    } = req;
+
+    // Parse model ID (flm://{engine_id}/{model}, a profile or a group) and apply its profile
+    let target = match resolve_chat_target(&state, model.clone()).await {
+        Ok(target) => target,
+        Err(e) => return from_json_error(e),
+    };
+    let defaults = &target.defaults;
+    let Some(max_tokens) = max_tokens.or(defaults.max_tokens) else {
+        return error_response(StatusCode::BAD_REQUEST, "max_tokens: Field required");
+    };
+    let temperature = temperature.or(defaults.temperature.map(f64::from));
+    let stop_sequences = if stop_sequences.is_empty() {
+        defaults.stop.clone()
+    } else {
+        stop_sequences
+    };
+    if let Err(message) = validate_max_tokens(Some(max_tokens)) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    if let Err(message) = validate_messages(&messages) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    if let Err(message) = validate_stop_sequences(&stop_sequences) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    if let Err(message) = validate_temperature(temperature) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    let sampling = SamplingParams {
+        top_p,
+        top_k,
+        ..SamplingParams::default()
+    }
+    .or_defaults(&defaults.sampling);
+    if let Err(message) = validate_sampling(&sampling, stream) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+
+    // Tools share the OpenAI validation rules
+    let tools: Vec<OpenAiTool> = tools
+        .into_iter()
+        .map(|tool| OpenAiTool {
+            kind: "function".to_string(),
+            function: OpenAiFunctionDefinition {
+                name: tool.name,
+                description: tool.description,
+                parameters: tool.input_schema,
+            },
+        })
+        .collect();
+    if let Err(message) = validate_tools(&tools) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    let tools: Vec<ToolDefinition> = tools
+        .into_iter()
+        .map(|tool| ToolDefinition {
+            name: tool.function.name,
+            description: tool.function.description,
+            parameters: tool.function.parameters,
+        })
+        .collect();
+    let tool_choice = match parse_tool_choice(tool_choice, &tools) {
+        Ok(choice) => choice,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let skip_api_prompt = match parse_api_prompt_opt_out(&headers) {
+        Ok(skip) => skip,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+
+    let (group, backends) =
+        match resolve_chat_backends(&state, &target, api_key_scopes.as_deref()).await {
+            Ok(resolved) => resolved,
+            Err(e) => return from_json_error(e),
+        };
+    let primary = &backends[0];
+
+    let capabilities = primary.engine.capabilities();
+    let limits = AttachmentLimits::from_capabilities(&capabilities);
+    let mut messages = match convert_messages(system, messages, &HttpClient::new(), &limits).await {
+        Ok(messages) => messages,
+        Err(e) => return from_json_error(e),
+    };
+    let has_images = messages.iter().any(|m| {
+        m.attachments
+            .iter()
+            .any(|a| a.kind == MultimodalAttachmentKind::InputImage)
+    });
+    if has_images && !capabilities.vision_inputs {
+        return error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Image input is not supported by the selected engine",
+        );
+    }
+    if !tools.is_empty() && !capabilities.tools {
+        return error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Tool calling is not supported by the selected engine",
+        );
+    }
+    let api_prompt = if skip_api_prompt {
+        None
+    } else {
+        apply_api_prompt(&state, &mut messages, api_key_label, &target.model).await
+    };
+
+    let chat_req = ChatRequest {
+        engine_id: primary.engine.id(),
+        model_id: primary.model_id.clone(),
+        messages,
+        stream,
+        temperature: temperature.map(|t| t as f32),
+        max_tokens: Some(max_tokens),
+        stop: stop_sequences,
+        requested_modalities: Vec::new(),
+        tools,
+        tool_choice,
+        response_format: None,
+        sampling,
+    };
+
+    let tokens = match reserve_tokens(
+        token_budget.as_ref().map(|budget| &budget.0),
+        estimate_prompt_tokens(&chat_req.messages),
+    )
+    .await
+    {
+        Ok(tokens) => tokens,
+        Err(rejection) => {
+            return rejection
+                .with_retry_after(error_response(rejection.status(), rejection.message()))
+        }
+    };
+    let dispatch = ChatDispatch {
+        balancer: &state.balancer,
+        concurrency: &state.concurrency,
+        client: client_key(api_key_id.as_ref().map(|id| &id.0)),
+        tokens,
+    };
+
+    let mut response = if stream {
+        stream_messages(&dispatch, &backends, chat_req, model, max_tokens).await
+    } else {
+        match dispatch_chat(&dispatch, &backends, chat_req, ResponseValidation::Off).await {
+            Ok(response) => {
+                axum::Json(message_response(&model, &response, max_tokens)).into_response()
+            }
+            Err(e) => dispatch_error_response(e, "Messages request failed"),
+        }
+    };
+    attach_chat_audit_details(&mut response, group, target.profile, api_prompt);
+    response
+}
+
+/// Anthropic-style error for a chat request no backend could serve
+fn dispatch_error_response(error: ChatDispatchError, context: &str) -> axum::response::Response {
+    match error {
+        ChatDispatchError::Queue(rejection) => {
+            rejection.with_retry_after(error_response(rejection.status(), rejection.message()))
+        }
+        ChatDispatchError::Engine(EngineError::UnsupportedOperation { reason, .. }) => {
+            error_response(StatusCode::BAD_REQUEST, &reason)
+        }
+        ChatDispatchError::Violation(reason) => error_response(StatusCode::BAD_GATEWAY, &reason),
+        ChatDispatchError::Engine(e) => {
+            error!(error_type = engine_error_type(&e), "{context}");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process messages request",
+            )
+        }
+    }
+}
+
+/// Stream a messages response as typed Anthropic SSE events
+async fn stream_messages(
+    dispatch: &ChatDispatch<'_>,
+    backends: &[ChatBackend],
+    chat_req: ChatRequest,
+    model: String,
+    max_tokens: u32,
+) -> axum::response::Response {
+    let chunks = match start_chat_stream(dispatch, backends, chat_req).await {
+        Ok(chunks) => chunks,
+        Err(ChatDispatchError::Engine(EngineError::UnsupportedOperation { reason, .. })) => {
+            return error_response(StatusCode::BAD_REQUEST, &reason);
+        }
+        Err(ChatDispatchError::Queue(rejection)) => {
+            return rejection
+                .with_retry_after(error_response(rejection.status(), rejection.message()))
+        }
+        Err(ChatDispatchError::Violation(reason)) => {
+            return error_response(StatusCode::BAD_GATEWAY, &reason);
+        }
+        Err(ChatDispatchError::Engine(e)) => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Failed to start messages stream"
+            );
+            return error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to start streaming messages",
+            );
+        }
+    };
+
+    let tokens = dispatch.tokens.clone();
+    let mut translator = StreamTranslator::new(model, max_tokens);
+    let start = futures::stream::iter(vec![sse_event(translator.message_start())]);
+    let events = chunks.flat_map(move |chunk_result| {
+        let events: Vec<Result<Event, axum::Error>> = match chunk_result {
+            Ok(chunk) => {
+                if let Some(tokens) = &tokens {
+                    tokens.observe_chat_chunk(&chunk);
+                }
+                translator
+                    .on_chunk(chunk)
+                    .into_iter()
+                    .map(sse_event)
+                    .collect()
+            }
+            Err(e) => {
+                error!(
+                    error_type = engine_error_type(&e),
+                    "Error in messages stream"
+                );
+                vec![sse_event((
+                    "error",
+                    json!({
+                        "type": "error",
+                        "error": { "type": "api_error", "message": "Stream error" }
+                    }),
+                ))]
+            }
+        };
+        futures::stream::iter(events)
+    });
+
+    Sse::new(start.chain(events)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flm_core::domain::chat::{ToolCallDelta, UsageStats};
+
+    fn stream_chunk(
+        content: &str,
+        tool_calls: Vec<ToolCallDelta>,
+        is_done: bool,
+    ) -> ChatStreamChunk {
+        ChatStreamChunk {
+            delta: text_message(ChatRole::Assistant, content.to_string()),
+            usage: is_done.then_some(UsageStats {
+                prompt_tokens: 5,
+                completion_tokens: 3,
+                total_tokens: 8,
+            }),
+            is_done,
+            audio: Vec::new(),
+            tool_calls,
+            logprobs: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_convert_messages_maps_system_and_tool_blocks() {
+        let request: MessagesRequest = serde_json::from_value(json!({
+            "model": "flm://ollama/llama3",
+            "max_tokens": 64,
+            "system": [{ "type": "text", "text": "Be brief." }],
+            "messages": [
+                { "role": "user", "content": "Weather in Paris?" },
+                {
+                    "role": "assistant",
+                    "content": [
+                        { "type": "text", "text": "Checking." },
+                        { "type": "tool_use", "id": "toolu_1", "name": "weather", "input": { "city": "Paris" } }
+                    ]
+                },
+                {
+                    "role": "user",
+                    "content": [
+                        { "type": "tool_result", "tool_use_id": "toolu_1", "content": "Sunny" },
+                        { "type": "text", "text": "Thanks" }
+                    ]
+                }
+            ]
+        }))
+        .unwrap();
+
+        let limits = AttachmentLimits::from_capabilities(&Default::default());
+        let messages = convert_messages(
+            request.system,
+            request.messages,
+            &HttpClient::new(),
+            &limits,
+        )
+        .await
+        .unwrap();
+
+        let roles: Vec<ChatRole> = messages.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(
+            roles,
+            vec![
+                ChatRole::System,
+                ChatRole::User,
+                ChatRole::Assistant,
+                ChatRole::Tool,
+                ChatRole::User
+            ]
+        );
+        assert_eq!(messages[0].content, "Be brief.");
+        assert_eq!(messages[2].tool_calls[0].name, "weather");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&messages[2].tool_calls[0].arguments)
+                .unwrap(),
+            json!({ "city": "Paris" })
+        );
+        assert_eq!(messages[3].tool_call_id.as_deref(), Some("toolu_1"));
+        assert_eq!(messages[3].content, "Sunny");
+        assert_eq!(messages[4].content, "Thanks");
+    }
+
+    #[tokio::test]
+    async fn test_convert_messages_rejects_tool_use_from_user() {
+        let messages: Vec<AnthropicMessage> = serde_json::from_value(json!([{
+            "role": "user",
+            "content": [{ "type": "tool_use", "id": "toolu_1", "name": "weather", "input": {} }]
+        }]))
+        .unwrap();
+
+        let limits = AttachmentLimits::from_capabilities(&Default::default());
+        let result = convert_messages(None, messages, &HttpClient::new(), &limits).await;
+        assert!(matches!(result, Err((StatusCode::BAD_REQUEST, _))));
+    }
+
+    #[test]
+    fn test_message_response_reports_tool_use() {
+        let mut message = text_message(ChatRole::Assistant, String::new());
+        message.tool_calls.push(ToolCall {
+            id: "call_1".to_string(),
+            name: "weather".to_string(),
+            arguments: "{\"city\":\"Paris\"}".to_string(),
+        });
+        let response = ChatResponse {
+            usage: UsageStats {
+                prompt_tokens: 10,
+                completion_tokens: 4,
+                total_tokens: 14,
+            },
+            messages: vec![message],
+            audio: Vec::new(),
+            logprobs: Vec::new(),
+        };
+
+        let body = message_response("flm://ollama/llama3", &response, 64);
+        assert_eq!(body["stop_reason"], "tool_use");
+        assert_eq!(body["content"].as_array().unwrap().len(), 1);
+        assert_eq!(body["content"][0]["type"], "tool_use");
+        assert_eq!(body["content"][0]["input"]["city"], "Paris");
+        assert_eq!(body["usage"]["input_tokens"], 10);
+        assert_eq!(body["usage"]["output_tokens"], 4);
+    }
+
+    #[test]
+    fn test_stream_translator_event_sequence() {
+        let mut translator = StreamTranslator::new("flm://ollama/llama3".to_string(), 3);
+        let mut events = vec![translator.message_start()];
+        events.extend(translator.on_chunk(stream_chunk("Hel", Vec::new(), false)));
+        events.extend(translator.on_chunk(stream_chunk("lo", Vec::new(), false)));
+        events.extend(translator.on_chunk(stream_chunk(
+            "",
+            vec![ToolCallDelta {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("weather".to_string()),
+                arguments: Some("{\"city\":".to_string()),
+            }],
+            false,
+        )));
+        events.extend(translator.on_chunk(stream_chunk("", Vec::new(), true)));
+
+        let names: Vec<&str> = events.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "message_start",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_stop",
+                "message_delta",
+                "message_stop"
+            ]
+        );
+        assert_eq!(events[2].1["delta"]["text"], "Hel");
+        assert_eq!(events[5].1["index"], 1);
+        assert_eq!(events[5].1["content_block"]["id"], "call_1");
+        assert_eq!(events[6].1["delta"]["type"], "input_json_delta");
+        assert_eq!(events[8].1["delta"]["stop_reason"], "tool_use");
+        assert_eq!(events[8].1["usage"]["output_tokens"], 3);
+    }
+}
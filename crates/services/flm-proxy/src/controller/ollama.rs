@@ -0,0 +1,1225 @@
+//! Ollama-compatible API facade (`/api/chat`, `/api/generate`, `/api/tags`, `/api/show`)
+//!
+//! Lets Ollama-only clients use any registered engine. Models are named
+//! `{engine_id}/{model}` (the `flm://` prefix is optional) and streaming
+//! responses are NDJSON, as in Ollama. Enabled with `ProxyConfig::ollama_api`;
+//! the routes sit behind the same auth, policy and audit middleware as the
+//! OpenAI-compatible endpoints, and chat requests share their model profile,
+//! model group and managed system prompt resolution.
+
+use super::{
+    apply_api_prompt, attach_chat_audit_details, dispatch_chat, engine_error_type, in_model_scope,
+    is_supported_image_mime, parse_api_prompt_opt_out, parse_response_format,
+    resolve_chat_backends, resolve_chat_target, split_model_id, start_chat_stream,
+    validate_engine_id, validate_max_tokens, validate_model_name, validate_sampling,
+    validate_stop_sequences, validate_temperature, validate_tools, AttachmentLimits, ChatBackend,
+    ChatDispatch, ChatDispatchError, ChatTarget, JsonError, OpenAiTool, ResponseValidation,
+};
+use crate::concurrency::client_key;
+use crate::middleware::{ApiKeyLabel, AppState};
+use crate::token_quota::{
+    estimate_prompt_tokens, estimate_text_tokens, reserve_tokens, QuotaRejection, TokenBudget,
+};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use flm_core::domain::chat::{
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, CompletionRequest, MultimodalAttachment,
+    MultimodalAttachmentKind, ResponseFormat, SamplingParams, ToolCall, ToolCallDelta,
+    ToolDefinition, UsageStats,
+};
+use flm_core::domain::engine::ModelInfo;
+use flm_core::domain::models::ModelProfileDefaults;
+use flm_core::domain::security::ApiKeyScopes;
+use flm_core::error::EngineError;
+use flm_core::ports::LlmEngine;
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tracing::error;
+
+/// Ollama `/api/chat` request
+#[derive(serde::Deserialize)]
+pub(super) struct OllamaChatRequest {
+    model: String,
+    messages: Vec<OllamaMessage>,
+    #[serde(default)]
+    tools: Vec<OpenAiTool>,
+    #[serde(default)]
+    format: Option<serde_json::Value>,
+    #[serde(default)]
+    options: OllamaOptions,
+    #[serde(default = "default_stream")]
+    stream: bool,
+}
+
+/// Ollama `/api/generate` request
+#[derive(serde::Deserialize)]
+pub(super) struct OllamaGenerateRequest {
+    model: String,
+    #[serde(default)]
+    prompt: String,
+    #[serde(default)]
+    suffix: Option<String>,
+    #[serde(default)]
+    system: Option<String>,
+    #[serde(default)]
+    raw: bool,
+    #[serde(default)]
+    images: Vec<String>,
+    #[serde(default)]
+    format: Option<serde_json::Value>,
+    #[serde(default)]
+    options: OllamaOptions,
+    #[serde(default = "default_stream")]
+    stream: bool,
+}
+
+/// Ollama `/api/show` request (`name` is the pre-0.5 field name)
+#[derive(serde::Deserialize)]
+pub(super) struct OllamaShowRequest {
+    #[serde(alias = "name")]
+    model: String,
+}
+
+/// Ollama streams unless told otherwise
+fn default_stream() -> bool {
+    true
+}
+
+#[derive(serde::Deserialize)]
+struct OllamaMessage {
+    role: String,
+    #[serde(default)]
+    content: String,
+    /// Base64-encoded images (no data URL prefix)
+    #[serde(default)]
+    images: Vec<String>,
+    #[serde(default)]
+    tool_calls: Vec<OllamaToolCall>,
+}
+
+#[derive(serde::Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(serde::Deserialize)]
+struct OllamaFunctionCall {
+    name: String,
+    /// Arguments as a JSON object (not an encoded string as in OpenAI)
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+/// Subset of Ollama `options` mapped onto core request fields
+#[derive(Default, serde::Deserialize)]
+struct OllamaOptions {
+    #[serde(default)]
+    temperature: Option<f64>,
+    /// `-1` / `-2` mean "no limit" in Ollama
+    #[serde(default)]
+    num_predict: Option<i64>,
+    #[serde(default)]
+    stop: Vec<String>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    top_k: Option<u32>,
+    #[serde(default)]
+    seed: Option<i64>,
+    #[serde(default)]
+    repeat_penalty: Option<f32>,
+    #[serde(default)]
+    presence_penalty: Option<f32>,
+    #[serde(default)]
+    frequency_penalty: Option<f32>,
+}
+
+impl OllamaOptions {
+    fn max_tokens(&self) -> Option<u32> {
+        self.num_predict
+            .filter(|n| *n > 0)
+            .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+    }
+
+    fn sampling(&self) -> SamplingParams {
+        SamplingParams {
+            top_p: self.top_p,
+            top_k: self.top_k,
+            seed: self.seed,
+            presence_penalty: self.presence_penalty,
+            frequency_penalty: self.frequency_penalty,
+            repeat_penalty: self.repeat_penalty,
+            ..SamplingParams::default()
+        }
+    }
+
+    /// Fill options the client left unset from the model profile's defaults
+    fn or_defaults(self, defaults: &ModelProfileDefaults) -> Self {
+        let sampling = &defaults.sampling;
+        Self {
+            temperature: self.temperature.or(defaults.temperature.map(f64::from)),
+            num_predict: self.num_predict.or(defaults.max_tokens.map(i64::from)),
+            stop: if self.stop.is_empty() {
+                defaults.stop.clone()
+            } else {
+                self.stop
+            },
+            top_p: self.top_p.or(sampling.top_p),
+            top_k: self.top_k.or(sampling.top_k),
+            seed: self.seed.or(sampling.seed),
+            repeat_penalty: self.repeat_penalty.or(sampling.repeat_penalty),
+            presence_penalty: self.presence_penalty.or(sampling.presence_penalty),
+            frequency_penalty: self.frequency_penalty.or(sampling.frequency_penalty),
+        }
+    }
+
+    /// Validate with the same rules as `/v1/chat/completions`
+    fn validate(&self, stream: bool) -> Result<(), &'static str> {
+        validate_temperature(self.temperature)?;
+        validate_max_tokens(self.max_tokens())?;
+        validate_stop_sequences(&self.stop)?;
+        validate_sampling(&self.sampling(), stream)
+    }
+}
+
+/// Target of an Ollama model name
+struct ResolvedModel {
+    engine_id: String,
+    /// Normalized `flm://{engine_id}/{model}` ID
+    model_id: String,
+}
+
+/// Normalize an Ollama model name to a `flm://` model ID
+fn normalize_model_id(name: &str) -> String {
+    if name.starts_with("flm://") {
+        name.to_string()
+    } else {
+        format!("flm://{name}")
+    }
+}
+
+/// Resolve `{engine_id}/{model}` (or `flm://{engine_id}/{model}`) to an engine and model ID
+fn resolve_model(name: &str) -> Result<ResolvedModel, &'static str> {
+    let model_id = normalize_model_id(name);
+    let (engine_id, model_name) =
+        split_model_id(&model_id).ok_or("model must be named {engine_id}/{model}")?;
+    validate_engine_id(&engine_id)?;
+    validate_model_name(&model_name)?;
+    Ok(ResolvedModel {
+        engine_id,
+        model_id,
+    })
+}
+
+/// Resolve the model of a chat request, including profiles and model groups
+async fn resolve_chat_model(
+    state: &AppState,
+    name: &str,
+) -> Result<ChatTarget, axum::response::Response> {
+    resolve_chat_target(state, normalize_model_id(name))
+        .await
+        .map_err(from_json_error)
+}
+
+/// Ollama model name for a normalized model ID
+fn ollama_model_name(model_id: &str) -> &str {
+    model_id.strip_prefix("flm://").unwrap_or(model_id)
+}
+
+/// Ollama-style error response (`{"error": "..."}`)
+fn error_response(status: StatusCode, message: &str) -> axum::response::Response {
+    (status, axum::Json(json!({ "error": message }))).into_response()
+}
+
+/// Re-shape an OpenAI-style error from the shared resolution helpers
+fn from_json_error((status, body): JsonError) -> axum::response::Response {
+    let message = body["error"]["message"]
+        .as_str()
+        .unwrap_or("Invalid request");
+    error_response(status, message)
+}
+
+fn engine_error_response(error: EngineError) -> axum::response::Response {
+    match error {
+        EngineError::UnsupportedOperation { reason, .. } => {
+            error_response(StatusCode::BAD_REQUEST, &reason)
+        }
+        e => {
+            error!(
+                error_type = engine_error_type(&e),
+                "Ollama facade request failed"
+            );
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to process request",
+            )
+        }
+    }
+}
+
+async fn find_engine(
+    state: &AppState,
+    engine_id: &str,
+) -> Result<Arc<dyn LlmEngine>, axum::response::Response> {
+    state
+        .engine_repo
+        .list_registered()
+        .await
+        .into_iter()
+        .find(|e| e.id() == engine_id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Engine not found"))
+}
+
+/// Map Ollama `format` (`"json"` or a JSON schema) to a response format
+fn parse_format(format: Option<serde_json::Value>) -> Result<Option<ResponseFormat>, &'static str> {
+    match format {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(s)) if s.is_empty() => Ok(None),
+        Some(serde_json::Value::String(s)) if s == "json" => Ok(Some(ResponseFormat::JsonObject)),
+        Some(schema @ serde_json::Value::Object(_)) => parse_response_format(Some(json!({
+            "type": "json_schema",
+            "json_schema": { "name": "ollama_format", "schema": schema }
+        }))),
+        Some(_) => Err("format must be \"json\" or a JSON schema object"),
+    }
+}
+
+/// Detect the MIME type of a decoded image from its magic bytes
+fn sniff_image_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Decode Ollama `images` (raw base64) into attachments
+fn decode_images(
+    images: Vec<String>,
+    limits: &AttachmentLimits,
+) -> Result<Vec<MultimodalAttachment>, &'static str> {
+    images
+        .into_iter()
+        .map(|image| {
+            // Base64 is 4/3 the decoded size; reject oversized input before decoding
+            if image.len() / 4 * 3 > limits.data_image {
+                return Err("Image exceeds the maximum allowed size");
+            }
+            let data = general_purpose::STANDARD
+                .decode(image.trim())
+                .map_err(|_| "images must be base64-encoded")?;
+            let mime = sniff_image_mime(&data)
+                .filter(|mime| is_supported_image_mime(mime))
+                .ok_or("Unsupported image format. Allowed: PNG, JPEG, WebP")?;
+            Ok(MultimodalAttachment {
+                kind: MultimodalAttachmentKind::InputImage,
+                size_bytes: Some(u64::try_from(data.len()).unwrap_or(u64::MAX)),
+                data,
+                mime_type: mime.to_string(),
+                filename: None,
+                detail: None,
+                duration_ms: None,
+            })
+        })
+        .collect()
+}
+
+/// Convert Ollama chat messages to core chat messages
+///
+/// Ollama tool calls carry no IDs, so IDs are generated for assistant tool
+/// calls and handed out in order to the `tool` messages that follow.
+fn convert_messages(
+    raw_messages: Vec<OllamaMessage>,
+    limits: &AttachmentLimits,
+) -> Result<Vec<ChatMessage>, &'static str> {
+    let mut converted = Vec::with_capacity(raw_messages.len());
+    let mut pending_call_ids = VecDeque::new();
+    let mut next_call_id = 0;
+    for raw in raw_messages {
+        let role = match raw.role.as_str() {
+            "system" => ChatRole::System,
+            "user" => ChatRole::User,
+            "assistant" => ChatRole::Assistant,
+            "tool" => ChatRole::Tool,
+            _ => return Err("Invalid message role. Must be one of: system, user, assistant, tool"),
+        };
+        let tool_calls: Vec<ToolCall> = raw
+            .tool_calls
+            .into_iter()
+            .map(|call| {
+                let id = format!("call_{next_call_id}");
+                next_call_id += 1;
+                pending_call_ids.push_back(id.clone());
+                ToolCall {
+                    id,
+                    name: call.function.name,
+                    arguments: call.function.arguments.to_string(),
+                }
+            })
+            .collect();
+        let tool_call_id = if role == ChatRole::Tool {
+            let id = pending_call_ids.pop_front().unwrap_or_else(|| {
+                next_call_id += 1;
+                format!("call_{}", next_call_id - 1)
+            });
+            Some(id)
+        } else {
+            None
+        };
+        converted.push(ChatMessage {
+            role,
+            content: raw.content,
+            attachments: decode_images(raw.images, limits)?,
+            tool_calls,
+            tool_call_id,
+        });
+    }
+    Ok(converted)
+}
+
+/// Ollama `done_reason` for a finished generation
+fn done_reason(usage: &UsageStats, max_tokens: Option<u32>) -> &'static str {
+    match max_tokens {
+        Some(max) if usage.completion_tokens >= max => "length",
+        _ => "stop",
+    }
+}
+
+fn tool_calls_json(calls: &[ToolCall]) -> serde_json::Value {
+    calls
+        .iter()
+        .map(|call| {
+            let arguments = serde_json::from_str::<serde_json::Value>(&call.arguments)
+                .ok()
+                .filter(|value| value.is_object())
+                .unwrap_or_else(|| json!({}));
+            json!({ "function": { "name": call.name, "arguments": arguments } })
+        })
+        .collect()
+}
+
+fn created_at() -> String {
+    Utc::now().to_rfc3339()
+}
+
+/// NDJSON response from a stream of JSON lines
+fn ndjson_response(
+    lines: impl futures::Stream<Item = serde_json::Value> + Send + 'static,
+) -> axum::response::Response {
+    let body = axum::body::Body::from_stream(
+        lines.map(|line| Ok::<_, std::convert::Infallible>(format!("{line}\n"))),
+    );
+    (
+        [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+        body,
+    )
+        .into_response()
+}
+
+/// Accumulates streamed tool call fragments into complete calls
+#[derive(Default)]
+struct ToolCallAccumulator {
+    calls: Vec<(u32, ToolCall)>,
+}
+
+impl ToolCallAccumulator {
+    fn push(&mut self, delta: ToolCallDelta) {
+        let position = match self
+            .calls
+            .iter()
+            .position(|(index, _)| *index == delta.index)
+        {
+            Some(position) => position,
+            None => {
+                self.calls.push((
+                    delta.index,
+                    ToolCall {
+                        id: String::new(),
+                        name: String::new(),
+                        arguments: String::new(),
+                    },
+                ));
+                self.calls.len() - 1
+            }
+        };
+        let call = &mut self.calls[position].1;
+        if let Some(id) = delta.id {
+            call.id = id;
+        }
+        if let Some(name) = delta.name {
+            call.name.push_str(&name);
+        }
+        if let Some(arguments) = delta.arguments {
+            call.arguments.push_str(&arguments);
+        }
+    }
+
+    fn take(&mut self) -> Vec<ToolCall> {
+        std::mem::take(&mut self.calls)
+            .into_iter()
+            .map(|(_, call)| call)
+            .collect()
+    }
+}
+
+/// Build a chat request, checking engine capabilities for images and tools
+fn build_chat_request(
+    backend: &ChatBackend,
+    messages: Vec<ChatMessage>,
+    tools: Vec<ToolDefinition>,
+    response_format: Option<ResponseFormat>,
+    options: OllamaOptions,
+    stream: bool,
+) -> Result<ChatRequest, axum::response::Response> {
+    let capabilities = backend.engine.capabilities();
+    let has_images = messages.iter().any(|m| !m.attachments.is_empty());
+    if has_images && !capabilities.vision_inputs {
+        return Err(error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Image input is not supported by the selected engine",
+        ));
+    }
+    if !tools.is_empty() && !capabilities.tools {
+        return Err(error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Tool calling is not supported by the selected engine",
+        ));
+    }
+    Ok(ChatRequest {
+        engine_id: backend.engine.id(),
+        model_id: backend.model_id.clone(),
+        messages,
+        stream,
+        temperature: options.temperature.map(|t| t as f32),
+        max_tokens: options.max_tokens(),
+        sampling: options.sampling(),
+        stop: options.stop,
+        requested_modalities: Vec::new(),
+        tools,
+        tool_choice: None,
+        response_format,
+    })
+}
+
+/// Field carrying generated text: `message` for `/api/chat`, `response` for `/api/generate`
+#[derive(Clone, Copy)]
+enum ChatShape {
+    Chat,
+    Generate,
+}
+
+impl ChatShape {
+    fn body(
+        self,
+        model: &str,
+        content: &str,
+        tool_calls: &[ToolCall],
+        done: bool,
+    ) -> serde_json::Value {
+        let mut body = json!({ "model": model, "created_at": created_at(), "done": done });
+        match self {
+            ChatShape::Chat => {
+                let mut message = json!({ "role": "assistant", "content": content });
+                if !tool_calls.is_empty() {
+                    message["tool_calls"] = tool_calls_json(tool_calls);
+                }
+                body["message"] = message;
+            }
+            ChatShape::Generate => body["response"] = json!(content),
+        }
+        body
+    }
+}
+
+/// Usage reported when the engine omits it from the final chunk
+fn no_usage() -> UsageStats {
+    UsageStats {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        total_tokens: 0,
+    }
+}
+
+fn finish_body(body: &mut serde_json::Value, usage: &UsageStats, done_reason: &str) {
+    body["done_reason"] = json!(done_reason);
+    body["prompt_eval_count"] = json!(usage.prompt_tokens);
+    body["eval_count"] = json!(usage.completion_tokens);
+}
+
+fn chat_response_body(
+    shape: ChatShape,
+    model: &str,
+    response: &ChatResponse,
+    max_tokens: Option<u32>,
+) -> serde_json::Value {
+    let (content, tool_calls) = response
+        .messages
+        .first()
+        .map(|m| (m.content.as_str(), m.tool_calls.as_slice()))
+        .unwrap_or(("", &[]));
+    let mut body = shape.body(model, content, tool_calls, true);
+    finish_body(
+        &mut body,
+        &response.usage,
+        done_reason(&response.usage, max_tokens),
+    );
+    body
+}
+
+/// Run a chat request and answer in Ollama's chat or generate shape
+///
+/// The request reserves its estimated prompt tokens against `budget` and is
+/// dispatched to `backends` in order (failing over within a model group);
+/// `client` is the queue fairness key and `model` the name echoed back.
+async fn run_chat(
+    state: &AppState,
+    client: &str,
+    budget: Option<&TokenBudget>,
+    backends: &[ChatBackend],
+    req: ChatRequest,
+    model: String,
+    shape: ChatShape,
+) -> axum::response::Response {
+    let tokens = match reserve_tokens(budget, estimate_prompt_tokens(&req.messages)).await {
+        Ok(tokens) => tokens,
+        Err(rejection) => return quota_error_response(&rejection),
+    };
+    let dispatch = ChatDispatch {
+        balancer: &state.balancer,
+        concurrency: &state.concurrency,
+        client,
+        tokens,
+    };
+    let max_tokens = req.max_tokens;
+    if !req.stream {
+        return match dispatch_chat(&dispatch, backends, req, ResponseValidation::Off).await {
+            Ok(response) => {
+                axum::Json(chat_response_body(shape, &model, &response, max_tokens)).into_response()
+            }
+            Err(e) => dispatch_error_response(e),
+        };
+    }
+
+    let chunks = match start_chat_stream(&dispatch, backends, req).await {
+        Ok(chunks) => chunks,
+        Err(e) => return dispatch_error_response(e),
+    };
+    let tokens = dispatch.tokens.clone();
+    let mut tool_calls = ToolCallAccumulator::default();
+    let lines = chunks.filter_map(move |chunk_result| {
+        let line = match chunk_result {
+            Ok(chunk) => {
+                if let Some(tokens) = &tokens {
+                    tokens.observe_chat_chunk(&chunk);
+                }
+                for delta in chunk.tool_calls {
+                    tool_calls.push(delta);
+                }
+                if chunk.is_done {
+                    let calls = tool_calls.take();
+                    let mut body = shape.body(&model, &chunk.delta.content, &calls, true);
+                    let usage = chunk.usage.unwrap_or_else(no_usage);
+                    finish_body(&mut body, &usage, done_reason(&usage, max_tokens));
+                    Some(body)
+                } else if chunk.delta.content.is_empty() {
+                    None
+                } else {
+                    Some(shape.body(&model, &chunk.delta.content, &[], false))
+                }
+            }
+            Err(e) => {
+                error!(
+                    error_type = engine_error_type(&e),
+                    "Error in Ollama facade stream"
+                );
+                Some(json!({ "error": "Stream error" }))
+            }
+        };
+        futures::future::ready(line)
+    });
+    ndjson_response(lines)
+}
+
+/// Ollama-style error for a chat request no backend could serve
+fn dispatch_error_response(error: ChatDispatchError) -> axum::response::Response {
+    match error {
+        ChatDispatchError::Queue(rejection) => {
+            rejection.with_retry_after(error_response(rejection.status(), rejection.message()))
+        }
+        ChatDispatchError::Violation(reason) => error_response(StatusCode::BAD_GATEWAY, &reason),
+        ChatDispatchError::Engine(e) => engine_error_response(e),
+    }
+}
+
+/// Ollama-style error for a request over the API key's token limits
+fn quota_error_response(rejection: &QuotaRejection) -> axum::response::Response {
+    rejection.with_retry_after(error_response(rejection.status(), rejection.message()))
+}
+
+/// Handle `/api/chat`
+pub(super) async fn handle_chat(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    api_key_label: Option<axum::Extension<ApiKeyLabel>>,
+    api_key_id: Option<axum::Extension<String>>,
+    token_budget: Option<axum::Extension<TokenBudget>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OllamaChatRequest>,
+) -> axum::response::Response {
+    let OllamaChatRequest {
+        model,
+        messages,
+        tools,
+        format,
+        options,
+        stream,
+    } = req;
+
+    let name = ollama_model_name(&normalize_model_id(&model)).to_string();
+    let target = match resolve_chat_model(&state, &model).await {
+        Ok(target) => target,
+        Err(response) => return response,
+    };
+    if messages.len() > 100 {
+        return error_response(StatusCode::BAD_REQUEST, "Too many messages");
+    }
+    if messages.iter().any(|m| m.content.len() > 1_048_576) {
+        return error_response(StatusCode::BAD_REQUEST, "Message content too long");
+    }
+    let options = options.or_defaults(&target.defaults);
+    if let Err(message) = options.validate(stream) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    if let Err(message) = validate_tools(&tools) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    let response_format = match parse_format(format) {
+        Ok(format) => format,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let skip_api_prompt = match parse_api_prompt_opt_out(&headers) {
+        Ok(skip) => skip,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let tools: Vec<ToolDefinition> = tools
+        .into_iter()
+        .map(|tool| ToolDefinition {
+            name: tool.function.name,
+            description: tool.function.description,
+            parameters: tool.function.parameters,
+        })
+        .collect();
+
+    let (group, backends) =
+        match resolve_chat_backends(&state, &target, api_key_scopes.as_deref()).await {
+            Ok(resolved) => resolved,
+            Err(e) => return from_json_error(e),
+        };
+    let limits = AttachmentLimits::from_capabilities(&backends[0].engine.capabilities());
+    let mut messages = match convert_messages(messages, &limits) {
+        Ok(messages) => messages,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    // Ollama answers an empty conversation by loading the model
+    if messages.is_empty() {
+        let mut body = ChatShape::Chat.body(&name, "", &[], true);
+        body["done_reason"] = json!("load");
+        return axum::Json(body).into_response();
+    }
+    let api_prompt = if skip_api_prompt {
+        None
+    } else {
+        apply_api_prompt(&state, &mut messages, api_key_label, &target.model).await
+    };
+
+    let mut response = match build_chat_request(
+        &backends[0],
+        messages,
+        tools,
+        response_format,
+        options,
+        stream,
+    ) {
+        Ok(req) => {
+            let client = client_key(api_key_id.as_ref().map(|id| &id.0));
+            run_chat(
+                &state,
+                client,
+                token_budget.as_ref().map(|budget| &budget.0),
+                &backends,
+                req,
+                name,
+                ChatShape::Chat,
+            )
+            .await
+        }
+        Err(response) => response,
+    };
+    attach_chat_audit_details(&mut response, group, target.profile, api_prompt);
+    response
+}
+
+/// Handle `/api/generate`
+///
+/// Raw prompts and fill-in-the-middle (`suffix`) go to the engine's raw
+/// completion endpoint; everything else is sent as a chat turn so the model's
+/// chat template applies, as Ollama does.
+pub(super) async fn handle_generate(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    api_key_label: Option<axum::Extension<ApiKeyLabel>>,
+    api_key_id: Option<axum::Extension<String>>,
+    token_budget: Option<axum::Extension<TokenBudget>>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OllamaGenerateRequest>,
+) -> axum::response::Response {
+    let OllamaGenerateRequest {
+        model,
+        prompt,
+        suffix,
+        system,
+        raw,
+        images,
+        format,
+        options,
+        stream,
+    } = req;
+
+    let name = ollama_model_name(&normalize_model_id(&model)).to_string();
+    let target = match resolve_chat_model(&state, &model).await {
+        Ok(target) => target,
+        Err(response) => return response,
+    };
+    if prompt.len() > 1_048_576 || suffix.as_ref().is_some_and(|s| s.len() > 1_048_576) {
+        return error_response(StatusCode::BAD_REQUEST, "prompt or suffix is too long");
+    }
+    let options = options.or_defaults(&target.defaults);
+    if let Err(message) = options.validate(stream) {
+        return error_response(StatusCode::BAD_REQUEST, message);
+    }
+    let response_format = match parse_format(format) {
+        Ok(format) => format,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let skip_api_prompt = match parse_api_prompt_opt_out(&headers) {
+        Ok(skip) => skip,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+
+    let (group, backends) =
+        match resolve_chat_backends(&state, &target, api_key_scopes.as_deref()).await {
+            Ok(resolved) => resolved,
+            Err(e) => return from_json_error(e),
+        };
+    // Ollama answers an empty prompt by loading the model
+    if prompt.is_empty() && suffix.is_none() {
+        let mut body = ChatShape::Generate.body(&name, "", &[], true);
+        body["done_reason"] = json!("load");
+        return axum::Json(body).into_response();
+    }
+
+    if !raw && suffix.is_none() {
+        let limits = AttachmentLimits::from_capabilities(&backends[0].engine.capabilities());
+        let attachments = match decode_images(images, &limits) {
+            Ok(attachments) => attachments,
+            Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+        };
+        let mut messages = Vec::new();
+        if let Some(system) = system.filter(|s| !s.is_empty()) {
+            messages.push(ChatMessage {
+                role: ChatRole::System,
+                content: system,
+                attachments: Vec::new(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
+            });
+        }
+        messages.push(ChatMessage {
+            role: ChatRole::User,
+            content: prompt,
+            attachments,
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        });
+        let api_prompt = if skip_api_prompt {
+            None
+        } else {
+            apply_api_prompt(&state, &mut messages, api_key_label, &target.model).await
+        };
+        let mut response = match build_chat_request(
+            &backends[0],
+            messages,
+            Vec::new(),
+            response_format,
+            options,
+            stream,
+        ) {
+            Ok(req) => {
+                let client = client_key(api_key_id.as_ref().map(|id| &id.0));
+                run_chat(
+                    &state,
+                    client,
+                    token_budget.as_ref().map(|budget| &budget.0),
+                    &backends,
+                    req,
+                    name,
+                    ChatShape::Generate,
+                )
+                .await
+            }
+            Err(response) => response,
+        };
+        attach_chat_audit_details(&mut response, group, target.profile, api_prompt);
+        return response;
+    }
+
+    if !images.is_empty() || response_format.is_some() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "images and format are not supported for raw or suffix prompts",
+        );
+    }
+    // Raw completions go to a single engine; there is no chat template to fail over with
+    if group.is_some() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "model groups are not supported for raw or suffix prompts",
+        );
+    }
+    let engine = backends[0].engine.clone();
+    let req = CompletionRequest {
+        engine_id: target.engine_id,
+        model_id: target.model,
+        prompt,
+        suffix,
+        stream,
+        temperature: options.temperature.map(|t| t as f32),
+        max_tokens: options.max_tokens(),
+        sampling: options.sampling(),
+        stop: options.stop,
+        best_of: None,
+    };
+    let estimated = estimate_text_tokens(&req.prompt)
+        + req.suffix.as_deref().map(estimate_text_tokens).unwrap_or(0);
+    let tokens =
+        match reserve_tokens(token_budget.as_ref().map(|budget| &budget.0), estimated).await {
+            Ok(tokens) => tokens,
+            Err(rejection) => return quota_error_response(&rejection),
+        };
+    if let Some(tokens) = &tokens {
+        tokens.set_target(&req.engine_id, &req.model_id);
+    }
+
+    if !stream {
+        return match engine.complete(req).await {
+            Ok(response) => {
+                if let Some(tokens) = &tokens {
+                    tokens.record(&response.usage);
+                }
+                let choice = response.choices.into_iter().next();
+                let text = choice.as_ref().map_or("", |c| c.text.as_str());
+                let mut body = ChatShape::Generate.body(&name, text, &[], true);
+                let reason = match choice.and_then(|c| c.finish_reason).as_deref() {
+                    Some("length") => "length",
+                    _ => "stop",
+                };
+                finish_body(&mut body, &response.usage, reason);
+                axum::Json(body).into_response()
+            }
+            Err(e) => engine_error_response(e),
+        };
+    }
+
+    let chunks = match engine.complete_stream(req).await {
+        Ok(chunks) => chunks,
+        Err(e) => return engine_error_response(e),
+    };
+    let lines = chunks.filter_map(move |chunk_result| {
+        if let (Some(tokens), Ok(chunk)) = (&tokens, &chunk_result) {
+            tokens.observe_output(&chunk.text, chunk.usage.as_ref());
+        }
+        let line = match chunk_result {
+            Ok(chunk) if chunk.is_done => {
+                let mut body = ChatShape::Generate.body(&name, &chunk.text, &[], true);
+                let reason = match chunk.finish_reason.as_deref() {
+                    Some("length") => "length",
+                    _ => "stop",
+                };
+                finish_body(&mut body, &chunk.usage.unwrap_or_else(no_usage), reason);
+                Some(body)
+            }
+            Ok(chunk) if chunk.text.is_empty() => None,
+            Ok(chunk) => Some(ChatShape::Generate.body(&name, &chunk.text, &[], false)),
+            Err(e) => {
+                error!(
+                    error_type = engine_error_type(&e),
+                    "Error in Ollama facade stream"
+                );
+                Some(json!({ "error": "Stream error" }))
+            }
+        };
+        futures::future::ready(line)
+    });
+    ndjson_response(lines)
+}
+
+/// Ollama `details` object for a model
+fn model_details() -> serde_json::Value {
+    json!({
+        "parent_model": "",
+        "format": "",
+        "family": "",
+        "families": null,
+        "parameter_size": "",
+        "quantization_level": ""
+    })
+}
+
+/// Ollama `/api/tags` entry for a model
+fn tag_entry(model: &ModelInfo) -> serde_json::Value {
+    let name = ollama_model_name(&model.model_id);
+    json!({
+        "name": name,
+        "model": name,
+        "modified_at": "1970-01-01T00:00:00Z",
+        "size": 0,
+        "digest": "",
+        "details": model_details()
+    })
+}
+
+/// Handle `/api/tags` (models of every registered engine)
+pub(super) async fn handle_tags(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+) -> axum::Json<serde_json::Value> {
+    let mut models = Vec::new();
+    for engine in state.engine_repo.list_registered().await {
+        // Skip engines that fail to list models, as /v1/models does
+        if let Ok(engine_models) = engine.list_models().await {
+            models.extend(
+                engine_models
+                    .iter()
+                    .filter(|m| in_model_scope(api_key_scopes.as_deref(), &m.model_id))
+                    .map(tag_entry),
+            );
+        }
+    }
+    axum::Json(json!({ "models": models }))
+}
+
+/// Handle `/api/show`
+pub(super) async fn handle_show(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    api_key_scopes: Option<axum::Extension<ApiKeyScopes>>,
+    axum::Json(req): axum::Json<OllamaShowRequest>,
+) -> axum::response::Response {
+    let model = match resolve_model(&req.model) {
+        Ok(model) => model,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    // Models outside the key's scopes are as invisible as in /api/tags
+    if !in_model_scope(api_key_scopes.as_deref(), &model.model_id) {
+        let message = format!("model '{}' not found", req.model);
+        return error_response(StatusCode::NOT_FOUND, &message);
+    }
+    let engine = match find_engine(&state, &model.engine_id).await {
+        Ok(engine) => engine,
+        Err(response) => return response,
+    };
+    let info = match engine.list_models().await {
+        Ok(models) => models.into_iter().find(|m| m.model_id == model.model_id),
+        Err(e) => return engine_error_response(e),
+    };
+    let Some(info) = info else {
+        let message = format!("model '{}' not found", req.model);
+        return error_response(StatusCode::NOT_FOUND, &message);
+    };
+
+    axum::Json(show_body(&info, &engine.capabilities())).into_response()
+}
+
+/// `/api/show` body; `model_info` uses the `{architecture}.context_length` key clients look up
+fn show_body(
+    info: &ModelInfo,
+    engine_capabilities: &flm_core::domain::models::EngineCapabilities,
+) -> serde_json::Value {
+    let (tools, vision) = match &info.capabilities {
+        Some(caps) => (caps.tools, caps.vision),
+        None => (engine_capabilities.tools, engine_capabilities.vision_inputs),
+    };
+    let mut capabilities = vec!["completion"];
+    if tools {
+        capabilities.push("tools");
+    }
+    if vision {
+        capabilities.push("vision");
+    }
+    if info.supports_embeddings {
+        capabilities.push("embedding");
+    }
+
+    let mut model_info = json!({ "general.architecture": "flm" });
+    if let Some(context_length) = info.context_length {
+        model_info["flm.context_length"] = json!(context_length);
+    }
+
+    json!({
+        "modelfile": "",
+        "parameters": "",
+        "template": "",
+        "details": model_details(),
+        "model_info": model_info,
+        "capabilities": capabilities,
+        "modified_at": "1970-01-01T00:00:00Z"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flm_core::domain::models::{EngineCapabilities, ModelCapabilities};
+
+    #[test]
+    fn test_resolve_model_accepts_optional_prefix() {
+        let model = resolve_model("vllm/meta-llama/Llama-3-8B").unwrap();
+        assert_eq!(model.engine_id, "vllm");
+        assert_eq!(model.model_id, "flm://vllm/meta-llama/Llama-3-8B");
+        assert_eq!(
+            resolve_model("flm://vllm/meta-llama/Llama-3-8B")
+                .unwrap()
+                .model_id,
+            model.model_id
+        );
+        assert!(resolve_model("llama3:latest").is_err());
+    }
+
+    #[test]
+    fn test_options_or_defaults_keeps_explicit_values() {
+        let options: OllamaOptions = serde_json::from_value(json!({
+            "temperature": 0.2,
+            "num_predict": -1
+        }))
+        .unwrap();
+        let defaults: ModelProfileDefaults = serde_json::from_value(json!({
+            "temperature": 0.9,
+            "max_tokens": 256,
+            "stop": ["###"],
+            "top_p": 0.8
+        }))
+        .unwrap();
+
+        let options = options.or_defaults(&defaults);
+        assert_eq!(options.temperature, Some(0.2));
+        assert_eq!(options.num_predict, Some(-1));
+        assert_eq!(options.stop, vec!["###".to_string()]);
+        assert_eq!(options.top_p, Some(0.8));
+        assert_eq!(options.top_k, None);
+    }
+
+    #[test]
+    fn test_convert_messages_pairs_tool_results_with_calls() {
+        let messages: Vec<OllamaMessage> = serde_json::from_value(json!([
+            { "role": "user", "content": "Weather in Paris and Rome?" },
+            {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [
+                    { "function": { "name": "weather", "arguments": { "city": "Paris" } } },
+                    { "function": { "name": "weather", "arguments": { "city": "Rome" } } }
+                ]
+            },
+            { "role": "tool", "content": "Sunny" },
+            { "role": "tool", "content": "Rainy" }
+        ]))
+        .unwrap();
+
+        let limits = AttachmentLimits::from_capabilities(&EngineCapabilities::default());
+        let converted = convert_messages(messages, &limits).unwrap();
+
+        let call_ids: Vec<&str> = converted[1]
+            .tool_calls
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(call_ids, vec!["call_0", "call_1"]);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&converted[1].tool_calls[1].arguments)
+                .unwrap(),
+            json!({ "city": "Rome" })
+        );
+        assert_eq!(converted[2].tool_call_id.as_deref(), Some("call_0"));
+        assert_eq!(converted[3].tool_call_id.as_deref(), Some("call_1"));
+    }
+
+    #[test]
+    fn test_decode_images_sniffs_mime_type() {
+        let png = general_purpose::STANDARD.encode(b"\x89PNG\r\n\x1a\nrest");
+        let limits = AttachmentLimits::from_capabilities(&EngineCapabilities::default());
+        let attachments = decode_images(vec![png], &limits).unwrap();
+        assert_eq!(attachments[0].mime_type, "image/png");
+
+        let text = general_purpose::STANDARD.encode(b"not an image");
+        assert!(decode_images(vec![text], &limits).is_err());
+    }
+
+    #[test]
+    fn test_tool_call_accumulator_joins_fragments() {
+        let mut accumulator = ToolCallAccumulator::default();
+        accumulator.push(ToolCallDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            name: Some("weather".to_string()),
+            arguments: Some("{\"city\":".to_string()),
+        });
+        accumulator.push(ToolCallDelta {
+            index: 0,
+            id: None,
+            name: None,
+            arguments: Some("\"Paris\"}".to_string()),
+        });
+
+        let calls = accumulator.take();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, "{\"city\":\"Paris\"}");
+        assert_eq!(
+            tool_calls_json(&calls),
+            json!([{ "function": { "name": "weather", "arguments": { "city": "Paris" } } }])
+        );
+    }
+
+    #[test]
+    fn test_show_body_lists_capabilities() {
+        let info = ModelInfo {
+            engine_id: "vllm".to_string(),
+            model_id: "flm://vllm/llava".to_string(),
+            display_name: "llava".to_string(),
+            context_length: Some(4096),
+            supports_streaming: true,
+            supports_embeddings: false,
+            capabilities: Some(ModelCapabilities {
+                reasoning: false,
+                tools: true,
+                vision: true,
+                audio_inputs: false,
+                audio_outputs: false,
+            }),
+        };
+
+        let body = show_body(&info, &EngineCapabilities::default());
+        assert_eq!(
+            body["capabilities"],
+            json!(["completion", "tools", "vision"])
+        );
+        assert_eq!(body["model_info"]["flm.context_length"], 4096);
+        assert_eq!(tag_entry(&info)["name"], "vllm/llava");
+    }
+}
@@ -2,11 +2,18 @@
 //!
 //! This is a simple in-memory implementation for the proxy server.
 //! Engines should be registered by the CLI before starting the proxy.
+//! Engines listed in the `config.db` registry are loaded with
+//! [`register_from_registry`] when the proxy starts.
 
 use async_trait::async_trait;
-use flm_core::ports::{EngineRepository, LlmEngine};
+use flm_core::domain::engine::{EngineCredentials, EngineRegistration};
+use flm_core::domain::models::{EngineId, EngineKind};
+use flm_core::error::EngineError;
+use flm_core::ports::{EngineRegistryRepository, EngineRepository, LlmEngine};
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use tracing::error;
+use std::time::Duration;
+use tracing::{error, warn};
 
 /// Simple in-memory EngineRepository for proxy server
 pub struct InMemoryEngineRepository {
@@ -60,6 +67,147 @@ impl EngineRepository for InMemoryEngineRepository {
     }
 }
 
+/// Build an engine adapter for a registry entry
+///
+/// Applies the entry's timeout, auth header, bearer token and TLS settings to
+/// the HTTP client. The bearer token and the auth header value live in the OS
+/// keyring, so callers resolve them and pass them in.
+pub fn build_engine(
+    registration: &EngineRegistration,
+    credentials: &EngineCredentials,
+) -> Result<Arc<dyn LlmEngine>, EngineError> {
+    let default_timeout = match registration.kind {
+        EngineKind::StableDiffusion => flm_engine_sdwebui::DEFAULT_TIMEOUT_SECS,
+        _ => flm_engine_ollama::DEFAULT_TIMEOUT_SECS,
+    };
+    let timeout = registration.timeout_secs.unwrap_or(default_timeout);
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(timeout));
+    let mut headers = reqwest::header::HeaderMap::new();
+
+    if let Some(header_name) = &registration.auth_header_name {
+        let name =
+            reqwest::header::HeaderName::from_bytes(header_name.as_bytes()).map_err(|e| {
+                EngineError::InvalidResponse {
+                    reason: format!("Invalid auth header name '{header_name}': {e}"),
+                }
+            })?;
+        if let Some(header_value) = &credentials.auth_header_value {
+            let mut value = reqwest::header::HeaderValue::from_str(header_value).map_err(|e| {
+                EngineError::InvalidResponse {
+                    reason: format!("Invalid auth header value: {e}"),
+                }
+            })?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+    }
+    if let Some(token) = &credentials.bearer_token {
+        let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))
+            .map_err(|e| EngineError::InvalidResponse {
+                reason: format!("Invalid bearer token: {e}"),
+            })?;
+        value.set_sensitive(true);
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    if !headers.is_empty() {
+        builder = builder.default_headers(headers);
+    }
+
+    if let Some(path) = &registration.tls.ca_cert_path {
+        let pem = std::fs::read(path).map_err(|e| EngineError::InvalidResponse {
+            reason: format!("Failed to read CA certificate {path}: {e}"),
+        })?;
+        let cert =
+            reqwest::Certificate::from_pem(&pem).map_err(|e| EngineError::InvalidResponse {
+                reason: format!("Invalid CA certificate {path}: {e}"),
+            })?;
+        builder = builder.add_root_certificate(cert);
+    }
+    if registration.tls.insecure_skip_verify {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    let client = builder.build().map_err(|e| EngineError::NetworkError {
+        reason: format!("Failed to create HTTP client: {e}"),
+    })?;
+
+    let id = registration.id.clone();
+    let base_url = registration.base_url.clone();
+    let engine: Arc<dyn LlmEngine> = match registration.kind {
+        EngineKind::Ollama => Arc::new(flm_engine_ollama::OllamaEngine::with_client(
+            id, base_url, client,
+        )),
+        EngineKind::Vllm => Arc::new(flm_engine_vllm::VllmEngine::with_client(
+            id, base_url, client,
+        )),
+        EngineKind::LmStudio => Arc::new(flm_engine_lmstudio::LmStudioEngine::with_client(
+            id, base_url, client,
+        )),
+        EngineKind::LlamaCpp => Arc::new(flm_engine_llamacpp::LlamaCppEngine::with_client(
+            id, base_url, client,
+        )),
+        EngineKind::StableDiffusion => Arc::new(flm_engine_sdwebui::SdWebUiEngine::with_client(
+            id, base_url, client,
+        )),
+        EngineKind::OpenAiCompatible => Arc::new(
+            flm_engine_openai::OpenAiCompatibleEngine::with_client(id, base_url, client)
+                .with_model_capabilities(registration.model_capabilities.clone()),
+        ),
+    };
+    Ok(engine)
+}
+
+/// Register every engine from the registry into `repo`
+///
+/// `credentials` maps engine IDs to the secrets resolved from the keyring.
+/// Entries that fail to build are logged and skipped so one bad entry does not
+/// keep the proxy from starting. Returns the number of engines registered.
+pub async fn register_from_registry(
+    repo: &dyn EngineRepository,
+    registry: &dyn EngineRegistryRepository,
+    credentials: &HashMap<EngineId, EngineCredentials>,
+) -> usize {
+    let registrations = match registry.list().await {
+        Ok(registrations) => registrations,
+        Err(e) => {
+            warn!(error = %e, "Failed to load engine registry");
+            return 0;
+        }
+    };
+
+    let mut registered = 0;
+    for registration in &registrations {
+        let secrets = credentials
+            .get(&registration.id)
+            .cloned()
+            .unwrap_or_default();
+        if registration.bearer_token_in_keyring && secrets.bearer_token.is_none() {
+            warn!(
+                engine_id = %registration.id,
+                "Bearer token not found in keyring; sending requests without it"
+            );
+        }
+        if registration.auth_header_name.is_some() && secrets.auth_header_value.is_none() {
+            warn!(
+                engine_id = %registration.id,
+                "Auth header value not found in keyring; sending requests without it"
+            );
+        }
+        match build_engine(registration, &secrets) {
+            Ok(engine) => {
+                repo.register(engine).await;
+                registered += 1;
+            }
+            Err(e) => warn!(
+                engine_id = %registration.id,
+                error = %e,
+                "Skipping registered engine"
+            ),
+        }
+    }
+    registered
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +336,65 @@ mod tests {
         let engines = repo.list_registered().await;
         assert!(engines.is_empty());
     }
+
+    fn registration(kind: EngineKind) -> EngineRegistration {
+        EngineRegistration {
+            id: "remote-gpu".to_string(),
+            kind,
+            base_url: "https://gpu-01.internal:8000".to_string(),
+            auth_header_name: Some("X-Api-Key".to_string()),
+            timeout_secs: Some(120),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn secrets() -> EngineCredentials {
+        EngineCredentials {
+            bearer_token: None,
+            auth_header_value: Some("secret".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_build_engine_uses_registered_kind() {
+        for kind in [
+            EngineKind::Ollama,
+            EngineKind::Vllm,
+            EngineKind::LmStudio,
+            EngineKind::LlamaCpp,
+            EngineKind::StableDiffusion,
+            EngineKind::OpenAiCompatible,
+        ] {
+            let engine = build_engine(&registration(kind.clone()), &secrets()).unwrap();
+            assert_eq!(engine.id(), "remote-gpu");
+            assert_eq!(engine.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_build_engine_rejects_bad_settings() {
+        let mut bad_header = registration(EngineKind::Vllm);
+        bad_header.auth_header_name = Some("Bad Header".to_string());
+        assert!(build_engine(&bad_header, &secrets()).is_err());
+
+        let mut missing_ca = registration(EngineKind::Vllm);
+        missing_ca.tls.ca_cert_path = Some("/nonexistent/ca.pem".to_string());
+        assert!(build_engine(&missing_ca, &secrets()).is_err());
+
+        let registration = registration(EngineKind::OpenAiCompatible);
+        let bad_token = EngineCredentials {
+            bearer_token: Some("line\nbreak".to_string()),
+            ..secrets()
+        };
+        assert!(build_engine(&registration, &bad_token).is_err());
+        let bad_value = EngineCredentials {
+            auth_header_value: Some("line\nbreak".to_string()),
+            ..secrets()
+        };
+        assert!(build_engine(&registration, &bad_value).is_err());
+    }
 }
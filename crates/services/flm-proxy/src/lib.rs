@@ -3,7 +3,11 @@
 //! This crate provides the Axum-based HTTP proxy server implementation.
 
 pub mod adapters;
+pub mod audit_sink;
+pub mod backup;
+pub mod balancer;
 pub mod certificate;
+pub mod concurrency;
 pub mod controller;
 pub mod dns;
 pub mod engine_repo;
@@ -11,7 +15,9 @@ pub mod http_client;
 pub mod metrics;
 pub mod middleware;
 pub mod process_controller;
+pub mod retention;
 pub mod security;
+pub mod token_quota;
 pub mod utils;
 
 pub use controller::AxumProxyController;
@@ -4,7 +4,13 @@
 //! See `docs/PROXY_SPEC.md` for the complete specification.
 
 mod adapters;
+mod audit_sink;
+// The binary only takes periodic backups; create/verify/restore are used by the CLI
+#[allow(dead_code)]
+mod backup;
+mod balancer;
 mod certificate;
+mod concurrency;
 mod controller;
 mod daemon;
 mod engine_repo;
@@ -12,7 +18,9 @@ mod http_client;
 mod metrics;
 mod middleware;
 mod process_controller;
+mod retention;
 mod security;
+mod token_quota;
 mod utils;
 
 pub use controller::AxumProxyController;
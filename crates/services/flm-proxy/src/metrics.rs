@@ -7,6 +7,7 @@ use axum::routing::get;
 use axum::Router;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Metrics collector for flm-proxy
 pub struct Metrics {
@@ -30,6 +31,16 @@ pub struct Metrics {
     pub intrusions_detected: AtomicU64,
     /// Total number of anomalies detected
     pub anomalies_detected: AtomicU64,
+    /// Current number of requests waiting for a concurrency slot
+    pub queue_depth: AtomicU64,
+    /// Number of queued requests that were admitted
+    pub queue_admitted: AtomicU64,
+    /// Total time admitted requests spent queued (microseconds)
+    pub queue_wait_micros: AtomicU64,
+    /// Total number of requests rejected because a queue was full
+    pub queue_rejected: AtomicU64,
+    /// Total number of requests that timed out while queued
+    pub queue_timeouts: AtomicU64,
 }
 
 impl Metrics {
@@ -46,6 +57,11 @@ impl Metrics {
             auth_failure: AtomicU64::new(0),
             intrusions_detected: AtomicU64::new(0),
             anomalies_detected: AtomicU64::new(0),
+            queue_depth: AtomicU64::new(0),
+            queue_admitted: AtomicU64::new(0),
+            queue_wait_micros: AtomicU64::new(0),
+            queue_rejected: AtomicU64::new(0),
+            queue_timeouts: AtomicU64::new(0),
         }
     }
 
@@ -99,6 +115,39 @@ impl Metrics {
         self.anomalies_detected.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Count a request entering a concurrency queue
+    pub fn increment_queue_depth(&self) {
+        self.queue_depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a request leaving a concurrency queue (admitted, timed out or cancelled)
+    pub fn decrement_queue_depth(&self) {
+        let _ = self
+            .queue_depth
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| {
+                depth.checked_sub(1)
+            });
+    }
+
+    /// Record how long an admitted request waited in a queue
+    pub fn record_queue_wait(&self, wait: Duration) {
+        self.queue_admitted.fetch_add(1, Ordering::Relaxed);
+        self.queue_wait_micros.fetch_add(
+            u64::try_from(wait.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Increment queue overflow counter
+    pub fn increment_queue_rejected(&self) {
+        self.queue_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Increment queue timeout counter
+    pub fn increment_queue_timeouts(&self) {
+        self.queue_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Export metrics in Prometheus format
     pub fn export_prometheus(&self) -> String {
         let mut output = String::new();
@@ -183,6 +232,46 @@ impl Metrics {
             self.anomalies_detected.load(Ordering::Relaxed)
         ));
 
+        output.push_str(
+            "# HELP flm_proxy_queue_depth Current number of requests waiting for a concurrency slot\n",
+        );
+        output.push_str("# TYPE flm_proxy_queue_depth gauge\n");
+        output.push_str(&format!(
+            "flm_proxy_queue_depth {}\n",
+            self.queue_depth.load(Ordering::Relaxed)
+        ));
+
+        output.push_str(
+            "# HELP flm_proxy_queue_wait_seconds Time admitted requests spent waiting in a queue\n",
+        );
+        output.push_str("# TYPE flm_proxy_queue_wait_seconds summary\n");
+        output.push_str(&format!(
+            "flm_proxy_queue_wait_seconds_sum {}\n",
+            self.queue_wait_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        ));
+        output.push_str(&format!(
+            "flm_proxy_queue_wait_seconds_count {}\n",
+            self.queue_admitted.load(Ordering::Relaxed)
+        ));
+
+        output.push_str(
+            "# HELP flm_proxy_queue_rejected Total number of requests rejected because a queue was full\n",
+        );
+        output.push_str("# TYPE flm_proxy_queue_rejected counter\n");
+        output.push_str(&format!(
+            "flm_proxy_queue_rejected {}\n",
+            self.queue_rejected.load(Ordering::Relaxed)
+        ));
+
+        output.push_str(
+            "# HELP flm_proxy_queue_timeouts Total number of requests that timed out while queued\n",
+        );
+        output.push_str("# TYPE flm_proxy_queue_timeouts counter\n");
+        output.push_str(&format!(
+            "flm_proxy_queue_timeouts {}\n",
+            self.queue_timeouts.load(Ordering::Relaxed)
+        ));
+
         output
     }
 }
@@ -193,6 +282,36 @@ impl Default for Metrics {
     }
 }
 
+/// Create metrics endpoint router
+#[allow(dead_code)]
+pub fn create_metrics_router(metrics: Arc<Metrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics)
+}
+
+/// Metrics endpoint handler
+pub async fn metrics_handler(
+    axum::extract::State(metrics): axum::extract::State<Arc<Metrics>>,
+) -> Response {
+    let body = metrics.export_prometheus();
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(body.into())
+        .unwrap_or_else(|e| {
+            tracing::error!("Failed to build metrics response: {}", e);
+            // Fallback to a simple error response
+            Response::builder()
+                .status(500)
+                .body(axum::body::Body::from("Internal server error"))
+                .unwrap_or_else(|_| {
+                    // Last resort: return an empty 500 response
+                    Response::new(axum::body::Body::empty())
+                })
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,6 +405,29 @@ mod tests {
         assert_eq!(metrics.anomalies_detected.load(Ordering::Relaxed), 1);
     }
 
+    #[test]
+    fn test_metrics_queue_counters() {
+        let metrics = Metrics::new();
+        metrics.increment_queue_depth();
+        metrics.increment_queue_depth();
+        metrics.decrement_queue_depth();
+        metrics.record_queue_wait(Duration::from_millis(1500));
+        metrics.increment_queue_rejected();
+        metrics.increment_queue_timeouts();
+        assert_eq!(metrics.queue_depth.load(Ordering::Relaxed), 1);
+
+        metrics.decrement_queue_depth();
+        metrics.decrement_queue_depth();
+        assert_eq!(metrics.queue_depth.load(Ordering::Relaxed), 0);
+
+        let output = metrics.export_prometheus();
+        assert!(output.contains("flm_proxy_queue_depth 0"));
+        assert!(output.contains("flm_proxy_queue_wait_seconds_sum 1.5"));
+        assert!(output.contains("flm_proxy_queue_wait_seconds_count 1"));
+        assert!(output.contains("flm_proxy_queue_rejected 1"));
+        assert!(output.contains("flm_proxy_queue_timeouts 1"));
+    }
+
     #[test]
     fn test_metrics_export_prometheus() {
         let metrics = Metrics::new();
@@ -308,33 +450,3 @@ mod tests {
         assert_eq!(metrics.requests_total.load(Ordering::Relaxed), 0);
     }
 }
-
-/// Create metrics endpoint router
-#[allow(dead_code)]
-pub fn create_metrics_router(metrics: Arc<Metrics>) -> Router {
-    Router::new()
-        .route("/metrics", get(metrics_handler))
-        .with_state(metrics)
-}
-
-/// Metrics endpoint handler
-pub async fn metrics_handler(
-    axum::extract::State(metrics): axum::extract::State<Arc<Metrics>>,
-) -> Response {
-    let body = metrics.export_prometheus();
-    Response::builder()
-        .status(200)
-        .header("Content-Type", "text/plain; version=0.0.4")
-        .body(body.into())
-        .unwrap_or_else(|e| {
-            tracing::error!("Failed to build metrics response: {}", e);
-            // Fallback to a simple error response
-            Response::builder()
-                .status(500)
-                .body(axum::body::Body::from("Internal server error"))
-                .unwrap_or_else(|_| {
-                    // Last resort: return an empty 500 response
-                    Response::new(axum::body::Body::empty())
-                })
-        })
-}
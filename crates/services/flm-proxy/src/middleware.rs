@@ -9,7 +9,9 @@ use axum::http::{HeaderMap, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use flm_core::domain::policy::PolicyDocument;
 use flm_core::domain::proxy::ProxyEgressConfig;
+use flm_core::domain::security::{ApiKeyRateLimit, ApiKeyScopes, EndpointScope};
 use flm_core::services::SecurityService;
 use ipnet::IpNet;
 use std::fs::OpenOptions;
@@ -48,6 +50,16 @@ impl RateLimitStateEntry {
     }
 }
 
+/// Label of the authenticated API key, stored in request extensions by `auth_middleware`
+#[derive(Clone, Debug)]
+pub struct ApiKeyLabel(pub String);
+
+/// Structured details a handler attaches to its response for the audit log
+///
+/// `audit_logging_middleware` stores the JSON in `audit_logs.details`.
+#[derive(Clone, Debug)]
+pub struct AuditDetails(pub serde_json::Value);
+
 /// Application state for the proxy server
 #[derive(Clone)]
 pub struct AppState {
@@ -55,6 +67,18 @@ pub struct AppState {
     pub security_repo: Arc<crate::adapters::SqliteSecurityRepository>,
     pub engine_service: Arc<flm_core::services::EngineService>,
     pub engine_repo: Arc<dyn flm_core::ports::EngineRepository + Send + Sync>,
+    /// Model profiles from config.db (None when no config.db is configured)
+    pub model_profiles: Option<Arc<dyn flm_core::ports::ModelProfileRepository>>,
+    /// Managed system prompt templates from config.db (None when no config.db is configured)
+    pub api_prompts: Option<Arc<dyn flm_core::ports::ApiPromptRepository>>,
+    /// Model groups from config.db (None when no config.db is configured)
+    pub model_groups: Option<Arc<dyn flm_core::ports::ModelGroupRepository>>,
+    /// Backend selection state for model groups
+    pub balancer: Arc<crate::balancer::Balancer>,
+    /// Per-engine / per-model concurrency caps and request queues
+    pub concurrency: Arc<crate::concurrency::ConcurrencyLimiter>,
+    /// Per-API-key token consumption (TPM and daily/monthly quotas)
+    pub token_quotas: Arc<crate::token_quota::TokenQuotas>,
     /// Rate limit state: API key ID -> token bucket + RPM counters
     pub rate_limit_state: Arc<RwLock<std::collections::HashMap<String, RateLimitStateEntry>>>,
     /// IP-based rate limit state: IP address -> (request count, reset time)
@@ -109,9 +133,9 @@ pub async fn policy_check_middleware(
     // Get security policy
     match state.security_service.get_policy("default").await {
         Ok(Some(policy)) => {
-            // Validate policy JSON format (but allow empty policy)
-            if serde_json::from_str::<serde_json::Value>(&policy.policy_json).is_err() {
-                // Invalid JSON - treat as not configured
+            // Validate policy JSON format and fields (but allow empty policy)
+            if let Err(reason) = PolicyDocument::parse_lenient(&policy.policy_json) {
+                // Invalid policy - treat as not configured
                 debug!(
                     middleware = "policy_check_middleware",
                     path = %path,
@@ -120,6 +144,7 @@ pub async fn policy_check_middleware(
                 warn!(
                     error_type = "invalid_policy_json",
                     path = %request.uri().path(),
+                    reason = %reason,
                     "Invalid security policy JSON. Denying access for security."
                 );
                 return create_forbidden_response("Invalid security policy. Access denied.")
@@ -182,12 +207,13 @@ pub async fn policy_check_middleware(
 /// 1. IP whitelist check
 /// 2. CORS headers
 /// 3. Rate limiting
+/// 4. Token rate limit and quotas (`rate_limit.tpm` / `daily_tokens` / `monthly_tokens`)
 ///
 /// Note: This should run after authentication middleware to have access to the API key.
 pub async fn policy_middleware(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: HeaderMap,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
     // Get client IP from request
@@ -228,13 +254,15 @@ pub async fn policy_middleware(
     };
 
     // Parse policy JSON
-    let policy_json: serde_json::Value = match serde_json::from_str(&policy.policy_json) {
-        Ok(json) => json,
-        Err(_) => {
+    // Unknown fields were reported at startup; they are ignored here
+    let mut policy_doc = match PolicyDocument::parse_lenient(&policy.policy_json) {
+        Ok((policy_doc, _)) => policy_doc,
+        Err(reason) => {
             // Invalid policy JSON - fail closed for security
-            // Don't expose parsing error details
+            // Don't expose parsing error details to the client
             error!(
                 error_type = "invalid_policy_json",
+                reason = %reason,
                 "Invalid security policy JSON. Denying access for security."
             );
             return create_forbidden_response("Invalid security policy. Access denied.")
@@ -242,48 +270,46 @@ pub async fn policy_middleware(
         }
     };
 
+    // Per-key rate limits (set by auth_middleware) replace the policy's values
+    if let Some(scopes) = request.extensions().get::<ApiKeyScopes>() {
+        apply_api_key_rate_limit(&mut policy_doc, &scopes.rate_limit);
+    }
+
     // 1. Check IP whitelist
-    if let Some(ip_whitelist) = policy_json.get("ip_whitelist") {
-        if let Some(ip_list) = ip_whitelist.as_array() {
-            if !ip_list.is_empty() {
-                debug!(
-                    middleware = "policy_middleware",
-                    path = %path,
-                    client_ip = %client_ip,
-                    whitelist_size = ip_list.len(),
-                    "policy_middleware: Checking IP whitelist"
-                );
-                let allowed = ip_list.iter().any(|ip_entry| {
-                    if let Some(ip_str) = ip_entry.as_str() {
-                        check_ip_allowed(&client_ip, ip_str)
-                    } else {
-                        false
-                    }
-                });
-
-                if !allowed {
-                    debug!(
-                        middleware = "policy_middleware",
-                        path = %path,
-                        client_ip = %client_ip,
-                        "policy_middleware: IP address not in whitelist, denying"
-                    );
-                    return create_forbidden_response("IP address not in whitelist")
-                        .into_response();
-                }
+    if let Some(ip_list) = &policy_doc.ip_whitelist {
+        if !ip_list.is_empty() {
+            debug!(
+                middleware = "policy_middleware",
+                path = %path,
+                client_ip = %client_ip,
+                whitelist_size = ip_list.len(),
+                "policy_middleware: Checking IP whitelist"
+            );
+            let allowed = ip_list
+                .iter()
+                .any(|ip_str| check_ip_allowed(&client_ip, ip_str));
+
+            if !allowed {
                 debug!(
                     middleware = "policy_middleware",
                     path = %path,
                     client_ip = %client_ip,
-                    "policy_middleware: IP address in whitelist, allowing"
-                );
-            } else {
-                debug!(
-                    middleware = "policy_middleware",
-                    path = %path,
-                    "policy_middleware: IP whitelist is empty, skipping check"
+                    "policy_middleware: IP address not in whitelist, denying"
                 );
+                return create_forbidden_response("IP address not in whitelist").into_response();
             }
+            debug!(
+                middleware = "policy_middleware",
+                path = %path,
+                client_ip = %client_ip,
+                "policy_middleware: IP address in whitelist, allowing"
+            );
+        } else {
+            debug!(
+                middleware = "policy_middleware",
+                path = %path,
+                "policy_middleware: IP whitelist is empty, skipping check"
+            );
         }
     } else {
         debug!(
@@ -294,37 +320,13 @@ pub async fn policy_middleware(
     }
 
     // 2. Extract CORS headers from policy
-    let cors_headers = extract_cors_headers(&policy_json);
+    let cors_headers = extract_cors_headers(&policy_doc);
 
     // 3. Check IP-based rate limit (configurable from policy, default 1000 req/min)
     // This is in addition to API key-based rate limiting
     {
         // Get IP rate limit settings from policy, or use defaults
-        let (ip_rate_limit_rpm, ip_rate_limit_burst) =
-            if let Some(ip_rate_limit) = policy_json.get("ip_rate_limit") {
-                let rpm = ip_rate_limit
-                    .get("rpm")
-                    .and_then(|v| v.as_u64())
-                    .and_then(|v| u32::try_from(v).ok())
-                    .unwrap_or_else(|| {
-                        warn!("RPM value exceeds u32::MAX, clamping to u32::MAX");
-                        u32::MAX
-                    })
-                    .min(1000);
-                let burst = ip_rate_limit
-                    .get("burst")
-                    .and_then(|v| v.as_u64())
-                    .and_then(|v| u32::try_from(v).ok())
-                    .unwrap_or_else(|| {
-                        warn!("Burst value exceeds u32::MAX, clamping to u32::MAX");
-                        u32::MAX
-                    })
-                    .min(rpm);
-                (rpm, burst)
-            } else {
-                // Default: 1000 requests per minute
-                (1000u32, 1000u32)
-            };
+        let (ip_rate_limit_rpm, ip_rate_limit_burst) = policy_doc.ip_request_limit();
 
         // Apply dynamic adjustment based on IP's past behavior
         let (adjusted_rpm, adjusted_burst) = adjust_ip_rate_limit_dynamically(
@@ -388,67 +390,49 @@ pub async fn policy_middleware(
     // Note: auth_middleware runs before policy_middleware, so the API key ID
     // should be available in request extensions if authentication succeeded.
     let rate_limit_info = if let Some(api_key_id) = request.extensions().get::<String>() {
-        if let Some(rate_limit) = policy_json.get("rate_limit") {
-            if let Some(rpm) = rate_limit.get("rpm").and_then(|v| v.as_u64()) {
-                if rpm > 0 {
-                    let burst = rate_limit
-                        .get("burst")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(rpm);
-
-                    // Debug: Log rate limit check
-                    let log_path = std::env::temp_dir().join("rate_limit_debug.log");
-                    if let Ok(mut file) =
-                        OpenOptions::new().create(true).append(true).open(&log_path)
-                    {
-                        if let Err(e) = file.write_all(
-                            format!(
-                                "[POLICY_MIDDLEWARE] Checking rate limit for api_key_id={}, rpm={}, burst={}\n",
-                                utils::mask_identifier(api_key_id),
-                                rpm,
-                                burst
-                            )
-                            .as_bytes(),
-                        ) {
-                            debug!("Failed to write debug log: {}", e);
-                        }
-                        if let Err(e) = file.flush() {
-                            debug!("Failed to flush debug log: {}", e);
-                        }
-                    }
-
-                    let (allowed, remaining, reset_time) =
-                        check_rate_limit_with_info(&state, api_key_id, rpm as u32, burst as u32)
-                            .await;
-
-                    // Debug: Log rate limit result
-                    if let Ok(mut file) =
-                        OpenOptions::new().create(true).append(true).open(&log_path)
-                    {
-                        if let Err(e) = file.write_all(
-                            format!(
-                                "[POLICY_MIDDLEWARE] Rate limit result: allowed={allowed}, remaining={remaining}\n"
-                            )
-                            .as_bytes(),
-                        ) {
-                            debug!("Failed to write debug log: {}", e);
-                        }
-                        if let Err(e) = file.flush() {
-                            debug!("Failed to flush debug log: {}", e);
-                        }
-                    }
+        if let Some((rpm, burst)) = policy_doc.rate_limit.and_then(|r| r.request_limit()) {
+            // Debug: Log rate limit check
+            let log_path = std::env::temp_dir().join("rate_limit_debug.log");
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&log_path) {
+                if let Err(e) = file.write_all(
+                    format!(
+                        "[POLICY_MIDDLEWARE] Checking rate limit for api_key_id={}, rpm={}, burst={}\n",
+                        utils::mask_identifier(api_key_id),
+                        rpm,
+                        burst
+                    )
+                    .as_bytes(),
+                ) {
+                    debug!("Failed to write debug log: {}", e);
+                }
+                if let Err(e) = file.flush() {
+                    debug!("Failed to flush debug log: {}", e);
+                }
+            }
 
-                    if !allowed {
-                        return create_rate_limit_response().into_response();
-                    }
+            let (allowed, remaining, reset_time) =
+                check_rate_limit_with_info(&state, api_key_id, rpm as u32, burst as u32).await;
 
-                    Some((remaining, reset_time))
-                } else {
-                    None
+            // Debug: Log rate limit result
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&log_path) {
+                if let Err(e) = file.write_all(
+                    format!(
+                        "[POLICY_MIDDLEWARE] Rate limit result: allowed={allowed}, remaining={remaining}\n"
+                    )
+                    .as_bytes(),
+                ) {
+                    debug!("Failed to write debug log: {}", e);
                 }
-            } else {
-                None
+                if let Err(e) = file.flush() {
+                    debug!("Failed to flush debug log: {}", e);
+                }
+            }
+
+            if !allowed {
+                return create_rate_limit_response().into_response();
             }
+
+            Some((burst, remaining, reset_time))
         } else {
             None
         }
@@ -471,6 +455,29 @@ pub async fn policy_middleware(
         None
     };
 
+    // 5. Check token limits and hand the key's token budget to the handlers,
+    // which reserve estimated prompt tokens and record the reported usage
+    if let Some(api_key_id) = request.extensions().get::<String>().cloned() {
+        let limits = crate::token_quota::TokenLimits::from_policy(&policy_doc);
+        if let Err(rejection) = state.token_quotas.check(&api_key_id, &limits).await {
+            debug!(
+                middleware = "policy_middleware",
+                path = %path,
+                limit = rejection.kind.as_str(),
+                "policy_middleware: Token limit exceeded, denying"
+            );
+            return rejection.to_response();
+        }
+        request
+            .extensions_mut()
+            .insert(crate::token_quota::TokenBudget::new(
+                state.token_quotas.clone(),
+                api_key_id,
+                path.clone(),
+                limits,
+            ));
+    }
+
     // All checks passed, continue with request
     let mut response = next.run(request).await;
 
@@ -481,55 +488,42 @@ pub async fn policy_middleware(
     }
 
     // Add rate limit headers if rate limiting is active
-    if let Some((remaining, reset_time)) = rate_limit_info {
-        if let Some(rate_limit) = policy_json.get("rate_limit") {
-            if let Some(rpm) = rate_limit.get("rpm").and_then(|v| v.as_u64()) {
-                let burst = rate_limit
-                    .get("burst")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(rpm);
-
-                // X-RateLimit-Limit
-                if let Ok(limit_value) = axum::http::HeaderValue::from_str(&burst.to_string()) {
-                    headers.insert(
-                        axum::http::HeaderName::from_static("x-ratelimit-limit"),
-                        limit_value,
-                    );
-                }
+    if let Some((burst, remaining, reset_time)) = rate_limit_info {
+        // X-RateLimit-Limit
+        if let Ok(limit_value) = axum::http::HeaderValue::from_str(&burst.to_string()) {
+            headers.insert(
+                axum::http::HeaderName::from_static("x-ratelimit-limit"),
+                limit_value,
+            );
+        }
 
-                // X-RateLimit-Remaining
-                if let Ok(remaining_value) =
-                    axum::http::HeaderValue::from_str(&remaining.to_string())
-                {
-                    headers.insert(
-                        axum::http::HeaderName::from_static("x-ratelimit-remaining"),
-                        remaining_value,
-                    );
-                }
+        // X-RateLimit-Remaining
+        if let Ok(remaining_value) = axum::http::HeaderValue::from_str(&remaining.to_string()) {
+            headers.insert(
+                axum::http::HeaderName::from_static("x-ratelimit-remaining"),
+                remaining_value,
+            );
+        }
 
-                // X-RateLimit-Reset (Unix timestamp)
-                let reset_timestamp = match reset_time.duration_since(std::time::UNIX_EPOCH) {
-                    Ok(duration) => duration.as_secs(),
-                    Err(_) => {
-                        warn!(
-                            error_type = "reset_timestamp_calc_failed",
-                            "Failed to calculate reset timestamp. Using current time."
-                        );
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs()
-                    }
-                };
-                if let Ok(reset_value) =
-                    axum::http::HeaderValue::from_str(&reset_timestamp.to_string())
-                {
-                    headers.insert(
-                        axum::http::HeaderName::from_static("x-ratelimit-reset"),
-                        reset_value,
-                    );
-                }
+        // X-RateLimit-Reset (Unix timestamp)
+        let reset_timestamp = match reset_time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => {
+                warn!(
+                    error_type = "reset_timestamp_calc_failed",
+                    "Failed to calculate reset timestamp. Using current time."
+                );
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
             }
+        };
+        if let Ok(reset_value) = axum::http::HeaderValue::from_str(&reset_timestamp.to_string()) {
+            headers.insert(
+                axum::http::HeaderName::from_static("x-ratelimit-reset"),
+                reset_value,
+            );
         }
     }
 
@@ -951,6 +945,7 @@ pub async fn ip_block_check_middleware(
 /// Authentication middleware
 ///
 /// This middleware extracts the Bearer token from the Authorization header
+/// (or the `x-api-key` header used by Anthropic-style clients)
 /// and verifies it using SecurityService.
 /// Note: `/health` endpoint is exempt from authentication.
 pub async fn auth_middleware(
@@ -972,8 +967,16 @@ pub async fn auth_middleware(
         return create_not_found_response().into_response();
     }
 
-    // Extract Authorization header
-    let auth_header = match headers.get("authorization").and_then(|h| h.to_str().ok()) {
+    // Extract Authorization header, falling back to `x-api-key` (Anthropic Messages API clients)
+    let x_api_key = headers
+        .get("x-api-key")
+        .and_then(|h| h.to_str().ok())
+        .map(|key| format!("Bearer {key}"));
+    let auth_header = match headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .or(x_api_key.as_deref())
+    {
         Some(h) => h,
         None => {
             // No authorization header - record failure
@@ -1082,9 +1085,25 @@ pub async fn auth_middleware(
     // Verify the API key
     match state.security_service.verify_api_key(token).await {
         Ok(Some(record)) => {
+            // Enforce the key's IP allowlist and endpoint scopes
+            if let Some(message) = api_key_scope_violation(&record.scopes, path, &client_ip) {
+                debug!(
+                    middleware = "auth_middleware",
+                    path = %path,
+                    api_key_id = %utils::mask_identifier(&record.id),
+                    "auth_middleware: {message}, denying"
+                );
+                return api_key_scope_denied_response(message).into_response();
+            }
+
             // API key is valid, continue to next middleware/handler
             // Store API key ID in request extensions for rate limiting
             request.extensions_mut().insert(record.id.clone());
+            request
+                .extensions_mut()
+                .insert(ApiKeyLabel(record.label.clone()));
+            // Model scopes and per-key rate limits are applied further down
+            request.extensions_mut().insert(record.scopes);
             next.run(request).await
         }
         Ok(None) => {
@@ -1170,6 +1189,67 @@ pub async fn auth_middleware(
     }
 }
 
+/// Overlay a key's own rate limits on the policy's `rate_limit` section
+fn apply_api_key_rate_limit(policy: &mut PolicyDocument, limits: &ApiKeyRateLimit) {
+    if limits.is_empty() {
+        return;
+    }
+    let rate_limit = policy.rate_limit.unwrap_or_default();
+    policy.rate_limit = Some(rate_limit.with_key_overrides(limits));
+}
+
+/// Endpoint class of a request path, for API key endpoint scopes
+pub(crate) fn endpoint_scope(path: &str) -> Option<EndpointScope> {
+    match path {
+        "/v1/chat/completions" | "/v1/completions" | "/v1/messages" | "/api/chat"
+        | "/api/generate" => Some(EndpointScope::Chat),
+        "/v1/embeddings" => Some(EndpointScope::Embeddings),
+        "/v1/models" | "/api/tags" | "/api/show" => Some(EndpointScope::Models),
+        "/metrics" => Some(EndpointScope::Metrics),
+        _ if path.starts_with("/v1/audio/") => Some(EndpointScope::Audio),
+        _ if path.starts_with("/v1/images/") => Some(EndpointScope::Images),
+        _ => None,
+    }
+}
+
+/// Why an authenticated key may not make this request, if it may not
+///
+/// Checks the key's IP allowlist and endpoint scopes; model scopes need the
+/// request body and are checked by the handlers.
+fn api_key_scope_violation(
+    scopes: &ApiKeyScopes,
+    path: &str,
+    client_ip: &IpAddr,
+) -> Option<&'static str> {
+    if !scopes.ip_allowlist.is_empty()
+        && !scopes
+            .ip_allowlist
+            .iter()
+            .any(|entry| check_ip_allowed(client_ip, entry))
+    {
+        return Some("API key is not allowed from this IP address");
+    }
+    match endpoint_scope(path) {
+        Some(scope) if !scopes.allows_endpoint(scope) => {
+            Some("API key is not allowed to use this endpoint")
+        }
+        _ => None,
+    }
+}
+
+fn api_key_scope_denied_response(message: &str) -> (StatusCode, axum::Json<serde_json::Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        axum::Json(serde_json::json!({
+            "error": {
+                "message": message,
+                "type": "access_denied",
+                "code": "api_key_scope_denied"
+            }
+        })),
+    )
+}
+
 /// Extract client IP from request
 ///
 /// Security: Only trusts X-Forwarded-For and X-Real-IP headers if the request
@@ -1267,50 +1347,6 @@ pub(crate) fn check_ip_allowed(client_ip: &IpAddr, whitelist_entry: &str) -> boo
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-
-    #[test]
-    fn test_check_ip_allowed_exact_match_ipv4() {
-        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
-        assert!(check_ip_allowed(&ip, "192.168.1.100"));
-        assert!(!check_ip_allowed(&ip, "192.168.1.101"));
-    }
-
-    #[test]
-    fn test_check_ip_allowed_cidr_ipv4() {
-        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
-        assert!(check_ip_allowed(&ip, "192.168.1.0/24"));
-        assert!(check_ip_allowed(&ip, "192.168.0.0/16"));
-        assert!(!check_ip_allowed(&ip, "10.0.0.0/8"));
-    }
-
-    #[test]
-    fn test_check_ip_allowed_exact_match_ipv6() {
-        let ip = IpAddr::V6(Ipv6Addr::new(
-            0x2001, 0x0db8, 0x85a3, 0x0000, 0x0000, 0x8a2e, 0x0370, 0x7334,
-        ));
-        assert!(check_ip_allowed(&ip, "2001:db8:85a3::8a2e:370:7334"));
-    }
-
-    #[test]
-    fn test_check_ip_allowed_cidr_ipv6() {
-        let ip = IpAddr::V6(Ipv6Addr::new(
-            0x2001, 0x0db8, 0x85a3, 0x0000, 0x0000, 0x8a2e, 0x0370, 0x7334,
-        ));
-        assert!(check_ip_allowed(&ip, "2001:db8:85a3::/64"));
-    }
-
-    #[test]
-    fn test_check_ip_allowed_invalid_entry() {
-        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
-        assert!(!check_ip_allowed(&ip, "invalid"));
-        assert!(!check_ip_allowed(&ip, "192.168.1.0/999"));
-    }
-}
-
 /// Check rate limit for an API key
 /// Returns (allowed, remaining, reset_time)
 /// Uses hybrid approach: memory cache + database persistence
@@ -1576,7 +1612,7 @@ async fn adjust_ip_rate_limit_dynamically(
                 result as u32
             } else {
                 warn!("Rate limit calculation overflow: base_rpm={}, result={}, clamping", base_rpm, result);
-                u32::MAX.min(base_rpm / 2)
+                base_rpm / 2
             }
         };
         let burst_50 = {
@@ -1585,7 +1621,7 @@ async fn adjust_ip_rate_limit_dynamically(
                 result as u32
             } else {
                 warn!("Rate limit calculation overflow: base_burst={}, result={}, clamping", base_burst, result);
-                u32::MAX.min(base_burst / 2)
+                base_burst / 2
             }
         };
         return (rpm_50, burst_50);
@@ -1602,7 +1638,7 @@ async fn adjust_ip_rate_limit_dynamically(
                 result as u32
             } else {
                 warn!("Rate limit calculation overflow: base_rpm={}, result={}, clamping", base_rpm, result);
-                u32::MAX.min((base_rpm as f64 * 0.75) as u32)
+                (base_rpm as f64 * 0.75) as u32
             }
         };
         let burst_75 = {
@@ -1611,7 +1647,7 @@ async fn adjust_ip_rate_limit_dynamically(
                 result as u32
             } else {
                 warn!("Rate limit calculation overflow: base_burst={}, result={}, clamping", base_burst, result);
-                u32::MAX.min((base_burst as f64 * 0.75) as u32)
+                (base_burst as f64 * 0.75) as u32
             }
         };
         return (rpm_75, burst_75);
@@ -1727,25 +1763,16 @@ fn create_forbidden_response(message: &str) -> (StatusCode, axum::Json<serde_jso
 
 /// Extract CORS headers from policy JSON
 fn extract_cors_headers(
-    policy_json: &serde_json::Value,
+    policy: &PolicyDocument,
 ) -> Vec<(axum::http::HeaderName, axum::http::HeaderValue)> {
     let mut headers = Vec::new();
 
-    if let Some(cors) = policy_json.get("cors") {
+    if let Some(cors) = &policy.cors {
         // Access-Control-Allow-Origin
-        if let Some(origin) = cors.get("allowed_origins") {
-            if let Some(origins) = origin.as_array() {
-                if !origins.is_empty() {
-                    // For simplicity, use the first origin or "*" if empty
-                    let origin_value = origins.first().and_then(|o| o.as_str()).unwrap_or("*");
-                    if let Ok(value) = axum::http::HeaderValue::from_str(origin_value) {
-                        let name =
-                            axum::http::HeaderName::from_static("access-control-allow-origin");
-                        headers.push((name, value));
-                    }
-                }
-            } else if let Some(origin_str) = origin.as_str() {
-                if let Ok(value) = axum::http::HeaderValue::from_str(origin_str) {
+        if let Some(origins) = &cors.allowed_origins {
+            if let Some(origin_value) = origins.first() {
+                // For simplicity, use the first origin
+                if let Ok(value) = axum::http::HeaderValue::from_str(origin_value) {
                     let name = axum::http::HeaderName::from_static("access-control-allow-origin");
                     headers.push((name, value));
                 }
@@ -1753,37 +1780,27 @@ fn extract_cors_headers(
         }
 
         // Access-Control-Allow-Methods
-        if let Some(methods) = cors.get("allowed_methods") {
-            if let Some(methods_array) = methods.as_array() {
-                let methods_str: Vec<String> = methods_array
+        if let Some(methods) = &cors.allowed_methods {
+            if !methods.is_empty() {
+                let methods_value = methods
                     .iter()
-                    .filter_map(|m| m.as_str().map(|s| s.to_uppercase()))
-                    .collect();
-                if !methods_str.is_empty() {
-                    let methods_value = methods_str.join(", ");
-                    if let Ok(value) = axum::http::HeaderValue::from_str(&methods_value) {
-                        let name =
-                            axum::http::HeaderName::from_static("access-control-allow-methods");
-                        headers.push((name, value));
-                    }
+                    .map(|m| m.to_uppercase())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if let Ok(value) = axum::http::HeaderValue::from_str(&methods_value) {
+                    let name = axum::http::HeaderName::from_static("access-control-allow-methods");
+                    headers.push((name, value));
                 }
             }
         }
 
         // Access-Control-Allow-Headers
-        if let Some(headers_list) = cors.get("allowed_headers") {
-            if let Some(headers_array) = headers_list.as_array() {
-                let headers_str: Vec<String> = headers_array
-                    .iter()
-                    .filter_map(|h| h.as_str().map(|s| s.to_string()))
-                    .collect();
-                if !headers_str.is_empty() {
-                    let headers_value = headers_str.join(", ");
-                    if let Ok(value) = axum::http::HeaderValue::from_str(&headers_value) {
-                        let name =
-                            axum::http::HeaderName::from_static("access-control-allow-headers");
-                        headers.push((name, value));
-                    }
+        if let Some(headers_list) = &cors.allowed_headers {
+            if !headers_list.is_empty() {
+                let headers_value = headers_list.join(", ");
+                if let Ok(value) = axum::http::HeaderValue::from_str(&headers_value) {
+                    let name = axum::http::HeaderName::from_static("access-control-allow-headers");
+                    headers.push((name, value));
                 }
             }
         }
@@ -1973,6 +1990,10 @@ pub async fn audit_logging_middleware(
 
     // Get status code
     let status = response.status().as_u16();
+    let details = response
+        .extensions()
+        .get::<AuditDetails>()
+        .map(|d| d.0.to_string());
 
     // Determine event type and severity based on status code
     let (event_type, severity) = if status == 200 || status == 201 {
@@ -1996,7 +2017,7 @@ pub async fn audit_logging_middleware(
         let metadata = AuditLogMetadata {
             severity,
             ip: Some(&client_ip_str_clone),
-            details: None, // details can be added later for specific events
+            details: details.as_deref(),
         };
         if let Err(e) = security_repo
             .save_audit_log(
@@ -2021,3 +2042,101 @@ pub async fn audit_logging_middleware(
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flm_core::domain::policy::RateLimitPolicy;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_check_ip_allowed_exact_match_ipv4() {
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
+        assert!(check_ip_allowed(&ip, "192.168.1.100"));
+        assert!(!check_ip_allowed(&ip, "192.168.1.101"));
+    }
+
+    #[test]
+    fn test_check_ip_allowed_cidr_ipv4() {
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
+        assert!(check_ip_allowed(&ip, "192.168.1.0/24"));
+        assert!(check_ip_allowed(&ip, "192.168.0.0/16"));
+        assert!(!check_ip_allowed(&ip, "10.0.0.0/8"));
+    }
+
+    #[test]
+    fn test_check_ip_allowed_exact_match_ipv6() {
+        let ip = IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0x0db8, 0x85a3, 0x0000, 0x0000, 0x8a2e, 0x0370, 0x7334,
+        ));
+        assert!(check_ip_allowed(&ip, "2001:db8:85a3::8a2e:370:7334"));
+    }
+
+    #[test]
+    fn test_check_ip_allowed_cidr_ipv6() {
+        let ip = IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0x0db8, 0x85a3, 0x0000, 0x0000, 0x8a2e, 0x0370, 0x7334,
+        ));
+        assert!(check_ip_allowed(&ip, "2001:db8:85a3::/64"));
+    }
+
+    #[test]
+    fn test_check_ip_allowed_invalid_entry() {
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100));
+        assert!(!check_ip_allowed(&ip, "invalid"));
+        assert!(!check_ip_allowed(&ip, "192.168.1.0/999"));
+    }
+
+    #[test]
+    fn test_api_key_rate_limit_overrides_policy() {
+        let mut policy =
+            PolicyDocument::parse(r#"{"rate_limit": {"rpm": 60, "burst": 10}}"#).unwrap();
+        let limits = ApiKeyRateLimit {
+            rpm: Some(5),
+            tpm: Some(1000),
+            ..ApiKeyRateLimit::default()
+        };
+        apply_api_key_rate_limit(&mut policy, &limits);
+        assert_eq!(
+            policy.rate_limit,
+            Some(RateLimitPolicy {
+                rpm: Some(5),
+                burst: Some(10),
+                tpm: Some(1000),
+                ..RateLimitPolicy::default()
+            })
+        );
+
+        let mut policy = PolicyDocument::default();
+        apply_api_key_rate_limit(&mut policy, &ApiKeyRateLimit::default());
+        assert_eq!(policy.rate_limit, None);
+        apply_api_key_rate_limit(&mut policy, &limits);
+        assert_eq!(
+            policy.rate_limit,
+            Some(RateLimitPolicy {
+                rpm: Some(5),
+                tpm: Some(1000),
+                ..RateLimitPolicy::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_api_key_scope_violation() {
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3));
+        let scopes = ApiKeyScopes {
+            endpoints: vec![EndpointScope::Chat],
+            ip_allowlist: vec!["10.0.0.0/8".to_string()],
+            ..ApiKeyScopes::default()
+        };
+        assert!(api_key_scope_violation(&scopes, "/v1/chat/completions", &ip).is_none());
+        assert!(api_key_scope_violation(&scopes, "/api/generate", &ip).is_none());
+        assert!(api_key_scope_violation(&scopes, "/v1/embeddings", &ip).is_some());
+        assert!(api_key_scope_violation(&scopes, "/v1/audio/speech", &ip).is_some());
+        assert!(api_key_scope_violation(&scopes, "/health", &ip).is_none());
+
+        let outside = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        assert!(api_key_scope_violation(&scopes, "/v1/chat/completions", &outside).is_some());
+        assert!(api_key_scope_violation(&ApiKeyScopes::default(), "/metrics", &outside).is_none());
+    }
+}
@@ -0,0 +1,556 @@
+//! Retention of security.db event tables
+//!
+//! `audit_logs`, `intrusion_attempts`, `anomaly_detections` and
+//! `resource_alerts` are pruned according to the `retention` section of the
+//! security policy (`flm_core::domain::security::RetentionPolicy`). Rows
+//! older than `max_age_days`, or beyond the newest `max_rows`, are deleted
+//! oldest first in chunks. With `archive_dir` set, each chunk is first
+//! appended to `{archive_dir}/{table}-{timestamp}.jsonl.gz` as a gzip member
+//! holding one JSON object per row.
+//!
+//! Pruning `audit_logs` records the hash of the last deleted entry in
+//! `audit_chain_anchors`, so new entries and `flm security audit-logs verify`
+//! continue the hash chain from there.
+
+use crate::adapters::SqliteSecurityRepository;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use flm_core::domain::policy::PolicyDocument;
+use flm_core::domain::security::{RetentionPolicy, RetentionTable, TableRetention};
+use flm_core::error::RepoError;
+use flm_core::ports::SecurityRepository;
+use sqlx::sqlite::SqliteConnection;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
+
+/// Rows deleted per transaction, so audit log writers are not blocked for long
+const PRUNE_CHUNK_ROWS: i64 = 10_000;
+
+/// Delay before the first run, so pruning does not slow down startup
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(60);
+
+/// Rows pruned from one table
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruneOutcome {
+    pub deleted: u64,
+    /// Archive the deleted rows were appended to
+    pub archive: Option<PathBuf>,
+}
+
+/// Prune the tables of the `default` policy's `retention` section
+/// periodically
+///
+/// The policy is re-read before every run, so changes made with
+/// `flm security policy set` apply without a restart. Failures are logged
+/// and retried at the next run.
+pub fn spawn_retention_task(repo: Arc<SqliteSecurityRepository>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        loop {
+            let policy = match load_retention_policy(repo.as_ref()).await {
+                Ok(policy) => policy,
+                Err(reason) => {
+                    warn!(reason = %reason, "Invalid retention policy, skipping pruning");
+                    None
+                }
+            };
+            let Some(policy) = policy else {
+                tokio::time::sleep(RetentionPolicy::default().interval()).await;
+                continue;
+            };
+            for (table, outcome) in prune_all(repo.as_ref(), &policy, chrono::Utc::now()).await {
+                if outcome.deleted > 0 {
+                    info!(
+                        table = table.table_name(),
+                        deleted = outcome.deleted,
+                        archive = ?outcome.archive,
+                        "Pruned security.db table"
+                    );
+                }
+            }
+            tokio::time::sleep(policy.interval()).await;
+        }
+    })
+}
+
+async fn load_retention_policy(
+    repo: &SqliteSecurityRepository,
+) -> Result<Option<RetentionPolicy>, String> {
+    let Some(policy) = repo
+        .fetch_policy("default")
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+    Ok(PolicyDocument::parse_lenient(&policy.policy_json)?
+        .0
+        .retention)
+}
+
+/// Prune every table that has limits in `policy`
+///
+/// A table that fails is logged and skipped.
+pub async fn prune_all(
+    repo: &SqliteSecurityRepository,
+    policy: &RetentionPolicy,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<(RetentionTable, PruneOutcome)> {
+    let archive_dir = policy.archive_dir.as_deref().map(Path::new);
+    let mut outcomes = Vec::new();
+    for table in RetentionTable::ALL {
+        let Some(limits) = policy.table(table) else {
+            continue;
+        };
+        match prune_table(repo, table, limits, archive_dir, now).await {
+            Ok(outcome) => outcomes.push((table, outcome)),
+            Err(e) => error!(table = table.table_name(), error = %e, "Failed to prune table"),
+        }
+    }
+    outcomes
+}
+
+/// Delete the rows of `table` beyond `limits`, archiving them to
+/// `archive_dir` first if set
+pub async fn prune_table(
+    repo: &SqliteSecurityRepository,
+    table: RetentionTable,
+    limits: TableRetention,
+    archive_dir: Option<&Path>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<PruneOutcome, RepoError> {
+    let name = table.table_name();
+    let io_error = |e: sqlx::Error| RepoError::IoError {
+        reason: format!("Failed to prune {name}: {e}"),
+    };
+    let mut conn = repo.pool().acquire().await.map_err(io_error)?;
+    let Some(through) = prune_boundary(&mut conn, name, limits, now)
+        .await
+        .map_err(io_error)?
+    else {
+        return Ok(PruneOutcome::default());
+    };
+
+    let mut archive = match archive_dir {
+        Some(dir) => Some(
+            Archive::new(dir, name, now, &mut conn)
+                .await
+                .map_err(io_error)?,
+        ),
+        None => None,
+    };
+    let mut chain_anchor = None;
+    let mut deleted = 0;
+    loop {
+        // BEGIN IMMEDIATE keeps audit log writers from linking to an entry
+        // while it is deleted
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut *conn)
+            .await
+            .map_err(io_error)?;
+        let result = prune_chunk(
+            &mut conn,
+            table,
+            through,
+            archive.as_mut(),
+            &mut chain_anchor,
+            deleted,
+        )
+        .await;
+        let end = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+        let ended = sqlx::query(end).execute(&mut *conn).await;
+        let chunk = result
+            .and_then(|chunk| ended.map(|_| chunk))
+            .map_err(io_error)?;
+        if chunk == 0 {
+            break;
+        }
+        deleted += chunk;
+    }
+
+    Ok(PruneOutcome {
+        deleted,
+        archive: archive.and_then(|archive| archive.written.then_some(archive.path)),
+    })
+}
+
+/// Highest rowid to delete: everything older than `max_age_days` or all but
+/// the newest `max_rows` rows, whichever is more
+async fn prune_boundary(
+    conn: &mut SqliteConnection,
+    table: &str,
+    limits: TableRetention,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<i64>, sqlx::Error> {
+    let mut through = None;
+    if let Some(days) = limits.max_age_days {
+        // created_at is SQLite's datetime('now'), UTC
+        let cutoff = (now - chrono::Duration::days(i64::from(days)))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let (first_kept, last): (Option<i64>, Option<i64>) = sqlx::query_as(&format!(
+            "SELECT (SELECT MIN(rowid) FROM {table} WHERE created_at >= ?), (SELECT MAX(rowid) FROM {table})"
+        ))
+        .bind(&cutoff)
+        .fetch_one(&mut *conn)
+        .await?;
+        through = match first_kept {
+            Some(first_kept) => Some(first_kept - 1),
+            None => last,
+        };
+    }
+    if let Some(max_rows) = limits.max_rows {
+        let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&mut *conn)
+            .await?;
+        let excess = rows.saturating_sub(i64::try_from(max_rows).unwrap_or(i64::MAX));
+        if excess > 0 {
+            let by_rows: i64 = sqlx::query_scalar(&format!(
+                "SELECT rowid FROM {table} ORDER BY rowid LIMIT 1 OFFSET ?"
+            ))
+            .bind(excess - 1)
+            .fetch_one(&mut *conn)
+            .await?;
+            through = through.max(Some(by_rows));
+        }
+    }
+    Ok(through.filter(|through| *through > 0))
+}
+
+/// Archive and delete up to [`PRUNE_CHUNK_ROWS`] of the oldest rows up to
+/// `through`, returning how many were deleted
+async fn prune_chunk(
+    conn: &mut SqliteConnection,
+    table: RetentionTable,
+    through: i64,
+    archive: Option<&mut Archive>,
+    chain_anchor: &mut Option<i64>,
+    deleted_before: u64,
+) -> Result<u64, sqlx::Error> {
+    let name = table.table_name();
+    let chunk_through: Option<i64> = sqlx::query_scalar(&format!(
+        "SELECT MAX(rowid) FROM (SELECT rowid FROM {name} WHERE rowid <= ? ORDER BY rowid LIMIT ?)"
+    ))
+    .bind(through)
+    .bind(PRUNE_CHUNK_ROWS)
+    .fetch_one(&mut *conn)
+    .await?;
+    let Some(chunk_through) = chunk_through else {
+        return Ok(0);
+    };
+
+    let archive_path = match archive {
+        Some(archive) => {
+            let rows: Vec<String> = sqlx::query_scalar(&format!(
+                "SELECT {} FROM {name} WHERE rowid <= ? ORDER BY rowid",
+                archive.row_json
+            ))
+            .bind(chunk_through)
+            .fetch_all(&mut *conn)
+            .await?;
+            // The rows must be on disk before they are deleted
+            archive.append(&rows).await?;
+            Some(archive.path.display().to_string())
+        }
+        None => None,
+    };
+
+    let last_entry: Option<(Option<String>, Option<String>)> = match table {
+        RetentionTable::AuditLogs => Some(
+            sqlx::query_as("SELECT entry_hash, hash_alg FROM audit_logs WHERE id = ?")
+                .bind(chunk_through)
+                .fetch_one(&mut *conn)
+                .await?,
+        ),
+        _ => None,
+    };
+
+    let deleted = sqlx::query(&format!("DELETE FROM {name} WHERE rowid <= ?"))
+        .bind(chunk_through)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    if let Some((entry_hash, hash_alg)) = last_entry {
+        let pruned_rows = i64::try_from(deleted_before + deleted).unwrap_or(i64::MAX);
+        // One anchor per run, moved forward chunk by chunk
+        match *chain_anchor {
+            Some(anchor_id) => {
+                sqlx::query(
+                    "UPDATE audit_chain_anchors SET pruned_through_id = ?, entry_hash = ?, hash_alg = ?, pruned_rows = ?, archive_path = ? WHERE id = ?",
+                )
+                .bind(chunk_through)
+                .bind(&entry_hash)
+                .bind(&hash_alg)
+                .bind(pruned_rows)
+                .bind(&archive_path)
+                .bind(anchor_id)
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                let anchor_id = sqlx::query(
+                    "INSERT INTO audit_chain_anchors (pruned_through_id, entry_hash, hash_alg, pruned_rows, archive_path) VALUES (?, ?, ?, ?, ?)",
+                )
+                .bind(chunk_through)
+                .bind(&entry_hash)
+                .bind(&hash_alg)
+                .bind(pruned_rows)
+                .bind(&archive_path)
+                .execute(&mut *conn)
+                .await?
+                .last_insert_rowid();
+                *chain_anchor = Some(anchor_id);
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// Gzip-compressed JSONL file that pruned rows are appended to
+struct Archive {
+    path: PathBuf,
+    /// `json_object(...)` expression over the table's columns
+    row_json: String,
+    written: bool,
+}
+
+impl Archive {
+    async fn new(
+        dir: &Path,
+        table: &str,
+        now: chrono::DateTime<chrono::Utc>,
+        conn: &mut SqliteConnection,
+    ) -> Result<Self, sqlx::Error> {
+        let columns: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+                .fetch_all(&mut *conn)
+                .await?;
+        let row_json = format!(
+            "json_object({})",
+            columns
+                .iter()
+                .map(|column| format!("'{column}', \"{column}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Ok(Self {
+            path: dir.join(format!("{table}-{}.jsonl.gz", now.format("%Y%m%dT%H%M%SZ"))),
+            row_json,
+            written: false,
+        })
+    }
+
+    /// Append `rows` as one gzip member and sync the file
+    async fn append(&mut self, rows: &[String]) -> std::io::Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for row in rows {
+            encoder.write_all(row.as_bytes())?;
+            encoder.write_all(b"\n")?;
+        }
+        let member = encoder.finish()?;
+
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut options = tokio::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&self.path).await?;
+        file.write_all(&member).await?;
+        file.sync_all().await?;
+        self.written = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::AuditLogMetadata;
+    use std::io::Read;
+
+    async fn save_audit_logs(repo: &SqliteSecurityRepository, count: usize) {
+        for index in 0..count {
+            repo.save_audit_log(
+                &format!("req-{index}"),
+                None,
+                "/v1/chat/completions",
+                200,
+                Some(5),
+                Some("auth_success"),
+                AuditLogMetadata {
+                    severity: "low",
+                    ip: Some("127.0.0.1"),
+                    details: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    }
+
+    #[tokio::test]
+    async fn test_prune_by_age_and_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = SqliteSecurityRepository::new(dir.path().join("security.db"))
+            .await
+            .unwrap();
+        for (index, created_at) in [
+            "2026-08-01 00:00:00",
+            "2026-09-01 00:00:00",
+            "2026-10-10 00:00:00",
+            "2026-10-17 00:00:00",
+            "2026-10-18 00:00:00",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            sqlx::query(
+                "INSERT INTO intrusion_attempts (id, ip, pattern, score, created_at) VALUES (?, '203.0.113.7', 'sqli', 50, ?)",
+            )
+            .bind(format!("attempt-{index}"))
+            .bind(created_at)
+            .execute(repo.pool())
+            .await
+            .unwrap();
+        }
+        let remaining = || async {
+            sqlx::query_scalar::<_, String>("SELECT id FROM intrusion_attempts ORDER BY rowid")
+                .fetch_all(repo.pool())
+                .await
+                .unwrap()
+        };
+
+        let by_age = TableRetention {
+            max_age_days: Some(30),
+            max_rows: None,
+        };
+        let outcome = prune_table(
+            &repo,
+            RetentionTable::IntrusionAttempts,
+            by_age,
+            None,
+            now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.deleted, 2);
+        assert_eq!(outcome.archive, None);
+        assert_eq!(
+            remaining().await,
+            vec!["attempt-2", "attempt-3", "attempt-4"]
+        );
+
+        // Whichever limit removes more rows wins
+        let both = TableRetention {
+            max_age_days: Some(30),
+            max_rows: Some(1),
+        };
+        let outcome = prune_table(&repo, RetentionTable::IntrusionAttempts, both, None, now())
+            .await
+            .unwrap();
+        assert_eq!(outcome.deleted, 2);
+        assert_eq!(remaining().await, vec!["attempt-4"]);
+
+        let outcome = prune_table(&repo, RetentionTable::IntrusionAttempts, both, None, now())
+            .await
+            .unwrap();
+        assert_eq!(outcome, PruneOutcome::default());
+    }
+
+    #[tokio::test]
+    async fn test_prune_audit_logs_archives_and_keeps_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = dir.path().join("archive");
+        let repo = SqliteSecurityRepository::new(dir.path().join("security.db"))
+            .await
+            .unwrap();
+        save_audit_logs(&repo, 5).await;
+        let hash_of = |id: i64| {
+            let pool = repo.pool().clone();
+            async move {
+                sqlx::query_scalar::<_, String>("SELECT entry_hash FROM audit_logs WHERE id = ?")
+                    .bind(id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+        let third_hash = hash_of(3).await;
+        let last_hash = hash_of(5).await;
+
+        let policy = RetentionPolicy {
+            audit_logs: Some(TableRetention {
+                max_age_days: None,
+                max_rows: Some(2),
+            }),
+            archive_dir: Some(archive_dir.display().to_string()),
+            ..Default::default()
+        };
+        let outcomes = prune_all(&repo, &policy, now()).await;
+        assert_eq!(outcomes.len(), 1);
+        let (table, outcome) = &outcomes[0];
+        assert_eq!(*table, RetentionTable::AuditLogs);
+        assert_eq!(outcome.deleted, 3);
+        let archive = outcome.archive.clone().unwrap();
+        assert_eq!(
+            archive,
+            archive_dir.join("audit_logs-20261018T120000Z.jsonl.gz")
+        );
+
+        let mut archived = String::new();
+        flate2::read::MultiGzDecoder::new(std::fs::File::open(&archive).unwrap())
+            .read_to_string(&mut archived)
+            .unwrap();
+        let ids: Vec<i64> = archived
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["id"]
+                    .as_i64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let anchor: (i64, Option<String>, i64, Option<String>) = sqlx::query_as(
+            "SELECT pruned_through_id, entry_hash, pruned_rows, archive_path FROM audit_chain_anchors",
+        )
+        .fetch_one(repo.pool())
+        .await
+        .unwrap();
+        assert_eq!(
+            anchor,
+            (3, Some(third_hash), 3, Some(archive.display().to_string()))
+        );
+
+        // Once every chained entry is pruned, new entries link to the anchor
+        let policy = RetentionPolicy {
+            audit_logs: Some(TableRetention {
+                max_age_days: Some(1),
+                max_rows: None,
+            }),
+            ..Default::default()
+        };
+        let far_future = now() + chrono::Duration::days(3650);
+        let outcomes = prune_all(&repo, &policy, far_future).await;
+        assert_eq!(outcomes[0].1.deleted, 2);
+        save_audit_logs(&repo, 1).await;
+        let prev_hash: String = sqlx::query_scalar("SELECT prev_hash FROM audit_logs WHERE id = 6")
+            .fetch_one(repo.pool())
+            .await
+            .unwrap();
+        assert_eq!(prev_hash, last_hash);
+    }
+}
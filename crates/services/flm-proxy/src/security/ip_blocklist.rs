@@ -97,7 +97,7 @@ impl IpBlocklist {
         entry.last_attempt = now;
 
         // Apply blocking rules
-        let should_block = if entry.failure_count >= 20 {
+        if entry.failure_count >= 20 {
             // Permanent block
             entry.permanent_block = true;
             entry.blocked_until = None;
@@ -133,9 +133,7 @@ impl IpBlocklist {
                 "Authentication failure recorded (warning only)"
             );
             false
-        };
-
-        should_block
+        }
     }
 
     /// Unblock an IP address
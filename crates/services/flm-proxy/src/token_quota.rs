@@ -0,0 +1,790 @@
+//! Token-based rate limits and usage quotas per API key
+//!
+//! The security policy's `rate_limit` section may set `tpm` (tokens per
+//! minute), `daily_tokens` and `monthly_tokens`. Consumption is counted from
+//! the `UsageStats` engines report. Until a response arrives, the request's
+//! estimated prompt tokens are held as a reservation so concurrent requests
+//! cannot overshoot a limit. A stream that ends without reported usage is
+//! charged the prompt estimate plus an estimate of the streamed text. Daily
+//! totals are persisted in security.db (`token_usage`); days and months are UTC.
+//! Each charged request is also stored in `usage_records` with its endpoint,
+//! engine, model and latency for `flm usage report`.
+
+use crate::adapters::{SqliteSecurityRepository, UsageRecord};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use flm_core::domain::chat::{ChatMessage, ChatStreamChunk, UsageStats};
+use flm_core::domain::policy::PolicyDocument;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+/// Length of the tokens-per-minute window
+const TPM_WINDOW: Duration = Duration::from_secs(60);
+
+/// Fixed per-message overhead added to prompt estimates (role and separators)
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+
+/// Token limits from the policy's `rate_limit` section
+///
+/// A missing or zero value means no limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenLimits {
+    pub tpm: Option<u64>,
+    pub daily_tokens: Option<u64>,
+    pub monthly_tokens: Option<u64>,
+}
+
+impl TokenLimits {
+    /// Read `rate_limit.tpm`, `rate_limit.daily_tokens` and `rate_limit.monthly_tokens`
+    pub fn from_policy(policy: &PolicyDocument) -> Self {
+        let rate_limit = policy.rate_limit.unwrap_or_default();
+        let limit = |value: Option<u64>| value.filter(|v| *v > 0);
+        Self {
+            tpm: limit(rate_limit.tpm),
+            daily_tokens: limit(rate_limit.daily_tokens),
+            monthly_tokens: limit(rate_limit.monthly_tokens),
+        }
+    }
+
+    /// Whether no token limit is configured
+    pub fn is_unlimited(&self) -> bool {
+        self.tpm.is_none() && self.daily_tokens.is_none() && self.monthly_tokens.is_none()
+    }
+}
+
+/// Token limit that rejected a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaKind {
+    Tpm,
+    Daily,
+    Monthly,
+}
+
+impl QuotaKind {
+    /// Policy field name of the limit
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tpm => "tpm",
+            Self::Daily => "daily_tokens",
+            Self::Monthly => "monthly_tokens",
+        }
+    }
+}
+
+/// A request refused because the API key used up a token limit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuotaRejection {
+    pub kind: QuotaKind,
+    pub limit: u64,
+    /// Tokens consumed (and reserved) in the current window
+    pub used: u64,
+    /// Time until the window resets
+    pub retry_after: Duration,
+}
+
+impl QuotaRejection {
+    /// Value for the `Retry-After` header (whole seconds, at least 1)
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs().max(1)
+    }
+
+    /// Always 429
+    pub fn status(&self) -> StatusCode {
+        StatusCode::TOO_MANY_REQUESTS
+    }
+
+    /// Error code: `token_rate_limit_exceeded` for TPM, `token_quota_exceeded` for quotas
+    pub fn code(&self) -> &'static str {
+        match self.kind {
+            QuotaKind::Tpm => "token_rate_limit_exceeded",
+            QuotaKind::Daily | QuotaKind::Monthly => "token_quota_exceeded",
+        }
+    }
+
+    /// Client-facing error message
+    pub fn message(&self) -> &'static str {
+        match self.kind {
+            QuotaKind::Tpm => "Token rate limit exceeded",
+            QuotaKind::Daily => "Daily token quota exceeded",
+            QuotaKind::Monthly => "Monthly token quota exceeded",
+        }
+    }
+
+    /// Add the `Retry-After` header to an error response for this rejection
+    pub fn with_retry_after(&self, mut response: Response) -> Response {
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(self.retry_after_secs()),
+        );
+        response
+    }
+
+    /// OpenAI-style 429 response with `Retry-After`
+    pub fn to_response(&self) -> Response {
+        self.with_retry_after(
+            (
+                self.status(),
+                axum::Json(serde_json::json!({
+                    "error": {
+                        "message": self.message(),
+                        "type": "rate_limit_error",
+                        "code": self.code()
+                    }
+                })),
+            )
+                .into_response(),
+        )
+    }
+}
+
+/// Token consumption of one API key in the current windows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenUsageSnapshot {
+    pub minute_tokens: u64,
+    pub day_tokens: u64,
+    pub month_tokens: u64,
+    /// Estimated prompt tokens of requests still waiting for their usage
+    pub reserved_tokens: u64,
+}
+
+struct KeyUsage {
+    minute_start: Instant,
+    minute_tokens: u64,
+    day: NaiveDate,
+    day_tokens: u64,
+    month_tokens: u64,
+    reserved: u64,
+}
+
+impl KeyUsage {
+    /// Start new windows when the minute, day or month has passed
+    fn roll(&mut self, now: Instant, today: NaiveDate) {
+        if now.duration_since(self.minute_start) >= TPM_WINDOW {
+            self.minute_start = now;
+            self.minute_tokens = 0;
+        }
+        if today != self.day {
+            if (today.year(), today.month()) != (self.day.year(), self.day.month()) {
+                self.month_tokens = 0;
+            }
+            self.day = today;
+            self.day_tokens = 0;
+        }
+    }
+
+    /// Reject when a limit is used up or `extra` more tokens would exceed it
+    fn check(
+        &self,
+        limits: &TokenLimits,
+        extra: u64,
+        now: Instant,
+        now_utc: DateTime<Utc>,
+    ) -> Result<(), QuotaRejection> {
+        let windows = [
+            (QuotaKind::Tpm, limits.tpm, self.minute_tokens),
+            (QuotaKind::Daily, limits.daily_tokens, self.day_tokens),
+            (QuotaKind::Monthly, limits.monthly_tokens, self.month_tokens),
+        ];
+        for (kind, limit, consumed) in windows {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let used = consumed.saturating_add(self.reserved);
+            if used >= limit || used.saturating_add(extra) > limit {
+                let retry_after = match kind {
+                    QuotaKind::Tpm => {
+                        TPM_WINDOW.saturating_sub(now.duration_since(self.minute_start))
+                    }
+                    QuotaKind::Daily => until_next_day(now_utc),
+                    QuotaKind::Monthly => until_next_month(now_utc),
+                };
+                return Err(QuotaRejection {
+                    kind,
+                    limit,
+                    used,
+                    retry_after,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self) -> TokenUsageSnapshot {
+        TokenUsageSnapshot {
+            minute_tokens: self.minute_tokens,
+            day_tokens: self.day_tokens,
+            month_tokens: self.month_tokens,
+            reserved_tokens: self.reserved,
+        }
+    }
+}
+
+fn until_next_day(now: DateTime<Utc>) -> Duration {
+    let next = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|day| day.and_hms_opt(0, 0, 0));
+    until(now, next)
+}
+
+fn until_next_month(now: DateTime<Utc>) -> Duration {
+    let today = now.date_naive();
+    let (year, month) = if today.month() == 12 {
+        (today.year() + 1, 1)
+    } else {
+        (today.year(), today.month() + 1)
+    };
+    let next = NaiveDate::from_ymd_opt(year, month, 1).and_then(|day| day.and_hms_opt(0, 0, 0));
+    until(now, next)
+}
+
+fn until(now: DateTime<Utc>, next: Option<chrono::NaiveDateTime>) -> Duration {
+    next.and_then(|next| (next.and_utc() - now).to_std().ok())
+        .unwrap_or(TPM_WINDOW)
+}
+
+/// Per-proxy token usage tracker
+pub struct TokenQuotas {
+    repo: Arc<SqliteSecurityRepository>,
+    usage: Mutex<HashMap<String, KeyUsage>>,
+}
+
+impl TokenQuotas {
+    pub fn new(repo: Arc<SqliteSecurityRepository>) -> Self {
+        Self {
+            repo,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reject the request when the key has already used up one of `limits`
+    pub async fn check(
+        &self,
+        api_key_id: &str,
+        limits: &TokenLimits,
+    ) -> Result<(), QuotaRejection> {
+        if limits.is_unlimited() {
+            return Ok(());
+        }
+        self.ensure_loaded(api_key_id).await;
+        let now = Instant::now();
+        let now_utc = Utc::now();
+        let mut usage = self.lock();
+        match usage.get_mut(api_key_id) {
+            Some(entry) => {
+                entry.roll(now, now_utc.date_naive());
+                entry.check(limits, 0, now, now_utc)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Hold `estimated` prompt tokens for a request until its usage is known
+    ///
+    /// Fails when the estimate does not fit in the remaining limits. The
+    /// reservation is released when the returned handle is dropped; call
+    /// [`TokenReservation::record`] to count the engine-reported usage instead.
+    pub async fn reserve(
+        self: &Arc<Self>,
+        api_key_id: &str,
+        endpoint: &str,
+        limits: &TokenLimits,
+        estimated: u64,
+    ) -> Result<TokenReservation, QuotaRejection> {
+        self.ensure_loaded(api_key_id).await;
+        let now = Instant::now();
+        let now_utc = Utc::now();
+        {
+            let mut usage = self.lock();
+            let entry = usage
+                .entry(api_key_id.to_string())
+                .or_insert_with(|| empty_usage(now, now_utc.date_naive()));
+            entry.roll(now, now_utc.date_naive());
+            entry.check(limits, estimated, now, now_utc)?;
+            entry.reserved = entry.reserved.saturating_add(estimated);
+        }
+        Ok(TokenReservation {
+            inner: Arc::new(ReservationInner {
+                quotas: Arc::clone(self),
+                api_key_id: api_key_id.to_string(),
+                endpoint: endpoint.to_string(),
+                started: now,
+                target: Mutex::new(None),
+                estimated,
+                settled: AtomicBool::new(false),
+                streamed: AtomicBool::new(false),
+                streamed_tokens: AtomicU64::new(0),
+            }),
+        })
+    }
+
+    /// Current consumption of an API key (zero for keys not seen since startup)
+    pub fn usage(&self, api_key_id: &str) -> TokenUsageSnapshot {
+        let now = Instant::now();
+        let mut usage = self.lock();
+        usage
+            .get_mut(api_key_id)
+            .map(|entry| {
+                entry.roll(now, Utc::now().date_naive());
+                entry.snapshot()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Load today's and this month's totals from security.db the first time a key is seen
+    async fn ensure_loaded(&self, api_key_id: &str) {
+        if self.lock().contains_key(api_key_id) {
+            return;
+        }
+        let today = Utc::now().date_naive();
+        let (day_tokens, month_tokens) = match self
+            .repo
+            .fetch_token_usage_totals(api_key_id, &today.format("%Y-%m-%d").to_string())
+            .await
+        {
+            Ok(totals) => totals,
+            Err(e) => {
+                warn!(
+                    error_type = "token_usage_load_failed",
+                    error = %e,
+                    "Failed to load token usage, starting from zero"
+                );
+                (0, 0)
+            }
+        };
+        self.lock()
+            .entry(api_key_id.to_string())
+            .or_insert_with(|| KeyUsage {
+                day_tokens,
+                month_tokens,
+                ..empty_usage(Instant::now(), today)
+            });
+    }
+
+    /// Release a reservation and count the actual usage, if any
+    ///
+    /// `is_estimate` marks usage estimated by the proxy rather than reported by the engine.
+    fn settle(
+        &self,
+        reservation: &ReservationInner,
+        actual: Option<&UsageStats>,
+        is_estimate: bool,
+    ) {
+        let api_key_id = reservation.api_key_id.as_str();
+        let estimated = reservation.estimated;
+        let now = Instant::now();
+        let today = Utc::now().date_naive();
+        {
+            let mut usage = self.lock();
+            let entry = usage
+                .entry(api_key_id.to_string())
+                .or_insert_with(|| empty_usage(now, today));
+            entry.reserved = entry.reserved.saturating_sub(estimated);
+            if let Some(actual) = actual {
+                let tokens = u64::from(actual.total_tokens);
+                entry.roll(now, today);
+                entry.minute_tokens = entry.minute_tokens.saturating_add(tokens);
+                entry.day_tokens = entry.day_tokens.saturating_add(tokens);
+                entry.month_tokens = entry.month_tokens.saturating_add(tokens);
+            }
+        }
+
+        let Some(actual) = actual.cloned() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (engine_id, model_id) = reservation
+            .target
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+            .unzip();
+        let record = UsageRecord {
+            api_key_id: api_key_id.to_string(),
+            endpoint: reservation.endpoint.clone(),
+            engine_id,
+            model_id,
+            usage: actual,
+            latency_ms: now.duration_since(reservation.started).as_millis() as u64,
+            estimated: is_estimate,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        let repo = Arc::clone(&self.repo);
+        runtime.spawn(async move {
+            let usage_date = today.format("%Y-%m-%d").to_string();
+            if let Err(e) = repo
+                .record_token_usage(&record.api_key_id, &usage_date, &record.usage)
+                .await
+            {
+                error!(
+                    error_type = "token_usage_persist_failed",
+                    error = %e,
+                    "Failed to persist token usage"
+                );
+            }
+            if let Err(e) = repo.save_usage_record(&record).await {
+                error!(
+                    error_type = "usage_record_persist_failed",
+                    error = %e,
+                    "Failed to persist usage record"
+                );
+            }
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, KeyUsage>> {
+        self.usage
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn empty_usage(now: Instant, today: NaiveDate) -> KeyUsage {
+    KeyUsage {
+        minute_start: now,
+        minute_tokens: 0,
+        day: today,
+        day_tokens: 0,
+        month_tokens: 0,
+        reserved: 0,
+    }
+}
+
+/// Estimated prompt tokens held for one request
+///
+/// Clones share the reservation. Once the last clone is dropped without
+/// recorded usage, the estimate is released, or charged together with the
+/// estimated completion if streamed output was observed.
+#[derive(Clone)]
+pub struct TokenReservation {
+    inner: Arc<ReservationInner>,
+}
+
+struct ReservationInner {
+    quotas: Arc<TokenQuotas>,
+    api_key_id: String,
+    /// Request path the usage is recorded under
+    endpoint: String,
+    started: Instant,
+    /// Engine and model that served the request, once known
+    target: Mutex<Option<(String, String)>>,
+    estimated: u64,
+    settled: AtomicBool,
+    /// Whether streamed output was observed
+    streamed: AtomicBool,
+    /// Estimated completion tokens of the streamed output
+    streamed_tokens: AtomicU64,
+}
+
+impl TokenReservation {
+    /// Count the engine-reported usage in place of the estimate
+    ///
+    /// Only the first call counts; a stream reports usage once, on its final chunk.
+    pub fn record(&self, usage: &UsageStats) {
+        if !self.inner.settled.swap(true, Ordering::AcqRel) {
+            self.inner.quotas.settle(&self.inner, Some(usage), false);
+        }
+    }
+
+    /// Note the engine and model serving the request, for its usage record
+    ///
+    /// Called again when a model group fails over to another member.
+    pub fn set_target(&self, engine_id: &str, model_id: &str) {
+        *self
+            .inner
+            .target
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Some((engine_id.to_string(), model_id.to_string()));
+    }
+
+    /// Account for one streamed chunk of output
+    ///
+    /// Records the usage when the chunk reports it; otherwise adds the text to
+    /// the completion estimate charged if the stream ends without usage.
+    pub fn observe_output(&self, text: &str, usage: Option<&UsageStats>) {
+        match usage {
+            Some(usage) => self.record(usage),
+            None => {
+                self.inner.streamed.store(true, Ordering::Release);
+                self.inner
+                    .streamed_tokens
+                    .fetch_add(estimate_text_tokens(text), Ordering::AcqRel);
+            }
+        }
+    }
+
+    /// [`observe_output`](Self::observe_output) for a chat stream chunk (text and tool call arguments)
+    pub fn observe_chat_chunk(&self, chunk: &ChatStreamChunk) {
+        if chunk.usage.is_none() {
+            for call in &chunk.tool_calls {
+                self.observe_output(call.arguments.as_deref().unwrap_or_default(), None);
+            }
+        }
+        self.observe_output(&chunk.delta.content, chunk.usage.as_ref());
+    }
+}
+
+impl Drop for ReservationInner {
+    fn drop(&mut self) {
+        if *self.settled.get_mut() {
+            return;
+        }
+        if *self.streamed.get_mut() {
+            let completion = *self.streamed_tokens.get_mut();
+            let clamp = |tokens: u64| u32::try_from(tokens).unwrap_or(u32::MAX);
+            let estimated = UsageStats {
+                prompt_tokens: clamp(self.estimated),
+                completion_tokens: clamp(completion),
+                total_tokens: clamp(self.estimated.saturating_add(completion)),
+            };
+            self.quotas.settle(self, Some(&estimated), true);
+        } else {
+            self.quotas.settle(self, None, false);
+        }
+    }
+}
+
+/// Token accounting context of an authenticated request
+///
+/// `policy_middleware` stores it in the request extensions; handlers reserve
+/// the estimated prompt tokens through it before calling the engine.
+#[derive(Clone)]
+pub struct TokenBudget {
+    quotas: Arc<TokenQuotas>,
+    api_key_id: String,
+    endpoint: String,
+    limits: TokenLimits,
+}
+
+impl TokenBudget {
+    pub fn new(
+        quotas: Arc<TokenQuotas>,
+        api_key_id: String,
+        endpoint: String,
+        limits: TokenLimits,
+    ) -> Self {
+        Self {
+            quotas,
+            api_key_id,
+            endpoint,
+            limits,
+        }
+    }
+
+    /// Reserve `estimated` prompt tokens against the key's limits
+    pub async fn reserve(&self, estimated: u64) -> Result<TokenReservation, QuotaRejection> {
+        self.quotas
+            .reserve(&self.api_key_id, &self.endpoint, &self.limits, estimated)
+            .await
+    }
+}
+
+/// Reserve prompt tokens when the request carries a budget (authenticated requests)
+pub async fn reserve_tokens(
+    budget: Option<&TokenBudget>,
+    estimated: u64,
+) -> Result<Option<TokenReservation>, QuotaRejection> {
+    match budget {
+        Some(budget) => budget.reserve(estimated).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Rough token count of a text (about four characters per token)
+pub fn estimate_text_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Rough prompt token count of a conversation
+pub fn estimate_prompt_tokens(messages: &[ChatMessage]) -> u64 {
+    messages
+        .iter()
+        .map(|message| {
+            let tool_calls: u64 = message
+                .tool_calls
+                .iter()
+                .map(|call| {
+                    estimate_text_tokens(&call.name) + estimate_text_tokens(&call.arguments)
+                })
+                .sum();
+            MESSAGE_OVERHEAD_TOKENS + estimate_text_tokens(&message.content) + tool_calls
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn quotas() -> (tempfile::TempDir, Arc<TokenQuotas>) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = SqliteSecurityRepository::new(dir.path().join("security.db"))
+            .await
+            .unwrap();
+        (dir, Arc::new(TokenQuotas::new(Arc::new(repo))))
+    }
+
+    fn usage(total: u32) -> UsageStats {
+        UsageStats {
+            prompt_tokens: total / 2,
+            completion_tokens: total - total / 2,
+            total_tokens: total,
+        }
+    }
+
+    #[test]
+    fn test_token_limits_from_policy() {
+        let policy = PolicyDocument::parse(
+            r#"{"rate_limit": {"rpm": 60, "tpm": 1000, "daily_tokens": 0, "monthly_tokens": 50000}}"#,
+        )
+        .unwrap();
+        let limits = TokenLimits::from_policy(&policy);
+        assert_eq!(limits.tpm, Some(1000));
+        assert_eq!(limits.daily_tokens, None);
+        assert_eq!(limits.monthly_tokens, Some(50000));
+        assert!(TokenLimits::from_policy(&PolicyDocument::default()).is_unlimited());
+    }
+
+    #[tokio::test]
+    async fn test_reservation_counts_reported_usage() {
+        let (_dir, quotas) = quotas().await;
+        let limits = TokenLimits {
+            tpm: Some(100),
+            ..Default::default()
+        };
+
+        let first = quotas
+            .reserve("key", "/v1/chat/completions", &limits, 30)
+            .await
+            .unwrap();
+        assert_eq!(quotas.usage("key").reserved_tokens, 30);
+        // The pending estimate counts against the limit
+        let rejection = quotas
+            .reserve("key", "/v1/chat/completions", &limits, 80)
+            .await
+            .err()
+            .expect("over the limit");
+        assert_eq!(rejection.kind, QuotaKind::Tpm);
+        assert_eq!(rejection.code(), "token_rate_limit_exceeded");
+        assert!(rejection.retry_after_secs() <= 60);
+
+        first.record(&usage(90));
+        first.record(&usage(90));
+        let snapshot = quotas.usage("key");
+        assert_eq!(snapshot.reserved_tokens, 0);
+        assert_eq!(snapshot.minute_tokens, 90);
+        assert!(quotas
+            .reserve("key", "/v1/chat/completions", &limits, 20)
+            .await
+            .is_err());
+        assert!(quotas
+            .reserve("key", "/v1/chat/completions", &limits, 10)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_reservation_is_released() {
+        let (_dir, quotas) = quotas().await;
+        let limits = TokenLimits {
+            daily_tokens: Some(50),
+            ..Default::default()
+        };
+
+        let reservation = quotas
+            .reserve("key", "/v1/chat/completions", &limits, 40)
+            .await
+            .unwrap();
+        let clone = reservation.clone();
+        drop(reservation);
+        assert_eq!(quotas.usage("key").reserved_tokens, 40);
+        drop(clone);
+        assert_eq!(quotas.usage("key"), TokenUsageSnapshot::default());
+        assert!(quotas.check("key", &limits).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stream_without_usage_is_charged_estimate() {
+        let (_dir, quotas) = quotas().await;
+        let limits = TokenLimits {
+            daily_tokens: Some(100),
+            ..Default::default()
+        };
+
+        let reservation = quotas
+            .reserve("key", "/v1/chat/completions", &limits, 40)
+            .await
+            .unwrap();
+        reservation.observe_output("0123456789abcdef", None);
+        reservation.observe_output("0123", None);
+        drop(reservation);
+        let snapshot = quotas.usage("key");
+        assert_eq!(snapshot.reserved_tokens, 0);
+        assert_eq!(snapshot.day_tokens, 45);
+
+        let reservation = quotas
+            .reserve("key", "/v1/chat/completions", &limits, 10)
+            .await
+            .unwrap();
+        reservation.observe_output("ignored once usage is reported", Some(&usage(12)));
+        drop(reservation);
+        assert_eq!(quotas.usage("key").day_tokens, 57);
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_persisted_across_restarts() {
+        let (dir, quotas) = quotas().await;
+        let limits = TokenLimits {
+            daily_tokens: Some(100),
+            monthly_tokens: Some(1000),
+            ..Default::default()
+        };
+        quotas
+            .reserve("key", "/v1/chat/completions", &limits, 5)
+            .await
+            .unwrap()
+            .record(&usage(100));
+        // Persistence runs in a background task
+        let repo = SqliteSecurityRepository::new(dir.path().join("security.db"))
+            .await
+            .unwrap();
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        for _ in 0..50 {
+            if repo.fetch_token_usage_totals("key", &today).await.unwrap() == (100, 100) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let restarted = TokenQuotas::new(Arc::new(repo));
+        let rejection = restarted.check("key", &limits).await.unwrap_err();
+        assert_eq!(rejection.kind, QuotaKind::Daily);
+        assert_eq!(rejection.code(), "token_quota_exceeded");
+        assert_eq!(rejection.used, 100);
+        assert_eq!(restarted.usage("key").month_tokens, 100);
+    }
+
+    #[test]
+    fn test_estimate_prompt_tokens() {
+        let message = ChatMessage {
+            role: flm_core::domain::chat::ChatRole::User,
+            content: "abcdefgh".to_string(),
+            attachments: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        };
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("abcde"), 2);
+        assert_eq!(
+            estimate_prompt_tokens(&[message]),
+            2 + MESSAGE_OVERHEAD_TOKENS
+        );
+    }
+}
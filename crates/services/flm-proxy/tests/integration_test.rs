@@ -199,6 +199,63 @@ async fn test_proxy_models_endpoint() {
     controller.stop(handle).await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stored_policy_with_unknown_fields_still_serves_requests() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::SecurityRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-policy-unknown-fields");
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+
+    // Written by another build: `set_policy` would reject the extra fields
+    security_repo
+        .save_policy(SecurityPolicy {
+            id: "default".to_string(),
+            policy_json: serde_json::json!({
+                "schema_version": 2,
+                "cors": { "allowed_origins": ["http://localhost:3000"], "max_age": 600 },
+                "log_level": "debug"
+            })
+            .to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18178,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let response = reqwest::Client::new()
+        .get("http://localhost:18178/v1/models")
+        .header("Authorization", bearer_header(&api_key.plain))
+        .header("Origin", "http://localhost:3000")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .and_then(|value| value.to_str().ok()),
+        Some("http://localhost:3000")
+    );
+
+    controller.stop(handle).await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_proxy_authentication() {
     use flm_core::domain::security::SecurityPolicy;
@@ -2829,3 +2886,2079 @@ async fn test_model_capabilities_audio_check_with_model_specific_capabilities()
 
     controller.stop(handle).await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_response_format_validation() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-response-format");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18160,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+
+    // A schema that does not compile is rejected before any engine is contacted
+    let invalid_schema = serde_json::json!({
+        "model": "flm://test-engine/test-model",
+        "messages": [{ "role": "user", "content": "Extract the invoice fields" }],
+        "response_format": {
+            "type": "json_schema",
+            "json_schema": {
+                "name": "invoice",
+                "schema": { "type": "not-a-type" }
+            }
+        }
+    });
+    let response = client
+        .post("http://localhost:18160/v1/chat/completions")
+        .header("Authorization", bearer_header(&api_key.plain))
+        .json(&invalid_schema)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "invalid_response_format");
+
+    // Proxy-side validation cannot be combined with streaming
+    let streaming = serde_json::json!({
+        "model": "flm://test-engine/test-model",
+        "messages": [{ "role": "user", "content": "Extract the invoice fields" }],
+        "stream": true,
+        "response_format": { "type": "json_object" }
+    });
+    let response = client
+        .post("http://localhost:18160/v1/chat/completions")
+        .header("Authorization", bearer_header(&api_key.plain))
+        .header("X-FLM-Response-Validation", "retry")
+        .json(&streaming)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "invalid_response_validation");
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_response_format_violation_charges_every_attempt() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Mock Ollama engine that never answers with JSON
+    let calls = Arc::new(AtomicUsize::new(0));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let engine_calls = calls.clone();
+    let app = axum::Router::new().route(
+        "/api/chat",
+        axum::routing::post(move || {
+            engine_calls.fetch_add(1, Ordering::SeqCst);
+            async {
+                axum::Json(serde_json::json!({
+                    "model": "llama3",
+                    "message": { "role": "assistant", "content": "not json" },
+                    "done": true,
+                    "prompt_eval_count": 30,
+                    "eval_count": 20
+                }))
+            }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-response-format-usage-security");
+    let config_db = unique_db_path("flm-test-response-format-usage-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo.clone()));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "json-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18177,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let response = reqwest::Client::new()
+        .post("http://localhost:18177/v1/chat/completions")
+        .header("Authorization", bearer_header(&api_key.plain))
+        .header("X-FLM-Response-Validation", "retry")
+        .json(&serde_json::json!({
+            "model": "flm://json-engine/llama3",
+            "messages": [{ "role": "user", "content": "Extract the invoice fields" }],
+            "response_format": { "type": "json_object" }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_GATEWAY);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "response_format_violation");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // All three attempts are charged, not only the last one
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let mut totals = (0, 0);
+    for _ in 0..50 {
+        totals = security_repo
+            .fetch_token_usage_totals(&api_key.record.id, &today)
+            .await
+            .unwrap();
+        if totals.0 > 0 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(totals, (150, 150));
+
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    let mut records: Vec<(i64, i64)> = Vec::new();
+    for _ in 0..50 {
+        records = sqlx::query_as("SELECT total_tokens, estimated FROM usage_records")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        if !records.is_empty() {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(records, vec![(150, 0)]);
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_rejects_invalid_sampling_params() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-sampling-params");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18161,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let invalid_requests = [
+        serde_json::json!({ "top_p": 1.5 }),
+        serde_json::json!({ "frequency_penalty": -3.0 }),
+        serde_json::json!({ "top_logprobs": 5 }),
+        serde_json::json!({ "logit_bias": { "not-a-token": 1.0 } }),
+        serde_json::json!({ "n": 2, "stream": true }),
+    ];
+    for extra in invalid_requests {
+        let mut chat_request = serde_json::json!({
+            "model": "flm://test-engine/test-model",
+            "messages": [{ "role": "user", "content": "Hello" }]
+        });
+        for (key, value) in extra.as_object().unwrap() {
+            chat_request[key] = value.clone();
+        }
+
+        let response = client
+            .post("http://localhost:18161/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&chat_request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "expected 400 for {extra}"
+        );
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "invalid_sampling");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_applies_model_profile_defaults() {
+    use flm_core::adapters::SqliteModelProfileRepository;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-model-profile-security");
+    let config_db = unique_db_path("flm-test-model-profile-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    // Create config.db with a profile whose defaults fail validation, so the
+    // response shows whether they were merged into the request
+    SqliteModelProfileRepository::new(&config_db).await.unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO model_profiles (id, engine_id, model_id, label, parameters_json, version, updated_at) \
+         VALUES ('profile-1', 'test-engine', 'flm://test-engine/test-model', 'creative', ?, 2, ?)",
+    )
+    .bind(r#"{"temperature": 0.9, "top_p": 1.5}"#)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18162,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let cases = [
+        // Profile alias and the model's own profile both pick up the defaults
+        (
+            serde_json::json!({ "model": "flm://profile/creative" }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_sampling",
+        ),
+        (
+            serde_json::json!({ "model": "flm://test-engine/test-model" }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_sampling",
+        ),
+        // An explicit request value overrides the profile default
+        (
+            serde_json::json!({ "model": "flm://profile/creative", "top_p": 0.5 }),
+            reqwest::StatusCode::NOT_FOUND,
+            "engine_not_found",
+        ),
+        (
+            serde_json::json!({ "model": "flm://profile/missing" }),
+            reqwest::StatusCode::NOT_FOUND,
+            "model_profile_not_found",
+        ),
+    ];
+    for (mut chat_request, expected_status, expected_code) in cases {
+        chat_request["messages"] = serde_json::json!([{ "role": "user", "content": "Hello" }]);
+        let response = client
+            .post("http://localhost:18162/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&chat_request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            expected_status,
+            "request: {chat_request}"
+        );
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(
+            body["error"]["code"], expected_code,
+            "request: {chat_request}"
+        );
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_api_prompt_opt_out_header() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-api-prompt-security");
+    let config_db = unique_db_path("flm-test-api-prompt-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18163,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let chat_request = serde_json::json!({
+        "model": "flm://test-engine/test-model",
+        "messages": [{ "role": "user", "content": "Hello" }]
+    });
+    let cases = [
+        (
+            "maybe",
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_api_prompt_header",
+        ),
+        ("off", reqwest::StatusCode::NOT_FOUND, "engine_not_found"),
+        ("ON", reqwest::StatusCode::NOT_FOUND, "engine_not_found"),
+    ];
+    for (header_value, expected_status, expected_code) in cases {
+        let response = client
+            .post("http://localhost:18163/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .header("X-FLM-API-Prompt", header_value)
+            .json(&chat_request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "header: {header_value}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(
+            body["error"]["code"], expected_code,
+            "header: {header_value}"
+        );
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_completions_request_validation() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-completions-security");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18164,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let cases = [
+        (
+            serde_json::json!({
+                "model": "flm://test-engine/test-model",
+                "prompt": ["first", "second"]
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_prompt",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://test-engine/test-model",
+                "prompt": "Hello",
+                "best_of": 2,
+                "stream": true
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_best_of",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://test-engine/test-model",
+                "prompt": "Hello",
+                "logprobs": 5
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "unsupported_parameter",
+        ),
+        (
+            serde_json::json!({
+                "model": "test-model",
+                "prompt": "Hello"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_model",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://test-engine/test-model",
+                "prompt": ["Hello"],
+                "suffix": "!",
+                "echo": true,
+                "best_of": 1
+            }),
+            reqwest::StatusCode::NOT_FOUND,
+            "engine_not_found",
+        ),
+    ];
+    for (request, expected_status, expected_code) in cases {
+        let response = client
+            .post("http://localhost:18164/v1/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "request: {request}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], expected_code, "request: {request}");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_anthropic_messages_auth_and_errors() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-anthropic-security");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18165,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let url = "http://localhost:18165/v1/messages";
+    let request = serde_json::json!({
+        "model": "flm://test-engine/test-model",
+        "max_tokens": 64,
+        "system": "Be brief.",
+        "messages": [{ "role": "user", "content": [{ "type": "text", "text": "Hello" }] }]
+    });
+
+    // Anthropic clients authenticate with x-api-key instead of a Bearer token
+    let response = client.post(url).json(&request).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(url)
+        .header("x-api-key", &api_key.plain)
+        .header("anthropic-version", "2023-06-01")
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["type"], "error");
+    assert_eq!(body["error"]["type"], "not_found_error");
+
+    let mut missing_max_tokens = request.clone();
+    missing_max_tokens
+        .as_object_mut()
+        .unwrap()
+        .remove("max_tokens");
+    let response = client
+        .post(url)
+        .header("x-api-key", &api_key.plain)
+        .json(&missing_max_tokens)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["type"], "invalid_request_error");
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ollama_api_facade_is_opt_in() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-ollama-api-security");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let client = reqwest::Client::new();
+    for (port, ollama_api) in [(18166, false), (18167, true)] {
+        let controller = AxumProxyController::new();
+        let config = ProxyConfig {
+            mode: ProxyMode::LocalHttp,
+            port,
+            security_db_path: Some(security_db.to_str().unwrap().to_string()),
+            ollama_api,
+            ..Default::default()
+        };
+
+        let handle = controller.start(config).await.unwrap();
+        sleep(Duration::from_millis(500)).await;
+
+        let response = client
+            .get(format!("http://localhost:{port}/api/tags"))
+            .header("Authorization", bearer_header(&api_key.plain))
+            .send()
+            .await
+            .unwrap();
+        if !ollama_api {
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+            controller.stop(handle).await.unwrap();
+            continue;
+        }
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["models"], serde_json::json!([]));
+
+        let response = client
+            .post(format!("http://localhost:{port}/api/chat"))
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&serde_json::json!({
+                "model": "test-engine/test-model",
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "Engine not found");
+
+        let response = client
+            .post(format!("http://localhost:{port}/api/generate"))
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&serde_json::json!({ "model": "llama3:latest", "prompt": "Hello" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        // The facade is still behind API-key authentication
+        let response = client
+            .get(format!("http://localhost:{port}/api/tags"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        controller.stop(handle).await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_images_generations_request_validation() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-images-security");
+    let config_db = unique_db_path("flm-test-images-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    // Registered but unreachable: URL output is rejected before the web UI is called
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "sd-local".to_string(),
+            kind: EngineKind::StableDiffusion,
+            base_url: "http://127.0.0.1:1".to_string(),
+            auth_header_name: None,
+            timeout_secs: Some(2),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18168,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let cases = [
+        (
+            serde_json::json!({
+                "model": "sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_model",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "   "
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_prompt",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk",
+                "n": 11
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_n",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk",
+                "size": "1001x1000"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_size",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk",
+                "size": "large"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_size",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk",
+                "response_format": "png"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_response_format",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://sdwebui-default/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk",
+                "n": 2,
+                "size": "512x768",
+                "response_format": "b64_json",
+                "quality": "standard"
+            }),
+            reqwest::StatusCode::NOT_FOUND,
+            "engine_not_found",
+        ),
+        // `url` is the OpenAI default
+        (
+            serde_json::json!({
+                "model": "flm://sd-local/sd_xl_base_1.0",
+                "prompt": "a lighthouse at dusk"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "unsupported_response_format",
+        ),
+    ];
+    for (request, expected_status, expected_code) in cases {
+        let response = client
+            .post("http://localhost:18168/v1/images/generations")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "request: {request}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], expected_code, "request: {request}");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_audio_speech_request_validation() {
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-speech-security");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18169,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let cases = [
+        (
+            serde_json::json!({
+                "model": "kokoro",
+                "input": "Hello",
+                "voice": "af_bella"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_model",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://tts-engine/kokoro",
+                "input": "x".repeat(4097),
+                "voice": "af_bella"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_input",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://tts-engine/kokoro",
+                "input": "Hello",
+                "voice": ""
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_voice",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://tts-engine/kokoro",
+                "input": "Hello",
+                "voice": "af_bella",
+                "response_format": "midi"
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_response_format",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://tts-engine/kokoro",
+                "input": "Hello",
+                "voice": "af_bella",
+                "speed": 5.0
+            }),
+            reqwest::StatusCode::BAD_REQUEST,
+            "invalid_speed",
+        ),
+        (
+            serde_json::json!({
+                "model": "flm://tts-engine/kokoro",
+                "input": "Hello",
+                "voice": "af_bella",
+                "response_format": "opus",
+                "speed": 1.25
+            }),
+            reqwest::StatusCode::NOT_FOUND,
+            "engine_not_found",
+        ),
+    ];
+    for (request, expected_status, expected_code) in cases {
+        let response = client
+            .post("http://localhost:18169/v1/audio/speech")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "request: {request}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], expected_code, "request: {request}");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_model_group_resolution() {
+    use flm_core::adapters::SqliteModelGroupRepository;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-model-group-security");
+    let config_db = unique_db_path("flm-test-model-group-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    // A group whose members point at engines that are not registered
+    SqliteModelGroupRepository::new(&config_db).await.unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO model_groups (name, strategy) VALUES ('llama3', 'least-in-flight')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO model_group_members (group_name, model_id, position) \
+         VALUES ('llama3', 'flm://engine-a/llama3', 0), ('llama3', 'flm://engine-b/llama3', 1)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18170,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let cases = [
+        (
+            "flm://group/llama3",
+            reqwest::StatusCode::NOT_FOUND,
+            "engine_not_found",
+        ),
+        (
+            "flm://group/missing",
+            reqwest::StatusCode::NOT_FOUND,
+            "model_group_not_found",
+        ),
+    ];
+    for (model, expected_status, expected_code) in cases {
+        let response = client
+            .post("http://localhost:18170/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "model: {model}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], expected_code, "model: {model}");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_chat_completions_uses_registered_engines() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    let security_db = unique_db_path("flm-test-engine-registry-security");
+    let config_db = unique_db_path("flm-test-engine-registry-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    // A registered engine that is not reachable: it must be found, then fail upstream
+    let registry = SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap();
+    registry
+        .save(&EngineRegistration {
+            id: "remote-gpu".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: "http://127.0.0.1:1".to_string(),
+            auth_header_name: None,
+            timeout_secs: Some(2),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18171,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    for (model, registered) in [
+        ("flm://remote-gpu/llama3", true),
+        ("flm://unregistered/llama3", false),
+    ] {
+        let response = client
+            .post("http://localhost:18171/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap();
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap();
+        if registered {
+            assert_ne!(status, reqwest::StatusCode::NOT_FOUND, "model: {model}");
+            assert_ne!(body["error"]["code"], "engine_not_found", "model: {model}");
+        } else {
+            assert_eq!(status, reqwest::StatusCode::NOT_FOUND, "model: {model}");
+            assert_eq!(body["error"]["code"], "engine_not_found", "model: {model}");
+        }
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_concurrency_limit_queues_and_rejects() {
+    use flm_core::adapters::{SqliteConcurrencyLimitRepository, SqliteEngineRegistryRepository};
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    // Mock Ollama engine that takes a while to answer
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let app = axum::Router::new().route(
+        "/api/chat",
+        axum::routing::post(|| async {
+            sleep(Duration::from_millis(1500)).await;
+            axum::Json(serde_json::json!({
+                "model": "llama3",
+                "message": { "role": "assistant", "content": "hi" },
+                "done": true,
+                "prompt_eval_count": 1,
+                "eval_count": 1
+            }))
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-concurrency-security");
+    let config_db = unique_db_path("flm-test-concurrency-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    let registry = SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap();
+    registry
+        .save(&EngineRegistration {
+            id: "slow-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    // One request at a time, one more may wait
+    SqliteConcurrencyLimitRepository::new(&config_db)
+        .await
+        .unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO concurrency_limits (target, max_concurrent, max_queue, queue_timeout_secs) \
+         VALUES ('slow-engine', 1, 1, 20)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18172,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let send = |plain: String| async move {
+        reqwest::Client::new()
+            .post("http://localhost:18172/v1/chat/completions")
+            .header("Authorization", bearer_header(&plain))
+            .json(&serde_json::json!({
+                "model": "flm://slow-engine/llama3",
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap()
+    };
+
+    let running = tokio::spawn(send(api_key.plain.clone()));
+    sleep(Duration::from_millis(300)).await;
+    let queued = tokio::spawn(send(api_key.plain.clone()));
+    sleep(Duration::from_millis(300)).await;
+
+    // The slot is taken and the queue is full
+    let rejected = send(api_key.plain.clone()).await;
+    assert_eq!(rejected.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        rejected
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()),
+        Some("20")
+    );
+    let body: serde_json::Value = rejected.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "queue_full");
+
+    // The queued request runs once the first one finishes
+    assert_eq!(running.await.unwrap().status(), reqwest::StatusCode::OK);
+    assert_eq!(queued.await.unwrap().status(), reqwest::StatusCode::OK);
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_completions_queue_behind_chat_holding_the_engine_slot() {
+    use flm_core::adapters::{SqliteConcurrencyLimitRepository, SqliteEngineRegistryRepository};
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    // Mock Ollama engine: a slow chat, and a completion that reports whether
+    // the chat had already finished when it arrived
+    let chat_done = Arc::new(AtomicBool::new(false));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let app = axum::Router::new()
+        .route(
+            "/api/chat",
+            axum::routing::post({
+                let chat_done = chat_done.clone();
+                move || async move {
+                    sleep(Duration::from_millis(1500)).await;
+                    chat_done.store(true, Ordering::SeqCst);
+                    axum::Json(serde_json::json!({
+                        "model": "llama3",
+                        "message": { "role": "assistant", "content": "hi" },
+                        "done": true,
+                        "prompt_eval_count": 1,
+                        "eval_count": 1
+                    }))
+                }
+            }),
+        )
+        .route(
+            "/api/generate",
+            axum::routing::post({
+                let chat_done = chat_done.clone();
+                move || async move {
+                    let order = if chat_done.load(Ordering::SeqCst) {
+                        "after-chat"
+                    } else {
+                        "during-chat"
+                    };
+                    axum::Json(serde_json::json!({
+                        "model": "llama3",
+                        "response": order,
+                        "done": true,
+                        "prompt_eval_count": 1,
+                        "eval_count": 1
+                    }))
+                }
+            }),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-completions-queue-security");
+    let config_db = unique_db_path("flm-test-completions-queue-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+    security_service
+        .set_policy(SecurityPolicy {
+            id: "default".to_string(),
+            policy_json: "{}".to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "slow-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    // One request at a time across every endpoint of the engine
+    SqliteConcurrencyLimitRepository::new(&config_db)
+        .await
+        .unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO concurrency_limits (target, max_concurrent, max_queue, queue_timeout_secs) \
+         VALUES ('slow-engine', 1, 4, 20)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18179,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let chat = tokio::spawn(
+        client
+            .post("http://localhost:18179/v1/chat/completions")
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&serde_json::json!({
+                "model": "flm://slow-engine/llama3",
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send(),
+    );
+    sleep(Duration::from_millis(300)).await;
+
+    let completion = client
+        .post("http://localhost:18179/v1/completions")
+        .header("Authorization", bearer_header(&api_key.plain))
+        .json(&serde_json::json!({
+            "model": "flm://slow-engine/llama3",
+            "prompt": "Once upon a time"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(completion.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = completion.json().await.unwrap();
+    assert_eq!(body["choices"][0]["text"], "after-chat");
+    assert_eq!(
+        chat.await.unwrap().unwrap().status(),
+        reqwest::StatusCode::OK
+    );
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_chat_completions_daily_token_quota() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    // Mock Ollama engine reporting 30 prompt + 20 completion tokens
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let app = axum::Router::new().route(
+        "/api/chat",
+        axum::routing::post(|| async {
+            axum::Json(serde_json::json!({
+                "model": "llama3",
+                "message": { "role": "assistant", "content": "hi" },
+                "done": true,
+                "prompt_eval_count": 30,
+                "eval_count": 20
+            }))
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-token-quota-security");
+    let config_db = unique_db_path("flm-test-token-quota-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo.clone()));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+    let other_key = security_service.create_api_key("other-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({
+            "rate_limit": { "daily_tokens": 50 }
+        }))
+        .unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "metered-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18173,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let send = |plain: String| async move {
+        reqwest::Client::new()
+            .post("http://localhost:18173/v1/chat/completions")
+            .header("Authorization", bearer_header(&plain))
+            .json(&serde_json::json!({
+                "model": "flm://metered-engine/llama3",
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap()
+    };
+
+    // The first request fits; its reported usage uses up the quota
+    assert_eq!(
+        send(api_key.plain.clone()).await.status(),
+        reqwest::StatusCode::OK
+    );
+    let rejected = send(api_key.plain.clone()).await;
+    assert_eq!(rejected.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert!(rejected
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .is_some());
+    let body: serde_json::Value = rejected.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "token_quota_exceeded");
+
+    // Quotas are per API key
+    assert_eq!(
+        send(other_key.plain.clone()).await.status(),
+        reqwest::StatusCode::OK
+    );
+
+    // Usage is persisted per key and UTC day
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let mut totals = (0, 0);
+    for _ in 0..50 {
+        totals = security_repo
+            .fetch_token_usage_totals(&api_key.record.id, &today)
+            .await
+            .unwrap();
+        if totals == (50, 50) {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(totals, (50, 50));
+
+    // Each charged request also leaves a usage record with its target model
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", security_db.display()))
+        .await
+        .unwrap();
+    let mut keys: Vec<String> = Vec::new();
+    for _ in 0..50 {
+        keys = sqlx::query_scalar("SELECT api_key_id FROM usage_records ORDER BY api_key_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        if keys.len() == 2 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    let mut expected_keys = vec![api_key.record.id.clone(), other_key.record.id.clone()];
+    expected_keys.sort_unstable();
+    assert_eq!(keys, expected_keys);
+    let records: Vec<(String, String, String, i64, i64)> = sqlx::query_as(
+        "SELECT endpoint, engine_id, model_id, total_tokens, estimated FROM usage_records",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    for record in &records {
+        assert_eq!(
+            record,
+            &(
+                "/v1/chat/completions".to_string(),
+                "metered-engine".to_string(),
+                "flm://metered-engine/llama3".to_string(),
+                50,
+                0
+            )
+        );
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_anthropic_messages_apply_api_prompt() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::{Arc, Mutex};
+
+    // Mock Ollama engine that records the messages it receives
+    let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let recorder = received.clone();
+    let app = axum::Router::new().route(
+        "/api/chat",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let recorder = recorder.clone();
+            async move {
+                recorder.lock().unwrap().push(body["messages"].clone());
+                axum::Json(serde_json::json!({
+                    "model": "llama3",
+                    "message": { "role": "assistant", "content": "hi" },
+                    "done": true,
+                    "prompt_eval_count": 1,
+                    "eval_count": 1
+                }))
+            }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-anthropic-prompt-security");
+    let config_db = unique_db_path("flm-test-anthropic-prompt-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "prompt-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO api_prompts (api_id, template_text, version, updated_at) \
+         VALUES ('chat_completions', 'Managed prompt for {{model_id}}', 1, ?)",
+    )
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18174,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    for (header_value, expected_system) in [
+        (
+            "on",
+            "Managed prompt for flm://prompt-engine/llama3\n\nBe brief.",
+        ),
+        ("off", "Be brief."),
+    ] {
+        let response = client
+            .post("http://localhost:18174/v1/messages")
+            .header("x-api-key", &api_key.plain)
+            .header("X-FLM-API-Prompt", header_value)
+            .json(&serde_json::json!({
+                "model": "flm://prompt-engine/llama3",
+                "max_tokens": 64,
+                "system": "Be brief.",
+                "messages": [{ "role": "user", "content": "Hello" }]
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::OK,
+            "header: {header_value}"
+        );
+        let messages = received.lock().unwrap().pop().unwrap();
+        assert_eq!(messages[0]["role"], "system", "header: {header_value}");
+        assert_eq!(
+            messages[0]["content"], expected_system,
+            "header: {header_value}"
+        );
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ollama_api_facade_applies_api_prompt() {
+    use flm_core::adapters::SqliteEngineRegistryRepository;
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::SecurityPolicy;
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::{Arc, Mutex};
+
+    // Mock Ollama engine that records the messages it receives
+    let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let recorder = received.clone();
+    let app = axum::Router::new().route(
+        "/api/chat",
+        axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+            let recorder = recorder.clone();
+            async move {
+                recorder.lock().unwrap().push(body["messages"].clone());
+                axum::Json(serde_json::json!({
+                    "model": "llama3",
+                    "message": { "role": "assistant", "content": "hi" },
+                    "done": true,
+                    "prompt_eval_count": 1,
+                    "eval_count": 1
+                }))
+            }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-ollama-prompt-security");
+    let config_db = unique_db_path("flm-test-ollama-prompt-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let api_key = security_service.create_api_key("test-key").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "prompt-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO api_prompts (api_id, template_text, version, updated_at) \
+         VALUES ('chat_completions', 'Managed prompt for {{model_id}}', 1, ?)",
+    )
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18175,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ollama_api: true,
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let requests = [
+        (
+            "/api/chat",
+            serde_json::json!({
+                "model": "prompt-engine/llama3",
+                "messages": [{ "role": "user", "content": "Hello" }],
+                "stream": false
+            }),
+        ),
+        (
+            "/api/generate",
+            serde_json::json!({
+                "model": "prompt-engine/llama3",
+                "prompt": "Hello",
+                "stream": false
+            }),
+        ),
+    ];
+    for (path, request) in requests {
+        let response = client
+            .post(format!("http://localhost:18175{path}"))
+            .header("Authorization", bearer_header(&api_key.plain))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK, "path: {path}");
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["model"], "prompt-engine/llama3", "path: {path}");
+
+        let messages = received.lock().unwrap().pop().unwrap();
+        assert_eq!(messages[0]["role"], "system", "path: {path}");
+        assert_eq!(
+            messages[0]["content"], "Managed prompt for flm://prompt-engine/llama3",
+            "path: {path}"
+        );
+        assert_eq!(messages[1]["content"], "Hello", "path: {path}");
+    }
+
+    controller.stop(handle).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scoped_api_keys_restrict_endpoints_models_and_ips() {
+    use flm_core::adapters::{SqliteEngineRegistryRepository, SqliteModelGroupRepository};
+    use flm_core::domain::engine::EngineRegistration;
+    use flm_core::domain::models::EngineKind;
+    use flm_core::domain::security::{
+        ApiKeyRateLimit, ApiKeyScopes, EndpointScope, SecurityPolicy,
+    };
+    use flm_core::ports::EngineRegistryRepository;
+    use flm_core::services::SecurityService;
+    use flm_proxy::adapters::SqliteSecurityRepository;
+    use std::sync::Arc;
+
+    // Mock Ollama engine serving an 8B and a 70B model; replies name the model used
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let engine_addr = listener.local_addr().unwrap();
+    let app = axum::Router::new()
+        .route(
+            "/api/tags",
+            axum::routing::get(|| async {
+                axum::Json(serde_json::json!({
+                    "models": [{ "name": "llama3:8b" }, { "name": "llama3:70b" }]
+                }))
+            }),
+        )
+        .route(
+            "/api/chat",
+            axum::routing::post(
+                |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                    axum::Json(serde_json::json!({
+                        "model": body["model"],
+                        "message": { "role": "assistant", "content": body["model"] },
+                        "done": true,
+                        "prompt_eval_count": 3,
+                        "eval_count": 2
+                    }))
+                },
+            ),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let security_db = unique_db_path("flm-test-scoped-keys-security");
+    let config_db = unique_db_path("flm-test-scoped-keys-config");
+
+    let security_repo = SqliteSecurityRepository::new(&security_db).await.unwrap();
+    let security_service = Arc::new(SecurityService::new(security_repo));
+    let demo_key = security_service
+        .create_scoped_api_key(
+            "public-demo",
+            ApiKeyScopes {
+                endpoints: vec![EndpointScope::Chat, EndpointScope::Models],
+                models: vec!["flm://scoped-engine/llama3:8b*".to_string()],
+                rate_limit: ApiKeyRateLimit {
+                    rpm: Some(5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let remote_key = security_service
+        .create_scoped_api_key(
+            "office-only",
+            ApiKeyScopes {
+                ip_allowlist: vec!["10.0.0.0/8".to_string()],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let admin_key = security_service.create_api_key("admin").await.unwrap();
+
+    let policy = SecurityPolicy {
+        id: "default".to_string(),
+        policy_json: serde_json::to_string(&serde_json::json!({})).unwrap(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    security_service.set_policy(policy).await.unwrap();
+
+    SqliteEngineRegistryRepository::new(&config_db)
+        .await
+        .unwrap()
+        .save(&EngineRegistration {
+            id: "scoped-engine".to_string(),
+            kind: EngineKind::Ollama,
+            base_url: format!("http://{engine_addr}"),
+            auth_header_name: None,
+            timeout_secs: Some(10),
+            tls: Default::default(),
+            bearer_token_in_keyring: false,
+            model_capabilities: Default::default(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .await
+        .unwrap();
+    // A group whose primary member is outside the demo key's scopes
+    SqliteModelGroupRepository::new(&config_db).await.unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", config_db.display()))
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO model_groups (name, strategy) VALUES ('llama3', 'round-robin')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO model_group_members (group_name, model_id, position) \
+         VALUES ('llama3', 'flm://scoped-engine/llama3:70b', 0), \
+         ('llama3', 'flm://scoped-engine/llama3:8b', 1)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let controller = AxumProxyController::new();
+    let config = ProxyConfig {
+        mode: ProxyMode::LocalHttp,
+        port: 18176,
+        security_db_path: Some(security_db.to_str().unwrap().to_string()),
+        config_db_path: Some(config_db.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let handle = controller.start(config).await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let chat = |key: String, model: &'static str| {
+        let client = client.clone();
+        async move {
+            client
+                .post("http://localhost:18176/v1/chat/completions")
+                .header("Authorization", bearer_header(&key))
+                .json(&serde_json::json!({
+                    "model": model,
+                    "messages": [{ "role": "user", "content": "Hello" }]
+                }))
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    // The demo key can chat with the 8B model only
+    let response = chat(demo_key.plain.clone(), "flm://scoped-engine/llama3:8b").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = chat(demo_key.plain.clone(), "flm://scoped-engine/llama3:70b").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "model_not_allowed");
+
+    // Through the group, it is always served by the member it may use
+    for _ in 0..2 {
+        let response = chat(demo_key.plain.clone(), "flm://group/llama3").await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["choices"][0]["message"]["content"], "llama3:8b");
+    }
+
+    // Endpoints outside the key's scopes are denied before the handler
+    let response = client
+        .post("http://localhost:18176/v1/embeddings")
+        .header("Authorization", bearer_header(&demo_key.plain))
+        .json(&serde_json::json!({
+            "model": "flm://scoped-engine/llama3:8b",
+            "input": "Hello"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "api_key_scope_denied");
+
+    // /v1/models lists only the models the key may use
+    let models_for = |key: String| {
+        let client = client.clone();
+        async move {
+            let body: serde_json::Value = client
+                .get("http://localhost:18176/v1/models")
+                .header("Authorization", bearer_header(&key))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            let mut ids: Vec<String> = body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|model| model["id"].as_str().unwrap().to_string())
+                .collect();
+            ids.sort();
+            ids
+        }
+    };
+    assert_eq!(
+        models_for(demo_key.plain.clone()).await,
+        vec!["flm://scoped-engine/llama3:8b"]
+    );
+    assert_eq!(
+        models_for(admin_key.plain.clone()).await,
+        vec![
+            "flm://scoped-engine/llama3:70b",
+            "flm://scoped-engine/llama3:8b"
+        ]
+    );
+
+    // The unrestricted key still reaches every model
+    let response = chat(admin_key.plain.clone(), "flm://scoped-engine/llama3:70b").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // An IP-restricted key is refused from outside its allowlist
+    let response = chat(remote_key.plain.clone(), "flm://scoped-engine/llama3:8b").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "api_key_scope_denied");
+
+    // The key's own rpm applies although the policy has no rate limit
+    let mut statuses = Vec::new();
+    for _ in 0..8 {
+        statuses.push(
+            chat(demo_key.plain.clone(), "flm://scoped-engine/llama3:8b")
+                .await
+                .status(),
+        );
+    }
+    assert!(statuses.contains(&reqwest::StatusCode::TOO_MANY_REQUESTS));
+
+    controller.stop(handle).await.unwrap();
+}
@@ -66,11 +66,21 @@ async fn create_test_state() -> AppState {
         engine_repo,
     ));
 
+    let security_repo = Arc::new(security_repo);
     AppState {
         security_service,
-        security_repo: Arc::new(security_repo),
+        security_repo: security_repo.clone(),
         engine_service,
         engine_repo: engine_repo_impl,
+        model_profiles: None,
+        api_prompts: None,
+        model_groups: None,
+        balancer: Arc::new(flm_proxy::balancer::Balancer::new(None)),
+        concurrency: Arc::new(flm_proxy::concurrency::ConcurrencyLimiter::new(
+            Vec::new(),
+            Arc::new(flm_proxy::metrics::Metrics::new()),
+        )),
+        token_quotas: Arc::new(flm_proxy::token_quota::TokenQuotas::new(security_repo)),
         rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
         ip_rate_limit_state: Arc::new(RwLock::new(HashMap::new())),
         trusted_proxy_ips: vec![],
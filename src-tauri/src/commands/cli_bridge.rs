@@ -10,6 +10,8 @@ use log::warn;
 
 const CLI_BIN: &str = "flm";
 const CLI_TIMEOUT_SECS: u64 = 60;
+/// Model downloads run for as long as the transfer takes
+const MODEL_PULL_TIMEOUT_SECS: u64 = 24 * 60 * 60;
 
 /// Finds the flm CLI binary path.
 /// Searches in the following order:
@@ -146,6 +148,65 @@ pub async fn ipc_list_models(engine: Option<String>) -> Result<Value, CliBridgeE
     run_cli_json(args).await
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ModelPullRequest {
+    pub model: String,
+    pub engine: Option<String>,
+    pub sha256: Option<String>,
+    pub file_name: Option<String>,
+}
+
+#[tauri::command]
+pub async fn ipc_models_pull(payload: ModelPullRequest) -> Result<Value, CliBridgeError> {
+    let mut args: Vec<String> = vec!["models".to_string(), "pull".to_string(), payload.model];
+    if let Some(engine_id) = payload.engine {
+        args.push("--engine".to_string());
+        args.push(engine_id);
+    }
+    if let Some(sha256) = payload.sha256 {
+        args.push("--sha256".to_string());
+        args.push(sha256);
+    }
+    if let Some(file_name) = payload.file_name {
+        args.push("--file-name".to_string());
+        args.push(file_name);
+    }
+    args.push("--format".to_string());
+    args.push("json".to_string());
+    run_cli_json_with_timeout(args, MODEL_PULL_TIMEOUT_SECS).await
+}
+
+#[tauri::command]
+pub async fn ipc_models_delete(model: String, engine: Option<String>) -> Result<Value, CliBridgeError> {
+    run_cli_json(model_command_args("delete", vec![model], engine)).await
+}
+
+#[tauri::command]
+pub async fn ipc_models_show(model: String, engine: Option<String>) -> Result<Value, CliBridgeError> {
+    run_cli_json(model_command_args("show", vec![model], engine)).await
+}
+
+#[tauri::command]
+pub async fn ipc_models_copy(
+    source: String,
+    destination: String,
+    engine: Option<String>,
+) -> Result<Value, CliBridgeError> {
+    run_cli_json(model_command_args("copy", vec![source, destination], engine)).await
+}
+
+fn model_command_args(subcommand: &str, positional: Vec<String>, engine: Option<String>) -> Vec<String> {
+    let mut args: Vec<String> = vec!["models".to_string(), subcommand.to_string()];
+    args.extend(positional);
+    if let Some(engine_id) = engine {
+        args.push("--engine".to_string());
+        args.push(engine_id);
+    }
+    args.push("--format".to_string());
+    args.push("json".to_string());
+    args
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EngineHealthHistoryRequest {
     pub engine: Option<String>,
@@ -291,6 +352,9 @@ pub async fn ipc_security_policy_set(payload: SecurityPolicyInput) -> Result<Val
 #[derive(Debug, Deserialize)]
 pub struct ApiKeyCreateInput {
     pub label: String,
+    /// `<name>=<value>` scope restrictions, passed as repeated `--scope`
+    #[serde(default)]
+    pub scopes: Vec<String>,
 }
 
 #[tauri::command]
@@ -300,15 +364,19 @@ pub async fn ipc_api_keys_list() -> Result<Value, CliBridgeError> {
 
 #[tauri::command]
 pub async fn ipc_api_keys_create(payload: ApiKeyCreateInput) -> Result<Value, CliBridgeError> {
-    run_cli_json(vec![
+    let mut args = vec![
         "api-keys".to_string(),
         "create".to_string(),
         "--label".to_string(),
         payload.label,
         "--format".to_string(),
         "json".to_string(),
-    ])
-    .await
+    ];
+    for scope in payload.scopes {
+        args.push("--scope".to_string());
+        args.push(scope);
+    }
+    run_cli_json(args).await
 }
 
 #[derive(Debug, Deserialize)]
@@ -321,6 +389,30 @@ pub async fn ipc_api_keys_revoke(payload: ApiKeyRevokeRequest) -> Result<Value,
     run_cli_json(vec!["api-keys".to_string(), "revoke".to_string(), payload.id, "--format".to_string(), "json".to_string()]).await
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ApiKeyScopeRequest {
+    pub id: String,
+    /// Replacement scopes; empty lifts all restrictions
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn ipc_api_keys_scope(payload: ApiKeyScopeRequest) -> Result<Value, CliBridgeError> {
+    let mut args = vec![
+        "api-keys".to_string(),
+        "scope".to_string(),
+        payload.id,
+        "--format".to_string(),
+        "json".to_string(),
+    ];
+    for scope in payload.scopes {
+        args.push("--scope".to_string());
+        args.push(scope);
+    }
+    run_cli_json(args).await
+}
+
 #[tauri::command]
 pub async fn ipc_config_list() -> Result<Value, CliBridgeError> {
     run_cli_json(vec!["config".to_string(), "list".to_string(), "--format".to_string(), "json".to_string()]).await
@@ -728,6 +820,37 @@ pub async fn ipc_security_anomaly(
     run_cli_json(args).await
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UsageReportRequest {
+    pub key: Option<String>,
+    pub since: Option<String>,
+    pub group_by: Option<String>,
+}
+
+#[tauri::command]
+pub async fn ipc_usage_report(
+    payload: Option<UsageReportRequest>,
+) -> Result<Value, CliBridgeError> {
+    let mut args: Vec<String> = vec!["usage".to_string(), "report".to_string(), "--format".to_string(), "json".to_string()];
+
+    if let Some(p) = payload {
+        if let Some(key) = p.key {
+            args.push("--key".to_string());
+            args.push(key);
+        }
+        if let Some(since) = p.since {
+            args.push("--since".to_string());
+            args.push(since);
+        }
+        if let Some(group_by) = p.group_by {
+            args.push("--group-by".to_string());
+            args.push(group_by);
+        }
+    }
+
+    run_cli_json(args).await
+}
+
 #[tauri::command]
 pub async fn ipc_security_install_packaged_ca() -> Result<(), CliBridgeError> {
     let cert_path = resolve_packaged_ca_path();
@@ -816,6 +939,14 @@ fn resolve_packaged_ca_path() -> PathBuf {
 }
 
 async fn run_cli_json<I, S>(args: I) -> Result<Value, CliBridgeError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    run_cli_json_with_timeout(args, CLI_TIMEOUT_SECS).await
+}
+
+async fn run_cli_json_with_timeout<I, S>(args: I, timeout_secs: u64) -> Result<Value, CliBridgeError>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<str>,
@@ -824,13 +955,13 @@ where
     let binary_path = find_flm_binary()?;
     let mut command = Command::new(&binary_path);
     command.args(&arg_vec);
-    let output = timeout(Duration::from_secs(CLI_TIMEOUT_SECS), command.output())
+    let output = timeout(Duration::from_secs(timeout_secs), command.output())
         .await
         .map_err(|_| CliBridgeError {
             code: "CLI_TIMEOUT".into(),
             message: format!(
                 "CLI command exceeded timeout of {} seconds",
-                CLI_TIMEOUT_SECS
+                timeout_secs
             ),
             stderr: None,
         })?
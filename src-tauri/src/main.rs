@@ -36,6 +36,10 @@ fn main() {
             // CLI bridge commands
             commands::cli_bridge::ipc_detect_engines,
             commands::cli_bridge::ipc_list_models,
+            commands::cli_bridge::ipc_models_pull,
+            commands::cli_bridge::ipc_models_delete,
+            commands::cli_bridge::ipc_models_show,
+            commands::cli_bridge::ipc_models_copy,
             commands::cli_bridge::ipc_engines_health_history,
             commands::cli_bridge::ipc_proxy_start,
             commands::cli_bridge::ipc_proxy_status,
@@ -45,6 +49,7 @@ fn main() {
             commands::cli_bridge::ipc_api_keys_list,
             commands::cli_bridge::ipc_api_keys_create,
             commands::cli_bridge::ipc_api_keys_revoke,
+            commands::cli_bridge::ipc_api_keys_scope,
             commands::cli_bridge::ipc_config_list,
             commands::cli_bridge::ipc_config_get,
             commands::cli_bridge::ipc_config_set,
@@ -64,6 +69,7 @@ fn main() {
             commands::cli_bridge::ipc_security_audit_logs,
             commands::cli_bridge::ipc_security_intrusion,
             commands::cli_bridge::ipc_security_anomaly,
+            commands::cli_bridge::ipc_usage_report,
             commands::cli_bridge::ipc_security_install_packaged_ca,
             // Firewall commands
             commands::firewall::system_firewall_preview,